@@ -0,0 +1,176 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    handles::KeyHandle,
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+    structures::{
+        Attest, AttestInfo, Data, MaxBuffer, Name, NvCertifyInfo, Signature, TimeAttestInfo,
+    },
+    traits::Marshall,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
+
+/// Verifies the result of [Context::get_time].
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `signing_key_handle` - Handle of the key that was used to sign the attestation data.
+/// * `attest` - The attestation data that was returned by the TPM.
+/// * `signature` - The signature that was returned by the TPM.
+/// * `qualifying_data` - The qualifying data that was provided to the TPM.
+/// * `reset_count` - The expected reset count of the TPM, if it should be checked.
+/// * `restart_count` - The expected restart count of the TPM, if it should be checked.
+///
+/// # Details
+/// The signature over the attestation data is verified by the TPM using the
+/// signing key. The reset and restart counts can be used to detect if the TPM
+/// has been reset or restarted since they were last obtained, in which case the
+/// clock values are not comparable.
+///
+/// # Returns
+/// The verified [TimeAttestInfo].
+///
+/// # Errors
+/// * if the attestation data does not contain time information, an `InvalidParam`
+///   wrapper error is returned.
+/// * if the qualifying data, the reset count or the restart count do not match the
+///   attestation data, an `InconsistentParams` wrapper error is returned.
+/// * if the signature is not valid, the error from the TPM is returned.
+pub fn verify_time_attestation(
+    context: &mut Context,
+    signing_key_handle: KeyHandle,
+    attest: &Attest,
+    signature: Signature,
+    qualifying_data: &Data,
+    reset_count: Option<u32>,
+    restart_count: Option<u32>,
+) -> Result<TimeAttestInfo> {
+    let time_attest_info = match attest.attested() {
+        AttestInfo::Time { info } => *info,
+        _ => {
+            error!("The attestation data does not contain time information");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+    };
+
+    if attest.extra_data() != qualifying_data {
+        error!("The qualifying data does not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    if matches!(reset_count, Some(count) if count != attest.clock_info().reset_count()) {
+        error!("The reset count does not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    if matches!(restart_count, Some(count) if count != attest.clock_info().restart_count()) {
+        error!("The restart count does not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    verify_signature(context, signing_key_handle, attest, signature)?;
+
+    Ok(time_attest_info)
+}
+
+/// Verifies the result of [Context::nv_certify].
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `signing_key_handle` - Handle of the key that was used to sign the attestation data.
+/// * `attest` - The attestation data that was returned by the TPM.
+/// * `signature` - The signature that was returned by the TPM.
+/// * `qualifying_data` - The qualifying data that was provided to the TPM.
+/// * `nv_index_name` - The expected [Name] of the NV index.
+/// * `nv_contents` - The expected contents of the NV index, starting at offset zero.
+///
+/// # Details
+/// The signature over the attestation data is verified by the TPM using the
+/// signing key. The certified contents are compared with the expected contents
+/// at the offset that is included in the attestation data, so the full contents
+/// of the index can be provided even if only a part of them was certified.
+///
+/// # Returns
+/// The verified [NvCertifyInfo].
+///
+/// # Errors
+/// * if the attestation data does not contain NV information, an `InvalidParam`
+///   wrapper error is returned.
+/// * if the qualifying data, the index name or the contents do not match the
+///   attestation data, an `InconsistentParams` wrapper error is returned.
+/// * if the signature is not valid, the error from the TPM is returned.
+pub fn verify_nv_attestation(
+    context: &mut Context,
+    signing_key_handle: KeyHandle,
+    attest: &Attest,
+    signature: Signature,
+    qualifying_data: &Data,
+    nv_index_name: &Name,
+    nv_contents: &[u8],
+) -> Result<NvCertifyInfo> {
+    let nv_certify_info = match attest.attested() {
+        AttestInfo::Nv { info } => info.clone(),
+        _ => {
+            error!("The attestation data does not contain NV information");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+    };
+
+    if attest.extra_data() != qualifying_data {
+        error!("The qualifying data does not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    if nv_certify_info.index_name() != nv_index_name {
+        error!("The NV index name does not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    let start = usize::from(nv_certify_info.offset());
+    let end = start + nv_certify_info.nv_contents().len();
+    if nv_contents.get(start..end) != Some(nv_certify_info.nv_contents().as_slice()) {
+        error!("The NV contents do not match the attestation data");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    verify_signature(context, signing_key_handle, attest, signature)?;
+
+    Ok(nv_certify_info)
+}
+
+/// Verifies the signature over the attestation data with the signing key.
+pub(super) fn verify_signature(
+    context: &mut Context,
+    signing_key_handle: KeyHandle,
+    attest: &Attest,
+    signature: Signature,
+) -> Result<()> {
+    let (digest, _) = context.hash(
+        MaxBuffer::try_from(attest.marshall()?)?,
+        signature_hashing_algorithm(&signature)?,
+        Hierarchy::Null,
+    )?;
+    let _ = context.verify_signature(signing_key_handle, digest, signature)?;
+    Ok(())
+}
+
+/// Returns the hashing algorithm that was used to create the signature.
+fn signature_hashing_algorithm(signature: &Signature) -> Result<HashingAlgorithm> {
+    match signature {
+        Signature::RsaSsa(rsa_signature) | Signature::RsaPss(rsa_signature) => {
+            Ok(rsa_signature.hashing_algorithm())
+        }
+        Signature::EcDsa(ecc_signature)
+        | Signature::EcDaa(ecc_signature)
+        | Signature::Sm2(ecc_signature)
+        | Signature::EcSchnorr(ecc_signature) => Ok(ecc_signature.hashing_algorithm()),
+        Signature::Hmac(hash_agile) => Ok(hash_agile.algorithm()),
+        Signature::Null => {
+            error!("Attestation data without a signature cannot be verified");
+            Err(Error::local_error(WrapperErrorKind::InvalidParam))
+        }
+    }
+}
@@ -0,0 +1,479 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module for auditing the commands executed in a session
+//!
+//! An [AuditSession] is an HMAC session with the `audit` attribute set. The
+//! TPM extends the audit digest of the session with the command and response
+//! parameters of every command that is executed with it. The digest can then
+//! be signed by the TPM and verified with [AuditSession::report].
+//!
+//! An [AuditSessionGuard] closes the audit session when it is dropped, and
+//! keeps track of whether the session has been exclusive.
+use crate::{
+    abstraction::attestation,
+    attributes::SessionAttributesBuilder,
+    constants::{SessionType, TpmFormatZeroError},
+    error::{TpmFormatZeroResponseCode, TpmResponseCode},
+    handles::{KeyHandle, SessionHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        session_handles::{AuthSession, HmacSession},
+    },
+    structures::{
+        Attest, AttestInfo, Data, Digest, SessionAuditInfo, Signature, SignatureScheme,
+        SymmetricDefinition,
+    },
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
+
+/// An HMAC session used to audit commands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AuditSession {
+    session: HmacSession,
+}
+
+impl AuditSession {
+    /// Starts a new audit session
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `hashing_algorithm` - The [HashingAlgorithm] of the session, which is
+    ///                         also used to compute the audit digest.
+    ///
+    /// # Details
+    /// The session is unbound and unsalted and it is kept alive until it is
+    /// closed with [AuditSession::close].
+    pub fn start(context: &mut Context, hashing_algorithm: HashingAlgorithm) -> Result<Self> {
+        let session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Hmac,
+                SymmetricDefinition::Null,
+                hashing_algorithm,
+            )?
+            .ok_or_else(|| {
+                error!("Received an invalid handle when starting the audit session");
+                Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+            })?;
+        let (session_attributes, session_attributes_mask) =
+            SessionAttributesBuilder::for_audit().build();
+        let result =
+            context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask);
+        if let Err(e) = result {
+            let _ = context.flush_context(SessionHandle::from(session).into());
+            return Err(e);
+        }
+        Ok(AuditSession {
+            session: HmacSession::try_from(session)?,
+        })
+    }
+
+    /// Starts a new audit session, and returns a guard that closes it when
+    /// it is dropped.
+    ///
+    /// # Errors
+    /// * the errors returned by [AuditSession::start]
+    pub fn start_scoped(
+        context: &mut Context,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Result<AuditSessionGuard<'_>> {
+        let session = AuditSession::start(context, hashing_algorithm)?;
+        Ok(AuditSessionGuard {
+            context,
+            session,
+            executions: 0,
+            exclusive: true,
+            close_on_drop: true,
+        })
+    }
+
+    /// Returns the underlying [AuthSession].
+    pub fn session(&self) -> AuthSession {
+        self.session.into()
+    }
+
+    /// Executes the closure with the audit session as the first session
+    ///
+    /// # Details
+    /// Every command executed by `f` is recorded in the audit digest. As the
+    /// session is also an HMAC session, it can be used to authorize the commands,
+    /// provided that the auth values of the entities have been set in the context.
+    pub fn execute<F, T>(&self, context: &mut Context, f: F) -> T
+    where
+        F: FnOnce(&mut Context) -> T,
+    {
+        context.execute_with_session(Some(self.session()), f)
+    }
+
+    /// Executes the closure with the audit session, requiring it to be exclusive
+    ///
+    /// # Details
+    /// The `audit_exclusive` attribute is set for the commands executed by `f`,
+    /// so the TPM rejects them with a `TPM_RC_EXCLUSIVE` error if any command has
+    /// been executed without the audit session since it was last used.
+    ///
+    /// # Errors
+    /// * if `f` fails, its error is returned, even if clearing the `audit_exclusive`
+    ///   attribute afterwards fails as well, in which case that failure is logged
+    pub fn execute_exclusive<F, T>(&self, context: &mut Context, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+            .with_audit_exclusive(true)
+            .build();
+        context.tr_sess_set_attributes(
+            self.session(),
+            session_attributes,
+            session_attributes_mask,
+        )?;
+        let result = self.execute(context, f);
+        let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+            .with_audit_exclusive(false)
+            .build();
+        let restore_result = context
+            .tr_sess_set_attributes(self.session(), session_attributes, session_attributes_mask)
+            .map_err(|e| {
+                error!(
+                    "Failed to clear the audit exclusive attribute of the session: {}",
+                    e
+                );
+                e
+            });
+        let value = result?;
+        restore_result?;
+        Ok(value)
+    }
+
+    /// Creates a signed report of the commands executed with the audit session
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `signing_key_handle` - Handle of the key used to sign the report.
+    /// * `qualifying_data` - Qualifying data included in the report.
+    ///
+    /// # Details
+    /// The report is created with [Context::get_session_audit_digest], using
+    /// password sessions for the endorsement hierarchy and for the signing key,
+    /// whose auth values must have been set in the context. The signature over
+    /// the report is verified by the TPM before it is returned.
+    ///
+    /// # Errors
+    /// * if the attestation data does not match the qualifying data or does not
+    ///   contain session audit information, an `InconsistentParams` wrapper error
+    ///   is returned
+    /// * if the signature is not valid, the error from the TPM is returned
+    pub fn report(
+        &self,
+        context: &mut Context,
+        signing_key_handle: KeyHandle,
+        qualifying_data: Data,
+    ) -> Result<AuditReport> {
+        let (attest, signature) = context.execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                ctx.get_session_audit_digest(
+                    signing_key_handle,
+                    self.session,
+                    qualifying_data.clone(),
+                    SignatureScheme::Null,
+                )
+            },
+        )?;
+
+        let info = session_audit_info(&attest, &qualifying_data)?;
+        context.execute_without_session(|ctx| {
+            attestation::verify_signature(ctx, signing_key_handle, &attest, signature.clone())
+        })?;
+
+        Ok(AuditReport {
+            attest,
+            signature,
+            info,
+        })
+    }
+
+    /// Returns the unsigned attestation data of the commands executed with
+    /// the audit session
+    ///
+    /// # Details
+    /// The attestation data is obtained with [Context::get_session_audit_digest]
+    /// without a signing key, using a password session for the endorsement
+    /// hierarchy, whose auth value must have been set in the context.
+    ///
+    /// # Errors
+    /// * if the attestation data does not match the qualifying data or does not
+    ///   contain session audit information, an `InconsistentParams` wrapper error
+    ///   is returned
+    pub fn digest(
+        &self,
+        context: &mut Context,
+        qualifying_data: Data,
+    ) -> Result<SessionAuditDigest> {
+        let (attest, _) = context.execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                ctx.get_session_audit_digest(
+                    KeyHandle::Null,
+                    self.session,
+                    qualifying_data.clone(),
+                    SignatureScheme::Null,
+                )
+            },
+        )?;
+        let info = session_audit_info(&attest, &qualifying_data)?;
+        Ok(SessionAuditDigest { attest, info })
+    }
+
+    /// Closes the audit session
+    pub fn close(self, context: &mut Context) -> Result<()> {
+        context.flush_context(SessionHandle::from(self.session()).into())
+    }
+}
+
+/// An audit session that is closed when the guard is dropped
+///
+/// # Details
+/// The guard borrows the [Context] for as long as the audit session is
+/// alive, so every command executed with the context while the guard is
+/// alive goes through [AuditSessionGuard::execute] or
+/// [AuditSessionGuard::context].
+///
+/// Every closure executed after the first one is executed with the
+/// `audit_exclusive` attribute set. If a command that did not use the audit
+/// session was executed in the meantime, for instance by another application
+/// or through [AuditSessionGuard::context], the TPM rejects the command with
+/// a `TPM_RC_EXCLUSIVE` error. The guard then records that the session is
+/// no longer exclusive, and the following closures are executed without
+/// requiring exclusivity.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     abstraction::audit::AuditSession,
+/// #     interface_types::algorithm::HashingAlgorithm,
+/// #     structures::Data,
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// let mut audit_session = AuditSession::start_scoped(&mut context, HashingAlgorithm::Sha256)
+///     .expect("Failed to start the audit session");
+/// let random_bytes = audit_session
+///     .execute(|ctx| ctx.get_random(16))
+///     .expect("Failed to get random bytes");
+/// let digest = audit_session
+///     .finalize(Data::default())
+///     .expect("Failed to get the audit digest");
+/// assert!(digest.exclusive());
+/// ```
+#[derive(Debug)]
+pub struct AuditSessionGuard<'a> {
+    context: &'a mut Context,
+    session: AuditSession,
+    executions: usize,
+    exclusive: bool,
+    close_on_drop: bool,
+}
+
+impl AuditSessionGuard<'_> {
+    /// Returns the audit session.
+    pub fn session(&self) -> AuditSession {
+        self.session
+    }
+
+    /// Returns the context with which the audit session was started.
+    ///
+    /// # Details
+    /// The commands executed directly with the context are not audited,
+    /// and break the exclusivity of the audit session.
+    pub fn context(&mut self) -> &mut Context {
+        self.context
+    }
+
+    /// Returns the number of closures that have been executed successfully
+    /// with the audit session.
+    pub fn executions(&self) -> usize {
+        self.executions
+    }
+
+    /// Returns false if the guard has detected that a command was executed
+    /// without the audit session since it was first used.
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// Executes the closure with the audit session as the first session
+    ///
+    /// # Details
+    /// See [AuditSessionGuard] for how the exclusivity of the session is
+    /// tracked.
+    ///
+    /// # Errors
+    /// * the errors returned by the closure. If the TPM rejects a command
+    ///   because the session is no longer exclusive, the `TPM_RC_EXCLUSIVE`
+    ///   error is returned, and the closure is not executed again.
+    pub fn execute<F, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        let result = if self.executions > 0 && self.exclusive {
+            self.session.execute_exclusive(self.context, f)
+        } else {
+            self.session.execute(self.context, f)
+        };
+        match &result {
+            Ok(_) => self.executions += 1,
+            Err(e) if is_exclusive_error(e) => {
+                error!("The audit session is no longer exclusive");
+                self.exclusive = false;
+            }
+            Err(_) => (),
+        }
+        result
+    }
+
+    /// Returns the unsigned attestation data of the commands executed with
+    /// the audit session, and closes the session.
+    ///
+    /// # Errors
+    /// * the errors returned by [AuditSession::digest]. The session is closed
+    ///   even if they fail.
+    pub fn finalize(mut self, qualifying_data: Data) -> Result<SessionAuditDigest> {
+        let result = self.session.digest(self.context, qualifying_data);
+        self.close_on_drop = false;
+        let close_result = self.session.close(self.context);
+        let digest = result?;
+        close_result?;
+        Ok(digest)
+    }
+
+    /// Creates a signed report of the commands executed with the audit
+    /// session, and closes the session.
+    ///
+    /// # Errors
+    /// * the errors returned by [AuditSession::report]. The session is closed
+    ///   even if they fail.
+    pub fn finalize_signed(
+        mut self,
+        signing_key_handle: KeyHandle,
+        qualifying_data: Data,
+    ) -> Result<AuditReport> {
+        let result = self
+            .session
+            .report(self.context, signing_key_handle, qualifying_data);
+        self.close_on_drop = false;
+        let close_result = self.session.close(self.context);
+        let report = result?;
+        close_result?;
+        Ok(report)
+    }
+}
+
+impl Drop for AuditSessionGuard<'_> {
+    fn drop(&mut self) {
+        if self.close_on_drop {
+            let _ = self.session.close(self.context);
+        }
+    }
+}
+
+/// The unsigned attestation data of the commands executed with an [AuditSession].
+///
+/// # Details
+/// The attestation data can be marshalled with [Marshall](crate::traits::Marshall)
+/// in order to be signed, by a key of the TPM or by an external key.
+#[derive(Debug, Clone)]
+pub struct SessionAuditDigest {
+    attest: Attest,
+    info: SessionAuditInfo,
+}
+
+impl SessionAuditDigest {
+    /// Returns the attestation data.
+    pub const fn attest(&self) -> &Attest {
+        &self.attest
+    }
+
+    /// Returns the audit digest of the session.
+    pub const fn session_digest(&self) -> &Digest {
+        self.info.session_digest()
+    }
+
+    /// Returns true if all the audited commands were executed without
+    /// any intervening command that did not use the audit session.
+    pub fn exclusive(&self) -> bool {
+        self.info.exlusive_session()
+    }
+}
+
+/// A signed and verified report of the commands executed with an [AuditSession].
+#[derive(Debug, Clone)]
+pub struct AuditReport {
+    attest: Attest,
+    signature: Signature,
+    info: SessionAuditInfo,
+}
+
+impl AuditReport {
+    /// Returns the attestation data signed by the TPM.
+    pub const fn attest(&self) -> &Attest {
+        &self.attest
+    }
+
+    /// Returns the signature over the attestation data.
+    pub const fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    /// Returns the audit digest of the session.
+    pub const fn session_digest(&self) -> &Digest {
+        self.info.session_digest()
+    }
+
+    /// Returns true if all the audited commands were executed without
+    /// any intervening command that did not use the audit session.
+    pub fn exclusive(&self) -> bool {
+        self.info.exlusive_session()
+    }
+}
+
+/// Private function returning the session audit information of the attestation
+/// data, after checking that it contains the qualifying data.
+fn session_audit_info(attest: &Attest, qualifying_data: &Data) -> Result<SessionAuditInfo> {
+    if attest.extra_data() != qualifying_data {
+        error!("The qualifying data does not match the audit report");
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+    match attest.attested() {
+        AttestInfo::SessionAudit { info } => Ok(info.clone()),
+        _ => {
+            error!("The audit report does not contain session audit information");
+            Err(Error::local_error(WrapperErrorKind::InconsistentParams))
+        }
+    }
+}
+
+/// Private function returning true if the error is a `TPM_RC_EXCLUSIVE` error.
+fn is_exclusive_error(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatZero(
+            TpmFormatZeroResponseCode::Error(tpm_error),
+        ))) if tpm_error.error_number() == TpmFormatZeroError::Exclusive
+    )
+}
@@ -0,0 +1,141 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    abstraction::cipher::Cipher,
+    attributes::SessionAttributesBuilder,
+    constants::SessionType,
+    handles::{AuthHandle, KeyHandle, SessionHandle},
+    interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+    structures::{Digest, EncryptedSecret, IdObject},
+    Context, Error, Result, WrapperErrorKind,
+};
+use std::convert::{TryFrom, TryInto};
+
+#[cfg(feature = "openssl")]
+use crate::{
+    abstraction::outer_wrapper,
+    structures::{Name, Public},
+};
+
+#[cfg(feature = "openssl")]
+const IDENTITY_LABEL: &[u8] = b"IDENTITY\0";
+
+/// Activates a credential for an Attestation Key using an Endorsement Key
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `ak_handle` - The [KeyHandle] of the loaded Attestation Key for which
+///                 the credential was made.
+/// * `ek_handle` - The [KeyHandle] of the loaded Endorsement Key that was
+///                 used to protect the credential.
+/// * `credential_blob` - The credential that was created by `MakeCredential`.
+/// * `secret` - The secret that was created by `MakeCredential`.
+///
+/// # Details
+/// The Endorsement Key is expected to use the default policy from the
+/// TCG EK Credential Profile, i.e. the `PolicySecret` of the endorsement
+/// hierarchy. A policy session satisfying that policy is started and
+/// flushed after the credential has been activated.
+///
+/// The authorization value of the Attestation Key, and of the endorsement
+/// hierarchy, needs to have been set in the context (see [Context::tr_set_auth]).
+///
+/// # Returns
+/// The decrypted credential.
+pub fn activate_credential(
+    context: &mut Context,
+    ak_handle: KeyHandle,
+    ek_handle: KeyHandle,
+    credential_blob: IdObject,
+    secret: EncryptedSecret,
+) -> Result<Digest> {
+    let policy_auth_session = context
+        .start_auth_session(
+            None,
+            None,
+            None,
+            SessionType::Policy,
+            Cipher::aes_128_cfb().try_into()?,
+            HashingAlgorithm::Sha256,
+        )?
+        .ok_or_else(|| Error::local_error(WrapperErrorKind::WrongValueFromTpm))?;
+
+    let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+        .with_decrypt(true)
+        .with_encrypt(true)
+        .build();
+    context.tr_sess_set_attributes(
+        policy_auth_session,
+        session_attributes,
+        session_attributes_mask,
+    )?;
+
+    context.execute_with_temporary_object(
+        SessionHandle::from(policy_auth_session).into(),
+        |ctx, _| {
+            let _ = ctx.execute_with_nullauth_session(|ctx| {
+                ctx.policy_secret(
+                    PolicySession::try_from(policy_auth_session)?,
+                    AuthHandle::Endorsement,
+                    Default::default(),
+                    Default::default(),
+                    Default::default(),
+                    None,
+                )
+            })?;
+
+            ctx.execute_with_nullauth_session(|ctx| {
+                let (ak_auth_session, _, _) = ctx.sessions();
+                ctx.execute_with_sessions(
+                    (ak_auth_session, Some(policy_auth_session), None),
+                    |ctx| ctx.activate_credential(ak_handle, ek_handle, credential_blob, secret),
+                )
+            })
+        },
+    )
+}
+
+/// Makes a credential for an object without using a TPM
+///
+/// # Arguments
+/// * `ek_public` - The [Public] of the Endorsement Key of the TPM that is
+///                 going to activate the credential. Only RSA and ECC keys
+///                 with an AES CFB symmetric definition are supported.
+/// * `credential` - The credential that is to be protected.
+/// * `object_name` - The [Name] of the object for which the credential is
+///                   made, usually an Attestation Key.
+///
+/// # Details
+/// This is a software implementation of `TPM2_MakeCredential`, meant to be
+/// used by attestation servers that do not have access to a TPM. The result
+/// can be activated with [activate_credential] on the TPM holding the
+/// Endorsement Key, provided the object with the given name is loaded.
+///
+/// The function is only available when the `openssl` feature is enabled.
+///
+/// # Returns
+/// The credential blob, as an [IdObject], and the encrypted secret, as an
+/// [EncryptedSecret].
+///
+/// # Errors
+/// * if the Endorsement Key is not an RSA or ECC key, or uses an unsupported symmetric
+///   definition, curve or hashing algorithm, an `UnsupportedParam` wrapper error is returned
+/// * if any of the cryptographic operations fail an `InternalError` wrapper error is returned
+#[cfg(feature = "openssl")]
+pub fn make_credential(
+    ek_public: &Public,
+    credential: Digest,
+    object_name: Name,
+) -> Result<(IdObject, EncryptedSecret)> {
+    let (credential_blob, secret) = outer_wrapper::wrap(
+        ek_public,
+        IDENTITY_LABEL,
+        object_name.value(),
+        &outer_wrapper::size_prefixed(credential.as_bytes())?,
+    )?;
+    Ok((
+        IdObject::try_from(credential_blob)?,
+        EncryptedSecret::try_from(secret)?,
+    ))
+}
@@ -0,0 +1,74 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    constants::{tss::TPMA_PERMANENT_INLOCKOUT, CapabilityType, PropertyTag},
+    structures::{CapabilityData, TaggedTpmPropertyList},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// The state of the dictionary attack protection of the TPM
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LockoutStatus {
+    /// The current number of authorization failures.
+    pub lockout_counter: u32,
+    /// The number of authorization failures before the TPM enters lockout.
+    pub max_auth_fail: u32,
+    /// The time in seconds before the failure counter is decremented.
+    pub lockout_interval: u32,
+    /// The time in seconds after a failed lockout authorization before
+    /// the lockout authorization can be used again.
+    pub lockout_recovery: u32,
+    /// Whether the TPM is currently in lockout.
+    pub in_lockout: bool,
+}
+
+/// Retrieves the state of the dictionary attack protection of the TPM
+///
+/// # Details
+/// The values are read with [Context::get_capability] on every call, bypassing
+/// the cache used by [Context::get_tpm_property], as the lockout counter and the
+/// lockout state change while the TPM is in use.
+///
+/// # Errors
+/// * if the TPM does not return all of the lockout related properties, a
+///   `WrongValueFromTpm` wrapper error is returned
+pub fn get_lockout_status(context: &mut Context) -> Result<LockoutStatus> {
+    let property_count =
+        u32::from(PropertyTag::LockoutRecovery) - u32::from(PropertyTag::Permanent) + 1;
+    let (capability_data, _) = context.execute_without_session(|ctx| {
+        ctx.get_capability(
+            CapabilityType::TpmProperties,
+            PropertyTag::Permanent.into(),
+            property_count,
+        )
+    })?;
+    let properties = match capability_data {
+        CapabilityData::TpmProperties(properties) => properties,
+        _ => return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+    };
+
+    Ok(LockoutStatus {
+        lockout_counter: property_value(&properties, PropertyTag::LockoutCounter)?,
+        max_auth_fail: property_value(&properties, PropertyTag::MaxAuthFail)?,
+        lockout_interval: property_value(&properties, PropertyTag::LockoutInterval)?,
+        lockout_recovery: property_value(&properties, PropertyTag::LockoutRecovery)?,
+        in_lockout: (property_value(&properties, PropertyTag::Permanent)?
+            & TPMA_PERMANENT_INLOCKOUT)
+            != 0,
+    })
+}
+
+fn property_value(properties: &TaggedTpmPropertyList, property_tag: PropertyTag) -> Result<u32> {
+    properties
+        .find(property_tag)
+        .map(|tagged_property| tagged_property.value())
+        .ok_or_else(|| {
+            error!(
+                "Missing property {:?} in the response from the TPM",
+                property_tag
+            );
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })
+}
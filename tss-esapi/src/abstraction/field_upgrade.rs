@@ -0,0 +1,120 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module for updating and reading the TPM firmware
+//!
+//! The format of the firmware image, the size of its blocks and the manifest
+//! used to start the upgrade are defined by the TPM vendor. The functions in
+//! this module only take care of splitting the image in blocks that fit in a
+//! [MaxBuffer] and of sending them to the TPM, or of reading them back.
+use crate::{
+    constants::TpmFormatOneError,
+    error::TpmResponseCode,
+    structures::{HashAgile, MaxBuffer},
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::{convert::TryFrom, io::Read};
+
+/// The digests reported by the TPM after a block of the firmware image
+/// has been sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldUpgradeDigests {
+    /// The digest of the block that the TPM expects next, or `None` if the
+    /// last block has been received.
+    pub next_digest: Option<HashAgile>,
+    /// The digest of the first block of the field upgrade sequence.
+    pub first_digest: HashAgile,
+}
+
+/// Sends a firmware image to the TPM
+///
+/// # Details
+/// The image is read in blocks of at most [MaxBuffer::MAX_SIZE] bytes, which
+/// are sent in order with [Context::field_upgrade_data]. After each block,
+/// `on_block` is called with the digests reported by the TPM, so that the
+/// caller can track the progress of the upgrade or verify the digests
+/// against the manifest.
+///
+/// The upgrade stops when the image has been read completely or when the TPM
+/// reports that it does not expect any more blocks.
+///
+/// The field upgrade must have been started with [Context::field_upgrade_start].
+///
+/// # Errors
+/// * if reading from `image` fails, an `InvalidParam` wrapper error is returned
+/// * if the TPM reports that it does not expect any more blocks while part of
+///   the image has not been sent yet, a `WrongParamSize` wrapper error is returned
+pub fn upload_firmware<R, F>(context: &mut Context, mut image: R, mut on_block: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(&FieldUpgradeDigests),
+{
+    let mut block = read_block(&mut image)?;
+    while !block.is_empty() {
+        let (next_digest, first_digest) =
+            context.field_upgrade_data(MaxBuffer::try_from(block)?)?;
+        let digests = FieldUpgradeDigests {
+            next_digest,
+            first_digest,
+        };
+        on_block(&digests);
+
+        block = read_block(&mut image)?;
+        if digests.next_digest.is_none() && !block.is_empty() {
+            error!("The TPM does not expect more blocks but the firmware image has not ended");
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+    }
+    Ok(())
+}
+
+/// Reads the firmware installed in the TPM
+///
+/// # Details
+/// The blocks are read with [Context::firmware_read], starting with sequence
+/// number 0, until the end of the data is reached. The end of the data is
+/// either an empty block or a `TPM_RC_VALUE` error for a sequence number
+/// after the first block. The blocks are concatenated in the order they are
+/// returned by the TPM.
+///
+/// # Errors
+/// * if the TPM returns more blocks than can be numbered by a sequence number,
+///   a `WrongValueFromTpm` wrapper error is returned
+pub fn read_firmware(context: &mut Context) -> Result<Vec<u8>> {
+    let mut firmware = Vec::new();
+    let mut sequence_number: u32 = 0;
+    loop {
+        let block = match context.firmware_read(sequence_number) {
+            Ok(block) => block,
+            Err(Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatOne(response_code))))
+                if sequence_number > 0
+                    && response_code.error_number() == TpmFormatOneError::Value =>
+            {
+                return Ok(firmware);
+            }
+            Err(e) => return Err(e),
+        };
+        if block.is_empty() {
+            return Ok(firmware);
+        }
+        firmware.extend_from_slice(block.as_bytes());
+        sequence_number = sequence_number.checked_add(1).ok_or_else(|| {
+            error!("The TPM returned more firmware blocks than can be read");
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+    }
+}
+
+fn read_block<R: Read>(image: &mut R) -> Result<Vec<u8>> {
+    let mut block = Vec::with_capacity(MaxBuffer::MAX_SIZE);
+    let _ = image
+        .by_ref()
+        .take(MaxBuffer::MAX_SIZE as u64)
+        .read_to_end(&mut block)
+        .map_err(|e| {
+            error!("Failed to read the firmware image: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+    Ok(block)
+}
@@ -0,0 +1,169 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+mod registry;
+
+pub use registry::PersistentHandleRegistry;
+
+use crate::{
+    constants::{
+        tss::{TPM2_PERSISTENT_FIRST, TPM2_PERSISTENT_LAST, TPM2_PLATFORM_PERSISTENT},
+        CapabilityType,
+    },
+    handles::{KeyHandle, ObjectHandle, PersistentTpmHandle, TpmHandle},
+    interface_types::{dynamic_handles::Persistent, resource_handles::Provision},
+    structures::{CapabilityItem, Name, Public},
+    tss2_esys::TPM2_HANDLE,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
+
+/// Lists all the persistent objects' handles, names and public areas
+///
+/// # Details
+/// The handles of the persistent objects are retrieved with
+/// [Context::get_capabilities_iter], and each object is then read with
+/// [Context::tr_from_tpm_public] and [Context::read_public]. The ESYS
+/// handles of the objects are closed before returning.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf};
+/// use tss_esapi::abstraction::handles;
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// for (persistent_handle, _name, public) in
+///     handles::list_persistent(&mut context).expect("Failed to list the persistent objects")
+/// {
+///     println!("{:?}: {:?}", persistent_handle, public.object_attributes());
+/// }
+/// ```
+pub fn list_persistent(context: &mut Context) -> Result<Vec<(PersistentTpmHandle, Name, Public)>> {
+    context.execute_without_session(|ctx| {
+        get_persistent_handles(ctx, TPM2_PERSISTENT_FIRST)?
+            .into_iter()
+            .map(|persistent_handle| {
+                get_persistent_info(ctx, persistent_handle)
+                    .map(|(name, public)| (persistent_handle, name, public))
+            })
+            .collect()
+    })
+}
+
+/// Returns the Name and Public associated with a persistent TPM handle
+fn get_persistent_info(
+    context: &mut Context,
+    persistent_handle: PersistentTpmHandle,
+) -> Result<(Name, Public)> {
+    context
+        .tr_from_tpm_public(TpmHandle::Persistent(persistent_handle))
+        .and_then(|mut object_handle| {
+            context
+                .read_public(KeyHandle::from(object_handle))
+                .map_err(|e| {
+                    let _ = context.tr_close(&mut object_handle);
+                    e
+                })
+                .and_then(|(public, name, _)| {
+                    context.tr_close(&mut object_handle)?;
+                    Ok((name, public))
+                })
+        })
+}
+
+/// Returns the first persistent handle that is not used, in the range of
+/// persistent handles of the hierarchy
+///
+/// # Details
+/// The owner hierarchy uses the handles from `0x81000000` to `0x817FFFFF`,
+/// and the platform hierarchy the handles from `0x81800000` to `0x81FFFFFF`.
+///
+/// # Errors
+/// * if all the handles of the range are used, an `InvalidParam` error is returned
+pub fn find_free_persistent(
+    context: &mut Context,
+    provision: Provision,
+) -> Result<PersistentTpmHandle> {
+    let (first, last) = persistent_range(provision);
+    let used_handles = context.execute_without_session(|ctx| get_persistent_handles(ctx, first))?;
+    let mut candidate = first;
+    for used_handle in used_handles.into_iter().map(TPM2_HANDLE::from) {
+        if used_handle > candidate || candidate > last {
+            break;
+        }
+        if used_handle == candidate {
+            candidate += 1;
+        }
+    }
+    if candidate > last {
+        error!("All the persistent handles of the hierarchy are used");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    PersistentTpmHandle::new(candidate)
+}
+
+/// Makes a transient object persistent, at the first free persistent handle
+/// of the hierarchy
+///
+/// # Details
+/// See [find_free_persistent]. This requires an authorization session for
+/// the hierarchy. The ESYS handle of the persistent object is closed, the
+/// transient object remains loaded.
+pub fn make_persistent(
+    context: &mut Context,
+    provision: Provision,
+    object_handle: ObjectHandle,
+) -> Result<PersistentTpmHandle> {
+    let persistent_handle = find_free_persistent(context, provision)?;
+    let mut persistent_object_handle = context.evict_control(
+        provision,
+        object_handle,
+        Persistent::Persistent(persistent_handle),
+    )?;
+    context.tr_close(&mut persistent_object_handle)?;
+    Ok(persistent_handle)
+}
+
+/// Evicts a persistent object from the TPM
+///
+/// # Details
+/// This requires an authorization session for the hierarchy.
+pub fn evict_persistent(
+    context: &mut Context,
+    provision: Provision,
+    persistent_handle: PersistentTpmHandle,
+) -> Result<()> {
+    let object_handle = context.execute_without_session(|ctx| {
+        ctx.tr_from_tpm_public(TpmHandle::Persistent(persistent_handle))
+    })?;
+    let _ = context.evict_control(
+        provision,
+        object_handle,
+        Persistent::Persistent(persistent_handle),
+    )?;
+    Ok(())
+}
+
+/// Returns the first and last persistent handles of the hierarchy
+fn persistent_range(provision: Provision) -> (TPM2_HANDLE, TPM2_HANDLE) {
+    match provision {
+        Provision::Owner => (TPM2_PERSISTENT_FIRST, TPM2_PLATFORM_PERSISTENT - 1),
+        Provision::Platform => (TPM2_PLATFORM_PERSISTENT, TPM2_PERSISTENT_LAST),
+    }
+}
+
+/// Returns the persistent handles, starting with `first`, in increasing order
+fn get_persistent_handles(
+    context: &mut Context,
+    first: TPM2_HANDLE,
+) -> Result<Vec<PersistentTpmHandle>> {
+    context
+        .get_capabilities_iter(CapabilityType::Handles, first)
+        .map(|item| match item? {
+            CapabilityItem::Handle(tpm_handle) => PersistentTpmHandle::try_from(tpm_handle),
+            _ => Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+        })
+        .collect()
+}
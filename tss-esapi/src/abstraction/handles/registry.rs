@@ -0,0 +1,210 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use super::{evict_persistent, make_persistent};
+use crate::{
+    handles::{ObjectHandle, PersistentTpmHandle},
+    interface_types::resource_handles::Provision,
+    tss2_esys::TPM2_HANDLE,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path};
+
+/// A registry mapping names to persistent handles
+///
+/// # Details
+/// The registry allocates the persistent handles of the objects made
+/// persistent with [PersistentHandleRegistry::allocate], so that they can
+/// later be found by name. It can be stored in a file, with
+/// [PersistentHandleRegistry::save] and [PersistentHandleRegistry::load],
+/// or serialized with `serde`.
+///
+/// The file has one line per name, holding the persistent handle in
+/// hexadecimal followed by a space and the name, e.g. `0x81000001 srk`.
+///
+/// The registry is not synchronized with the TPM: an entry is left in the
+/// registry if its object is evicted by other means, and can then be removed
+/// with [PersistentHandleRegistry::remove].
+///
+/// # Example
+/// ```rust,no_run
+/// # use tss_esapi::{Context, TctiNameConf, handles::ObjectHandle};
+/// use tss_esapi::{
+///     abstraction::handles::PersistentHandleRegistry,
+///     interface_types::resource_handles::Provision,
+/// };
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// # let key_handle = ObjectHandle::None;
+/// let mut registry = PersistentHandleRegistry::load("/var/lib/service/handles")
+///     .expect("Failed to load the registry");
+/// let _ = context
+///     .execute_with_nullauth_session(|ctx| {
+///         registry.allocate(ctx, "srk", Provision::Owner, key_handle)
+///     })
+///     .expect("Failed to make the key persistent");
+/// registry
+///     .save("/var/lib/service/handles")
+///     .expect("Failed to save the registry");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistentHandleRegistry {
+    handles: BTreeMap<String, TPM2_HANDLE>,
+}
+
+impl PersistentHandleRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the registry from a file.
+    ///
+    /// # Details
+    /// If the file does not exist, an empty registry is returned.
+    ///
+    /// # Errors
+    /// * if the file can not be read, or if it is not a valid registry,
+    ///   an `InvalidParam` error is returned
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = match fs::read_to_string(path.as_ref()) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => {
+                error!("Failed to read the persistent handle registry: {}", e);
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+        };
+        let mut registry = Self::new();
+        for line in content.lines().filter(|line| !line.is_empty()) {
+            let (handle, name) = line.split_once(' ').ok_or_else(|| {
+                error!("Invalid entry in the persistent handle registry: {}", line);
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })?;
+            let handle =
+                TPM2_HANDLE::from_str_radix(handle.trim_start_matches("0x"), 16).map_err(|e| {
+                    error!("Invalid handle in the persistent handle registry: {}", e);
+                    Error::local_error(WrapperErrorKind::InvalidParam)
+                })?;
+            registry.insert(name, PersistentTpmHandle::new(handle)?)?;
+        }
+        Ok(registry)
+    }
+
+    /// Saves the registry to a file.
+    ///
+    /// # Details
+    /// The registry is written to a temporary file in the same directory,
+    /// which then replaces the file, so that the file is never left partially
+    /// written.
+    ///
+    /// # Errors
+    /// * if the file can not be written, an `InvalidParam` error is returned
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let content: String = self
+            .handles
+            .iter()
+            .map(|(name, handle)| format!("{:#010x} {}\n", handle, name))
+            .collect();
+        let mut temporary_path = path.as_os_str().to_owned();
+        temporary_path.push(".tmp");
+        fs::write(&temporary_path, content)
+            .and_then(|_| fs::rename(&temporary_path, path))
+            .map_err(|e| {
+                error!("Failed to write the persistent handle registry: {}", e);
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })
+    }
+
+    /// Returns the persistent handle registered with the name, if any.
+    pub fn get(&self, name: &str) -> Option<PersistentTpmHandle> {
+        self.handles
+            .get(name)
+            .and_then(|&handle| PersistentTpmHandle::new(handle).ok())
+    }
+
+    /// Returns the registered names, in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.handles.keys().map(String::as_str)
+    }
+
+    /// Registers a persistent handle with a name.
+    ///
+    /// # Errors
+    /// * if the name is empty, contains a line break, or is already
+    ///   registered, an `InvalidParam` error is returned
+    pub fn insert(&mut self, name: &str, persistent_handle: PersistentTpmHandle) -> Result<()> {
+        self.ensure_name_available(name)?;
+        let _ = self
+            .handles
+            .insert(name.to_owned(), persistent_handle.into());
+        Ok(())
+    }
+
+    /// Removes the name from the registry, without evicting the object.
+    pub fn remove(&mut self, name: &str) -> Option<PersistentTpmHandle> {
+        self.handles
+            .remove(name)
+            .and_then(|handle| PersistentTpmHandle::new(handle).ok())
+    }
+
+    /// Makes a transient object persistent at a free persistent handle, and
+    /// registers the handle with the name.
+    ///
+    /// # Details
+    /// See [make_persistent](super::make_persistent). This requires an
+    /// authorization session for the hierarchy.
+    ///
+    /// # Errors
+    /// * if the name is empty, contains a line break, or is already
+    ///   registered, an `InvalidParam` error is returned
+    pub fn allocate(
+        &mut self,
+        context: &mut Context,
+        name: &str,
+        provision: Provision,
+        object_handle: ObjectHandle,
+    ) -> Result<PersistentTpmHandle> {
+        self.ensure_name_available(name)?;
+        let persistent_handle = make_persistent(context, provision, object_handle)?;
+        let _ = self
+            .handles
+            .insert(name.to_owned(), persistent_handle.into());
+        Ok(persistent_handle)
+    }
+
+    /// Evicts the object registered with the name, and removes the name from
+    /// the registry.
+    ///
+    /// # Details
+    /// This requires an authorization session for the hierarchy.
+    ///
+    /// # Errors
+    /// * if the name is not registered, an `InvalidParam` error is returned
+    pub fn evict(&mut self, context: &mut Context, provision: Provision, name: &str) -> Result<()> {
+        let persistent_handle = self.get(name).ok_or_else(|| {
+            error!("The name {} is not registered", name);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        evict_persistent(context, provision, persistent_handle)?;
+        let _ = self.remove(name);
+        Ok(())
+    }
+
+    /// Private method checking that the name can be registered.
+    fn ensure_name_available(&self, name: &str) -> Result<()> {
+        if name.is_empty() || name.contains(['\n', '\r']) {
+            error!("Invalid name for a persistent handle: {:?}", name);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        if self.handles.contains_key(name) {
+            error!("The name {} is already registered", name);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    handles::{ObjectHandle, PcrHandle},
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+    structures::{Digest, DigestValues, HashcheckTicket, MaxBuffer},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// Hasher that uses a hash sequence in the TPM
+///
+/// # Details
+/// Allows data of arbitrary size to be hashed by the TPM. The data
+/// is sent to the TPM in chunks that are as large as the TPM
+/// allows for its input buffers.
+///
+/// The sequence object is created with an empty authorization value
+/// so an authorization session (e.g. a password session) needs to be
+/// set in the context when calling [TpmHasher::update] and [TpmHasher::finalize].
+///
+/// If the hasher is dropped before it has been finalized then the sequence
+/// object is flushed from the TPM.
+#[derive(Debug)]
+pub struct TpmHasher<'a> {
+    sequence: Sequence<'a>,
+}
+
+impl<'a> TpmHasher<'a> {
+    /// Starts a new hash sequence in the TPM.
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `hashing_algorithm` - The [HashingAlgorithm] to use.
+    pub fn new(context: &'a mut Context, hashing_algorithm: HashingAlgorithm) -> Result<Self> {
+        let buffer_size = super::max_input_buffer_size(context)?;
+        let sequence_handle = context.hash_sequence_start(hashing_algorithm, None)?;
+        Ok(TpmHasher {
+            sequence: Sequence::new(context, sequence_handle, buffer_size),
+        })
+    }
+
+    /// Adds data to the hash.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.sequence.update(data)
+    }
+
+    /// Completes the hash sequence.
+    ///
+    /// # Arguments
+    /// * `hierarchy` - The [Hierarchy] of the returned ticket.
+    ///
+    /// # Returns
+    /// The digest of all the data that has been added to the hasher and
+    /// a ticket that indicates if the digest can be used in a signing
+    /// operation that uses a restricted signing key.
+    pub fn finalize(mut self, hierarchy: Hierarchy) -> Result<(Digest, HashcheckTicket)> {
+        self.sequence.complete(hierarchy)
+    }
+}
+
+/// HMAC calculator that uses an HMAC sequence in the TPM
+///
+/// # Details
+/// Allows an HMAC of data of arbitrary size to be calculated by the TPM
+/// using a loaded keyed hash key. The data is sent to the TPM in chunks
+/// that are as large as the TPM allows for its input buffers.
+///
+/// An authorization session for the key needs to be set in the context
+/// when calling [TpmHmac::new]. The sequence object is created with an empty
+/// authorization value so an authorization session (e.g. a password session)
+/// needs to be set in the context when calling [TpmHmac::update] and
+/// [TpmHmac::finalize].
+///
+/// If the HMAC calculator is dropped before it has been finalized then the
+/// sequence object is flushed from the TPM.
+#[derive(Debug)]
+pub struct TpmHmac<'a> {
+    sequence: Sequence<'a>,
+}
+
+impl<'a> TpmHmac<'a> {
+    /// Starts a new HMAC sequence in the TPM.
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `key_handle` - The [ObjectHandle] of the keyed hash key to use.
+    /// * `hashing_algorithm` - The [HashingAlgorithm] to use.
+    pub fn new(
+        context: &'a mut Context,
+        key_handle: ObjectHandle,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Result<Self> {
+        let buffer_size = super::max_input_buffer_size(context)?;
+        let sequence_handle = context.hmac_sequence_start(key_handle, hashing_algorithm, None)?;
+        Ok(TpmHmac {
+            sequence: Sequence::new(context, sequence_handle, buffer_size),
+        })
+    }
+
+    /// Adds data to the HMAC.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.sequence.update(data)
+    }
+
+    /// Completes the HMAC sequence.
+    ///
+    /// # Returns
+    /// The HMAC of all the data that has been added.
+    pub fn finalize(mut self) -> Result<Digest> {
+        self.sequence
+            .complete(Hierarchy::Null)
+            .map(|(digest, _)| digest)
+    }
+}
+
+/// Event hasher that uses an event sequence in the TPM
+///
+/// # Details
+/// Allows an event of arbitrary size to be hashed by the TPM, using
+/// all the hashing algorithms for which the TPM has PCR banks, and
+/// extended into a PCR. The data is sent to the TPM in chunks that are
+/// as large as the TPM allows for its input buffers.
+///
+/// The sequence object is created with an empty authorization value
+/// so an authorization session (e.g. a password session) needs to be
+/// set in the context when calling [TpmEventSequence::update].
+/// [TpmEventSequence::finalize] requires one authorization session for
+/// the PCR and another one for the sequence object.
+///
+/// If the event sequence is dropped before it has been finalized then the
+/// sequence object is flushed from the TPM.
+#[derive(Debug)]
+pub struct TpmEventSequence<'a> {
+    sequence: Sequence<'a>,
+}
+
+impl<'a> TpmEventSequence<'a> {
+    /// Starts a new event sequence in the TPM.
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    pub fn new(context: &'a mut Context) -> Result<Self> {
+        let buffer_size = super::max_input_buffer_size(context)?;
+        let sequence_handle = context.hash_sequence_start(HashingAlgorithm::Null, None)?;
+        Ok(TpmEventSequence {
+            sequence: Sequence::new(context, sequence_handle, buffer_size),
+        })
+    }
+
+    /// Adds data to the event.
+    pub fn update(&mut self, data: &[u8]) -> Result<()> {
+        self.sequence.update(data)
+    }
+
+    /// Completes the event sequence.
+    ///
+    /// # Arguments
+    /// * `pcr_handle` - The [PcrHandle] of the PCR that is going to be extended
+    ///                  with the digests of the event. If `None` is provided then
+    ///                  no PCR is extended.
+    ///
+    /// # Returns
+    /// The digests of the event, one for each of the PCR banks.
+    pub fn finalize(mut self, pcr_handle: Option<PcrHandle>) -> Result<DigestValues> {
+        self.sequence.complete_event(pcr_handle)
+    }
+}
+
+/// A sequence object that is flushed when dropped
+/// unless it has been completed.
+#[derive(Debug)]
+struct Sequence<'a> {
+    context: &'a mut Context,
+    sequence_handle: Option<ObjectHandle>,
+    buffer_size: usize,
+}
+
+impl<'a> Sequence<'a> {
+    fn new(context: &'a mut Context, sequence_handle: ObjectHandle, buffer_size: usize) -> Self {
+        Sequence {
+            context,
+            sequence_handle: Some(sequence_handle),
+            buffer_size,
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) -> Result<()> {
+        let sequence_handle = self.sequence_handle()?;
+        for chunk in data.chunks(self.buffer_size) {
+            self.context
+                .sequence_update(sequence_handle, MaxBuffer::from_bytes(chunk)?)?;
+        }
+        Ok(())
+    }
+
+    fn complete(&mut self, hierarchy: Hierarchy) -> Result<(Digest, HashcheckTicket)> {
+        let sequence_handle = self.sequence_handle()?;
+        let result =
+            self.context
+                .sequence_complete(sequence_handle, MaxBuffer::default(), hierarchy)?;
+        self.sequence_handle = None;
+        Ok(result)
+    }
+
+    fn complete_event(&mut self, pcr_handle: Option<PcrHandle>) -> Result<DigestValues> {
+        let sequence_handle = self.sequence_handle()?;
+        let result = self.context.event_sequence_complete(
+            pcr_handle,
+            sequence_handle,
+            MaxBuffer::default(),
+        )?;
+        self.sequence_handle = None;
+        Ok(result)
+    }
+
+    fn sequence_handle(&self) -> Result<ObjectHandle> {
+        self.sequence_handle.ok_or_else(|| {
+            error!("The sequence has already been completed");
+            Error::local_error(WrapperErrorKind::InvalidHandleState)
+        })
+    }
+}
+
+impl Drop for Sequence<'_> {
+    fn drop(&mut self) {
+        if let Some(sequence_handle) = self.sequence_handle.take() {
+            let _ = self.context.flush_context(sequence_handle);
+        }
+    }
+}
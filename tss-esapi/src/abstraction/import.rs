@@ -0,0 +1,72 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module for creating import blobs in software
+//!
+//! This module allows wrapping externally generated keys so that they can be
+//! loaded in the TPM with [Context::import](crate::Context::import) under a
+//! storage parent, without the private key ever being sent in plain text.
+//!
+//! The blob is created in the same way as in the `TPM2_Duplicate` command,
+//! using only an outer wrapper: a random seed is protected with the public key
+//! of the new parent and is used to derive the symmetric key that encrypts the
+//! sensitive area and the HMAC key that protects its integrity.
+//!
+//! The module is only available when the `openssl` feature is enabled.
+use crate::{
+    abstraction::outer_wrapper,
+    structures::{EncryptedSecret, Private, Public, Sensitive},
+    traits::Marshall,
+    Result,
+};
+use std::convert::TryFrom;
+use zeroize::Zeroize;
+
+const DUPLICATE_LABEL: &[u8] = b"DUPLICATE\0";
+
+/// Creates an import blob for an external key
+///
+/// # Arguments
+/// * `parent_public` - The [Public] of the storage key under which the object
+///                     is going to be imported. Only RSA and ECC storage keys
+///                     with an AES CFB symmetric definition are supported.
+/// * `object_public` - The [Public] of the object that is being wrapped.
+/// * `object_sensitive` - The [Sensitive] containing the private part of the
+///                        object that is being wrapped.
+///
+/// # Details
+/// The object must be a duplicable object, i.e. the `fixed_tpm` and `fixed_parent`
+/// attributes must be clear. It must also match the sensitive area, otherwise
+/// the import will be rejected by the TPM.
+///
+/// The returned values are meant to be passed to [Context::import](crate::Context::import)
+/// together with `object_public`, using no encryption key and a
+/// [SymmetricDefinitionObject::Null](crate::structures::SymmetricDefinitionObject::Null) symmetric algorithm.
+///
+/// # Returns
+/// The duplicate blob, as a [Private], and the encrypted seed, as an
+/// [EncryptedSecret].
+///
+/// # Errors
+/// * if the parent is not an RSA or ECC key, or uses an unsupported symmetric
+///   definition, curve or hashing algorithm, an `UnsupportedParam` wrapper error is returned
+/// * if any of the cryptographic operations fail an `InternalError` wrapper error is returned
+pub fn create_import_blob(
+    parent_public: &Public,
+    object_public: &Public,
+    object_sensitive: &Sensitive,
+) -> Result<(Private, EncryptedSecret)> {
+    let name = outer_wrapper::object_name(object_public)?;
+    let mut marshalled_sensitive = object_sensitive.marshall()?;
+    let mut sensitive = outer_wrapper::size_prefixed(&marshalled_sensitive)?;
+    marshalled_sensitive.zeroize();
+
+    let result = outer_wrapper::wrap(parent_public, DUPLICATE_LABEL, &name, &sensitive);
+    sensitive.zeroize();
+    let (duplicate, encrypted_secret) = result?;
+
+    Ok((
+        Private::try_from(duplicate)?,
+        EncryptedSecret::try_from(encrypted_secret)?,
+    ))
+}
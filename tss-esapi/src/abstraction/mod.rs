@@ -2,14 +2,37 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ak;
+pub mod attestation;
+pub mod audit;
 pub mod cipher;
+pub mod credential;
+pub mod dictionary_attack;
 pub mod ek;
+pub mod field_upgrade;
+pub mod handles;
+pub mod hash;
+#[cfg(feature = "openssl")]
+pub mod import;
 pub mod nv;
+#[cfg(feature = "openssl")]
+mod outer_wrapper;
 pub mod pcr;
+pub mod policy;
+pub mod primary;
 pub mod public;
+pub mod session;
+pub mod symmetric;
 pub mod transient;
+pub mod x509;
 
-use crate::{attributes::ObjectAttributesBuilder, structures::PublicBuilder};
+use crate::{
+    attributes::ObjectAttributesBuilder,
+    constants::PropertyTag,
+    structures::{MaxBuffer, PublicBuilder},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
 
 /// KeyCustomizaion allows to adjust how a key is going to be created
 pub trait KeyCustomization {
@@ -60,3 +83,17 @@ impl IntoKeyCustomization for Option<DefaultKey> {
         None
     }
 }
+
+/// Get the maximum size of the input buffers of the TPM.
+fn max_input_buffer_size(context: &mut Context) -> Result<usize> {
+    Ok(context
+        .get_tpm_property(PropertyTag::InputBuffer)?
+        .map(usize::try_from)
+        .transpose()
+        .map_err(|_| {
+            error!("Failed to obtain valid maximum input buffer size");
+            Error::local_error(WrapperErrorKind::InternalError)
+        })?
+        .map(|size| std::cmp::min(size, MaxBuffer::MAX_SIZE))
+        .unwrap_or(MaxBuffer::MAX_SIZE))
+}
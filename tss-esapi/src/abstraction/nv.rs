@@ -1,16 +1,26 @@
 // Copyright 2020 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod bit_field;
+mod counter;
+mod extend_index;
+mod pin;
+
+pub use bit_field::BitField;
+pub use counter::Counter;
+pub use extend_index::ExtendIndex;
+pub use pin::{PinCounterParameters, PinIndex};
 
 use std::{
     convert::{TryFrom, TryInto},
-    io::Read,
+    io::{Read, Write},
 };
 
 use crate::{
     constants::{tss::*, CapabilityType, PropertyTag},
     handles::{AuthHandle, NvIndexHandle, NvIndexTpmHandle, TpmHandle},
-    interface_types::resource_handles::NvAuth,
-    structures::{CapabilityData, MaxNvBuffer, Name, NvPublic},
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::NvAuth},
+    structures::{Auth, CapabilityData, CapabilityItem, Digest, MaxNvBuffer, Name, NvPublic},
+    tss2_esys::TPMI_ALG_HASH,
     Context, Error, Result, WrapperErrorKind,
 };
 
@@ -27,20 +37,146 @@ pub fn read_full(
     .open(context)?;
     let mut result = Vec::with_capacity(rw.size());
 
-    let _ = rw.read_to_end(&mut result).map_err(|e| {
-        // Try to convert the error back into a tss-esapi::Error if it was one originally
-        match e.into_inner() {
-            None => Error::WrapperError(WrapperErrorKind::InvalidParam),
-            Some(e) => match e.downcast::<Error>() {
-                Ok(e) => *e,
-                Err(_) => Error::WrapperError(WrapperErrorKind::InvalidParam),
-            },
-        }
-    })?;
+    let _ = rw.read_to_end(&mut result).map_err(io_error_to_error)?;
 
     Ok(result)
 }
 
+/// Allows writing an NV Index completely, regardless of the max TPM NV buffer size
+///
+/// The data is written starting at offset zero, using as many calls to
+/// [Context::nv_write] as needed.
+///
+/// # Errors
+/// * if the data is larger than the NV Index, a `WrongParamSize` wrapper error is returned
+pub fn write_full(
+    context: &mut Context,
+    auth_handle: NvAuth,
+    nv_index_handle: NvIndexTpmHandle,
+    data: &[u8],
+) -> Result<()> {
+    let mut rw = NvOpenOptions::ExistingIndex {
+        auth_handle,
+        nv_index_handle,
+    }
+    .open(context)?;
+    if data.len() > rw.size() {
+        log::error!(
+            "The data ({} bytes) does not fit in the NV Index ({} bytes)",
+            data.len(),
+            rw.size()
+        );
+        return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+    }
+
+    rw.write_all(data).map_err(io_error_to_error)
+}
+
+/// Tries to convert the error back into a tss-esapi::Error if it was one originally
+fn io_error_to_error(e: std::io::Error) -> Error {
+    match e.into_inner() {
+        None => Error::WrapperError(WrapperErrorKind::InvalidParam),
+        Some(e) => match e.downcast::<Error>() {
+            Ok(e) => *e,
+            Err(_) => Error::WrapperError(WrapperErrorKind::InvalidParam),
+        },
+    }
+}
+
+/// Writes a policy digest to an NV Index, to be used with [Context::policy_authorize_nv]
+///
+/// # Details
+/// [Context::policy_authorize_nv] compares the policy digest of the session with the
+/// `TPMT_HA` held by the NV Index, i.e. the hashing algorithm (as a big-endian 16-bit
+/// value) followed by the policy digest, starting at offset zero. This function writes
+/// `policy_digest` in that layout, so `hashing_algorithm` must be the hashing algorithm
+/// of the policy sessions and the NV Index must be an ordinary index that is at least
+/// two bytes larger than the digest.
+///
+/// # Errors
+/// * if the hashing algorithm is `Null`, an `InvalidParam` wrapper error is returned
+/// * if the data is larger than the NV Index, a `WrongParamSize` wrapper error is returned
+pub fn write_policy_digest(
+    context: &mut Context,
+    auth_handle: NvAuth,
+    nv_index_handle: NvIndexTpmHandle,
+    hashing_algorithm: HashingAlgorithm,
+    policy_digest: &Digest,
+) -> Result<()> {
+    if hashing_algorithm == HashingAlgorithm::Null {
+        log::error!("The hashing algorithm of the policy digest cannot be Null");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    let mut data = TPMI_ALG_HASH::from(hashing_algorithm)
+        .to_be_bytes()
+        .to_vec();
+    data.extend_from_slice(policy_digest.as_bytes());
+    write_full(context, auth_handle, nv_index_handle, &data)
+}
+
+/// Sets bits in a bit field NV Index
+///
+/// The bits that are set in `bits` are set in the NV Index, see
+/// [Context::nv_set_bits] for the details.
+pub fn set_bits(
+    context: &mut Context,
+    auth_handle: NvAuth,
+    nv_index_handle: NvIndexTpmHandle,
+    bits: u64,
+) -> Result<()> {
+    NvOpenOptions::ExistingIndex {
+        auth_handle,
+        nv_index_handle,
+    }
+    .open(context)?
+    .set_bits(bits)
+}
+
+/// Changes the authorization value of an NV Index
+///
+/// The policy session that satisfies the ADMIN role of the index must be
+/// set as the first session of the context, see [Context::nv_change_auth]
+/// for the details.
+pub fn change_auth(
+    context: &mut Context,
+    nv_index_handle: NvIndexTpmHandle,
+    new_auth: Auth,
+) -> Result<()> {
+    with_nv_index_handle(context, nv_index_handle, |ctx, nv_index_handle| {
+        ctx.nv_change_auth(nv_index_handle, new_auth)
+    })
+}
+
+/// Size of the data of the NV index types that hold a 64-bit value.
+const U64_SIZE: usize = 8;
+
+/// Reads the 64-bit value of an NV index of type counter or bits.
+fn read_u64(context: &mut Context, auth_handle: NvAuth, esys_handle: NvIndexHandle) -> Result<u64> {
+    let data = context.nv_read(auth_handle, esys_handle, U64_SIZE as u16, 0)?;
+    // The value is an 8 byte unsigned big-endian value.
+    let bytes: [u8; U64_SIZE] = data.to_vec().try_into().map_err(|_| {
+        log::error!("The NV index has an invalid size ({} bytes)", data.len());
+        Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+    })?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Executes `f` with an ESYS handle for the NV index, which is closed afterwards
+fn with_nv_index_handle<F, T>(
+    context: &mut Context,
+    nv_index_tpm_handle: NvIndexTpmHandle,
+    f: F,
+) -> Result<T>
+where
+    F: FnOnce(&mut Context, NvIndexHandle) -> Result<T>,
+{
+    let mut object_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index_tpm_handle.into()))?;
+    let result = f(context, NvIndexHandle::from(object_handle));
+    context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+    result
+}
+
 /// Returns the NvPublic and Name associated with an NV index TPM handle
 fn get_nv_index_info(
     context: &mut Context,
@@ -83,6 +219,46 @@ pub fn list(context: &mut Context) -> Result<Vec<(NvPublic, Name)>> {
     })
 }
 
+/// Iterates over all the currently defined NV Indexes' handles and public components
+///
+/// # Details
+/// The handles of the NV Indexes are retrieved when this function is called,
+/// following the continuation of the handles capability if the TPM does not
+/// return all of them at once. The public components are read lazily, while
+/// iterating, so an error when reading one of them does not end the iteration.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf};
+/// use tss_esapi::abstraction::nv;
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// for nv_index in nv::list_iter(&mut context).expect("Failed to list the NV Indexes") {
+///     let (nv_index_handle, nv_public) = nv_index.expect("Failed to read the NV Index");
+///     println!("{:?}: {} bytes", nv_index_handle, nv_public.data_size());
+/// }
+/// ```
+pub fn list_iter(
+    context: &mut Context,
+) -> Result<impl Iterator<Item = Result<(NvIndexTpmHandle, NvPublic)>> + '_> {
+    let nv_index_handles = context.execute_without_session(|ctx| {
+        ctx.get_capabilities_iter(CapabilityType::Handles, TPM2_NV_INDEX_FIRST)
+            .map(|item| match item? {
+                CapabilityItem::Handle(tpm_handle) => NvIndexTpmHandle::try_from(tpm_handle),
+                _ => Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+            })
+            .collect::<Result<Vec<NvIndexTpmHandle>>>()
+    })?;
+
+    Ok(nv_index_handles.into_iter().map(move |nv_index_handle| {
+        context
+            .execute_without_session(|ctx| get_nv_index_info(ctx, nv_index_handle))
+            .map(|(nv_public, _)| (nv_index_handle, nv_public))
+    }))
+}
+
 /// Options and flags which can be used to determine how a non-volatile storage index is opened.
 #[non_exhaustive]
 #[derive(Debug, Clone)]
@@ -182,6 +358,14 @@ impl NvReaderWriter<'_> {
     pub fn size(&self) -> usize {
         self.data_size
     }
+
+    /// Sets bits in the non-volatile storage index
+    ///
+    /// The index must be a bit field index.
+    pub fn set_bits(&mut self, bits: u64) -> Result<()> {
+        self.context
+            .nv_set_bits(self.auth_handle, self.nv_idx, bits)
+    }
 }
 
 impl Read for NvReaderWriter<'_> {
@@ -196,7 +380,7 @@ impl Read for NvReaderWriter<'_> {
         let res = self
             .context
             .nv_read(self.auth_handle, self.nv_idx, size, self.offset as u16)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         buf[0..size as usize].copy_from_slice(&res);
         self.offset += size as usize;
 
@@ -204,7 +388,7 @@ impl Read for NvReaderWriter<'_> {
     }
 }
 
-impl std::io::Write for NvReaderWriter<'_> {
+impl Write for NvReaderWriter<'_> {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         if self.data_size < self.offset {
             return Ok(0);
@@ -213,11 +397,10 @@ impl std::io::Write for NvReaderWriter<'_> {
         let desired_size = std::cmp::min(buf.len(), self.data_size - self.offset);
         let size = std::cmp::min(self.buffer_size, desired_size) as u16;
 
-        let data = MaxNvBuffer::from_bytes(&buf[0..size.into()])
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let data = MaxNvBuffer::from_bytes(&buf[0..size.into()]).map_err(std::io::Error::other)?;
         self.context
             .nv_write(self.auth_handle, self.nv_idx, data, self.offset as u16)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            .map_err(std::io::Error::other)?;
         self.offset += size as usize;
 
         Ok(size.into())
@@ -0,0 +1,144 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use super::{read_u64, with_nv_index_handle, U64_SIZE};
+use crate::{
+    attributes::NvIndexAttributesBuilder,
+    constants::NvIndexType,
+    handles::{NvIndexTpmHandle, ObjectHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{NvAuth, Provision},
+    },
+    structures::NvPublicBuilder,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// Bit field stored in an NV index
+///
+/// # Details
+/// The bit field is an NV index of type [NvIndexType::Bits], holding a
+/// 64-bit value in which bits can be set but never cleared, which makes
+/// it suitable for one-way flags such as revocation flags or feature fuses.
+///
+/// A bit field index cannot be read before it has been written for the
+/// first time, so both [BitField::define] and [BitField::open] initialize
+/// it, without setting any bit, if it has never been written.
+#[derive(Debug, Copy, Clone)]
+pub struct BitField {
+    nv_index_handle: NvIndexTpmHandle,
+    auth_handle: NvAuth,
+}
+
+impl BitField {
+    /// Defines a new bit field NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - The hierarchy that defines and authorizes the use of the index.
+    /// * `nv_index_handle` - The handle of the new NV index.
+    /// * `name_hashing_algorithm` - The hashing algorithm used to compute the name of the index.
+    ///
+    /// # Details
+    /// The index can be read and written with the authorization of `auth_handle`.
+    pub fn define(
+        context: &mut Context,
+        auth_handle: Provision,
+        nv_index_handle: NvIndexTpmHandle,
+        name_hashing_algorithm: HashingAlgorithm,
+    ) -> Result<Self> {
+        let attributes_builder =
+            NvIndexAttributesBuilder::new().with_nv_index_type(NvIndexType::Bits);
+        let (attributes_builder, nv_auth) = match auth_handle {
+            Provision::Owner => (
+                attributes_builder
+                    .with_owner_write(true)
+                    .with_owner_read(true),
+                NvAuth::Owner,
+            ),
+            Provision::Platform => (
+                attributes_builder
+                    .with_pp_write(true)
+                    .with_pp_read(true)
+                    .with_platform_create(true),
+                NvAuth::Platform,
+            ),
+        };
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index_handle)
+            .with_index_name_algorithm(name_hashing_algorithm)
+            .with_index_attributes(attributes_builder.build()?)
+            .with_data_area_size(U64_SIZE)
+            .build()?;
+
+        let mut object_handle: ObjectHandle = context
+            .nv_define_space(auth_handle, None, nv_public)?
+            .into();
+        context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+
+        BitField::open(context, nv_auth, nv_index_handle)
+    }
+
+    /// Opens an existing bit field NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The handle of the NV index.
+    ///
+    /// # Errors
+    /// * if the NV index is not a bit field, or if it cannot be read or
+    ///   written with `auth_handle`, an `InvalidParam` wrapper error is returned
+    pub fn open(
+        context: &mut Context,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexTpmHandle,
+    ) -> Result<Self> {
+        with_nv_index_handle(context, nv_index_handle, |ctx, esys_handle| {
+            let (nv_public, _) =
+                ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))?;
+            let attributes = nv_public.attributes();
+            if attributes.index_type()? != NvIndexType::Bits {
+                error!("The NV index is not a bit field");
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+            let (readable, writable) = match auth_handle {
+                NvAuth::Owner => (attributes.owner_read(), attributes.owner_write()),
+                NvAuth::Platform => (attributes.pp_read(), attributes.pp_write()),
+                NvAuth::NvIndex(_) => (attributes.auth_read(), attributes.auth_write()),
+            };
+            if !readable || !writable {
+                error!("The NV index cannot be read and written with the provided authorization");
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+            if !attributes.written() {
+                ctx.nv_set_bits(auth_handle, esys_handle, 0)?;
+            }
+            Ok(BitField {
+                nv_index_handle,
+                auth_handle,
+            })
+        })
+    }
+
+    /// Returns the handle of the NV index
+    pub const fn nv_index_handle(&self) -> NvIndexTpmHandle {
+        self.nv_index_handle
+    }
+
+    /// Sets the bits of `mask` in the bit field
+    ///
+    /// The bits that are clear in `mask` are left unchanged.
+    pub fn set_bits(&self, context: &mut Context, mask: u64) -> Result<()> {
+        with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            ctx.nv_set_bits(self.auth_handle, esys_handle, mask)
+        })
+    }
+
+    /// Reads the bit field
+    pub fn read(&self, context: &mut Context) -> Result<u64> {
+        with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            read_u64(ctx, self.auth_handle, esys_handle)
+        })
+    }
+}
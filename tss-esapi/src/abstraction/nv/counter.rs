@@ -0,0 +1,190 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use super::{read_u64, with_nv_index_handle, U64_SIZE};
+use crate::{
+    attributes::NvIndexAttributesBuilder,
+    constants::NvIndexType,
+    handles::{NvIndexHandle, NvIndexTpmHandle, ObjectHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{NvAuth, Provision},
+    },
+    structures::{Name, NvPublicBuilder},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// Monotonic counter stored in an NV index
+///
+/// # Details
+/// The counter is an NV index of type [NvIndexType::Counter], which can
+/// only be incremented. A counter index cannot be read before it has been
+/// incremented for the first time, and its initial value is not zero but
+/// the highest value that any counter in the TPM has had. Both
+/// [Counter::define] and [Counter::open] take care of that by incrementing
+/// the counter if it has never been incremented.
+///
+/// The [Name] of the index and the last value of the counter are kept, so
+/// that an index that has been undefined and defined again with different
+/// properties, or a counter that has gone backwards, is detected.
+#[derive(Debug, Clone)]
+pub struct Counter {
+    nv_index_handle: NvIndexTpmHandle,
+    auth_handle: NvAuth,
+    name: Name,
+    value: u64,
+}
+
+impl Counter {
+    /// Defines a new counter NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - The hierarchy that defines and authorizes the use of the index.
+    /// * `nv_index_handle` - The handle of the new NV index.
+    /// * `name_hashing_algorithm` - The hashing algorithm used to compute the name of the index.
+    ///
+    /// # Details
+    /// The index can be read and incremented with the authorization of
+    /// `auth_handle`, and it is incremented once so that it can be read.
+    pub fn define(
+        context: &mut Context,
+        auth_handle: Provision,
+        nv_index_handle: NvIndexTpmHandle,
+        name_hashing_algorithm: HashingAlgorithm,
+    ) -> Result<Self> {
+        let attributes_builder =
+            NvIndexAttributesBuilder::new().with_nv_index_type(NvIndexType::Counter);
+        let (attributes_builder, nv_auth) = match auth_handle {
+            Provision::Owner => (
+                attributes_builder
+                    .with_owner_write(true)
+                    .with_owner_read(true),
+                NvAuth::Owner,
+            ),
+            Provision::Platform => (
+                attributes_builder
+                    .with_pp_write(true)
+                    .with_pp_read(true)
+                    .with_platform_create(true),
+                NvAuth::Platform,
+            ),
+        };
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index_handle)
+            .with_index_name_algorithm(name_hashing_algorithm)
+            .with_index_attributes(attributes_builder.build()?)
+            .with_data_area_size(U64_SIZE)
+            .build()?;
+
+        let mut object_handle: ObjectHandle = context
+            .nv_define_space(auth_handle, None, nv_public)?
+            .into();
+        context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+
+        Counter::open(context, nv_auth, nv_index_handle)
+    }
+
+    /// Opens an existing counter NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The handle of the NV index.
+    ///
+    /// # Details
+    /// If the counter has never been incremented, it is incremented once so
+    /// that it can be read.
+    ///
+    /// # Errors
+    /// * if the NV index is not a counter, an `InvalidParam` wrapper error is returned
+    pub fn open(
+        context: &mut Context,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexTpmHandle,
+    ) -> Result<Self> {
+        with_nv_index_handle(context, nv_index_handle, |ctx, esys_handle| {
+            let (nv_public, _) =
+                ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))?;
+            if nv_public.attributes().index_type()? != NvIndexType::Counter {
+                error!("The NV index is not a counter");
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+            if !nv_public.attributes().written() {
+                ctx.nv_increment(auth_handle, esys_handle)?;
+            }
+            // The name changes when the index is written for the first time.
+            let (_, name) = ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))?;
+            let value = read_u64(ctx, auth_handle, esys_handle)?;
+            Ok(Counter {
+                nv_index_handle,
+                auth_handle,
+                name,
+                value,
+            })
+        })
+    }
+
+    /// Returns the handle of the NV index
+    pub const fn nv_index_handle(&self) -> NvIndexTpmHandle {
+        self.nv_index_handle
+    }
+
+    /// Returns the last value of the counter that has been read
+    pub const fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Increments the counter
+    ///
+    /// # Returns
+    /// The new value of the counter.
+    ///
+    /// # Errors
+    /// * if the NV index has been recreated, or if the counter did not
+    ///   increase, an `InconsistentParams` wrapper error is returned
+    pub fn increment(&mut self, context: &mut Context) -> Result<u64> {
+        let value = with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            self.check_name(ctx, esys_handle)?;
+            ctx.nv_increment(self.auth_handle, esys_handle)?;
+            read_u64(ctx, self.auth_handle, esys_handle)
+        })?;
+        if value <= self.value {
+            error!("The counter did not increase ({} <= {})", value, self.value);
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        self.value = value;
+        Ok(value)
+    }
+
+    /// Reads the counter
+    ///
+    /// # Returns
+    /// The current value of the counter.
+    ///
+    /// # Errors
+    /// * if the NV index has been recreated, or if the counter went
+    ///   backwards, an `InconsistentParams` wrapper error is returned
+    pub fn read(&mut self, context: &mut Context) -> Result<u64> {
+        let value = with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            self.check_name(ctx, esys_handle)?;
+            read_u64(ctx, self.auth_handle, esys_handle)
+        })?;
+        if value < self.value {
+            error!("The counter went backwards ({} < {})", value, self.value);
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        self.value = value;
+        Ok(value)
+    }
+
+    /// Checks that the NV index is the one that was opened.
+    fn check_name(&self, context: &mut Context, esys_handle: NvIndexHandle) -> Result<()> {
+        let (_, name) = context.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))?;
+        if name != self.name {
+            error!("The name of the NV index has changed, the counter has been recreated");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        Ok(())
+    }
+}
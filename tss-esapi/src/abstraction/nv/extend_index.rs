@@ -0,0 +1,225 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use super::with_nv_index_handle;
+#[cfg(feature = "openssl")]
+use crate::abstraction::outer_wrapper::message_digest;
+use crate::{
+    attributes::NvIndexAttributesBuilder,
+    constants::NvIndexType,
+    handles::{NvIndexTpmHandle, ObjectHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{Hierarchy, NvAuth, Provision},
+    },
+    structures::{Digest, MaxBuffer, MaxNvBuffer, NvPublicBuilder},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::{TryFrom, TryInto};
+
+/// Extend NV index, usable as an append-only log
+///
+/// # Details
+/// The extend index is an NV index of type [NvIndexType::Extend], whose
+/// value is a digest computed with the name algorithm of the index. The
+/// value starts as a digest of zeros and is only modified by extending it
+/// with events, in the same way as a PCR: the new value is the digest of
+/// the old value concatenated with the event.
+///
+/// The expected value of the index for a list of events can be recomputed
+/// in order to verify the events against the value of the index.
+#[derive(Debug, Copy, Clone)]
+pub struct ExtendIndex {
+    nv_index_handle: NvIndexTpmHandle,
+    auth_handle: NvAuth,
+    hashing_algorithm: HashingAlgorithm,
+    digest_size: usize,
+}
+
+impl ExtendIndex {
+    /// Defines a new extend NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - The hierarchy that defines and authorizes the use of the index.
+    /// * `nv_index_handle` - The handle of the new NV index.
+    /// * `hashing_algorithm` - The hashing algorithm used to extend the index,
+    ///                         which is the name algorithm of the index.
+    pub fn define(
+        context: &mut Context,
+        auth_handle: Provision,
+        nv_index_handle: NvIndexTpmHandle,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Result<Self> {
+        let (attributes_builder, nv_auth) = match auth_handle {
+            Provision::Owner => (
+                NvIndexAttributesBuilder::new()
+                    .with_owner_write(true)
+                    .with_owner_read(true),
+                NvAuth::Owner,
+            ),
+            Provision::Platform => (
+                NvIndexAttributesBuilder::new()
+                    .with_pp_write(true)
+                    .with_pp_read(true)
+                    .with_platform_create(true),
+                NvAuth::Platform,
+            ),
+        };
+        let nv_public = NvPublicBuilder::new_extend_index()
+            .with_nv_index(nv_index_handle)
+            .with_index_name_algorithm(hashing_algorithm)
+            .with_index_attributes(attributes_builder.build()?)
+            .build()?;
+        let digest_size = nv_public.data_size();
+
+        let mut object_handle: ObjectHandle = context
+            .nv_define_space(auth_handle, None, nv_public)?
+            .into();
+        context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+
+        Ok(ExtendIndex {
+            nv_index_handle,
+            auth_handle: nv_auth,
+            hashing_algorithm,
+            digest_size,
+        })
+    }
+
+    /// Opens an existing extend NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The handle of the NV index.
+    ///
+    /// # Errors
+    /// * if the NV index is not an extend index, an `InvalidParam` wrapper error is returned
+    pub fn open(
+        context: &mut Context,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexTpmHandle,
+    ) -> Result<Self> {
+        let (nv_public, _) = with_nv_index_handle(context, nv_index_handle, |ctx, esys_handle| {
+            ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))
+        })?;
+        if nv_public.attributes().index_type()? != NvIndexType::Extend {
+            error!("The NV index is not an extend index");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(ExtendIndex {
+            nv_index_handle,
+            auth_handle,
+            hashing_algorithm: nv_public.name_algorithm(),
+            digest_size: nv_public.data_size(),
+        })
+    }
+
+    /// Returns the handle of the NV index
+    pub const fn nv_index_handle(&self) -> NvIndexTpmHandle {
+        self.nv_index_handle
+    }
+
+    /// Returns the hashing algorithm used to extend the index
+    pub const fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.hashing_algorithm
+    }
+
+    /// Extends the index with an event
+    ///
+    /// # Details
+    /// The event is hashed by the TPM together with the current value of
+    /// the index. Events larger than [MaxNvBuffer::MAX_SIZE] can be
+    /// hashed first, and their digest used as the event instead.
+    pub fn extend(&self, context: &mut Context, event: &[u8]) -> Result<()> {
+        let data = MaxNvBuffer::from_bytes(event)?;
+        with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            ctx.nv_extend(self.auth_handle, esys_handle, data)
+        })
+    }
+
+    /// Reads the current value of the index
+    ///
+    /// # Details
+    /// An index that has never been extended cannot be read from the TPM,
+    /// so its initial value, a digest of zeros, is returned instead.
+    pub fn read(&self, context: &mut Context) -> Result<Digest> {
+        let size = self.digest_size.try_into().map_err(|_| {
+            error!("Invalid size of the extend index ({})", self.digest_size);
+            Error::local_error(WrapperErrorKind::InternalError)
+        })?;
+        with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            let (nv_public, _) =
+                ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))?;
+            if !nv_public.attributes().written() {
+                return Digest::try_from(vec![0u8; self.digest_size]);
+            }
+            let data = ctx.nv_read(self.auth_handle, esys_handle, size, 0)?;
+            Digest::from_bytes(data.as_bytes())
+        })
+    }
+
+    /// Computes the expected value of the index, in software, after extending it with `events`
+    ///
+    /// # Details
+    /// The events are expected to be the ones used to extend the index since
+    /// it was defined, in the same order.
+    ///
+    /// The function is only available when the `openssl` feature is enabled.
+    #[cfg(feature = "openssl")]
+    pub fn expected_value<'a, I>(&self, events: I) -> Result<Digest>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let message_digest = message_digest(self.hashing_algorithm)?;
+        let mut value = vec![0u8; self.digest_size];
+        for event in events {
+            let mut hasher = openssl::hash::Hasher::new(message_digest).map_err(|e| {
+                error!("Failed to create hasher: {}", e);
+                Error::local_error(WrapperErrorKind::InternalError)
+            })?;
+            hasher
+                .update(&value)
+                .and_then(|_| hasher.update(event))
+                .map_err(|e| {
+                    error!("Failed to hash data: {}", e);
+                    Error::local_error(WrapperErrorKind::InternalError)
+                })?;
+            value = hasher
+                .finish()
+                .map_err(|e| {
+                    error!("Failed to finish hash: {}", e);
+                    Error::local_error(WrapperErrorKind::InternalError)
+                })?
+                .to_vec();
+        }
+        Digest::try_from(value)
+    }
+
+    /// Computes the expected value of the index, with the TPM, after extending it with `events`
+    ///
+    /// # Details
+    /// The events are expected to be the ones used to extend the index since
+    /// it was defined, in the same order.
+    ///
+    /// Each event, together with the digest, must fit in a [MaxBuffer].
+    pub fn expected_value_with_tpm<'a, I>(&self, context: &mut Context, events: I) -> Result<Digest>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut value = Digest::try_from(vec![0u8; self.digest_size])?;
+        for event in events {
+            let mut data = value.as_bytes().to_vec();
+            data.extend_from_slice(event);
+            let (digest, _) = context.execute_without_session(|ctx| {
+                ctx.hash(
+                    MaxBuffer::try_from(data)?,
+                    self.hashing_algorithm,
+                    Hierarchy::Null,
+                )
+            })?;
+            value = digest;
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,291 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use super::{with_nv_index_handle, U64_SIZE};
+use crate::{
+    attributes::NvIndexAttributesBuilder,
+    constants::{NvIndexType, TpmFormatOneError, TpmFormatZeroError},
+    error::{TpmFormatZeroResponseCode, TpmResponseCode},
+    handles::{NvIndexTpmHandle, ObjectHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{NvAuth, Provision},
+    },
+    structures::{Auth, MaxNvBuffer, NvPublicBuilder},
+    tss2_esys::TPMS_NV_PIN_COUNTER_PARAMETERS,
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::convert::{TryFrom, TryInto};
+
+/// The data of a PIN pass or PIN fail NV index
+///
+/// # Details
+/// This corresponds to the TPMS_NV_PIN_COUNTER_PARAMETERS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PinCounterParameters {
+    pin_count: u32,
+    pin_limit: u32,
+}
+
+impl PinCounterParameters {
+    /// Creates new PIN counter parameters
+    pub const fn new(pin_count: u32, pin_limit: u32) -> Self {
+        PinCounterParameters {
+            pin_count,
+            pin_limit,
+        }
+    }
+
+    /// Returns the number of times the PIN has been used (PIN pass) or
+    /// the number of failed authorizations (PIN fail).
+    pub const fn pin_count(&self) -> u32 {
+        self.pin_count
+    }
+
+    /// Returns the number of uses or failures after which the PIN
+    /// cannot be used anymore.
+    pub const fn pin_limit(&self) -> u32 {
+        self.pin_limit
+    }
+
+    /// Returns the marshalled parameters, as stored in the NV index.
+    pub fn to_bytes(&self) -> [u8; U64_SIZE] {
+        let mut bytes = [0u8; U64_SIZE];
+        bytes[..4].copy_from_slice(&self.pin_count.to_be_bytes());
+        bytes[4..].copy_from_slice(&self.pin_limit.to_be_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[u8]> for PinCounterParameters {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != U64_SIZE {
+            error!(
+                "Invalid size of the PIN counter parameters ({} bytes)",
+                bytes.len()
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+        Ok(PinCounterParameters {
+            pin_count: u32::from_be_bytes(bytes[..4].try_into().unwrap()),
+            pin_limit: u32::from_be_bytes(bytes[4..].try_into().unwrap()),
+        })
+    }
+}
+
+impl From<PinCounterParameters> for TPMS_NV_PIN_COUNTER_PARAMETERS {
+    fn from(pin_counter_parameters: PinCounterParameters) -> Self {
+        TPMS_NV_PIN_COUNTER_PARAMETERS {
+            pinCount: pin_counter_parameters.pin_count,
+            pinLimit: pin_counter_parameters.pin_limit,
+        }
+    }
+}
+
+impl From<TPMS_NV_PIN_COUNTER_PARAMETERS> for PinCounterParameters {
+    fn from(tpms_nv_pin_counter_parameters: TPMS_NV_PIN_COUNTER_PARAMETERS) -> Self {
+        PinCounterParameters {
+            pin_count: tpms_nv_pin_counter_parameters.pinCount,
+            pin_limit: tpms_nv_pin_counter_parameters.pinLimit,
+        }
+    }
+}
+
+/// PIN pass or PIN fail NV index
+///
+/// # Details
+/// The authorization value of the index is a PIN, whose use is limited by
+/// the TPM using the [PinCounterParameters] stored in the index:
+/// * for an index of type [NvIndexType::PinPass], the count is incremented
+///   on each successful use of the PIN, so the PIN can be used `pin_limit` times.
+/// * for an index of type [NvIndexType::PinFail], the count is incremented
+///   on each failed use of the PIN, so the PIN cannot be used anymore
+///   after `pin_limit` failures.
+///
+/// The parameters can be read and written with the authorization of
+/// the hierarchy used to open the index, e.g. to reset the count.
+#[derive(Debug, Copy, Clone)]
+pub struct PinIndex {
+    nv_index_handle: NvIndexTpmHandle,
+    auth_handle: NvAuth,
+    index_type: NvIndexType,
+}
+
+impl PinIndex {
+    /// Defines a new PIN pass or PIN fail NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - The hierarchy that defines the index and manages its parameters.
+    /// * `nv_index_handle` - The handle of the new NV index.
+    /// * `index_type` - Either [NvIndexType::PinPass] or [NvIndexType::PinFail].
+    /// * `name_hashing_algorithm` - The hashing algorithm used to compute the name of the index.
+    /// * `pin` - The PIN.
+    /// * `pin_limit` - The limit of uses (PIN pass) or failures (PIN fail) of the PIN.
+    ///
+    /// # Details
+    /// The index is not subject to the dictionary attack protection of the
+    /// TPM, as the uses of the PIN are limited by the index itself.
+    ///
+    /// # Errors
+    /// * if the index type is not a PIN type, an `InvalidParam` wrapper error is returned
+    pub fn define(
+        context: &mut Context,
+        auth_handle: Provision,
+        nv_index_handle: NvIndexTpmHandle,
+        index_type: NvIndexType,
+        name_hashing_algorithm: HashingAlgorithm,
+        pin: Auth,
+        pin_limit: u32,
+    ) -> Result<Self> {
+        if index_type != NvIndexType::PinPass && index_type != NvIndexType::PinFail {
+            error!("The NV index type {:?} is not a PIN type", index_type);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let attributes_builder = NvIndexAttributesBuilder::new()
+            .with_nv_index_type(index_type)
+            .with_auth_read(true)
+            .with_no_da(true);
+        let (attributes_builder, nv_auth) = match auth_handle {
+            Provision::Owner => (
+                attributes_builder
+                    .with_owner_write(true)
+                    .with_owner_read(true),
+                NvAuth::Owner,
+            ),
+            Provision::Platform => (
+                attributes_builder
+                    .with_pp_write(true)
+                    .with_pp_read(true)
+                    .with_platform_create(true),
+                NvAuth::Platform,
+            ),
+        };
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index_handle)
+            .with_index_name_algorithm(name_hashing_algorithm)
+            .with_index_attributes(attributes_builder.build()?)
+            .with_data_area_size(U64_SIZE)
+            .build()?;
+
+        let mut object_handle: ObjectHandle = context
+            .nv_define_space(auth_handle, Some(pin), nv_public)?
+            .into();
+        context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+
+        let pin_index = PinIndex {
+            nv_index_handle,
+            auth_handle: nv_auth,
+            index_type,
+        };
+        pin_index.set_parameters(context, PinCounterParameters::new(0, pin_limit))?;
+        Ok(pin_index)
+    }
+
+    /// Opens an existing PIN pass or PIN fail NV index
+    ///
+    /// # Arguments
+    /// * `context` - The [Context] used to communicate with the TPM.
+    /// * `auth_handle` - Handle indicating the source of authorization value
+    ///                   for managing the parameters of the index.
+    /// * `nv_index_handle` - The handle of the NV index.
+    ///
+    /// # Errors
+    /// * if the NV index is not a PIN index, or if the PIN cannot be used to
+    ///   authorize reading the index, an `InvalidParam` wrapper error is returned
+    pub fn open(
+        context: &mut Context,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexTpmHandle,
+    ) -> Result<Self> {
+        let (nv_public, _) = with_nv_index_handle(context, nv_index_handle, |ctx, esys_handle| {
+            ctx.execute_without_session(|ctx| ctx.nv_read_public(esys_handle))
+        })?;
+        let attributes = nv_public.attributes();
+        let index_type = attributes.index_type()?;
+        if index_type != NvIndexType::PinPass && index_type != NvIndexType::PinFail {
+            error!("The NV index is not a PIN index");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        if !attributes.auth_read() {
+            error!("The PIN of the NV index cannot be used to read it");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(PinIndex {
+            nv_index_handle,
+            auth_handle,
+            index_type,
+        })
+    }
+
+    /// Returns the handle of the NV index
+    pub const fn nv_index_handle(&self) -> NvIndexTpmHandle {
+        self.nv_index_handle
+    }
+
+    /// Returns the type of the NV index
+    pub const fn index_type(&self) -> NvIndexType {
+        self.index_type
+    }
+
+    /// Reads the parameters of the index
+    pub fn parameters(&self, context: &mut Context) -> Result<PinCounterParameters> {
+        let data = with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            ctx.nv_read(self.auth_handle, esys_handle, U64_SIZE as u16, 0)
+        })?;
+        PinCounterParameters::try_from(data.as_slice())
+    }
+
+    /// Writes the parameters of the index
+    ///
+    /// # Details
+    /// This can be used to reset the count or to change the limit.
+    pub fn set_parameters(
+        &self,
+        context: &mut Context,
+        parameters: PinCounterParameters,
+    ) -> Result<()> {
+        let data = MaxNvBuffer::try_from(parameters.to_bytes().to_vec())?;
+        with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            ctx.nv_write(self.auth_handle, esys_handle, data, 0)
+        })
+    }
+
+    /// Tries to use the PIN
+    ///
+    /// # Details
+    /// The PIN is used to authorize reading the index, which updates the
+    /// count of the index as described in [PinIndex].
+    ///
+    /// # Returns
+    /// Whether the PIN has been accepted by the TPM. A PIN is not accepted
+    /// if it is wrong or if the limit of the index has been reached.
+    pub fn try_use_pin(&self, context: &mut Context, pin: Auth) -> Result<bool> {
+        let result = with_nv_index_handle(context, self.nv_index_handle, |ctx, esys_handle| {
+            ctx.tr_set_auth(esys_handle.into(), pin)?;
+            ctx.nv_read(
+                NvAuth::NvIndex(esys_handle),
+                esys_handle,
+                U64_SIZE as u16,
+                0,
+            )
+        });
+        match result {
+            Ok(_) => Ok(true),
+            Err(Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatOne(response_code))))
+                if response_code.error_number() == TpmFormatOneError::AuthFail
+                    || response_code.error_number() == TpmFormatOneError::BadAuth =>
+            {
+                Ok(false)
+            }
+            Err(Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatZero(
+                TpmFormatZeroResponseCode::Error(response_code),
+            )))) if response_code.error_number() == TpmFormatZeroError::NvAuthorization => {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Software implementation of the outer wrapper used by the TPM
+//!
+//! The outer wrapper protects data sent to a TPM under one of its storage
+//! keys, as done by `TPM2_Duplicate` and `TPM2_MakeCredential`. A random seed
+//! is protected with the public key of the storage key and is used to derive
+//! the symmetric key that encrypts the data and the HMAC key that protects
+//! its integrity, binding it to the name of an object.
+use crate::{
+    interface_types::{
+        algorithm::{HashingAlgorithm, SymmetricMode},
+        ecc::EccCurve,
+        key_bits::AesKeyBits,
+    },
+    structures::{EccParameter, EccPoint, Public, SymmetricDefinitionObject},
+    traits::Marshall,
+    tss2_esys::TPMI_ALG_HASH,
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    derive::Deriver,
+    ec::{EcGroup, EcKey},
+    encrypt::Encrypter,
+    error::ErrorStack,
+    hash::{hash, Hasher, MessageDigest},
+    nid::Nid,
+    pkey::PKey,
+    rand::rand_bytes,
+    rsa::{Padding, Rsa},
+    sign::Signer,
+    symm::{encrypt, Cipher},
+};
+use std::convert::{TryFrom, TryInto};
+use zeroize::Zeroize;
+
+const STORAGE_LABEL: &[u8] = b"STORAGE\0";
+const INTEGRITY_LABEL: &[u8] = b"INTEGRITY\0";
+
+/// Protects the data with an outer wrapper for the given storage key
+///
+/// # Arguments
+/// * `protector_public` - The [Public] of the storage key that is going to
+///                        unwrap the data.
+/// * `label` - The label used when protecting the seed, including the
+///             terminating null byte.
+/// * `name` - The name of the object to which the data is bound.
+/// * `data` - The data that is to be protected, already size prefixed.
+///
+/// # Returns
+/// The HMAC prefixed encrypted data and the protected seed.
+pub(super) fn wrap(
+    protector_public: &Public,
+    label: &[u8],
+    name: &[u8],
+    data: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let name_alg = protector_public.name_hashing_algorithm();
+    let (mut seed, encrypted_seed) = create_seed(protector_public, label)?;
+
+    let (cipher, key_bits) = symmetric_cipher(protector_symmetric(protector_public)?)?;
+    let mut sym_key = kdf_a(name_alg, &seed, STORAGE_LABEL, name, &[], key_bits)?;
+    let encrypted_data =
+        encrypt(cipher, &sym_key, Some(&[0u8; 16]), data).map_err(openssl_error)?;
+    sym_key.zeroize();
+
+    let hmac_key_bits = digest_size(name_alg)? * 8;
+    let mut hmac_key = kdf_a(name_alg, &seed, INTEGRITY_LABEL, &[], &[], hmac_key_bits)?;
+    seed.zeroize();
+    let pkey = PKey::hmac(&hmac_key).map_err(openssl_error)?;
+    hmac_key.zeroize();
+    let mut signer = Signer::new(message_digest(name_alg)?, &pkey).map_err(openssl_error)?;
+    signer.update(&encrypted_data).map_err(openssl_error)?;
+    signer.update(name).map_err(openssl_error)?;
+    let outer_hmac = signer.sign_to_vec().map_err(openssl_error)?;
+
+    let mut wrapped = size_prefixed(&outer_hmac)?;
+    wrapped.extend_from_slice(&encrypted_data);
+    Ok((wrapped, encrypted_seed))
+}
+
+/// Computes the name of an object from its public area.
+pub(super) fn object_name(public: &Public) -> Result<Vec<u8>> {
+    let name_alg = public.name_hashing_algorithm();
+    let digest = hash(message_digest(name_alg)?, &public.marshall()?).map_err(openssl_error)?;
+    let mut name = TPMI_ALG_HASH::from(name_alg).to_be_bytes().to_vec();
+    name.extend_from_slice(&digest);
+    Ok(name)
+}
+
+/// Creates the seed and protects it with the public key of the storage key.
+fn create_seed(protector_public: &Public, label: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    let name_alg = protector_public.name_hashing_algorithm();
+    let seed_size = digest_size(name_alg)?;
+    match protector_public {
+        Public::Rsa {
+            parameters, unique, ..
+        } => {
+            let exponent = match parameters.exponent().value() {
+                0 => 65537,
+                value => value,
+            };
+            let rsa = Rsa::from_public_components(
+                BigNum::from_slice(unique.as_bytes()).map_err(openssl_error)?,
+                BigNum::from_u32(exponent).map_err(openssl_error)?,
+            )
+            .map_err(openssl_error)?;
+            let pkey = PKey::from_rsa(rsa).map_err(openssl_error)?;
+
+            let mut seed = vec![0u8; seed_size];
+            rand_bytes(&mut seed).map_err(openssl_error)?;
+
+            let md = message_digest(name_alg)?;
+            let mut encrypter = Encrypter::new(&pkey).map_err(openssl_error)?;
+            encrypter
+                .set_rsa_padding(Padding::PKCS1_OAEP)
+                .map_err(openssl_error)?;
+            encrypter.set_rsa_oaep_md(md).map_err(openssl_error)?;
+            encrypter.set_rsa_mgf1_md(md).map_err(openssl_error)?;
+            encrypter.set_rsa_oaep_label(label).map_err(openssl_error)?;
+            let mut encrypted_seed =
+                vec![0u8; encrypter.encrypt_len(&seed).map_err(openssl_error)?];
+            let encrypted_len = encrypter
+                .encrypt(&seed, &mut encrypted_seed)
+                .map_err(openssl_error)?;
+            encrypted_seed.truncate(encrypted_len);
+            Ok((seed, encrypted_seed))
+        }
+        Public::Ecc {
+            parameters, unique, ..
+        } => {
+            let group = EcGroup::from_curve_name(curve_nid(parameters.ecc_curve())?)
+                .map_err(openssl_error)?;
+            let protector_x = BigNum::from_slice(unique.x().as_bytes()).map_err(openssl_error)?;
+            let protector_y = BigNum::from_slice(unique.y().as_bytes()).map_err(openssl_error)?;
+            let protector_key =
+                EcKey::from_public_key_affine_coordinates(&group, &protector_x, &protector_y)
+                    .map_err(openssl_error)?;
+            let ephemeral_key = EcKey::generate(&group).map_err(openssl_error)?;
+
+            let ephemeral_pkey = PKey::from_ec_key(ephemeral_key.clone()).map_err(openssl_error)?;
+            let protector_pkey = PKey::from_ec_key(protector_key).map_err(openssl_error)?;
+            let mut deriver = Deriver::new(&ephemeral_pkey).map_err(openssl_error)?;
+            deriver.set_peer(&protector_pkey).map_err(openssl_error)?;
+            let mut z = deriver.derive_to_vec().map_err(openssl_error)?;
+
+            // The shared secret is the x coordinate of the shared point, so its
+            // size is the size of the coordinates on the curve.
+            let coordinate_size = z.len();
+            let mut ephemeral_x = BigNum::new().map_err(openssl_error)?;
+            let mut ephemeral_y = BigNum::new().map_err(openssl_error)?;
+            let mut bn_ctx = BigNumContext::new().map_err(openssl_error)?;
+            ephemeral_key
+                .public_key()
+                .affine_coordinates_gfp(&group, &mut ephemeral_x, &mut ephemeral_y, &mut bn_ctx)
+                .map_err(openssl_error)?;
+            let ephemeral_point = EccPoint::new(
+                EccParameter::try_from(padded(&ephemeral_x, coordinate_size)?)?,
+                EccParameter::try_from(padded(&ephemeral_y, coordinate_size)?)?,
+            );
+
+            let seed = kdf_e(
+                name_alg,
+                &z,
+                label,
+                ephemeral_point.x().as_bytes(),
+                unique.x().as_bytes(),
+                seed_size * 8,
+            )?;
+            z.zeroize();
+
+            let mut encrypted_seed = size_prefixed(ephemeral_point.x().as_bytes())?;
+            encrypted_seed.extend_from_slice(&size_prefixed(ephemeral_point.y().as_bytes())?);
+            Ok((seed, encrypted_seed))
+        }
+        _ => {
+            error!("Only RSA and ECC storage keys are supported");
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+/// The key derivation function KDFa as defined in the TPM specification
+/// (Part 1, section 11.4.10.2).
+fn kdf_a(
+    hashing_algorithm: HashingAlgorithm,
+    key: &[u8],
+    label: &[u8],
+    context_u: &[u8],
+    context_v: &[u8],
+    bits: usize,
+) -> Result<Vec<u8>> {
+    let pkey = PKey::hmac(key).map_err(openssl_error)?;
+    let md = message_digest(hashing_algorithm)?;
+    let bits_bytes = u32::try_from(bits)
+        .map_err(|e| {
+            error!("Failed to convert number of bits to u32: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?
+        .to_be_bytes();
+    derive_key(bits, |counter| {
+        let mut signer = Signer::new(md, &pkey)?;
+        signer.update(&counter.to_be_bytes())?;
+        signer.update(label)?;
+        signer.update(context_u)?;
+        signer.update(context_v)?;
+        signer.update(&bits_bytes)?;
+        signer.sign_to_vec()
+    })
+}
+
+/// The key derivation function KDFe as defined in the TPM specification
+/// (Part 1, section 11.4.10.3).
+fn kdf_e(
+    hashing_algorithm: HashingAlgorithm,
+    z: &[u8],
+    label: &[u8],
+    party_u_info: &[u8],
+    party_v_info: &[u8],
+    bits: usize,
+) -> Result<Vec<u8>> {
+    let md = message_digest(hashing_algorithm)?;
+    derive_key(bits, |counter| {
+        let mut data = counter.to_be_bytes().to_vec();
+        data.extend_from_slice(z);
+        data.extend_from_slice(label);
+        data.extend_from_slice(party_u_info);
+        data.extend_from_slice(party_v_info);
+        let digest = hash(md, &data).map(|digest| digest.to_vec());
+        data.zeroize();
+        digest
+    })
+}
+
+/// Runs the counter based derivation until enough bits have been produced.
+///
+/// Only multiples of 8 bits are supported, which covers all the keys derived here.
+fn derive_key<F>(bits: usize, mut round: F) -> Result<Vec<u8>>
+where
+    F: FnMut(u32) -> std::result::Result<Vec<u8>, ErrorStack>,
+{
+    let size = bits / 8;
+    let mut key = Vec::with_capacity(size);
+    let mut counter: u32 = 0;
+    while key.len() < size {
+        counter += 1;
+        key.extend_from_slice(&round(counter).map_err(openssl_error)?);
+    }
+    key.truncate(size);
+    Ok(key)
+}
+
+/// Returns the symmetric definition of a storage key.
+fn protector_symmetric(protector_public: &Public) -> Result<SymmetricDefinitionObject> {
+    match protector_public {
+        Public::Rsa { parameters, .. } => Ok(parameters.symmetric_definition_object()),
+        Public::Ecc { parameters, .. } => Ok(parameters.symmetric_definition_object()),
+        _ => {
+            error!("Only RSA and ECC storage keys are supported");
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+fn symmetric_cipher(symmetric: SymmetricDefinitionObject) -> Result<(Cipher, usize)> {
+    match symmetric {
+        SymmetricDefinitionObject::Aes {
+            key_bits,
+            mode: SymmetricMode::Cfb,
+        } => Ok(match key_bits {
+            AesKeyBits::Aes128 => (Cipher::aes_128_cfb128(), 128),
+            AesKeyBits::Aes192 => (Cipher::aes_192_cfb128(), 192),
+            AesKeyBits::Aes256 => (Cipher::aes_256_cfb128(), 256),
+        }),
+        _ => {
+            error!("Only AES in CFB mode is supported as symmetric algorithm of the storage key");
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+pub(super) fn message_digest(hashing_algorithm: HashingAlgorithm) -> Result<MessageDigest> {
+    match hashing_algorithm {
+        HashingAlgorithm::Sha1 => Ok(MessageDigest::sha1()),
+        HashingAlgorithm::Sha256 => Ok(MessageDigest::sha256()),
+        HashingAlgorithm::Sha384 => Ok(MessageDigest::sha384()),
+        HashingAlgorithm::Sha512 => Ok(MessageDigest::sha512()),
+        HashingAlgorithm::Sha3_256 => Ok(MessageDigest::sha3_256()),
+        HashingAlgorithm::Sha3_384 => Ok(MessageDigest::sha3_384()),
+        HashingAlgorithm::Sha3_512 => Ok(MessageDigest::sha3_512()),
+        _ => {
+            error!("Unsupported hashing algorithm: {:?}", hashing_algorithm);
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+/// Hashes the concatenation of `chunks` with `hashing_algorithm`.
+pub(super) fn hash_chunks(
+    hashing_algorithm: HashingAlgorithm,
+    chunks: &[&[u8]],
+) -> Result<Vec<u8>> {
+    let mut hasher = Hasher::new(message_digest(hashing_algorithm)?).map_err(|e| {
+        error!("Failed to create hasher: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    for chunk in chunks {
+        hasher.update(chunk).map_err(|e| {
+            error!("Failed to hash data: {}", e);
+            Error::local_error(WrapperErrorKind::InternalError)
+        })?;
+    }
+    hasher.finish().map(|digest| digest.to_vec()).map_err(|e| {
+        error!("Failed to finish hash: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })
+}
+
+fn digest_size(hashing_algorithm: HashingAlgorithm) -> Result<usize> {
+    message_digest(hashing_algorithm).map(|md| md.size())
+}
+
+fn curve_nid(ecc_curve: EccCurve) -> Result<Nid> {
+    match ecc_curve {
+        EccCurve::NistP192 => Ok(Nid::X9_62_PRIME192V1),
+        EccCurve::NistP224 => Ok(Nid::SECP224R1),
+        EccCurve::NistP256 => Ok(Nid::X9_62_PRIME256V1),
+        EccCurve::NistP384 => Ok(Nid::SECP384R1),
+        EccCurve::NistP521 => Ok(Nid::SECP521R1),
+        _ => {
+            error!("Unsupported curve: {:?}", ecc_curve);
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+/// Prefixes the data with its size, as done for the TPM2B types.
+pub(super) fn size_prefixed(data: &[u8]) -> Result<Vec<u8>> {
+    let size: u16 = data.len().try_into().map_err(|e| {
+        error!("Failed to convert size of buffer to u16: {}", e);
+        Error::local_error(WrapperErrorKind::WrongParamSize)
+    })?;
+    let mut buffer = size.to_be_bytes().to_vec();
+    buffer.extend_from_slice(data);
+    Ok(buffer)
+}
+
+fn padded(value: &BigNum, size: usize) -> Result<Vec<u8>> {
+    let size = i32::try_from(size).map_err(|e| {
+        error!("Failed to convert coordinate size to i32: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    value.to_vec_padded(size).map_err(openssl_error)
+}
+
+fn openssl_error(e: ErrorStack) -> Error {
+    error!("OpenSSL operation failed: {}", e);
+    Error::local_error(WrapperErrorKind::InternalError)
+}
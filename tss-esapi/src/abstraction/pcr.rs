@@ -1,12 +1,29 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 mod bank;
+mod bank_allocation;
 mod data;
+#[cfg(feature = "openssl")]
+mod prediction;
+mod snapshot;
 
-use crate::{structures::PcrSelectionList, Context, Result};
+use crate::{
+    abstraction::hash::TpmHasher,
+    constants::{CapabilityType, PropertyTag},
+    handles::PcrHandle,
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+    structures::{CapabilityData, DigestValues, Event, PcrSelectionList, PcrSelectionListBuilder},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
 
 pub use bank::PcrBank;
+pub use bank_allocation::PcrBankAllocation;
 pub use data::PcrData;
+#[cfg(feature = "openssl")]
+pub use prediction::PcrPredictor;
+pub use snapshot::PcrSnapshot;
 
 /// Function that reads all the PCRs in a selection list and returns
 /// the result as PCR data.
@@ -70,3 +87,177 @@ pub fn read_all(
     }
     Ok(pcr_data)
 }
+
+/// Function that extends a PCR in all the allocated banks with an event.
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `pcr_handle` - A [PcrHandle] to the PCR slot that is to be extended.
+/// * `event_data` - The raw data of the event.
+///
+/// # Details
+/// Events that fit in an [Event] are sent to the TPM with
+/// [Context::pcr_event], which hashes the data with the algorithm of
+/// each bank and extends all the banks in a single command.
+///
+/// Larger events are hashed by the TPM, in chunks, with the algorithm
+/// of each of the allocated banks, and the PCR is then extended in all
+/// the banks at once with [Context::pcr_extend].
+///
+/// An authorization session for the PCR needs to be set in the context.
+///
+/// # Returns
+/// The digests of the event that have been used to extend the banks.
+///
+/// # Example
+///
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf, interface_types::session_handles::AuthSession};
+/// # // Create context
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// use tss_esapi::handles::PcrHandle;
+///
+/// let digests = context
+///     .execute_with_session(Some(AuthSession::Password), |ctx| {
+///         tss_esapi::abstraction::pcr::extend(ctx, PcrHandle::Pcr16, b"event data")
+///     })
+///     .expect("pcr::extend failed");
+/// ```
+pub fn extend(
+    context: &mut Context,
+    pcr_handle: PcrHandle,
+    event_data: &[u8],
+) -> Result<DigestValues> {
+    if event_data.len() <= Event::MAX_SIZE {
+        return context.pcr_event(pcr_handle, Event::from_bytes(event_data)?);
+    }
+
+    let mut digests = DigestValues::new();
+    for hashing_algorithm in active_banks(context)? {
+        let mut hasher = TpmHasher::new(context, hashing_algorithm)?;
+        hasher.update(event_data)?;
+        let (digest, _) = hasher.finalize(Hierarchy::Null)?;
+        digests.set(hashing_algorithm, digest);
+    }
+    context.pcr_extend(pcr_handle, digests.clone())?;
+    Ok(digests)
+}
+
+/// Function that reads the values of all the allocated PCR.
+///
+/// # Details
+/// The allocated PCR are retrieved from the TPM capabilities and are
+/// read with as few calls to [Context::pcr_read] as the TPM allows.
+///
+/// # Example
+///
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf};
+/// # // Create context
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// #
+/// use tss_esapi::{interface_types::algorithm::HashingAlgorithm, structures::PcrSlot};
+///
+/// let pcr_snapshot = tss_esapi::abstraction::pcr::snapshot(&mut context)
+///     .expect("pcr::snapshot failed");
+/// let pcr0 = &pcr_snapshot[(HashingAlgorithm::Sha256, PcrSlot::Slot0)];
+/// ```
+pub fn snapshot(context: &mut Context) -> Result<PcrSnapshot> {
+    let pcr_selection_list = allocated_pcrs(context)?;
+    context
+        .execute_without_session(|ctx| read_all(ctx, pcr_selection_list))
+        .map(PcrSnapshot::from)
+}
+
+/// Function that retrieves the PCR banks implemented by the TPM
+/// and the PCR allocated in each of them.
+///
+/// # Details
+/// The banks in which no PCR is allocated are also returned.
+///
+/// # Errors
+/// * if the number of PCR cannot be retrieved from the TPM, a
+///   `WrongValueFromTpm` wrapper error is returned
+///
+/// # Example
+///
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf};
+/// # // Create context
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// #
+/// use tss_esapi::interface_types::algorithm::HashingAlgorithm;
+///
+/// let pcr_bank_allocations = tss_esapi::abstraction::pcr::allocated_banks(&mut context)
+///     .expect("pcr::allocated_banks failed");
+/// let sha256_fully_allocated = pcr_bank_allocations
+///     .iter()
+///     .any(|pcr_bank_allocation| {
+///         pcr_bank_allocation.hashing_algorithm() == HashingAlgorithm::Sha256
+///             && pcr_bank_allocation.is_fully_allocated()
+///     });
+/// ```
+pub fn allocated_banks(context: &mut Context) -> Result<Vec<PcrBankAllocation>> {
+    let pcr_count = context
+        .get_tpm_property(PropertyTag::PcrCount)?
+        .map(usize::try_from)
+        .transpose()
+        .map_err(|_| {
+            error!("Failed to obtain a valid number of PCR");
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?
+        .ok_or_else(|| {
+            error!("Missing number of PCR in the response from the TPM");
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+    Ok(assigned_pcrs(context)?
+        .get_selections()
+        .iter()
+        .map(|pcr_selection| PcrBankAllocation::new(*pcr_selection, pcr_count))
+        .collect())
+}
+
+/// Returns the hashing algorithms of the banks in which
+/// at least one PCR is allocated.
+fn active_banks(context: &mut Context) -> Result<Vec<HashingAlgorithm>> {
+    Ok(allocated_pcrs(context)?
+        .get_selections()
+        .iter()
+        .map(|pcr_selection| pcr_selection.hashing_algorithm())
+        .collect())
+}
+
+/// Returns the allocated PCR, without the banks in
+/// which no PCR is allocated.
+fn allocated_pcrs(context: &mut Context) -> Result<PcrSelectionList> {
+    assigned_pcrs(context)?
+        .get_selections()
+        .iter()
+        .filter(|pcr_selection| !pcr_selection.is_empty())
+        .fold(PcrSelectionListBuilder::new(), |builder, pcr_selection| {
+            builder.with_selection(pcr_selection.hashing_algorithm(), &pcr_selection.selected())
+        })
+        .build()
+}
+
+/// Returns the assigned PCR capability of the TPM.
+fn assigned_pcrs(context: &mut Context) -> Result<PcrSelectionList> {
+    let (capability_data, _) = context
+        .execute_without_session(|ctx| ctx.get_capability(CapabilityType::AssignedPcr, 0, 1))?;
+    match capability_data {
+        CapabilityData::AssignedPcr(pcr_selection_list) => Ok(pcr_selection_list),
+        _ => {
+            error!("Unexpected capability data when reading the allocated PCR banks");
+            Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm))
+        }
+    }
+}
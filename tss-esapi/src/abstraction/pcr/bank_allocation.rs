@@ -0,0 +1,71 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{PcrSelection, PcrSlot},
+};
+use std::convert::TryFrom;
+
+/// The allocation of the PCR in a bank
+///
+/// # Details
+/// Describes a PCR bank implemented by the TPM, with the PCR
+/// that are allocated in it. The allocations are retrieved with
+/// [allocated_banks](crate::abstraction::pcr::allocated_banks).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PcrBankAllocation {
+    pcr_selection: PcrSelection,
+    pcr_count: usize,
+}
+
+impl PcrBankAllocation {
+    /// Creates a new bank allocation from the [PcrSelection] with the allocated
+    /// PCR and the number of PCR implemented by the TPM.
+    pub const fn new(pcr_selection: PcrSelection, pcr_count: usize) -> Self {
+        PcrBankAllocation {
+            pcr_selection,
+            pcr_count,
+        }
+    }
+
+    /// Returns the hashing algorithm of the bank.
+    pub const fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.pcr_selection.hashing_algorithm()
+    }
+
+    /// Returns the number of PCR implemented by the TPM.
+    pub const fn pcr_count(&self) -> usize {
+        self.pcr_count
+    }
+
+    /// Returns the [PcrSelection] with the allocated PCR.
+    pub const fn pcr_selection(&self) -> &PcrSelection {
+        &self.pcr_selection
+    }
+
+    /// Returns the allocated PCR.
+    pub fn allocated(&self) -> Vec<PcrSlot> {
+        self.pcr_selection.selected()
+    }
+
+    /// Returns true if the PCR is allocated in the bank.
+    pub fn is_allocated(&self, pcr_slot: PcrSlot) -> bool {
+        self.pcr_selection.is_selected(pcr_slot)
+    }
+
+    /// Returns true if no PCR is allocated in the bank.
+    pub fn is_empty(&self) -> bool {
+        self.pcr_selection.is_empty()
+    }
+
+    /// Returns true if all the PCR implemented by the TPM are allocated in the bank.
+    pub fn is_fully_allocated(&self) -> bool {
+        (0..self.pcr_count).all(|index| {
+            1u32.checked_shl(index as u32)
+                .and_then(|value| PcrSlot::try_from(value).ok())
+                .map(|pcr_slot| self.is_allocated(pcr_slot))
+                .unwrap_or(false)
+        })
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    abstraction::{outer_wrapper::hash_chunks, pcr::PcrSnapshot, policy::Calculator},
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{Digest, PcrSelectionList, PcrSlot},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// Predicts the values of the PCR after a list of planned events
+///
+/// # Details
+/// Starting from an initial [PcrSnapshot], e.g. the values of the PCR at the
+/// start of the boot, the events are applied in order, in the same way as the
+/// TPM extends the PCR, and the expected values and the corresponding
+/// `PolicyPCR` policy digest can be computed. This allows sealing data against
+/// a future state of the PCR.
+///
+/// The computations are done in software, so the predictor is only available
+/// when the `openssl` feature is enabled.
+#[derive(Debug, Clone)]
+pub struct PcrPredictor {
+    pcr_snapshot: PcrSnapshot,
+}
+
+impl PcrPredictor {
+    /// Creates a new predictor starting from `pcr_snapshot`.
+    pub fn new(pcr_snapshot: PcrSnapshot) -> Self {
+        PcrPredictor { pcr_snapshot }
+    }
+
+    /// Applies an event to the PCR in all the banks of the snapshot.
+    ///
+    /// # Details
+    /// The event data is hashed with the algorithm of each bank, and the
+    /// PCR is extended with the resulting digest, as done by
+    /// [Context::pcr_event](crate::Context::pcr_event).
+    ///
+    /// # Errors
+    /// * if the snapshot does not contain the PCR in any of the banks, an
+    ///   `InvalidParam` wrapper error is returned
+    pub fn extend_event(&mut self, pcr_slot: PcrSlot, event_data: &[u8]) -> Result<()> {
+        let hashing_algorithms: Vec<HashingAlgorithm> = self
+            .pcr_snapshot
+            .hashing_algorithms()
+            .filter(|&hashing_algorithm| {
+                self.pcr_snapshot.get(hashing_algorithm, pcr_slot).is_some()
+            })
+            .collect();
+        if hashing_algorithms.is_empty() {
+            error!("The snapshot does not contain {:?} in any bank", pcr_slot);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        for hashing_algorithm in hashing_algorithms {
+            let event_digest = Digest::from_bytes(&hash_chunks(hashing_algorithm, &[event_data])?)?;
+            self.extend_digest(hashing_algorithm, pcr_slot, &event_digest)?;
+        }
+        Ok(())
+    }
+
+    /// Extends the PCR in a bank with a digest.
+    ///
+    /// # Details
+    /// The new value of the PCR is the digest of its current value
+    /// concatenated with `digest`, as done by [Context::pcr_extend](crate::Context::pcr_extend).
+    ///
+    /// # Errors
+    /// * if the snapshot does not contain the PCR in the bank, an `InvalidParam`
+    ///   wrapper error is returned
+    /// * if the size of `digest` does not match the hashing algorithm of the
+    ///   bank, a `WrongParamSize` wrapper error is returned
+    pub fn extend_digest(
+        &mut self,
+        hashing_algorithm: HashingAlgorithm,
+        pcr_slot: PcrSlot,
+        digest: &Digest,
+    ) -> Result<()> {
+        let current_value = self
+            .pcr_snapshot
+            .get(hashing_algorithm, pcr_slot)
+            .ok_or_else(|| {
+                error!(
+                    "The snapshot does not contain {:?} in the {:?} bank",
+                    pcr_slot, hashing_algorithm
+                );
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })?;
+        if digest.len() != current_value.len() {
+            error!(
+                "Invalid digest size for the {:?} bank ({} != {})",
+                hashing_algorithm,
+                digest.len(),
+                current_value.len()
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+        let new_value = Digest::from_bytes(&hash_chunks(
+            hashing_algorithm,
+            &[current_value.as_bytes(), digest.as_bytes()],
+        )?)?;
+        let pcr_bank = self
+            .pcr_snapshot
+            .bank_mut(hashing_algorithm)
+            .ok_or_else(|| {
+                error!("The bank has been removed from the snapshot");
+                Error::local_error(WrapperErrorKind::InternalError)
+            })?;
+        let _ = pcr_bank.remove_digest(pcr_slot);
+        pcr_bank.insert_digest(pcr_slot, new_value)
+    }
+
+    /// Returns the predicted values of the PCR.
+    pub fn pcr_snapshot(&self) -> &PcrSnapshot {
+        &self.pcr_snapshot
+    }
+
+    /// Consumes the predictor and returns the predicted values of the PCR.
+    pub fn into_pcr_snapshot(self) -> PcrSnapshot {
+        self.pcr_snapshot
+    }
+
+    /// Computes the policy digest of a `PolicyPCR` assertion for the predicted values.
+    ///
+    /// # Arguments
+    /// * `pcr_selection_list` - The PCR that are included in the assertion.
+    /// * `policy_hashing_algorithm` - The hashing algorithm of the policy session.
+    /// * `policy_digest` - The policy digest before the assertion. If `None` is
+    ///                     provided, the initial policy digest (all zeros) is used.
+    ///
+    /// # Details
+    /// The returned digest is the one that a policy session using
+    /// `policy_hashing_algorithm` has after calling
+    /// [Context::policy_pcr](crate::Context::policy_pcr) with `pcr_selection_list`
+    /// once the PCR have the predicted values, so it can be used as the
+    /// authorization policy of a sealed object.
+    ///
+    /// # Errors
+    /// * if the snapshot does not contain one of the selected PCR, an `InvalidParam`
+    ///   wrapper error is returned
+    /// * if the size of `policy_digest` does not match `policy_hashing_algorithm`,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_pcr_digest(
+        &self,
+        pcr_selection_list: &PcrSelectionList,
+        policy_hashing_algorithm: HashingAlgorithm,
+        policy_digest: Option<&Digest>,
+    ) -> Result<Digest> {
+        let mut calculator = match policy_digest {
+            Some(policy_digest) => {
+                Calculator::with_policy_digest(policy_hashing_algorithm, policy_digest)?
+            }
+            None => Calculator::new(policy_hashing_algorithm)?,
+        };
+        let pcr_digest = Digest::from_bytes(&hash_chunks(
+            policy_hashing_algorithm,
+            &[&self.pcr_snapshot.composite_data(pcr_selection_list)?],
+        )?)?;
+        let _ = calculator.policy_pcr(&pcr_digest, pcr_selection_list)?;
+        calculator.policy_digest()
+    }
+}
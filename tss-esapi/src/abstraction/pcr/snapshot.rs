@@ -0,0 +1,212 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(feature = "openssl")]
+use crate::abstraction::outer_wrapper::message_digest;
+use crate::{
+    abstraction::pcr::{PcrBank, PcrData},
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{Digest, PcrSelectionList, PcrSlot},
+    tss2_esys::TPMI_ALG_HASH,
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::ops::Index;
+
+/// Snapshot of the values of the PCR in one or more banks
+///
+/// # Details
+/// The values are indexed by the hashing algorithm of the bank and
+/// the [PcrSlot], e.g. `snapshot[(HashingAlgorithm::Sha256, PcrSlot::Slot0)]`.
+///
+/// A snapshot of all the allocated banks can be taken with
+/// [snapshot](crate::abstraction::pcr::snapshot). The snapshot implements
+/// `Serialize` and `Deserialize`, so it can be stored and compared with
+/// later values of the PCR.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<SerializedPcr>", into = "Vec<SerializedPcr>")]
+pub struct PcrSnapshot {
+    banks: Vec<(HashingAlgorithm, PcrBank)>,
+}
+
+impl PcrSnapshot {
+    /// Returns the [Digest] of the PCR in the bank, if the snapshot contains it.
+    pub fn get(&self, hashing_algorithm: HashingAlgorithm, pcr_slot: PcrSlot) -> Option<&Digest> {
+        self.bank(hashing_algorithm)
+            .and_then(|pcr_bank| pcr_bank.get_digest(pcr_slot))
+    }
+
+    /// Returns the [PcrBank] with the values of the bank, if the snapshot contains it.
+    pub fn bank(&self, hashing_algorithm: HashingAlgorithm) -> Option<&PcrBank> {
+        self.banks
+            .iter()
+            .find(|(alg, _)| *alg == hashing_algorithm)
+            .map(|(_, bank)| bank)
+    }
+
+    /// Private method for finding a PCR bank, used by the predictions.
+    #[cfg(feature = "openssl")]
+    pub(super) fn bank_mut(&mut self, hashing_algorithm: HashingAlgorithm) -> Option<&mut PcrBank> {
+        self.banks
+            .iter_mut()
+            .find(|(alg, _)| *alg == hashing_algorithm)
+            .map(|(_, bank)| bank)
+    }
+
+    /// Returns the hashing algorithms of the banks in the snapshot.
+    pub fn hashing_algorithms(&self) -> impl Iterator<Item = HashingAlgorithm> + '_ {
+        self.banks.iter().map(|(alg, _)| *alg)
+    }
+
+    /// Returns an iterator over the values in the snapshot, ordered by bank and [PcrSlot].
+    pub fn iter(&self) -> impl Iterator<Item = (HashingAlgorithm, PcrSlot, &Digest)> + '_ {
+        self.banks.iter().flat_map(|(alg, bank)| {
+            bank.into_iter()
+                .map(move |(pcr_slot, digest)| (*alg, *pcr_slot, digest))
+        })
+    }
+
+    /// Returns the number of values in the snapshot.
+    pub fn len(&self) -> usize {
+        self.banks.iter().map(|(_, bank)| bank.len()).sum()
+    }
+
+    /// Returns true if there are no values in the snapshot.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the concatenation of the values of the PCR in the selection.
+    ///
+    /// # Details
+    /// The values are concatenated in the order used by the TPM when computing
+    /// the PCR digest of a selection, i.e. in the order of the banks in the
+    /// selection and, within a bank, in ascending order of [PcrSlot].
+    ///
+    /// # Errors
+    /// * if the snapshot does not contain one of the selected PCR, an `InvalidParam`
+    ///   wrapper error is returned
+    pub fn composite_data(&self, pcr_selection_list: &PcrSelectionList) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        for pcr_selection in pcr_selection_list.get_selections() {
+            let hashing_algorithm = pcr_selection.hashing_algorithm();
+            for pcr_slot in pcr_selection.selected() {
+                let digest = self.get(hashing_algorithm, pcr_slot).ok_or_else(|| {
+                    error!(
+                        "The snapshot does not contain {:?} in the {:?} bank",
+                        pcr_slot, hashing_algorithm
+                    );
+                    Error::local_error(WrapperErrorKind::InvalidParam)
+                })?;
+                data.extend_from_slice(digest.as_bytes());
+            }
+        }
+        Ok(data)
+    }
+
+    /// Computes the digest of the values of the PCR in the selection.
+    ///
+    /// # Details
+    /// This is the digest computed by the TPM, e.g. in a quote or in
+    /// `PolicyPCR`, for the selection, using `hashing_algorithm`.
+    ///
+    /// The function is only available when the `openssl` feature is enabled.
+    ///
+    /// # Errors
+    /// * if the snapshot does not contain one of the selected PCR, an `InvalidParam`
+    ///   wrapper error is returned
+    /// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+    ///   error is returned
+    #[cfg(feature = "openssl")]
+    pub fn composite_digest(
+        &self,
+        pcr_selection_list: &PcrSelectionList,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Result<Digest> {
+        let data = self.composite_data(pcr_selection_list)?;
+        let digest =
+            openssl::hash::hash(message_digest(hashing_algorithm)?, &data).map_err(|e| {
+                error!("Failed to hash the PCR values: {}", e);
+                Error::local_error(WrapperErrorKind::InternalError)
+            })?;
+        Digest::from_bytes(&digest)
+    }
+}
+
+impl Index<(HashingAlgorithm, PcrSlot)> for PcrSnapshot {
+    type Output = Digest;
+
+    /// Returns the [Digest] of the PCR in the bank.
+    ///
+    /// # Panics
+    /// Panics if the snapshot does not contain the PCR.
+    fn index(&self, (hashing_algorithm, pcr_slot): (HashingAlgorithm, PcrSlot)) -> &Digest {
+        self.get(hashing_algorithm, pcr_slot).unwrap_or_else(|| {
+            panic!(
+                "The snapshot does not contain {:?} in the {:?} bank",
+                pcr_slot, hashing_algorithm
+            )
+        })
+    }
+}
+
+impl From<PcrData> for PcrSnapshot {
+    fn from(pcr_data: PcrData) -> Self {
+        let mut banks: Vec<(HashingAlgorithm, PcrBank)> = pcr_data.into_iter().collect();
+        // The banks are kept in a fixed order so that snapshots
+        // can be compared regardless of how the PCR were read.
+        banks.sort_by_key(|(hashing_algorithm, _)| TPMI_ALG_HASH::from(*hashing_algorithm));
+        PcrSnapshot { banks }
+    }
+}
+
+/// The serialized form of a value in a [PcrSnapshot].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SerializedPcr {
+    hashing_algorithm: TPMI_ALG_HASH,
+    pcr_index: u32,
+    digest: Vec<u8>,
+}
+
+impl From<PcrSnapshot> for Vec<SerializedPcr> {
+    fn from(pcr_snapshot: PcrSnapshot) -> Self {
+        pcr_snapshot
+            .iter()
+            .map(|(hashing_algorithm, pcr_slot, digest)| SerializedPcr {
+                hashing_algorithm: hashing_algorithm.into(),
+                pcr_index: u32::from(pcr_slot).trailing_zeros(),
+                digest: digest.as_bytes().to_vec(),
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<SerializedPcr>> for PcrSnapshot {
+    type Error = Error;
+
+    fn try_from(serialized_pcrs: Vec<SerializedPcr>) -> Result<Self> {
+        let mut banks: Vec<(HashingAlgorithm, PcrBank)> = Vec::new();
+        for serialized_pcr in serialized_pcrs {
+            let hashing_algorithm = HashingAlgorithm::try_from(serialized_pcr.hashing_algorithm)?;
+            let pcr_slot = 1u32
+                .checked_shl(serialized_pcr.pcr_index)
+                .ok_or_else(|| {
+                    error!("Invalid PCR index {}", serialized_pcr.pcr_index);
+                    Error::local_error(WrapperErrorKind::InvalidParam)
+                })
+                .and_then(PcrSlot::try_from)?;
+            let digest = Digest::try_from(serialized_pcr.digest)?;
+            match banks.iter_mut().find(|(alg, _)| *alg == hashing_algorithm) {
+                Some((_, pcr_bank)) => pcr_bank.insert_digest(pcr_slot, digest)?,
+                None => banks.push((
+                    hashing_algorithm,
+                    PcrBank::create(vec![pcr_slot], vec![digest])?,
+                )),
+            }
+        }
+        banks.sort_by_key(|(hashing_algorithm, _)| TPMI_ALG_HASH::from(*hashing_algorithm));
+        Ok(PcrSnapshot { banks })
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+#[cfg(feature = "openssl")]
+mod builder;
+#[cfg(feature = "openssl")]
+mod calculator;
+#[cfg(feature = "openssl")]
+mod fapi;
+#[cfg(feature = "openssl")]
+mod or_tree;
+#[cfg(feature = "openssl")]
+mod replay;
+#[cfg(feature = "openssl")]
+mod serialized;
+
+#[cfg(feature = "openssl")]
+use crate::{
+    abstraction::outer_wrapper::message_digest, interface_types::algorithm::HashingAlgorithm,
+};
+#[cfg(feature = "openssl")]
+use crate::{
+    abstraction::pcr::{self, PcrSnapshot},
+    structures::{PcrSelectionList, Public},
+    traits::Marshall,
+};
+use crate::{
+    constants::CommandCode,
+    handles::{AuthHandle, KeyHandle},
+    interface_types::{resource_handles::Hierarchy, session_handles::PolicySession},
+    structures::{
+        AuthTicket, Digest, MaxBuffer, Name, Nonce, PolicyExpiration, Signature, SignatureScheme,
+        Ticket, Timeout,
+    },
+    tss2_esys::TPM2_CC,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::{convert::TryFrom, time::Duration};
+
+#[cfg(feature = "openssl")]
+pub use builder::{Policy, PolicyAssertion, SecretEntity};
+#[cfg(feature = "openssl")]
+pub use calculator::Calculator;
+#[cfg(feature = "openssl")]
+pub use fapi::FapiPolicy;
+#[cfg(feature = "openssl")]
+pub use or_tree::PolicyOrTree;
+#[cfg(feature = "openssl")]
+pub use replay::{replay, PolicyResolver, SignedAuthorization};
+
+/// Returns the data that is signed by an authority to authorize a policy
+/// with [Context::policy_signed](crate::Context::policy_signed).
+///
+/// # Details
+/// The data is the concatenation of `nonce_tpm`, `expiration` (as a
+/// big-endian 32-bit integer), `cp_hash_a` and `policy_ref`. The authority
+/// signs the digest of the data (`aHash`), computed with the hashing
+/// algorithm of its signing scheme, and the TPM recomputes the same digest
+/// when verifying the signature. The parameters must therefore have the
+/// same values as the ones later given to
+/// [Context::policy_signed](crate::Context::policy_signed).
+///
+/// An authorization that expires must be bound to the policy session, with
+/// the current nonce of the session returned by
+/// [Context::session_nonce_tpm](crate::Context::session_nonce_tpm) as `nonce_tpm`.
+///
+/// # Errors
+/// * if the expiration is too long, or if a ticket is requested for an
+///   authorization that does not expire, an `InvalidParam` wrapper error
+///   is returned
+pub fn signed_authorization_data(
+    nonce_tpm: &Nonce,
+    expiration: PolicyExpiration,
+    cp_hash_a: &Digest,
+    policy_ref: &Nonce,
+) -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(
+        nonce_tpm.len() + std::mem::size_of::<i32>() + cp_hash_a.len() + policy_ref.len(),
+    );
+    data.extend_from_slice(nonce_tpm.as_bytes());
+    data.extend_from_slice(&i32::try_from(expiration)?.to_be_bytes());
+    data.extend_from_slice(cp_hash_a.as_bytes());
+    data.extend_from_slice(policy_ref.as_bytes());
+    Ok(data)
+}
+
+/// Returns the data that is hashed to compute the command parameter hash
+/// (`cpHash`) of a command.
+///
+/// # Arguments
+/// * `command_code` - The command code of the command.
+/// * `handle_names` - The names of the entities referenced by the handles of the
+///                    command, in the order of the handles.
+/// * `marshalled_parameters` - The marshalled parameters of the command, in the
+///                             order of the parameters.
+///
+/// # Details
+/// The data is the concatenation of the command code (as a big-endian 32-bit
+/// integer), the names and the parameters. The digest of the data, computed with
+/// the hashing algorithm of a policy session, can be given to
+/// [Context::policy_cp_hash](crate::Context::policy_cp_hash) to restrict the
+/// policy to a command with these exact handles and parameters, without
+/// executing the command first.
+pub fn cp_hash_data(
+    command_code: CommandCode,
+    handle_names: &[Name],
+    marshalled_parameters: &[u8],
+) -> Vec<u8> {
+    let mut data = TPM2_CC::from(command_code).to_be_bytes().to_vec();
+    for handle_name in handle_names {
+        data.extend_from_slice(handle_name.value());
+    }
+    data.extend_from_slice(marshalled_parameters);
+    data
+}
+
+/// Computes the command parameter hash (`cpHash`) of a command.
+///
+/// # Details
+/// The hash is the digest, computed with `hashing_algorithm`, of the data
+/// returned by [cp_hash_data], with the same arguments.
+///
+/// The function is only available when the `openssl` feature is enabled.
+///
+/// # Errors
+/// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+///   error is returned
+#[cfg(feature = "openssl")]
+pub fn cp_hash(
+    hashing_algorithm: HashingAlgorithm,
+    command_code: CommandCode,
+    handle_names: &[Name],
+    marshalled_parameters: &[u8],
+) -> Result<Digest> {
+    let data = cp_hash_data(command_code, handle_names, marshalled_parameters);
+    let digest = openssl::hash::hash(message_digest(hashing_algorithm)?, &data).map_err(|e| {
+        error!("Failed to hash the command parameters: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    Digest::from_bytes(&digest)
+}
+
+/// Returns the data that is hashed to compute the name hash (`nameHash`)
+/// of the entities referenced by the handles of a command.
+///
+/// # Details
+/// The data is the concatenation of the names, in the order of the handles
+/// of the command. The digest of the data, computed with the hashing algorithm
+/// of a policy session, can be given to
+/// [Context::policy_name_hash](crate::Context::policy_name_hash) to restrict
+/// the policy to a command acting on these exact entities.
+pub fn name_hash_data(handle_names: &[Name]) -> Vec<u8> {
+    handle_names
+        .iter()
+        .flat_map(|handle_name| handle_name.value().iter().copied())
+        .collect()
+}
+
+/// Computes the name hash (`nameHash`) of the entities referenced by the
+/// handles of a command.
+///
+/// # Details
+/// The hash is the digest, computed with `hashing_algorithm`, of the data
+/// returned by [name_hash_data], with the same names.
+///
+/// The function is only available when the `openssl` feature is enabled.
+///
+/// # Errors
+/// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+///   error is returned
+#[cfg(feature = "openssl")]
+pub fn name_hash(hashing_algorithm: HashingAlgorithm, handle_names: &[Name]) -> Result<Digest> {
+    let digest = openssl::hash::hash(
+        message_digest(hashing_algorithm)?,
+        &name_hash_data(handle_names),
+    )
+    .map_err(|e| {
+        error!("Failed to hash the names: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    Digest::from_bytes(&digest)
+}
+
+/// Computes the template hash of a [Public] structure.
+///
+/// # Details
+/// The hash is the digest, computed with `hashing_algorithm`, of the marshalled
+/// public area, as computed by the TPM for the template of the object creation
+/// commands. It can be given to [Context::policy_template](crate::Context::policy_template)
+/// to restrict the policy to the creation of objects with exactly this template,
+/// e.g. in the policy of the endorsement hierarchy used when provisioning an EK.
+///
+/// The function is only available when the `openssl` feature is enabled. Without
+/// it, the hash can be computed with [Context::hash](crate::Context::hash) from the
+/// data returned by [Marshall::marshall](crate::traits::Marshall::marshall).
+///
+/// # Errors
+/// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+///   error is returned
+#[cfg(feature = "openssl")]
+pub fn template_hash(hashing_algorithm: HashingAlgorithm, public: &Public) -> Result<Digest> {
+    let digest = openssl::hash::hash(message_digest(hashing_algorithm)?, &public.marshall()?)
+        .map_err(|e| {
+            error!("Failed to hash the template: {}", e);
+            Error::local_error(WrapperErrorKind::InternalError)
+        })?;
+    Digest::from_bytes(&digest)
+}
+
+/// Returns the data that is signed by an authority to approve a policy,
+/// for [Context::policy_authorize](crate::Context::policy_authorize).
+///
+/// # Details
+/// The data is the concatenation of `approved_policy` and `policy_ref`. The
+/// authority signs the digest of the data (`aHash`), computed with the name
+/// hashing algorithm of its signing key, which is the digest the TPM uses when
+/// checking the ticket given to `PolicyAuthorize`.
+pub fn approved_policy_data(approved_policy: &Digest, policy_ref: &Nonce) -> Vec<u8> {
+    let mut data = Vec::with_capacity(approved_policy.len() + policy_ref.len());
+    data.extend_from_slice(approved_policy.as_bytes());
+    data.extend_from_slice(policy_ref.as_bytes());
+    data
+}
+
+/// Computes the digest (`aHash`) that is signed by an authority to approve a policy.
+///
+/// # Details
+/// The hash is the digest, computed with `hashing_algorithm`, of the data
+/// returned by [approved_policy_data], with the same arguments. The hashing
+/// algorithm must be the name hashing algorithm of the signing key. This allows
+/// the policy to be signed by an authority whose key is not in a TPM, the public
+/// part of the key then being loaded with
+/// [Context::load_external_public](crate::Context::load_external_public) to
+/// [authorize] the policy.
+///
+/// The function is only available when the `openssl` feature is enabled.
+///
+/// # Errors
+/// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+///   error is returned
+#[cfg(feature = "openssl")]
+pub fn approved_policy_hash(
+    hashing_algorithm: HashingAlgorithm,
+    approved_policy: &Digest,
+    policy_ref: &Nonce,
+) -> Result<Digest> {
+    let digest = openssl::hash::hash(
+        message_digest(hashing_algorithm)?,
+        &approved_policy_data(approved_policy, policy_ref),
+    )
+    .map_err(|e| {
+        error!("Failed to hash the approved policy: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    Digest::from_bytes(&digest)
+}
+
+/// Signs a policy with a key loaded in the TPM, to approve it.
+///
+/// # Arguments
+/// * `key_handle` - The signing key of the authority. The key must have a
+///                  signing scheme.
+/// * `approved_policy` - The policy digest that is approved.
+/// * `policy_ref` - The policy qualifier, which must also be given to [authorize].
+///
+/// # Details
+/// The digest of the approved policy is computed by the TPM, with the name
+/// hashing algorithm of the key, and signed with the scheme of the key. The
+/// signature can be stored with the approved policy and later given to
+/// [authorize], with the public part of the key, to satisfy a policy ending
+/// with a `PolicyAuthorize` assertion for the key.
+///
+/// The sessions of the context are used to authorize the use of the key.
+pub fn sign_approved_policy(
+    context: &mut Context,
+    key_handle: KeyHandle,
+    approved_policy: &Digest,
+    policy_ref: &Nonce,
+) -> Result<Signature> {
+    let (public, _, _) = context.execute_without_session(|ctx| ctx.read_public(key_handle))?;
+    let (a_hash, validation) = context.execute_without_session(|ctx| {
+        ctx.hash(
+            MaxBuffer::from_bytes(&approved_policy_data(approved_policy, policy_ref))?,
+            public.name_hashing_algorithm(),
+            Hierarchy::Owner,
+        )
+    })?;
+    context.sign(key_handle, a_hash, SignatureScheme::Null, validation)
+}
+
+/// Satisfies a `PolicyAuthorize` assertion with a signed policy.
+///
+/// # Arguments
+/// * `policy_session` - The policy session, in which the approved policy has
+///                      been satisfied.
+/// * `key_handle` - The signing key of the authority. Only the public part of
+///                  the key is needed, but the key must not be loaded in the
+///                  `Null` hierarchy, as the TPM then does not produce a ticket.
+/// * `policy_ref` - The policy qualifier used when signing the policy.
+/// * `signature` - The signature of the approved policy, e.g. returned by
+///                 [sign_approved_policy].
+///
+/// # Details
+/// The approved policy is the current policy digest of the session. The signature
+/// is verified by the TPM with [Context::verify_signature](crate::Context::verify_signature),
+/// and the resulting ticket is given to [Context::policy_authorize](crate::Context::policy_authorize),
+/// which replaces the policy digest of the session by the digest of a
+/// `PolicyAuthorize` assertion for the key.
+///
+/// # Errors
+/// * if the signature is not valid for the approved policy, the TSS error
+///   returned by the TPM is returned
+pub fn authorize(
+    context: &mut Context,
+    policy_session: PolicySession,
+    key_handle: KeyHandle,
+    policy_ref: Nonce,
+    signature: Signature,
+) -> Result<()> {
+    context.execute_without_session(|ctx| {
+        let (public, key_name, _) = ctx.read_public(key_handle)?;
+        let approved_policy = ctx.policy_get_digest(policy_session)?;
+        let (a_hash, _) = ctx.hash(
+            MaxBuffer::from_bytes(&approved_policy_data(&approved_policy, &policy_ref))?,
+            public.name_hashing_algorithm(),
+            Hierarchy::Null,
+        )?;
+        let ticket = ctx.verify_signature(key_handle, a_hash, signature)?;
+        ctx.policy_authorize(
+            policy_session,
+            approved_policy,
+            policy_ref,
+            &key_name,
+            ticket,
+        )
+    })
+}
+
+/// Computes a policy bound to the current values of PCR.
+///
+/// # Arguments
+/// * `pcr_selection_list` - The PCR to which the policy is bound.
+///
+/// # Details
+/// The current values of the selected PCR are read with [pcr::read_all],
+/// and the policy, made of a single `PolicyPCR` assertion for these values,
+/// is computed in software. The policy uses the hashing algorithm of the
+/// first bank of the selection, which must therefore be the name hashing
+/// algorithm of the object sealed with the policy.
+///
+/// Returns the policy digest, to be used as the authorization policy when
+/// creating the object, and the policy itself, which can be stored and later
+/// executed with [Policy::execute] or [replay] to authorize the use of the
+/// object, as long as the PCR keep their current values.
+///
+/// The function is only available when the `openssl` feature is enabled.
+///
+/// # Errors
+/// * if the selection is empty, an `InvalidParam` wrapper error is returned
+/// * if the hashing algorithm of the first bank is not supported, an
+///   `UnsupportedParam` wrapper error is returned
+#[cfg(feature = "openssl")]
+pub fn seal_to_current_pcrs(
+    context: &mut Context,
+    pcr_selection_list: PcrSelectionList,
+) -> Result<(Digest, Policy)> {
+    let hashing_algorithm = pcr_selection_list
+        .get_selections()
+        .first()
+        .map(|pcr_selection| pcr_selection.hashing_algorithm())
+        .ok_or_else(|| {
+            error!("The PCR selection is empty");
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+    let pcr_snapshot = PcrSnapshot::from(
+        context.execute_without_session(|ctx| pcr::read_all(ctx, pcr_selection_list.clone()))?,
+    );
+    let pcr_policy_digest =
+        pcr_snapshot.composite_digest(&pcr_selection_list, hashing_algorithm)?;
+    let policy = Policy::new(hashing_algorithm).pcr(pcr_selection_list, pcr_policy_digest);
+    Ok((policy.digest()?, policy))
+}
+
+/// An authorization given by a `PolicySigned` or `PolicySecret` assertion,
+/// captured as a ticket
+///
+/// # Details
+/// The ticket, with the parameters of the assertion that produced it, allows
+/// the authorization to be given again in other policy sessions with
+/// [Context::policy_ticket](crate::Context::policy_ticket), without a new
+/// signature or a new authorization of the entity, until it expires.
+#[derive(Debug, Clone)]
+pub struct AuthorizationTicket {
+    timeout: Timeout,
+    ticket: AuthTicket,
+    cp_hash_a: Digest,
+    policy_ref: Nonce,
+    auth_name: Name,
+}
+
+impl AuthorizationTicket {
+    /// Creates an authorization ticket from the values returned by
+    /// [Context::policy_signed](crate::Context::policy_signed) or
+    /// [Context::policy_secret_with_expiration](crate::Context::policy_secret_with_expiration),
+    /// and the parameters given to it.
+    ///
+    /// # Arguments
+    /// * `timeout` - The timeout returned with the ticket.
+    /// * `ticket` - The ticket.
+    /// * `cp_hash_a` - The command parameter hash the authorization is restricted to.
+    /// * `policy_ref` - The reference to the policy that is authorized.
+    /// * `auth_name` - The name of the key or of the entity that gave the authorization.
+    ///
+    /// # Errors
+    /// * if the ticket is a NULL ticket, i.e. no ticket was produced by the TPM,
+    ///   an `InvalidParam` wrapper error is returned
+    pub fn new(
+        timeout: Timeout,
+        ticket: AuthTicket,
+        cp_hash_a: Digest,
+        policy_ref: Nonce,
+        auth_name: Name,
+    ) -> Result<Self> {
+        if ticket.digest().is_empty() {
+            error!("No ticket was produced for the authorization");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(AuthorizationTicket {
+            timeout,
+            ticket,
+            cp_hash_a,
+            policy_ref,
+            auth_name,
+        })
+    }
+
+    /// Returns the timeout of the authorization.
+    pub fn timeout(&self) -> &Timeout {
+        &self.timeout
+    }
+
+    /// Returns the ticket.
+    pub fn ticket(&self) -> &AuthTicket {
+        &self.ticket
+    }
+
+    /// Returns the command parameter hash the authorization is restricted to.
+    pub fn cp_hash_a(&self) -> &Digest {
+        &self.cp_hash_a
+    }
+
+    /// Returns the reference to the policy that is authorized.
+    pub fn policy_ref(&self) -> &Nonce {
+        &self.policy_ref
+    }
+
+    /// Returns the name of the key or of the entity that gave the authorization.
+    pub fn auth_name(&self) -> &Name {
+        &self.auth_name
+    }
+
+    /// Gives the authorization in a policy session, with
+    /// [Context::policy_ticket](crate::Context::policy_ticket).
+    ///
+    /// # Errors
+    /// * if the authorization has expired, or the ticket was produced by
+    ///   another TPM or after a reset of the TPM, the TSS error returned by
+    ///   the TPM is returned
+    pub fn execute(&self, context: &mut Context, policy_session: PolicySession) -> Result<()> {
+        context.execute_without_session(|ctx| {
+            ctx.policy_ticket(
+                policy_session,
+                self.timeout.clone(),
+                self.cp_hash_a.clone(),
+                self.policy_ref.clone(),
+                &self.auth_name,
+                self.ticket.clone(),
+            )
+        })
+    }
+}
+
+/// Satisfies a `PolicySecret` assertion and returns the authorization as a ticket.
+///
+/// # Arguments
+/// * `policy_session` - The policy session.
+/// * `auth_handle` - The entity whose authorization value is required: an object,
+///                   an NV index or a hierarchy.
+/// * `nonce_tpm` - The current nonce of the policy session, as returned by
+///                 [Context::session_nonce_tpm](crate::Context::session_nonce_tpm).
+///                 The TPM only produces a ticket for an authorization bound to
+///                 a session.
+/// * `cp_hash_a` - The command parameter hash the authorization is restricted
+///                 to, or an empty digest if it is not restricted.
+/// * `policy_ref` - The reference to the policy that is authorized.
+/// * `expiration` - The duration after which the authorization expires.
+///
+/// # Details
+/// The sessions of the context are used to authorize the entity. The returned
+/// ticket can be given in other policy sessions, with
+/// [AuthorizationTicket::execute], until the authorization expires.
+///
+/// # Errors
+/// * if `nonce_tpm` is empty, or if the expiration is zero or too long, an
+///   `InvalidParam` wrapper error is returned
+pub fn secret_ticket(
+    context: &mut Context,
+    policy_session: PolicySession,
+    auth_handle: AuthHandle,
+    nonce_tpm: Nonce,
+    cp_hash_a: Digest,
+    policy_ref: Nonce,
+    expiration: Duration,
+) -> Result<AuthorizationTicket> {
+    if nonce_tpm.is_empty() {
+        error!("A ticket is only produced for an authorization bound to the session");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    let auth_name = context.execute_without_session(|ctx| ctx.tr_get_name(auth_handle.into()))?;
+    let (timeout, ticket) = context.policy_secret_with_expiration(
+        policy_session,
+        auth_handle,
+        nonce_tpm,
+        cp_hash_a.clone(),
+        policy_ref.clone(),
+        PolicyExpiration::AfterWithTicket(expiration),
+    )?;
+    AuthorizationTicket::new(timeout, ticket, cp_hash_a, policy_ref, auth_name)
+}
@@ -0,0 +1,699 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    replay::{self, NoResolver, PolicyResolver},
+    serialized::SerializedPolicy,
+    Calculator,
+};
+use crate::{
+    attributes::LocalityAttributes,
+    constants::CommandCode,
+    handles::{NvIndexTpmHandle, TpmHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm, resource_handles::HierarchyAuth,
+        session_handles::PolicySession,
+    },
+    structures::{CounterTimerCondition, Digest, DigestList, Name, Nonce, PcrSelectionList},
+    tss2_esys::TPM2_HANDLE,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// Enum representing the entity whose authorization value is required by
+/// a `PolicySecret` assertion
+#[derive(Debug, Clone)]
+pub enum SecretEntity {
+    /// An object, e.g. a key, identified by its name.
+    Name(Name),
+    /// An NV index.
+    ///
+    /// # Details
+    /// The name of the NV index depends on its attributes, so it must be the
+    /// name of the NV index when the policy is executed, e.g. after it has
+    /// been written.
+    NvIndex {
+        nv_index: NvIndexTpmHandle,
+        nv_index_name: Name,
+    },
+    /// A hierarchy, whose name is its handle.
+    Hierarchy(HierarchyAuth),
+}
+
+impl SecretEntity {
+    /// Returns the name of the entity, as used in the policy digest.
+    pub fn name(&self) -> Result<Name> {
+        match self {
+            SecretEntity::Name(name) => Ok(name.clone()),
+            SecretEntity::NvIndex { nv_index_name, .. } => Ok(nv_index_name.clone()),
+            SecretEntity::Hierarchy(hierarchy) => Name::try_from(
+                TPM2_HANDLE::from(TpmHandle::from(*hierarchy))
+                    .to_be_bytes()
+                    .to_vec(),
+            ),
+        }
+    }
+}
+
+/// Enum representing an assertion of a [Policy]
+///
+/// # Details
+/// Each variant, apart from [PolicyAssertion::Or], corresponds to the
+/// policy command of the [Context] with the same name, and holds the
+/// parameters given to it when the policy is executed.
+///
+/// The [PolicyAssertion::Signed], [PolicyAssertion::Secret],
+/// [PolicyAssertion::Authorize] and [PolicyAssertion::AuthorizeNv] assertions
+/// only hold the parameters known when the policy is authored, the signatures,
+/// the authorizations and the approved policies being resolved with a
+/// [PolicyResolver] when the policy is executed.
+#[derive(Debug, Clone)]
+pub enum PolicyAssertion {
+    /// A `PolicyPCR` assertion.
+    Pcr {
+        pcr_selection_list: PcrSelectionList,
+        pcr_policy_digest: Digest,
+    },
+    /// A `PolicyCommandCode` assertion.
+    CommandCode(CommandCode),
+    /// A `PolicyAuthValue` assertion.
+    AuthValue,
+    /// A `PolicyPassword` assertion.
+    Password,
+    /// A `PolicyLocality` assertion.
+    Locality(LocalityAttributes),
+    /// A `PolicyPhysicalPresence` assertion.
+    PhysicalPresence,
+    /// A `PolicyCpHash` assertion.
+    CpHash(Digest),
+    /// A `PolicyNameHash` assertion.
+    NameHash(Digest),
+    /// A `PolicyNvWritten` assertion.
+    NvWritten(bool),
+    /// A `PolicyCounterTimer` assertion.
+    CounterTimer(CounterTimerCondition),
+    /// A `PolicyTemplate` assertion.
+    Template(Digest),
+    /// A `PolicyDuplicationSelect` assertion.
+    DuplicationSelect {
+        object_name: Name,
+        new_parent_name: Name,
+        include_object: bool,
+    },
+    /// A `PolicySigned` assertion, for the key of an authority.
+    Signed { auth_name: Name, policy_ref: Nonce },
+    /// A `PolicySecret` assertion, for the authorization value of an entity.
+    Secret {
+        entity: SecretEntity,
+        policy_ref: Nonce,
+    },
+    /// A `PolicyAuthorize` assertion, for the key of an authority.
+    Authorize { key_sign: Name, policy_ref: Nonce },
+    /// A `PolicyAuthorizeNV` assertion, for an NV index holding the approved
+    /// policy digest.
+    AuthorizeNv {
+        nv_index: NvIndexTpmHandle,
+        nv_index_name: Name,
+    },
+    /// A `PolicyOR` assertion, satisfied by satisfying one of the branches.
+    Or(Vec<Policy>),
+}
+
+/// A policy, built from a sequence of assertions
+///
+/// # Details
+/// The policy is both a description of the assertions, from which the policy
+/// digest used as the authorization policy of an object or an NV index is
+/// computed in software, and an execution plan that can later be replayed in
+/// a policy session to satisfy the policy.
+///
+/// The assertions are added in the order in which they are executed. Branches
+/// are combined with [Policy::or], and the digests of the branches of a
+/// `PolicyOR` assertion are computed and given to the TPM when the policy is
+/// executed.
+///
+/// The policy implements `Serialize` and `Deserialize`, so a policy authored
+/// when an object is provisioned can be stored next to it and loaded again,
+/// possibly on another machine, when the policy has to be satisfied. The
+/// serialized form is versioned and does not depend on the format used by
+/// the serializer.
+///
+/// The policy is only available when the `openssl` feature is enabled.
+///
+/// # Example
+/// ```rust
+/// # use std::convert::TryFrom;
+/// # use tss_esapi::{
+/// #     abstraction::policy::Policy,
+/// #     constants::CommandCode,
+/// #     interface_types::algorithm::HashingAlgorithm,
+/// #     structures::{Digest, PcrSelectionListBuilder, PcrSlot},
+/// # };
+/// let pcr_selection_list = PcrSelectionListBuilder::new()
+///     .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot7])
+///     .build()?;
+/// let pcr_policy_digest = Digest::try_from(vec![0xAB; 32])?;
+/// let branch_b = Policy::new(HashingAlgorithm::Sha256).auth_value();
+/// let policy = Policy::new(HashingAlgorithm::Sha256)
+///     .pcr(pcr_selection_list, pcr_policy_digest)
+///     .or(branch_b)
+///     .command_code(CommandCode::Unseal);
+/// let policy_digest = policy.digest()?;
+/// # Ok::<(), tss_esapi::Error>(())
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SerializedPolicy", into = "SerializedPolicy")]
+pub struct Policy {
+    pub(super) hashing_algorithm: HashingAlgorithm,
+    pub(super) assertions: Vec<PolicyAssertion>,
+}
+
+impl Policy {
+    /// Creates a new policy, without assertions, for a policy session
+    /// using `hashing_algorithm`.
+    pub const fn new(hashing_algorithm: HashingAlgorithm) -> Self {
+        Policy {
+            hashing_algorithm,
+            assertions: Vec::new(),
+        }
+    }
+
+    /// Returns the hashing algorithm of the policy.
+    pub const fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.hashing_algorithm
+    }
+
+    /// Returns the assertions of the policy, in the order in which they are executed.
+    pub fn assertions(&self) -> &[PolicyAssertion] {
+        &self.assertions
+    }
+
+    /// Adds a `PolicyPCR` assertion.
+    pub fn pcr(self, pcr_selection_list: PcrSelectionList, pcr_policy_digest: Digest) -> Self {
+        self.with_assertion(PolicyAssertion::Pcr {
+            pcr_selection_list,
+            pcr_policy_digest,
+        })
+    }
+
+    /// Adds a `PolicyCommandCode` assertion.
+    pub fn command_code(self, code: CommandCode) -> Self {
+        self.with_assertion(PolicyAssertion::CommandCode(code))
+    }
+
+    /// Adds a `PolicyAuthValue` assertion.
+    pub fn auth_value(self) -> Self {
+        self.with_assertion(PolicyAssertion::AuthValue)
+    }
+
+    /// Adds a `PolicyPassword` assertion.
+    pub fn password(self) -> Self {
+        self.with_assertion(PolicyAssertion::Password)
+    }
+
+    /// Adds a `PolicyLocality` assertion.
+    pub fn locality(self, locality: LocalityAttributes) -> Self {
+        self.with_assertion(PolicyAssertion::Locality(locality))
+    }
+
+    /// Adds a `PolicyPhysicalPresence` assertion.
+    pub fn physical_presence(self) -> Self {
+        self.with_assertion(PolicyAssertion::PhysicalPresence)
+    }
+
+    /// Adds a `PolicyCpHash` assertion.
+    pub fn cp_hash(self, cp_hash_a: Digest) -> Self {
+        self.with_assertion(PolicyAssertion::CpHash(cp_hash_a))
+    }
+
+    /// Adds a `PolicyNameHash` assertion.
+    pub fn name_hash(self, name_hash: Digest) -> Self {
+        self.with_assertion(PolicyAssertion::NameHash(name_hash))
+    }
+
+    /// Adds a `PolicyNvWritten` assertion.
+    pub fn nv_written(self, written_set: bool) -> Self {
+        self.with_assertion(PolicyAssertion::NvWritten(written_set))
+    }
+
+    /// Adds a `PolicyCounterTimer` assertion.
+    pub fn counter_timer(self, condition: CounterTimerCondition) -> Self {
+        self.with_assertion(PolicyAssertion::CounterTimer(condition))
+    }
+
+    /// Adds a `PolicyTemplate` assertion.
+    pub fn template(self, template_hash: Digest) -> Self {
+        self.with_assertion(PolicyAssertion::Template(template_hash))
+    }
+
+    /// Adds a `PolicyDuplicationSelect` assertion.
+    pub fn duplication_select(
+        self,
+        object_name: Name,
+        new_parent_name: Name,
+        include_object: bool,
+    ) -> Self {
+        self.with_assertion(PolicyAssertion::DuplicationSelect {
+            object_name,
+            new_parent_name,
+            include_object,
+        })
+    }
+
+    /// Adds a `PolicySigned` assertion.
+    ///
+    /// # Details
+    /// When the policy is executed, the signature of the authority is
+    /// requested with [PolicyResolver::sign_authorization].
+    pub fn signed(self, auth_name: Name, policy_ref: Nonce) -> Self {
+        self.with_assertion(PolicyAssertion::Signed {
+            auth_name,
+            policy_ref,
+        })
+    }
+
+    /// Adds a `PolicySecret` assertion for an object.
+    ///
+    /// # Details
+    /// When the policy is executed, the handle of the object is requested
+    /// with [PolicyResolver::secret_auth_handle].
+    pub fn secret(self, auth_name: Name, policy_ref: Nonce) -> Self {
+        self.with_assertion(PolicyAssertion::Secret {
+            entity: SecretEntity::Name(auth_name),
+            policy_ref,
+        })
+    }
+
+    /// Adds a `PolicySecret` assertion for an NV index.
+    ///
+    /// # Details
+    /// When the policy is executed, the authorization value of the NV index
+    /// is requested with [PolicyResolver::nv_index_auth_value].
+    pub fn secret_nv_index(
+        self,
+        nv_index: NvIndexTpmHandle,
+        nv_index_name: Name,
+        policy_ref: Nonce,
+    ) -> Self {
+        self.with_assertion(PolicyAssertion::Secret {
+            entity: SecretEntity::NvIndex {
+                nv_index,
+                nv_index_name,
+            },
+            policy_ref,
+        })
+    }
+
+    /// Adds a `PolicySecret` assertion for a hierarchy.
+    ///
+    /// # Details
+    /// When the policy is executed, the sessions of the context are used to
+    /// authorize the hierarchy, so its authorization value must have been set
+    /// with [Context::tr_set_auth].
+    pub fn secret_hierarchy(self, hierarchy: HierarchyAuth, policy_ref: Nonce) -> Self {
+        self.with_assertion(PolicyAssertion::Secret {
+            entity: SecretEntity::Hierarchy(hierarchy),
+            policy_ref,
+        })
+    }
+
+    /// Adds a `PolicyAuthorize` assertion.
+    ///
+    /// # Details
+    /// The TPM replaces the policy digest of the session when the assertion
+    /// is executed, so the assertion is usually the first one of the policy.
+    /// When the policy is executed, the approved policy and its signature are
+    /// requested with [PolicyResolver::approved_policy], and the approved policy
+    /// is executed before the assertion.
+    pub fn authorize(self, key_sign: Name, policy_ref: Nonce) -> Self {
+        self.with_assertion(PolicyAssertion::Authorize {
+            key_sign,
+            policy_ref,
+        })
+    }
+
+    /// Adds a `PolicyAuthorizeNV` assertion.
+    ///
+    /// # Details
+    /// The name of the NV index is part of the policy digest, so it must be
+    /// the name of the NV index after the approved policy digest has been
+    /// written to it. When the policy is executed, the approved policy digest
+    /// is read from the NV index and the approved policy is requested with
+    /// [PolicyResolver::nv_approved_policy], and executed before the assertion.
+    pub fn authorize_nv(self, nv_index: NvIndexTpmHandle, nv_index_name: Name) -> Self {
+        self.with_assertion(PolicyAssertion::AuthorizeNv {
+            nv_index,
+            nv_index_name,
+        })
+    }
+
+    /// Combines the policy with another branch in a `PolicyOR` assertion.
+    ///
+    /// # Details
+    /// The resulting policy is satisfied if either the assertions added so far
+    /// or the assertions of `branch` are satisfied. If the policy only consists
+    /// of a `PolicyOR` assertion, `branch` is added to its branches, so
+    /// `a.or(b).or(c)` results in a single assertion with three branches.
+    ///
+    /// A `PolicyOR` assertion has at most eight branches, and the branches must
+    /// use the hashing algorithm of the policy, otherwise an error is returned
+    /// when the policy digest is computed or when the policy is executed.
+    pub fn or(mut self, branch: Policy) -> Self {
+        if let [PolicyAssertion::Or(branches)] = self.assertions.as_mut_slice() {
+            branches.push(branch);
+            return self;
+        }
+        let hashing_algorithm = self.hashing_algorithm;
+        Policy::new(hashing_algorithm).with_assertion(PolicyAssertion::Or(vec![self, branch]))
+    }
+
+    /// Computes the policy digest of the policy.
+    ///
+    /// # Details
+    /// The digest is computed in software with a [Calculator], and is the
+    /// digest of a policy session after the policy has been executed.
+    ///
+    /// # Errors
+    /// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+    ///   error is returned
+    /// * if a `PolicyOR` assertion has too few or too many branches, or is not the
+    ///   first assertion of the policy, an `InvalidParam` wrapper error is returned
+    /// * if a branch does not use the hashing algorithm of the policy, an
+    ///   `InconsistentParams` wrapper error is returned
+    /// * if the size of a digest in an assertion does not match the hashing
+    ///   algorithm, a `WrongParamSize` wrapper error is returned
+    pub fn digest(&self) -> Result<Digest> {
+        let mut calculator = Calculator::new(self.hashing_algorithm)?;
+        for (index, assertion) in self.assertions.iter().enumerate() {
+            let _ = match assertion {
+                PolicyAssertion::Pcr {
+                    pcr_selection_list,
+                    pcr_policy_digest,
+                } => calculator.policy_pcr(pcr_policy_digest, pcr_selection_list)?,
+                PolicyAssertion::CommandCode(code) => calculator.policy_command_code(*code)?,
+                PolicyAssertion::AuthValue => calculator.policy_auth_value()?,
+                PolicyAssertion::Password => calculator.policy_password()?,
+                PolicyAssertion::Locality(locality) => calculator.policy_locality(*locality)?,
+                PolicyAssertion::PhysicalPresence => calculator.policy_physical_presence()?,
+                PolicyAssertion::CpHash(cp_hash_a) => calculator.policy_cp_hash(cp_hash_a)?,
+                PolicyAssertion::NameHash(name_hash) => calculator.policy_name_hash(name_hash)?,
+                PolicyAssertion::NvWritten(written_set) => {
+                    calculator.policy_nv_written(*written_set)?
+                }
+                PolicyAssertion::CounterTimer(condition) => {
+                    calculator.policy_counter_timer(condition)?
+                }
+                PolicyAssertion::Template(template_hash) => {
+                    calculator.policy_template(template_hash)?
+                }
+                PolicyAssertion::DuplicationSelect {
+                    object_name,
+                    new_parent_name,
+                    include_object,
+                } => calculator.policy_duplication_select(
+                    object_name,
+                    new_parent_name,
+                    *include_object,
+                )?,
+                PolicyAssertion::Signed {
+                    auth_name,
+                    policy_ref,
+                } => calculator.policy_signed(auth_name, policy_ref)?,
+                PolicyAssertion::Secret { entity, policy_ref } => {
+                    calculator.policy_secret(&entity.name()?, policy_ref)?
+                }
+                PolicyAssertion::Authorize {
+                    key_sign,
+                    policy_ref,
+                } => calculator.policy_authorize(key_sign, policy_ref)?,
+                PolicyAssertion::AuthorizeNv { nv_index_name, .. } => {
+                    calculator.policy_authorize_nv(nv_index_name)?
+                }
+                PolicyAssertion::Or(branches) => {
+                    check_or_position(index)?;
+                    calculator.policy_or(&self.branch_digests(branches)?)?
+                }
+            };
+        }
+        calculator.policy_digest()
+    }
+
+    /// Executes the policy in a policy session.
+    ///
+    /// # Details
+    /// The assertions are executed in order. For a `PolicyOR` assertion, the
+    /// branches are tried in order until the assertions of one of them can be
+    /// executed, restarting the session with
+    /// [Context::policy_restart] and executing the previous assertions again
+    /// before trying the next branch. A branch is only rejected if the TPM
+    /// returns an error when one of its assertions is executed, e.g. because
+    /// the PCR do not have the expected values. The assertions that are only
+    /// checked when the session is used, e.g. `PolicyCommandCode`, do not cause
+    /// a branch to be rejected.
+    ///
+    /// The assertions are executed without the sessions of the context. After
+    /// the execution, the session can be used to authorize the guarded command.
+    ///
+    /// The policy must not contain assertions with references, see
+    /// [Policy::execute_with_resolver].
+    ///
+    /// # Errors
+    /// * if none of the combinations of branches can be executed, the error
+    ///   returned when executing the last one is returned
+    pub fn execute(&self, context: &mut Context, policy_session: PolicySession) -> Result<()> {
+        self.execute_with_resolver(context, policy_session, &mut NoResolver)
+    }
+
+    /// Executes the policy in a policy session, resolving the references of
+    /// the assertions with `resolver`.
+    ///
+    /// # Details
+    /// The policy is executed as with [Policy::execute], apart from the
+    /// assertions with references:
+    /// * for a `PolicySigned` assertion, the signature of the authority is
+    ///   requested from the resolver. The authorization does not expire and
+    ///   is not bound to the session nor to a command.
+    /// * for a `PolicySecret` assertion, the sessions of the context are used
+    ///   to authorize the entity. The handle of an object, or the authorization
+    ///   value of an NV index, is requested from the resolver. The authorization
+    ///   does not expire and is not bound to the session nor to a command.
+    /// * for a `PolicyAuthorize` assertion, the approved policy is requested
+    ///   from the resolver and executed, and then authorized with
+    ///   [authorize](super::authorize).
+    /// * for a `PolicyAuthorizeNV` assertion, the approved policy digest is
+    ///   read from the NV index, the approved policy is requested from the
+    ///   resolver and executed, and then authorized with
+    ///   [Context::policy_authorize_nv]. The sessions of the context are used
+    ///   to authorize the reading of the NV index.
+    ///
+    /// # Errors
+    /// * if a reference cannot be resolved, the error returned by the resolver
+    ///   is returned
+    /// * if none of the combinations of branches can be executed, the error
+    ///   returned when executing the last one is returned
+    pub fn execute_with_resolver(
+        &self,
+        context: &mut Context,
+        policy_session: PolicySession,
+        resolver: &mut dyn PolicyResolver,
+    ) -> Result<()> {
+        let paths = self.paths()?;
+        let path_count = paths.len();
+        let mut result = Ok(());
+        for (index, path) in paths.iter().enumerate() {
+            result = path.iter().try_for_each(|step| {
+                step.execute(context, policy_session, self.hashing_algorithm, resolver)
+            });
+            if result.is_ok() || index + 1 == path_count {
+                break;
+            }
+            context.execute_without_session(|ctx| ctx.policy_restart(policy_session))?;
+        }
+        result
+    }
+
+    /// Private method for adding an assertion.
+    fn with_assertion(mut self, assertion: PolicyAssertion) -> Self {
+        self.assertions.push(assertion);
+        self
+    }
+
+    /// Private method for computing the digests of the branches of a
+    /// `PolicyOR` assertion.
+    fn branch_digests(&self, branches: &[Policy]) -> Result<DigestList> {
+        if branches.len() > DigestList::MAX_SIZE {
+            error!(
+                "Too many branches in a PolicyOR assertion (> {})",
+                DigestList::MAX_SIZE
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let mut digest_list = DigestList::new();
+        for branch in branches {
+            if branch.hashing_algorithm != self.hashing_algorithm {
+                error!(
+                    "The hashing algorithm of a branch ({:?}) does not match the one of the policy ({:?})",
+                    branch.hashing_algorithm, self.hashing_algorithm
+                );
+                return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+            }
+            digest_list.add(branch.digest()?)?;
+        }
+        Ok(digest_list)
+    }
+
+    /// Private method returning the sequences of commands that satisfy
+    /// the policy, one for each combination of branches.
+    fn paths(&self) -> Result<Vec<Vec<Step<'_>>>> {
+        let mut paths: Vec<Vec<Step<'_>>> = vec![Vec::new()];
+        for (index, assertion) in self.assertions.iter().enumerate() {
+            let assertion_paths = match assertion {
+                PolicyAssertion::Or(branches) => {
+                    check_or_position(index)?;
+                    let digest_list = self.branch_digests(branches)?;
+                    let mut assertion_paths = Vec::new();
+                    for branch in branches {
+                        for mut branch_path in branch.paths()? {
+                            branch_path.push(Step::Or(digest_list.clone()));
+                            assertion_paths.push(branch_path);
+                        }
+                    }
+                    assertion_paths
+                }
+                _ => vec![vec![Step::Assertion(assertion)]],
+            };
+            paths = paths
+                .iter()
+                .flat_map(|path| {
+                    assertion_paths.iter().map(move |assertion_path| {
+                        path.iter().chain(assertion_path).cloned().collect()
+                    })
+                })
+                .collect();
+        }
+        Ok(paths)
+    }
+}
+
+/// A command executed to satisfy a policy.
+#[derive(Debug, Clone)]
+enum Step<'a> {
+    Assertion(&'a PolicyAssertion),
+    Or(DigestList),
+}
+
+impl Step<'_> {
+    fn execute(
+        &self,
+        context: &mut Context,
+        policy_session: PolicySession,
+        hashing_algorithm: HashingAlgorithm,
+        resolver: &mut dyn PolicyResolver,
+    ) -> Result<()> {
+        let assertion = match self {
+            Step::Assertion(assertion) => assertion,
+            Step::Or(digest_list) => {
+                return context.execute_without_session(|ctx| {
+                    ctx.policy_or(policy_session, digest_list.clone())
+                })
+            }
+        };
+        match assertion {
+            PolicyAssertion::Signed {
+                auth_name,
+                policy_ref,
+            } => replay::execute_signed(context, policy_session, auth_name, policy_ref, resolver),
+            PolicyAssertion::Secret { entity, policy_ref } => {
+                replay::execute_secret(context, policy_session, entity, policy_ref, resolver)
+            }
+            PolicyAssertion::Authorize {
+                key_sign,
+                policy_ref,
+            } => replay::execute_authorize(context, policy_session, key_sign, policy_ref, resolver),
+            PolicyAssertion::AuthorizeNv { nv_index, .. } => replay::execute_authorize_nv(
+                context,
+                policy_session,
+                hashing_algorithm,
+                *nv_index,
+                resolver,
+            ),
+            _ => context
+                .execute_without_session(|ctx| execute_assertion(ctx, policy_session, assertion)),
+        }
+    }
+}
+
+/// Executes an assertion without references.
+fn execute_assertion(
+    context: &mut Context,
+    policy_session: PolicySession,
+    assertion: &PolicyAssertion,
+) -> Result<()> {
+    match assertion {
+        PolicyAssertion::Pcr {
+            pcr_selection_list,
+            pcr_policy_digest,
+        } => context.policy_pcr(
+            policy_session,
+            pcr_policy_digest.clone(),
+            pcr_selection_list.clone(),
+        ),
+        PolicyAssertion::CommandCode(code) => context.policy_command_code(policy_session, *code),
+        PolicyAssertion::AuthValue => context.policy_auth_value(policy_session),
+        PolicyAssertion::Password => context.policy_password(policy_session),
+        PolicyAssertion::Locality(locality) => context.policy_locality(policy_session, *locality),
+        PolicyAssertion::PhysicalPresence => context.policy_physical_presence(policy_session),
+        PolicyAssertion::CpHash(cp_hash_a) => {
+            context.policy_cp_hash(policy_session, cp_hash_a.clone())
+        }
+        PolicyAssertion::NameHash(name_hash) => {
+            context.policy_name_hash(policy_session, name_hash.clone())
+        }
+        PolicyAssertion::NvWritten(written_set) => {
+            context.policy_nv_written(policy_session, *written_set)
+        }
+        PolicyAssertion::CounterTimer(condition) => {
+            context.policy_counter_timer(policy_session, condition.clone())
+        }
+        PolicyAssertion::Template(template_hash) => {
+            context.policy_template(policy_session, template_hash.clone())
+        }
+        PolicyAssertion::DuplicationSelect {
+            object_name,
+            new_parent_name,
+            include_object,
+        } => context.policy_duplication_select(
+            policy_session,
+            object_name.clone(),
+            new_parent_name.clone(),
+            *include_object,
+        ),
+        PolicyAssertion::Signed { .. }
+        | PolicyAssertion::Secret { .. }
+        | PolicyAssertion::Authorize { .. }
+        | PolicyAssertion::AuthorizeNv { .. } => {
+            error!("An assertion with references must be executed with a resolver");
+            Err(Error::local_error(WrapperErrorKind::InternalError))
+        }
+        PolicyAssertion::Or(_) => {
+            error!("A PolicyOR assertion must be executed with its branches");
+            Err(Error::local_error(WrapperErrorKind::InternalError))
+        }
+    }
+}
+
+/// Checks that a `PolicyOR` assertion is the first assertion of a policy.
+///
+/// # Details
+/// The TPM only accepts a `PolicyOR` assertion if the policy digest of the
+/// session is the digest of one of the branches, so the assertions before
+/// it must be part of the branches.
+fn check_or_position(index: usize) -> Result<()> {
+    if index > 0 {
+        error!("A PolicyOR assertion must be the first assertion of the policy");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    Ok(())
+}
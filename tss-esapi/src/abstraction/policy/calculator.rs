@@ -0,0 +1,349 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    abstraction::outer_wrapper::{hash_chunks, message_digest},
+    attributes::LocalityAttributes,
+    constants::CommandCode,
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{CounterTimerCondition, Digest, DigestList, Name, Nonce, PcrSelectionList},
+    traits::Marshall,
+    tss2_esys::{TPM2_CC, TPM2_EO, TPMA_LOCALITY},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// Computes policy digests in software
+///
+/// # Details
+/// The calculator updates a policy digest in the same way as the TPM
+/// does for a trial session, so the authorization policy of an object
+/// or an NV index can be computed without a TPM, e.g. by an attestation
+/// server or a sealing tool. Each method corresponds to the policy
+/// command of the [Context](crate::Context) with the same name, and
+/// the assertions must be added in the order in which they are later
+/// executed in the policy session.
+///
+/// The computations are done in software, so the calculator is only
+/// available when the `openssl` feature is enabled.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{
+/// #     abstraction::policy::Calculator, constants::CommandCode,
+/// #     interface_types::algorithm::HashingAlgorithm,
+/// # };
+/// let mut calculator = Calculator::new(HashingAlgorithm::Sha256)?;
+/// calculator
+///     .policy_auth_value()?
+///     .policy_command_code(CommandCode::Unseal)?;
+/// let policy_digest = calculator.policy_digest()?;
+/// # Ok::<(), tss_esapi::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calculator {
+    hashing_algorithm: HashingAlgorithm,
+    policy_digest: Vec<u8>,
+}
+
+impl Calculator {
+    /// Creates a new calculator for a policy session using `hashing_algorithm`.
+    ///
+    /// # Details
+    /// The calculator starts with the initial policy digest (all zeros).
+    ///
+    /// # Errors
+    /// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+    ///   error is returned
+    pub fn new(hashing_algorithm: HashingAlgorithm) -> Result<Self> {
+        let digest_size = message_digest(hashing_algorithm)?.size();
+        Ok(Calculator {
+            hashing_algorithm,
+            policy_digest: vec![0u8; digest_size],
+        })
+    }
+
+    /// Creates a new calculator continuing from `policy_digest`.
+    ///
+    /// # Errors
+    /// * if the hashing algorithm is not supported, an `UnsupportedParam` wrapper
+    ///   error is returned
+    /// * if the size of `policy_digest` does not match the hashing algorithm, a
+    ///   `WrongParamSize` wrapper error is returned
+    pub fn with_policy_digest(
+        hashing_algorithm: HashingAlgorithm,
+        policy_digest: &Digest,
+    ) -> Result<Self> {
+        let calculator = Calculator::new(hashing_algorithm)?;
+        calculator.check_digest_size(policy_digest)?;
+        Ok(Calculator {
+            hashing_algorithm,
+            policy_digest: policy_digest.as_bytes().to_vec(),
+        })
+    }
+
+    /// Returns the hashing algorithm of the policy.
+    pub const fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.hashing_algorithm
+    }
+
+    /// Returns the current policy digest.
+    pub fn policy_digest(&self) -> Result<Digest> {
+        Digest::from_bytes(&self.policy_digest)
+    }
+
+    /// Computes the policy digest of a `PolicySigned` assertion.
+    ///
+    /// # Arguments
+    /// * `auth_name` - The name of the key used to sign the authorization.
+    /// * `policy_ref` - The policy qualifier.
+    pub fn policy_signed(&mut self, auth_name: &Name, policy_ref: &Nonce) -> Result<&mut Self> {
+        self.policy_update(CommandCode::PolicySigned, auth_name, policy_ref)
+    }
+
+    /// Computes the policy digest of a `PolicySecret` assertion.
+    ///
+    /// # Arguments
+    /// * `auth_name` - The name of the entity whose authorization is provided.
+    /// * `policy_ref` - The policy qualifier.
+    pub fn policy_secret(&mut self, auth_name: &Name, policy_ref: &Nonce) -> Result<&mut Self> {
+        self.policy_update(CommandCode::PolicySecret, auth_name, policy_ref)
+    }
+
+    /// Computes the policy digest of a `PolicyOR` assertion.
+    ///
+    /// # Details
+    /// The new policy digest only depends on the digests of the branches, which
+    /// can be computed with other calculators using the same hashing algorithm.
+    ///
+    /// # Errors
+    /// * if the list does not contain at least two digests, an `InvalidParam`
+    ///   wrapper error is returned
+    /// * if the size of one of the digests does not match the hashing algorithm,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_or(&mut self, digest_list: &DigestList) -> Result<&mut Self> {
+        if digest_list.len() < 2 {
+            error!(
+                "Invalid number of digests in the list ({} < 2)",
+                digest_list.len()
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let mut digests = Vec::new();
+        for digest in digest_list.value() {
+            self.check_digest_size(digest)?;
+            digests.extend_from_slice(digest.as_bytes());
+        }
+        self.policy_digest = vec![0u8; self.policy_digest.len()];
+        self.extend(CommandCode::PolicyOr, &[&digests])
+    }
+
+    /// Computes the policy digest of a `PolicyPCR` assertion.
+    ///
+    /// # Arguments
+    /// * `pcr_policy_digest` - The digest of the expected values of the PCR in
+    ///                         the selection, computed with the hashing algorithm
+    ///                         of the policy.
+    /// * `pcr_selection_list` - The PCR that are included in the assertion.
+    ///
+    /// # Details
+    /// The digest of the values can be computed with
+    /// [PcrSnapshot::composite_digest](crate::abstraction::pcr::PcrSnapshot::composite_digest).
+    ///
+    /// # Errors
+    /// * if the size of `pcr_policy_digest` does not match the hashing algorithm,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_pcr(
+        &mut self,
+        pcr_policy_digest: &Digest,
+        pcr_selection_list: &PcrSelectionList,
+    ) -> Result<&mut Self> {
+        self.check_digest_size(pcr_policy_digest)?;
+        self.extend(
+            CommandCode::PolicyPcr,
+            &[
+                &pcr_selection_list.marshall()?,
+                pcr_policy_digest.as_bytes(),
+            ],
+        )
+    }
+
+    /// Computes the policy digest of a `PolicyLocality` assertion.
+    pub fn policy_locality(&mut self, locality: LocalityAttributes) -> Result<&mut Self> {
+        self.extend(
+            CommandCode::PolicyLocality,
+            &[&TPMA_LOCALITY::from(locality).to_be_bytes()],
+        )
+    }
+
+    /// Computes the policy digest of a `PolicyCounterTimer` assertion.
+    pub fn policy_counter_timer(&mut self, condition: &CounterTimerCondition) -> Result<&mut Self> {
+        let args = hash_chunks(
+            self.hashing_algorithm,
+            &[
+                condition.operand_b().as_bytes(),
+                &condition.offset().to_be_bytes(),
+                &TPM2_EO::from(condition.operation()).to_be_bytes(),
+            ],
+        )?;
+        self.extend(CommandCode::PolicyCounterTimer, &[&args])
+    }
+
+    /// Computes the policy digest of a `PolicyCommandCode` assertion.
+    pub fn policy_command_code(&mut self, code: CommandCode) -> Result<&mut Self> {
+        self.extend(
+            CommandCode::PolicyCommandCode,
+            &[&TPM2_CC::from(code).to_be_bytes()],
+        )
+    }
+
+    /// Computes the policy digest of a `PolicyPhysicalPresence` assertion.
+    pub fn policy_physical_presence(&mut self) -> Result<&mut Self> {
+        self.extend(CommandCode::PolicyPhysicalPresence, &[])
+    }
+
+    /// Computes the policy digest of a `PolicyCpHash` assertion.
+    ///
+    /// # Errors
+    /// * if the size of `cp_hash_a` does not match the hashing algorithm,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_cp_hash(&mut self, cp_hash_a: &Digest) -> Result<&mut Self> {
+        self.check_digest_size(cp_hash_a)?;
+        self.extend(CommandCode::PolicyCpHash, &[cp_hash_a.as_bytes()])
+    }
+
+    /// Computes the policy digest of a `PolicyNameHash` assertion.
+    ///
+    /// # Errors
+    /// * if the size of `name_hash` does not match the hashing algorithm,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_name_hash(&mut self, name_hash: &Digest) -> Result<&mut Self> {
+        self.check_digest_size(name_hash)?;
+        self.extend(CommandCode::PolicyNameHash, &[name_hash.as_bytes()])
+    }
+
+    /// Computes the policy digest of a `PolicyDuplicationSelect` assertion.
+    ///
+    /// # Details
+    /// The name of the object is only included in the policy digest if
+    /// `include_object` is set.
+    pub fn policy_duplication_select(
+        &mut self,
+        object_name: &Name,
+        new_parent_name: &Name,
+        include_object: bool,
+    ) -> Result<&mut Self> {
+        let object_name = if include_object {
+            object_name.value()
+        } else {
+            &[]
+        };
+        self.extend(
+            CommandCode::PolicyDuplicationSelect,
+            &[
+                object_name,
+                new_parent_name.value(),
+                &[u8::from(include_object)],
+            ],
+        )
+    }
+
+    /// Computes the policy digest of a `PolicyAuthorize` assertion.
+    ///
+    /// # Arguments
+    /// * `key_sign` - The name of the key used to sign the approved policies.
+    /// * `policy_ref` - The policy qualifier.
+    ///
+    /// # Details
+    /// The resulting policy digest does not depend on the previous assertions,
+    /// which are replaced by the approved policy when the policy is executed.
+    pub fn policy_authorize(&mut self, key_sign: &Name, policy_ref: &Nonce) -> Result<&mut Self> {
+        self.policy_digest = vec![0u8; self.policy_digest.len()];
+        self.policy_update(CommandCode::PolicyAuthorize, key_sign, policy_ref)
+    }
+
+    /// Computes the policy digest of a `PolicyAuthValue` assertion.
+    pub fn policy_auth_value(&mut self) -> Result<&mut Self> {
+        self.extend(CommandCode::PolicyAuthValue, &[])
+    }
+
+    /// Computes the policy digest of a `PolicyPassword` assertion.
+    ///
+    /// # Details
+    /// The policy digest is the same as the one of a `PolicyAuthValue`
+    /// assertion, the two assertions only differ in the way the
+    /// authorization value is provided when the policy is executed.
+    pub fn policy_password(&mut self) -> Result<&mut Self> {
+        self.extend(CommandCode::PolicyAuthValue, &[])
+    }
+
+    /// Computes the policy digest of a `PolicyNvWritten` assertion.
+    pub fn policy_nv_written(&mut self, written_set: bool) -> Result<&mut Self> {
+        self.extend(CommandCode::PolicyNvWritten, &[&[u8::from(written_set)]])
+    }
+
+    /// Computes the policy digest of a `PolicyTemplate` assertion.
+    ///
+    /// # Errors
+    /// * if the size of `template_hash` does not match the hashing algorithm,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn policy_template(&mut self, template_hash: &Digest) -> Result<&mut Self> {
+        self.check_digest_size(template_hash)?;
+        self.extend(CommandCode::PolicyTemplate, &[template_hash.as_bytes()])
+    }
+
+    /// Computes the policy digest of a `PolicyAuthorizeNV` assertion.
+    ///
+    /// # Arguments
+    /// * `nv_index_name` - The name of the NV index holding the approved policy.
+    ///
+    /// # Details
+    /// The resulting policy digest does not depend on the previous assertions,
+    /// which are replaced by the approved policy when the policy is executed.
+    pub fn policy_authorize_nv(&mut self, nv_index_name: &Name) -> Result<&mut Self> {
+        self.policy_digest = vec![0u8; self.policy_digest.len()];
+        self.extend(CommandCode::PolicyAuthorizeNv, &[nv_index_name.value()])
+    }
+
+    /// Private method implementing the `PolicyUpdate()` function of the
+    /// specification, used by the assertions that are qualified with a
+    /// policy reference.
+    fn policy_update(
+        &mut self,
+        command_code: CommandCode,
+        name: &Name,
+        policy_ref: &Nonce,
+    ) -> Result<&mut Self> {
+        let _ = self.extend(command_code, &[name.value()])?;
+        self.policy_digest = hash_chunks(
+            self.hashing_algorithm,
+            &[self.policy_digest.as_slice(), policy_ref.as_bytes()],
+        )?;
+        Ok(self)
+    }
+
+    /// Private method for extending the policy digest with a command code
+    /// followed by the command specific data.
+    fn extend(&mut self, command_code: CommandCode, data: &[&[u8]]) -> Result<&mut Self> {
+        let command_code = TPM2_CC::from(command_code).to_be_bytes();
+        let mut chunks: Vec<&[u8]> = vec![self.policy_digest.as_slice(), &command_code[..]];
+        chunks.extend_from_slice(data);
+        self.policy_digest = hash_chunks(self.hashing_algorithm, &chunks)?;
+        Ok(self)
+    }
+
+    /// Private method for checking that a digest has the size of the
+    /// digests of the hashing algorithm of the policy.
+    fn check_digest_size(&self, digest: &Digest) -> Result<()> {
+        if digest.len() != self.policy_digest.len() {
+            error!(
+                "Invalid digest size for the {:?} policy ({} != {})",
+                self.hashing_algorithm,
+                digest.len(),
+                self.policy_digest.len()
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,549 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{serialized::deserialize_counter_timer_condition, Policy, PolicyAssertion};
+use crate::{
+    abstraction::outer_wrapper::message_digest,
+    attributes::LocalityAttributes,
+    constants::{
+        tss::{
+            TPM2_CC_FIRST, TPM2_CC_LAST, TPM2_EO_BITCLEAR, TPM2_EO_BITSET, TPM2_EO_EQ, TPM2_EO_NEQ,
+            TPM2_EO_SIGNED_GE, TPM2_EO_SIGNED_GT, TPM2_EO_SIGNED_LE, TPM2_EO_SIGNED_LT,
+            TPM2_EO_UNSIGNED_GE, TPM2_EO_UNSIGNED_GT, TPM2_EO_UNSIGNED_LE, TPM2_EO_UNSIGNED_LT,
+            TPMA_LOCALITY_TPM2_LOC_FOUR, TPMA_LOCALITY_TPM2_LOC_ONE, TPMA_LOCALITY_TPM2_LOC_THREE,
+            TPMA_LOCALITY_TPM2_LOC_TWO, TPMA_LOCALITY_TPM2_LOC_ZERO,
+        },
+        CommandCode,
+    },
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{Digest, Name, Nonce, PcrSelectionList, PcrSelectionListBuilder, PcrSlot},
+    tss2_esys::{TPM2_CC, TPM2_EO, TPMA_LOCALITY},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use num_traits::FromPrimitive;
+use serde::Deserialize;
+use std::convert::TryFrom;
+
+/// A policy in the JSON policy language of the TSS Feature API (FAPI)
+///
+/// # Details
+/// The policy is deserialized from the JSON documents used by the FAPI
+/// tooling of tpm2-tss, e.g. with `serde_json`, and converted to a [Policy]
+/// with [FapiPolicy::to_policy]. The policy can then be executed through
+/// ESAPI, with [Policy::execute_with_resolver] or [replay](super::replay),
+/// the `PolicySigned`, `PolicySecret` and `PolicyAuthorize` elements being
+/// resolved by the [PolicyResolver](super::PolicyResolver).
+///
+/// The following policy elements are supported: `POLICYPCR` (with `pcrs`),
+/// `POLICYCOMMANDCODE`, `POLICYAUTHVALUE`, `POLICYPASSWORD`, `POLICYLOCALITY`,
+/// `POLICYPHYSICALPRESENCE`, `POLICYCPHASH` (with `cpHash`), `POLICYNAMEHASH`
+/// (with `nameHash`), `POLICYNVWRITTEN`, `POLICYCOUNTERTIMER`, `POLICYTEMPLATE`
+/// (with `templateHash`), `POLICYDUPLICATIONSELECT` (with `objectName` and
+/// `newParentName`), `POLICYOR`, `POLICYSIGNED` and `POLICYAUTHORIZE` (with
+/// `keyName`) and `POLICYSECRET` (with `objectName`). The elements referring
+/// to keys or objects by their FAPI path, their PEM encoding or their public
+/// area, as well as the elements referring to the current PCR values, need the
+/// FAPI key store and cannot be converted.
+///
+/// The policy is only available when the `openssl` feature is enabled.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{
+/// #     abstraction::policy::FapiPolicy,
+/// #     interface_types::algorithm::HashingAlgorithm,
+/// # };
+/// let json = r#"{
+///     "description": "Unseal with the authorization value",
+///     "policy": [
+///         { "type": "POLICYAUTHVALUE" },
+///         { "type": "POLICYCOMMANDCODE", "code": "UNSEAL" }
+///     ]
+/// }"#;
+/// let fapi_policy: FapiPolicy = serde_json::from_str(json).expect("Invalid policy");
+/// let policy = fapi_policy.to_policy(HashingAlgorithm::Sha256)?;
+/// let policy_digest = policy.digest()?;
+/// # Ok::<(), tss_esapi::Error>(())
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FapiPolicy {
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    policy_digests: Vec<FapiDigestValue>,
+    policy: Vec<FapiPolicyElement>,
+}
+
+impl FapiPolicy {
+    /// Returns the description of the policy.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Converts the policy to a [Policy] for a policy session using
+    /// `hashing_algorithm`.
+    ///
+    /// # Details
+    /// If the FAPI policy holds a policy digest for `hashing_algorithm`
+    /// (`policyDigests`), the digest of the converted policy is checked
+    /// against it.
+    ///
+    /// # Errors
+    /// * if an element, or a value in an element, is not supported, an
+    ///   `UnsupportedParam` wrapper error is returned
+    /// * if a value in an element is not valid, an `InvalidParam` wrapper
+    ///   error is returned
+    /// * if the digest of the converted policy does not match the policy
+    ///   digest of the FAPI policy, an `InconsistentParams` wrapper error
+    ///   is returned
+    pub fn to_policy(&self, hashing_algorithm: HashingAlgorithm) -> Result<Policy> {
+        let policy = policy_from_elements(hashing_algorithm, &self.policy)?;
+        for policy_digest in &self.policy_digests {
+            if hashing_algorithm_from_name(&policy_digest.hash_alg)? != hashing_algorithm {
+                continue;
+            }
+            if Digest::try_from(bytes_from_hex(&policy_digest.digest)?)? != policy.digest()? {
+                error!("The digest of the policy does not match the digest of the FAPI policy");
+                return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+            }
+        }
+        Ok(policy)
+    }
+}
+
+/// A policy digest of a FAPI policy.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FapiDigestValue {
+    hash_alg: String,
+    digest: String,
+}
+
+/// A PCR value of a `POLICYPCR` element.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FapiPcrValue {
+    pcr: u32,
+    hash_alg: String,
+    digest: String,
+}
+
+/// A branch of a `POLICYOR` element.
+#[derive(Debug, Clone, Deserialize)]
+struct FapiBranch {
+    policy: Vec<FapiPolicyElement>,
+}
+
+/// A `TPMI_YES_NO` value, as a boolean or as `"YES"` or `"NO"`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FapiYesNo {
+    Bool(bool),
+    Text(String),
+}
+
+/// A `TPM2_CC` value, as a number or as a name.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FapiCommandCode {
+    Value(TPM2_CC),
+    Name(String),
+}
+
+/// A `TPMA_LOCALITY` value, as a number or as a list of localities.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FapiLocality {
+    Value(TPMA_LOCALITY),
+    Names(Vec<String>),
+}
+
+/// An element of a FAPI policy.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type")]
+enum FapiPolicyElement {
+    #[serde(rename = "POLICYPCR", alias = "PolicyPCR")]
+    Pcr { pcrs: Vec<FapiPcrValue> },
+    #[serde(rename = "POLICYCOMMANDCODE", alias = "PolicyCommandCode")]
+    CommandCode { code: FapiCommandCode },
+    #[serde(rename = "POLICYAUTHVALUE", alias = "PolicyAuthValue")]
+    AuthValue {},
+    #[serde(rename = "POLICYPASSWORD", alias = "PolicyPassword")]
+    Password {},
+    #[serde(rename = "POLICYLOCALITY", alias = "PolicyLocality")]
+    Locality { locality: FapiLocality },
+    #[serde(rename = "POLICYPHYSICALPRESENCE", alias = "PolicyPhysicalPresence")]
+    PhysicalPresence {},
+    #[serde(
+        rename = "POLICYCPHASH",
+        alias = "PolicyCpHash",
+        rename_all = "camelCase"
+    )]
+    CpHash { cp_hash: String },
+    #[serde(
+        rename = "POLICYNAMEHASH",
+        alias = "PolicyNameHash",
+        rename_all = "camelCase"
+    )]
+    NameHash { name_hash: String },
+    #[serde(
+        rename = "POLICYNVWRITTEN",
+        alias = "PolicyNvWritten",
+        rename_all = "camelCase"
+    )]
+    NvWritten { written_set: FapiYesNo },
+    #[serde(
+        rename = "POLICYCOUNTERTIMER",
+        alias = "PolicyCounterTimer",
+        rename_all = "camelCase"
+    )]
+    CounterTimer {
+        operand_b: String,
+        offset: u16,
+        operation: String,
+    },
+    #[serde(
+        rename = "POLICYTEMPLATE",
+        alias = "PolicyTemplate",
+        rename_all = "camelCase"
+    )]
+    Template { template_hash: String },
+    #[serde(
+        rename = "POLICYDUPLICATIONSELECT",
+        alias = "PolicyDuplicationSelect",
+        rename_all = "camelCase"
+    )]
+    DuplicationSelect {
+        #[serde(default)]
+        object_name: String,
+        new_parent_name: String,
+        include_object: Option<FapiYesNo>,
+    },
+    #[serde(rename = "POLICYOR", alias = "PolicyOR")]
+    Or { branches: Vec<FapiBranch> },
+    #[serde(
+        rename = "POLICYSIGNED",
+        alias = "PolicySigned",
+        rename_all = "camelCase"
+    )]
+    Signed {
+        key_name: Option<String>,
+        #[serde(default)]
+        policy_ref: String,
+    },
+    #[serde(
+        rename = "POLICYSECRET",
+        alias = "PolicySecret",
+        rename_all = "camelCase"
+    )]
+    Secret {
+        object_name: Option<String>,
+        #[serde(default)]
+        policy_ref: String,
+    },
+    #[serde(
+        rename = "POLICYAUTHORIZE",
+        alias = "PolicyAuthorize",
+        rename_all = "camelCase"
+    )]
+    Authorize {
+        key_name: Option<String>,
+        #[serde(default)]
+        policy_ref: String,
+    },
+}
+
+fn policy_from_elements(
+    hashing_algorithm: HashingAlgorithm,
+    elements: &[FapiPolicyElement],
+) -> Result<Policy> {
+    elements
+        .iter()
+        .try_fold(Policy::new(hashing_algorithm), add_element)
+}
+
+fn add_element(policy: Policy, element: &FapiPolicyElement) -> Result<Policy> {
+    Ok(match element {
+        FapiPolicyElement::Pcr { pcrs } => {
+            let (pcr_selection_list, pcr_policy_digest) =
+                pcr_assertion(policy.hashing_algorithm(), pcrs)?;
+            policy.pcr(pcr_selection_list, pcr_policy_digest)
+        }
+        FapiPolicyElement::CommandCode { code } => policy.command_code(command_code(code)?),
+        FapiPolicyElement::AuthValue {} => policy.auth_value(),
+        FapiPolicyElement::Password {} => policy.password(),
+        FapiPolicyElement::Locality { locality } => policy.locality(locality_attributes(locality)?),
+        FapiPolicyElement::PhysicalPresence {} => policy.physical_presence(),
+        FapiPolicyElement::CpHash { cp_hash } => {
+            policy.cp_hash(Digest::try_from(bytes_from_hex(cp_hash)?)?)
+        }
+        FapiPolicyElement::NameHash { name_hash } => {
+            policy.name_hash(Digest::try_from(bytes_from_hex(name_hash)?)?)
+        }
+        FapiPolicyElement::NvWritten { written_set } => policy.nv_written(yes_no(written_set)?),
+        FapiPolicyElement::CounterTimer {
+            operand_b,
+            offset,
+            operation,
+        } => policy.counter_timer(deserialize_counter_timer_condition(
+            bytes_from_hex(operand_b)?,
+            *offset,
+            arithmetic_operation(operation)?,
+        )?),
+        FapiPolicyElement::Template { template_hash } => {
+            policy.template(Digest::try_from(bytes_from_hex(template_hash)?)?)
+        }
+        FapiPolicyElement::DuplicationSelect {
+            object_name,
+            new_parent_name,
+            include_object,
+        } => policy.duplication_select(
+            Name::try_from(bytes_from_hex(object_name)?)?,
+            Name::try_from(bytes_from_hex(new_parent_name)?)?,
+            include_object
+                .as_ref()
+                .map(yes_no)
+                .transpose()?
+                .unwrap_or(false),
+        ),
+        FapiPolicyElement::Or { branches } => {
+            if branches.len() < 2 {
+                error!(
+                    "Invalid number of branches in a POLICYOR element ({} < 2)",
+                    branches.len()
+                );
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+            // The digests of the branches are computed from the digest of the
+            // previous elements, so the previous elements are part of each branch.
+            let hashing_algorithm = policy.hashing_algorithm();
+            let branches = branches
+                .iter()
+                .map(|branch| {
+                    let mut branch_policy =
+                        policy_from_elements(hashing_algorithm, &branch.policy)?;
+                    let mut assertions = policy.assertions.clone();
+                    assertions.append(&mut branch_policy.assertions);
+                    branch_policy.assertions = assertions;
+                    Ok(branch_policy)
+                })
+                .collect::<Result<Vec<Policy>>>()?;
+            let mut policy = Policy::new(hashing_algorithm);
+            policy.assertions.push(PolicyAssertion::Or(branches));
+            policy
+        }
+        FapiPolicyElement::Signed {
+            key_name,
+            policy_ref,
+        } => policy.signed(
+            referenced_name("POLICYSIGNED", key_name)?,
+            Nonce::try_from(bytes_from_hex(policy_ref)?)?,
+        ),
+        FapiPolicyElement::Secret {
+            object_name,
+            policy_ref,
+        } => policy.secret(
+            referenced_name("POLICYSECRET", object_name)?,
+            Nonce::try_from(bytes_from_hex(policy_ref)?)?,
+        ),
+        FapiPolicyElement::Authorize {
+            key_name,
+            policy_ref,
+        } => policy.authorize(
+            referenced_name("POLICYAUTHORIZE", key_name)?,
+            Nonce::try_from(bytes_from_hex(policy_ref)?)?,
+        ),
+    })
+}
+
+/// Computes the PCR selection and the PCR policy digest of a `POLICYPCR` element.
+fn pcr_assertion(
+    hashing_algorithm: HashingAlgorithm,
+    pcrs: &[FapiPcrValue],
+) -> Result<(PcrSelectionList, Digest)> {
+    if pcrs.is_empty() {
+        error!("A POLICYPCR element needs at least one PCR value");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    let pcr_values = pcrs
+        .iter()
+        .map(|pcr_value| {
+            let pcr_slot = 1u32
+                .checked_shl(pcr_value.pcr)
+                .ok_or_else(|| {
+                    error!("Invalid PCR index {}", pcr_value.pcr);
+                    Error::local_error(WrapperErrorKind::InvalidParam)
+                })
+                .and_then(PcrSlot::try_from)?;
+            Ok((
+                hashing_algorithm_from_name(&pcr_value.hash_alg)?,
+                pcr_slot,
+                bytes_from_hex(&pcr_value.digest)?,
+            ))
+        })
+        .collect::<Result<Vec<(HashingAlgorithm, PcrSlot, Vec<u8>)>>>()?;
+    let pcr_selection_list = pcr_values
+        .iter()
+        .fold(
+            PcrSelectionListBuilder::new(),
+            |builder, (bank, pcr_slot, _)| builder.with_selection(*bank, &[*pcr_slot]),
+        )
+        .build()?;
+
+    // The PCR values are concatenated in the order of the selection.
+    let mut data = Vec::new();
+    for pcr_selection in pcr_selection_list.get_selections() {
+        for pcr_slot in pcr_selection.selected() {
+            let (_, _, digest) = pcr_values
+                .iter()
+                .find(|(bank, slot, _)| {
+                    *bank == pcr_selection.hashing_algorithm() && *slot == pcr_slot
+                })
+                .ok_or_else(|| {
+                    error!("Missing value of the selected PCR {:?}", pcr_slot);
+                    Error::local_error(WrapperErrorKind::InternalError)
+                })?;
+            data.extend_from_slice(digest);
+        }
+    }
+    let pcr_policy_digest = openssl::hash::hash(message_digest(hashing_algorithm)?, &data)
+        .map_err(|e| {
+            error!("Failed to hash the PCR values: {}", e);
+            Error::local_error(WrapperErrorKind::InternalError)
+        })?;
+    Ok((pcr_selection_list, Digest::from_bytes(&pcr_policy_digest)?))
+}
+
+/// Returns the name referenced by an element, which must be given by value.
+fn referenced_name(element_type: &str, name: &Option<String>) -> Result<Name> {
+    let name = name.as_ref().ok_or_else(|| {
+        error!(
+            "Only {} elements referring to entities by name are supported",
+            element_type
+        );
+        Error::local_error(WrapperErrorKind::UnsupportedParam)
+    })?;
+    Name::try_from(bytes_from_hex(name)?)
+}
+
+/// Normalizes the name of a constant, ignoring the case, the underscores
+/// and the prefix of the TSS constants.
+fn normalized_name(name: &str, prefix: &str) -> String {
+    let name = name
+        .chars()
+        .filter(|c| *c != '_')
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>();
+    name.strip_prefix(prefix).unwrap_or(&name).to_string()
+}
+
+fn command_code(code: &FapiCommandCode) -> Result<CommandCode> {
+    match code {
+        FapiCommandCode::Value(value) => CommandCode::try_from(*value),
+        FapiCommandCode::Name(name) => {
+            let name = normalized_name(name, "TPM2CC");
+            (TPM2_CC_FIRST..=TPM2_CC_LAST)
+                .filter_map(CommandCode::from_u32)
+                .find(|command_code| normalized_name(&format!("{:?}", command_code), "") == name)
+                .ok_or_else(|| {
+                    error!("Unsupported command code {}", name);
+                    Error::local_error(WrapperErrorKind::UnsupportedParam)
+                })
+        }
+    }
+}
+
+fn hashing_algorithm_from_name(name: &str) -> Result<HashingAlgorithm> {
+    match normalized_name(name, "TPM2ALG").as_str() {
+        "SHA1" | "SHA" => Ok(HashingAlgorithm::Sha1),
+        "SHA256" => Ok(HashingAlgorithm::Sha256),
+        "SHA384" => Ok(HashingAlgorithm::Sha384),
+        "SHA512" => Ok(HashingAlgorithm::Sha512),
+        "SM3256" => Ok(HashingAlgorithm::Sm3_256),
+        "SHA3256" => Ok(HashingAlgorithm::Sha3_256),
+        "SHA3384" => Ok(HashingAlgorithm::Sha3_384),
+        "SHA3512" => Ok(HashingAlgorithm::Sha3_512),
+        _ => {
+            error!("Unsupported hashing algorithm {}", name);
+            Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+        }
+    }
+}
+
+fn arithmetic_operation(name: &str) -> Result<TPM2_EO> {
+    match normalized_name(name, "TPM2EO").as_str() {
+        "EQ" => Ok(TPM2_EO_EQ),
+        "NEQ" => Ok(TPM2_EO_NEQ),
+        "SIGNEDGT" => Ok(TPM2_EO_SIGNED_GT),
+        "UNSIGNEDGT" => Ok(TPM2_EO_UNSIGNED_GT),
+        "SIGNEDLT" => Ok(TPM2_EO_SIGNED_LT),
+        "UNSIGNEDLT" => Ok(TPM2_EO_UNSIGNED_LT),
+        "SIGNEDGE" => Ok(TPM2_EO_SIGNED_GE),
+        "UNSIGNEDGE" => Ok(TPM2_EO_UNSIGNED_GE),
+        "SIGNEDLE" => Ok(TPM2_EO_SIGNED_LE),
+        "UNSIGNEDLE" => Ok(TPM2_EO_UNSIGNED_LE),
+        "BITSET" => Ok(TPM2_EO_BITSET),
+        "BITCLEAR" => Ok(TPM2_EO_BITCLEAR),
+        _ => {
+            error!("Invalid arithmetic operation {}", name);
+            Err(Error::local_error(WrapperErrorKind::InvalidParam))
+        }
+    }
+}
+
+fn locality_attributes(locality: &FapiLocality) -> Result<LocalityAttributes> {
+    let value = match locality {
+        FapiLocality::Value(value) => *value,
+        FapiLocality::Names(names) => names.iter().try_fold(0, |value, name| {
+            let bit = match normalized_name(name, "TPM2LOC").as_str() {
+                "ZERO" => TPMA_LOCALITY_TPM2_LOC_ZERO,
+                "ONE" => TPMA_LOCALITY_TPM2_LOC_ONE,
+                "TWO" => TPMA_LOCALITY_TPM2_LOC_TWO,
+                "THREE" => TPMA_LOCALITY_TPM2_LOC_THREE,
+                "FOUR" => TPMA_LOCALITY_TPM2_LOC_FOUR,
+                _ => {
+                    error!("Invalid locality {}", name);
+                    return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+                }
+            };
+            Ok(value | bit)
+        })?,
+    };
+    Ok(LocalityAttributes::from(value))
+}
+
+fn yes_no(value: &FapiYesNo) -> Result<bool> {
+    match value {
+        FapiYesNo::Bool(value) => Ok(*value),
+        FapiYesNo::Text(text) => match normalized_name(text, "TPM2").as_str() {
+            "YES" => Ok(true),
+            "NO" => Ok(false),
+            _ => {
+                error!("Invalid TPMI_YES_NO value {}", text);
+                Err(Error::local_error(WrapperErrorKind::InvalidParam))
+            }
+        },
+    }
+}
+
+fn bytes_from_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex
+        .strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex);
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        error!("Invalid hexadecimal value {}", hex);
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| {
+                error!("Invalid hexadecimal value {}", hex);
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })
+        })
+        .collect()
+}
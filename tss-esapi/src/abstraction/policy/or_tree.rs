@@ -0,0 +1,154 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Calculator, Policy};
+use crate::{
+    interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+    structures::{Digest, DigestList},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// A tree of `PolicyOR` assertions combining any number of policies
+///
+/// # Details
+/// A `PolicyOR` assertion combines at most eight branches. The tree combines
+/// the leaf policies in groups of eight, then combines the digests of the
+/// groups in the same way, until a single digest, the root digest, remains.
+/// The root digest is the authorization policy that is satisfied by satisfying
+/// any of the leaf policies.
+///
+/// To satisfy the policy, only the selected leaf policy is executed, followed
+/// by the `PolicyOR` assertions on the path from the leaf to the root, each
+/// one with the digests of the group the path goes through.
+///
+/// The tree is only available when the `openssl` feature is enabled.
+#[derive(Debug, Clone)]
+pub struct PolicyOrTree {
+    hashing_algorithm: HashingAlgorithm,
+    leaves: Vec<Policy>,
+    // The digests of the nodes, level by level, starting with the leaves.
+    levels: Vec<Vec<Digest>>,
+}
+
+impl PolicyOrTree {
+    /// Creates the tree combining `leaves`.
+    ///
+    /// # Errors
+    /// * if `leaves` is empty, an `InvalidParam` wrapper error is returned
+    /// * if a leaf does not use `hashing_algorithm`, an `InconsistentParams`
+    ///   wrapper error is returned
+    /// * if the policy digest of a leaf cannot be computed, the error returned
+    ///   by [Policy::digest] is returned
+    pub fn new(hashing_algorithm: HashingAlgorithm, leaves: Vec<Policy>) -> Result<Self> {
+        if leaves.is_empty() {
+            error!("A policy tree needs at least one leaf");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let mut level = Vec::with_capacity(leaves.len());
+        for leaf in &leaves {
+            if leaf.hashing_algorithm() != hashing_algorithm {
+                error!(
+                    "The hashing algorithm of a leaf ({:?}) does not match the one of the tree ({:?})",
+                    leaf.hashing_algorithm(), hashing_algorithm
+                );
+                return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+            }
+            level.push(leaf.digest()?);
+        }
+
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            let next_level = level
+                .chunks(DigestList::MAX_SIZE)
+                .map(|group| match group {
+                    [digest] => Ok(digest.clone()),
+                    _ => Calculator::new(hashing_algorithm)?
+                        .policy_or(&digest_list(group)?)?
+                        .policy_digest(),
+                })
+                .collect::<Result<Vec<Digest>>>()?;
+            levels.push(level);
+            level = next_level;
+        }
+        levels.push(level);
+
+        Ok(PolicyOrTree {
+            hashing_algorithm,
+            leaves,
+            levels,
+        })
+    }
+
+    /// Returns the hashing algorithm of the tree.
+    pub const fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.hashing_algorithm
+    }
+
+    /// Returns the leaf policies of the tree.
+    pub fn leaves(&self) -> &[Policy] {
+        &self.leaves
+    }
+
+    /// Returns the root digest of the tree.
+    pub fn digest(&self) -> Digest {
+        // The last level always holds exactly one digest.
+        self.levels[self.levels.len() - 1][0].clone()
+    }
+
+    /// Executes the selected leaf policy in a policy session, followed by
+    /// the `PolicyOR` assertions on the path from the leaf to the root.
+    ///
+    /// # Arguments
+    /// * `policy_session` - The policy session.
+    /// * `leaf_index` - The index of the leaf in the policies given when the
+    ///                  tree was created.
+    ///
+    /// # Errors
+    /// * if the index does not refer to a leaf, an `InvalidParam` wrapper
+    ///   error is returned
+    pub fn execute(
+        &self,
+        context: &mut Context,
+        policy_session: PolicySession,
+        leaf_index: usize,
+    ) -> Result<()> {
+        let leaf = self.leaves.get(leaf_index).ok_or_else(|| {
+            error!(
+                "Invalid leaf index {} (>= {})",
+                leaf_index,
+                self.leaves.len()
+            );
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        leaf.execute(context, policy_session)?;
+
+        let mut index = leaf_index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let group_index = index / DigestList::MAX_SIZE;
+            let group = level
+                .chunks(DigestList::MAX_SIZE)
+                .nth(group_index)
+                .ok_or_else(|| {
+                    error!("Invalid node index {} in the policy tree", index);
+                    Error::local_error(WrapperErrorKind::InternalError)
+                })?;
+            if group.len() > 1 {
+                let digest_list = digest_list(group)?;
+                context
+                    .execute_without_session(|ctx| ctx.policy_or(policy_session, digest_list))?;
+            }
+            index = group_index;
+        }
+        Ok(())
+    }
+}
+
+/// Creates a [DigestList] with the digests of a group.
+fn digest_list(digests: &[Digest]) -> Result<DigestList> {
+    let mut digest_list = DigestList::new();
+    for digest in digests {
+        digest_list.add(digest.clone())?;
+    }
+    Ok(digest_list)
+}
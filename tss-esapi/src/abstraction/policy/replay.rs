@@ -0,0 +1,375 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{authorize, Policy, SecretEntity};
+use crate::{
+    abstraction::outer_wrapper::message_digest,
+    constants::SessionType,
+    handles::{
+        AuthHandle, KeyHandle, NvIndexHandle, NvIndexTpmHandle, ObjectHandle, SessionHandle,
+    },
+    interface_types::{
+        algorithm::HashingAlgorithm, resource_handles::NvAuth, session_handles::PolicySession,
+    },
+    structures::{Auth, Digest, Name, Nonce, PolicyExpiration, Signature, SymmetricDefinition},
+    tss2_esys::TPMI_ALG_HASH,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
+
+/// Trait for resolving the references of a [Policy] when it is replayed
+///
+/// # Details
+/// Some assertions cannot be executed only from the content of the policy,
+/// because they need signatures from an external authority, or a policy that
+/// has been approved after the policy was authored. When the policy is
+/// executed with [Policy::execute_with_resolver] or [replay], these values
+/// are requested from the resolver.
+///
+/// All the methods have a default implementation, returning an
+/// `UnsupportedParam` wrapper error for the methods resolving signatures,
+/// entities and approved policies, so a resolver only has to implement the methods needed
+/// by the assertions of its policies.
+pub trait PolicyResolver {
+    /// Signs the authorization of a `PolicySigned` assertion.
+    ///
+    /// # Arguments
+    /// * `auth_name` - The name of the key of the authority, as given when the
+    ///                 policy was authored.
+    /// * `nonce_tpm` - The current nonce of the policy session, to which the
+    ///                 authorization must be bound.
+    /// * `policy_ref` - The policy qualifier.
+    ///
+    /// # Details
+    /// The resolver chooses the expiration of the authorization and the
+    /// command parameter hash it is restricted to, and signs the digest of the
+    /// data returned by [signed_authorization_data](super::signed_authorization_data)
+    /// for `nonce_tpm`, these values and `policy_ref`.
+    fn sign_authorization(
+        &mut self,
+        _context: &mut Context,
+        auth_name: &Name,
+        _nonce_tpm: &Nonce,
+        _policy_ref: &Nonce,
+    ) -> Result<SignedAuthorization> {
+        error!(
+            "No signature can be resolved for the authority {:?}",
+            auth_name
+        );
+        Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+    }
+
+    /// Returns the handle of the entity of a `PolicySecret` assertion.
+    ///
+    /// # Arguments
+    /// * `auth_name` - The name of the entity, as given when the policy was
+    ///                 authored.
+    ///
+    /// # Details
+    /// The authorization value of the entity must have been set with
+    /// [Context::tr_set_auth], as the sessions of the context are used to
+    /// authorize the entity.
+    fn secret_auth_handle(
+        &mut self,
+        _context: &mut Context,
+        auth_name: &Name,
+    ) -> Result<AuthHandle> {
+        error!("No handle can be resolved for the entity {:?}", auth_name);
+        Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+    }
+
+    /// Returns the authorization value of the NV index of a `PolicySecret`
+    /// assertion.
+    ///
+    /// # Details
+    /// The authorization value is set on the NV index before the assertion is
+    /// executed, the sessions of the context being used to authorize it. The
+    /// default implementation returns an empty authorization value.
+    fn nv_index_auth_value(
+        &mut self,
+        _context: &mut Context,
+        _nv_index: NvIndexTpmHandle,
+    ) -> Result<Auth> {
+        Ok(Auth::default())
+    }
+
+    /// Returns the approved policy of a `PolicyAuthorize` assertion.
+    ///
+    /// # Arguments
+    /// * `key_sign` - The name of the key of the authority, as given when the
+    ///                policy was authored.
+    /// * `policy_ref` - The policy qualifier.
+    ///
+    /// # Details
+    /// Returns the approved policy, the key of the authority, of which only the
+    /// public part needs to be loaded in the TPM, and the signature of the approved
+    /// policy, e.g. returned by [sign_approved_policy](super::sign_approved_policy).
+    fn approved_policy(
+        &mut self,
+        _context: &mut Context,
+        key_sign: &Name,
+        _policy_ref: &Nonce,
+    ) -> Result<(Policy, KeyHandle, Signature)> {
+        error!(
+            "No approved policy can be resolved for the authority {:?}",
+            key_sign
+        );
+        Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+    }
+
+    /// Returns the authorization handle used to read the NV index of a
+    /// `PolicyAuthorizeNV` assertion.
+    ///
+    /// # Details
+    /// The default implementation returns [NvAuth::Owner].
+    fn nv_auth(&mut self, _nv_index_handle: NvIndexHandle) -> NvAuth {
+        NvAuth::Owner
+    }
+
+    /// Returns the approved policy of a `PolicyAuthorizeNV` assertion.
+    ///
+    /// # Arguments
+    /// * `nv_index` - The NV index holding the approved policy digest.
+    /// * `approved_policy` - The policy digest read from the NV index.
+    ///
+    /// # Details
+    /// The returned policy must have `approved_policy` as its policy digest.
+    fn nv_approved_policy(
+        &mut self,
+        _context: &mut Context,
+        nv_index: NvIndexTpmHandle,
+        _approved_policy: &Digest,
+    ) -> Result<Policy> {
+        error!(
+            "No approved policy can be resolved for the NV index {:?}",
+            nv_index
+        );
+        Err(Error::local_error(WrapperErrorKind::UnsupportedParam))
+    }
+}
+
+/// An authorization of a `PolicySigned` assertion, returned by
+/// [PolicyResolver::sign_authorization].
+#[derive(Debug, Clone)]
+pub struct SignedAuthorization {
+    /// The key of the authority, of which only the public part needs to be
+    /// loaded in the TPM.
+    pub key_handle: KeyHandle,
+    /// The expiration of the authorization.
+    pub expiration: PolicyExpiration,
+    /// The command parameter hash the authorization is restricted to, or an
+    /// empty digest if it is not restricted.
+    pub cp_hash_a: Digest,
+    /// The signature of the authority.
+    pub signature: Signature,
+}
+
+/// A resolver without any reference.
+#[derive(Debug, Copy, Clone)]
+pub(super) struct NoResolver;
+
+impl PolicyResolver for NoResolver {}
+
+/// Replays a policy in a new policy session.
+///
+/// # Details
+/// A policy session, using the hashing algorithm of the policy, is started and
+/// the policy is executed in it with [Policy::execute_with_resolver], the
+/// references of the policy being resolved with `resolver`. The state checked
+/// by the assertions, e.g. the current PCR values, is read by the TPM itself
+/// when the assertions are executed.
+///
+/// The returned session is ready to authorize the command guarded by the policy,
+/// and must be flushed by the caller when it is no longer needed. If the policy
+/// cannot be satisfied, the session is flushed before the error is returned.
+///
+/// # Errors
+/// * if the TPM does not return a handle for the session, a `WrongValueFromTpm`
+///   wrapper error is returned
+/// * if the policy cannot be executed, the error returned by
+///   [Policy::execute_with_resolver] is returned
+pub fn replay(
+    context: &mut Context,
+    policy: &Policy,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<PolicySession> {
+    let session = context
+        .execute_without_session(|ctx| {
+            ctx.start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Policy,
+                SymmetricDefinition::AES_128_CFB,
+                policy.hashing_algorithm(),
+            )
+        })?
+        .ok_or_else(|| {
+            error!("Received an invalid handle when starting the policy session");
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+    let result = PolicySession::try_from(session).and_then(|policy_session| {
+        policy
+            .execute_with_resolver(context, policy_session, resolver)
+            .map(|_| policy_session)
+    });
+    if result.is_err() {
+        let _ = context
+            .execute_without_session(|ctx| ctx.flush_context(SessionHandle::from(session).into()));
+    }
+    result
+}
+
+/// Executes a `PolicySigned` assertion with a signature from the resolver.
+///
+/// The authorization is bound to the policy session, through its current
+/// nonce, and its expiration and command parameter hash are chosen by the
+/// resolver.
+pub(super) fn execute_signed(
+    context: &mut Context,
+    policy_session: PolicySession,
+    auth_name: &Name,
+    policy_ref: &Nonce,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<()> {
+    let nonce_tpm = context.session_nonce_tpm(policy_session.into())?;
+    let SignedAuthorization {
+        key_handle,
+        expiration,
+        cp_hash_a,
+        signature,
+    } = resolver.sign_authorization(context, auth_name, &nonce_tpm, policy_ref)?;
+    context
+        .execute_without_session(|ctx| {
+            ctx.policy_signed(
+                policy_session,
+                key_handle.into(),
+                nonce_tpm,
+                cp_hash_a,
+                policy_ref.clone(),
+                expiration,
+                signature,
+            )
+        })
+        .map(|_| ())
+}
+
+/// Executes a `PolicySecret` assertion for an entity, resolving the handle
+/// of an object or the authorization value of an NV index with the resolver.
+///
+/// The authorization does not expire and is not bound to the session
+/// nor to a command.
+pub(super) fn execute_secret(
+    context: &mut Context,
+    policy_session: PolicySession,
+    entity: &SecretEntity,
+    policy_ref: &Nonce,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<()> {
+    let policy_secret = |context: &mut Context, auth_handle: AuthHandle| {
+        context
+            .policy_secret_with_expiration(
+                policy_session,
+                auth_handle,
+                Nonce::default(),
+                Digest::default(),
+                policy_ref.clone(),
+                PolicyExpiration::Never,
+            )
+            .map(|_| ())
+    };
+    match entity {
+        SecretEntity::Name(auth_name) => {
+            let auth_handle = resolver.secret_auth_handle(context, auth_name)?;
+            policy_secret(context, auth_handle)
+        }
+        SecretEntity::Hierarchy(hierarchy) => {
+            policy_secret(context, ObjectHandle::from(*hierarchy).into())
+        }
+        SecretEntity::NvIndex { nv_index, .. } => {
+            let mut object_handle = context
+                .execute_without_session(|ctx| ctx.tr_from_tpm_public((*nv_index).into()))?;
+            let result = resolver
+                .nv_index_auth_value(context, *nv_index)
+                .and_then(|auth| context.tr_set_auth(object_handle, auth))
+                .and_then(|_| policy_secret(context, object_handle.into()));
+            context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+            result
+        }
+    }
+}
+
+/// Executes a `PolicyAuthorize` assertion, satisfying the approved policy
+/// from the resolver first.
+pub(super) fn execute_authorize(
+    context: &mut Context,
+    policy_session: PolicySession,
+    key_sign: &Name,
+    policy_ref: &Nonce,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<()> {
+    let (approved_policy, key_handle, signature) =
+        resolver.approved_policy(context, key_sign, policy_ref)?;
+    approved_policy.execute_with_resolver(context, policy_session, resolver)?;
+    authorize(
+        context,
+        policy_session,
+        key_handle,
+        policy_ref.clone(),
+        signature,
+    )
+}
+
+/// Executes a `PolicyAuthorizeNV` assertion, satisfying the approved policy,
+/// read from the NV index and resolved by the resolver, first.
+///
+/// The sessions of the context are used to authorize the reading of the
+/// NV index.
+pub(super) fn execute_authorize_nv(
+    context: &mut Context,
+    policy_session: PolicySession,
+    hashing_algorithm: HashingAlgorithm,
+    nv_index: NvIndexTpmHandle,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<()> {
+    let mut object_handle =
+        context.execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))?;
+    let nv_index_handle = NvIndexHandle::from(object_handle);
+    let result = read_approved_policy(context, hashing_algorithm, nv_index_handle, resolver)
+        .and_then(|approved_policy| {
+            resolver
+                .nv_approved_policy(context, nv_index, &approved_policy)?
+                .execute_with_resolver(context, policy_session, resolver)?;
+            let nv_auth = resolver.nv_auth(nv_index_handle);
+            context.policy_authorize_nv(nv_auth, nv_index_handle, policy_session)
+        });
+    context.execute_without_session(|ctx| ctx.tr_close(&mut object_handle))?;
+    result
+}
+
+/// Reads the policy digest held by an NV index, in the layout written
+/// by [write_policy_digest](crate::abstraction::nv::write_policy_digest).
+fn read_approved_policy(
+    context: &mut Context,
+    hashing_algorithm: HashingAlgorithm,
+    nv_index_handle: NvIndexHandle,
+    resolver: &mut dyn PolicyResolver,
+) -> Result<Digest> {
+    let digest_size = message_digest(hashing_algorithm)?.size();
+    let size = u16::try_from(std::mem::size_of::<TPMI_ALG_HASH>() + digest_size).map_err(|_| {
+        error!("Invalid size of the policy digest ({})", digest_size);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })?;
+    let nv_auth = resolver.nv_auth(nv_index_handle);
+    let data = context.nv_read(nv_auth, nv_index_handle, size, 0)?;
+    let (algorithm, digest) = data.split_at(std::mem::size_of::<TPMI_ALG_HASH>());
+    if algorithm != TPMI_ALG_HASH::from(hashing_algorithm).to_be_bytes() {
+        error!(
+            "The policy digest of the NV index does not use the hashing algorithm of the policy ({:?})",
+            hashing_algorithm
+        );
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+    Digest::from_bytes(digest)
+}
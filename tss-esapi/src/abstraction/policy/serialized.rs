@@ -0,0 +1,442 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{Policy, PolicyAssertion, SecretEntity};
+use crate::{
+    attributes::LocalityAttributes,
+    constants::{ArithmeticOperation, CommandCode},
+    handles::{NvIndexTpmHandle, TpmHandle},
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::HierarchyAuth},
+    structures::{
+        CounterTimerCondition, CounterTimerConditionBuilder, CounterTimerField, Digest, Name,
+        Nonce, PcrSelectSize, PcrSelection, PcrSelectionList, PcrSlot,
+    },
+    tss2_esys::{TPM2_CC, TPM2_EO, TPM2_HANDLE, TPMA_LOCALITY, TPMI_ALG_HASH, TPML_PCR_SELECTION},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+
+/// The version of the serialized form of a [Policy].
+const VERSION: u32 = 1;
+
+/// The serialized form of a [Policy].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct SerializedPolicy {
+    version: u32,
+    hashing_algorithm: TPMI_ALG_HASH,
+    assertions: Vec<SerializedAssertion>,
+}
+
+/// The serialized form of a branch of a `PolicyOR` assertion.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SerializedBranch {
+    hashing_algorithm: TPMI_ALG_HASH,
+    assertions: Vec<SerializedAssertion>,
+}
+
+/// The serialized form of a [PolicyAssertion].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum SerializedAssertion {
+    Pcr {
+        pcr_selections: Vec<SerializedPcrSelection>,
+        pcr_policy_digest: Vec<u8>,
+    },
+    CommandCode {
+        code: TPM2_CC,
+    },
+    AuthValue,
+    Password,
+    Locality {
+        locality: TPMA_LOCALITY,
+    },
+    PhysicalPresence,
+    CpHash {
+        cp_hash_a: Vec<u8>,
+    },
+    NameHash {
+        name_hash: Vec<u8>,
+    },
+    NvWritten {
+        written_set: bool,
+    },
+    CounterTimer {
+        operand_b: Vec<u8>,
+        offset: u16,
+        operation: TPM2_EO,
+    },
+    Template {
+        template_hash: Vec<u8>,
+    },
+    DuplicationSelect {
+        object_name: Vec<u8>,
+        new_parent_name: Vec<u8>,
+        include_object: bool,
+    },
+    Signed {
+        auth_name: Vec<u8>,
+        policy_ref: Vec<u8>,
+    },
+    Secret {
+        auth_name: Vec<u8>,
+        policy_ref: Vec<u8>,
+    },
+    SecretNvIndex {
+        nv_index: TPM2_HANDLE,
+        nv_index_name: Vec<u8>,
+        policy_ref: Vec<u8>,
+    },
+    SecretHierarchy {
+        hierarchy: TPM2_HANDLE,
+        policy_ref: Vec<u8>,
+    },
+    Authorize {
+        key_sign: Vec<u8>,
+        policy_ref: Vec<u8>,
+    },
+    AuthorizeNv {
+        nv_index: TPM2_HANDLE,
+        nv_index_name: Vec<u8>,
+    },
+    Or {
+        branches: Vec<SerializedBranch>,
+    },
+}
+
+/// The serialized form of a [PcrSelection].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SerializedPcrSelection {
+    hashing_algorithm: TPMI_ALG_HASH,
+    size_of_select: u8,
+    pcr_indices: Vec<u32>,
+}
+
+impl From<Policy> for SerializedPolicy {
+    fn from(policy: Policy) -> Self {
+        SerializedPolicy {
+            version: VERSION,
+            hashing_algorithm: policy.hashing_algorithm.into(),
+            assertions: serialize_assertions(policy.assertions),
+        }
+    }
+}
+
+impl TryFrom<SerializedPolicy> for Policy {
+    type Error = Error;
+
+    fn try_from(serialized_policy: SerializedPolicy) -> Result<Self> {
+        if serialized_policy.version != VERSION {
+            error!(
+                "Unsupported version of the serialized policy ({} != {})",
+                serialized_policy.version, VERSION
+            );
+            return Err(Error::local_error(WrapperErrorKind::UnsupportedParam));
+        }
+        deserialize_policy(
+            serialized_policy.hashing_algorithm,
+            serialized_policy.assertions,
+        )
+    }
+}
+
+fn serialize_assertions(assertions: Vec<PolicyAssertion>) -> Vec<SerializedAssertion> {
+    assertions
+        .into_iter()
+        .map(|assertion| match assertion {
+            PolicyAssertion::Pcr {
+                pcr_selection_list,
+                pcr_policy_digest,
+            } => SerializedAssertion::Pcr {
+                pcr_selections: pcr_selection_list
+                    .get_selections()
+                    .iter()
+                    .map(|pcr_selection| SerializedPcrSelection {
+                        hashing_algorithm: pcr_selection.hashing_algorithm().into(),
+                        size_of_select: pcr_selection.size_of_select().as_u8(),
+                        pcr_indices: pcr_selection
+                            .selected()
+                            .into_iter()
+                            .map(|pcr_slot| u32::from(pcr_slot).trailing_zeros())
+                            .collect(),
+                    })
+                    .collect(),
+                pcr_policy_digest: pcr_policy_digest.as_bytes().to_vec(),
+            },
+            PolicyAssertion::CommandCode(code) => {
+                SerializedAssertion::CommandCode { code: code.into() }
+            }
+            PolicyAssertion::AuthValue => SerializedAssertion::AuthValue,
+            PolicyAssertion::Password => SerializedAssertion::Password,
+            PolicyAssertion::Locality(locality) => SerializedAssertion::Locality {
+                locality: locality.into(),
+            },
+            PolicyAssertion::PhysicalPresence => SerializedAssertion::PhysicalPresence,
+            PolicyAssertion::CpHash(cp_hash_a) => SerializedAssertion::CpHash {
+                cp_hash_a: cp_hash_a.as_bytes().to_vec(),
+            },
+            PolicyAssertion::NameHash(name_hash) => SerializedAssertion::NameHash {
+                name_hash: name_hash.as_bytes().to_vec(),
+            },
+            PolicyAssertion::NvWritten(written_set) => {
+                SerializedAssertion::NvWritten { written_set }
+            }
+            PolicyAssertion::CounterTimer(condition) => SerializedAssertion::CounterTimer {
+                operand_b: condition.operand_b().as_bytes().to_vec(),
+                offset: condition.offset(),
+                operation: condition.operation().into(),
+            },
+            PolicyAssertion::Template(template_hash) => SerializedAssertion::Template {
+                template_hash: template_hash.as_bytes().to_vec(),
+            },
+            PolicyAssertion::DuplicationSelect {
+                object_name,
+                new_parent_name,
+                include_object,
+            } => SerializedAssertion::DuplicationSelect {
+                object_name: object_name.value().to_vec(),
+                new_parent_name: new_parent_name.value().to_vec(),
+                include_object,
+            },
+            PolicyAssertion::Signed {
+                auth_name,
+                policy_ref,
+            } => SerializedAssertion::Signed {
+                auth_name: auth_name.value().to_vec(),
+                policy_ref: policy_ref.as_bytes().to_vec(),
+            },
+            PolicyAssertion::Secret { entity, policy_ref } => match entity {
+                SecretEntity::Name(auth_name) => SerializedAssertion::Secret {
+                    auth_name: auth_name.value().to_vec(),
+                    policy_ref: policy_ref.as_bytes().to_vec(),
+                },
+                SecretEntity::NvIndex {
+                    nv_index,
+                    nv_index_name,
+                } => SerializedAssertion::SecretNvIndex {
+                    nv_index: nv_index.into(),
+                    nv_index_name: nv_index_name.value().to_vec(),
+                    policy_ref: policy_ref.as_bytes().to_vec(),
+                },
+                SecretEntity::Hierarchy(hierarchy) => SerializedAssertion::SecretHierarchy {
+                    hierarchy: TpmHandle::from(hierarchy).into(),
+                    policy_ref: policy_ref.as_bytes().to_vec(),
+                },
+            },
+            PolicyAssertion::Authorize {
+                key_sign,
+                policy_ref,
+            } => SerializedAssertion::Authorize {
+                key_sign: key_sign.value().to_vec(),
+                policy_ref: policy_ref.as_bytes().to_vec(),
+            },
+            PolicyAssertion::AuthorizeNv {
+                nv_index,
+                nv_index_name,
+            } => SerializedAssertion::AuthorizeNv {
+                nv_index: nv_index.into(),
+                nv_index_name: nv_index_name.value().to_vec(),
+            },
+            PolicyAssertion::Or(branches) => SerializedAssertion::Or {
+                branches: branches
+                    .into_iter()
+                    .map(|branch| SerializedBranch {
+                        hashing_algorithm: branch.hashing_algorithm.into(),
+                        assertions: serialize_assertions(branch.assertions),
+                    })
+                    .collect(),
+            },
+        })
+        .collect()
+}
+
+fn deserialize_policy(
+    hashing_algorithm: TPMI_ALG_HASH,
+    assertions: Vec<SerializedAssertion>,
+) -> Result<Policy> {
+    let mut policy = Policy::new(HashingAlgorithm::try_from(hashing_algorithm)?);
+    for assertion in assertions {
+        policy.assertions.push(deserialize_assertion(assertion)?);
+    }
+    Ok(policy)
+}
+
+fn deserialize_assertion(assertion: SerializedAssertion) -> Result<PolicyAssertion> {
+    Ok(match assertion {
+        SerializedAssertion::Pcr {
+            pcr_selections,
+            pcr_policy_digest,
+        } => PolicyAssertion::Pcr {
+            pcr_selection_list: deserialize_pcr_selection_list(pcr_selections)?,
+            pcr_policy_digest: Digest::try_from(pcr_policy_digest)?,
+        },
+        SerializedAssertion::CommandCode { code } => {
+            PolicyAssertion::CommandCode(CommandCode::try_from(code)?)
+        }
+        SerializedAssertion::AuthValue => PolicyAssertion::AuthValue,
+        SerializedAssertion::Password => PolicyAssertion::Password,
+        SerializedAssertion::Locality { locality } => {
+            PolicyAssertion::Locality(LocalityAttributes::from(locality))
+        }
+        SerializedAssertion::PhysicalPresence => PolicyAssertion::PhysicalPresence,
+        SerializedAssertion::CpHash { cp_hash_a } => {
+            PolicyAssertion::CpHash(Digest::try_from(cp_hash_a)?)
+        }
+        SerializedAssertion::NameHash { name_hash } => {
+            PolicyAssertion::NameHash(Digest::try_from(name_hash)?)
+        }
+        SerializedAssertion::NvWritten { written_set } => PolicyAssertion::NvWritten(written_set),
+        SerializedAssertion::CounterTimer {
+            operand_b,
+            offset,
+            operation,
+        } => PolicyAssertion::CounterTimer(deserialize_counter_timer_condition(
+            operand_b, offset, operation,
+        )?),
+        SerializedAssertion::Template { template_hash } => {
+            PolicyAssertion::Template(Digest::try_from(template_hash)?)
+        }
+        SerializedAssertion::DuplicationSelect {
+            object_name,
+            new_parent_name,
+            include_object,
+        } => PolicyAssertion::DuplicationSelect {
+            object_name: Name::try_from(object_name)?,
+            new_parent_name: Name::try_from(new_parent_name)?,
+            include_object,
+        },
+        SerializedAssertion::Signed {
+            auth_name,
+            policy_ref,
+        } => PolicyAssertion::Signed {
+            auth_name: Name::try_from(auth_name)?,
+            policy_ref: Nonce::try_from(policy_ref)?,
+        },
+        SerializedAssertion::Secret {
+            auth_name,
+            policy_ref,
+        } => PolicyAssertion::Secret {
+            entity: SecretEntity::Name(Name::try_from(auth_name)?),
+            policy_ref: Nonce::try_from(policy_ref)?,
+        },
+        SerializedAssertion::SecretNvIndex {
+            nv_index,
+            nv_index_name,
+            policy_ref,
+        } => PolicyAssertion::Secret {
+            entity: SecretEntity::NvIndex {
+                nv_index: NvIndexTpmHandle::new(nv_index)?,
+                nv_index_name: Name::try_from(nv_index_name)?,
+            },
+            policy_ref: Nonce::try_from(policy_ref)?,
+        },
+        SerializedAssertion::SecretHierarchy {
+            hierarchy,
+            policy_ref,
+        } => PolicyAssertion::Secret {
+            entity: SecretEntity::Hierarchy(HierarchyAuth::try_from(TpmHandle::try_from(
+                hierarchy,
+            )?)?),
+            policy_ref: Nonce::try_from(policy_ref)?,
+        },
+        SerializedAssertion::Authorize {
+            key_sign,
+            policy_ref,
+        } => PolicyAssertion::Authorize {
+            key_sign: Name::try_from(key_sign)?,
+            policy_ref: Nonce::try_from(policy_ref)?,
+        },
+        SerializedAssertion::AuthorizeNv {
+            nv_index,
+            nv_index_name,
+        } => PolicyAssertion::AuthorizeNv {
+            nv_index: NvIndexTpmHandle::new(nv_index)?,
+            nv_index_name: Name::try_from(nv_index_name)?,
+        },
+        SerializedAssertion::Or { branches } => {
+            if branches.len() < 2 {
+                error!(
+                    "Invalid number of branches in a PolicyOR assertion ({} < 2)",
+                    branches.len()
+                );
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+            PolicyAssertion::Or(
+                branches
+                    .into_iter()
+                    .map(|branch| deserialize_policy(branch.hashing_algorithm, branch.assertions))
+                    .collect::<Result<Vec<Policy>>>()?,
+            )
+        }
+    })
+}
+
+fn deserialize_pcr_selection_list(
+    pcr_selections: Vec<SerializedPcrSelection>,
+) -> Result<PcrSelectionList> {
+    if pcr_selections.len() > PcrSelectionList::MAX_SIZE {
+        error!(
+            "Invalid number of PCR selections (> {})",
+            PcrSelectionList::MAX_SIZE
+        );
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    // The selections are converted through the TSS type in order to
+    // keep the order of the banks, which is part of the policy digest.
+    let mut tpml_pcr_selection = TPML_PCR_SELECTION::default();
+    for serialized_pcr_selection in pcr_selections {
+        let pcr_slots = serialized_pcr_selection
+            .pcr_indices
+            .iter()
+            .map(|&pcr_index| {
+                1u32.checked_shl(pcr_index)
+                    .ok_or_else(|| {
+                        error!("Invalid PCR index {}", pcr_index);
+                        Error::local_error(WrapperErrorKind::InvalidParam)
+                    })
+                    .and_then(PcrSlot::try_from)
+            })
+            .collect::<Result<Vec<PcrSlot>>>()?;
+        let pcr_selection = PcrSelection::create(
+            HashingAlgorithm::try_from(serialized_pcr_selection.hashing_algorithm)?,
+            PcrSelectSize::try_from(serialized_pcr_selection.size_of_select)?,
+            &pcr_slots,
+        )?;
+        tpml_pcr_selection.pcrSelections[tpml_pcr_selection.count as usize] = pcr_selection.into();
+        tpml_pcr_selection.count += 1;
+    }
+    tpml_pcr_selection.try_into()
+}
+
+pub(super) fn deserialize_counter_timer_condition(
+    operand_b: Vec<u8>,
+    offset: u16,
+    operation: TPM2_EO,
+) -> Result<CounterTimerCondition> {
+    let field = [
+        CounterTimerField::Time,
+        CounterTimerField::Clock,
+        CounterTimerField::ResetCount,
+        CounterTimerField::RestartCount,
+        CounterTimerField::Safe,
+    ]
+    .iter()
+    .copied()
+    .find(|field| field.offset() == offset && field.size() == operand_b.len())
+    .ok_or_else(|| {
+        error!(
+            "Invalid counter timer field (offset = {}, size = {})",
+            offset,
+            operand_b.len()
+        );
+        Error::local_error(WrapperErrorKind::InvalidParam)
+    })?;
+    let value = operand_b
+        .iter()
+        .fold(0u64, |value, &byte| (value << 8) | u64::from(byte));
+    CounterTimerConditionBuilder::new()
+        .with_field(field)
+        .with_operation(ArithmeticOperation::try_from(operation)?)
+        .with_value(value)
+        .build()
+}
@@ -0,0 +1,148 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    handles::{KeyHandle, TpmHandle},
+    interface_types::resource_handles::Hierarchy,
+    structures::{Name, Public},
+    traits::{Marshall, UnMarshall},
+    tss2_esys::TPM2_HANDLE,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The record of how a primary key was created, and of the name it got
+///
+/// # Details
+/// Primary keys are derived deterministically from the seed of their
+/// hierarchy and from their template, including the unique field. Storing
+/// the record, which implements the `Serialize` and `Deserialize` defined by
+/// `serde`, allows recreating the primary key (e.g. the SRK) when a service
+/// starts, instead of making it persistent.
+///
+/// When the key is recreated with [PrimaryKeyRecord::recreate], its name is
+/// compared with the recorded name, which detects that the seed of the
+/// hierarchy has changed, e.g. because the TPM has been cleared.
+///
+/// The `Null` hierarchy is not supported, as its seed changes every time
+/// the TPM is reset.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, TctiNameConf,
+/// #     interface_types::key_bits::RsaKeyBits,
+/// #     structures::{RsaExponent, SymmetricDefinitionObject},
+/// #     utils::create_restricted_decryption_rsa_public,
+/// # };
+/// use tss_esapi::{
+///     abstraction::primary::PrimaryKeyRecord,
+///     interface_types::resource_handles::Hierarchy,
+/// };
+/// # let mut context =
+/// #     Context::new(
+/// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// # let template = create_restricted_decryption_rsa_public(
+/// #     SymmetricDefinitionObject::AES_128_CFB,
+/// #     RsaKeyBits::Rsa2048,
+/// #     RsaExponent::default(),
+/// # )
+/// # .expect("Failed to create the template");
+/// let (srk_handle, record) = context
+///     .execute_with_nullauth_session(|ctx| {
+///         PrimaryKeyRecord::create(ctx, Hierarchy::Owner, template)
+///     })
+///     .expect("Failed to create the SRK");
+/// # context.flush_context(srk_handle.into()).expect("Failed to flush the SRK");
+/// // Store the record, and recreate the SRK later on.
+/// let srk_handle = context
+///     .execute_with_nullauth_session(|ctx| record.recreate(ctx))
+///     .expect("Failed to recreate the SRK");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrimaryKeyRecord {
+    hierarchy: TPM2_HANDLE,
+    template: Vec<u8>,
+    name: Vec<u8>,
+}
+
+impl PrimaryKeyRecord {
+    /// Creates a primary key, and returns its handle together with the record
+    /// of its creation.
+    ///
+    /// # Details
+    /// This requires an authorization session for the hierarchy.
+    ///
+    /// # Errors
+    /// * if the hierarchy is the `Null` hierarchy, an `InvalidParam` error is returned
+    pub fn create(
+        context: &mut Context,
+        hierarchy: Hierarchy,
+        template: Public,
+    ) -> Result<(KeyHandle, PrimaryKeyRecord)> {
+        if hierarchy == Hierarchy::Null {
+            error!("The primary keys of the Null hierarchy can not be recreated");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let marshalled_template = template.marshall()?;
+        let key_handle = context
+            .create_primary(hierarchy, template, None, None, None, None)?
+            .key_handle;
+        let name = context.tr_get_name(key_handle.into()).map_err(|e| {
+            let _ = context.flush_context(key_handle.into());
+            e
+        })?;
+        Ok((
+            key_handle,
+            PrimaryKeyRecord {
+                hierarchy: TpmHandle::from(hierarchy).into(),
+                template: marshalled_template,
+                name: name.value().to_vec(),
+            },
+        ))
+    }
+
+    /// Recreates the primary key, and verifies that it has the recorded name.
+    ///
+    /// # Details
+    /// This requires an authorization session for the hierarchy.
+    ///
+    /// # Errors
+    /// * if the name of the recreated key is not the recorded name, which
+    ///   happens when the seed of the hierarchy has changed, the key is
+    ///   flushed and a `WrongValueFromTpm` error is returned
+    pub fn recreate(&self, context: &mut Context) -> Result<KeyHandle> {
+        let hierarchy = self.hierarchy()?;
+        let template = self.template()?;
+        let expected_name = self.name()?;
+        let key_handle = context
+            .create_primary(hierarchy, template, None, None, None, None)?
+            .key_handle;
+        let name = context.tr_get_name(key_handle.into()).map_err(|e| {
+            let _ = context.flush_context(key_handle.into());
+            e
+        })?;
+        if name != expected_name {
+            error!("The recreated primary key does not have the recorded name, the seed of the hierarchy has changed");
+            context.flush_context(key_handle.into())?;
+            return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+        }
+        Ok(key_handle)
+    }
+
+    /// Returns the hierarchy of the primary key.
+    pub fn hierarchy(&self) -> Result<Hierarchy> {
+        Hierarchy::try_from(TpmHandle::try_from(self.hierarchy)?)
+    }
+
+    /// Returns the template used to create the primary key.
+    pub fn template(&self) -> Result<Public> {
+        Public::unmarshall(&self.template)
+    }
+
+    /// Returns the name of the primary key.
+    pub fn name(&self) -> Result<Name> {
+        Name::try_from(self.name.clone())
+    }
+}
@@ -0,0 +1,1048 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module for managing the lifetime of authorization sessions
+//!
+//! A TPM only has a few slots for sessions, and a session that is not flushed
+//! keeps its slot until the TPM is reset. A [SessionGuard] flushes the session
+//! it holds when it is dropped, including when an error is returned early.
+//!
+//! A [SessionBuilder] starts salted and bound sessions, with the parameters
+//! and attributes needed to use them for parameter encryption.
+//!
+//! A [SessionPool] keeps a fixed number of sessions started with a
+//! [SessionBuilder], and hands them out to the threads sharing a [Context].
+//!
+//! A [SessionSwapper] saves the contexts of the least recently used sessions
+//! so that more sessions can be used than the TPM can keep loaded.
+use crate::{
+    abstraction::ek,
+    attributes::{SessionAttributes, SessionAttributesBuilder, SessionAttributesMask},
+    constants::{PropertyTag, SessionType, TpmFormatOneError, TpmFormatZeroWarning},
+    error::{ArgumentNumber, TpmFormatZeroResponseCode, TpmResponseCode},
+    handles::{KeyHandle, ObjectHandle, SessionHandle},
+    interface_types::{
+        algorithm::{AsymmetricAlgorithm, HashingAlgorithm},
+        resource_handles::Hierarchy,
+        session_handles::AuthSession,
+    },
+    structures::{Auth, Nonce, SymmetricDefinition},
+    utils::SavedSession,
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::{
+    convert::TryFrom,
+    ops::Deref,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+};
+
+/// An authorization session that is flushed when the guard is dropped
+///
+/// # Details
+/// The guard borrows the [Context] for as long as the session is alive. The
+/// context is accessed through [SessionGuard::context], and
+/// [SessionGuard::execute] executes a closure with the session as the first
+/// session of the context.
+///
+/// When the guard is dropped, the session is flushed and removed from the
+/// sessions of the context, if it was set. The session can be kept alive
+/// with [SessionGuard::release].
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     constants::SessionType,
+/// #     interface_types::algorithm::HashingAlgorithm,
+/// #     structures::SymmetricDefinition,
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// let mut session = context
+///     .start_scoped_auth_session(
+///         None,
+///         None,
+///         None,
+///         SessionType::Hmac,
+///         SymmetricDefinition::AES_128_CFB,
+///         HashingAlgorithm::Sha256,
+///     )
+///     .expect("Failed to create session");
+/// let random_bytes = session
+///     .execute(|ctx| ctx.get_random(16))
+///     .expect("Failed to get random bytes");
+/// // The session is flushed here.
+/// drop(session);
+/// ```
+#[derive(Debug)]
+pub struct SessionGuard<'a> {
+    context: &'a mut Context,
+    session: AuthSession,
+    flush_on_drop: bool,
+}
+
+impl<'a> SessionGuard<'a> {
+    /// Creates a guard for a session started with `context`.
+    pub fn new(context: &'a mut Context, session: AuthSession) -> Self {
+        SessionGuard {
+            context,
+            session,
+            flush_on_drop: true,
+        }
+    }
+
+    /// Returns the session.
+    pub fn session(&self) -> AuthSession {
+        self.session
+    }
+
+    /// Returns the context with which the session was started.
+    pub fn context(&mut self) -> &mut Context {
+        self.context
+    }
+
+    /// Executes the closure with the session as the first session, and
+    /// the others set to None.
+    pub fn execute<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Context) -> T,
+    {
+        let session = self.session;
+        self.context.execute_with_session(Some(session), f)
+    }
+
+    /// Releases the session from the guard, without flushing it.
+    ///
+    /// # Details
+    /// The session must then be flushed by the caller when it is no
+    /// longer needed.
+    pub fn release(mut self) -> AuthSession {
+        self.flush_on_drop = false;
+        self.session
+    }
+}
+
+impl Deref for SessionGuard<'_> {
+    type Target = AuthSession;
+
+    fn deref(&self) -> &Self::Target {
+        &self.session
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if !self.flush_on_drop || self.session == AuthSession::Password {
+            return;
+        }
+        let (session_1, session_2, session_3) = self.context.sessions();
+        let remove = |session: Option<AuthSession>| session.filter(|s| *s != self.session);
+        self.context
+            .set_sessions((remove(session_1), remove(session_2), remove(session_3)));
+        let _ = self
+            .context
+            .flush_context(SessionHandle::from(self.session).into());
+    }
+}
+
+/// The key used to salt a session.
+#[derive(Debug, Copy, Clone)]
+enum Salt {
+    None,
+    Key(KeyHandle),
+    Ek(AsymmetricAlgorithm),
+}
+
+/// The entity to which a session is bound
+///
+/// # Details
+/// The session key of a bound session is derived from the authorization
+/// value of the entity, which must be known by the context when the session
+/// is started. When the session authorizes a command on the entity it is
+/// bound to, the authorization value is not used again to compute the HMAC
+/// of the command. When it authorizes a command on another entity, the
+/// authorization value of that entity is used, as for an unbound session.
+/// ESYS computes the HMAC accordingly, provided that the authorization
+/// values of the entities have been set with [Context::tr_set_auth].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SessionBinding {
+    None,
+    Object(ObjectHandle),
+    Hierarchy(Hierarchy),
+}
+
+impl SessionBinding {
+    /// Returns the handle of the entity to which the session is bound.
+    pub fn handle(&self) -> Option<ObjectHandle> {
+        match self {
+            SessionBinding::None => None,
+            SessionBinding::Object(object_handle) => Some(*object_handle),
+            SessionBinding::Hierarchy(hierarchy) => Some(ObjectHandle::from(*hierarchy)),
+        }
+    }
+}
+
+/// The nonceCaller of a session.
+#[derive(Debug, Clone)]
+enum NonceCaller {
+    Generated,
+    Sized(usize),
+    Explicit(Nonce),
+}
+
+/// A builder for authorization sessions
+///
+/// # Details
+/// By default, the builder starts an unsalted and unbound HMAC session, using
+/// [HashingAlgorithm::Sha256] and [SymmetricDefinition::AES_128_CFB], with the
+/// `continueSession`, `decrypt` and `encrypt` attributes set, so the session is
+/// ready to authorize commands and to encrypt their sensitive parameters.
+/// The nonceCaller of the session is generated by ESYS, unless it is set with
+/// [SessionBuilder::with_nonce_caller] or [SessionBuilder::with_nonce_size].
+///
+/// A salted session derives its session key from a secret that is encrypted
+/// with a key of the TPM, so the key cannot be computed by an observer of the
+/// commands sent to the TPM. A bound session derives its session key from the
+/// authorization value of an entity.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     abstraction::session::SessionBuilder,
+/// #     interface_types::algorithm::AsymmetricAlgorithm,
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// let session = SessionBuilder::new()
+///     .salted_with_ek(AsymmetricAlgorithm::Rsa)
+///     .build_scoped(&mut context)
+///     .expect("Failed to start the session");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SessionBuilder {
+    session_type: SessionType,
+    salt: Salt,
+    nonce_caller: NonceCaller,
+    binding: SessionBinding,
+    bind_auth: Option<Auth>,
+    symmetric: SymmetricDefinition,
+    auth_hash: HashingAlgorithm,
+    parameter_encryption: bool,
+}
+
+impl SessionBuilder {
+    /// Creates a new builder for an HMAC session.
+    pub const fn new() -> Self {
+        SessionBuilder {
+            session_type: SessionType::Hmac,
+            salt: Salt::None,
+            nonce_caller: NonceCaller::Generated,
+            binding: SessionBinding::None,
+            bind_auth: None,
+            symmetric: SymmetricDefinition::AES_128_CFB,
+            auth_hash: HashingAlgorithm::Sha256,
+            parameter_encryption: true,
+        }
+    }
+
+    /// Sets the type of the session.
+    pub fn with_session_type(mut self, session_type: SessionType) -> Self {
+        self.session_type = session_type;
+        self
+    }
+
+    /// Salts the session with a loaded key.
+    ///
+    /// # Details
+    /// The key must be a decryption key, and only its public part needs to be
+    /// loaded in the TPM.
+    pub fn salted_with(mut self, key_handle: KeyHandle) -> Self {
+        self.salt = Salt::Key(key_handle);
+        self
+    }
+
+    /// Salts the session with the endorsement key.
+    ///
+    /// # Details
+    /// The endorsement key is created from the default template of `key_algorithm`
+    /// with [ek::create_ek_object] when the session is started, and flushed once
+    /// the session has been started.
+    pub fn salted_with_ek(mut self, key_algorithm: AsymmetricAlgorithm) -> Self {
+        self.salt = Salt::Ek(key_algorithm);
+        self
+    }
+
+    /// Binds the session to an entity.
+    ///
+    /// # Details
+    /// The authorization value of the entity must have been set with
+    /// [Context::tr_set_auth] before the session is started, or with
+    /// [SessionBuilder::with_bind_auth].
+    pub fn with_binding(mut self, binding: SessionBinding) -> Self {
+        self.binding = binding;
+        self
+    }
+
+    /// Binds the session to an object, see [SessionBuilder::with_binding].
+    pub fn bound_to(self, bind: ObjectHandle) -> Self {
+        self.with_binding(SessionBinding::Object(bind))
+    }
+
+    /// Binds the session to a hierarchy, see [SessionBuilder::with_binding].
+    pub fn bound_to_hierarchy(self, hierarchy: Hierarchy) -> Self {
+        self.with_binding(SessionBinding::Hierarchy(hierarchy))
+    }
+
+    /// Sets the authorization value of the entity to which the session is
+    /// bound in the context, before the session is started.
+    pub fn with_bind_auth(mut self, auth: Auth) -> Self {
+        self.bind_auth = Some(auth);
+        self
+    }
+
+    /// Sets the nonceCaller of the session.
+    ///
+    /// # Details
+    /// The nonce must be at least 16 bytes long, and no longer than the
+    /// digest of the hashing algorithm of the session.
+    pub fn with_nonce_caller(mut self, nonce: Nonce) -> Self {
+        self.nonce_caller = NonceCaller::Explicit(nonce);
+        self
+    }
+
+    /// Sets the size of the nonceCaller of the session.
+    ///
+    /// # Details
+    /// The nonce is obtained from the random number generator of the
+    /// TPM when the session is started. By default, the size of the nonce
+    /// is the size of the digest of the hashing algorithm of the session.
+    pub fn with_nonce_size(mut self, nonce_size: usize) -> Self {
+        self.nonce_caller = NonceCaller::Sized(nonce_size);
+        self
+    }
+
+    /// Sets the symmetric algorithm used for parameter encryption.
+    pub fn with_symmetric(mut self, symmetric: SymmetricDefinition) -> Self {
+        self.symmetric = symmetric;
+        self
+    }
+
+    /// Sets the hashing algorithm of the session.
+    pub fn with_auth_hash(mut self, auth_hash: HashingAlgorithm) -> Self {
+        self.auth_hash = auth_hash;
+        self
+    }
+
+    /// Sets whether the `decrypt` and `encrypt` attributes of the session
+    /// are set.
+    pub fn with_parameter_encryption(mut self, parameter_encryption: bool) -> Self {
+        self.parameter_encryption = parameter_encryption;
+        self
+    }
+
+    /// Starts the session.
+    ///
+    /// # Details
+    /// The session is started without the sessions of the context, and must be
+    /// flushed by the caller when it is no longer needed.
+    ///
+    /// # Errors
+    /// * if parameter encryption is requested for a trial session or without a
+    ///   symmetric algorithm, an `InconsistentParams` wrapper error is returned
+    /// * if the authorization value of the bound entity is set for an unbound
+    ///   session, an `InconsistentParams` wrapper error is returned
+    /// * if the size of the nonceCaller is invalid, a `WrongParamSize` wrapper error
+    ///   is returned
+    /// * if the TPM does not return a handle for the session, a `WrongValueFromTpm`
+    ///   wrapper error is returned
+    pub fn build(self, context: &mut Context) -> Result<AuthSession> {
+        let (session_attributes, session_attributes_mask) = self.session_attributes()?;
+        let bind = self.binding.handle();
+        match (bind, &self.bind_auth) {
+            (Some(bind), Some(bind_auth)) => context.tr_set_auth(bind, bind_auth.clone())?,
+            (None, Some(_)) => {
+                error!("The authorization value of the bound entity is set for an unbound session");
+                return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+            }
+            _ => (),
+        }
+        let nonce = match &self.nonce_caller {
+            NonceCaller::Generated => None,
+            NonceCaller::Sized(nonce_size) => Some(random_nonce(context, *nonce_size)?),
+            NonceCaller::Explicit(nonce) => Some(nonce.clone()),
+        };
+        let (tpm_key, flush_tpm_key) = match self.salt {
+            Salt::None => (None, false),
+            Salt::Key(key_handle) => (Some(key_handle), false),
+            Salt::Ek(key_algorithm) => (
+                Some(ek::create_ek_object(context, key_algorithm, None)?),
+                true,
+            ),
+        };
+        let result = context.execute_without_session(|ctx| {
+            ctx.start_auth_session(
+                tpm_key,
+                bind,
+                nonce,
+                self.session_type,
+                self.symmetric,
+                self.auth_hash,
+            )
+        });
+        let flush_result = match (tpm_key, flush_tpm_key) {
+            (Some(key_handle), true) => context.flush_context(key_handle.into()),
+            _ => Ok(()),
+        };
+        let session = result?.ok_or_else(|| {
+            error!("Received an invalid handle when starting the session");
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+
+        let result = flush_result.and_then(|_| {
+            context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+        });
+        if let Err(e) = result {
+            let _ = context.flush_context(SessionHandle::from(session).into());
+            return Err(e);
+        }
+        Ok(session)
+    }
+
+    /// Starts the session, and returns a guard that flushes it when it is dropped.
+    ///
+    /// # Errors
+    /// * the errors returned by [SessionBuilder::build]
+    pub fn build_scoped(self, context: &mut Context) -> Result<SessionGuard<'_>> {
+        let session = self.build(context)?;
+        Ok(SessionGuard::new(context, session))
+    }
+
+    /// Private method returning the attributes of the session.
+    fn session_attributes(&self) -> Result<(SessionAttributes, SessionAttributesMask)> {
+        SessionAttributesBuilder::new()
+            .with_continue_session(true)
+            .with_decrypt(self.parameter_encryption)
+            .with_encrypt(self.parameter_encryption)
+            .with_session_type(self.session_type)
+            .with_symmetric(self.symmetric)
+            .try_build()
+    }
+}
+
+impl Default for SessionBuilder {
+    fn default() -> Self {
+        SessionBuilder::new()
+    }
+}
+
+/// Private function returning a nonce of `nonce_size` bytes obtained from
+/// the random number generator of the TPM.
+///
+/// The nonce is sent in clear when the session is started, so it is not
+/// encrypted, even if the automatic parameter encryption is enabled.
+fn random_nonce(context: &mut Context, nonce_size: usize) -> Result<Nonce> {
+    let random_bytes =
+        context.execute_without_session(|ctx| ctx.get_random_in_clear(nonce_size))?;
+    if random_bytes.len() != nonce_size {
+        error!(
+            "Received {} random bytes instead of {}",
+            random_bytes.len(),
+            nonce_size
+        );
+        return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+    }
+    Nonce::from_bytes(random_bytes.as_bytes())
+}
+
+/// A pool of authorization sessions
+///
+/// # Details
+/// The pool has a fixed number of slots, each holding at most one session.
+/// A slot is checked out with [SessionPool::checkout] or
+/// [SessionPool::try_checkout], and returned to the pool when the
+/// [PooledSession] is dropped.
+///
+/// The pool does not own the [Context] with which its sessions are started:
+/// the sessions are only valid for that context, which must be passed to
+/// [PooledSession::execute]. Checking out a slot does not require the
+/// context, so threads sharing the context behind a lock can wait for a
+/// slot without holding the lock.
+///
+/// When a command executed with a pooled session fails because the TPM
+/// reports the session as invalid, the session is flushed, and a new
+/// session is started the next time the slot is used. This replaces
+/// sessions that are no longer valid, for instance after the TPM has been
+/// reset. Other errors, e.g. authorization failures, leave the session in
+/// the pool.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     abstraction::session::{SessionBuilder, SessionPool},
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// let pool = SessionPool::new(&mut context, SessionBuilder::new(), 2)
+///     .expect("Failed to create the session pool");
+/// let mut session = pool.checkout();
+/// let random_bytes = session
+///     .execute(&mut context, |ctx| ctx.get_random(16))
+///     .expect("Failed to get random bytes");
+/// drop(session);
+/// pool.close(&mut context).expect("Failed to close the session pool");
+/// ```
+#[derive(Debug)]
+pub struct SessionPool {
+    builder: SessionBuilder,
+    size: usize,
+    slots: Mutex<Vec<Option<AuthSession>>>,
+    slot_returned: Condvar,
+}
+
+impl SessionPool {
+    /// Creates a pool of `size` sessions started with `builder`.
+    ///
+    /// # Details
+    /// The size of the pool is limited to the number of sessions the TPM
+    /// guarantees it can keep loaded, as reported by
+    /// [PropertyTag::HrLoadedMin], as the sessions of the pool stay loaded.
+    /// All the sessions are started when the pool is created.
+    ///
+    /// # Errors
+    /// * if `size` is zero, an `InvalidParam` wrapper error is returned
+    /// * the errors returned by [SessionBuilder::build]. The sessions
+    ///   already started are flushed.
+    pub fn new(context: &mut Context, builder: SessionBuilder, size: usize) -> Result<Self> {
+        if size == 0 {
+            error!("The session pool must contain at least one session");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let size = match context.get_tpm_property(PropertyTag::HrLoadedMin)? {
+            Some(max) => size.min(usize::try_from(max).unwrap_or(usize::MAX)),
+            None => size,
+        };
+        let mut sessions = Vec::with_capacity(size);
+        for _ in 0..size {
+            match builder.clone().build(context) {
+                Ok(session) => sessions.push(Some(session)),
+                Err(e) => {
+                    for session in sessions.into_iter().flatten() {
+                        let _ = context.flush_context(SessionHandle::from(session).into());
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(SessionPool {
+            builder,
+            size,
+            slots: Mutex::new(sessions),
+            slot_returned: Condvar::new(),
+        })
+    }
+
+    /// Returns the number of sessions of the pool.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the number of slots that are not checked out.
+    pub fn available(&self) -> usize {
+        self.slots().len()
+    }
+
+    /// Checks out a slot of the pool, waiting for one to be returned
+    /// if all of them are checked out.
+    ///
+    /// # Details
+    /// The context must not be locked by the calling thread while it
+    /// waits, otherwise the slots checked out by the other threads may
+    /// never be returned.
+    pub fn checkout(&self) -> PooledSession<'_> {
+        let mut slots = self.slots();
+        loop {
+            if let Some(session) = slots.pop() {
+                return PooledSession {
+                    pool: self,
+                    session,
+                };
+            }
+            slots = self
+                .slot_returned
+                .wait(slots)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Checks out a slot of the pool, or returns None if all of them
+    /// are checked out.
+    pub fn try_checkout(&self) -> Option<PooledSession<'_>> {
+        self.slots().pop().map(|session| PooledSession {
+            pool: self,
+            session,
+        })
+    }
+
+    /// Flushes the sessions of the pool.
+    ///
+    /// # Details
+    /// The slots that are still checked out are not flushed. The pool
+    /// cannot be closed while they are borrowed.
+    ///
+    /// # Errors
+    /// * the first error returned when flushing a session. All the sessions
+    ///   are flushed even if one of them fails.
+    pub fn close(self, context: &mut Context) -> Result<()> {
+        let slots = self
+            .slots
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        slots
+            .into_iter()
+            .flatten()
+            .map(|session| context.flush_context(SessionHandle::from(session).into()))
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Private method returning the slots that are not checked out.
+    ///
+    /// # Details
+    /// The slots are never left in an inconsistent state, so the lock is
+    /// recovered if another thread panicked while holding it.
+    fn slots(&self) -> MutexGuard<'_, Vec<Option<AuthSession>>> {
+        self.slots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A slot checked out from a [SessionPool]
+///
+/// # Details
+/// The slot is returned to the pool when it is dropped.
+#[derive(Debug)]
+pub struct PooledSession<'a> {
+    pool: &'a SessionPool,
+    session: Option<AuthSession>,
+}
+
+impl PooledSession<'_> {
+    /// Returns the session of the slot, starting a new one if the previous
+    /// session was invalidated.
+    ///
+    /// # Errors
+    /// * the errors returned by [SessionBuilder::build]
+    pub fn session(&mut self, context: &mut Context) -> Result<AuthSession> {
+        match self.session {
+            Some(session) => Ok(session),
+            None => {
+                let session = self.pool.builder.clone().build(context)?;
+                self.session = Some(session);
+                Ok(session)
+            }
+        }
+    }
+
+    /// Executes the closure with the session as the first session, and
+    /// the others set to None.
+    ///
+    /// # Details
+    /// If the closure returns an error of the TPM indicating that the session
+    /// is not valid, i.e. that it is not loaded or that its handle or nonce
+    /// is not valid, the session is invalidated with [PooledSession::invalidate].
+    ///
+    /// # Errors
+    /// * the errors returned by [PooledSession::session]
+    /// * the errors returned by the closure
+    pub fn execute<F, T>(&mut self, context: &mut Context, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        let session = self.session(context)?;
+        let result = context.execute_with_session(Some(session), f);
+        if matches!(&result, Err(e) if is_session_error(e)) {
+            self.invalidate(context);
+        }
+        result
+    }
+
+    /// Flushes the session of the slot, so that a new session is started
+    /// the next time the slot is used.
+    pub fn invalidate(&mut self, context: &mut Context) {
+        if let Some(session) = self.session.take() {
+            let _ = context.flush_context(SessionHandle::from(session).into());
+        }
+    }
+}
+
+impl Drop for PooledSession<'_> {
+    fn drop(&mut self) {
+        self.pool.slots().push(self.session.take());
+        self.pool.slot_returned.notify_one();
+    }
+}
+
+/// Identifier of a session managed by a [SessionSwapper]
+///
+/// # Details
+/// The handle of a session may change every time the session is loaded,
+/// so sessions are identified by an identifier that does not.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SwappedSessionId(u64);
+
+/// The state of a session managed by a [SessionSwapper].
+#[derive(Debug)]
+enum SwappedSessionState {
+    Loaded(AuthSession),
+    Saved {
+        saved_session: SavedSession,
+        save_sequence: u64,
+    },
+}
+
+/// A session managed by a [SessionSwapper].
+#[derive(Debug)]
+struct SwappedSession {
+    id: SwappedSessionId,
+    state: SwappedSessionState,
+}
+
+/// A set of sessions that are saved when they are not used
+///
+/// # Details
+/// The TPM can only keep a few sessions loaded at the same time. The
+/// swapper keeps at most `max_loaded` of its sessions loaded, and saves
+/// the context of the least recently used session when another one has
+/// to be loaded. Saved sessions are loaded again when they are used.
+///
+/// When the TPM refuses to save a session because the oldest saved
+/// session is too old (the `ContextGap` warning), the oldest saved session
+/// is loaded and saved again, and the save is retried.
+///
+/// The sessions must not be set as the sessions of the context outside of
+/// [SessionSwapper::execute], as they may be saved by the swapper at any
+/// time.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     abstraction::session::{SessionBuilder, SessionSwapper},
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// let mut swapper = SessionSwapper::with_max_loaded(1).expect("Failed to create the swapper");
+/// let first = SessionBuilder::new().build(&mut context).expect("Failed to start the session");
+/// let first = swapper.insert(&mut context, first).expect("Failed to insert the session");
+/// let second = SessionBuilder::new().build(&mut context).expect("Failed to start the session");
+/// let second = swapper.insert(&mut context, second).expect("Failed to insert the session");
+/// // The first session has been saved, and is loaded again here.
+/// let random_bytes = swapper
+///     .execute(&mut context, first, |ctx| ctx.get_random(16))
+///     .expect("Failed to get random bytes");
+/// swapper.close(&mut context).expect("Failed to close the swapper");
+/// ```
+#[derive(Debug)]
+pub struct SessionSwapper {
+    max_loaded: usize,
+    // Ordered from the least recently used session to the most recently used.
+    sessions: Vec<SwappedSession>,
+    next_id: u64,
+    next_save_sequence: u64,
+}
+
+impl SessionSwapper {
+    /// Creates a swapper that keeps loaded as many sessions as the TPM
+    /// guarantees it can keep loaded.
+    ///
+    /// # Details
+    /// The number of sessions is the one reported by [PropertyTag::HrLoadedMin].
+    /// The TPM may be able to load more sessions, but some of them may be used
+    /// by other applications.
+    ///
+    /// # Errors
+    /// * if the TPM does not report the property, a `WrongValueFromTpm` wrapper
+    ///   error is returned
+    pub fn new(context: &mut Context) -> Result<Self> {
+        let max_loaded = context
+            .get_tpm_property(PropertyTag::HrLoadedMin)?
+            .ok_or_else(|| {
+                error!("The TPM did not report the minimum number of loaded sessions");
+                Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+            })?;
+        SessionSwapper::with_max_loaded(usize::try_from(max_loaded).unwrap_or(usize::MAX))
+    }
+
+    /// Creates a swapper that keeps at most `max_loaded` sessions loaded.
+    ///
+    /// # Errors
+    /// * if `max_loaded` is zero, an `InvalidParam` wrapper error is returned
+    pub fn with_max_loaded(max_loaded: usize) -> Result<Self> {
+        if max_loaded == 0 {
+            error!("The swapper must be able to keep at least one session loaded");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(SessionSwapper {
+            max_loaded,
+            sessions: Vec::new(),
+            next_id: 0,
+            next_save_sequence: 0,
+        })
+    }
+
+    /// Returns the maximum number of sessions kept loaded.
+    pub fn max_loaded(&self) -> usize {
+        self.max_loaded
+    }
+
+    /// Returns the number of sessions managed by the swapper.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Returns true if the swapper does not manage any session.
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Returns the number of sessions that are loaded.
+    pub fn loaded(&self) -> usize {
+        self.sessions
+            .iter()
+            .filter(|s| matches!(s.state, SwappedSessionState::Loaded(_)))
+            .count()
+    }
+
+    /// Adds a loaded session to the swapper, saving the least recently used
+    /// sessions if more than `max_loaded` sessions are loaded.
+    ///
+    /// # Errors
+    /// * if `session` is [AuthSession::Password], an `InvalidParam` wrapper
+    ///   error is returned
+    /// * the errors returned when saving the sessions. The session is added
+    ///   to the swapper even if they fail.
+    pub fn insert(
+        &mut self,
+        context: &mut Context,
+        session: AuthSession,
+    ) -> Result<SwappedSessionId> {
+        if session == AuthSession::Password {
+            error!("The password session cannot be swapped");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let id = SwappedSessionId(self.next_id);
+        self.next_id += 1;
+        self.sessions.push(SwappedSession {
+            id,
+            state: SwappedSessionState::Loaded(session),
+        });
+        self.make_room(context, self.max_loaded)?;
+        Ok(id)
+    }
+
+    /// Returns the session with the identifier `id`, loading it if it was
+    /// saved.
+    ///
+    /// # Details
+    /// The session becomes the most recently used session. Its handle is
+    /// only valid until another session of the swapper is used.
+    ///
+    /// # Errors
+    /// * if the swapper has no session with the identifier `id`, an
+    ///   `InvalidParam` wrapper error is returned
+    /// * the errors returned when saving or loading the sessions
+    pub fn session(&mut self, context: &mut Context, id: SwappedSessionId) -> Result<AuthSession> {
+        let index = self.index_of(id)?;
+        let swapped_session = self.sessions.remove(index);
+        let is_loaded = matches!(swapped_session.state, SwappedSessionState::Loaded(_));
+        self.sessions.push(swapped_session);
+        if !is_loaded {
+            self.make_room(context, self.max_loaded - 1)?;
+        }
+        self.load(context, self.sessions.len() - 1)
+    }
+
+    /// Executes the closure with the session with the identifier `id` as the
+    /// first session, and the others set to None.
+    ///
+    /// # Errors
+    /// * the errors returned by [SessionSwapper::session]
+    /// * the errors returned by the closure
+    pub fn execute<F, T>(&mut self, context: &mut Context, id: SwappedSessionId, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        let session = self.session(context, id)?;
+        context.execute_with_session(Some(session), f)
+    }
+
+    /// Removes the session with the identifier `id` from the swapper, and
+    /// returns it loaded.
+    ///
+    /// # Details
+    /// The session must then be flushed by the caller when it is no
+    /// longer needed.
+    ///
+    /// # Errors
+    /// * the errors returned by [SessionSwapper::session]
+    pub fn remove(&mut self, context: &mut Context, id: SwappedSessionId) -> Result<AuthSession> {
+        let session = self.session(context, id)?;
+        let _ = self.sessions.pop();
+        Ok(session)
+    }
+
+    /// Flushes all the sessions of the swapper.
+    ///
+    /// # Details
+    /// The saved sessions are loaded in order to be flushed.
+    ///
+    /// # Errors
+    /// * the first error returned when loading or flushing a session. All
+    ///   the sessions are flushed even if one of them fails.
+    pub fn close(self, context: &mut Context) -> Result<()> {
+        self.sessions
+            .into_iter()
+            .map(|swapped_session| {
+                let session = match swapped_session.state {
+                    SwappedSessionState::Loaded(session) => session,
+                    SwappedSessionState::Saved { saved_session, .. } => {
+                        context.session_context_load(saved_session)?
+                    }
+                };
+                context.flush_context(SessionHandle::from(session).into())
+            })
+            .fold(Ok(()), Result::and)
+    }
+
+    /// Private method returning the index of the session with the identifier `id`.
+    fn index_of(&self, id: SwappedSessionId) -> Result<usize> {
+        self.sessions
+            .iter()
+            .position(|s| s.id == id)
+            .ok_or_else(|| {
+                error!("The swapper has no session with the identifier {}", id.0);
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })
+    }
+
+    /// Private method saving the least recently used sessions until at most
+    /// `max_loaded` sessions are loaded. The most recently used session is
+    /// never saved.
+    fn make_room(&mut self, context: &mut Context, max_loaded: usize) -> Result<()> {
+        while self.loaded() > max_loaded {
+            let index = self.sessions[..self.sessions.len() - 1]
+                .iter()
+                .position(|s| matches!(s.state, SwappedSessionState::Loaded(_)))
+                .ok_or_else(|| {
+                    error!("No session of the swapper can be saved");
+                    Error::local_error(WrapperErrorKind::InvalidHandleState)
+                })?;
+            self.save(context, index)?;
+        }
+        Ok(())
+    }
+
+    /// Private method loading the session at `index`.
+    fn load(&mut self, context: &mut Context, index: usize) -> Result<AuthSession> {
+        let swapped_session = &mut self.sessions[index];
+        let session = match &swapped_session.state {
+            SwappedSessionState::Loaded(session) => return Ok(*session),
+            SwappedSessionState::Saved { saved_session, .. } => {
+                context.session_context_load(saved_session.clone())?
+            }
+        };
+        swapped_session.state = SwappedSessionState::Loaded(session);
+        Ok(session)
+    }
+
+    /// Private method saving the session at `index`.
+    ///
+    /// # Details
+    /// If the TPM returns a `ContextGap` warning, the oldest saved session is
+    /// loaded and saved again before the save is retried.
+    fn save(&mut self, context: &mut Context, index: usize) -> Result<()> {
+        let session = self.load(context, index)?;
+        let saved_session = match context.session_context_save(session) {
+            Err(e) if is_context_gap(&e) => {
+                self.refresh_oldest(context, index)?;
+                context.session_context_save(session)?
+            }
+            result => result?,
+        };
+        self.set_saved(index, saved_session);
+        Ok(())
+    }
+
+    /// Private method loading and saving again the session that was saved
+    /// the earliest, other than the session at `excluded`.
+    fn refresh_oldest(&mut self, context: &mut Context, excluded: usize) -> Result<()> {
+        let index = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != excluded)
+            .filter_map(|(index, s)| match s.state {
+                SwappedSessionState::Saved { save_sequence, .. } => Some((index, save_sequence)),
+                SwappedSessionState::Loaded(_) => None,
+            })
+            .min_by_key(|(_, save_sequence)| *save_sequence)
+            .map(|(index, _)| index)
+            .ok_or_else(|| {
+                error!("The context gap is caused by a session not managed by the swapper");
+                Error::local_error(WrapperErrorKind::InvalidHandleState)
+            })?;
+        let session = self.load(context, index)?;
+        let saved_session = context.session_context_save(session)?;
+        self.set_saved(index, saved_session);
+        Ok(())
+    }
+
+    /// Private method recording that the session at `index` has been saved.
+    fn set_saved(&mut self, index: usize, saved_session: SavedSession) {
+        self.sessions[index].state = SwappedSessionState::Saved {
+            saved_session,
+            save_sequence: self.next_save_sequence,
+        };
+        self.next_save_sequence += 1;
+    }
+}
+
+/// Private function returning true if the error is a `ContextGap` warning.
+fn is_context_gap(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatZero(
+            TpmFormatZeroResponseCode::Warning(warning),
+        ))) if warning.error_number() == TpmFormatZeroWarning::ContextGap
+    )
+}
+
+/// Private function returning true if the error indicates that the first
+/// session of the command is not valid.
+fn is_session_error(error: &Error) -> bool {
+    let response_code = match error {
+        Error::TssError(ReturnCode::Tpm(response_code))
+        | Error::TssError(ReturnCode::TpmResourceManager(response_code)) => response_code,
+        _ => return false,
+    };
+    match response_code {
+        TpmResponseCode::FormatOne(response_code) => {
+            response_code.argument_number() == ArgumentNumber::Session(1)
+                && matches!(
+                    response_code.error_number(),
+                    TpmFormatOneError::Handle
+                        | TpmFormatOneError::Nonce
+                        | TpmFormatOneError::Expired
+                )
+        }
+        TpmResponseCode::FormatZero(TpmFormatZeroResponseCode::Warning(warning)) => {
+            warning.error_number() == TpmFormatZeroWarning::ReferenceS0
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    handles::KeyHandle,
+    interface_types::algorithm::SymmetricMode,
+    structures::{InitialValue, MaxBuffer},
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::{
+    convert::TryFrom,
+    io::{ErrorKind, Read, Write},
+};
+
+/// The largest block size, in bytes, of the symmetric algorithms
+/// supported by the TPM.
+///
+/// # Details
+/// The chunks that are sent to the TPM are kept as a multiple of
+/// this value so that block modes (i.e. CBC and ECB) can be chained.
+const MAX_SYMMETRIC_BLOCK_SIZE: usize = 16;
+
+/// Encrypts all the data read from `reader` and writes the result to `writer`.
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `key_handle` - A [KeyHandle] of the symmetric cipher key to be used.
+/// * `mode` - The [SymmetricMode] to be used.
+/// * `initial_value` - The [InitialValue] to start the encryption with.
+/// * `reader` - The source of the plain text.
+/// * `writer` - The destination of the cipher text.
+///
+/// # Details
+/// See [encrypt_decrypt_stream] for more details.
+///
+/// # Returns
+/// The chaining value that was returned from the last call to the TPM.
+pub fn encrypt_stream<R: Read, W: Write>(
+    context: &mut Context,
+    key_handle: KeyHandle,
+    mode: SymmetricMode,
+    initial_value: InitialValue,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<InitialValue> {
+    encrypt_decrypt_stream(
+        context,
+        key_handle,
+        false,
+        mode,
+        initial_value,
+        reader,
+        writer,
+    )
+}
+
+/// Decrypts all the data read from `reader` and writes the result to `writer`.
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `key_handle` - A [KeyHandle] of the symmetric cipher key to be used.
+/// * `mode` - The [SymmetricMode] to be used.
+/// * `initial_value` - The [InitialValue] that was used when the data was encrypted.
+/// * `reader` - The source of the cipher text.
+/// * `writer` - The destination of the plain text.
+///
+/// # Details
+/// See [encrypt_decrypt_stream] for more details.
+///
+/// # Returns
+/// The chaining value that was returned from the last call to the TPM.
+pub fn decrypt_stream<R: Read, W: Write>(
+    context: &mut Context,
+    key_handle: KeyHandle,
+    mode: SymmetricMode,
+    initial_value: InitialValue,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<InitialValue> {
+    encrypt_decrypt_stream(
+        context,
+        key_handle,
+        true,
+        mode,
+        initial_value,
+        reader,
+        writer,
+    )
+}
+
+/// Encrypts or decrypts all the data read from `reader` and writes the result to `writer`.
+///
+/// # Arguments
+/// * `context` - The [Context] used to communicate with the TPM.
+/// * `key_handle` - A [KeyHandle] of the symmetric cipher key to be used.
+/// * `decrypt` - A boolean indicating if the data should be decrypted or encrypted.
+///               If set to true the data will be decrypted else encrypted.
+/// * `mode` - The [SymmetricMode] to be used.
+/// * `initial_value` - An initial value as required by the algorithm.
+/// * `reader` - The source of the input data.
+/// * `writer` - The destination of the output data.
+///
+/// # Details
+/// The data is read in chunks as large as the TPM allows for its input buffers
+/// (rounded down to a multiple of the cipher block size). All chunks except the
+/// last one are therefore complete blocks and the initial value that the TPM
+/// returns for each chunk is used as the initial value for the next one.
+///
+/// The session requirements are the same as for [Context::encrypt_decrypt_2].
+///
+/// The TPM does not perform any padding so when using a block mode (i.e. CBC and ECB)
+/// the total length of the data needs to be a multiple of the cipher block size.
+///
+/// # Errors
+/// * if reading from `reader` or writing to `writer` fails then an `InvalidParam`
+///   wrapper error is returned.
+///
+/// # Returns
+/// The chaining value that was returned from the last call to the TPM. If no data
+/// was available in `reader` then `initial_value` is returned.
+pub fn encrypt_decrypt_stream<R: Read, W: Write>(
+    context: &mut Context,
+    key_handle: KeyHandle,
+    decrypt: bool,
+    mode: SymmetricMode,
+    initial_value: InitialValue,
+    reader: &mut R,
+    writer: &mut W,
+) -> Result<InitialValue> {
+    let chunk_size = max_chunk_size(context)?;
+    let mut chunk = vec![0u8; chunk_size];
+    let mut chaining_value = initial_value;
+    loop {
+        let read_size = read_chunk(reader, &mut chunk)?;
+        if read_size == 0 {
+            return Ok(chaining_value);
+        }
+        let (out_data, iv_out) = context.encrypt_decrypt_2(
+            key_handle,
+            decrypt,
+            mode,
+            MaxBuffer::try_from(chunk[..read_size].to_vec())?,
+            chaining_value,
+        )?;
+        writer.write_all(out_data.as_bytes()).map_err(|e| {
+            error!("Failed to write the processed data: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        chaining_value = iv_out;
+        if read_size < chunk_size {
+            return Ok(chaining_value);
+        }
+    }
+}
+
+/// Returns the size of the chunks that can be sent to the TPM.
+fn max_chunk_size(context: &mut Context) -> Result<usize> {
+    let chunk_size = super::max_input_buffer_size(context)? / MAX_SYMMETRIC_BLOCK_SIZE
+        * MAX_SYMMETRIC_BLOCK_SIZE;
+    if chunk_size == 0 {
+        error!("The TPM input buffer is smaller than one cipher block");
+        return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+    }
+    Ok(chunk_size)
+}
+
+/// Fills the chunk with data from the reader.
+///
+/// Less data than the size of the chunk is only returned
+/// if the reader has reached its end.
+fn read_chunk<R: Read>(reader: &mut R, chunk: &mut [u8]) -> Result<usize> {
+    let mut read_size = 0;
+    while read_size < chunk.len() {
+        match reader.read(&mut chunk[read_size..]) {
+            Ok(0) => break,
+            Ok(size) => read_size += size,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => {
+                error!("Failed to read the data to be processed: {}", e);
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+        }
+    }
+    Ok(read_size)
+}
@@ -18,9 +18,10 @@ use crate::{
         resource_handles::Hierarchy,
     },
     structures::{
-        Auth, CreateKeyResult, Data, Digest, EccPoint, EccScheme, Public, PublicBuilder,
-        PublicEccParametersBuilder, PublicKeyRsa, PublicRsaParametersBuilder, RsaExponent,
-        RsaScheme, Signature, SignatureScheme, SymmetricDefinitionObject, VerifiedTicket,
+        Auth, CreateKeyResult, CreateLoadedKeyResult, Data, Digest, EccPoint, EccScheme, Public,
+        PublicBuilder, PublicEccParametersBuilder, PublicKeyRsa, PublicRsaParametersBuilder,
+        RsaExponent, RsaScheme, Signature, SignatureScheme, SymmetricDefinitionObject,
+        VerifiedTicket,
     },
     tcti_ldr::TctiNameConf,
     tss2_esys::*,
@@ -143,16 +144,7 @@ impl TransientKeyContext {
         key_params: KeyParams,
         auth_size: usize,
     ) -> Result<(KeyMaterial, Option<Auth>)> {
-        if auth_size > 32 {
-            return Err(Error::local_error(ErrorKind::WrongParamSize));
-        }
-        let key_auth = if auth_size > 0 {
-            self.set_session_attrs()?;
-            let random_bytes = self.context.get_random(auth_size)?;
-            Some(Auth::from_bytes(random_bytes.as_bytes())?)
-        } else {
-            None
-        };
+        let key_auth = self.generate_key_auth(auth_size)?;
 
         self.set_session_attrs()?;
         let CreateKeyResult {
@@ -175,6 +167,120 @@ impl TransientKeyContext {
         Ok((key_material, key_auth))
     }
 
+    /// Create a new key and load it into the TPM.
+    ///
+    /// A key is created as a descendant of the context root key, with the given parameters,
+    /// and loaded in the same call to the TPM.
+    ///
+    /// If successful, the result contains the [KeyHandle] of the loaded key, the [KeyMaterial]
+    /// of the key and the authentication value for said key. The authentication value is
+    /// already set on the handle.
+    ///
+    /// The key attributes are the same as for [TransientKeyContext::create_key].
+    ///
+    /// The caller is responsible for flushing the key from the TPM once it is no longer
+    /// needed, e.g. through the [Context] obtained with `as_mut`.
+    ///
+    /// # Constraints
+    /// * `auth_size` must be at most 32
+    ///
+    /// # Errors
+    /// * if the authentication size is larger than 32 a `WrongParamSize` wrapper error is returned
+    pub fn create_loaded_key(
+        &mut self,
+        key_params: KeyParams,
+        auth_size: usize,
+    ) -> Result<(KeyHandle, KeyMaterial, Option<Auth>)> {
+        let key_auth = self.generate_key_auth(auth_size)?;
+
+        self.set_session_attrs()?;
+        let CreateLoadedKeyResult {
+            key_handle,
+            out_private,
+            out_public,
+        } = self.context.create_loaded(
+            self.root_key_handle.into(),
+            TransientKeyContext::get_public_from_params(key_params, None)?,
+            key_auth.clone(),
+            None,
+        )?;
+
+        if let Some(key_auth_value) = key_auth.clone() {
+            self.context
+                .tr_set_auth(key_handle.into(), key_auth_value)
+                .or_else(|e| {
+                    self.context.flush_context(key_handle.into())?;
+                    Err(e)
+                })?;
+        }
+        let public = out_public.try_into().or_else(|e| {
+            self.context.flush_context(key_handle.into())?;
+            Err(e)
+        })?;
+
+        let key_material = KeyMaterial {
+            public,
+            private: out_private.as_bytes().to_vec(),
+        };
+        Ok((key_handle, key_material, key_auth))
+    }
+
+    /// Change the authentication value of an existing key.
+    ///
+    /// The key is loaded with its current authentication value and a new, random,
+    /// authentication value of the given size is set on it. The resulting key is then
+    /// re-loaded with the new authentication value to ensure it is usable.
+    ///
+    /// If successful, the result contains the new [KeyMaterial] of the key and the new
+    /// authentication value. The previous key material remains valid with the old
+    /// authentication value, so it is up to the caller to discard it.
+    ///
+    /// # Constraints
+    /// * `auth_size` must be at most 32
+    ///
+    /// # Errors
+    /// * if the authentication size is larger than 32 a `WrongParamSize` wrapper error is returned
+    /// * if the key material does not contain a private part an `InvalidParam` wrapper error is returned
+    pub fn change_key_auth(
+        &mut self,
+        key_material: KeyMaterial,
+        key_params: KeyParams,
+        key_auth: Option<Auth>,
+        auth_size: usize,
+    ) -> Result<(KeyMaterial, Option<Auth>)> {
+        if key_material.private.is_empty() {
+            error!("Cannot change the authentication value of a public key");
+            return Err(Error::local_error(ErrorKind::InvalidParam));
+        }
+        let new_key_auth = self.generate_key_auth(auth_size)?;
+        let public = key_material.public.clone();
+
+        let key_handle = self.load_key(key_params, key_material, key_auth)?;
+        self.set_session_attrs()?;
+        let private = self
+            .context
+            .object_change_auth(
+                key_handle.into(),
+                self.root_key_handle.into(),
+                new_key_auth.clone().unwrap_or_default(),
+            )
+            .or_else(|e| {
+                self.context.flush_context(key_handle.into())?;
+                Err(e)
+            })?;
+        self.context.flush_context(key_handle.into())?;
+
+        let new_key_material = KeyMaterial {
+            public,
+            private: private.as_bytes().to_vec(),
+        };
+        let key_handle =
+            self.load_key(key_params, new_key_material.clone(), new_key_auth.clone())?;
+        self.context.flush_context(key_handle.into())?;
+
+        Ok((new_key_material, new_key_auth))
+    }
+
     /// Load the public part of a key.
     ///
     /// Returns the appropriate key material after verifying that the key can be loaded.
@@ -397,6 +503,25 @@ impl TransientKeyContext {
         Ok(key_material)
     }
 
+    /// Generates a random authentication value of the given size.
+    ///
+    /// No authentication value is generated if the size is 0.
+    ///
+    /// # Errors
+    /// * if the authentication size is larger than 32 a `WrongParamSize` wrapper error is returned
+    fn generate_key_auth(&mut self, auth_size: usize) -> Result<Option<Auth>> {
+        if auth_size > 32 {
+            return Err(Error::local_error(ErrorKind::WrongParamSize));
+        }
+        if auth_size > 0 {
+            self.set_session_attrs()?;
+            let random_bytes = self.context.get_random(auth_size)?;
+            Ok(Some(Auth::from_bytes(random_bytes.as_bytes())?))
+        } else {
+            Ok(None)
+        }
+    }
+
     /// Sets the encrypt and decrypt flags on the main session used by the context.
     ///
     /// # Errors
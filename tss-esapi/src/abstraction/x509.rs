@@ -0,0 +1,185 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    structures::{MaxBuffer, Signature},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use picky_asn1::{
+    bit_string::BitString,
+    wrapper::{Asn1SequenceOf, BitStringAsn1, IntegerAsn1},
+};
+use picky_asn1_der::Asn1RawDer;
+use serde::Serialize;
+use std::convert::TryFrom;
+
+/// The ECDSA-Sig-Value structure from RFC 3279.
+#[derive(Serialize)]
+struct EcdsaSignatureValue {
+    r: IntegerAsn1,
+    s: IntegerAsn1,
+}
+
+/// The Certificate structure from RFC 5280, with the TBSCertificate and the
+/// signature algorithm kept as they were encoded by the TPM.
+#[derive(Serialize)]
+struct Certificate {
+    tbs_certificate: Asn1RawDer,
+    signature_algorithm: Asn1RawDer,
+    signature_value: BitStringAsn1,
+}
+
+/// Creates the partial certificate that is used as input to
+/// `Context::certify_x509`.
+///
+/// # Arguments
+/// * `issuer` - The DER encoded issuer Name.
+/// * `validity` - The DER encoded Validity.
+/// * `subject` - The DER encoded subject Name.
+/// * `extensions` - The DER encoded, explicitly tagged, extensions.
+///
+/// # Details
+/// Each of the arguments needs to contain exactly one DER encoded element
+/// of the corresponding type from the TBSCertificate in RFC 5280.
+///
+/// # Errors
+/// * if any of the arguments is not a single DER element, an `InvalidParam`
+///   wrapper error is returned.
+/// * if the resulting partial certificate is too large, a `WrongParamSize`
+///   wrapper error is returned.
+pub fn partial_certificate(
+    issuer: &[u8],
+    validity: &[u8],
+    subject: &[u8],
+    extensions: &[u8],
+) -> Result<MaxBuffer> {
+    let elements = [issuer, validity, subject, extensions]
+        .iter()
+        .map(|element| single_element(element))
+        .collect::<Result<Vec<Asn1RawDer>>>()?;
+    MaxBuffer::try_from(encode(&Asn1SequenceOf(elements))?)
+}
+
+/// Merges the partial certificate with the fields that were added by the TPM.
+///
+/// # Arguments
+/// * `partial_certificate` - The partial certificate that was provided to the TPM.
+/// * `added_to_certificate` - The DER encoded fields that were returned by the TPM.
+///
+/// # Returns
+/// The DER encoded TBSCertificate that was signed by the TPM.
+///
+/// # Errors
+/// * if any of the arguments does not have the expected structure, an `InvalidParam`
+///   wrapper error is returned.
+pub fn tbs_certificate(partial_certificate: &[u8], added_to_certificate: &[u8]) -> Result<Vec<u8>> {
+    let partial_elements = sequence_elements(partial_certificate)?;
+    if partial_elements.len() < 3 {
+        error!("The partial certificate does not contain issuer, validity and subject");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    let added_elements = sequence_elements(added_to_certificate)?;
+    if added_elements.len() != 4 {
+        error!("The fields added to the certificate do not contain version, serial number, signature algorithm and subject public key info");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+
+    let elements = added_elements[..3]
+        .iter()
+        .chain(partial_elements[..3].iter())
+        .chain(added_elements[3..].iter())
+        .chain(partial_elements[3..].iter())
+        .cloned()
+        .collect::<Vec<Asn1RawDer>>();
+    encode(&Asn1SequenceOf(elements))
+}
+
+/// Creates a full certificate out of the results of `Context::certify_x509`.
+///
+/// # Arguments
+/// * `partial_certificate` - The partial certificate that was provided to the TPM.
+/// * `added_to_certificate` - The DER encoded fields that were returned by the TPM.
+/// * `signature` - The signature that was returned by the TPM.
+///
+/// # Returns
+/// The DER encoded certificate.
+///
+/// # Errors
+/// * if any of the arguments does not have the expected structure, an `InvalidParam`
+///   wrapper error is returned.
+/// * if the signature is not an RSA or ECC signature, an `UnsupportedParam`
+///   wrapper error is returned.
+pub fn certificate(
+    partial_certificate: &[u8],
+    added_to_certificate: &[u8],
+    signature: &Signature,
+) -> Result<Vec<u8>> {
+    let tbs_certificate = tbs_certificate(partial_certificate, added_to_certificate)?;
+    // The signature algorithm of the certificate is the same as in the TBSCertificate.
+    let signature_algorithm = sequence_elements(added_to_certificate)?.swap_remove(2);
+
+    let signature_value = match signature {
+        Signature::RsaSsa(rsa_signature) | Signature::RsaPss(rsa_signature) => {
+            rsa_signature.signature().as_bytes().to_vec()
+        }
+        Signature::EcDsa(ecc_signature)
+        | Signature::EcDaa(ecc_signature)
+        | Signature::Sm2(ecc_signature)
+        | Signature::EcSchnorr(ecc_signature) => encode(&EcdsaSignatureValue {
+            r: unsigned_integer(ecc_signature.signature_r().as_bytes()),
+            s: unsigned_integer(ecc_signature.signature_s().as_bytes()),
+        })?,
+        _ => {
+            error!("Signature algorithm is not supported in certificates");
+            return Err(Error::local_error(WrapperErrorKind::UnsupportedParam));
+        }
+    };
+    encode(&Certificate {
+        tbs_certificate: Asn1RawDer(tbs_certificate),
+        signature_algorithm,
+        signature_value: BitStringAsn1(BitString::with_bytes(signature_value)),
+    })
+}
+
+/// Returns the DER elements contained in a DER encoded SEQUENCE.
+fn sequence_elements(data: &[u8]) -> Result<Vec<Asn1RawDer>> {
+    let sequence = single_element(data)?;
+    picky_asn1_der::from_bytes::<Asn1SequenceOf<Asn1RawDer>>(&sequence.0)
+        .map(|elements| elements.0)
+        .map_err(|e| {
+            error!("Data is not a DER encoded SEQUENCE: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })
+}
+
+/// Checks that the data contains exactly one DER element.
+fn single_element(data: &[u8]) -> Result<Asn1RawDer> {
+    let element = picky_asn1_der::from_bytes::<Asn1RawDer>(data).map_err(|e| {
+        error!("Invalid DER element: {}", e);
+        Error::local_error(WrapperErrorKind::InvalidParam)
+    })?;
+    if element.0.len() != data.len() {
+        error!("Data contains more than one DER element");
+        return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+    }
+    Ok(element)
+}
+
+/// Encodes a value as DER.
+fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    picky_asn1_der::to_vec(value).map_err(|e| {
+        error!("Failed to encode the DER element: {}", e);
+        Error::local_error(WrapperErrorKind::InternalError)
+    })
+}
+
+/// Creates a DER INTEGER out of an unsigned big endian integer.
+fn unsigned_integer(value: &[u8]) -> IntegerAsn1 {
+    // The leading zeros of the fixed size TPM parameters are not allowed in DER.
+    let value = match value.iter().position(|&byte| byte != 0) {
+        Some(first_non_zero) => &value[first_non_zero..],
+        None => &[0][..],
+    };
+    IntegerAsn1::from_bytes_be_unsigned(value.to_vec())
+}
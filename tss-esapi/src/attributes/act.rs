@@ -0,0 +1,28 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tss2_esys::TPMA_ACT;
+use bitfield::bitfield;
+
+bitfield! {
+    /// Bitfield representing the authenticated countdown timer attributes.
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    pub struct ActAttributes(TPMA_ACT);
+    impl Debug;
+
+    pub signaled, _: 0;
+    pub preserve_signaled, _: 1;
+    // 31:2 Reserved
+}
+
+impl From<TPMA_ACT> for ActAttributes {
+    fn from(tpma_act: TPMA_ACT) -> Self {
+        ActAttributes(tpma_act)
+    }
+}
+
+impl From<ActAttributes> for TPMA_ACT {
+    fn from(act_attributes: ActAttributes) -> Self {
+        act_attributes.0
+    }
+}
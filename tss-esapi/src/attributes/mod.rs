@@ -21,6 +21,11 @@ pub mod algorithm;
 
 pub mod command_code;
 
+#[cfg(has_tpms_act_data)]
+pub mod act;
+
+#[cfg(has_tpms_act_data)]
+pub use act::ActAttributes;
 pub use algorithm::AlgorithmAttributes;
 pub use command_code::CommandCodeAttributes;
 pub use locality::{LocalityAttributes, LocalityAttributesBuilder};
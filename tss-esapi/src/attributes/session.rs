@@ -1,5 +1,9 @@
-use crate::{tss2_esys::TPMA_SESSION, Error, Result, WrapperErrorKind};
+use crate::{
+    constants::SessionType, structures::SymmetricDefinition, tss2_esys::TPMA_SESSION, Error,
+    Result, WrapperErrorKind,
+};
 use bitfield::bitfield;
+use log::error;
 use std::convert::TryFrom;
 
 // SESSION ATTRIBUTES
@@ -73,7 +77,7 @@ bitfield! {
     reserved, _: 4, 3; // Reserved 3,4 (Shall be clear)
     _, use_decrypt: 5;
     _, use_encrypt: 6;
-    _, use_audit: 7;
+    audit_is_used, use_audit: 7;
 }
 
 impl SessionAttributesMask {
@@ -115,10 +119,17 @@ impl TryFrom<SessionAttributesMask> for TPMA_SESSION {
 /// A builder that is used to create
 /// SessionAttributes and a corresponding
 /// SessionAttributesMask.
+///
+/// # Details
+/// The attributes built with [SessionAttributesBuilder::build] are not
+/// validated. [SessionAttributesBuilder::try_build] also validates the
+/// combination of attributes, see [SessionAttributesBuilder::validate].
 #[derive(Debug, Copy, Clone)]
 pub struct SessionAttributesBuilder {
     attributes: SessionAttributes,
     mask: SessionAttributesMask,
+    session_type: Option<SessionType>,
+    symmetric: Option<SymmetricDefinition>,
 }
 
 impl SessionAttributesBuilder {
@@ -126,9 +137,34 @@ impl SessionAttributesBuilder {
         SessionAttributesBuilder {
             attributes: SessionAttributes(0),
             mask: SessionAttributesMask(0),
+            session_type: None,
+            symmetric: None,
         }
     }
 
+    /// Creates a builder with the attributes of a session used to
+    /// encrypt the sensitive parameters of commands and responses.
+    ///
+    /// # Details
+    /// The `continueSession`, `decrypt` and `encrypt` attributes are set.
+    pub fn for_parameter_encryption() -> SessionAttributesBuilder {
+        SessionAttributesBuilder::new()
+            .with_continue_session(true)
+            .with_decrypt(true)
+            .with_encrypt(true)
+    }
+
+    /// Creates a builder with the attributes of a session used to
+    /// audit commands.
+    ///
+    /// # Details
+    /// The `continueSession` and `audit` attributes are set.
+    pub fn for_audit() -> SessionAttributesBuilder {
+        SessionAttributesBuilder::new()
+            .with_continue_session(true)
+            .with_audit(true)
+    }
+
     pub fn with_continue_session(mut self, set: bool) -> Self {
         self.attributes.set_continue_session(set);
         self.mask.use_continue_session(true);
@@ -165,9 +201,71 @@ impl SessionAttributesBuilder {
         self
     }
 
+    /// Sets the type of the session the attributes are built for, so that
+    /// it is taken into account by [SessionAttributesBuilder::validate].
+    pub const fn with_session_type(mut self, session_type: SessionType) -> Self {
+        self.session_type = Some(session_type);
+        self
+    }
+
+    /// Sets the symmetric algorithm of the session the attributes are built
+    /// for, so that it is taken into account by [SessionAttributesBuilder::validate].
+    pub const fn with_symmetric(mut self, symmetric: SymmetricDefinition) -> Self {
+        self.symmetric = Some(symmetric);
+        self
+    }
+
+    /// Validates the combination of attributes.
+    ///
+    /// # Details
+    /// The attributes that are not set by the builder are left unchanged
+    /// when the attributes are applied to a session, so only the
+    /// combinations that are known to be invalid are rejected:
+    /// * `auditExclusive` and `auditReset` require `audit`, so they cannot
+    ///   be set while `audit` is cleared.
+    /// * a trial session cannot authorize commands, so neither `decrypt`,
+    ///   `encrypt` nor `audit` can be set for it.
+    /// * parameter encryption requires a symmetric algorithm, so neither
+    ///   `decrypt` nor `encrypt` can be set for a session started with
+    ///   [SymmetricDefinition::Null].
+    ///
+    /// # Errors
+    /// * if the combination of attributes is invalid, an `InconsistentParams`
+    ///   wrapper error is returned
+    pub fn validate(&self) -> Result<()> {
+        let audit_is_cleared = self.mask.audit_is_used() && !self.attributes.audit();
+        if audit_is_cleared && self.attributes.audit_exclusive() {
+            error!("The auditExclusive attribute requires the audit attribute");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        if audit_is_cleared && self.attributes.audit_reset() {
+            error!("The auditReset attribute requires the audit attribute");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        let encrypts = self.attributes.decrypt() || self.attributes.encrypt();
+        if self.session_type == Some(SessionType::Trial) && (encrypts || self.attributes.audit()) {
+            error!("Trial sessions cannot be used for parameter encryption or audit");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        if self.symmetric == Some(SymmetricDefinition::Null) && encrypts {
+            error!("Parameter encryption requires a session with a symmetric algorithm");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        Ok(())
+    }
+
     pub fn build(self) -> (SessionAttributes, SessionAttributesMask) {
         (self.attributes, self.mask)
     }
+
+    /// Validates the combination of attributes, and builds them.
+    ///
+    /// # Errors
+    /// * the errors returned by [SessionAttributesBuilder::validate]
+    pub fn try_build(self) -> Result<(SessionAttributes, SessionAttributesMask)> {
+        self.validate()?;
+        Ok(self.build())
+    }
 }
 
 impl Default for SessionAttributesBuilder {
@@ -0,0 +1,51 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    constants::tss::{TPM_AT_ANY, TPM_AT_ERROR, TPM_AT_PV1, TPM_AT_VEND},
+    tss2_esys::TPM_AT,
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::convert::TryFrom;
+
+/// Enum representing the tags of the attributes of an attached component.
+///
+/// # Details
+/// This corresponds to TPM_AT. Values starting at TPM_AT_VEND are
+/// vendor-specific, and the offset from TPM_AT_VEND is kept in
+/// the `Vendor` variant.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AcAttributeTag {
+    Any,
+    Error,
+    Pv1,
+    Vendor(u32),
+}
+
+impl From<AcAttributeTag> for TPM_AT {
+    fn from(ac_attribute_tag: AcAttributeTag) -> TPM_AT {
+        match ac_attribute_tag {
+            AcAttributeTag::Any => TPM_AT_ANY,
+            AcAttributeTag::Error => TPM_AT_ERROR,
+            AcAttributeTag::Pv1 => TPM_AT_PV1,
+            AcAttributeTag::Vendor(offset) => TPM_AT_VEND | offset,
+        }
+    }
+}
+
+impl TryFrom<TPM_AT> for AcAttributeTag {
+    type Error = Error;
+
+    fn try_from(tpm_at: TPM_AT) -> Result<AcAttributeTag> {
+        match tpm_at {
+            TPM_AT_ANY => Ok(AcAttributeTag::Any),
+            TPM_AT_ERROR => Ok(AcAttributeTag::Error),
+            TPM_AT_PV1 => Ok(AcAttributeTag::Pv1),
+            _ if tpm_at & TPM_AT_VEND != 0 => Ok(AcAttributeTag::Vendor(tpm_at & !TPM_AT_VEND)),
+            _ => {
+                error!("value = {} did not match any AcAttributeTag.", tpm_at);
+                Err(Error::local_error(WrapperErrorKind::InvalidParam))
+            }
+        }
+    }
+}
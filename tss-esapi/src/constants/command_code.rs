@@ -150,3 +150,52 @@ impl From<CommandCode> for TPM2_CC {
         command_code.to_u32().unwrap()
     }
 }
+
+/// A vendor-specific command code.
+///
+/// # Details
+/// Vendor-specific command codes are the TPM2_CC values that have the
+/// vendor bit (`V`) set. They are defined by the TPM vendors, so they are
+/// not part of [CommandCode].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VendorCommandCode(TPM2_CC);
+
+impl VendorCommandCode {
+    /// The bit indicating a vendor-specific command code.
+    const VENDOR_BIT: TPM2_CC = 0x2000_0000;
+
+    /// Creates the vendor-specific command code with the given command index.
+    pub const fn new(command_index: u16) -> Self {
+        VendorCommandCode(Self::VENDOR_BIT | command_index as TPM2_CC)
+    }
+
+    /// Returns the command index of the vendor-specific command code.
+    pub const fn command_index(&self) -> u16 {
+        self.0 as u16
+    }
+}
+
+impl TryFrom<TPM2_CC> for VendorCommandCode {
+    type Error = Error;
+
+    fn try_from(tpm2_cc: TPM2_CC) -> Result<Self> {
+        if tpm2_cc & !(Self::VENDOR_BIT | TPM2_CC::from(u16::MAX)) != 0 {
+            error!("Encountered non zero reserved bits");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        if tpm2_cc & Self::VENDOR_BIT == 0 {
+            error!(
+                "The command code {:#010X} is not a vendor-specific command code",
+                tpm2_cc
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(VendorCommandCode(tpm2_cc))
+    }
+}
+
+impl From<VendorCommandCode> for TPM2_CC {
+    fn from(vendor_command_code: VendorCommandCode) -> Self {
+        vendor_command_code.0
+    }
+}
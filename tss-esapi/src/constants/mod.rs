@@ -59,8 +59,23 @@ pub mod command_code;
 /// Constants -> TPM_PT_PCR section of the specification.
 pub mod pcr_property_tag;
 
+/// Representation of the constants defined in
+/// Constants -> TPM_CLOCK_ADJUST section of the specification.
+pub mod clock_adjust;
+
+/// Representation of the constants defined in
+/// Constants -> TPM_AT section of the specification.
+pub mod ac_attribute_tag;
+
+/// Representation of the constants defined in
+/// Constants -> TPM_EO section of the specification.
+pub mod arithmetic_operation;
+
+pub use ac_attribute_tag::AcAttributeTag;
+pub use arithmetic_operation::ArithmeticOperation;
 pub use capabilities::CapabilityType;
-pub use command_code::CommandCode;
+pub use clock_adjust::ClockAdjust;
+pub use command_code::{CommandCode, VendorCommandCode};
 pub use ecc::EccCurveIdentifier;
 pub use nv_index_type::NvIndexType;
 pub use pcr_property_tag::PcrPropertyTag;
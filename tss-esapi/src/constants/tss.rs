@@ -313,6 +313,11 @@ pub const TPM2_CLOCK_FINE_FASTER: TPM2_CLOCK_ADJUST = 1; /* Speed the Clock upda
 pub const TPM2_CLOCK_MEDIUM_FASTER: TPM2_CLOCK_ADJUST = 2; /* Speed the Clock update rate by one medium adjustment step. */
 pub const TPM2_CLOCK_COARSE_FASTER: TPM2_CLOCK_ADJUST = 3; /* Speed the Clock update rate by one coarse adjustment step. */
 
+pub const TPM_AT_ANY: TPM_AT = 0x00000000; /* In a command, a non-specific request for AC information; in a response, indicates that outputData is not meaningful */
+pub const TPM_AT_ERROR: TPM_AT = 0x00000001; /* Indicates a TCG defined, device-specific error */
+pub const TPM_AT_PV1: TPM_AT = 0x00000002; /* Indicates the most significant 32 bits of a pairing value for the AC */
+pub const TPM_AT_VEND: TPM_AT = 0x80000000; /* Value added to a TPM_AT to indicate a vendor-specific tag value */
+
 pub const TPM2_EO_EQ: TPM2_EO = 0x0000; /* A  B */
 pub const TPM2_EO_NEQ: TPM2_EO = 0x0001; /* A  B */
 pub const TPM2_EO_SIGNED_GT: TPM2_EO = 0x0002; /* A > B signed */
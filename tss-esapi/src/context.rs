@@ -3,10 +3,13 @@
 mod handle_manager;
 use crate::{
     attributes::SessionAttributesBuilder,
-    constants::{CapabilityType, PropertyTag, SessionType},
-    handles::{ObjectHandle, SessionHandle},
+    constants::{AlgorithmIdentifier, CapabilityType, CommandCode, PropertyTag, SessionType},
+    handles::{
+        lock_transient_object_registry, EsysContextPtr, ObjectHandle, SessionHandle,
+        SharedTransientObjectRegistry,
+    },
     interface_types::{algorithm::HashingAlgorithm, session_handles::AuthSession},
-    structures::{CapabilityData, SymmetricDefinition},
+    structures::{CapabilityData, CapabilityItem, SymmetricDefinition},
     tcti_ldr::{TabrmdConfig, TctiContext, TctiNameConf},
     tss2_esys::*,
     Error, Result, ReturnCode, WrapperErrorKind as ErrorKind,
@@ -14,8 +17,10 @@ use crate::{
 use handle_manager::HandleManager;
 use log::{error, info};
 use mbox::MBox;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::ptr::null_mut;
+use std::sync::Arc;
 
 /// Safe abstraction over an ESYS_CONTEXT.
 ///
@@ -61,6 +66,18 @@ pub struct Context {
     handle_manager: HandleManager,
     /// A cache of determined TPM limits
     cached_tpm_properties: HashMap<PropertyTag, u32>,
+    /// A cache of the commands implemented by the TPM
+    cached_supported_commands: Option<HashSet<CommandCode>>,
+    /// A cache of the algorithms implemented by the TPM
+    cached_supported_algorithms: Option<HashSet<AlgorithmIdentifier>>,
+    /// Whether the sensitive parameters of commands are always encrypted
+    parameter_encryption: bool,
+    /// The policy used to retry commands failing with a transient error
+    retry_policy: RetryPolicy,
+    /// The transient objects guarded by a `TransientObject` or owned by a `Loaded` handle
+    transient_objects: SharedTransientObjectRegistry,
+    /// The auth values that are set again when objects are loaded
+    auth_registry: AuthRegistry,
 }
 
 // Implementation of the TPM commands
@@ -70,6 +87,16 @@ mod tpm_commands;
 mod session_administration;
 // Implementation of the general ESAPI ESYS_TR functions
 mod general_esys_tr;
+// Implementation of the automatic parameter encryption
+mod parameter_encryption;
+// Implementation of the retry of commands failing with a transient error
+mod retry;
+pub use retry::RetryPolicy;
+// Implementation of the guards of transient objects
+mod transient_object;
+// Implementation of the registry of auth values
+mod auth_registry;
+use auth_registry::AuthRegistry;
 
 impl Context {
     /// Create a new ESYS context based on the desired TCTI
@@ -106,13 +133,23 @@ impl Context {
         )?;
 
         let esys_context = unsafe { Some(MBox::from_raw(esys_context)) };
-        Ok(Context {
+        let mut context = Context {
             esys_context,
             sessions: (None, None, None),
             _tcti_context,
             handle_manager: HandleManager::new(),
             cached_tpm_properties: HashMap::new(),
-        })
+            cached_supported_commands: None,
+            cached_supported_algorithms: None,
+            parameter_encryption: false,
+            retry_policy: RetryPolicy::none(),
+            transient_objects: Default::default(),
+            auth_registry: Default::default(),
+        };
+        let esys_context = EsysContextPtr::new(context.mut_context());
+        lock_transient_object_registry(&context.transient_objects)
+            .set_esys_context(Some(esys_context));
+        Ok(context)
     }
 
     /// Create a new ESYS context based on the TAB Resource Manager Daemon.
@@ -252,6 +289,12 @@ impl Context {
     }
 
     /// Executes the closure with a single session set, and the others set to None
+    ///
+    /// # Details
+    /// When the session is a bound session, ESYS computes the HMAC of the
+    /// commands without the authorization value of the entity the session
+    /// is bound to, and with the authorization value of any other entity,
+    /// see [SessionBinding](crate::abstraction::session::SessionBinding).
     pub fn execute_with_session<F, T>(&mut self, session_handle: Option<AuthSession>, f: F) -> T
     where
         // We only need to call f once, so it can be FnOnce
@@ -374,6 +417,103 @@ impl Context {
         Ok(None)
     }
 
+    /// Determine if a command is implemented by the TPM
+    ///
+    /// # Details
+    /// The commands implemented by the TPM are retrieved with
+    /// [Context::get_capabilities_iter] on the first call and cached
+    /// for the subsequent calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, tcti_ldr::TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// use tss_esapi::constants::CommandCode;
+    ///
+    /// let supported = context
+    ///     .supports_command(CommandCode::PolicyAuthorizeNv)
+    ///     .expect("Failed to get the commands implemented by the TPM");
+    /// ```
+    pub fn supports_command(&mut self, command_code: CommandCode) -> Result<bool> {
+        if let Some(supported_commands) = &self.cached_supported_commands {
+            return Ok(supported_commands.contains(&command_code));
+        }
+
+        let items = self.execute_without_session(|ctx| {
+            ctx.get_capabilities_iter(CapabilityType::Command, 0)
+                .collect::<Result<Vec<_>>>()
+        })?;
+        let supported_commands = items
+            .into_iter()
+            .filter_map(|item| match item {
+                // Vendor specific commands are not represented by CommandCode
+                CapabilityItem::Command(command_code_attributes)
+                    if !command_code_attributes.is_vendor_specific() =>
+                {
+                    CommandCode::try_from(item.property()).ok()
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let supported = supported_commands.contains(&command_code);
+        self.cached_supported_commands = Some(supported_commands);
+        Ok(supported)
+    }
+
+    /// Determine if an algorithm is implemented by the TPM
+    ///
+    /// # Details
+    /// The algorithms implemented by the TPM are retrieved with
+    /// [Context::get_capabilities_iter] on the first call and cached
+    /// for the subsequent calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, tcti_ldr::TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// use tss_esapi::interface_types::algorithm::HashingAlgorithm;
+    ///
+    /// let supported = context
+    ///     .supports_algorithm(HashingAlgorithm::Sha384)
+    ///     .expect("Failed to get the algorithms implemented by the TPM");
+    /// ```
+    pub fn supports_algorithm<A>(&mut self, algorithm: A) -> Result<bool>
+    where
+        A: Into<AlgorithmIdentifier>,
+    {
+        let algorithm = algorithm.into();
+        if let Some(supported_algorithms) = &self.cached_supported_algorithms {
+            return Ok(supported_algorithms.contains(&algorithm));
+        }
+
+        let items = self.execute_without_session(|ctx| {
+            ctx.get_capabilities_iter(CapabilityType::Algorithms, 0)
+                .collect::<Result<Vec<_>>>()
+        })?;
+        let supported_algorithms = items
+            .into_iter()
+            .filter_map(|item| match item {
+                CapabilityItem::Algorithm(algorithm_property) => {
+                    Some(algorithm_property.algorithm_identifier())
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let supported = supported_algorithms.contains(&algorithm);
+        self.cached_supported_algorithms = Some(supported_algorithms);
+        Ok(supported)
+    }
+
     // ////////////////////////////////////////////////////////////////////////
     //  Private Methods Section
     // ////////////////////////////////////////////////////////////////////////
@@ -386,6 +526,19 @@ impl Context {
             .unwrap() // will only fail if called from Drop after .take()
     }
 
+    /// Private method executing the closure with the ESYS context locked
+    /// against the guards of the transient objects, which flush their object
+    /// through the ESYS context when they are dropped.
+    fn execute_locked<F, T>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Context) -> T,
+    {
+        let transient_objects = Arc::clone(&self.transient_objects);
+        let mut registry = lock_transient_object_registry(&transient_objects);
+        self.forget_flushed_objects(registry.take_flushed());
+        f(self)
+    }
+
     /// Private method for retrieving the ESYS session handle for
     /// the optional session 1.
     fn optional_session_1(&self) -> ESYS_TR {
@@ -441,6 +594,15 @@ impl Drop for Context {
     fn drop(&mut self) {
         info!("Closing context.");
 
+        // Stop the guards of the transient objects from flushing them, as
+        // the objects are flushed below.
+        let flushed = {
+            let mut registry = lock_transient_object_registry(&self.transient_objects);
+            registry.set_esys_context(None);
+            registry.take_flushed()
+        };
+        self.forget_flushed_objects(flushed);
+
         // Flush handles
         for handle in self.handle_manager.handles_to_flush() {
             info!("Flushing handle {}", ESYS_TR::from(handle));
@@ -0,0 +1,148 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    handles::ObjectHandle,
+    structures::{Auth, Name},
+    tss2_esys::ESYS_TR,
+    Context, Result,
+};
+use log::error;
+use std::{collections::HashMap, fmt};
+
+/// The callback supplying the auth value of an object from its name
+type AuthCallback = Box<dyn FnMut(&Name) -> Option<Auth> + Send>;
+
+/// The auth values of the objects, indexed by the names of the objects
+///
+/// # Details
+/// The names of the objects do not change when the objects are loaded
+/// again, contrary to their handles. The auth values are zeroized when
+/// they are dropped.
+#[derive(Default)]
+pub(crate) struct AuthRegistry {
+    auth_values: HashMap<Vec<u8>, Auth>,
+    callback: Option<AuthCallback>,
+}
+
+impl AuthRegistry {
+    /// Returns true if there is no auth value and no callback.
+    fn is_empty(&self) -> bool {
+        self.auth_values.is_empty() && self.callback.is_none()
+    }
+}
+
+impl fmt::Debug for AuthRegistry {
+    // The auth values are not printed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthRegistry")
+            .field("auth_values", &self.auth_values.len())
+            .field("callback", &self.callback.is_some())
+            .finish()
+    }
+}
+
+impl Context {
+    /// Sets the auth value of an object, and registers it.
+    ///
+    /// # Details
+    /// The auth value is set with [tr_set_auth](Context::tr_set_auth), and
+    /// is recorded together with the name of the object. It is set again
+    /// automatically when the object is loaded with
+    /// [context_load](Context::context_load), or restored with
+    /// [tr_deserialize](Context::tr_deserialize), so that the sessions
+    /// authorizing the object can use it.
+    pub fn register_auth(&mut self, object_handle: ObjectHandle, auth: Auth) -> Result<()> {
+        let name = self.tr_get_name(object_handle)?;
+        self.tr_set_auth(object_handle, auth.clone())?;
+        let _ = self
+            .auth_registry
+            .auth_values
+            .insert(name.value().to_vec(), auth);
+        Ok(())
+    }
+
+    /// Removes the registered auth value of an object.
+    ///
+    /// # Details
+    /// The auth value that has been set for the handle of the object is
+    /// not changed.
+    ///
+    /// # Returns
+    /// True if an auth value was registered for the object.
+    pub fn unregister_auth(&mut self, object_handle: ObjectHandle) -> Result<bool> {
+        let name = self.tr_get_name(object_handle)?;
+        Ok(self
+            .auth_registry
+            .auth_values
+            .remove(name.value())
+            .is_some())
+    }
+
+    /// Removes all the registered auth values.
+    pub fn clear_registered_auths(&mut self) {
+        self.auth_registry.auth_values.clear();
+    }
+
+    /// Sets the callback supplying the auth values of the objects that are
+    /// not registered.
+    ///
+    /// # Details
+    /// The callback is called with the name of an object when the object is
+    /// loaded with [context_load](Context::context_load), or restored with
+    /// [tr_deserialize](Context::tr_deserialize), and its auth value is not
+    /// registered. The auth value it returns, if any, is then registered.
+    pub fn set_auth_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Name) -> Option<Auth> + Send + 'static,
+    {
+        self.auth_registry.callback = Some(Box::new(callback));
+    }
+
+    /// Removes the callback supplying the auth values.
+    pub fn clear_auth_callback(&mut self) {
+        self.auth_registry.callback = None;
+    }
+
+    /// Sets the registered auth value of an object that has been loaded
+    /// or restored, if any.
+    ///
+    /// # Details
+    /// The errors are logged, as the object has been loaded even if its
+    /// auth value could not be set.
+    pub(crate) fn reapply_auth(&mut self, object_handle: ObjectHandle) {
+        if self.auth_registry.is_empty() {
+            return;
+        }
+        if let Err(e) = self.try_reapply_auth(object_handle) {
+            error!(
+                "Failed to set the registered auth value of Handle({}): {}",
+                ESYS_TR::from(object_handle),
+                e
+            );
+        }
+    }
+
+    /// Private method setting the registered auth value of an object, or
+    /// the auth value supplied by the callback.
+    fn try_reapply_auth(&mut self, object_handle: ObjectHandle) -> Result<()> {
+        let name = self.tr_get_name(object_handle)?;
+        if let Some(auth) = self.auth_registry.auth_values.get(name.value()).cloned() {
+            return self.tr_set_auth(object_handle, auth);
+        }
+        let auth = match self.auth_registry.callback.as_mut() {
+            Some(callback) => callback(&name),
+            None => None,
+        };
+        match auth {
+            Some(auth) => {
+                self.tr_set_auth(object_handle, auth.clone())?;
+                let _ = self
+                    .auth_registry
+                    .auth_values
+                    .insert(name.value().to_vec(), auth);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
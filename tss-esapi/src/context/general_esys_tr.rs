@@ -3,14 +3,19 @@
 use crate::{
     context::handle_manager::HandleDropAction,
     handles::ObjectHandle,
-    handles::{handle_conversion::TryIntoNotNone, TpmHandle},
+    handles::{handle_conversion::TryIntoNotNone, KeyHandle, TpmHandle},
     structures::Auth,
-    structures::Name,
-    tss2_esys::{Esys_TR_Close, Esys_TR_FromTPMPublic, Esys_TR_GetName, Esys_TR_SetAuth},
-    Context, Result, ReturnCode,
+    structures::{Name, Public},
+    tss2_esys::{
+        size_t, Esys_TR_Close, Esys_TR_Deserialize, Esys_TR_FromTPMPublic, Esys_TR_GetName,
+        Esys_TR_Serialize, Esys_TR_SetAuth,
+    },
+    utils::SerializedHandle,
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
 };
 use log::error;
-use std::convert::TryFrom;
+use mbox::MBox;
+use std::convert::{TryFrom, TryInto};
 use std::ptr::null_mut;
 use zeroize::Zeroize;
 
@@ -37,13 +42,15 @@ impl Context {
     /// ```
     pub fn tr_set_auth(&mut self, object_handle: ObjectHandle, auth: Auth) -> Result<()> {
         let mut auth_value = auth.into();
-        ReturnCode::ensure_success(
-            unsafe { Esys_TR_SetAuth(self.mut_context(), object_handle.into(), &auth_value) },
-            |ret| {
-                auth_value.buffer.zeroize();
-                error!("Error when setting authentication value: {:#010X}", ret);
-            },
-        )
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_TR_SetAuth(ctx.mut_context(), object_handle.into(), &auth_value) },
+                |ret| {
+                    auth_value.buffer.zeroize();
+                    error!("Error when setting authentication value: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Retrieve the name of an object from the object handle.
@@ -128,12 +135,14 @@ impl Context {
     /// ```
     pub fn tr_get_name(&mut self, object_handle: ObjectHandle) -> Result<Name> {
         let mut name_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe { Esys_TR_GetName(self.mut_context(), object_handle.into(), &mut name_ptr) },
-            |ret| {
-                error!("Error in getting name: {:#010X}", ret);
-            },
-        )?;
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_TR_GetName(ctx.mut_context(), object_handle.into(), &mut name_ptr) },
+                |ret| {
+                    error!("Error in getting name: {:#010X}", ret);
+                },
+            )
+        })?;
         Name::try_from(Context::ffi_data_to_owned(name_ptr))
     }
 
@@ -237,24 +246,26 @@ impl Context {
     /// ```
     pub fn tr_from_tpm_public(&mut self, tpm_handle: TpmHandle) -> Result<ObjectHandle> {
         let mut object = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_TR_FromTPMPublic(
-                    self.mut_context(),
-                    tpm_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut object,
-                )
-            },
-            |ret| {
-                error!(
-                    "Error when getting ESYS handle from TPM handle: {:#010X}",
-                    ret
-                );
-            },
-        )?;
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TR_FromTPMPublic(
+                        ctx.mut_context(),
+                        tpm_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut object,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when getting ESYS handle from TPM handle: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })?;
         self.handle_manager.add_handle(
             object.into(),
             if tpm_handle.may_be_flushed() {
@@ -266,6 +277,45 @@ impl Context {
         Ok(object.into())
     }
 
+    /// Used to construct an esys object from a transient or persistent object inside
+    /// the TPM, and to read its public area.
+    ///
+    /// # Arguments
+    /// * `tpm_handle` - The TPM handle of the transient or persistent object.
+    ///
+    /// # Returns
+    /// The handle to the ESYS object that was created, together with the public
+    /// area and the name of the object.
+    ///
+    /// # Details
+    /// This is [tr_from_tpm_public](Context::tr_from_tpm_public) followed by
+    /// [read_public](Context::read_public). If the public area can not be read,
+    /// the ESYS object is closed.
+    ///
+    /// # Errors
+    /// * if the TPM handle is not the handle of a transient or persistent object,
+    ///   an `InvalidParam` wrapper error is returned
+    pub fn object_from_tpm_handle(
+        &mut self,
+        tpm_handle: TpmHandle,
+    ) -> Result<(ObjectHandle, Public, Name)> {
+        if !matches!(
+            tpm_handle,
+            TpmHandle::Transient(_) | TpmHandle::Persistent(_)
+        ) {
+            error!("The TPM handle is not the handle of a transient or persistent object");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        let mut object_handle = self.tr_from_tpm_public(tpm_handle)?;
+        match self.read_public(KeyHandle::from(object_handle)) {
+            Ok((public, name, _)) => Ok((object_handle, public, name)),
+            Err(e) => {
+                let _ = self.tr_close(&mut object_handle);
+                Err(e)
+            }
+        }
+    }
+
     /// Instructs the ESAPI to release the metadata and resources allocated for a specific ObjectHandle.
     ///
     /// This is useful for cleaning up handles for which the context cannot be flushed.
@@ -357,12 +407,14 @@ impl Context {
     /// ```
     pub fn tr_close(&mut self, object_handle: &mut ObjectHandle) -> Result<()> {
         let mut rsrc_handle = object_handle.try_into_not_none()?;
-        ReturnCode::ensure_success(
-            unsafe { Esys_TR_Close(self.mut_context(), &mut rsrc_handle) },
-            |ret| {
-                error!("Error when closing an ESYS handle: {:#010X}", ret);
-            },
-        )?;
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_TR_Close(ctx.mut_context(), &mut rsrc_handle) },
+                |ret| {
+                    error!("Error when closing an ESYS handle: {:#010X}", ret);
+                },
+            )
+        })?;
 
         self.handle_manager.set_as_closed(*object_handle)?;
         *object_handle = ObjectHandle::from(rsrc_handle);
@@ -371,23 +423,114 @@ impl Context {
 
     #[cfg(has_esys_tr_get_tpm_handle)]
     /// Retrieve the `TpmHandle` stored in the given object.
+    ///
+    /// # Arguments
+    /// * `object_handle` - The [ObjectHandle] of the ESYS object.
+    ///
+    /// # Details
+    /// The TPM is not accessed. This is only available when the bindings are
+    /// generated for version 2.4.0 of the TSS libraries, or a later one.
     pub fn tr_get_tpm_handle(&mut self, object_handle: ObjectHandle) -> Result<TpmHandle> {
         use crate::{constants::tss::TPM2_RH_UNASSIGNED, tss2_esys::Esys_TR_GetTpmHandle};
         let mut tpm_handle = TPM2_RH_UNASSIGNED;
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_TR_GetTpmHandle(self.mut_context(), object_handle.into(), &mut tpm_handle)
-            },
-            |ret| {
-                error!(
-                    "Error when getting TPM handle from ESYS handle: {:#010X}",
-                    ret
-                );
-            },
-        )?;
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TR_GetTpmHandle(ctx.mut_context(), object_handle.into(), &mut tpm_handle)
+                },
+                |ret| {
+                    error!(
+                        "Error when getting TPM handle from ESYS handle: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })?;
         TpmHandle::try_from(tpm_handle)
     }
 
-    // Missing function: Esys_TR_Serialize
-    // Missing function: Esys_TR_Deserialize
+    /// Serializes the metadata of an ESYS object.
+    ///
+    /// # Arguments
+    /// * `object_handle` - The [ObjectHandle] of the object whose metadata is serialized.
+    ///
+    /// # Returns
+    /// The serialized metadata, which includes the name of the object.
+    ///
+    /// # Details
+    /// The metadata can be restored with [tr_deserialize](Context::tr_deserialize).
+    /// This is mostly useful for persistent objects and NV indexes, which outlive
+    /// the context.
+    pub fn tr_serialize(&mut self, object_handle: ObjectHandle) -> Result<SerializedHandle> {
+        let mut buffer_ptr = null_mut();
+        let mut buffer_size: size_t = 0;
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TR_Serialize(
+                        ctx.mut_context(),
+                        object_handle.into(),
+                        &mut buffer_ptr,
+                        &mut buffer_size,
+                    )
+                },
+                |ret| {
+                    error!("Error when serializing an ESYS handle: {:#010X}", ret);
+                },
+            )
+        })?;
+        if buffer_ptr.is_null() {
+            error!("Serialized ESYS handle is empty");
+            return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+        }
+        let buffer_size = usize::try_from(buffer_size).map_err(|e| {
+            error!(
+                "Failed to parse serialized ESYS handle size as usize: {}",
+                e
+            );
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+        let buffer = unsafe { MBox::<[u8]>::from_raw_parts(buffer_ptr, buffer_size) };
+        Ok(SerializedHandle::from(buffer.to_vec()))
+    }
+
+    /// Restores an ESYS object from its serialized metadata.
+    ///
+    /// # Arguments
+    /// * `serialized_handle` - The metadata returned by [tr_serialize](Context::tr_serialize).
+    ///
+    /// # Returns
+    /// A handle to the ESYS object that was restored.
+    ///
+    /// # Details
+    /// The TPM is not accessed, so the metadata is trusted as is. The handle is
+    /// closed, and not flushed, when the context is dropped. The auth value
+    /// registered for the object, if any, is set (see
+    /// [register_auth](Context::register_auth)).
+    pub fn tr_deserialize(&mut self, serialized_handle: &SerializedHandle) -> Result<ObjectHandle> {
+        let buffer = serialized_handle.buffer();
+        let mut object = ObjectHandle::None.into();
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TR_Deserialize(
+                        ctx.mut_context(),
+                        buffer.as_ptr(),
+                        buffer.len().try_into().map_err(|e| {
+                            error!("Failed to convert size of buffer to TSS size_t type: {}", e);
+                            Error::local_error(WrapperErrorKind::InvalidParam)
+                        })?,
+                        &mut object,
+                    )
+                },
+                |ret| {
+                    error!("Error when deserializing an ESYS handle: {:#010X}", ret);
+                },
+            )
+        })?;
+        self.handle_manager
+            .add_handle(object.into(), HandleDropAction::Close)?;
+        self.reapply_auth(object.into());
+        Ok(object.into())
+    }
 }
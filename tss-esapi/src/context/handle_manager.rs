@@ -128,6 +128,12 @@ impl HandleManager {
             .collect::<Vec<ObjectHandle>>()
     }
 
+    /// Returns the action to be taken for an open handle, or None if the
+    /// handle is not open.
+    pub fn drop_action(&self, handle: ObjectHandle) -> Option<HandleDropAction> {
+        self.open_handles.get(&handle).copied()
+    }
+
     /// Indicates if the manager has any open handles
     pub fn has_open_handles(&self) -> bool {
         !self.open_handles.is_empty()
@@ -0,0 +1,171 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    attributes::{SessionAttributes, SessionAttributesBuilder},
+    constants::CommandCode,
+    interface_types::session_handles::AuthSession,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+
+/// The parameters of a command that are sensitive, and how they are encrypted.
+#[derive(Debug, Copy, Clone)]
+struct SensitiveParameters {
+    /// The first command parameter is sensitive (`decrypt` attribute).
+    command: bool,
+    /// The first response parameter is sensitive (`encrypt` attribute).
+    response: bool,
+}
+
+impl SensitiveParameters {
+    /// Returns the sensitive parameters of a command, if any.
+    fn of(command_code: CommandCode) -> Option<Self> {
+        let (command, response) = match command_code {
+            CommandCode::Create
+            | CommandCode::CreatePrimary
+            | CommandCode::CreateLoaded
+            | CommandCode::LoadExternal
+            | CommandCode::Import
+            | CommandCode::ObjectChangeAuth
+            | CommandCode::HierarchyChangeAuth
+            | CommandCode::NvDefineSpace
+            | CommandCode::NvChangeAuth
+            | CommandCode::NvWrite => (true, false),
+            CommandCode::Unseal
+            | CommandCode::GetRandom
+            | CommandCode::NvRead
+            | CommandCode::RsaDecrypt => (false, true),
+            CommandCode::EncryptDecrypt2 => (true, true),
+            _ => return None,
+        };
+        Some(SensitiveParameters { command, response })
+    }
+
+    /// Returns the sensitive parameters that are not encrypted by the
+    /// attributes of the sessions.
+    ///
+    /// # Details
+    /// The TPM allows one session with the `decrypt` attribute and one
+    /// session with the `encrypt` attribute, which may be different sessions.
+    fn not_encrypted_with(&self, sessions_attributes: &[SessionAttributes]) -> Self {
+        SensitiveParameters {
+            command: self.command && !sessions_attributes.iter().any(|a| a.decrypt()),
+            response: self.response && !sessions_attributes.iter().any(|a| a.encrypt()),
+        }
+    }
+}
+
+impl Context {
+    /// Enables or disables the automatic encryption of sensitive parameters.
+    ///
+    /// # Details
+    /// When enabled, the `decrypt` and `encrypt` attributes are set, for the
+    /// duration of the command, on one of the sessions of the context for the
+    /// commands whose first command or response parameter is sensitive, e.g.
+    /// [create](Context::create), [import](Context::import), [unseal](Context::unseal),
+    /// [get_random](Context::get_random), [nv_read](Context::nv_read),
+    /// [nv_write](Context::nv_write) or the commands changing authorization values.
+    ///
+    /// The attributes that are missing are set on the first HMAC or policy
+    /// session of the context, unless the sessions already encrypt the
+    /// parameters, possibly with the `decrypt` and `encrypt` attributes set on
+    /// different sessions. If the context has no such session, these commands
+    /// fail with a `MissingAuthSession` wrapper error instead of sending the
+    /// parameters in clear. A session that does not use a symmetric algorithm
+    /// cannot encrypt the parameters, and the TPM rejects the command.
+    ///
+    /// Parameter encryption is disabled by default.
+    pub fn set_parameter_encryption(&mut self, enabled: bool) {
+        self.parameter_encryption = enabled;
+    }
+
+    /// Returns true if the automatic encryption of sensitive parameters is enabled.
+    pub fn parameter_encryption(&self) -> bool {
+        self.parameter_encryption
+    }
+
+    /// Executes a command with its sensitive parameters encrypted, if the
+    /// automatic parameter encryption is enabled.
+    pub(crate) fn execute_with_parameter_encryption<F, T>(
+        &mut self,
+        command_code: CommandCode,
+        f: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        let previous_attributes = self.enable_parameter_encryption(command_code)?;
+        let result = f(self);
+        if let Some((session, attributes)) = previous_attributes {
+            self.restore_parameter_encryption(session, attributes);
+        }
+        result
+    }
+
+    /// Private method setting the attributes encrypting the sensitive
+    /// parameters of a command on one of the sessions.
+    ///
+    /// Returns the session and its previous attributes, if they were changed.
+    fn enable_parameter_encryption(
+        &mut self,
+        command_code: CommandCode,
+    ) -> Result<Option<(AuthSession, SessionAttributes)>> {
+        let sensitive_parameters = match SensitiveParameters::of(command_code) {
+            Some(sensitive_parameters) if self.parameter_encryption => sensitive_parameters,
+            _ => return Ok(None),
+        };
+        let (session_1, session_2, session_3) = self.sessions;
+        let sessions = [session_1, session_2, session_3]
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|session| *session != AuthSession::Password)
+            .collect::<Vec<AuthSession>>();
+        let sessions_attributes = sessions
+            .iter()
+            .map(|session| self.tr_sess_get_attributes(*session))
+            .collect::<Result<Vec<SessionAttributes>>>()?;
+        let missing_attributes = sensitive_parameters.not_encrypted_with(&sessions_attributes);
+        if !missing_attributes.command && !missing_attributes.response {
+            return Ok(None);
+        }
+
+        let session = sessions.first().copied().ok_or_else(|| {
+            error!(
+                "No session can encrypt the sensitive parameters of {:?}",
+                command_code
+            );
+            Error::local_error(WrapperErrorKind::MissingAuthSession)
+        })?;
+        let previous_attributes = sessions_attributes[0];
+        let mut attributes_builder = SessionAttributesBuilder::new();
+        if missing_attributes.command {
+            attributes_builder = attributes_builder.with_decrypt(true);
+        }
+        if missing_attributes.response {
+            attributes_builder = attributes_builder.with_encrypt(true);
+        }
+        let (attributes, attributes_mask) = attributes_builder.build();
+        self.tr_sess_set_attributes(session, attributes, attributes_mask)?;
+        Ok(Some((session, previous_attributes)))
+    }
+
+    /// Private method restoring the attributes of a session after a command.
+    fn restore_parameter_encryption(
+        &mut self,
+        session: AuthSession,
+        attributes: SessionAttributes,
+    ) {
+        // A session that is not continued is flushed after the command.
+        if !attributes.continue_session() {
+            return;
+        }
+        let (attributes, attributes_mask) = SessionAttributesBuilder::new()
+            .with_decrypt(attributes.decrypt())
+            .with_encrypt(attributes.encrypt())
+            .build();
+        if let Err(e) = self.tr_sess_set_attributes(session, attributes, attributes_mask) {
+            error!("Failed to restore the attributes of the session: {}", e);
+        }
+    }
+}
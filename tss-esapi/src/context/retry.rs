@@ -0,0 +1,162 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    constants::TpmFormatZeroWarning,
+    error::{TpmFormatZeroResponseCode, TpmResponseCode},
+    Context, Error, Result, ReturnCode,
+};
+use log::warn;
+use std::{thread, time::Duration};
+
+/// The policy used to retry commands failing with a transient error
+///
+/// # Details
+/// The TPM returns a warning when it is temporarily unable to execute a
+/// command: `TPM_RC_RETRY`, `TPM_RC_YIELDED`, `TPM_RC_TESTING` or
+/// `TPM_RC_NV_RATE`. ESYS already resubmits the commands failing with the
+/// first three a few times, without waiting between the attempts. The policy
+/// retries the commands until `max_attempts` attempts have been made, waiting
+/// between the attempts for a backoff that starts at `initial_backoff` and
+/// doubles after every attempt, up to `max_backoff`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates a retry policy.
+    ///
+    /// # Details
+    /// A `max_attempts` of zero is handled as one attempt.
+    pub const fn new(max_attempts: u32, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff,
+            max_backoff,
+        }
+    }
+
+    /// Creates a retry policy that never retries the commands.
+    pub const fn none() -> Self {
+        RetryPolicy::new(1, Duration::from_millis(0), Duration::from_millis(0))
+    }
+
+    /// Returns the maximum number of attempts.
+    pub const fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns the backoff before the first retry.
+    pub const fn initial_backoff(&self) -> Duration {
+        self.initial_backoff
+    }
+
+    /// Returns the maximum backoff between two attempts.
+    pub const fn max_backoff(&self) -> Duration {
+        self.max_backoff
+    }
+
+    /// Returns the backoff after the failed attempt `attempt`, starting at 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+
+    /// Returns true if the error is a transient error, after which the
+    /// command can be retried.
+    pub fn is_transient(error: &Error) -> bool {
+        matches!(
+            error,
+            Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatZero(
+                TpmFormatZeroResponseCode::Warning(warning),
+            ))) if matches!(
+                warning.error_number(),
+                TpmFormatZeroWarning::Retry
+                    | TpmFormatZeroWarning::Yielded
+                    | TpmFormatZeroWarning::Testing
+                    | TpmFormatZeroWarning::NvRate
+            )
+        )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::none()
+    }
+}
+
+impl Context {
+    /// Sets the policy used to retry the commands of the context.
+    ///
+    /// # Details
+    /// Every command executed by the context is retried transparently, as
+    /// specified by the policy, when it fails with a transient error. By
+    /// default, the commands are not retried.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{Context, RetryPolicy, Tcti};
+    /// # use std::time::Duration;
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// context.set_retry_policy(RetryPolicy::new(
+    ///     5,
+    ///     Duration::from_millis(10),
+    ///     Duration::from_secs(1),
+    /// ));
+    /// let random_bytes = context.get_random(16).expect("Failed to get random bytes");
+    /// ```
+    pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+        self.retry_policy = retry_policy;
+    }
+
+    /// Returns the policy used to retry the commands of the context.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+
+    /// Executes the closure, and executes it again if it fails with a transient
+    /// error, as specified by the retry policy of the context.
+    ///
+    /// # Details
+    /// The closure is executed again from the start, so it should only execute
+    /// a single command.
+    ///
+    /// # Errors
+    /// * the error returned by the last execution of the closure
+    pub(crate) fn execute_with_retry<F, T>(&mut self, mut f: F) -> Result<T>
+    where
+        F: FnMut(&mut Context) -> Result<T>,
+    {
+        let retry_policy = self.retry_policy;
+        let mut attempt = 1;
+        loop {
+            match self.execute_locked(&mut f) {
+                Err(e)
+                    if attempt < retry_policy.max_attempts() && RetryPolicy::is_transient(&e) =>
+                {
+                    let backoff = retry_policy.backoff(attempt);
+                    warn!(
+                        "Transient error ({}), retrying in {:?} (attempt {}/{})",
+                        e,
+                        backoff,
+                        attempt,
+                        retry_policy.max_attempts()
+                    );
+                    thread::sleep(backoff);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
@@ -4,11 +4,13 @@ use crate::{
     attributes::{SessionAttributes, SessionAttributesMask},
     handles::SessionHandle,
     interface_types::session_handles::AuthSession,
-    tss2_esys::{Esys_TRSess_GetAttributes, Esys_TRSess_SetAttributes},
+    structures::Nonce,
+    tss2_esys::{Esys_TRSess_GetAttributes, Esys_TRSess_GetNonceTPM, Esys_TRSess_SetAttributes},
     Context, Result, ReturnCode,
 };
 use log::error;
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
+use std::ptr::null_mut;
 
 impl Context {
     /// Set the given attributes on a given session.
@@ -18,38 +20,79 @@ impl Context {
         attributes: SessionAttributes,
         mask: SessionAttributesMask,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_TRSess_SetAttributes(
-                    self.mut_context(),
-                    SessionHandle::from(session).into(),
-                    attributes.try_into()?,
-                    mask.try_into()?,
-                )
-            },
-            |ret| {
-                error!("Error when setting session attributes: {:#010X}", ret);
-            },
-        )
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TRSess_SetAttributes(
+                        ctx.mut_context(),
+                        SessionHandle::from(session).into(),
+                        attributes.try_into()?,
+                        mask.try_into()?,
+                    )
+                },
+                |ret| {
+                    error!("Error when setting session attributes: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Get session attribute flags.
+    ///
+    /// # Details
+    /// The attributes are the ones that are currently set on the session,
+    /// and can be inspected with the getters of [SessionAttributes], e.g.
+    /// [SessionAttributes::continue_session].
+    ///
+    /// # Errors
+    /// * if the attributes returned for the session have reserved bits set,
+    ///   an `InvalidParam` wrapper error is returned
     pub fn tr_sess_get_attributes(&mut self, session: AuthSession) -> Result<SessionAttributes> {
         let mut flags = 0;
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_TRSess_GetAttributes(
-                    self.mut_context(),
-                    SessionHandle::from(session).into(),
-                    &mut flags,
-                )
-            },
-            |ret| {
-                error!("Error when getting session attributes: {:#010X}", ret);
-            },
-        )?;
-        Ok(SessionAttributes(flags))
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TRSess_GetAttributes(
+                        ctx.mut_context(),
+                        SessionHandle::from(session).into(),
+                        &mut flags,
+                    )
+                },
+                |ret| {
+                    error!("Error when getting session attributes: {:#010X}", ret);
+                },
+            )
+        })?;
+        SessionAttributes::try_from(flags)
     }
 
-    // Missing function: Esys_TRSess_GetNonceTPM
+    /// Get the current nonce of the TPM for a session.
+    ///
+    /// # Details
+    /// The nonce is the last `nonceTPM` returned by the TPM for the session.
+    /// It changes each time the session is used, and can be used to bind an
+    /// authorization to the session, e.g. in the data signed for
+    /// [policy_signed](Context::policy_signed), which is required for an
+    /// authorization that expires.
+    pub fn session_nonce_tpm(&mut self, session: AuthSession) -> Result<Nonce> {
+        let mut nonce_tpm_ptr = null_mut();
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TRSess_GetNonceTPM(
+                        ctx.mut_context(),
+                        SessionHandle::from(session).into(),
+                        &mut nonce_tpm_ptr,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when getting the TPM nonce of the session: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })?;
+        Nonce::try_from(Context::ffi_data_to_owned(nonce_tpm_ptr))
+    }
 }
@@ -1,10 +1,15 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::CommandCode,
     handles::KeyHandle,
+    interface_types::{algorithm::EccKeyExchangeAlgorithm, ecc::EccCurve},
     structures::Data,
-    structures::{EccPoint, PublicKeyRsa, RsaDecryptionScheme},
-    tss2_esys::{Esys_ECDH_KeyGen, Esys_ECDH_ZGen, Esys_RSA_Decrypt, Esys_RSA_Encrypt},
+    structures::{AlgorithmDetailEcc, EccPoint, PublicKeyRsa, RsaDecryptionScheme},
+    tss2_esys::{
+        Esys_ECC_Parameters, Esys_ECDH_KeyGen, Esys_ECDH_ZGen, Esys_RSA_Decrypt, Esys_RSA_Encrypt,
+        Esys_ZGen_2Phase,
+    },
     Context, Result, ReturnCode,
 };
 use log::error;
@@ -21,24 +26,26 @@ impl Context {
         label: Data,
     ) -> Result<PublicKeyRsa> {
         let mut out_data_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_RSA_Encrypt(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &message.into(),
-                    &in_scheme.into(),
-                    &label.into(),
-                    &mut out_data_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when performing RSA encryption: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_RSA_Encrypt(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &message.clone().into(),
+                        &in_scheme.into(),
+                        &label.clone().into(),
+                        &mut out_data_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing RSA encryption: {:#010X}", ret);
+                },
+            )
+        })?;
         PublicKeyRsa::try_from(Context::ffi_data_to_owned(out_data_ptr))
     }
 
@@ -51,24 +58,28 @@ impl Context {
         label: Data,
     ) -> Result<PublicKeyRsa> {
         let mut message_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_RSA_Decrypt(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &cipher_text.into(),
-                    &in_scheme.into(),
-                    &label.into(),
-                    &mut message_ptr,
+        self.execute_with_parameter_encryption(CommandCode::RsaDecrypt, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_RSA_Decrypt(
+                            ctx.mut_context(),
+                            key_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &cipher_text.clone().into(),
+                            &in_scheme.into(),
+                            &label.clone().into(),
+                            &mut message_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error when performing RSA decryption: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error when performing RSA decryption: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         PublicKeyRsa::try_from(Context::ffi_data_to_owned(message_ptr))
     }
 
@@ -181,22 +192,24 @@ impl Context {
     pub fn ecdh_key_gen(&mut self, key_handle: KeyHandle) -> Result<(EccPoint, EccPoint)> {
         let mut z_point_ptr = null_mut();
         let mut pub_point_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ECDH_KeyGen(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut z_point_ptr,
-                    &mut pub_point_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when generating ECDH keypair: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ECDH_KeyGen(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut z_point_ptr,
+                        &mut pub_point_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when generating ECDH keypair: {:#010X}", ret);
+                },
+            )
+        })?;
 
         let z_point = Context::ffi_data_to_owned(z_point_ptr);
         let pub_point = Context::ffi_data_to_owned(pub_point_ptr);
@@ -317,26 +330,136 @@ impl Context {
     /// ```
     pub fn ecdh_z_gen(&mut self, key_handle: KeyHandle, in_point: EccPoint) -> Result<EccPoint> {
         let mut out_point_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ECDH_ZGen(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &in_point.into(),
-                    &mut out_point_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when performing ECDH ZGen: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ECDH_ZGen(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &in_point.clone().into(),
+                        &mut out_point_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing ECDH ZGen: {:#010X}", ret);
+                },
+            )
+        })?;
         let out_point = Context::ffi_data_to_owned(out_point_ptr);
         EccPoint::try_from(out_point.point)
     }
 
-    // Missing function: ECC_Parameters
-    // Missing function: ZGen_2Phase
+    /// Get the parameters of an ECC curve.
+    ///
+    /// # Arguments
+    /// * `curve` - The [EccCurve] for which the parameters are requested.
+    ///
+    /// # Details
+    /// The TPM returns an error if the curve is not supported.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, tcti_ldr::TctiNameConf,
+    /// #     interface_types::ecc::EccCurve,
+    /// # };
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// let parameters = context
+    ///     .ecc_parameters(EccCurve::NistP256)
+    ///     .expect("Call to ecc_parameters failed");
+    /// assert_eq!(parameters.ecc_curve(), EccCurve::NistP256);
+    /// assert_eq!(parameters.key_size(), 256);
+    /// ```
+    pub fn ecc_parameters(&mut self, curve: EccCurve) -> Result<AlgorithmDetailEcc> {
+        let mut parameters_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ECC_Parameters(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        curve.into(),
+                        &mut parameters_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when getting ECC parameters: {:#010X}", ret);
+                },
+            )
+        })?;
+        AlgorithmDetailEcc::try_from(Context::ffi_data_to_owned(parameters_ptr))
+    }
+
+    /// Perform the second phase of a two-phase key exchange.
+    ///
+    /// # Arguments
+    /// * `key_a` - A [KeyHandle] of an unrestricted ECC decryption key. This is
+    ///             party A's static key.
+    /// * `in_qs_b` - Party B's static public key as an [EccPoint].
+    /// * `in_qe_b` - Party B's ephemeral public key as an [EccPoint].
+    /// * `in_scheme` - The [EccKeyExchangeAlgorithm] to be used for the key exchange.
+    /// * `counter` - The value returned by [Context::ec_ephemeral] when party A's
+    ///               ephemeral key was generated.
+    ///
+    /// # Details
+    /// This command supports two-phase key exchange protocols. It is used in
+    /// combination with [Context::ec_ephemeral] which generates the
+    /// ephemeral key and returns the public point of that ephemeral key along with
+    /// a numeric value that allows the TPM to regenerate the associated private key.
+    ///
+    /// The `counter` value can only be used once, when this command has completed
+    /// successfully the TPM will no longer be able to regenerate the ephemeral
+    /// private key.
+    ///
+    /// The command returns a tuple consisting of:
+    /// * `out_z1` - The X and Y coordinates of the computed value (scheme dependent).
+    /// * `out_z2` - The X and Y coordinates of the second computed value (scheme dependent).
+    pub fn zgen_2phase(
+        &mut self,
+        key_a: KeyHandle,
+        in_qs_b: EccPoint,
+        in_qe_b: EccPoint,
+        in_scheme: EccKeyExchangeAlgorithm,
+        counter: u16,
+    ) -> Result<(EccPoint, EccPoint)> {
+        let mut out_z1_ptr = null_mut();
+        let mut out_z2_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ZGen_2Phase(
+                        ctx.mut_context(),
+                        key_a.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &in_qs_b.clone().into(),
+                        &in_qe_b.clone().into(),
+                        in_scheme.into(),
+                        counter,
+                        &mut out_z1_ptr,
+                        &mut out_z2_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing ZGen_2Phase: {:#010X}", ret);
+                },
+            )
+        })?;
+        let out_z1 = Context::ffi_data_to_owned(out_z1_ptr);
+        let out_z2 = Context::ffi_data_to_owned(out_z2_ptr);
+        Ok((
+            EccPoint::try_from(out_z1.point)?,
+            EccPoint::try_from(out_z2.point)?,
+        ))
+    }
 }
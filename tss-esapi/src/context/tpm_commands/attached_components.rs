@@ -1,9 +1,167 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::Context;
+#[cfg(has_esys_ac_commands)]
+use crate::{
+    constants::AcAttributeTag,
+    handles::{AuthHandle, ObjectHandle, SessionHandle},
+    interface_types::{
+        resource_handles::{AttachedComponent, NvAuth},
+        session_handles::PolicySession,
+        YesNo,
+    },
+    structures::{AcCapabilityList, AcOutput, MaxBuffer, Name},
+    tss2_esys::{Esys_AC_GetCapability, Esys_AC_Send, Esys_Policy_AC_SendSelect},
+    Result, ReturnCode,
+};
+#[cfg(has_esys_ac_commands)]
+use log::error;
+#[cfg(has_esys_ac_commands)]
+use std::{convert::TryFrom, ptr::null_mut};
 
 impl Context {
-    // Missing function: AC_GetCapability
-    // Missing function: AC_Send
-    // Missing function: Policy_AC_SendSelect
+    #[cfg(has_esys_ac_commands)]
+    /// Get the attributes of an attached component.
+    ///
+    /// # Arguments
+    /// * `ac` - The [AttachedComponent] that is queried.
+    /// * `capability` - The tag of the first attribute to return.
+    /// * `count` - The maximum number of attributes to return.
+    ///
+    /// # Returns
+    /// The attributes of the attached component and a boolean indicating
+    /// if there are more attributes available.
+    pub fn ac_get_capability(
+        &mut self,
+        ac: AttachedComponent,
+        capability: AcAttributeTag,
+        count: u32,
+    ) -> Result<(AcCapabilityList, bool)> {
+        let mut capability_data_ptr = null_mut();
+        let mut more_data = YesNo::No.into();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_AC_GetCapability(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        ObjectHandle::try_from(ac)?.into(),
+                        capability.into(),
+                        count,
+                        &mut more_data,
+                        &mut capability_data_ptr,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when getting attached component capabilities: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })?;
+
+        Ok((
+            AcCapabilityList::try_from(Context::ffi_data_to_owned(capability_data_ptr))?,
+            YesNo::try_from(more_data)?.into(),
+        ))
+    }
+
+    #[cfg(has_esys_ac_commands)]
+    /// Send an object to an attached component.
+    ///
+    /// # Arguments
+    /// * `send_object` - The handle of the object being sent.
+    /// * `auth_handle` - The handle indicating the source of the authorization
+    ///                   value of the attached component.
+    /// * `ac` - The [AttachedComponent] to which the object is sent.
+    /// * `ac_data_in` - Non-secret data that is sent to the attached component.
+    ///
+    /// # Details
+    /// This command requires authorization sessions for both `send_object`
+    /// and `auth_handle`.
+    ///
+    /// # Returns
+    /// The response of the attached component.
+    pub fn ac_send(
+        &mut self,
+        send_object: ObjectHandle,
+        auth_handle: NvAuth,
+        ac: AttachedComponent,
+        ac_data_in: MaxBuffer,
+    ) -> Result<AcOutput> {
+        let mut ac_data_out_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_AC_Send(
+                        ctx.mut_context(),
+                        send_object.into(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        ObjectHandle::try_from(ac)?.into(),
+                        &ac_data_in.into(),
+                        &mut ac_data_out_ptr,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when sending an object to an attached component: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })?;
+
+        AcOutput::try_from(Context::ffi_data_to_owned(ac_data_out_ptr))
+    }
+
+    #[cfg(has_esys_ac_commands)]
+    /// Allows qualification of the attached component and the object
+    /// to be sent in an [Context::ac_send] command.
+    ///
+    /// # Arguments
+    /// * `policy_session` - The [PolicySession] being extended.
+    /// * `object_name` - The [Name] of the object being sent.
+    /// * `auth_handle_name` - The [Name] associated with the `auth_handle`
+    ///                        used in the [Context::ac_send] command.
+    /// * `ac_name` - The [Name] of the attached component.
+    /// * `include_object` - Whether `object_name` is included in the
+    ///                      policy digest.
+    pub fn policy_ac_send_select(
+        &mut self,
+        policy_session: PolicySession,
+        object_name: Name,
+        auth_handle_name: Name,
+        ac_name: Name,
+        include_object: bool,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Policy_AC_SendSelect(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &object_name.into(),
+                        &auth_handle_name.into(),
+                        &ac_name.into(),
+                        YesNo::from(include_object).into(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when computing policy attached component send select: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
 }
@@ -1,11 +1,20 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    handles::{KeyHandle, ObjectHandle},
+    handles::{KeyHandle, ObjectHandle, SessionHandle},
+    interface_types::session_handles::{AuthSession, HmacSession},
     structures::{Attest, AttestBuffer, Data, PcrSelectionList, Signature, SignatureScheme},
-    tss2_esys::{Esys_Certify, Esys_Quote},
+    tss2_esys::{
+        Esys_Certify, Esys_GetCommandAuditDigest, Esys_GetSessionAuditDigest, Esys_GetTime,
+        Esys_Quote,
+    },
     Context, Result, ReturnCode,
 };
+#[cfg(has_esys_certify_x509)]
+use crate::{
+    structures::{Digest, MaxBuffer},
+    tss2_esys::Esys_CertifyX509,
+};
 use log::error;
 use std::convert::TryFrom;
 use std::ptr::null_mut;
@@ -124,25 +133,27 @@ impl Context {
     ) -> Result<(Attest, Signature)> {
         let mut certify_info_ptr = null_mut();
         let mut signature_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Certify(
-                    self.mut_context(),
-                    object_handle.into(),
-                    signing_key_handle.into(),
-                    self.required_session_1()?,
-                    self.required_session_2()?,
-                    self.optional_session_3(),
-                    &qualifying_data.into(),
-                    &signing_scheme.into(),
-                    &mut certify_info_ptr,
-                    &mut signature_ptr,
-                )
-            },
-            |ret| {
-                error!("Error in certifying: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Certify(
+                        ctx.mut_context(),
+                        object_handle.into(),
+                        signing_key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        &mut certify_info_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in certifying: {:#010X}", ret);
+                },
+            )
+        })?;
 
         let certify_info = Context::ffi_data_to_owned(certify_info_ptr);
         let signature = Context::ffi_data_to_owned(signature_ptr);
@@ -167,25 +178,27 @@ impl Context {
     ) -> Result<(Attest, Signature)> {
         let mut quoted_ptr = null_mut();
         let mut signature_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Quote(
-                    self.mut_context(),
-                    signing_key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &qualifying_data.into(),
-                    &signing_scheme.into(),
-                    &pcr_selection_list.into(),
-                    &mut quoted_ptr,
-                    &mut signature_ptr,
-                )
-            },
-            |ret| {
-                error!("Error in quoting PCR: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Quote(
+                        ctx.mut_context(),
+                        signing_key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        &pcr_selection_list.clone().into(),
+                        &mut quoted_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in quoting PCR: {:#010X}", ret);
+                },
+            )
+        })?;
 
         let quoted = Context::ffi_data_to_owned(quoted_ptr);
         let signature = Context::ffi_data_to_owned(signature_ptr);
@@ -195,8 +208,256 @@ impl Context {
         ))
     }
 
-    // Missing function: GetSessionAuditDigest
-    // Missing function: GestCommandAuditDigest
-    // Missing function: GetTime
-    // Missing function: CertifyX509
+    /// Get a signed copy of the audit digest of a session
+    ///
+    /// # Arguments
+    /// * `signing_key_handle` - Handle of the key used to sign the attestation buffer
+    /// * `session` - The audit session whose digest is signed
+    /// * `qualifying_data` - Qualifying data
+    /// * `signing_scheme` - Signing scheme to use if the scheme for `signing_key_handle` is `Null`.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the endorsement hierarchy
+    /// (the privacy administrator) and another one for the signing key. Neither of them
+    /// can be `session`.
+    ///
+    /// The attested data contains an [AttestInfo::SessionAudit](crate::structures::AttestInfo::SessionAudit)
+    /// with the audit digest of the session and whether the session has been exclusive.
+    /// See [crate::abstraction::audit] for a helper that manages the audit session.
+    ///
+    /// # Returns
+    /// The command returns a tuple consisting of:
+    /// * `attest_data` - TPM-generated attestation data.
+    /// * `signature` - Signature for the attestation data.
+    ///
+    /// # Errors
+    /// * if the qualifying data provided is too long, a `WrongParamSize` wrapper error will be returned
+    pub fn get_session_audit_digest(
+        &mut self,
+        signing_key_handle: KeyHandle,
+        session: HmacSession,
+        qualifying_data: Data,
+        signing_scheme: SignatureScheme,
+    ) -> Result<(Attest, Signature)> {
+        let mut audit_info_ptr = null_mut();
+        let mut signature_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetSessionAuditDigest(
+                        ctx.mut_context(),
+                        ObjectHandle::Endorsement.into(),
+                        signing_key_handle.into(),
+                        SessionHandle::from(AuthSession::from(session)).into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        &mut audit_info_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in getting session audit digest: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        let audit_info = Context::ffi_data_to_owned(audit_info_ptr);
+        let signature = Context::ffi_data_to_owned(signature_ptr);
+        Ok((
+            Attest::try_from(AttestBuffer::try_from(audit_info)?)?,
+            Signature::try_from(signature)?,
+        ))
+    }
+
+    /// Get a signed copy of the command audit digest
+    ///
+    /// # Arguments
+    /// * `signing_key_handle` - Handle of the key used to sign the attestation buffer
+    /// * `qualifying_data` - Qualifying data
+    /// * `signing_scheme` - Signing scheme to use if the scheme for `signing_key_handle` is `Null`.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the endorsement hierarchy
+    /// (the privacy administrator) and another one for the signing key.
+    ///
+    /// The attested data contains an [AttestInfo::CommandAudit](crate::structures::AttestInfo::CommandAudit)
+    /// with the audit counter, the audit digest and the digest of the list of audited
+    /// commands, which can be retrieved with [Attest::command_audit_info]. The audited
+    /// commands are selected with [Context::set_command_code_audit_status].
+    ///
+    /// If the signing key is not the `Null` handle, the audit digest is reset after
+    /// it has been signed.
+    ///
+    /// # Returns
+    /// The command returns a tuple consisting of:
+    /// * `attest_data` - TPM-generated attestation data.
+    /// * `signature` - Signature for the attestation data.
+    ///
+    /// # Errors
+    /// * if the qualifying data provided is too long, a `WrongParamSize` wrapper error will be returned
+    pub fn get_command_audit_digest(
+        &mut self,
+        signing_key_handle: KeyHandle,
+        qualifying_data: Data,
+        signing_scheme: SignatureScheme,
+    ) -> Result<(Attest, Signature)> {
+        let mut audit_info_ptr = null_mut();
+        let mut signature_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetCommandAuditDigest(
+                        ctx.mut_context(),
+                        ObjectHandle::Endorsement.into(),
+                        signing_key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        &mut audit_info_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in getting command audit digest: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        let audit_info = Context::ffi_data_to_owned(audit_info_ptr);
+        let signature = Context::ffi_data_to_owned(signature_ptr);
+        Ok((
+            Attest::try_from(AttestBuffer::try_from(audit_info)?)?,
+            Signature::try_from(signature)?,
+        ))
+    }
+
+    /// Get a signed copy of the current time and clock of the TPM
+    ///
+    /// # Arguments
+    /// * `signing_key_handle` - Handle of the key used to sign the attestation buffer
+    /// * `qualifying_data` - Qualifying data
+    /// * `signing_scheme` - Signing scheme to use if the scheme for `signing_key_handle` is `Null`.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the endorsement hierarchy
+    /// (the privacy administrator) and another one for the signing key.
+    ///
+    /// The attested data contains an [AttestInfo::Time](crate::structures::AttestInfo::Time) with
+    /// the time information. The signature over the attested data can be checked
+    /// using [crate::abstraction::attestation::verify_time_attestation].
+    ///
+    /// # Returns
+    /// The command returns a tuple consisting of:
+    /// * `attest_data` - TPM-generated attestation data.
+    /// * `signature` - Signature for the attestation data.
+    ///
+    /// # Errors
+    /// * if the qualifying data provided is too long, a `WrongParamSize` wrapper error will be returned
+    pub fn get_time(
+        &mut self,
+        signing_key_handle: KeyHandle,
+        qualifying_data: Data,
+        signing_scheme: SignatureScheme,
+    ) -> Result<(Attest, Signature)> {
+        let mut time_info_ptr = null_mut();
+        let mut signature_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetTime(
+                        ctx.mut_context(),
+                        ObjectHandle::Endorsement.into(),
+                        signing_key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        &mut time_info_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in getting time: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        let time_info = Context::ffi_data_to_owned(time_info_ptr);
+        let signature = Context::ffi_data_to_owned(signature_ptr);
+        Ok((
+            Attest::try_from(AttestBuffer::try_from(time_info)?)?,
+            Signature::try_from(signature)?,
+        ))
+    }
+
+    /// Certifies an object by producing a signed X.509 certificate for it.
+    ///
+    /// # Arguments
+    /// * `object_handle` - Handle of the object to be certified
+    /// * `signing_key_handle` - Handle of the key used to sign the certificate
+    /// * `signing_scheme` - Signing scheme to use if the scheme for `signing_key_handle` is `Null`.
+    /// * `partial_certificate` - A DER encoded partial certificate (see
+    ///                           [crate::abstraction::x509::partial_certificate]).
+    ///
+    /// # Details
+    /// The TPM adds the version, serial number, signature algorithm and subject public key
+    /// info fields to the `partial_certificate` and signs the resulting TBSCertificate.
+    ///
+    /// This command requires an authorization session for the object (with the admin role)
+    /// and another one for the signing key.
+    ///
+    /// # Returns
+    /// The command returns a tuple consisting of:
+    /// * `added_to_certificate` - A DER encoded SEQUENCE containing the fields that
+    ///                            were added to `partial_certificate` by the TPM.
+    /// * `tbs_digest` - The digest that was signed.
+    /// * `signature` - The signature over the TBSCertificate.
+    ///
+    /// The full certificate can be assembled using [crate::abstraction::x509::certificate].
+    #[cfg(has_esys_certify_x509)]
+    pub fn certify_x509(
+        &mut self,
+        object_handle: ObjectHandle,
+        signing_key_handle: KeyHandle,
+        signing_scheme: SignatureScheme,
+        partial_certificate: MaxBuffer,
+    ) -> Result<(MaxBuffer, Digest, Signature)> {
+        let mut added_to_certificate_ptr = null_mut();
+        let mut tbs_digest_ptr = null_mut();
+        let mut signature_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_CertifyX509(
+                        ctx.mut_context(),
+                        object_handle.into(),
+                        signing_key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &Data::default().into(),
+                        &signing_scheme.into(),
+                        &partial_certificate.into(),
+                        &mut added_to_certificate_ptr,
+                        &mut tbs_digest_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in certifying X509: {:#010X}", ret);
+                },
+            )
+        })?;
+        Ok((
+            MaxBuffer::try_from(Context::ffi_data_to_owned(added_to_certificate_ptr))?,
+            Digest::try_from(Context::ffi_data_to_owned(tbs_digest_ptr))?,
+            Signature::try_from(Context::ffi_data_to_owned(signature_ptr))?,
+        ))
+    }
 }
@@ -1,7 +1,47 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::Context;
+#[cfg(has_esys_act_set_timeout)]
+use crate::{
+    handles::ObjectHandle, interface_types::resource_handles::Act, tss2_esys::Esys_ACT_SetTimeout,
+    Result, ReturnCode,
+};
+#[cfg(has_esys_act_set_timeout)]
+use log::error;
 
 impl Context {
-    // Missing function: ACT_SetTimeout
+    #[cfg(has_esys_act_set_timeout)]
+    /// Set the timeout of an authenticated countdown timer (ACT).
+    ///
+    /// # Arguments
+    /// * `act_handle` - The [Act] whose timeout is set.
+    /// * `start_timeout` - The number of seconds before the ACT is signaled.
+    ///                     A value of 0 signals the ACT immediately.
+    ///
+    /// # Details
+    /// Setting a new timeout clears the signaled state of the ACT. The state
+    /// of the ACTs can be read with [Context::get_capability] using
+    /// [CapabilityType::Act](crate::constants::CapabilityType::Act).
+    ///
+    /// This command requires an authorization session for the ACT, whose
+    /// authorization value is set with `TPM2_HierarchyChangeAuth`.
+    pub fn act_set_timeout(&mut self, act_handle: Act, start_timeout: u32) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ACT_SetTimeout(
+                        ctx.mut_context(),
+                        ObjectHandle::from(act_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        start_timeout,
+                    )
+                },
+                |ret| {
+                    error!("Error when setting the ACT timeout: {:#010X}", ret);
+                },
+            )
+        })
+    }
 }
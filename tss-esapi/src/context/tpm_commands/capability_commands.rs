@@ -1,12 +1,18 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    constants::CapabilityType,
+    constants::{CapabilityType, PcrPropertyTag},
     interface_types::YesNo,
-    structures::{CapabilityData, PublicParameters},
+    structures::{
+        AlgorithmPropertyList, CapabilityData, CapabilityItem, CommandCodeAttributesList,
+        CommandCodeList, EccCurveList, HandleList, PcrSelectionList, PublicParameters,
+        TaggedPcrPropertyList, TaggedTpmPropertyList,
+    },
     tss2_esys::{Esys_GetCapability, Esys_TestParms},
-    Context, Result, ReturnCode,
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
 };
+#[cfg(has_tpms_act_data)]
+use crate::{handles::PermanentTpmHandle, structures::ActDataList, tss2_esys::TPM2_HANDLE};
 use log::{error, warn};
 use std::convert::TryFrom;
 use std::ptr::null_mut;
@@ -49,24 +55,26 @@ impl Context {
         let mut capability_data_ptr = null_mut();
         let mut more_data = YesNo::No.into();
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_GetCapability(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    capability.into(),
-                    property,
-                    property_count,
-                    &mut more_data,
-                    &mut capability_data_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when getting capabilities: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetCapability(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        capability.into(),
+                        property,
+                        property_count,
+                        &mut more_data,
+                        &mut capability_data_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when getting capabilities: {:#010X}", ret);
+                },
+            )
+        })?;
 
         Ok((
             CapabilityData::try_from(Context::ffi_data_to_owned(capability_data_ptr))?,
@@ -74,28 +82,239 @@ impl Context {
         ))
     }
 
+    /// Get an iterator over the capability information about the TPM.
+    ///
+    /// # Arguments
+    /// * `capability` - The [CapabilityType] to retrieve.
+    /// * `property` - The first property to retrieve, e.g. the first handle
+    ///                for [CapabilityType::Handles].
+    ///
+    /// # Details
+    /// The capability is retrieved with [Context::get_capability], which is
+    /// called again starting after the last returned item as long as the TPM
+    /// reports that more data is available.
+    ///
+    /// Errors are returned by the iterator, which ends after an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// use tss_esapi::{constants::CapabilityType, structures::CapabilityItem};
+    ///
+    /// for item in context.get_capabilities_iter(CapabilityType::Command, 0) {
+    ///     if let CapabilityItem::Command(command_code_attributes) =
+    ///         item.expect("Failed to get the commands")
+    ///     {
+    ///         println!("{:?}", command_code_attributes);
+    ///     }
+    /// }
+    /// ```
+    pub fn get_capabilities_iter(
+        &mut self,
+        capability: CapabilityType,
+        property: u32,
+    ) -> impl Iterator<Item = Result<CapabilityItem>> + '_ {
+        CapabilitiesIter {
+            context: self,
+            capability,
+            property: Some(property),
+            items: Vec::new().into_iter(),
+        }
+    }
+
+    /// Get the PCR properties of the TPM.
+    ///
+    /// # Details
+    /// The properties are retrieved with [Context::get_capabilities_iter]
+    /// using [CapabilityType::PcrProperties], and indicate the PCRs
+    /// that have each of the [PcrPropertyTag] properties.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// use tss_esapi::constants::PcrPropertyTag;
+    ///
+    /// let pcr_properties = context
+    ///     .pcr_properties()
+    ///     .expect("Failed to get the PCR properties");
+    /// if let Some(resettable) = pcr_properties.find(PcrPropertyTag::ResetL0) {
+    ///     println!("PCRs resettable from locality 0: {:?}", resettable.selected_pcrs());
+    /// }
+    /// ```
+    pub fn pcr_properties(&mut self) -> Result<TaggedPcrPropertyList> {
+        let tagged_pcr_selects = self
+            .get_capabilities_iter(CapabilityType::PcrProperties, PcrPropertyTag::Save.into())
+            .map(|item| match item? {
+                CapabilityItem::PcrProperty(tagged_pcr_select) => Ok(tagged_pcr_select),
+                _ => Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        TaggedPcrPropertyList::try_from(tagged_pcr_selects)
+    }
+
+    #[cfg(has_tpms_act_data)]
+    /// Get the state of the authenticated countdown timers (ACT) of the TPM.
+    ///
+    /// # Details
+    /// The state is retrieved with [Context::get_capabilities_iter]
+    /// using [CapabilityType::Act]. The list is empty if the TPM does
+    /// not implement any ACT, and TPMs implementing a version of the
+    /// specification without ACTs return an error.
+    pub fn act_capabilities(&mut self) -> Result<ActDataList> {
+        let act_data = self
+            .get_capabilities_iter(
+                CapabilityType::Act,
+                TPM2_HANDLE::from(PermanentTpmHandle::AuthenticatedTimersFirst),
+            )
+            .map(|item| match item? {
+                CapabilityItem::Act(act_data) => Ok(act_data),
+                _ => Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ActDataList::try_from(act_data)
+    }
+
     /// Test if the given parameters are supported by the TPM.
     ///
+    /// # Arguments
+    /// * `public_parameters` - The [PublicParameters] to test.
+    ///
+    /// # Details
+    /// This allows checking if a key template is supported by the TPM
+    /// before attempting to create the object. The parameters of a
+    /// template can be retrieved with [Public::parameters](crate::structures::Public::parameters).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// use tss_esapi::{
+    ///     interface_types::{algorithm::HashingAlgorithm, ecc::EccCurve},
+    ///     structures::{EccScheme, HashScheme, PublicEccParametersBuilder, PublicParameters},
+    /// };
+    ///
+    /// let ecc_parameters = PublicEccParametersBuilder::new_unrestricted_signing_key(
+    ///     EccScheme::EcDsa(HashScheme::new(HashingAlgorithm::Sha256)),
+    ///     EccCurve::NistP256,
+    /// )
+    /// .build()
+    /// .expect("Failed to build ECC parameters");
+    ///
+    /// context
+    ///     .test_parms(PublicParameters::Ecc(ecc_parameters))
+    ///     .expect("The TPM does not support the parameters");
+    /// ```
+    ///
     /// # Errors
     /// * if any of the public parameters is not compatible with the TPM,
     /// an `Err` containing the specific unmarshalling error will be returned.
-    pub fn test_parms(&mut self, public_parmeters: PublicParameters) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_TestParms(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &public_parmeters.into(),
-                )
-            },
-            |ret| {
-                warn!(
-                    "Parameters under test could not be unmarshalled: {:#010X}",
-                    ret
-                );
-            },
-        )
+    pub fn test_parms(&mut self, public_parameters: PublicParameters) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_TestParms(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &public_parameters.into(),
+                    )
+                },
+                |ret| {
+                    warn!(
+                        "Parameters under test could not be unmarshalled: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
     }
 }
+
+/// Iterator returned by [Context::get_capabilities_iter].
+struct CapabilitiesIter<'a> {
+    context: &'a mut Context,
+    capability: CapabilityType,
+    // The property of the next call to get_capability,
+    // None when the TPM has no more data.
+    property: Option<u32>,
+    items: std::vec::IntoIter<CapabilityItem>,
+}
+
+impl CapabilitiesIter<'_> {
+    /// Retrieves the next items of the capability.
+    fn fetch(&mut self, property: u32) -> Result<()> {
+        let (capability_data, more_data) = self.context.get_capability(
+            self.capability,
+            property,
+            max_property_count(self.capability),
+        )?;
+        let items = Vec::<CapabilityItem>::from(capability_data);
+        self.property = match (more_data, items.last()) {
+            (false, _) => None,
+            (true, Some(last)) => last.property().checked_add(1),
+            (true, None) => {
+                error!("The TPM reported more capability data without returning any");
+                return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+            }
+        };
+        self.items = items.into_iter();
+        Ok(())
+    }
+}
+
+impl Iterator for CapabilitiesIter<'_> {
+    type Item = Result<CapabilityItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+            let property = self.property.take()?;
+            if let Err(e) = self.fetch(property) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Returns the maximum number of items of the capability
+/// that fit in a response.
+fn max_property_count(capability: CapabilityType) -> u32 {
+    let max_size = match capability {
+        CapabilityType::Algorithms => AlgorithmPropertyList::MAX_SIZE,
+        CapabilityType::Handles => HandleList::MAX_SIZE,
+        CapabilityType::Command => CommandCodeAttributesList::MAX_SIZE,
+        CapabilityType::PpCommands | CapabilityType::AuditCommands => CommandCodeList::MAX_SIZE,
+        CapabilityType::AssignedPcr => PcrSelectionList::MAX_SIZE,
+        CapabilityType::TpmProperties => TaggedTpmPropertyList::MAX_SIZE,
+        CapabilityType::PcrProperties => TaggedPcrPropertyList::MAX_SIZE,
+        CapabilityType::EccCurves => EccCurveList::MAX_SIZE,
+        #[cfg(has_tpms_act_data)]
+        CapabilityType::Act => ActDataList::MAX_SIZE,
+        // Not supported by CapabilityData, the call will fail.
+        _ => 1,
+    };
+    max_size as u32
+}
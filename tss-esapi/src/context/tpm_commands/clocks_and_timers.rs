@@ -1,9 +1,116 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    constants::ClockAdjust,
+    handles::AuthHandle,
+    interface_types::resource_handles::Provision,
+    structures::TimeInfo,
+    tss2_esys::{Esys_ClockRateAdjust, Esys_ClockSet, Esys_ReadClock},
+    Context, Result, ReturnCode,
+};
+use log::error;
+use std::convert::TryFrom;
+use std::ptr::null_mut;
 
 impl Context {
-    // Missing function: ReadClock
-    // Missing function: ClockSet
-    // Missing function: ClockRateAdjust
+    /// Read the current time and clock of the TPM.
+    ///
+    /// # Details
+    /// The returned [TimeInfo] contains the time elapsed since the last
+    /// TPM reset or `TPM2_Startup(CLEAR)`, together with the [ClockInfo](crate::structures::ClockInfo)
+    /// holding the clock value, the reset and restart counts and the safe flag.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// let time_info = context.read_clock().expect("Failed to read the clock");
+    /// let _clock = time_info.clock_info().clock();
+    /// ```
+    pub fn read_clock(&mut self) -> Result<TimeInfo> {
+        let mut current_time_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ReadClock(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut current_time_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when reading the clock: {:#010X}", ret);
+                },
+            )
+        })?;
+        TimeInfo::try_from(Context::ffi_data_to_owned(current_time_ptr))
+    }
+
+    /// Advance the clock of the TPM.
+    ///
+    /// # Arguments
+    /// * `auth` - The [Provision] authorization handle.
+    /// * `new_time` - The new value of the clock, in milliseconds.
+    ///
+    /// # Details
+    /// The clock can only be moved forward, the TPM returns an error if `new_time`
+    /// is lower than the current clock value.
+    ///
+    /// This command requires an authorization session for the owner or
+    /// platform hierarchy.
+    pub fn clock_set(&mut self, auth: Provision, new_time: u64) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ClockSet(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        new_time,
+                    )
+                },
+                |ret| {
+                    error!("Error when setting the clock: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Adjust the update rate of the clock of the TPM.
+    ///
+    /// # Arguments
+    /// * `auth` - The [Provision] authorization handle.
+    /// * `rate_adjust` - The [ClockAdjust] to apply to the update rate.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the owner or
+    /// platform hierarchy.
+    pub fn clock_rate_adjust(&mut self, auth: Provision, rate_adjust: ClockAdjust) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ClockRateAdjust(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        rate_adjust.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when adjusting the clock rate: {:#010X}", ret);
+                },
+            )
+        })
+    }
 }
@@ -1,7 +1,59 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    handles::AuthHandle,
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Provision},
+    structures::CommandCodeList,
+    tss2_esys::{Esys_SetCommandCodeAuditStatus, TPML_CC},
+    Context, Result, ReturnCode,
+};
+use log::error;
 
 impl Context {
-    // Missing function: SetCommandCodeAuditStatus
+    /// Change the audit status of commands
+    ///
+    /// # Arguments
+    /// * `auth` - The [Provision] authorization handle.
+    /// * `audit_alg` - The [HashingAlgorithm] used for the command audit digest.
+    /// * `set_list` - A [CommandCodeList] with the commands to be added to the list of audited commands.
+    /// * `clear_list` - A [CommandCodeList] with the commands to be removed from the list of audited commands.
+    ///
+    /// # Details
+    /// If `audit_alg` is not [HashingAlgorithm::Null], the hashing algorithm of the audit
+    /// digest is changed and the audit digest is cleared. In that case both lists must be
+    /// empty, as the TPM does not change the list of audited commands at the same time.
+    ///
+    /// The current list of audited commands can be read with [Context::get_capability] using
+    /// [CapabilityType::AuditCommands](crate::constants::CapabilityType::AuditCommands)
+    /// and the audit digest can be retrieved with [Context::get_command_audit_digest].
+    ///
+    /// This command requires an authorization session for the owner or
+    /// platform hierarchy.
+    pub fn set_command_code_audit_status(
+        &mut self,
+        auth: Provision,
+        audit_alg: HashingAlgorithm,
+        set_list: CommandCodeList,
+        clear_list: CommandCodeList,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SetCommandCodeAuditStatus(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        audit_alg.into(),
+                        &TPML_CC::from(set_list.clone()),
+                        &TPML_CC::from(clear_list.clone()),
+                    )
+                },
+                |ret| {
+                    error!("Error when setting the command audit status: {:#010X}", ret);
+                },
+            )
+        })
+    }
 }
@@ -1,12 +1,29 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::{
+        tss::{
+            TPM2_ACTIVE_SESSION_FIRST, TPM2_HR_HANDLE_MASK, TPM2_LOADED_SESSION_FIRST,
+            TPM2_TRANSIENT_FIRST,
+        },
+        CapabilityType, SessionType,
+    },
     context::handle_manager::HandleDropAction,
-    handles::{handle_conversion::TryIntoNotNone, AuthHandle, ObjectHandle, PersistentTpmHandle},
-    interface_types::{dynamic_handles::Persistent, resource_handles::Provision},
-    tss2_esys::{Esys_ContextLoad, Esys_ContextSave, Esys_EvictControl, Esys_FlushContext},
-    utils::TpmsContext,
-    Context, Result, ReturnCode,
+    handles::{
+        handle_conversion::TryIntoNotNone, AuthHandle, KeyHandle, ObjectHandle,
+        PersistentTpmHandle, SessionHandle, TpmHandle,
+    },
+    interface_types::{
+        dynamic_handles::Persistent,
+        resource_handles::Provision,
+        session_handles::{AuthSession, HmacSession, PolicySession},
+    },
+    structures::CapabilityItem,
+    tss2_esys::{
+        Esys_ContextLoad, Esys_ContextSave, Esys_EvictControl, Esys_FlushContext, TPM2_HANDLE,
+    },
+    utils::{SavedSession, TpmsContext},
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
 };
 use log::error;
 use std::convert::{TryFrom, TryInto};
@@ -20,40 +37,162 @@ impl Context {
     /// be returned
     pub fn context_save(&mut self, handle: ObjectHandle) -> Result<TpmsContext> {
         let mut context_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe { Esys_ContextSave(self.mut_context(), handle.into(), &mut context_ptr) },
-            |ret| {
-                error!("Error in saving context: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_ContextSave(ctx.mut_context(), handle.into(), &mut context_ptr) },
+                |ret| {
+                    error!("Error in saving context: {:#010X}", ret);
+                },
+            )
+        })?;
         TpmsContext::try_from(Context::ffi_data_to_owned(context_ptr))
     }
 
     /// Load a previously saved context into the TPM and return the object handle.
     ///
+    /// # Details
+    /// The auth value registered for a transient object, if any, is set (see
+    /// [Context::register_auth]).
+    ///
     /// # Errors
     /// * if conversion from `TpmsContext` to the native `TPMS_CONTEXT` fails, a `WrongParamSize`
     /// error will be returned
     pub fn context_load(&mut self, context: TpmsContext) -> Result<ObjectHandle> {
+        let is_transient_object = matches!(
+            TpmHandle::try_from(context.saved_handle()),
+            Ok(TpmHandle::Transient(_))
+        );
         let mut esys_loaded_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ContextLoad(
-                    self.mut_context(),
-                    &context.try_into()?,
-                    &mut esys_loaded_handle,
-                )
-            },
-            |ret| {
-                error!("Error in loading context: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ContextLoad(
+                        ctx.mut_context(),
+                        &context.clone().try_into()?,
+                        &mut esys_loaded_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error in loading context: {:#010X}", ret);
+                },
+            )
+        })?;
         let loaded_handle = ObjectHandle::from(esys_loaded_handle);
         self.handle_manager
             .add_handle(loaded_handle, HandleDropAction::Flush)?;
+        if is_transient_object {
+            self.reapply_auth(loaded_handle);
+        }
         Ok(loaded_handle)
     }
 
+    /// Save the context of a transient key and return it.
+    ///
+    /// # Details
+    /// The key remains loaded, and can be flushed once its context has been
+    /// saved. The saved context can be serialized, either with `serde` or with
+    /// [Marshall](crate::traits::Marshall), and loaded again with
+    /// [Context::key_context_load], also by another process, which avoids
+    /// creating or loading the key again from its parent.
+    ///
+    /// The context of a key in the `Null` hierarchy can not be loaded after the
+    /// TPM has been reset.
+    ///
+    /// # Errors
+    /// * if the handle is not the handle of a transient object of the context,
+    ///   an `InvalidParam` error is returned
+    pub fn key_context_save(&mut self, key_handle: KeyHandle) -> Result<TpmsContext> {
+        if self.handle_manager.drop_action(key_handle.into()) != Some(HandleDropAction::Flush) {
+            error!("The handle is not the handle of a transient object of the context");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        self.context_save(key_handle.into())
+    }
+
+    /// Load the saved context of a transient key and return the key handle.
+    ///
+    /// # Errors
+    /// * if the saved context is not the context of a transient object, an
+    ///   `InvalidParam` error is returned
+    pub fn key_context_load(&mut self, context: TpmsContext) -> Result<KeyHandle> {
+        match TpmHandle::try_from(context.saved_handle()) {
+            Ok(TpmHandle::Transient(_)) => {}
+            _ => {
+                error!("The saved context is not the context of a transient object");
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+        }
+        self.context_load(context).map(KeyHandle::from)
+    }
+
+    /// Save the context of an authorization session and return it.
+    ///
+    /// # Details
+    /// Saving the context of a session frees the slot it used in the TPM,
+    /// so that other sessions can be loaded. The session is removed from
+    /// the sessions of the context, and its handle is no longer valid: the
+    /// session is used again by loading it with [Context::session_context_load].
+    ///
+    /// The TPM assigns a context ID to every saved session, and the difference
+    /// between the IDs of the oldest and the newest saved sessions is limited to
+    /// [PropertyTag::ContextGapMax](crate::constants::PropertyTag::ContextGapMax).
+    /// When saving a session would exceed it, the TPM returns a `ContextGap`
+    /// warning, and the oldest saved session must be loaded again (and saved
+    /// again, if needed) or flushed before another session can be saved.
+    ///
+    /// # Errors
+    /// * if `session` is [AuthSession::Password], an `InvalidParam` wrapper error
+    ///   is returned
+    pub fn session_context_save(&mut self, session: AuthSession) -> Result<SavedSession> {
+        let (session_type, hashing_algorithm) = match session {
+            AuthSession::HmacSession(HmacSession::HmacSession {
+                hashing_algorithm, ..
+            }) => (SessionType::Hmac, hashing_algorithm),
+            AuthSession::PolicySession(PolicySession::PolicySession {
+                hashing_algorithm,
+                session_type,
+                ..
+            }) => (session_type, hashing_algorithm),
+            AuthSession::Password => {
+                error!("The password session cannot be saved");
+                return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+            }
+        };
+        let handle = ObjectHandle::from(SessionHandle::from(session));
+        let context = self.context_save(handle)?;
+        self.handle_manager.set_as_flushed(handle)?;
+        let remove = |s: Option<AuthSession>| s.filter(|s| *s != session);
+        self.sessions = (
+            remove(self.sessions.0),
+            remove(self.sessions.1),
+            remove(self.sessions.2),
+        );
+        Ok(SavedSession::new(context, session_type, hashing_algorithm))
+    }
+
+    /// Load the saved context of an authorization session into the TPM and
+    /// return the session.
+    ///
+    /// # Details
+    /// The handle of the returned session may differ from the handle the
+    /// session had before it was saved. A saved session can only be loaded
+    /// once, unless it is saved again.
+    ///
+    /// # Errors
+    /// * the errors returned by [Context::context_load]
+    /// * if the TPM does not return a handle for the session, a `WrongValueFromTpm`
+    ///   wrapper error is returned
+    pub fn session_context_load(&mut self, saved_session: SavedSession) -> Result<AuthSession> {
+        let session_type = saved_session.session_type();
+        let hashing_algorithm = saved_session.hashing_algorithm();
+        let handle = self.context_load(saved_session.context().clone())?;
+        AuthSession::create(session_type, SessionHandle::from(handle), hashing_algorithm)
+            .ok_or_else(|| {
+                error!("Received an invalid handle when loading the session");
+                Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+            })
+    }
+
     /// Flush the context of an object from the TPM.
     ///
     /// # Example
@@ -134,15 +273,143 @@ impl Context {
     /// })
     /// ```
     pub fn flush_context(&mut self, handle: ObjectHandle) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe { Esys_FlushContext(self.mut_context(), handle.try_into_not_none()?) },
-            |ret| {
-                error!("Error in flushing context: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_FlushContext(ctx.mut_context(), handle.try_into_not_none()?) },
+                |ret| {
+                    error!("Error in flushing context: {:#010X}", ret);
+                },
+            )
+        })?;
+        self.unguard_transient_object(handle);
         self.handle_manager.set_as_flushed(handle)
     }
 
+    /// Flushes all the transient objects loaded in the TPM.
+    ///
+    /// # Details
+    /// The transient objects are enumerated with the handles capability, so
+    /// this also flushes the objects that were not loaded with this context,
+    /// e.g. by a process that crashed. When a resource manager is used, only
+    /// the objects of the connection of the context are visible. The objects
+    /// loaded with the context are flushed through its own handles, which
+    /// are then no longer valid.
+    ///
+    /// # Returns
+    /// The number of objects that were flushed.
+    ///
+    /// # Errors
+    /// * the first error returned when flushing an object. All the objects
+    ///   are flushed even if one of them fails.
+    pub fn flush_all_transient(&mut self) -> Result<usize> {
+        self.flush_all_handles(&[TPM2_TRANSIENT_FIRST])
+    }
+
+    /// Flushes all the loaded and saved sessions of the TPM.
+    ///
+    /// # Details
+    /// The sessions are enumerated with the handles capability, see
+    /// [Context::flush_all_transient]. The sessions of the context are
+    /// cleared.
+    ///
+    /// # Returns
+    /// The number of sessions that were flushed.
+    ///
+    /// # Errors
+    /// * the first error returned when flushing a session. All the sessions
+    ///   are flushed even if one of them fails.
+    pub fn flush_all_sessions(&mut self) -> Result<usize> {
+        self.clear_sessions();
+        self.flush_all_handles(&[TPM2_LOADED_SESSION_FIRST, TPM2_ACTIVE_SESSION_FIRST])
+    }
+
+    /// Private method flushing all the handles returned by the handles
+    /// capability for each of the `first` properties.
+    fn flush_all_handles(&mut self, first: &[TPM2_HANDLE]) -> Result<usize> {
+        let mut tpm_handles = Vec::new();
+        for &property in first {
+            let handles = self.execute_without_session(|ctx| {
+                ctx.get_capabilities_iter(CapabilityType::Handles, property)
+                    .map(|item| match item? {
+                        CapabilityItem::Handle(tpm_handle) => Ok(tpm_handle),
+                        _ => Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm)),
+                    })
+                    .collect::<Result<Vec<TpmHandle>>>()
+            })?;
+            for tpm_handle in handles {
+                if !tpm_handles.contains(&tpm_handle) {
+                    tpm_handles.push(tpm_handle);
+                }
+            }
+        }
+        // The objects and sessions of the context are flushed through the
+        // handles of the context, so that they are not flushed again when
+        // the context is dropped.
+        let own_handles = self
+            .handle_manager
+            .handles_to_flush()
+            .into_iter()
+            .filter_map(|handle| {
+                self.own_tpm_handle(handle)
+                    .map(|tpm_handle| (tpm_handle, handle))
+            })
+            .collect::<Vec<(TpmHandle, ObjectHandle)>>();
+        let mut flushed = 0;
+        let mut result = Ok(());
+        for tpm_handle in tpm_handles {
+            let own_handle = own_handles
+                .iter()
+                .find(|(own_tpm_handle, _)| is_same_tpm_handle(*own_tpm_handle, tpm_handle))
+                .map(|(_, handle)| *handle);
+            let flush_result = match own_handle {
+                Some(handle) => self.flush_context(handle),
+                None => self
+                    .execute_without_session(|ctx| ctx.tr_from_tpm_public(tpm_handle))
+                    .and_then(|handle| {
+                        // The policy and saved sessions are not flushed when
+                        // their handle is dropped, but they are flushed here.
+                        if self.handle_manager.drop_action(handle) == Some(HandleDropAction::Close)
+                        {
+                            self.handle_manager.set_as_closed(handle)?;
+                            self.handle_manager
+                                .add_handle(handle, HandleDropAction::Flush)?;
+                        }
+                        self.flush_context(handle)
+                    }),
+            };
+            match flush_result {
+                Ok(()) => flushed += 1,
+                Err(e) => {
+                    error!(
+                        "Failed to flush TPM handle {:#010X}",
+                        TPM2_HANDLE::from(tpm_handle)
+                    );
+                    result = result.and(Err(e));
+                }
+            }
+        }
+        result.map(|_| flushed)
+    }
+
+    /// Private method returning the TPM handle of a handle of the context,
+    /// if it can be retrieved.
+    #[cfg(has_esys_tr_get_tpm_handle)]
+    fn own_tpm_handle(&mut self, handle: ObjectHandle) -> Option<TpmHandle> {
+        self.tr_get_tpm_handle(handle).ok()
+    }
+
+    /// Private method returning the TPM handle of a handle of the context,
+    /// if it can be retrieved.
+    ///
+    /// # Details
+    /// The serialized metadata of an ESYS handle starts with the TPM handle.
+    #[cfg(not(has_esys_tr_get_tpm_handle))]
+    fn own_tpm_handle(&mut self, handle: ObjectHandle) -> Option<TpmHandle> {
+        let serialized_handle = self.tr_serialize(handle).ok()?;
+        let tpm_handle = serialized_handle.buffer().get(0..4)?.try_into().ok()?;
+        TpmHandle::try_from(TPM2_HANDLE::from_be_bytes(tpm_handle)).ok()
+    }
+
     /// Evicts persistent objects or allows certain transient objects
     /// to be made persistent.
     ///
@@ -416,23 +683,25 @@ impl Context {
         persistent: Persistent,
     ) -> Result<ObjectHandle> {
         let mut new_object_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_EvictControl(
-                    self.mut_context(),
-                    AuthHandle::from(auth).into(),
-                    object_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    PersistentTpmHandle::from(persistent).into(),
-                    &mut new_object_handle,
-                )
-            },
-            |ret| {
-                error!("Error in evict control: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_EvictControl(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth).into(),
+                        object_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        PersistentTpmHandle::from(persistent).into(),
+                        &mut new_object_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error in evict control: {:#010X}", ret);
+                },
+            )
+        })?;
         let new_object_handle = ObjectHandle::from(new_object_handle);
         // If you look at the specification and see that it says ESYS_TR_NULL
         // then that is an error in the spec. ESYS_TR_NULL was renamed to
@@ -446,3 +715,27 @@ impl Context {
         Ok(new_object_handle)
     }
 }
+
+/// Private function returning true if the TPM handles are handles of the same
+/// object or session.
+///
+/// # Details
+/// The handles of a session are compared without their type, as the handles
+/// capability reports the loaded sessions as HMAC sessions.
+fn is_same_tpm_handle(first: TpmHandle, second: TpmHandle) -> bool {
+    let is_session = |tpm_handle: TpmHandle| {
+        matches!(
+            tpm_handle,
+            TpmHandle::HmacSession(_)
+                | TpmHandle::LoadedSession(_)
+                | TpmHandle::PolicySession(_)
+                | TpmHandle::SavedSession(_)
+        )
+    };
+    if is_session(first) && is_session(second) {
+        TPM2_HANDLE::from(first) & TPM2_HR_HANDLE_MASK
+            == TPM2_HANDLE::from(second) & TPM2_HR_HANDLE_MASK
+    } else {
+        first == second
+    }
+}
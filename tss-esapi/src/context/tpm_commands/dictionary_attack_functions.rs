@@ -1,8 +1,86 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    handles::ObjectHandle,
+    interface_types::resource_handles::Lockout,
+    tss2_esys::{Esys_DictionaryAttackLockReset, Esys_DictionaryAttackParameters},
+    Context, Result, ReturnCode,
+};
+use log::error;
 
 impl Context {
-    // Missing function: DictionaryAttackLockReset
-    // Missing function: DictionaryAttackParameters
+    /// Cancel the effect of a TPM lockout due to a number of successive authorization failures.
+    ///
+    /// # Details
+    /// Resets the failure counter of the dictionary attack protection,
+    /// allowing objects that require authorization to be used again.
+    ///
+    /// # Arguments
+    /// * `lock_handle` - The [Lockout] authorization handle.
+    ///
+    /// This command requires an authorization session for the lockout hierarchy.
+    pub fn dictionary_attack_lock_reset(&mut self, lock_handle: Lockout) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_DictionaryAttackLockReset(
+                        ctx.mut_context(),
+                        ObjectHandle::from(lock_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error while resetting dictionary attack lockout: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
+
+    /// Change the lockout parameters.
+    ///
+    /// # Arguments
+    /// * `lock_handle` - The [Lockout] authorization handle.
+    /// * `new_max_tries` - Count of authorization failures before the lockout is imposed.
+    /// * `new_recovery_time` - Time in seconds before the authorization failure count
+    ///                         is automatically decremented. A value of zero disables
+    ///                         the dictionary attack protection.
+    /// * `lockout_recovery` - Time in seconds after a lockout authorization failure
+    ///                        before the lockout authorization can be used again.
+    ///
+    /// This command requires an authorization session for the lockout hierarchy.
+    pub fn dictionary_attack_parameters(
+        &mut self,
+        lock_handle: Lockout,
+        new_max_tries: u32,
+        new_recovery_time: u32,
+        lockout_recovery: u32,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_DictionaryAttackParameters(
+                        ctx.mut_context(),
+                        ObjectHandle::from(lock_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        new_max_tries,
+                        new_recovery_time,
+                        lockout_recovery,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error while setting dictionary attack parameters: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
 }
@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::Context;
 use crate::{
+    constants::CommandCode,
     handles::ObjectHandle,
-    structures::{Data, EncryptedSecret, Private, Public, SymmetricDefinitionObject},
-    tss2_esys::{Esys_Duplicate, Esys_Import},
+    structures::{Data, EncryptedSecret, Name, Private, Public, SymmetricDefinitionObject},
+    tss2_esys::{Esys_Duplicate, Esys_Import, Esys_Rewrap},
     Result, ReturnCode,
 };
 use log::error;
@@ -305,26 +306,28 @@ impl Context {
         let mut encryption_key_out_ptr = null_mut();
         let mut duplicate_ptr = null_mut();
         let mut out_sym_seed_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Duplicate(
-                    self.mut_context(),
-                    object_to_duplicate.into(),
-                    new_parent_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &encryption_key_in.unwrap_or_default().into(),
-                    &symmetric_alg.into(),
-                    &mut encryption_key_out_ptr,
-                    &mut duplicate_ptr,
-                    &mut out_sym_seed_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when performing duplication: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Duplicate(
+                        ctx.mut_context(),
+                        object_to_duplicate.into(),
+                        new_parent_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &encryption_key_in.clone().unwrap_or_default().into(),
+                        &symmetric_alg.into(),
+                        &mut encryption_key_out_ptr,
+                        &mut duplicate_ptr,
+                        &mut out_sym_seed_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing duplication: {:#010X}", ret);
+                },
+            )
+        })?;
 
         Ok((
             Data::try_from(Context::ffi_data_to_owned(encryption_key_out_ptr))?,
@@ -333,7 +336,68 @@ impl Context {
         ))
     }
 
-    // Missing function: Rewrap
+    /// Rewrap a duplicated object so that it may be imported under a different parent.
+    ///
+    /// # Details
+    /// This command allows the TPM to serve in the role as a Duplication Authority. The
+    /// outer wrapper of the duplicated object is removed using the seed that is decrypted
+    /// with `old_parent` and a new outer wrapper is then created using a seed that is
+    /// encrypted with `new_parent`.
+    ///
+    /// # Arguments
+    /// * `old_parent` - An [ObjectHandle] of the parent of the object referenced by `in_duplicate`.
+    ///                  If [ObjectHandle::Null] is used then `in_duplicate` is expected to not
+    ///                  have an outer wrapper.
+    /// * `new_parent` - An [ObjectHandle] of the new parent. If [ObjectHandle::Null] is used
+    ///                  then no outer wrapper is applied.
+    /// * `in_duplicate` - The duplicated object, as returned by [Context::duplicate].
+    /// * `name` - The [Name] of the duplicated object.
+    /// * `in_sym_seed` - The seed of the outer wrapper of `in_duplicate`, encrypted with
+    ///                   `old_parent`.
+    ///
+    /// This command requires an authorization session for `old_parent`.
+    ///
+    /// # Returns
+    /// The duplicated object with the new outer wrapper and the seed of the new outer wrapper,
+    /// encrypted with `new_parent`.
+    pub fn rewrap(
+        &mut self,
+        old_parent: ObjectHandle,
+        new_parent: ObjectHandle,
+        in_duplicate: Private,
+        name: Name,
+        in_sym_seed: EncryptedSecret,
+    ) -> Result<(Private, EncryptedSecret)> {
+        let mut out_duplicate_ptr = null_mut();
+        let mut out_sym_seed_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Rewrap(
+                        ctx.mut_context(),
+                        old_parent.into(),
+                        new_parent.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &in_duplicate.clone().into(),
+                        &name.clone().into(),
+                        &in_sym_seed.clone().into(),
+                        &mut out_duplicate_ptr,
+                        &mut out_sym_seed_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing rewrap: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        Ok((
+            Private::try_from(Context::ffi_data_to_owned(out_duplicate_ptr))?,
+            EncryptedSecret::try_from(Context::ffi_data_to_owned(out_sym_seed_ptr))?,
+        ))
+    }
 
     /// Import attaches imported object to a new parent.
     ///
@@ -663,26 +727,30 @@ impl Context {
         symmetric_alg: SymmetricDefinitionObject,
     ) -> Result<Private> {
         let mut out_private_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Import(
-                    self.mut_context(),
-                    parent_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &encryption_key.unwrap_or_default().into(),
-                    &public.try_into()?,
-                    &duplicate.into(),
-                    &encrypted_secret.into(),
-                    &symmetric_alg.into(),
-                    &mut out_private_ptr,
+        self.execute_with_parameter_encryption(CommandCode::Import, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_Import(
+                            ctx.mut_context(),
+                            parent_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &encryption_key.clone().unwrap_or_default().into(),
+                            &public.clone().try_into()?,
+                            &duplicate.clone().into(),
+                            &encrypted_secret.clone().into(),
+                            &symmetric_alg.into(),
+                            &mut out_private_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error when performing import: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error when performing import: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         Private::try_from(Context::ffi_data_to_owned(out_private_ptr))
     }
 }
@@ -2,19 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     attributes::LocalityAttributes,
-    constants::CommandCode,
-    handles::{AuthHandle, ObjectHandle, SessionHandle},
-    interface_types::{session_handles::PolicySession, YesNo},
+    constants::{CommandCode, TpmFormatOneError},
+    error::TpmResponseCode,
+    handles::{AuthHandle, NvIndexHandle, ObjectHandle, SessionHandle},
+    interface_types::{resource_handles::NvAuth, session_handles::PolicySession, YesNo},
     structures::{
-        AuthTicket, Digest, DigestList, Name, Nonce, PcrSelectionList, Signature, Timeout,
-        VerifiedTicket,
+        AuthTicket, CounterTimerCondition, Digest, DigestList, Name, Nonce, PcrSelectionList,
+        PolicyExpiration, Signature, Timeout, VerifiedTicket,
     },
     tss2_esys::{
-        Esys_PolicyAuthValue, Esys_PolicyAuthorize, Esys_PolicyCommandCode, Esys_PolicyCpHash,
-        Esys_PolicyDuplicationSelect, Esys_PolicyGetDigest, Esys_PolicyLocality,
-        Esys_PolicyNameHash, Esys_PolicyNvWritten, Esys_PolicyOR, Esys_PolicyPCR,
-        Esys_PolicyPassword, Esys_PolicyPhysicalPresence, Esys_PolicySecret, Esys_PolicySigned,
-        Esys_PolicyTemplate,
+        Esys_PolicyAuthValue, Esys_PolicyAuthorize, Esys_PolicyAuthorizeNV, Esys_PolicyCommandCode,
+        Esys_PolicyCounterTimer, Esys_PolicyCpHash, Esys_PolicyDuplicationSelect,
+        Esys_PolicyGetDigest, Esys_PolicyLocality, Esys_PolicyNameHash, Esys_PolicyNvWritten,
+        Esys_PolicyOR, Esys_PolicyPCR, Esys_PolicyPassword, Esys_PolicyPhysicalPresence,
+        Esys_PolicySecret, Esys_PolicySigned, Esys_PolicyTemplate, Esys_PolicyTicket,
+        TPMT_TK_VERIFIED,
     },
     Context, Error, Result, ReturnCode, WrapperErrorKind as ErrorKind,
 };
@@ -25,6 +27,32 @@ use std::time::Duration;
 
 impl Context {
     /// Cause the policy to include a signed authorization
+    ///
+    /// # Arguments
+    /// * `policy_session` - The policy session being extended.
+    /// * `auth_object` - The loaded public key of the authority that signed the authorization.
+    /// * `nonce_tpm` - The nonce of the policy session, or an empty nonce if the
+    ///                 authorization is not bound to the session.
+    /// * `cp_hash_a` - The command parameter hash the authorization is restricted
+    ///                 to, or an empty digest if it is not restricted.
+    /// * `policy_ref` - The reference to the policy that is authorized.
+    /// * `expiration` - The expiration of the authorization.
+    /// * `signature` - The signature of the authority over the digest of the data returned by
+    ///                 [signed_authorization_data](crate::abstraction::policy::signed_authorization_data).
+    ///
+    /// # Details
+    /// The command does not require an authorization session.
+    ///
+    /// # Returns
+    /// The timeout of the authorization and the ticket that can be used with
+    /// `PolicyTicket`. The ticket is only produced by the TPM if it was requested
+    /// with [PolicyExpiration::AfterWithTicket] and `nonce_tpm` is not empty,
+    /// otherwise a NULL ticket is returned.
+    ///
+    /// # Errors
+    /// * if the expiration is too long, or if a ticket is requested for an
+    ///   authorization that does not expire, an `InvalidParam` wrapper error
+    ///   is returned
     #[allow(clippy::too_many_arguments)]
     pub fn policy_signed(
         &mut self,
@@ -33,36 +61,35 @@ impl Context {
         nonce_tpm: Nonce,
         cp_hash_a: Digest,
         policy_ref: Nonce,
-        expiration: Option<Duration>,
+        expiration: PolicyExpiration,
         signature: Signature,
     ) -> Result<(Timeout, AuthTicket)> {
         let mut out_timeout_ptr = null_mut();
         let mut out_policy_ticket_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicySigned(
-                    self.mut_context(),
-                    auth_object.into(),
-                    SessionHandle::from(policy_session).into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &nonce_tpm.into(),
-                    &cp_hash_a.into(),
-                    &policy_ref.into(),
-                    i32::try_from(expiration.map_or(0, |v| v.as_secs())).map_err(|e| {
-                        error!("Unable to convert duration to i32: {}", e);
-                        Error::local_error(ErrorKind::InvalidParam)
-                    })?,
-                    &signature.try_into()?,
-                    &mut out_timeout_ptr,
-                    &mut out_policy_ticket_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when sending policy signed: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicySigned(
+                        ctx.mut_context(),
+                        auth_object.into(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &nonce_tpm.clone().into(),
+                        &cp_hash_a.clone().into(),
+                        &policy_ref.clone().into(),
+                        i32::try_from(expiration)?,
+                        &signature.clone().try_into()?,
+                        &mut out_timeout_ptr,
+                        &mut out_policy_ticket_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when sending policy signed: {:#010X}", ret);
+                },
+            )
+        })?;
         Ok((
             Timeout::try_from(Context::ffi_data_to_owned(out_timeout_ptr))?,
             AuthTicket::try_from(Context::ffi_data_to_owned(out_policy_ticket_ptr))?,
@@ -70,6 +97,10 @@ impl Context {
     }
 
     /// Cause the policy to require a secret in authValue
+    ///
+    /// # Details
+    /// See [policy_secret_with_expiration](Context::policy_secret_with_expiration),
+    /// where the expiration is given as a [PolicyExpiration].
     pub fn policy_secret(
         &mut self,
         policy_session: PolicySession,
@@ -78,40 +109,138 @@ impl Context {
         cp_hash_a: Digest,
         policy_ref: Nonce,
         expiration: Option<Duration>,
+    ) -> Result<(Timeout, AuthTicket)> {
+        self.policy_secret_with_expiration(
+            policy_session,
+            auth_handle,
+            nonce_tpm,
+            cp_hash_a,
+            policy_ref,
+            expiration.map_or(PolicyExpiration::Never, PolicyExpiration::After),
+        )
+    }
+
+    /// Cause the policy to require a secret in authValue, with a typed expiration
+    ///
+    /// # Arguments
+    /// * `policy_session` - The policy session being extended.
+    /// * `auth_handle` - The entity whose authorization value is required. It can be
+    ///                   an object, an NV index (e.g. converted from an [NvIndexHandle])
+    ///                   or a hierarchy (e.g. [AuthHandle::Owner]).
+    /// * `nonce_tpm` - The nonce of the policy session, or an empty nonce if the
+    ///                 authorization is not bound to the session.
+    /// * `cp_hash_a` - The command parameter hash the authorization is restricted
+    ///                 to, or an empty digest if it is not restricted.
+    /// * `policy_ref` - The reference to the policy that is authorized.
+    /// * `expiration` - The expiration of the authorization.
+    ///
+    /// # Details
+    /// The command requires an authorization session for `auth_handle`, which
+    /// is taken from the sessions of the context.
+    ///
+    /// # Returns
+    /// The timeout of the authorization and the ticket that can be used with
+    /// [policy_ticket](Context::policy_ticket). The ticket is only produced by
+    /// the TPM if it was requested with [PolicyExpiration::AfterWithTicket] and
+    /// `nonce_tpm` is not empty, otherwise a NULL ticket is returned.
+    ///
+    /// # Errors
+    /// * if the expiration is too long, or if a ticket is requested for an
+    ///   authorization that does not expire, an `InvalidParam` wrapper error
+    ///   is returned
+    pub fn policy_secret_with_expiration(
+        &mut self,
+        policy_session: PolicySession,
+        auth_handle: AuthHandle,
+        nonce_tpm: Nonce,
+        cp_hash_a: Digest,
+        policy_ref: Nonce,
+        expiration: PolicyExpiration,
     ) -> Result<(Timeout, AuthTicket)> {
         let mut out_timeout_ptr = null_mut();
         let mut out_policy_ticket_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicySecret(
-                    self.mut_context(),
-                    auth_handle.into(),
-                    SessionHandle::from(policy_session).into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &nonce_tpm.into(),
-                    &cp_hash_a.into(),
-                    &policy_ref.into(),
-                    i32::try_from(expiration.map_or(0, |v| v.as_secs())).map_err(|e| {
-                        error!("Unable to convert duration to i32: {}", e);
-                        Error::local_error(ErrorKind::InvalidParam)
-                    })?,
-                    &mut out_timeout_ptr,
-                    &mut out_policy_ticket_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when sending policy secret: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicySecret(
+                        ctx.mut_context(),
+                        auth_handle.into(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &nonce_tpm.clone().into(),
+                        &cp_hash_a.clone().into(),
+                        &policy_ref.clone().into(),
+                        i32::try_from(expiration)?,
+                        &mut out_timeout_ptr,
+                        &mut out_policy_ticket_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when sending policy secret: {:#010X}", ret);
+                },
+            )
+        })?;
         Ok((
             Timeout::try_from(Context::ffi_data_to_owned(out_timeout_ptr))?,
             AuthTicket::try_from(Context::ffi_data_to_owned(out_policy_ticket_ptr))?,
         ))
     }
 
-    // Missing function: PolicyTicket
+    /// Cause the policy to include an authorization given by a ticket
+    ///
+    /// # Arguments
+    /// * `policy_session` - The policy session being extended.
+    /// * `timeout` - The timeout returned together with the ticket.
+    /// * `cp_hash_a` - The command parameter hash the authorization is restricted to,
+    ///                 which must be the one used when the ticket was produced.
+    /// * `policy_ref` - The reference to the policy that is authorized, which must be
+    ///                  the one used when the ticket was produced.
+    /// * `auth_name` - The name of the object that produced the authorization, i.e. the
+    ///                 `auth_object` of [policy_signed](Context::policy_signed) or the
+    ///                 `auth_handle` of
+    ///                 [policy_secret_with_expiration](Context::policy_secret_with_expiration).
+    /// * `ticket` - The ticket produced by [policy_signed](Context::policy_signed) or
+    ///              [policy_secret_with_expiration](Context::policy_secret_with_expiration).
+    ///
+    /// # Details
+    /// A ticket allows an authorization to be used in several policy sessions
+    /// without producing a new one, e.g. without a new signature, until the
+    /// authorization expires.
+    ///
+    /// The command does not require an authorization session.
+    pub fn policy_ticket(
+        &mut self,
+        policy_session: PolicySession,
+        timeout: Timeout,
+        cp_hash_a: Digest,
+        policy_ref: Nonce,
+        auth_name: &Name,
+        ticket: AuthTicket,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyTicket(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &timeout.clone().into(),
+                        &cp_hash_a.clone().into(),
+                        &policy_ref.clone().into(),
+                        auth_name.as_ref(),
+                        &ticket.clone().try_into()?,
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy ticket: {:#010X}", ret);
+                },
+            )
+        })
+    }
 
     /// Cause conditional gating of a policy based on an OR'd condition.
     ///
@@ -138,21 +267,23 @@ impl Context {
             return Err(Error::local_error(ErrorKind::WrongParamSize));
         }
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyOR(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &digest_list.try_into()?,
-                )
-            },
-            |ret| {
-                error!("Error when computing policy OR: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyOR(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &digest_list.clone().try_into()?,
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy OR: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on PCR.
@@ -172,22 +303,24 @@ impl Context {
         pcr_policy_digest: Digest,
         pcr_selection_list: PcrSelectionList,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyPCR(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &pcr_policy_digest.into(),
-                    &pcr_selection_list.into(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy PCR: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyPCR(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &pcr_policy_digest.clone().into(),
+                        &pcr_selection_list.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy PCR: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on locality.
@@ -199,25 +332,107 @@ impl Context {
         policy_session: PolicySession,
         locality: LocalityAttributes,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyLocality(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    locality.into(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy locality: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyLocality(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        locality.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy locality: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     // Missing function: PolicyNV
-    // Missing function: PolicyCounterTimer
+
+    /// Cause conditional gating of a policy based on the time information of the TPM.
+    ///
+    /// # Arguments
+    /// * `policy_session` - The policy session being extended.
+    /// * `condition` - The condition on the time information, built with a
+    ///                 [CounterTimerConditionBuilder](crate::structures::CounterTimerConditionBuilder).
+    ///
+    /// # Details
+    /// The TPM will ensure that the condition holds for its current time information
+    /// (the time, the clock, the reset count, the restart count or whether the clock
+    /// is safe), e.g. to restrict the policy to the current boot cycle or to a period
+    /// of time.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf};
+    /// # use tss_esapi::{
+    /// #     constants::{ArithmeticOperation, SessionType},
+    /// #     interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+    /// #     structures::{CounterTimerConditionBuilder, CounterTimerField, SymmetricDefinition},
+    /// # };
+    /// # use std::convert::TryFrom;
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// # let policy_session = PolicySession::try_from(
+    /// #     context
+    /// #         .start_auth_session(
+    /// #             None,
+    /// #             None,
+    /// #             None,
+    /// #             SessionType::Trial,
+    /// #             SymmetricDefinition::AES_256_CFB,
+    /// #             HashingAlgorithm::Sha256,
+    /// #         )
+    /// #         .expect("Failed to start auth session")
+    /// #         .expect("Received invalid handle"),
+    /// # )
+    /// # .expect("Failed to convert auth session into policy session");
+    /// // Restrict the policy to the current boot cycle.
+    /// let reset_count = context.read_clock().expect("Failed to read clock").clock_info().reset_count();
+    /// let condition = CounterTimerConditionBuilder::new()
+    ///     .with_field(CounterTimerField::ResetCount)
+    ///     .with_operation(ArithmeticOperation::Eq)
+    ///     .with_value(reset_count.into())
+    ///     .build()
+    ///     .expect("Failed to build counter timer condition");
+    /// context
+    ///     .policy_counter_timer(policy_session, condition)
+    ///     .expect("Failed to call policy_counter_timer");
+    /// ```
+    pub fn policy_counter_timer(
+        &mut self,
+        policy_session: PolicySession,
+        condition: CounterTimerCondition,
+    ) -> Result<()> {
+        let offset = condition.offset();
+        let operation = condition.operation();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyCounterTimer(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &condition.operand_b().clone().into(),
+                        offset,
+                        operation.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy counter timer: {:#010X}", ret);
+                },
+            )
+        })
+    }
 
     /// Cause conditional gating of a policy based on command code of authorized command.
     ///
@@ -228,100 +443,158 @@ impl Context {
         policy_session: PolicySession,
         code: CommandCode,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyCommandCode(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    code.into(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy command code: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyCommandCode(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        code.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy command code: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on physical presence.
     ///
     /// The TPM will ensure that the current policy can only complete when physical
     /// presence is asserted. The way this is done is implementation-specific.
+    ///
+    /// # Details
+    /// The command itself does not check physical presence, it only records in
+    /// the session that physical presence is required. The check is done by the
+    /// TPM when the session is used to authorize a command, so the assertion is
+    /// typically made by platform firmware right before that command is executed.
+    ///
+    /// # Errors
+    /// * if physical presence is not asserted when the session is used, or if
+    ///   the TPM or the current locality does not support asserting physical
+    ///   presence, the authorized command fails with `TPM_RC_PP`. Use
+    ///   [execute_with_physical_presence](Context::execute_with_physical_presence)
+    ///   to get a [PhysicalPresenceRequired](ErrorKind::PhysicalPresenceRequired)
+    ///   error instead.
     pub fn policy_physical_presence(&mut self, policy_session: PolicySession) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyPhysicalPresence(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!(
-                    "Error when computing policy physical presence: {:#010X}",
-                    ret
-                );
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyPhysicalPresence(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when computing policy physical presence: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
+
+    /// Executes the closure with the policy session, which requires the assertion of
+    /// physical presence, set as the only session.
+    ///
+    /// # Errors
+    /// * if the TPM rejects the authorization with `TPM_RC_PP` because physical presence
+    ///   is not asserted, or cannot be asserted on the TPM or the current locality, a
+    ///   [PhysicalPresenceRequired](ErrorKind::PhysicalPresenceRequired) wrapper error
+    ///   is returned.
+    pub fn execute_with_physical_presence<F, T>(
+        &mut self,
+        policy_session: PolicySession,
+        f: F,
+    ) -> Result<T>
+    where
+        F: FnOnce(&mut Context) -> Result<T>,
+    {
+        self.execute_with_session(Some(policy_session.into()), f)
+            .map_err(|error| match error {
+                Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatOne(response_code)))
+                    if response_code.error_number() == TpmFormatOneError::Pp =>
+                {
+                    error!("The authorization requires the assertion of physical presence");
+                    Error::local_error(ErrorKind::PhysicalPresenceRequired)
+                }
+                error => error,
+            })
     }
 
     /// Cause conditional gating of a policy based on command parameters.
     ///
     /// The TPM will ensure that the current policy can only be used to authorize
     /// a command where the parameters are hashed into cp_hash_a.
+    ///
+    /// The expected hash of a command can be computed, without executing the
+    /// command, with [cp_hash](crate::abstraction::policy::cp_hash) or from the
+    /// data returned by [cp_hash_data](crate::abstraction::policy::cp_hash_data).
     pub fn policy_cp_hash(
         &mut self,
         policy_session: PolicySession,
         cp_hash_a: Digest,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyCpHash(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &cp_hash_a.into(),
-                )
-            },
-            |ret| {
-                error!(
-                    "Error when computing policy command parameters: {:#010X}",
-                    ret
-                );
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyCpHash(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &cp_hash_a.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when computing policy command parameters: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on name hash.
     ///
     /// The TPM will ensure that the current policy can only be used to authorize
     /// a command acting on an object whose name hashes to name_hash.
+    ///
+    /// The expected hash of the names can be computed with
+    /// [name_hash](crate::abstraction::policy::name_hash) or from the data
+    /// returned by [name_hash_data](crate::abstraction::policy::name_hash_data).
     pub fn policy_name_hash(
         &mut self,
         policy_session: PolicySession,
         name_hash: Digest,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyNameHash(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &name_hash.into(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy name hash: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyNameHash(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &name_hash.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy name hash: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on duplication parent's name.
@@ -337,6 +610,12 @@ impl Context {
     /// Set `include_object` only when this command is used in conjunction with
     /// [`policy_authorize`][Context::policy_authorize].
     ///
+    /// When `include_object` is not set, the policy digest does not depend on
+    /// `object_name`, so the digest can be computed in a trial session with an
+    /// empty name before the object exists. The name of the object must still
+    /// be provided when the policy is satisfied, as the TPM checks that the
+    /// `Duplicate` command is applied to that object and to the new parent.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -411,26 +690,28 @@ impl Context {
         new_parent_name: Name,
         include_object: bool,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyDuplicationSelect(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &object_name.into(),
-                    &new_parent_name.into(),
-                    YesNo::from(include_object).into(),
-                )
-            },
-            |ret| {
-                error!(
-                    "Error when computing policy duplication select: {:#010X}",
-                    ret
-                );
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyDuplicationSelect(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &object_name.clone().into(),
+                        &new_parent_name.clone().into(),
+                        YesNo::from(include_object).into(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when computing policy duplication select: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on an authorized policy
@@ -448,24 +729,27 @@ impl Context {
         key_sign: &Name,
         check_ticket: VerifiedTicket,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyAuthorize(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &approved_policy.into(),
-                    &policy_ref.into(),
-                    key_sign.as_ref(),
-                    &check_ticket.try_into()?,
-                )
-            },
-            |ret| {
-                error!("Error when computing policy authorize: {:#010X}", ret);
-            },
-        )
+        let check_ticket = TPMT_TK_VERIFIED::try_from(check_ticket)?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyAuthorize(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &approved_policy.clone().into(),
+                        &policy_ref.clone().into(),
+                        key_sign.as_ref(),
+                        &check_ticket,
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy authorize: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on authValue.
@@ -473,20 +757,22 @@ impl Context {
     /// The TPM will ensure that the current policy requires the user to know the authValue
     /// used when creating the object.
     pub fn policy_auth_value(&mut self, policy_session: PolicySession) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyAuthValue(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy auth value: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyAuthValue(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy auth value: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Cause conditional gating of a policy based on password.
@@ -494,44 +780,48 @@ impl Context {
     /// The TPM will ensure that the current policy requires the user to know the password
     /// used when creating the object.
     pub fn policy_password(&mut self, policy_session: PolicySession) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyPassword(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error when computing policy password: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyPassword(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy password: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Function for retrieving the current policy digest for
     /// the session.
     pub fn policy_get_digest(&mut self, policy_session: PolicySession) -> Result<Digest> {
         let mut policy_digest_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyGetDigest(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut policy_digest_ptr,
-                )
-            },
-            |ret| {
-                error!(
-                    "Error failed to perform policy get digest operation: {:#010X}.",
-                    ret
-                );
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyGetDigest(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut policy_digest_ptr,
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error failed to perform policy get digest operation: {:#010X}.",
+                        ret
+                    );
+                },
+            )
+        })?;
 
         Digest::try_from(Context::ffi_data_to_owned(policy_digest_ptr))
     }
@@ -539,29 +829,37 @@ impl Context {
     /// Cause conditional gating of a policy based on NV written state.
     ///
     /// The TPM will ensure that the NV index that is used has a specific written state.
+    ///
+    /// # Details
+    /// Used in the authorization policy of an NV index with `written_set` set to
+    /// `false`, e.g. together with [policy_command_code](Context::policy_command_code)
+    /// for `NV_Write`, the policy only allows writing the index while it has not been
+    /// written, which makes the index write-once.
     pub fn policy_nv_written(
         &mut self,
         policy_session: PolicySession,
         written_set: bool,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyNvWritten(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    written_set.into(),
-                )
-            },
-            |ret| {
-                error!(
-                    "Error when computing policy NV written state: {:#010X}",
-                    ret
-                );
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyNvWritten(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        written_set.into(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when computing policy NV written state: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
     }
 
     /// Bind policy to a specific creation template.
@@ -569,29 +867,84 @@ impl Context {
     /// # Arguments
     /// * `policy_session` - The [policy session][PolicySession] being extended.
     /// * `template_hash` - The [digest][Digest] to be added to the policy.
+    ///
+    /// # Details
+    /// The TPM will ensure that the policy can only be used to authorize the
+    /// creation of an object whose template hashes to `template_hash`. The hash
+    /// of a template can be computed with
+    /// [template_hash](crate::abstraction::policy::template_hash).
     pub fn policy_template(
         &mut self,
         policy_session: PolicySession,
         template_hash: Digest,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyTemplate(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &template_hash.into(),
-                )
-            },
-            |ret| {
-                error!(
-                    "Failed to bind template to a specific creation template: {:#010X}",
-                    ret
-                );
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyTemplate(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &template_hash.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Failed to bind template to a specific creation template: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
+
+    /// Cause conditional gating of a policy based on a policy stored in an NV index.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The handle used to authorize the reading of the NV index.
+    /// * `nv_index_handle` - The handle of the NV index holding the authorized policy.
+    /// * `policy_session` - The policy session being extended.
+    ///
+    /// # Details
+    /// The TPM will ensure that the current policy digest of the session equals the
+    /// policy digest stored in the NV index. If this is the case, the policy digest
+    /// of the session is replaced by a value derived from the name of the NV index,
+    /// so the policy of an object can be changed after its creation by writing a new
+    /// policy digest to the index.
+    ///
+    /// The NV index must be an ordinary index that has been written, and must hold a
+    /// `TPMT_HA` with the hashing algorithm of the session followed by the policy
+    /// digest. The index can be written in that layout with
+    /// [write_policy_digest](crate::abstraction::nv::write_policy_digest).
+    ///
+    /// # Errors
+    /// * if the current policy digest does not match the one of the NV index, the
+    ///   TPM returns an error
+    pub fn policy_authorize_nv(
+        &mut self,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+        policy_session: PolicySession,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyAuthorizeNV(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error when computing policy authorize NV: {:#010X}", ret);
+                },
+            )
+        })
     }
-    // Missing function: PolicyAuthorizeNV
 }
@@ -1,8 +1,137 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    handles::KeyHandle,
+    interface_types::ecc::EccCurve,
+    structures::{EccParameter, EccPoint, SensitiveData},
+    tss2_esys::{Esys_Commit, Esys_EC_Ephemeral, TPM2B_ECC_POINT},
+    Context, Result, ReturnCode,
+};
+use log::error;
+use std::convert::TryFrom;
+use std::ptr::null_mut;
 
 impl Context {
-    // Missing function: Commit
-    // Missing function: EC_Ephemeral
+    /// Perform the first part of an ECC anonymous signing operation.
+    ///
+    /// # Arguments
+    /// * `sign_handle` - A [KeyHandle] of an ECC signing key that uses
+    ///                   a split signing scheme (e.g. ECDAA).
+    /// * `p1` - An optional [EccPoint] that is a point on the curve of the key.
+    /// * `s2` - Optional [SensitiveData] used to compute the x coordinate of `E`.
+    /// * `y2` - An optional [EccParameter] that is used as the y coordinate of `E`.
+    ///
+    /// # Details
+    /// This command performs the point multiplications on the provided points and
+    /// returns intermediate signing values. The `sign_handle` parameter must refer
+    /// to an ECC key and the signing scheme must be anonymous.
+    ///
+    /// If any of the optional arguments are `None` then the corresponding
+    /// empty buffers will be sent to the TPM. The `s2` and `y2` arguments need to
+    /// either be both `None` or both `Some`.
+    ///
+    /// The command returns a tuple consisting of:
+    /// * `k` - An [EccPoint] that is `K := [ds](x2, y2)`.
+    /// * `l` - An [EccPoint] that is `L := [r](x2, y2)`.
+    /// * `e` - An [EccPoint] that is `E := [r]P1`.
+    /// * `counter` - The least significant 16 bits of the commit count that
+    ///               is to be used as the commit count in the subsequent call to
+    ///               [Context::sign].
+    pub fn commit(
+        &mut self,
+        sign_handle: KeyHandle,
+        p1: Option<EccPoint>,
+        s2: Option<SensitiveData>,
+        y2: Option<EccParameter>,
+    ) -> Result<(EccPoint, EccPoint, EccPoint, u16)> {
+        let mut k_ptr = null_mut();
+        let mut l_ptr = null_mut();
+        let mut e_ptr = null_mut();
+        let mut counter = 0;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Commit(
+                        ctx.mut_context(),
+                        sign_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &p1.clone().map(TPM2B_ECC_POINT::from).unwrap_or_default(),
+                        &s2.clone().unwrap_or_default().into(),
+                        &y2.clone().unwrap_or_default().into(),
+                        &mut k_ptr,
+                        &mut l_ptr,
+                        &mut e_ptr,
+                        &mut counter,
+                    )
+                },
+                |ret| {
+                    error!("Error when performing commit: {:#010X}", ret);
+                },
+            )
+        })?;
+        let k = Context::ffi_data_to_owned(k_ptr);
+        let l = Context::ffi_data_to_owned(l_ptr);
+        let e = Context::ffi_data_to_owned(e_ptr);
+        Ok((
+            EccPoint::try_from(k.point)?,
+            EccPoint::try_from(l.point)?,
+            EccPoint::try_from(e.point)?,
+            counter,
+        ))
+    }
+
+    /// Generate an ephemeral key pair.
+    ///
+    /// # Arguments
+    /// * `curve` - The [EccCurve] on which the ephemeral key pair is to be generated.
+    ///
+    /// # Details
+    /// This command creates an ephemeral key for use in a two-phase key
+    /// exchange protocol. The private part of the key is not returned,
+    /// instead the TPM returns a counter value that allows it to regenerate
+    /// the private key when it is needed by [Context::zgen_2phase].
+    ///
+    /// The command returns a tuple consisting of:
+    /// * `q` - The ephemeral public key as an [EccPoint].
+    /// * `counter` - The least significant 16 bits of the commit count.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, TctiNameConf, interface_types::ecc::EccCurve};
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// let (q, counter) = context
+    ///     .ec_ephemeral(EccCurve::NistP256)
+    ///     .expect("Failed to generate ephemeral key");
+    /// ```
+    pub fn ec_ephemeral(&mut self, curve: EccCurve) -> Result<(EccPoint, u16)> {
+        let mut q_ptr = null_mut();
+        let mut counter = 0;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_EC_Ephemeral(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        curve.into(),
+                        &mut q_ptr,
+                        &mut counter,
+                    )
+                },
+                |ret| {
+                    error!("Error when generating ephemeral key: {:#010X}", ret);
+                },
+            )
+        })?;
+        let q = Context::ffi_data_to_owned(q_ptr);
+        Ok((EccPoint::try_from(q.point)?, counter))
+    }
 }
@@ -1,9 +1,139 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    constants::tss::TPM2_ALG_NULL,
+    handles::{AuthHandle, KeyHandle},
+    interface_types::resource_handles::Platform,
+    structures::{Digest, HashAgile, MaxBuffer, Signature},
+    tss2_esys::{Esys_FieldUpgradeData, Esys_FieldUpgradeStart, Esys_FirmwareRead},
+    Context, Result, ReturnCode,
+};
+use log::error;
+use std::convert::{TryFrom, TryInto};
+use std::ptr::null_mut;
 
 impl Context {
-    // Missing function: FieldUpgradeStart
-    // Missing function: FieldUpgradeData
-    // Missing function: FirmwareRead
+    /// Start a field upgrade of the TPM firmware.
+    ///
+    /// # Details
+    /// Validates the signature of the digest of the field upgrade manifest and
+    /// puts the TPM in Field Upgrade Mode, after which the firmware image can be
+    /// sent with [Context::field_upgrade_data].
+    ///
+    /// # Arguments
+    /// * `authorization` - The [Platform] authorization handle.
+    /// * `key_handle` - The [KeyHandle] of the public key used to verify the manifest signature.
+    /// * `fu_digest` - The [Digest] of the first block of the field upgrade sequence.
+    /// * `manifest_signature` - The [Signature] over `fu_digest`, made with the key
+    ///                          in `key_handle`.
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    /// The format of the firmware image and of the manifest are defined by the TPM vendor.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    pub fn field_upgrade_start(
+        &mut self,
+        authorization: Platform,
+        key_handle: KeyHandle,
+        fu_digest: Digest,
+        manifest_signature: Signature,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_FieldUpgradeStart(
+                        ctx.mut_context(),
+                        AuthHandle::from(authorization).into(),
+                        key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &fu_digest.clone().into(),
+                        &manifest_signature.clone().try_into()?,
+                    )
+                },
+                |ret| {
+                    error!("Error when starting the field upgrade: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Send a block of the firmware image to the TPM.
+    ///
+    /// # Details
+    /// Must be called after [Context::field_upgrade_start], once for each
+    /// block of the firmware image, in the order defined by the TPM vendor.
+    ///
+    /// # Arguments
+    /// * `fu_data` - A [MaxBuffer] containing the next block of the firmware image.
+    ///
+    /// # Returns
+    /// The tuple `(next_digest, first_digest)`, where `next_digest` is the digest
+    /// of the block that the TPM expects next, or `None` if this was the last block,
+    /// and `first_digest` is the digest of the first block of the sequence.
+    pub fn field_upgrade_data(
+        &mut self,
+        fu_data: MaxBuffer,
+    ) -> Result<(Option<HashAgile>, HashAgile)> {
+        let mut next_digest_ptr = null_mut();
+        let mut first_digest_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_FieldUpgradeData(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &fu_data.clone().into(),
+                        &mut next_digest_ptr,
+                        &mut first_digest_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when sending field upgrade data: {:#010X}", ret);
+                },
+            )
+        })?;
+        let next_digest = Context::ffi_data_to_owned(next_digest_ptr);
+        let first_digest = HashAgile::try_from(Context::ffi_data_to_owned(first_digest_ptr))?;
+        if next_digest.hashAlg == TPM2_ALG_NULL {
+            Ok((None, first_digest))
+        } else {
+            Ok((Some(HashAgile::try_from(next_digest)?), first_digest))
+        }
+    }
+
+    /// Read a block of the firmware installed in the TPM.
+    ///
+    /// # Arguments
+    /// * `sequence_number` - The number of the block to read. The first block has
+    ///                       sequence number 0.
+    ///
+    /// # Returns
+    /// A [MaxBuffer] with the requested block. The TPM returns an empty buffer when
+    /// there are no more blocks to read.
+    pub fn firmware_read(&mut self, sequence_number: u32) -> Result<MaxBuffer> {
+        let mut fu_data_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_FirmwareRead(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        sequence_number,
+                        &mut fu_data_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when reading the firmware: {:#010X}", ret);
+                },
+            )
+        })?;
+        MaxBuffer::try_from(Context::ffi_data_to_owned(fu_data_ptr))
+    }
 }
@@ -1,12 +1,292 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    context::handle_manager::HandleDropAction,
+    handles::{ObjectHandle, PcrHandle, TpmHandle},
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+    structures::{Auth, Digest, DigestValues, HashcheckTicket, MaxBuffer},
+    tss2_esys::{
+        Esys_EventSequenceComplete, Esys_HMAC_Start, Esys_HashSequenceStart, Esys_SequenceComplete,
+        Esys_SequenceUpdate,
+    },
+    Context, Result, ReturnCode,
+};
+use log::error;
+use std::convert::TryFrom;
+use std::ptr::null_mut;
 
 impl Context {
-    // Missing function: HMAC_Start
+    /// Starts an HMAC sequence.
+    ///
+    /// # Arguments
+    /// * `handle` - The [ObjectHandle] of the keyed hash key that is going to be used.
+    /// * `hashing_algorithm` - The [HashingAlgorithm] that is going to be used for the HMAC.
+    /// * `auth` - The authorization value that is going to be required in order to use
+    ///            the sequence object.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the key.
+    ///
+    /// The returned handle refers to a sequence object that is loaded in the TPM.
+    /// Data is added to the sequence using [Context::sequence_update] and the sequence
+    /// is completed, and the sequence object removed from the TPM, using
+    /// [Context::sequence_complete].
+    pub fn hmac_sequence_start(
+        &mut self,
+        handle: ObjectHandle,
+        hashing_algorithm: HashingAlgorithm,
+        auth: Option<Auth>,
+    ) -> Result<ObjectHandle> {
+        let mut sequence_handle = ObjectHandle::None.into();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_HMAC_Start(
+                        ctx.mut_context(),
+                        handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &auth.clone().unwrap_or_default().into(),
+                        hashing_algorithm.into(),
+                        &mut sequence_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error failed to start HMAC sequence: {:#010X}", ret);
+                },
+            )
+        })?;
+        let sequence_handle = ObjectHandle::from(sequence_handle);
+        self.handle_manager
+            .add_handle(sequence_handle, HandleDropAction::Flush)?;
+        Ok(sequence_handle)
+    }
+
     // Missing function: MAC_Start
-    // Missing function: HashSequenceStart
-    // Missing function: SequenceUpdate
-    // Missing function: SequenceComplete
-    // Missing function: EventSequenceComplete
+
+    /// Starts a hash sequence.
+    ///
+    /// # Arguments
+    /// * `hashing_algorithm` - The [HashingAlgorithm] that is going to be used for the
+    ///                         sequence. If [HashingAlgorithm::Null] is used then an event
+    ///                         sequence is started instead.
+    /// * `auth` - The authorization value that is going to be required in order to use
+    ///            the sequence object.
+    ///
+    /// # Details
+    /// The returned handle refers to a sequence object that is loaded in the TPM.
+    /// Data is added to the sequence using [Context::sequence_update] and the sequence
+    /// is completed, and the sequence object removed from the TPM, using
+    /// [Context::sequence_complete].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{Context, tcti_ldr::TctiNameConf,
+    /// #     structures::{MaxBuffer, Ticket},
+    /// #     interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+    /// #     interface_types::session_handles::AuthSession,
+    /// # };
+    /// # use std::convert::TryFrom;
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// let sequence_handle = context
+    ///     .hash_sequence_start(HashingAlgorithm::Sha256, None)
+    ///     .expect("Call to hash_sequence_start failed");
+    /// let (digest, ticket) = context.execute_with_session(Some(AuthSession::Password), |ctx| {
+    ///     ctx.sequence_update(
+    ///         sequence_handle,
+    ///         MaxBuffer::try_from("There is ".as_bytes().to_vec())
+    ///             .expect("Failed to create buffer for input data."),
+    ///     )
+    ///     .expect("Call to sequence_update failed");
+    ///     ctx.sequence_complete(
+    ///         sequence_handle,
+    ///         MaxBuffer::try_from("no spoon".as_bytes().to_vec())
+    ///             .expect("Failed to create buffer for input data."),
+    ///         Hierarchy::Owner,
+    ///     )
+    ///     .expect("Call to sequence_complete failed")
+    /// });
+    /// let expected_hashed_data: [u8; 32] = [
+    ///     0x6b, 0x38, 0x4d, 0x2b, 0xfb, 0x0e, 0x0d, 0xfb, 0x64, 0x89, 0xdb, 0xf4, 0xf8, 0xe9,
+    ///     0xe5, 0x2f, 0x71, 0xee, 0xb1, 0x0d, 0x06, 0x4c, 0x56, 0x59, 0x70, 0xcd, 0xd9, 0x44,
+    ///     0x43, 0x18, 0x5d, 0xc1,
+    /// ];
+    /// assert_eq!(&expected_hashed_data[..], digest.as_bytes());
+    /// assert_eq!(ticket.hierarchy(), Hierarchy::Owner);
+    /// ```
+    pub fn hash_sequence_start(
+        &mut self,
+        hashing_algorithm: HashingAlgorithm,
+        auth: Option<Auth>,
+    ) -> Result<ObjectHandle> {
+        let mut sequence_handle = ObjectHandle::None.into();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_HashSequenceStart(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &auth.clone().unwrap_or_default().into(),
+                        hashing_algorithm.into(),
+                        &mut sequence_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error failed to start hash sequence: {:#010X}", ret);
+                },
+            )
+        })?;
+        let sequence_handle = ObjectHandle::from(sequence_handle);
+        self.handle_manager
+            .add_handle(sequence_handle, HandleDropAction::Flush)?;
+        Ok(sequence_handle)
+    }
+
+    /// Adds data to a hash or HMAC sequence.
+    ///
+    /// # Arguments
+    /// * `sequence_handle` - The [ObjectHandle] of the sequence object.
+    /// * `data` - The data that is going to be added to the sequence.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the
+    /// sequence object.
+    pub fn sequence_update(
+        &mut self,
+        sequence_handle: ObjectHandle,
+        data: MaxBuffer,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SequenceUpdate(
+                        ctx.mut_context(),
+                        sequence_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &data.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!("Error failed to update sequence: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Adds the last part of the data to a hash or HMAC sequence and returns the result.
+    ///
+    /// # Arguments
+    /// * `sequence_handle` - The [ObjectHandle] of the sequence object.
+    /// * `data` - The data that is going to be added to the sequence.
+    /// * `hierarchy` - The [Hierarchy] of the ticket that is returned for
+    ///                 hash sequences.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the
+    /// sequence object. The sequence object is flushed from the
+    /// TPM when the command succeeds.
+    ///
+    /// The ticket indicates if the digest can be used in a signing operation
+    /// that uses restricted signing key. A null ticket is returned for HMAC
+    /// sequences.
+    pub fn sequence_complete(
+        &mut self,
+        sequence_handle: ObjectHandle,
+        data: MaxBuffer,
+        hierarchy: Hierarchy,
+    ) -> Result<(Digest, HashcheckTicket)> {
+        let mut result_ptr = null_mut();
+        let mut validation_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SequenceComplete(
+                        ctx.mut_context(),
+                        sequence_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &data.clone().into(),
+                        if cfg!(hierarchy_is_esys_tr) {
+                            ObjectHandle::from(hierarchy).into()
+                        } else {
+                            TpmHandle::from(hierarchy).into()
+                        },
+                        &mut result_ptr,
+                        &mut validation_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error failed to complete sequence: {:#010X}", ret);
+                },
+            )
+        })?;
+        self.handle_manager.set_as_flushed(sequence_handle)?;
+        Ok((
+            Digest::try_from(Context::ffi_data_to_owned(result_ptr))?,
+            HashcheckTicket::try_from(Context::ffi_data_to_owned(validation_ptr))?,
+        ))
+    }
+
+    /// Adds the last part of the data to an event sequence and extends the result into a PCR.
+    ///
+    /// # Arguments
+    /// * `pcr_handle` - The [PcrHandle] of the PCR that is going to be extended. If `None`
+    ///                  is provided then no PCR is extended.
+    /// * `sequence_handle` - The [ObjectHandle] of the event sequence object.
+    /// * `data` - The data that is going to be added to the sequence.
+    ///
+    /// # Details
+    /// An event sequence is started by calling [Context::hash_sequence_start] with
+    /// [HashingAlgorithm::Null]. The data of the event is hashed with all the hashing
+    /// algorithms for which the TPM has PCR banks and the PCR is extended in all banks.
+    ///
+    /// This command requires an authorization session for the PCR and another one for
+    /// the sequence object. The sequence object is flushed from the TPM when the command
+    /// succeeds.
+    ///
+    /// # Returns
+    /// The digests of the event, one for each of the PCR banks.
+    pub fn event_sequence_complete(
+        &mut self,
+        pcr_handle: Option<PcrHandle>,
+        sequence_handle: ObjectHandle,
+        data: MaxBuffer,
+    ) -> Result<DigestValues> {
+        let mut results_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_EventSequenceComplete(
+                        ctx.mut_context(),
+                        pcr_handle
+                            .map(ObjectHandle::from)
+                            .unwrap_or(ObjectHandle::Null)
+                            .into(),
+                        sequence_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &data.clone().into(),
+                        &mut results_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error failed to complete event sequence: {:#010X}", ret);
+                },
+            )
+        })?;
+        self.handle_manager.set_as_flushed(sequence_handle)?;
+        DigestValues::try_from(Context::ffi_data_to_owned(results_ptr))
+    }
 }
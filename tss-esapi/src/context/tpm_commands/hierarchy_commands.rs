@@ -1,14 +1,22 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::CommandCode,
     context::handle_manager::HandleDropAction,
-    handles::{AuthHandle, KeyHandle, ObjectHandle},
-    interface_types::{resource_handles::Hierarchy, YesNo},
+    handles::{AuthHandle, KeyHandle, ObjectHandle, TpmHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{Enables, Hierarchy, Platform},
+        YesNo,
+    },
     structures::{
         Auth, CreatePrimaryKeyResult, CreationData, CreationTicket, Data, Digest, PcrSelectionList,
         Public, SensitiveCreate, SensitiveData,
     },
-    tss2_esys::{Esys_Clear, Esys_ClearControl, Esys_CreatePrimary, Esys_HierarchyChangeAuth},
+    tss2_esys::{
+        Esys_ChangeEPS, Esys_ChangePPS, Esys_Clear, Esys_ClearControl, Esys_CreatePrimary,
+        Esys_HierarchyChangeAuth, Esys_HierarchyControl, Esys_SetPrimaryPolicy,
+    },
     Context, Result, ReturnCode,
 };
 use log::error;
@@ -47,29 +55,33 @@ impl Context {
         let mut creation_ticket_ptr = null_mut();
         let mut object_handle = ObjectHandle::None.into();
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_CreatePrimary(
-                    self.mut_context(),
-                    ObjectHandle::from(primary_handle).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &sensitive_create.try_into()?,
-                    &public.try_into()?,
-                    &outside_info.unwrap_or_default().into(),
-                    &creation_pcrs.into(),
-                    &mut object_handle,
-                    &mut out_public_ptr,
-                    &mut creation_data_ptr,
-                    &mut creation_hash_ptr,
-                    &mut creation_ticket_ptr,
+        self.execute_with_parameter_encryption(CommandCode::CreatePrimary, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_CreatePrimary(
+                            ctx.mut_context(),
+                            ObjectHandle::from(primary_handle).into(),
+                            ctx.optional_session_1(),
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &sensitive_create.clone().try_into()?,
+                            &public.clone().try_into()?,
+                            &outside_info.clone().unwrap_or_default().into(),
+                            &creation_pcrs.clone().into(),
+                            &mut object_handle,
+                            &mut out_public_ptr,
+                            &mut creation_data_ptr,
+                            &mut creation_hash_ptr,
+                            &mut creation_ticket_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error in creating primary key: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error in creating primary key: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         let out_public_owned = Context::ffi_data_to_owned(out_public_ptr);
         let creation_data_owned = Context::ffi_data_to_owned(creation_data_ptr);
         let creation_hash_owned = Context::ffi_data_to_owned(creation_hash_ptr);
@@ -87,64 +99,257 @@ impl Context {
         })
     }
 
-    // Missing function: HierarchyControl
-    // Missing function: SetPrimaryPolicy
-    // Missing function: ChangePPS
-    // Missing function: ChangeEPS
+    /// Enable or disable the use of a hierarchy and its associated NV storage.
+    ///
+    /// # Details
+    /// The owner and endorsement hierarchies can be disabled by their own
+    /// authorization or by the platform authorization, and they can only be
+    /// enabled with the platform authorization. The platform hierarchy and
+    /// the platform NV storage can only be controlled with the platform
+    /// authorization.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [AuthHandle] of the hierarchy authorizing the command.
+    /// * `enable` - The [Enables] selector of the hierarchy that is to be enabled or disabled.
+    /// * `state` - `true` if the hierarchy should be enabled, `false` if it should be disabled.
+    ///
+    /// This command requires an authorization session for the `auth_handle`.
+    pub fn hierarchy_control(
+        &mut self,
+        auth_handle: AuthHandle,
+        enable: Enables,
+        state: bool,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_HierarchyControl(
+                        ctx.mut_context(),
+                        auth_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        if cfg!(hierarchy_is_esys_tr) {
+                            ObjectHandle::from(enable).into()
+                        } else {
+                            TpmHandle::from(enable).into()
+                        },
+                        YesNo::from(state).into(),
+                    )
+                },
+                |ret| {
+                    error!("Error in controlling hierarchy: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Set the authorization policy of a hierarchy.
+    ///
+    /// # Details
+    /// Sets the authorization policy of the owner, endorsement, platform or
+    /// lockout hierarchy, allowing the hierarchy to be administered with
+    /// policy sessions. An empty policy digest together with
+    /// [HashingAlgorithm::Null] removes the policy.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [AuthHandle] of the hierarchy.
+    /// * `auth_policy` - The policy digest.
+    /// * `hashing_algorithm` - The hashing algorithm used to compute the policy digest.
+    ///
+    /// This command requires an authorization session for the hierarchy.
+    ///
+    /// # Errors
+    /// * if the size of the policy digest does not match the hashing algorithm,
+    ///   the TPM returns an error with `Size`
+    pub fn set_primary_policy(
+        &mut self,
+        auth_handle: AuthHandle,
+        auth_policy: Digest,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SetPrimaryPolicy(
+                        ctx.mut_context(),
+                        auth_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &auth_policy.clone().into(),
+                        hashing_algorithm.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error in setting primary policy: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Change the platform primary seed.
+    ///
+    /// # Details
+    /// Replaces the platform primary seed with a new value generated by the TPM.
+    /// Primary objects of the platform hierarchy that were created from the old
+    /// seed can no longer be recreated, and the platform policy is reset.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    /// * if the authorization value of the platform hierarchy has not been set on the context
+    ///   (see [Context::tr_set_auth]), the TPM returns an error with `AuthFail` or `BadAuth`
+    pub fn change_pps(&mut self, auth_handle: Platform) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ChangePPS(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error in changing platform primary seed: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Change the endorsement primary seed.
+    ///
+    /// # Details
+    /// Replaces the endorsement primary seed with a new value generated by the TPM.
+    /// This invalidates the Endorsement Key and every other object in the endorsement
+    /// hierarchy, and resets the endorsement authorization value and policy.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    /// * if the authorization value of the platform hierarchy has not been set on the context
+    ///   (see [Context::tr_set_auth]), the TPM returns an error with `AuthFail` or `BadAuth`
+    pub fn change_eps(&mut self, auth_handle: Platform) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ChangeEPS(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error in changing endorsement primary seed: {:#010X}", ret);
+                },
+            )
+        })
+    }
 
     /// Clear all TPM context associated with a specific Owner
+    ///
+    /// # Details
+    /// Removes all objects and NV indices of the owner and endorsement
+    /// hierarchies, resets their authorization values and policies and
+    /// changes the storage primary seed.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Either [AuthHandle::Lockout] or [AuthHandle::Platform].
+    ///
+    /// This command requires an authorization session for the `auth_handle`.
+    /// It fails if the command has been disabled with [Context::clear_control].
     pub fn clear(&mut self, auth_handle: AuthHandle) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Clear(
-                    self.mut_context(),
-                    auth_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error in clearing TPM hierarchy: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Clear(
+                        ctx.mut_context(),
+                        auth_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error in clearing TPM hierarchy: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Disable or enable the TPM2_CLEAR command
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Either [AuthHandle::Lockout] or [AuthHandle::Platform]. The
+    ///                   command can only be enabled again with the platform authorization.
+    /// * `disable` - `true` if the command should be disabled, `false` if it should be enabled.
+    ///
+    /// This command requires an authorization session for the `auth_handle`.
     pub fn clear_control(&mut self, auth_handle: AuthHandle, disable: bool) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ClearControl(
-                    self.mut_context(),
-                    auth_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    YesNo::from(disable).into(),
-                )
-            },
-            |ret| {
-                error!("Error in controlling clear command: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ClearControl(
+                        ctx.mut_context(),
+                        auth_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        YesNo::from(disable).into(),
+                    )
+                },
+                |ret| {
+                    error!("Error in controlling clear command: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     /// Change authorization for a hierarchy root
+    ///
+    /// # Details
+    /// Changes the authorization value of the owner, endorsement, platform
+    /// or lockout hierarchy. On success, the new authorization value is also
+    /// set on the hierarchy handle in the context (see [Context::tr_set_auth]),
+    /// so that subsequent commands using the hierarchy are authorized with it.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [AuthHandle] of the hierarchy.
+    /// * `new_auth` - The new authorization value of the hierarchy.
+    ///
+    /// This command requires an authorization session for the hierarchy.
     pub fn hierarchy_change_auth(&mut self, auth_handle: AuthHandle, new_auth: Auth) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_HierarchyChangeAuth(
-                    self.mut_context(),
-                    auth_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &new_auth.into(),
+        self.execute_with_parameter_encryption(CommandCode::HierarchyChangeAuth, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_HierarchyChangeAuth(
+                            ctx.mut_context(),
+                            auth_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &new_auth.clone().into(),
+                        )
+                    },
+                    |ret| {
+                        error!("Error changing hierarchy auth: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error changing hierarchy auth: {:#010X}", ret);
-            },
-        )
+            })
+        })?;
+        self.tr_set_auth(auth_handle.into(), new_auth)
     }
 }
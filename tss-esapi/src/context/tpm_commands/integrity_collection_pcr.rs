@@ -1,9 +1,16 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    handles::PcrHandle,
-    structures::{DigestList, DigestValues, PcrSelectionList},
-    tss2_esys::{Esys_PCR_Extend, Esys_PCR_Read, Esys_PCR_Reset},
+    constants::tss::TPM2_PCR_FIRST,
+    handles::{AuthHandle, PcrHandle},
+    interface_types::{algorithm::HashingAlgorithm, resource_handles::Platform, YesNo},
+    structures::{
+        Auth, Digest, DigestList, DigestValues, Event, PcrAllocateResult, PcrSelectionList,
+    },
+    tss2_esys::{
+        Esys_PCR_Allocate, Esys_PCR_Event, Esys_PCR_Extend, Esys_PCR_Read, Esys_PCR_Reset,
+        Esys_PCR_SetAuthPolicy, Esys_PCR_SetAuthValue, ESYS_TR, ESYS_TR_PCR0,
+    },
     Context, Result, ReturnCode,
 };
 use log::error;
@@ -88,24 +95,112 @@ impl Context {
     /// });
     /// ```
     pub fn pcr_extend(&mut self, pcr_handle: PcrHandle, digests: DigestValues) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PCR_Extend(
-                    self.mut_context(),
-                    pcr_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &digests.try_into()?,
-                )
-            },
-            |ret| {
-                error!("Error when extending PCR: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_Extend(
+                        ctx.mut_context(),
+                        pcr_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &digests.clone().try_into()?,
+                    )
+                },
+                |ret| {
+                    error!("Error when extending PCR: {:#010X}", ret);
+                },
+            )
+        })
     }
 
-    // Missing function: PCR_Event
+    /// Extends a PCR with the digests of an event.
+    ///
+    /// # Arguments
+    /// * `pcr_handle`- A [PcrHandle] to the PCR slot that is to be extended.
+    /// * `event_data` - The [Event] data that is hashed and used to extend the slot.
+    ///
+    /// # Details
+    /// The TPM computes the digest of the event data for each implemented hashing
+    /// algorithm, and extends the slot in each of the allocated banks with the
+    /// digest computed with the algorithm of the bank, so the event does not have
+    /// to be hashed by the caller for every allocated bank.
+    ///
+    /// This command requires an authorization session for the PCR.
+    ///
+    /// # Returns
+    /// The digests of the event data, as [DigestValues].
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf,
+    /// #     constants::SessionType,
+    /// #     attributes::SessionAttributesBuilder,
+    /// #     structures::SymmetricDefinition,
+    /// #     interface_types::algorithm::HashingAlgorithm,
+    /// # };
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// # // Create session for a pcr
+    /// # let pcr_session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(pcr_session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// use std::convert::TryFrom;
+    /// use tss_esapi::{handles::PcrHandle, structures::Event};
+    ///
+    /// let event_data = Event::try_from(b"event".to_vec()).expect("Failed to create Event");
+    /// let digests = context
+    ///     .execute_with_session(Some(pcr_session), |ctx| {
+    ///         ctx.pcr_event(PcrHandle::Pcr16, event_data)
+    ///     })
+    ///     .expect("Call to pcr_event failed");
+    /// assert!(digests.get(HashingAlgorithm::Sha256).is_some());
+    /// ```
+    pub fn pcr_event(&mut self, pcr_handle: PcrHandle, event_data: Event) -> Result<DigestValues> {
+        let mut digests_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_Event(
+                        ctx.mut_context(),
+                        pcr_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &event_data.clone().into(),
+                        &mut digests_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when executing PCR event: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        DigestValues::try_from(Context::ffi_data_to_owned(digests_ptr))
+    }
 
     /// Reads the values of a PCR.
     ///
@@ -155,23 +250,25 @@ impl Context {
         let mut pcr_update_counter: u32 = 0;
         let mut pcr_selection_out_ptr = null_mut();
         let mut pcr_values_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PCR_Read(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &pcr_selection_list.into(),
-                    &mut pcr_update_counter,
-                    &mut pcr_selection_out_ptr,
-                    &mut pcr_values_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when reading PCR: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_Read(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &pcr_selection_list.clone().into(),
+                        &mut pcr_update_counter,
+                        &mut pcr_selection_out_ptr,
+                        &mut pcr_values_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when reading PCR: {:#010X}", ret);
+                },
+            )
+        })?;
 
         Ok((
             pcr_update_counter,
@@ -180,9 +277,155 @@ impl Context {
         ))
     }
 
-    // Missing function: PCR_Allocate
-    // Missing function: PCR_SetAuthPolicy
-    // Missing function: PCR_SetAuthValue
+    /// Sets the desired PCR allocation of the PCR banks.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    /// * `pcr_allocation` - A [PcrSelectionList] with the requested allocation.
+    ///
+    /// # Details
+    /// The allocation of the banks that are not present in `pcr_allocation` is
+    /// left unchanged, and a bank is deallocated by selecting no PCR in it. The
+    /// new allocation is not used by the TPM until the next TPM reset (i.e. a
+    /// [Context::startup] with [StartupType::Clear](crate::constants::StartupType::Clear)
+    /// after a power cycle).
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    ///
+    /// # Returns
+    /// A [PcrAllocateResult] indicating whether the allocation succeeded, together
+    /// with the space needed for the requested allocation and the space available.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    pub fn pcr_allocate(
+        &mut self,
+        auth_handle: Platform,
+        pcr_allocation: PcrSelectionList,
+    ) -> Result<PcrAllocateResult> {
+        let mut allocation_success = YesNo::No.into();
+        let mut max_pcr = 0;
+        let mut size_needed = 0;
+        let mut size_available = 0;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_Allocate(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &pcr_allocation.clone().into(),
+                        &mut allocation_success,
+                        &mut max_pcr,
+                        &mut size_needed,
+                        &mut size_available,
+                    )
+                },
+                |ret| {
+                    error!("Error when allocating PCR banks: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        Ok(PcrAllocateResult {
+            allocation_success: YesNo::try_from(allocation_success)?.into(),
+            max_pcr,
+            size_needed,
+            size_available,
+        })
+    }
+
+    /// Sets the authorization policy of a group of PCR.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    /// * `auth_policy` - The policy digest that authorizes the use of the PCR.
+    /// * `hashing_algorithm` - The hashing algorithm used to compute the policy digest.
+    /// * `pcr_handle` - A [PcrHandle] to a PCR in the group whose policy is to be set.
+    ///
+    /// # Details
+    /// The policy is set for all the PCR in the same policy group as `pcr_handle`,
+    /// which PCR belong to a policy group is defined by the platform specification.
+    /// The policy is removed by setting an empty digest with [HashingAlgorithm::Null].
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    /// * if the PCR does not belong to a policy group, the TPM returns an error
+    pub fn pcr_set_auth_policy(
+        &mut self,
+        auth_handle: Platform,
+        auth_policy: Digest,
+        hashing_algorithm: HashingAlgorithm,
+        pcr_handle: PcrHandle,
+    ) -> Result<()> {
+        // The handles of the PCR in the TPM are in the same order
+        // as the ESYS_TR values of the PCR handles.
+        let pcr_num = TPM2_PCR_FIRST + (ESYS_TR::from(pcr_handle) - ESYS_TR_PCR0);
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_SetAuthPolicy(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &auth_policy.clone().into(),
+                        hashing_algorithm.into(),
+                        pcr_num,
+                    )
+                },
+                |ret| {
+                    error!("Error when setting PCR auth policy: {:#010X}", ret);
+                },
+            )
+        })
+    }
+
+    /// Sets the authorization value of a group of PCR.
+    ///
+    /// # Arguments
+    /// * `pcr_handle` - A [PcrHandle] to a PCR in the group whose authorization value is to be set.
+    /// * `auth` - The new authorization value.
+    ///
+    /// # Details
+    /// The authorization value is set for all the PCR in the same authorization
+    /// group as `pcr_handle`, which PCR belong to an authorization group is defined
+    /// by the platform specification. The authorization value is reset to the
+    /// empty value on every TPM reset and TPM restart.
+    ///
+    /// This command requires an authorization session for the PCR, using the
+    /// current authorization value of the group. On success the new authorization
+    /// value is set on `pcr_handle` (see [Context::tr_set_auth]), but not on the
+    /// other PCR handles of the group.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    /// * if the PCR does not belong to an authorization group, the TPM returns an error
+    pub fn pcr_set_auth_value(&mut self, pcr_handle: PcrHandle, auth: Auth) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_SetAuthValue(
+                        ctx.mut_context(),
+                        pcr_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &auth.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when setting PCR auth value: {:#010X}", ret);
+                },
+            )
+        })?;
+        self.tr_set_auth(pcr_handle.into(), auth)
+    }
 
     /// Resets the value in a PCR.
     ///
@@ -238,20 +481,22 @@ impl Context {
     /// });
     /// ```
     pub fn pcr_reset(&mut self, pcr_handle: PcrHandle) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PCR_Reset(
-                    self.mut_context(),
-                    pcr_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error when resetting PCR: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PCR_Reset(
+                        ctx.mut_context(),
+                        pcr_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error when resetting PCR: {:#010X}", ret);
+                },
+            )
+        })
     }
 
     // Missing function: _TPM_Hash_Start
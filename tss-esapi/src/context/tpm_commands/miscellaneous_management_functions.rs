@@ -1,8 +1,98 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    handles::AuthHandle,
+    interface_types::resource_handles::Platform,
+    structures::{AlgorithmSet, CommandCodeList},
+    tss2_esys::{Esys_PP_Commands, Esys_SetAlgorithmSet, TPML_CC},
+    Context, Result, ReturnCode,
+};
+use log::error;
 
 impl Context {
-    // Missing function: PP_Commands
-    // Missing function: SetAlgorithmSet
+    /// Change the list of commands that require physical presence.
+    ///
+    /// # Details
+    /// The commands in `set_list` are added to the list of commands that
+    /// require the assertion of physical presence for platform authorization
+    /// and the commands in `clear_list` are removed from it. The current list
+    /// can be read with [Context::get_capability] using
+    /// [CapabilityType::PpCommands](crate::constants::CapabilityType::PpCommands).
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    /// * `set_list` - A [CommandCodeList] with the commands that will require physical presence.
+    /// * `clear_list` - A [CommandCodeList] with the commands that will no longer require
+    ///                  physical presence.
+    ///
+    /// This command requires an authorization session for the platform hierarchy and
+    /// physical presence to be asserted. Commands that always require physical presence,
+    /// such as `TPM2_PP_Commands` itself, cannot be removed from the list.
+    pub fn pp_commands(
+        &mut self,
+        auth_handle: Platform,
+        set_list: CommandCodeList,
+        clear_list: CommandCodeList,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PP_Commands(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &TPML_CC::from(set_list.clone()),
+                        &TPML_CC::from(clear_list.clone()),
+                    )
+                },
+                |ret| {
+                    error!(
+                        "Error when changing the commands requiring physical presence: {:#010X}",
+                        ret
+                    );
+                },
+            )
+        })
+    }
+
+    /// Select the set of algorithms used by the TPM.
+    ///
+    /// # Details
+    /// The selected set takes effect after the next `TPM2_Startup(CLEAR)`.
+    /// The values of the algorithm sets, and which algorithms they contain,
+    /// are defined by the TPM vendor.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Platform] authorization handle.
+    /// * `algorithm_set` - The [AlgorithmSet] that will be used by the TPM.
+    ///
+    /// This command requires an authorization session for the platform hierarchy.
+    ///
+    /// # Errors
+    /// * if no session has been set for the context, a `MissingAuthSession` wrapper error is returned
+    pub fn set_algorithm_set(
+        &mut self,
+        auth_handle: Platform,
+        algorithm_set: AlgorithmSet,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SetAlgorithmSet(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        algorithm_set.into(),
+                    )
+                },
+                |ret| {
+                    error!("Error when setting the algorithm set: {:#010X}", ret);
+                },
+            )
+        })
+    }
 }
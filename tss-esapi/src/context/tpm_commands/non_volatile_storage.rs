@@ -1,15 +1,21 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    attributes::NvIndexAttributes,
+    constants::CommandCode,
     context::handle_manager::HandleDropAction,
-    handles::{AuthHandle, NvIndexHandle, ObjectHandle},
+    handles::{AuthHandle, KeyHandle, NvIndexHandle, ObjectHandle},
     interface_types::resource_handles::{NvAuth, Provision},
-    structures::{Auth, MaxNvBuffer, Name, NvPublic},
+    structures::{
+        Attest, AttestBuffer, Auth, Data, MaxNvBuffer, Name, NvPublic, Signature, SignatureScheme,
+    },
     tss2_esys::{
-        Esys_NV_DefineSpace, Esys_NV_Increment, Esys_NV_Read, Esys_NV_ReadPublic,
-        Esys_NV_UndefineSpace, Esys_NV_Write,
+        Esys_NV_Certify, Esys_NV_ChangeAuth, Esys_NV_DefineSpace, Esys_NV_Extend,
+        Esys_NV_GlobalWriteLock, Esys_NV_Increment, Esys_NV_Read, Esys_NV_ReadLock,
+        Esys_NV_ReadPublic, Esys_NV_SetBits, Esys_NV_UndefineSpace, Esys_NV_Write,
+        Esys_NV_WriteLock,
     },
-    Context, Result, ReturnCode,
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
 };
 use log::error;
 use std::convert::{TryFrom, TryInto};
@@ -104,23 +110,27 @@ impl Context {
         public_info: NvPublic,
     ) -> Result<NvIndexHandle> {
         let mut nv_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_DefineSpace(
-                    self.mut_context(),
-                    AuthHandle::from(nv_auth).into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &auth.unwrap_or_default().into(),
-                    &public_info.try_into()?,
-                    &mut nv_handle,
+        self.execute_with_parameter_encryption(CommandCode::NvDefineSpace, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_NV_DefineSpace(
+                            ctx.mut_context(),
+                            AuthHandle::from(nv_auth).into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &auth.clone().unwrap_or_default().into(),
+                            &public_info.clone().try_into()?,
+                            &mut nv_handle,
+                        )
+                    },
+                    |ret| {
+                        error!("Error when defining NV space: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error when defining NV space: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
 
         self.handle_manager
             .add_handle(nv_handle.into(), HandleDropAction::Close)?;
@@ -207,21 +217,23 @@ impl Context {
         nv_auth: Provision,
         nv_index_handle: NvIndexHandle,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_UndefineSpace(
-                    self.mut_context(),
-                    AuthHandle::from(nv_auth).into(),
-                    nv_index_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error when undefining NV space: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_UndefineSpace(
+                        ctx.mut_context(),
+                        AuthHandle::from(nv_auth).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error when undefining NV space: {:#010X}", ret);
+                },
+            )
+        })?;
 
         self.handle_manager.set_as_closed(nv_index_handle.into())
     }
@@ -316,22 +328,24 @@ impl Context {
     pub fn nv_read_public(&mut self, nv_index_handle: NvIndexHandle) -> Result<(NvPublic, Name)> {
         let mut nv_public_ptr = null_mut();
         let mut nv_name_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_ReadPublic(
-                    self.mut_context(),
-                    nv_index_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut nv_public_ptr,
-                    &mut nv_name_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when reading NV public: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_ReadPublic(
+                        ctx.mut_context(),
+                        nv_index_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut nv_public_ptr,
+                        &mut nv_name_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when reading NV public: {:#010X}", ret);
+                },
+            )
+        })?;
 
         Ok((
             NvPublic::try_from(Context::ffi_data_to_owned(nv_public_ptr))?,
@@ -435,23 +449,27 @@ impl Context {
         data: MaxNvBuffer,
         offset: u16,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_Write(
-                    self.mut_context(),
-                    AuthHandle::from(auth_handle).into(),
-                    nv_index_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &data.into(),
-                    offset,
+        self.execute_with_parameter_encryption(CommandCode::NvWrite, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_NV_Write(
+                            ctx.mut_context(),
+                            AuthHandle::from(auth_handle).into(),
+                            nv_index_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &data.clone().into(),
+                            offset,
+                        )
+                    },
+                    |ret| {
+                        error!("Error when writing NV: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error when writing NV: {:#010X}", ret);
-            },
-        )
+            })
+        })
     }
 
     /// Increment monotonic counter index
@@ -541,25 +559,459 @@ impl Context {
         auth_handle: NvAuth,
         nv_index_handle: NvIndexHandle,
     ) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_Increment(
-                    self.mut_context(),
-                    AuthHandle::from(auth_handle).into(),
-                    nv_index_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| error!("Error when incrementing NV: {:#010X}", ret),
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_Increment(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| error!("Error when incrementing NV: {:#010X}", ret),
+            )
+        })
     }
 
-    // Missing function: NV_Extend
-    // Missing function: NV_SetBits
-    // Missing function: NV_WriteLock
-    // Missing function: NV_GlobalWriteLock
+    /// Extends data to an extend index
+    ///
+    /// # Details
+    /// This method is used to extend data to an NV index of
+    /// type [NvIndexType::Extend](crate::constants::NvIndexType::Extend),
+    /// in the same way as data is extended to a PCR. The new value of the
+    /// index is the digest of the old value of the index concatenated with
+    /// `data`, computed with the name algorithm of the index.
+    ///
+    /// Please beware that this method requires an authorization
+    /// session handle to be present.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       where data is to be extended.
+    /// * `data` - The data to extend, e.g. the digest of a measurement.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf, attributes::{SessionAttributes, NvIndexAttributes},
+    /// #     handles::NvIndexTpmHandle, interface_types::algorithm::HashingAlgorithm,
+    /// #     structures::{SymmetricDefinition, NvPublic}, constants::SessionType,
+    /// #     constants::nv_index_type::NvIndexType,
+    /// # };
+    /// use tss_esapi::{
+    ///       interface_types::resource_handles::{Provision, NvAuth}, structures::MaxNvBuffer,
+    /// };
+    /// use std::convert::TryFrom;
+    ///
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// # let session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         tss_esapi::interface_types::algorithm::HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributes::builder()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// # context.set_sessions((Some(session), None, None));
+    /// #
+    /// # let nv_index = NvIndexTpmHandle::new(0x01500028)
+    /// #     .expect("Failed to create NV index tpm handle");
+    /// #
+    /// # // Create NV index attributes
+    /// # let owner_nv_index_attributes = NvIndexAttributes::builder()
+    /// #     .with_owner_write(true)
+    /// #     .with_owner_read(true)
+    /// #     .with_nv_index_type(NvIndexType::Extend)
+    /// #     .build()
+    /// #     .expect("Failed to create owner nv index attributes");
+    /// #
+    /// # // Create owner nv public.
+    /// # let owner_nv_public = NvPublic::builder()
+    /// #     .with_nv_index(nv_index)
+    /// #     .with_index_name_algorithm(HashingAlgorithm::Sha256)
+    /// #     .with_index_attributes(owner_nv_index_attributes)
+    /// #     .with_data_area_size(32)
+    /// #     .build()
+    /// #     .expect("Failed to build NvPublic for owner");
+    /// #
+    /// let nv_index_handle = context
+    ///     .nv_define_space(Provision::Owner, None, owner_nv_public)
+    ///     .expect("Call to nv_define_space failed");
+    ///
+    /// let data = MaxNvBuffer::try_from(vec![0xA5; 32])
+    ///     .expect("Failed to create MaxNvBuffer from vec");
+    /// let nv_extend_result = context.nv_extend(NvAuth::Owner, nv_index_handle, data);
+    ///
+    /// context
+    ///     .nv_undefine_space(Provision::Owner, nv_index_handle)
+    ///     .expect("Call to nv_undefine_space failed");
+    ///
+    /// // Process result
+    /// nv_extend_result.expect("Call to nv_extend failed");
+    /// ```
+    pub fn nv_extend(
+        &mut self,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+        data: MaxNvBuffer,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_Extend(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &data.clone().into(),
+                    )
+                },
+                |ret| error!("Error when extending NV: {:#010X}", ret),
+            )
+        })
+    }
+
+    /// Sets bits in a bit field index
+    ///
+    /// # Details
+    /// This method is used to set bits in an NV index of
+    /// type [NvIndexType::Bits](crate::constants::NvIndexType::Bits).
+    /// The new value of the index is the bitwise OR of the current value
+    /// of the index and `bits`, so bits can only be set and never cleared.
+    ///
+    /// Please beware that this method requires an authorization
+    /// session handle to be present.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       where the bits are to be set.
+    /// * `bits` - The mask of the bits to set.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf, attributes::{SessionAttributes, NvIndexAttributes},
+    /// #     handles::NvIndexTpmHandle, interface_types::algorithm::HashingAlgorithm,
+    /// #     structures::{SymmetricDefinition, NvPublic}, constants::SessionType,
+    /// #     constants::nv_index_type::NvIndexType,
+    /// # };
+    /// use tss_esapi::interface_types::resource_handles::{Provision, NvAuth};
+    ///
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// # let session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         tss_esapi::interface_types::algorithm::HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributes::builder()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// # context.set_sessions((Some(session), None, None));
+    /// #
+    /// # let nv_index = NvIndexTpmHandle::new(0x0150002A)
+    /// #     .expect("Failed to create NV index tpm handle");
+    /// #
+    /// # // Create NV index attributes
+    /// # let owner_nv_index_attributes = NvIndexAttributes::builder()
+    /// #     .with_owner_write(true)
+    /// #     .with_owner_read(true)
+    /// #     .with_nv_index_type(NvIndexType::Bits)
+    /// #     .build()
+    /// #     .expect("Failed to create owner nv index attributes");
+    /// #
+    /// # // Create owner nv public.
+    /// # let owner_nv_public = NvPublic::builder()
+    /// #     .with_nv_index(nv_index)
+    /// #     .with_index_name_algorithm(HashingAlgorithm::Sha256)
+    /// #     .with_index_attributes(owner_nv_index_attributes)
+    /// #     .with_data_area_size(8)
+    /// #     .build()
+    /// #     .expect("Failed to build NvPublic for owner");
+    /// #
+    /// let nv_index_handle = context
+    ///     .nv_define_space(Provision::Owner, None, owner_nv_public)
+    ///     .expect("Call to nv_define_space failed");
+    ///
+    /// let nv_set_bits_result = context.nv_set_bits(NvAuth::Owner, nv_index_handle, 0b1010);
+    ///
+    /// context
+    ///     .nv_undefine_space(Provision::Owner, nv_index_handle)
+    ///     .expect("Call to nv_undefine_space failed");
+    ///
+    /// // Process result
+    /// nv_set_bits_result.expect("Call to nv_set_bits failed");
+    /// ```
+    pub fn nv_set_bits(
+        &mut self,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+        bits: u64,
+    ) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_SetBits(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        bits,
+                    )
+                },
+                |ret| error!("Error when setting bits in NV: {:#010X}", ret),
+            )
+        })
+    }
+
+    /// Prevents further writes to an nv index.
+    ///
+    /// # Details
+    /// This method is used to lock an NV index for writing. If the
+    /// index has the `write_stclear` attribute set, the lock is released
+    /// by the next TPM Reset or TPM Restart. If the index has the
+    /// `write_define` attribute set, the lock is permanent once the
+    /// index has been written, which allows write-once usage of the index.
+    ///
+    /// Please beware that this method requires an authorization
+    /// session handle to be present.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       that is to be locked.
+    ///
+    /// # Errors
+    /// * if the index has neither the `write_define` nor the `write_stclear`
+    ///   attribute set, an `InvalidParam` wrapper error is returned
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf, attributes::{SessionAttributes, NvIndexAttributes},
+    /// #     handles::NvIndexTpmHandle, interface_types::algorithm::HashingAlgorithm,
+    /// #     structures::{SymmetricDefinition, NvPublic}, constants::SessionType,
+    /// # };
+    /// use tss_esapi::interface_types::resource_handles::{Provision, NvAuth};
+    ///
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// # let session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         tss_esapi::interface_types::algorithm::HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributes::builder()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// # context.set_sessions((Some(session), None, None));
+    /// #
+    /// # let nv_index = NvIndexTpmHandle::new(0x0150002D)
+    /// #     .expect("Failed to create NV index tpm handle");
+    /// #
+    /// # // Create NV index attributes
+    /// # let owner_nv_index_attributes = NvIndexAttributes::builder()
+    /// #     .with_owner_write(true)
+    /// #     .with_owner_read(true)
+    /// #     .with_write_stclear(true)
+    /// #     .build()
+    /// #     .expect("Failed to create owner nv index attributes");
+    /// #
+    /// # // Create owner nv public.
+    /// # let owner_nv_public = NvPublic::builder()
+    /// #     .with_nv_index(nv_index)
+    /// #     .with_index_name_algorithm(HashingAlgorithm::Sha256)
+    /// #     .with_index_attributes(owner_nv_index_attributes)
+    /// #     .with_data_area_size(32)
+    /// #     .build()
+    /// #     .expect("Failed to build NvPublic for owner");
+    /// #
+    /// let nv_index_handle = context
+    ///     .nv_define_space(Provision::Owner, None, owner_nv_public)
+    ///     .expect("Call to nv_define_space failed");
+    ///
+    /// let nv_write_lock_result = context.nv_write_lock(NvAuth::Owner, nv_index_handle);
+    ///
+    /// context
+    ///     .nv_undefine_space(Provision::Owner, nv_index_handle)
+    ///     .expect("Call to nv_undefine_space failed");
+    ///
+    /// // Process result
+    /// nv_write_lock_result.expect("Call to nv_write_lock failed");
+    /// ```
+    pub fn nv_write_lock(
+        &mut self,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+    ) -> Result<()> {
+        let attributes = self.nv_index_attributes(nv_index_handle)?;
+        if !attributes.write_define() && !attributes.write_stclear() {
+            error!("The NV index cannot be write locked, as it has neither the write_define nor the write_stclear attribute set");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_WriteLock(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| error!("Error when write locking NV: {:#010X}", ret),
+            )
+        })
+    }
+
+    /// Prevents further writes to all the nv indices with the `global_lock` attribute set.
+    ///
+    /// # Details
+    /// This method is used to lock all the NV indices that have the
+    /// `global_lock` attribute set for writing. The lock is released by
+    /// the next TPM Reset or TPM Restart. Indices without the `global_lock`
+    /// attribute are not affected.
+    ///
+    /// Please beware that this method requires an authorization
+    /// session handle to be present.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - The [Provision] used for authorization.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf, attributes::{SessionAttributes, NvIndexAttributes},
+    /// #     handles::NvIndexTpmHandle, interface_types::algorithm::HashingAlgorithm,
+    /// #     structures::{SymmetricDefinition, NvPublic}, constants::SessionType,
+    /// # };
+    /// use tss_esapi::interface_types::resource_handles::{Provision, NvAuth};
+    ///
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// # let session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         tss_esapi::interface_types::algorithm::HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributes::builder()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// # context.set_sessions((Some(session), None, None));
+    /// #
+    /// # let nv_index = NvIndexTpmHandle::new(0x0150002E)
+    /// #     .expect("Failed to create NV index tpm handle");
+    /// #
+    /// # // Create NV index attributes
+    /// # let owner_nv_index_attributes = NvIndexAttributes::builder()
+    /// #     .with_owner_write(true)
+    /// #     .with_owner_read(true)
+    /// #     .with_global_lock(true)
+    /// #     .build()
+    /// #     .expect("Failed to create owner nv index attributes");
+    /// #
+    /// # // Create owner nv public.
+    /// # let owner_nv_public = NvPublic::builder()
+    /// #     .with_nv_index(nv_index)
+    /// #     .with_index_name_algorithm(HashingAlgorithm::Sha256)
+    /// #     .with_index_attributes(owner_nv_index_attributes)
+    /// #     .with_data_area_size(32)
+    /// #     .build()
+    /// #     .expect("Failed to build NvPublic for owner");
+    /// #
+    /// let nv_index_handle = context
+    ///     .nv_define_space(Provision::Owner, None, owner_nv_public)
+    ///     .expect("Call to nv_define_space failed");
+    ///
+    /// let nv_global_write_lock_result = context.nv_global_write_lock(Provision::Owner);
+    ///
+    /// context
+    ///     .nv_undefine_space(Provision::Owner, nv_index_handle)
+    ///     .expect("Call to nv_undefine_space failed");
+    ///
+    /// // Process result
+    /// nv_global_write_lock_result.expect("Call to nv_global_write_lock failed");
+    /// ```
+    pub fn nv_global_write_lock(&mut self, auth_handle: Provision) -> Result<()> {
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_GlobalWriteLock(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| error!("Error when global write locking NV: {:#010X}", ret),
+            )
+        })
+    }
 
     /// Reads data from the nv index.
     ///
@@ -665,28 +1117,261 @@ impl Context {
         offset: u16,
     ) -> Result<MaxNvBuffer> {
         let mut data_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_NV_Read(
-                    self.mut_context(),
-                    AuthHandle::from(auth_handle).into(),
-                    nv_index_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    size,
-                    offset,
-                    &mut data_ptr,
+        self.execute_with_parameter_encryption(CommandCode::NvRead, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_NV_Read(
+                            ctx.mut_context(),
+                            AuthHandle::from(auth_handle).into(),
+                            nv_index_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            size,
+                            offset,
+                            &mut data_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error when reading NV: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error when reading NV: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         MaxNvBuffer::try_from(Context::ffi_data_to_owned(data_ptr))
     }
 
-    // Missing function: NV_ReadLock
-    // Missing function: NV_ChangeAuth
-    // Missing function: NV_Certify
+    /// Prevents further reads from an nv index.
+    ///
+    /// # Details
+    /// This method is used to lock an NV index for reading. The lock
+    /// is released by the next TPM Reset or TPM Restart.
+    ///
+    /// Please beware that this method requires an authorization
+    /// session handle to be present.
+    ///
+    /// # Arguments
+    /// * `auth_handle` - Handle indicating the source of authorization value.
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       that is to be locked.
+    ///
+    /// # Errors
+    /// * if the index does not have the `read_stclear` attribute set, an
+    ///   `InvalidParam` wrapper error is returned
+    ///
+    /// # Example
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf, attributes::{SessionAttributes, NvIndexAttributes},
+    /// #     handles::NvIndexTpmHandle, interface_types::algorithm::HashingAlgorithm,
+    /// #     structures::{SymmetricDefinition, NvPublic}, constants::SessionType,
+    /// # };
+    /// use tss_esapi::interface_types::resource_handles::{Provision, NvAuth};
+    ///
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// #
+    /// # let session = context
+    /// #     .start_auth_session(
+    /// #         None,
+    /// #         None,
+    /// #         None,
+    /// #         SessionType::Hmac,
+    /// #         SymmetricDefinition::AES_256_CFB,
+    /// #         tss_esapi::interface_types::algorithm::HashingAlgorithm::Sha256,
+    /// #     )
+    /// #     .expect("Failed to create session")
+    /// #     .expect("Received invalid handle");
+    /// # let (session_attributes, session_attributes_mask) = SessionAttributes::builder()
+    /// #     .with_decrypt(true)
+    /// #     .with_encrypt(true)
+    /// #     .build();
+    /// # context.tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+    /// #     .expect("Failed to set attributes on session");
+    /// # context.set_sessions((Some(session), None, None));
+    /// #
+    /// # let nv_index = NvIndexTpmHandle::new(0x0150002F)
+    /// #     .expect("Failed to create NV index tpm handle");
+    /// #
+    /// # // Create NV index attributes
+    /// # let owner_nv_index_attributes = NvIndexAttributes::builder()
+    /// #     .with_owner_write(true)
+    /// #     .with_owner_read(true)
+    /// #     .with_read_stclear(true)
+    /// #     .build()
+    /// #     .expect("Failed to create owner nv index attributes");
+    /// #
+    /// # // Create owner nv public.
+    /// # let owner_nv_public = NvPublic::builder()
+    /// #     .with_nv_index(nv_index)
+    /// #     .with_index_name_algorithm(HashingAlgorithm::Sha256)
+    /// #     .with_index_attributes(owner_nv_index_attributes)
+    /// #     .with_data_area_size(32)
+    /// #     .build()
+    /// #     .expect("Failed to build NvPublic for owner");
+    /// #
+    /// let nv_index_handle = context
+    ///     .nv_define_space(Provision::Owner, None, owner_nv_public)
+    ///     .expect("Call to nv_define_space failed");
+    ///
+    /// let nv_read_lock_result = context.nv_read_lock(NvAuth::Owner, nv_index_handle);
+    ///
+    /// context
+    ///     .nv_undefine_space(Provision::Owner, nv_index_handle)
+    ///     .expect("Call to nv_undefine_space failed");
+    ///
+    /// // Process result
+    /// nv_read_lock_result.expect("Call to nv_read_lock failed");
+    /// ```
+    pub fn nv_read_lock(
+        &mut self,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+    ) -> Result<()> {
+        let attributes = self.nv_index_attributes(nv_index_handle)?;
+        if !attributes.read_stclear() {
+            error!("The NV index cannot be read locked, as it does not have the read_stclear attribute set");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_ReadLock(
+                        ctx.mut_context(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| error!("Error when read locking NV: {:#010X}", ret),
+            )
+        })
+    }
+
+    /// Changes the authorization value of an nv index.
+    ///
+    /// # Details
+    /// This method is used to change the authorization value of an NV
+    /// index. On success, the new authorization value is also set on the
+    /// nv index handle in the context (see [Context::tr_set_auth]), so that
+    /// subsequent commands using the index are authorized with it.
+    ///
+    /// The command requires the ADMIN role, so the first session must be
+    /// a policy session that satisfies the authorization policy of the index.
+    /// The policy of the index must include
+    /// [Context::policy_command_code] with
+    /// [CommandCode::NvChangeAuth](crate::constants::CommandCode::NvChangeAuth),
+    /// and the same command code must be asserted in the policy session
+    /// before calling this method. Because of this, the policy session must
+    /// be set as the first session of the context and HMAC sessions cannot
+    /// be used to authorize the command.
+    ///
+    /// # Arguments
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       for which the authorization value is changed.
+    /// * `new_auth` - The new authorization value of the index.
+    pub fn nv_change_auth(&mut self, nv_index_handle: NvIndexHandle, new_auth: Auth) -> Result<()> {
+        self.execute_with_parameter_encryption(CommandCode::NvChangeAuth, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_NV_ChangeAuth(
+                            ctx.mut_context(),
+                            nv_index_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &new_auth.clone().into(),
+                        )
+                    },
+                    |ret| error!("Error when changing NV auth: {:#010X}", ret),
+                )
+            })
+        })?;
+        self.tr_set_auth(nv_index_handle.into(), new_auth)
+    }
+
+    /// Certifies the contents of an nv index.
+    ///
+    /// # Arguments
+    /// * `signing_key_handle` - Handle of the key used to sign the attestation buffer.
+    /// * `auth_handle` - Handle indicating the source of authorization value
+    ///                   for reading the nv index.
+    /// * `nv_index_handle` - The [NvIndexHandle] associated with NV memory
+    ///                       that is to be certified.
+    /// * `qualifying_data` - Qualifying data.
+    /// * `signing_scheme` - Signing scheme to use if the scheme for `signing_key_handle` is `Null`.
+    /// * `size` - The number of octets to certify.
+    /// * `offset` - Octet offset into the NV area.
+    ///
+    /// # Details
+    /// This command requires an authorization session for the signing key
+    /// and another one for reading the nv index.
+    ///
+    /// The attested data contains an [AttestInfo::Nv](crate::structures::AttestInfo::Nv)
+    /// with the name of the index and the certified contents. The attested data
+    /// can be checked using [crate::abstraction::attestation::verify_nv_attestation].
+    ///
+    /// # Returns
+    /// The command returns a tuple consisting of:
+    /// * `attest_data` - TPM-generated attestation data.
+    /// * `signature` - Signature for the attestation data.
+    ///
+    /// # Errors
+    /// * if the qualifying data provided is too long, a `WrongParamSize` wrapper error will be returned
+    #[allow(clippy::too_many_arguments)]
+    pub fn nv_certify(
+        &mut self,
+        signing_key_handle: KeyHandle,
+        auth_handle: NvAuth,
+        nv_index_handle: NvIndexHandle,
+        qualifying_data: Data,
+        signing_scheme: SignatureScheme,
+        size: u16,
+        offset: u16,
+    ) -> Result<(Attest, Signature)> {
+        let mut certify_info_ptr = null_mut();
+        let mut signature_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_NV_Certify(
+                        ctx.mut_context(),
+                        signing_key_handle.into(),
+                        AuthHandle::from(auth_handle).into(),
+                        nv_index_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &qualifying_data.clone().into(),
+                        &signing_scheme.into(),
+                        size,
+                        offset,
+                        &mut certify_info_ptr,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| error!("Error when certifying NV: {:#010X}", ret),
+            )
+        })?;
+
+        let certify_info = Context::ffi_data_to_owned(certify_info_ptr);
+        let signature = Context::ffi_data_to_owned(signature_ptr);
+        Ok((
+            Attest::try_from(AttestBuffer::try_from(certify_info)?)?,
+            Signature::try_from(signature)?,
+        ))
+    }
+
+    /// Private function for retrieving the attributes of an nv index.
+    fn nv_index_attributes(&mut self, nv_index_handle: NvIndexHandle) -> Result<NvIndexAttributes> {
+        self.execute_without_session(|ctx| ctx.nv_read_public(nv_index_handle))
+            .map(|(nv_public, _)| nv_public.attributes())
+    }
 }
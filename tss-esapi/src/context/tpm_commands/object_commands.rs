@@ -4,16 +4,17 @@ mod create_command_input;
 mod create_command_output;
 
 use crate::{
+    constants::CommandCode,
     context::handle_manager::HandleDropAction,
     handles::{KeyHandle, ObjectHandle, TpmHandle},
     interface_types::resource_handles::Hierarchy,
     structures::{
-        Auth, CreateKeyResult, Data, Digest, EncryptedSecret, IdObject, Name, PcrSelectionList,
-        Private, Public, Sensitive, SensitiveData,
+        Auth, CreateKeyResult, CreateLoadedKeyResult, Data, Digest, EncryptedSecret, IdObject,
+        Name, PcrSelectionList, Private, Public, Sensitive, SensitiveCreate, SensitiveData,
     },
     tss2_esys::{
-        Esys_ActivateCredential, Esys_Create, Esys_Load, Esys_LoadExternal, Esys_MakeCredential,
-        Esys_ObjectChangeAuth, Esys_ReadPublic, Esys_Unseal,
+        Esys_ActivateCredential, Esys_Create, Esys_CreateLoaded, Esys_Load, Esys_LoadExternal,
+        Esys_MakeCredential, Esys_ObjectChangeAuth, Esys_ReadPublic, Esys_Unseal,
     },
     Context, Result, ReturnCode,
 };
@@ -64,29 +65,33 @@ impl Context {
 
         let mut output_parameters = CreateCommandOutputHandler::new();
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Create(
-                    self.mut_context(),
-                    input_parameters.ffi_in_parent_handle(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    input_parameters.ffi_in_sensitive(),
-                    input_parameters.ffi_in_public(),
-                    input_parameters.ffi_outside_info(),
-                    input_parameters.ffi_creation_pcr(),
-                    output_parameters.ffi_out_private_ptr(),
-                    output_parameters.ffi_out_public_ptr(),
-                    output_parameters.ffi_creation_data_ptr(),
-                    output_parameters.ffi_creation_hash_ptr(),
-                    output_parameters.ffi_creation_ticket_ptr(),
+        self.execute_with_parameter_encryption(CommandCode::Create, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_Create(
+                            ctx.mut_context(),
+                            input_parameters.ffi_in_parent_handle(),
+                            ctx.optional_session_1(),
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            input_parameters.ffi_in_sensitive(),
+                            input_parameters.ffi_in_public(),
+                            input_parameters.ffi_outside_info(),
+                            input_parameters.ffi_creation_pcr(),
+                            output_parameters.ffi_out_private_ptr(),
+                            output_parameters.ffi_out_public_ptr(),
+                            output_parameters.ffi_creation_data_ptr(),
+                            output_parameters.ffi_creation_hash_ptr(),
+                            output_parameters.ffi_creation_ticket_ptr(),
+                        )
+                    },
+                    |ret| {
+                        error!("Error in creating derived key: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error in creating derived key: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
 
         output_parameters.try_into()
     }
@@ -99,23 +104,25 @@ impl Context {
         public: Public,
     ) -> Result<KeyHandle> {
         let mut object_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Load(
-                    self.mut_context(),
-                    parent_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &private.into(),
-                    &public.try_into()?,
-                    &mut object_handle,
-                )
-            },
-            |ret| {
-                error!("Error in loading: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Load(
+                        ctx.mut_context(),
+                        parent_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &private.clone().into(),
+                        &public.clone().try_into()?,
+                        &mut object_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error in loading: {:#010X}", ret);
+                },
+            )
+        })?;
         let key_handle = KeyHandle::from(object_handle);
         self.handle_manager
             .add_handle(key_handle.into(), HandleDropAction::Flush)?;
@@ -130,27 +137,31 @@ impl Context {
         hierarchy: Hierarchy,
     ) -> Result<KeyHandle> {
         let mut object_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_LoadExternal(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &private.try_into()?,
-                    &public.try_into()?,
-                    if cfg!(hierarchy_is_esys_tr) {
-                        ObjectHandle::from(hierarchy).into()
-                    } else {
-                        TpmHandle::from(hierarchy).into()
+        self.execute_with_parameter_encryption(CommandCode::LoadExternal, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_LoadExternal(
+                            ctx.mut_context(),
+                            ctx.optional_session_1(),
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &private.clone().try_into()?,
+                            &public.clone().try_into()?,
+                            if cfg!(hierarchy_is_esys_tr) {
+                                ObjectHandle::from(hierarchy).into()
+                            } else {
+                                TpmHandle::from(hierarchy).into()
+                            },
+                            &mut object_handle,
+                        )
+                    },
+                    |ret| {
+                        error!("Error in loading external object: {:#010X}", ret);
                     },
-                    &mut object_handle,
                 )
-            },
-            |ret| {
-                error!("Error in loading external object: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
 
         let key_handle = KeyHandle::from(object_handle);
         self.handle_manager
@@ -165,27 +176,29 @@ impl Context {
         hierarchy: Hierarchy,
     ) -> Result<KeyHandle> {
         let mut object_handle = ObjectHandle::None.into();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_LoadExternal(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    null(),
-                    &public.try_into()?,
-                    if cfg!(hierarchy_is_esys_tr) {
-                        ObjectHandle::from(hierarchy).into()
-                    } else {
-                        TpmHandle::from(hierarchy).into()
-                    },
-                    &mut object_handle,
-                )
-            },
-            |ret| {
-                error!("Error in loading external public object: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_LoadExternal(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        null(),
+                        &public.clone().try_into()?,
+                        if cfg!(hierarchy_is_esys_tr) {
+                            ObjectHandle::from(hierarchy).into()
+                        } else {
+                            TpmHandle::from(hierarchy).into()
+                        },
+                        &mut object_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error in loading external public object: {:#010X}", ret);
+                },
+            )
+        })?;
 
         let key_handle = KeyHandle::from(object_handle);
         self.handle_manager
@@ -198,23 +211,25 @@ impl Context {
         let mut out_public_ptr = null_mut();
         let mut name_ptr = null_mut();
         let mut qualified_name_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ReadPublic(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut out_public_ptr,
-                    &mut name_ptr,
-                    &mut qualified_name_ptr,
-                )
-            },
-            |ret| {
-                error!("Error in reading public part of object: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ReadPublic(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut out_public_ptr,
+                        &mut name_ptr,
+                        &mut qualified_name_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in reading public part of object: {:#010X}", ret);
+                },
+            )
+        })?;
         Ok((
             Public::try_from(Context::ffi_data_to_owned(out_public_ptr))?,
             Name::try_from(Context::ffi_data_to_owned(name_ptr))?,
@@ -231,24 +246,26 @@ impl Context {
         secret: EncryptedSecret,
     ) -> Result<Digest> {
         let mut cert_info_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ActivateCredential(
-                    self.mut_context(),
-                    activate_handle.into(),
-                    key_handle.into(),
-                    self.required_session_1()?,
-                    self.required_session_2()?,
-                    self.optional_session_3(),
-                    &credential_blob.into(),
-                    &secret.into(),
-                    &mut cert_info_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when activating credential: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_ActivateCredential(
+                        ctx.mut_context(),
+                        activate_handle.into(),
+                        key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.required_session_2()?,
+                        ctx.optional_session_3(),
+                        &credential_blob.clone().into(),
+                        &secret.clone().into(),
+                        &mut cert_info_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when activating credential: {:#010X}", ret);
+                },
+            )
+        })?;
 
         Digest::try_from(Context::ffi_data_to_owned(cert_info_ptr))
     }
@@ -264,24 +281,26 @@ impl Context {
     ) -> Result<(IdObject, EncryptedSecret)> {
         let mut credential_blob_ptr = null_mut();
         let mut secret_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_MakeCredential(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &credential.into(),
-                    object_name.as_ref(),
-                    &mut credential_blob_ptr,
-                    &mut secret_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when making credential: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_MakeCredential(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &credential.clone().into(),
+                        object_name.as_ref(),
+                        &mut credential_blob_ptr,
+                        &mut secret_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when making credential: {:#010X}", ret);
+                },
+            )
+        })?;
         Ok((
             IdObject::try_from(Context::ffi_data_to_owned(credential_blob_ptr))?,
             EncryptedSecret::try_from(Context::ffi_data_to_owned(secret_ptr))?,
@@ -292,21 +311,25 @@ impl Context {
     pub fn unseal(&mut self, item_handle: ObjectHandle) -> Result<SensitiveData> {
         let mut out_data_ptr = null_mut();
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Unseal(
-                    self.mut_context(),
-                    item_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut out_data_ptr,
+        self.execute_with_parameter_encryption(CommandCode::Unseal, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_Unseal(
+                            ctx.mut_context(),
+                            item_handle.into(),
+                            ctx.optional_session_1(),
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &mut out_data_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error in unsealing: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error in unsealing: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         SensitiveData::try_from(Context::ffi_data_to_owned(out_data_ptr))
     }
 
@@ -318,25 +341,131 @@ impl Context {
         new_auth: Auth,
     ) -> Result<Private> {
         let mut out_private_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_ObjectChangeAuth(
-                    self.mut_context(),
-                    object_handle.into(),
-                    parent_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &new_auth.into(),
-                    &mut out_private_ptr,
+        self.execute_with_parameter_encryption(CommandCode::ObjectChangeAuth, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_ObjectChangeAuth(
+                            ctx.mut_context(),
+                            object_handle.into(),
+                            parent_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &new_auth.clone().into(),
+                            &mut out_private_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error changing object auth: {:#010X}", ret);
+                    },
                 )
-            },
-            |ret| {
-                error!("Error changing object auth: {:#010X}", ret);
-            },
-        )?;
+            })
+        })?;
         Private::try_from(Context::ffi_data_to_owned(out_private_ptr))
     }
 
-    // Missing function: CreateLoaded
+    /// Change the authorization value of a key and load the key with it.
+    ///
+    /// # Details
+    /// The key is not modified by the TPM, [Context::object_change_auth] returns
+    /// a new private area protected by `new_auth`. This method loads the new private
+    /// area under `parent_handle` and sets `new_auth` on the handle of the new key
+    /// (see [Context::tr_set_auth]).
+    ///
+    /// The key loaded with the previous authorization value is not flushed.
+    ///
+    /// # Arguments
+    /// * `key_handle` - The handle of the key, authorized with its current authorization value.
+    /// * `parent_handle` - The handle of the parent of the key.
+    /// * `new_auth` - The new authorization value of the key.
+    ///
+    /// # Returns
+    /// The handle of the key loaded with the new authorization value, and the new
+    /// private area of the key that replaces the previous one in storage.
+    pub fn change_key_auth(
+        &mut self,
+        key_handle: KeyHandle,
+        parent_handle: KeyHandle,
+        new_auth: Auth,
+    ) -> Result<(KeyHandle, Private)> {
+        let (public, _, _) = self.read_public(key_handle)?;
+        let private =
+            self.object_change_auth(key_handle.into(), parent_handle.into(), new_auth.clone())?;
+        let new_key_handle = self.load(parent_handle, private.clone(), public)?;
+        self.tr_set_auth(new_key_handle.into(), new_auth)
+            .or_else(|e| {
+                self.flush_context(new_key_handle.into())?;
+                Err(e)
+            })?;
+        Ok((new_key_handle, private))
+    }
+
+    /// Create an object and load it into the TPM.
+    ///
+    /// # Details
+    /// This command combines [Context::create] and [Context::load] into a single
+    /// call to the TPM. In addition to ordinary and derived objects, this command
+    /// can create primary objects and in that case the returned private area is empty.
+    ///
+    /// # Arguments
+    /// * `parent_handle` - The [ObjectHandle] of the parent for the new object. This
+    ///                     can be a storage key, a derivation parent or, if a primary
+    ///                     object is to be created, one of the hierarchies.
+    /// * `public` - The public template of the object that is being created.
+    /// * `auth_value` - The value used to be used for authorize usage of the object.
+    /// * `sensitive_data` - The data that is to be sealed, a key or derivation values.
+    ///
+    /// This command requires an authorization session for the parent.
+    ///
+    /// # Returns
+    /// The handle of the loaded object, together with its private and public areas.
+    pub fn create_loaded(
+        &mut self,
+        parent_handle: ObjectHandle,
+        public: Public,
+        auth_value: Option<Auth>,
+        sensitive_data: Option<SensitiveData>,
+    ) -> Result<CreateLoadedKeyResult> {
+        let sensitive_create = SensitiveCreate::new(
+            auth_value.unwrap_or_default(),
+            sensitive_data.unwrap_or_default(),
+        );
+        let mut object_handle = ObjectHandle::None.into();
+        let mut out_private_ptr = null_mut();
+        let mut out_public_ptr = null_mut();
+        self.execute_with_parameter_encryption(CommandCode::CreateLoaded, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_CreateLoaded(
+                            ctx.mut_context(),
+                            parent_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &sensitive_create.clone().try_into()?,
+                            &public.clone().try_into()?,
+                            &mut object_handle,
+                            &mut out_private_ptr,
+                            &mut out_public_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!("Error in creating and loading object: {:#010X}", ret);
+                    },
+                )
+            })
+        })?;
+        let out_private_owned = Context::ffi_data_to_owned(out_private_ptr);
+        let out_public_owned = Context::ffi_data_to_owned(out_public_ptr);
+        let key_handle = KeyHandle::from(object_handle);
+        self.handle_manager
+            .add_handle(key_handle.into(), HandleDropAction::Flush)?;
+        Ok(CreateLoadedKeyResult {
+            key_handle,
+            out_private: Private::try_from(out_private_owned)?,
+            out_public: Public::try_from(out_public_owned)?,
+        })
+    }
 }
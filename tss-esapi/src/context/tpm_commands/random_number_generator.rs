@@ -1,6 +1,7 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::CommandCode,
     structures::{Digest, SensitiveData},
     tss2_esys::{Esys_GetRandom, Esys_StirRandom},
     Context, Error, Result, ReturnCode, WrapperErrorKind as ErrorKind,
@@ -15,42 +16,58 @@ impl Context {
     /// # Errors
     /// * if converting `num_bytes` to `u16` fails, a `WrongParamSize` will be returned
     pub fn get_random(&mut self, num_bytes: usize) -> Result<Digest> {
+        self.execute_with_parameter_encryption(CommandCode::GetRandom, |ctx| {
+            ctx.get_random_in_clear(num_bytes)
+        })
+    }
+
+    /// Get a number of random bytes from the TPM, without encrypting them
+    /// even if the automatic parameter encryption is enabled.
+    ///
+    /// # Details
+    /// Used for random values that are sent in clear to the TPM afterwards,
+    /// e.g. the nonce of the caller of a session.
+    pub(crate) fn get_random_in_clear(&mut self, num_bytes: usize) -> Result<Digest> {
         let mut random_bytes_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_GetRandom(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    num_bytes
-                        .try_into()
-                        .map_err(|_| Error::local_error(ErrorKind::WrongParamSize))?,
-                    &mut random_bytes_ptr,
-                )
-            },
-            |ret| {
-                error!("Error in getting random bytes: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetRandom(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        num_bytes
+                            .try_into()
+                            .map_err(|_| Error::local_error(ErrorKind::WrongParamSize))?,
+                        &mut random_bytes_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in getting random bytes: {:#010X}", ret);
+                },
+            )
+        })?;
         Digest::try_from(Context::ffi_data_to_owned(random_bytes_ptr))
     }
 
     /// Add additional information into the TPM RNG state
     pub fn stir_random(&mut self, in_data: SensitiveData) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_StirRandom(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &in_data.into(),
-                )
-            },
-            |ret| {
-                error!("Error stirring random: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_StirRandom(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &in_data.clone().into(),
+                    )
+                },
+                |ret| {
+                    error!("Error stirring random: {:#010X}", ret);
+                },
+            )
+        })
     }
 }
@@ -1,6 +1,7 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    abstraction::session::SessionGuard,
     constants::SessionType,
     context::handle_manager::HandleDropAction,
     handles::{KeyHandle, ObjectHandle, SessionHandle},
@@ -9,8 +10,11 @@ use crate::{
         session_handles::{AuthSession, PolicySession},
     },
     structures::{Nonce, SymmetricDefinition},
-    tss2_esys::{Esys_PolicyRestart, Esys_StartAuthSession},
-    Context, Result, ReturnCode,
+    tss2_esys::{
+        Esys_PolicyRestart, Esys_StartAuthSession, TPM2_SHA1_DIGEST_SIZE, TPM2_SHA256_DIGEST_SIZE,
+        TPM2_SHA384_DIGEST_SIZE, TPM2_SHA512_DIGEST_SIZE, TPM2_SM3_256_DIGEST_SIZE,
+    },
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
 };
 use log::error;
 use std::{convert::TryInto, ptr::null};
@@ -21,6 +25,17 @@ impl Context {
     /// If the returned session handle from ESYS api is ESYS_TR_NONE then
     /// the value of the option in the result will be None.
     ///
+    /// # Details
+    /// The `nonce` is the nonceCaller of the session. When it is None, a
+    /// random nonce with the size of the digest of `auth_hash` is generated.
+    /// A nonce provided by the caller, for instance to bind external evidence
+    /// to the session or to reproduce test vectors, must be at least 16 bytes
+    /// long and no longer than the digest of `auth_hash`.
+    ///
+    /// # Errors
+    /// * if the size of `nonce` is invalid for `auth_hash`, a `WrongParamSize`
+    ///   wrapper error is returned
+    ///
     /// # Example
     ///
     /// ```rust
@@ -58,31 +73,36 @@ impl Context {
         symmetric: SymmetricDefinition,
         auth_hash: HashingAlgorithm,
     ) -> Result<Option<AuthSession>> {
+        if let Some(nonce) = &nonce {
+            ensure_valid_nonce_caller_size(nonce, auth_hash)?;
+        }
         let mut session_handle = ObjectHandle::None.into();
         let potential_tpm2b_nonce = nonce.map(|v| v.into());
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_StartAuthSession(
-                    self.mut_context(),
-                    tpm_key
-                        .map(ObjectHandle::from)
-                        .unwrap_or(ObjectHandle::None)
-                        .into(),
-                    bind.unwrap_or(ObjectHandle::None).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    potential_tpm2b_nonce.as_ref().map_or_else(null, |v| v),
-                    session_type.into(),
-                    &symmetric.try_into()?,
-                    auth_hash.into(),
-                    &mut session_handle,
-                )
-            },
-            |ret| {
-                error!("Error when creating a session: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_StartAuthSession(
+                        ctx.mut_context(),
+                        tpm_key
+                            .map(ObjectHandle::from)
+                            .unwrap_or(ObjectHandle::None)
+                            .into(),
+                        bind.unwrap_or(ObjectHandle::None).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        potential_tpm2b_nonce.as_ref().map_or_else(null, |v| v),
+                        session_type.into(),
+                        &symmetric.try_into()?,
+                        auth_hash.into(),
+                        &mut session_handle,
+                    )
+                },
+                |ret| {
+                    error!("Error when creating a session: {:#010X}", ret);
+                },
+            )
+        })?;
 
         self.handle_manager
             .add_handle(session_handle.into(), HandleDropAction::Flush)?;
@@ -93,21 +113,80 @@ impl Context {
         ))
     }
 
+    /// Start new authentication session and return a guard that flushes
+    /// the session when it is dropped.
+    ///
+    /// # Details
+    /// The parameters are the ones of [start_auth_session](Context::start_auth_session).
+    /// The guard borrows the context, which remains accessible through it, see
+    /// [SessionGuard].
+    ///
+    /// # Errors
+    /// * if the TPM does not return a handle for the session, a `WrongValueFromTpm`
+    ///   wrapper error is returned
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_scoped_auth_session(
+        &mut self,
+        tpm_key: Option<KeyHandle>,
+        bind: Option<ObjectHandle>,
+        nonce: Option<Nonce>,
+        session_type: SessionType,
+        symmetric: SymmetricDefinition,
+        auth_hash: HashingAlgorithm,
+    ) -> Result<SessionGuard<'_>> {
+        let session = self
+            .start_auth_session(tpm_key, bind, nonce, session_type, symmetric, auth_hash)?
+            .ok_or_else(|| {
+                error!("Received an invalid handle when starting the session");
+                Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+            })?;
+        Ok(SessionGuard::new(self, session))
+    }
+
     /// Restart the TPM Policy
     pub fn policy_restart(&mut self, policy_session: PolicySession) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_PolicyRestart(
-                    self.mut_context(),
-                    SessionHandle::from(policy_session).into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                )
-            },
-            |ret| {
-                error!("Error restarting policy: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_PolicyRestart(
+                        ctx.mut_context(),
+                        SessionHandle::from(policy_session).into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                    )
+                },
+                |ret| {
+                    error!("Error restarting policy: {:#010X}", ret);
+                },
+            )
+        })
+    }
+}
+
+/// The minimum size of the nonceCaller of a session.
+const MIN_NONCE_CALLER_SIZE: usize = 16;
+
+/// Private function ensuring that the nonceCaller of a session is at least
+/// 16 bytes long, and no longer than the digest of the session hash.
+fn ensure_valid_nonce_caller_size(nonce: &Nonce, auth_hash: HashingAlgorithm) -> Result<()> {
+    let max_size = match auth_hash {
+        HashingAlgorithm::Sha1 => TPM2_SHA1_DIGEST_SIZE,
+        HashingAlgorithm::Sha256 | HashingAlgorithm::Sha3_256 => TPM2_SHA256_DIGEST_SIZE,
+        HashingAlgorithm::Sm3_256 => TPM2_SM3_256_DIGEST_SIZE,
+        HashingAlgorithm::Sha384 | HashingAlgorithm::Sha3_384 => TPM2_SHA384_DIGEST_SIZE,
+        HashingAlgorithm::Sha512 | HashingAlgorithm::Sha3_512 => TPM2_SHA512_DIGEST_SIZE,
+        // The TPM rejects the session hash.
+        HashingAlgorithm::Null => return Ok(()),
+    } as usize;
+    if nonce.len() < MIN_NONCE_CALLER_SIZE || nonce.len() > max_size {
+        error!(
+            "Invalid nonceCaller size {} (expected between {} and {} bytes)",
+            nonce.len(),
+            MIN_NONCE_CALLER_SIZE,
+            max_size
+        );
+        return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
     }
+    Ok(())
 }
@@ -19,23 +19,25 @@ impl Context {
         signature: Signature,
     ) -> Result<VerifiedTicket> {
         let mut validation_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_VerifySignature(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &digest.into(),
-                    &signature.try_into()?,
-                    &mut validation_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when verifying signature: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_VerifySignature(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &digest.clone().into(),
+                        &signature.clone().try_into()?,
+                        &mut validation_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when verifying signature: {:#010X}", ret);
+                },
+            )
+        })?;
         VerifiedTicket::try_from(Context::ffi_data_to_owned(validation_ptr))
     }
 
@@ -48,24 +50,26 @@ impl Context {
         validation: HashcheckTicket,
     ) -> Result<Signature> {
         let mut signature_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Sign(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &digest.into(),
-                    &scheme.into(),
-                    &validation.try_into()?,
-                    &mut signature_ptr,
-                )
-            },
-            |ret| {
-                error!("Error when signing: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Sign(
+                        ctx.mut_context(),
+                        key_handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &digest.clone().into(),
+                        &scheme.into(),
+                        &validation.clone().try_into()?,
+                        &mut signature_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when signing: {:#010X}", ret);
+                },
+            )
+        })?;
         Signature::try_from(Context::ffi_data_to_owned(signature_ptr))
     }
 }
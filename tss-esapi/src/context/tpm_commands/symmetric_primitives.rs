@@ -1,6 +1,7 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::CommandCode,
     handles::{KeyHandle, ObjectHandle, TpmHandle},
     interface_types::{
         algorithm::{HashingAlgorithm, SymmetricMode},
@@ -193,29 +194,33 @@ impl Context {
     ) -> Result<(MaxBuffer, InitialValue)> {
         let mut out_data_ptr = null_mut();
         let mut iv_out_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_EncryptDecrypt2(
-                    self.mut_context(),
-                    key_handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &in_data.into(),
-                    decrypt.into(),
-                    mode.into(),
-                    &initial_value_in.into(),
-                    &mut out_data_ptr,
-                    &mut iv_out_ptr,
+        self.execute_with_parameter_encryption(CommandCode::EncryptDecrypt2, |ctx| {
+            ctx.execute_with_retry(|ctx| {
+                ReturnCode::ensure_success(
+                    unsafe {
+                        Esys_EncryptDecrypt2(
+                            ctx.mut_context(),
+                            key_handle.into(),
+                            ctx.required_session_1()?,
+                            ctx.optional_session_2(),
+                            ctx.optional_session_3(),
+                            &in_data.clone().into(),
+                            decrypt.into(),
+                            mode.into(),
+                            &initial_value_in.clone().into(),
+                            &mut out_data_ptr,
+                            &mut iv_out_ptr,
+                        )
+                    },
+                    |ret| {
+                        error!(
+                            "Error failed to perform encrypt or decrypt operations {:#010X}",
+                            ret
+                        );
+                    },
                 )
-            },
-            |ret| {
-                error!(
-                    "Error failed to perform encrypt or decrypt operations {:#010X}",
-                    ret
-                );
-            },
-        )?;
+            })
+        })?;
         Ok((
             MaxBuffer::try_from(Context::ffi_data_to_owned(out_data_ptr))?,
             InitialValue::try_from(Context::ffi_data_to_owned(iv_out_ptr))?,
@@ -269,28 +274,30 @@ impl Context {
     ) -> Result<(Digest, HashcheckTicket)> {
         let mut out_hash_ptr = null_mut();
         let mut validation_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_Hash(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &data.into(),
-                    hashing_algorithm.into(),
-                    if cfg!(hierarchy_is_esys_tr) {
-                        ObjectHandle::from(hierarchy).into()
-                    } else {
-                        TpmHandle::from(hierarchy).into()
-                    },
-                    &mut out_hash_ptr,
-                    &mut validation_ptr,
-                )
-            },
-            |ret| {
-                error!("Error failed to perform hash operation: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Hash(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &data.clone().into(),
+                        hashing_algorithm.into(),
+                        if cfg!(hierarchy_is_esys_tr) {
+                            ObjectHandle::from(hierarchy).into()
+                        } else {
+                            TpmHandle::from(hierarchy).into()
+                        },
+                        &mut out_hash_ptr,
+                        &mut validation_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error failed to perform hash operation: {:#010X}", ret);
+                },
+            )
+        })?;
         Ok((
             Digest::try_from(Context::ffi_data_to_owned(out_hash_ptr))?,
             HashcheckTicket::try_from(Context::ffi_data_to_owned(validation_ptr))?,
@@ -354,23 +361,25 @@ impl Context {
         alg_hash: HashingAlgorithm,
     ) -> Result<Digest> {
         let mut out_hmac_ptr = null_mut();
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_HMAC(
-                    self.mut_context(),
-                    handle.into(),
-                    self.required_session_1()?,
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &buffer.into(),
-                    alg_hash.into(),
-                    &mut out_hmac_ptr,
-                )
-            },
-            |ret| {
-                error!("Error in hmac: {:#010X}", ret);
-            },
-        )?;
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_HMAC(
+                        ctx.mut_context(),
+                        handle.into(),
+                        ctx.required_session_1()?,
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &buffer.clone().into(),
+                        alg_hash.into(),
+                        &mut out_hmac_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in hmac: {:#010X}", ret);
+                },
+            )
+        })?;
         Digest::try_from(Context::ffi_data_to_owned(out_hmac_ptr))
     }
 
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     interface_types::YesNo,
-    structures::MaxBuffer,
-    tss2_esys::{Esys_GetTestResult, Esys_SelfTest},
+    structures::{AlgorithmList, MaxBuffer, TestResult},
+    tss2_esys::{Esys_GetTestResult, Esys_IncrementalSelfTest, Esys_SelfTest},
     Context, Result, ReturnCode,
 };
 use log::error;
@@ -13,49 +13,113 @@ use std::ptr::null_mut;
 impl Context {
     /// Execute the TPM self test and returns the result
     pub fn self_test(&mut self, full_test: bool) -> Result<()> {
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_SelfTest(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    YesNo::from(full_test).into(),
-                )
-            },
-            |ret| {
-                error!("Error in self-test: {:#010X}", ret);
-            },
-        )
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_SelfTest(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        YesNo::from(full_test).into(),
+                    )
+                },
+                |ret| {
+                    error!("Error in self-test: {:#010X}", ret);
+                },
+            )
+        })
     }
 
-    // Missing function: incremental_self_test
+    /// Test the selected algorithms
+    ///
+    /// # Details
+    /// The TPM tests the algorithms in `to_test` that have not been tested yet.
+    /// Depending on the TPM, the tests may be run in the background, in which case
+    /// the command returns before they complete and [Context::get_test_result] can
+    /// be used to find out their outcome.
+    ///
+    /// # Arguments
+    /// * `to_test` - An [AlgorithmList] with the algorithms to be tested.
+    ///
+    /// # Returns
+    /// An [AlgorithmList] with the algorithms that still need to be tested.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use tss_esapi::{
+    /// #     Context, TctiNameConf,
+    /// #     constants::AlgorithmIdentifier,
+    /// #     structures::AlgorithmList,
+    /// # };
+    /// # use std::convert::TryFrom;
+    /// # // Create context
+    /// # let mut context =
+    /// #     Context::new(
+    /// #         TctiNameConf::from_environment_variable().expect("Failed to get TCTI"),
+    /// #     ).expect("Failed to create Context");
+    /// let to_test = AlgorithmList::try_from(vec![
+    ///     AlgorithmIdentifier::Sha256,
+    ///     AlgorithmIdentifier::Aes,
+    /// ])
+    /// .expect("Failed to create algorithm list");
+    /// let _to_do_list = context
+    ///     .incremental_self_test(to_test)
+    ///     .expect("Failed to run the incremental self test");
+    /// ```
+    pub fn incremental_self_test(&mut self, to_test: AlgorithmList) -> Result<AlgorithmList> {
+        let mut to_do_list_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_IncrementalSelfTest(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &to_test.clone().into(),
+                        &mut to_do_list_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error in incremental self-test: {:#010X}", ret);
+                },
+            )
+        })?;
+        AlgorithmList::try_from(Context::ffi_data_to_owned(to_do_list_ptr))
+    }
 
     /// Get the TPM self test result
     ///
-    /// The returned buffer data is manufacturer-specific information.
-    pub fn get_test_result(&mut self) -> Result<(MaxBuffer, Result<()>)> {
+    /// # Returns
+    /// A [TestResult] with the manufacturer-specific information about the
+    /// self tests and their outcome. The outcome is a `TPM_RC_TESTING`
+    /// warning while the tests are still running.
+    pub fn get_test_result(&mut self) -> Result<TestResult> {
         let mut out_data_ptr = null_mut();
         let mut test_result: u32 = 0;
 
-        ReturnCode::ensure_success(
-            unsafe {
-                Esys_GetTestResult(
-                    self.mut_context(),
-                    self.optional_session_1(),
-                    self.optional_session_2(),
-                    self.optional_session_3(),
-                    &mut out_data_ptr,
-                    &mut test_result,
-                )
-            },
-            |ret| {
-                error!("Error getting test result: {:#010X}", ret);
-            },
-        )?;
-        Ok((
-            MaxBuffer::try_from(Context::ffi_data_to_owned(out_data_ptr))?,
-            ReturnCode::ensure_success(test_result, |_| {}),
-        ))
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_GetTestResult(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &mut out_data_ptr,
+                        &mut test_result,
+                    )
+                },
+                |ret| {
+                    error!("Error getting test result: {:#010X}", ret);
+                },
+            )
+        })?;
+        Ok(TestResult {
+            out_data: MaxBuffer::try_from(Context::ffi_data_to_owned(out_data_ptr))?,
+            test_result: ReturnCode::ensure_success(test_result, |_| {}),
+        })
     }
 }
@@ -1,7 +1,195 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::Context;
+use crate::{
+    constants::{
+        tss::{TPM2_ST_NO_SESSIONS, TPM2_ST_SESSIONS},
+        VendorCommandCode,
+    },
+    structures::Data,
+    tss2_esys::{
+        size_t, Esys_GetTcti, Esys_Vendor_TCG_Test, TPM2_CC, TPM2_MAX_COMMAND_SIZE,
+        TPM2_MAX_RESPONSE_SIZE, TSS2_TCTI_CONTEXT, TSS2_TCTI_CONTEXT_COMMON_V1,
+        TSS2_TCTI_TIMEOUT_BLOCK,
+    },
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::convert::{TryFrom, TryInto};
+use std::ptr::null_mut;
+
+/// The size of the tag, size and command code (or response code) fields.
+const HEADER_SIZE: usize = 10;
 
 impl Context {
-    // Missing function: Vendor_TCG_Test
+    /// Execute the TCG test command.
+    ///
+    /// # Arguments
+    /// * `input_data` - Dummy data sent to the TPM.
+    ///
+    /// # Returns
+    /// The dummy data returned by the TPM.
+    pub fn vendor_tcg_test(&mut self, input_data: Data) -> Result<Data> {
+        let mut output_data_ptr = null_mut();
+        self.execute_with_retry(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    Esys_Vendor_TCG_Test(
+                        ctx.mut_context(),
+                        ctx.optional_session_1(),
+                        ctx.optional_session_2(),
+                        ctx.optional_session_3(),
+                        &input_data.clone().into(),
+                        &mut output_data_ptr,
+                    )
+                },
+                |ret| {
+                    error!("Error when executing the TCG test command: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        Data::try_from(Context::ffi_data_to_owned(output_data_ptr))
+    }
+
+    /// Execute a vendor-specific command.
+    ///
+    /// # Arguments
+    /// * `command_code` - The vendor-specific command code.
+    /// * `marshalled_params` - The marshalled handle area followed by the
+    ///                         marshalled parameters of the command.
+    ///
+    /// # Details
+    /// The ESAPI can not marshal commands it does not know about, so the
+    /// command header is built by this method and the command is sent
+    /// through the TCTI used by the context. The ESYS state (e.g. the
+    /// sessions and the loaded objects) is not affected.
+    ///
+    /// For the same reason, the command is sent without an authorization
+    /// area, so only vendor commands that do not require authorization can
+    /// be issued with this method. As the sessions of the context can not
+    /// be used for the command, the method has to be called without any
+    /// session set, e.g. within [Context::execute_without_session].
+    ///
+    /// # Returns
+    /// The marshalled handle area and parameters of the response, i.e. the
+    /// response without the header.
+    ///
+    /// # Errors
+    /// * if sessions are set on the context, an `UnsupportedParam` wrapper
+    ///   error is returned
+    /// * if the command is too big, an `InvalidParam` wrapper error is returned
+    /// * if the TPM returns a malformed response, a `WrongValueFromTpm`
+    ///   wrapper error is returned
+    /// * if the TPM returns an error, the corresponding TSS error is returned
+    pub fn execute_vendor_command(
+        &mut self,
+        command_code: VendorCommandCode,
+        marshalled_params: &[u8],
+    ) -> Result<Vec<u8>> {
+        if self.sessions() != (None, None, None) {
+            error!("Vendor-specific commands can not be executed with sessions");
+            return Err(Error::local_error(WrapperErrorKind::UnsupportedParam));
+        }
+        let command_size = HEADER_SIZE + marshalled_params.len();
+        if command_size > TPM2_MAX_COMMAND_SIZE as usize {
+            error!("The command is too big (> {} bytes)", TPM2_MAX_COMMAND_SIZE);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+
+        let mut command = Vec::with_capacity(command_size);
+        command.extend_from_slice(&TPM2_ST_NO_SESSIONS.to_be_bytes());
+        command.extend_from_slice(&(command_size as u32).to_be_bytes());
+        command.extend_from_slice(&TPM2_CC::from(command_code).to_be_bytes());
+        command.extend_from_slice(marshalled_params);
+
+        let mut response = vec![0u8; TPM2_MAX_RESPONSE_SIZE as usize];
+        let mut response_size: size_t = response.len().try_into().map_err(|e| {
+            error!("Failed to convert size of buffer to TSS size_t type: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        let tcti = self.tcti()?;
+        // The TCTI context starts with the common header holding the
+        // transmit and receive functions.
+        let tcti_common = unsafe { &*(tcti as *const TSS2_TCTI_CONTEXT_COMMON_V1) };
+        let (transmit, receive) = match (tcti_common.transmit, tcti_common.receive) {
+            (Some(transmit), Some(receive)) => (transmit, receive),
+            _ => {
+                error!("The TCTI does not support sending commands");
+                return Err(Error::local_error(WrapperErrorKind::InternalError));
+            }
+        };
+        // The TCTI is shared with the guards of the transient objects, so
+        // the command and its response are exchanged with the ESYS context
+        // locked.
+        self.execute_locked(|_| {
+            ReturnCode::ensure_success(
+                unsafe {
+                    transmit(
+                        tcti,
+                        command.len().try_into().map_err(|e| {
+                            error!("Failed to convert size of buffer to TSS size_t type: {}", e);
+                            Error::local_error(WrapperErrorKind::InvalidParam)
+                        })?,
+                        command.as_ptr(),
+                    )
+                },
+                |ret| {
+                    error!("Error when transmitting the vendor command: {:#010X}", ret);
+                },
+            )?;
+            ReturnCode::ensure_success(
+                unsafe {
+                    receive(
+                        tcti,
+                        &mut response_size,
+                        response.as_mut_ptr(),
+                        TSS2_TCTI_TIMEOUT_BLOCK,
+                    )
+                },
+                |ret| {
+                    error!("Error when receiving the vendor response: {:#010X}", ret);
+                },
+            )
+        })?;
+
+        let response_size = usize::try_from(response_size).map_err(|e| {
+            error!("Failed to parse response size as usize: {}", e);
+            Error::local_error(WrapperErrorKind::WrongValueFromTpm)
+        })?;
+        if response_size < HEADER_SIZE || response_size > response.len() {
+            error!("Invalid vendor response size {}", response_size);
+            return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+        }
+        response.truncate(response_size);
+        let tag = u16::from_be_bytes(response[0..2].try_into().unwrap());
+        let size = u32::from_be_bytes(response[2..6].try_into().unwrap());
+        let response_code = u32::from_be_bytes(response[6..10].try_into().unwrap());
+        if (tag != TPM2_ST_NO_SESSIONS && tag != TPM2_ST_SESSIONS) || size as usize != response_size
+        {
+            error!(
+                "Invalid vendor response header (tag = {:#06X}, size = {})",
+                tag, size
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongValueFromTpm));
+        }
+        ReturnCode::ensure_success(response_code, |ret| {
+            error!("Error when executing the vendor command: {:#010X}", ret);
+        })?;
+
+        Ok(response.split_off(HEADER_SIZE))
+    }
+
+    /// Private function for retrieving the TCTI used by the ESYS context.
+    fn tcti(&mut self) -> Result<*mut TSS2_TCTI_CONTEXT> {
+        let mut tcti = null_mut();
+        self.execute_locked(|ctx| {
+            ReturnCode::ensure_success(
+                unsafe { Esys_GetTcti(ctx.mut_context(), &mut tcti) },
+                |ret| {
+                    error!("Error when retrieving the TCTI: {:#010X}", ret);
+                },
+            )
+        })?;
+        Ok(tcti)
+    }
 }
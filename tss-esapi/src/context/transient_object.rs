@@ -0,0 +1,111 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{
+    context::handle_manager::HandleDropAction,
+    handles::{
+        lock_transient_object_registry, Loaded, ObjectHandle, SharedTransientObjectRegistry,
+        TransientObject,
+    },
+    tss2_esys::ESYS_TR,
+    Context, Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::sync::Arc;
+
+impl Context {
+    /// Returns a guard that flushes the transient object when it is dropped.
+    ///
+    /// # Details
+    /// See [TransientObject]. The handle is typically returned by
+    /// [create_primary](Context::create_primary), [load](Context::load),
+    /// [load_external](Context::load_external) or [context_load](Context::context_load).
+    ///
+    /// # Errors
+    /// * if the handle is not a handle of the context that must be flushed,
+    ///   or if it is already guarded, an `InvalidHandleState` wrapper error
+    ///   is returned
+    pub fn transient_object<H: Into<ObjectHandle>>(
+        &mut self,
+        handle: H,
+    ) -> Result<TransientObject> {
+        let handle = handle.into();
+        let id = self.guard_transient_object(handle)?;
+        Ok(TransientObject::new(
+            handle,
+            id,
+            self.transient_objects.clone(),
+        ))
+    }
+
+    /// Takes the ownership of the handle of a transient object.
+    ///
+    /// # Details
+    /// See [Loaded]. The object can then only be flushed once through the
+    /// `Loaded` handle, with [flush_loaded](Context::flush_loaded), which
+    /// consumes it. The handle given to this method is not consumed, and
+    /// must not be used once the object has been flushed.
+    ///
+    /// # Errors
+    /// * if the handle is not a handle of the context that must be flushed,
+    ///   or if it is already owned or guarded, an `InvalidHandleState`
+    ///   wrapper error is returned
+    pub fn take_loaded<H>(&mut self, handle: H) -> Result<Loaded<H>>
+    where
+        H: Copy + Into<ObjectHandle>,
+    {
+        let _ = self.guard_transient_object(handle.into())?;
+        Ok(Loaded::new(handle))
+    }
+
+    /// Flushes the transient object owned by the `Loaded` handle.
+    ///
+    /// # Details
+    /// The handle is consumed, even if the object cannot be flushed. The
+    /// object is then flushed when the context is dropped.
+    ///
+    /// # Errors
+    /// * the errors returned by [flush_context](Context::flush_context)
+    pub fn flush_loaded<H>(&mut self, loaded: Loaded<H>) -> Result<()>
+    where
+        H: Copy + Into<ObjectHandle>,
+    {
+        self.flush_context(loaded.object_handle())
+    }
+
+    /// Removes the objects that have been flushed by their guard from the
+    /// handles of the context.
+    pub(crate) fn forget_flushed_objects(&mut self, flushed: Vec<ObjectHandle>) {
+        for handle in flushed {
+            if let Err(e) = self.handle_manager.set_as_flushed(handle) {
+                error!("Failed to forget the flushed transient object: {}", e);
+            }
+        }
+    }
+
+    /// Private method registering a guard for a transient object of the context.
+    fn guard_transient_object(&mut self, handle: ObjectHandle) -> Result<u64> {
+        let flushed = lock_transient_object_registry(&self.transient_objects).take_flushed();
+        self.forget_flushed_objects(flushed);
+        if self.handle_manager.drop_action(handle) != Some(HandleDropAction::Flush) {
+            error!(
+                "Handle({}) is not a transient object of the context",
+                ESYS_TR::from(handle)
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidHandleState));
+        }
+        lock_transient_object_registry(&self.transient_objects).guard(handle)
+    }
+
+    /// Removes the guard of an object that has been flushed, if any.
+    pub(crate) fn unguard_transient_object(&mut self, handle: ObjectHandle) {
+        lock_transient_object_registry(&self.transient_objects).unguard(handle);
+    }
+
+    /// Returns true if the registry is the registry of the guards of the context.
+    pub(crate) fn owns_transient_object_registry(
+        &self,
+        registry: &SharedTransientObjectRegistry,
+    ) -> bool {
+        Arc::ptr_eq(&self.transient_objects, registry)
+    }
+}
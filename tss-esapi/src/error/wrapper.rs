@@ -25,6 +25,9 @@ pub enum WrapperErrorKind {
     InvalidHandleState,
     /// An unexpected internal error occurred.
     InternalError,
+    /// Returned when the authorization of a command requires physical presence,
+    /// but it is not asserted or cannot be asserted on the TPM or locality.
+    PhysicalPresenceRequired,
 }
 
 impl std::fmt::Display for WrapperErrorKind {
@@ -53,6 +56,12 @@ impl std::fmt::Display for WrapperErrorKind {
             WrapperErrorKind::InternalError => {
                 write!(f, "an unexpected error occurred within the crate")
             }
+            WrapperErrorKind::PhysicalPresenceRequired => {
+                write!(
+                    f,
+                    "the authorization requires the assertion of physical presence"
+                )
+            }
         }
     }
 }
@@ -0,0 +1,120 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::handles::{KeyHandle, ObjectHandle};
+
+/// A handle of a transient object that is owned by the caller
+///
+/// # Details
+/// Contrary to [ObjectHandle] and [KeyHandle], a `Loaded` handle can
+/// neither be copied nor cloned, and it is consumed when the object is
+/// flushed with [Context::flush_loaded](crate::Context::flush_loaded).
+/// The `Loaded` handle itself can therefore not be flushed twice, nor used
+/// once the object has been flushed.
+///
+/// The methods of the [Context](crate::Context) still take copyable
+/// handles, which are obtained with `(&loaded).into()` or with
+/// [Loaded::handle]. These handles, like the handle given to
+/// [Context::take_loaded](crate::Context::take_loaded), are not tracked:
+/// using them once the object has been flushed fails at runtime with an
+/// ESYS bad reference error. They should only be used while the `Loaded`
+/// handle is alive.
+///
+/// Dropping a `Loaded` handle does not flush the object, which is then
+/// flushed when the context is dropped. See
+/// [TransientObject](crate::handles::TransientObject) for a handle that
+/// flushes the object when dropped.
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     interface_types::{
+/// #         algorithm::{HashingAlgorithm, RsaSchemeAlgorithm},
+/// #         key_bits::RsaKeyBits,
+/// #         resource_handles::Hierarchy,
+/// #     },
+/// #     structures::{RsaExponent, RsaScheme},
+/// #     utils::create_unrestricted_signing_rsa_public,
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// # let public = create_unrestricted_signing_rsa_public(
+/// #     RsaScheme::create(RsaSchemeAlgorithm::RsaSsa, Some(HashingAlgorithm::Sha256))
+/// #         .expect("Failed to create RSA scheme"),
+/// #     RsaKeyBits::Rsa2048,
+/// #     RsaExponent::default(),
+/// # )
+/// # .expect("Failed to create the public area");
+/// let key_handle = context
+///     .execute_with_nullauth_session(|ctx| {
+///         ctx.create_primary(Hierarchy::Owner, public, None, None, None, None)
+///     })
+///     .expect("Failed to create the primary key")
+///     .key_handle;
+/// let key = context.take_loaded(key_handle).expect("Failed to own the key");
+/// let _ = context
+///     .read_public((&key).into())
+///     .expect("Failed to read the public area");
+/// context.flush_loaded(key).expect("Failed to flush the key");
+/// // `key` has been moved, so it can no longer be used, but `key_handle`
+/// // can, and would fail at runtime.
+/// ```
+///
+/// ```compile_fail
+/// # use tss_esapi::{Context, handles::{KeyHandle, Loaded}};
+/// fn use_after_flush(context: &mut Context, key: Loaded<KeyHandle>) {
+///     context.flush_loaded(key).expect("Failed to flush the key");
+///     let _ = context.read_public((&key).into());
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+#[must_use = "the object is only flushed when the context is dropped if the handle is not used"]
+pub struct Loaded<H> {
+    handle: H,
+}
+
+impl<H> Loaded<H>
+where
+    H: Copy + Into<ObjectHandle>,
+{
+    /// Creates a handle owned by the caller.
+    pub(crate) fn new(handle: H) -> Self {
+        Loaded { handle }
+    }
+
+    /// Returns the handle of the object.
+    ///
+    /// # Details
+    /// The returned handle is not tracked, so it must not be used once
+    /// the object has been flushed.
+    pub fn handle(&self) -> H {
+        self.handle
+    }
+
+    /// Returns the handle of the object as an object handle.
+    ///
+    /// # Details
+    /// As with [Loaded::handle], the returned handle is not tracked.
+    pub fn object_handle(&self) -> ObjectHandle {
+        self.handle.into()
+    }
+}
+
+impl From<&Loaded<KeyHandle>> for KeyHandle {
+    fn from(loaded: &Loaded<KeyHandle>) -> Self {
+        loaded.handle
+    }
+}
+
+impl From<&Loaded<KeyHandle>> for ObjectHandle {
+    fn from(loaded: &Loaded<KeyHandle>) -> Self {
+        loaded.handle.into()
+    }
+}
+
+impl From<&Loaded<ObjectHandle>> for ObjectHandle {
+    fn from(loaded: &Loaded<ObjectHandle>) -> Self {
+        loaded.handle
+    }
+}
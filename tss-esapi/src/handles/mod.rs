@@ -33,3 +33,13 @@ pub use tpm::saved_session::SavedSessionTpmHandle;
 pub use tpm::transient::TransientTpmHandle;
 pub use tpm::TpmHandle;
 mod tpm;
+/////////////////////////////////////////////////////////
+/// Guards
+/////////////////////////////////////////////////////////
+pub use loaded::Loaded;
+pub use transient_object::TransientObject;
+pub(crate) use transient_object::{
+    lock_registry as lock_transient_object_registry, EsysContextPtr, SharedTransientObjectRegistry,
+};
+mod loaded;
+mod transient_object;
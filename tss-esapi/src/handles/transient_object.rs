@@ -0,0 +1,228 @@
+use crate::{
+    handles::{KeyHandle, ObjectHandle},
+    tss2_esys::{Esys_FlushContext, ESYS_CONTEXT},
+    Context, Error, Result, ReturnCode, WrapperErrorKind,
+};
+use log::error;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// The ESYS context of a [Context], through which the guards flush their
+/// object when they are dropped.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct EsysContextPtr(*mut ESYS_CONTEXT);
+
+// The ESYS context is only used with the registry locked, whichever thread
+// the context or the guards are used from.
+unsafe impl Send for EsysContextPtr {}
+
+impl EsysContextPtr {
+    /// Creates the pointer to the ESYS context of a context.
+    pub(crate) fn new(esys_context: *mut ESYS_CONTEXT) -> Self {
+        EsysContextPtr(esys_context)
+    }
+}
+
+/// The transient objects of a context that are guarded by a [TransientObject],
+/// or owned by a [Loaded](crate::handles::Loaded) handle.
+///
+/// # Details
+/// The registry also holds the ESYS context while the context is alive. The
+/// context locks the registry while it executes an ESYS function, so that the
+/// guards can flush their object from any thread.
+#[derive(Debug, Default)]
+pub(crate) struct TransientObjectRegistry {
+    next_id: u64,
+    guarded: HashMap<ObjectHandle, u64>,
+    esys_context: Option<EsysContextPtr>,
+    flushed: Vec<ObjectHandle>,
+}
+
+impl TransientObjectRegistry {
+    /// Registers a guard for the handle, and returns its identifier.
+    pub(crate) fn guard(&mut self, handle: ObjectHandle) -> Result<u64> {
+        if self.guarded.contains_key(&handle) {
+            error!("The object is already guarded");
+            return Err(Error::local_error(WrapperErrorKind::InvalidHandleState));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let _ = self.guarded.insert(handle, id);
+        Ok(id)
+    }
+
+    /// Removes the guard of the handle, if any.
+    pub(crate) fn unguard(&mut self, handle: ObjectHandle) {
+        let _ = self.guarded.remove(&handle);
+    }
+
+    /// Sets the ESYS context through which the guards flush their object,
+    /// or unsets it when the context is dropped.
+    pub(crate) fn set_esys_context(&mut self, esys_context: Option<EsysContextPtr>) {
+        self.esys_context = esys_context;
+    }
+
+    /// Returns the handles of the objects that have been flushed by their guard.
+    pub(crate) fn take_flushed(&mut self) -> Vec<ObjectHandle> {
+        std::mem::take(&mut self.flushed)
+    }
+
+    /// Private method removing the guard with the identifier `id`, and
+    /// returning true if it was still guarding the handle.
+    fn remove(&mut self, handle: ObjectHandle, id: u64) -> bool {
+        if self.guarded.get(&handle) == Some(&id) {
+            let _ = self.guarded.remove(&handle);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Private method flushing the object of a dropped guard.
+    ///
+    /// # Details
+    /// Once the context has been dropped, the object has already been
+    /// flushed by the context.
+    fn flush(&mut self, handle: ObjectHandle) {
+        if let Some(esys_context) = self.esys_context {
+            match ReturnCode::ensure_success(
+                unsafe { Esys_FlushContext(esys_context.0, handle.into()) },
+                |ret| {
+                    error!("Error in flushing context: {:#010X}", ret);
+                },
+            ) {
+                Ok(()) => self.flushed.push(handle),
+                Err(e) => error!("Failed to flush the dropped transient object: {}", e),
+            }
+        }
+    }
+}
+
+/// The registry shared between a context and the guards of its objects.
+pub(crate) type SharedTransientObjectRegistry = Arc<Mutex<TransientObjectRegistry>>;
+
+/// Locks the registry.
+///
+/// # Details
+/// The registry is never left in an inconsistent state, so the lock is
+/// recovered if another thread panicked while holding it.
+pub(crate) fn lock_registry(
+    registry: &SharedTransientObjectRegistry,
+) -> MutexGuard<'_, TransientObjectRegistry> {
+    registry.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A transient object that is flushed when the guard is dropped
+///
+/// # Details
+/// The guard does not borrow the [Context], so it can be returned from
+/// functions, stored alongside other guards, or sent to another thread.
+/// It shares the ESYS context with the context instead, and flushes the
+/// object when it is dropped, waiting for the command being executed by the
+/// context, if any, to complete. If the context has already been dropped,
+/// the object has been flushed by the context.
+///
+/// The object is flushed at most once: it is no longer guarded once it has
+/// been flushed with [TransientObject::flush] or [Context::flush_context],
+/// or released with [TransientObject::release].
+///
+/// # Example
+/// ```rust
+/// # use tss_esapi::{Context, Tcti,
+/// #     interface_types::{
+/// #         algorithm::{HashingAlgorithm, RsaSchemeAlgorithm},
+/// #         key_bits::RsaKeyBits,
+/// #         resource_handles::Hierarchy,
+/// #     },
+/// #     structures::{RsaExponent, RsaScheme},
+/// #     utils::create_unrestricted_signing_rsa_public,
+/// # };
+/// # let mut context =
+/// #     Context::new(
+/// #         Tcti::from_environment_variable().expect("Failed to get TCTI"),
+/// #     ).expect("Failed to create Context");
+/// # let public = create_unrestricted_signing_rsa_public(
+/// #     RsaScheme::create(RsaSchemeAlgorithm::RsaSsa, Some(HashingAlgorithm::Sha256))
+/// #         .expect("Failed to create RSA scheme"),
+/// #     RsaKeyBits::Rsa2048,
+/// #     RsaExponent::default(),
+/// # )
+/// # .expect("Failed to create the public area");
+/// let key_handle = context
+///     .execute_with_nullauth_session(|ctx| {
+///         ctx.create_primary(Hierarchy::Owner, public, None, None, None, None)
+///     })
+///     .expect("Failed to create the primary key")
+///     .key_handle;
+/// let key = context
+///     .transient_object(key_handle)
+///     .expect("Failed to guard the key");
+/// // The key is flushed here.
+/// key.flush(&mut context).expect("Failed to flush the key");
+/// ```
+#[derive(Debug)]
+pub struct TransientObject {
+    handle: ObjectHandle,
+    id: u64,
+    registry: SharedTransientObjectRegistry,
+}
+
+impl TransientObject {
+    /// Creates a guard registered in the registry of a context.
+    pub(crate) fn new(
+        handle: ObjectHandle,
+        id: u64,
+        registry: SharedTransientObjectRegistry,
+    ) -> Self {
+        TransientObject {
+            handle,
+            id,
+            registry,
+        }
+    }
+
+    /// Returns the handle of the object.
+    pub fn handle(&self) -> ObjectHandle {
+        self.handle
+    }
+
+    /// Returns the handle of the object as a key handle.
+    pub fn key_handle(&self) -> KeyHandle {
+        KeyHandle::from(self.handle)
+    }
+
+    /// Flushes the object.
+    ///
+    /// # Errors
+    /// * if `context` is not the context the object was loaded with, an
+    ///   `InvalidParam` wrapper error is returned
+    /// * the errors returned by [Context::flush_context]
+    pub fn flush(self, context: &mut Context) -> Result<()> {
+        if !context.owns_transient_object_registry(&self.registry) {
+            error!("The object was not loaded with this context");
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        context.flush_context(self.handle)
+    }
+
+    /// Releases the object from the guard, without flushing it.
+    ///
+    /// # Details
+    /// The object must then be flushed by the caller when it is no
+    /// longer needed.
+    pub fn release(self) -> ObjectHandle {
+        let _ = lock_registry(&self.registry).remove(self.handle, self.id);
+        self.handle
+    }
+}
+
+impl Drop for TransientObject {
+    fn drop(&mut self) {
+        let mut registry = lock_registry(&self.registry);
+        if registry.remove(self.handle, self.id) {
+            registry.flush(self.handle);
+        }
+    }
+}
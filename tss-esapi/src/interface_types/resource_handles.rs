@@ -1,12 +1,15 @@
 // Copyright 2020 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    constants::tss::TPM2_AC_FIRST,
     handles::{
         AttachedComponentTpmHandle, AuthHandle, NvIndexHandle, NvIndexTpmHandle, ObjectHandle,
         PermanentTpmHandle, TpmHandle,
     },
+    tss2_esys::{ESYS_TR, TPM2_HANDLE},
     Error, Result, WrapperErrorKind,
 };
+use log::error;
 use std::convert::TryFrom;
 //////////////////////////////////////////////////////////////////////////////////
 /// Hierarchy
@@ -450,8 +453,87 @@ impl From<AttachedComponent> for AttachedComponentTpmHandle {
     }
 }
 
+impl TryFrom<AttachedComponent> for ObjectHandle {
+    type Error = Error;
+
+    fn try_from(attached_component: AttachedComponent) -> Result<ObjectHandle> {
+        // ESYS_TR_RH_AC_FIRST and ESYS_TR_RH_AC_LAST are not available
+        // in all the versions of the TSS.
+        const ESYS_TR_RH_AC_FIRST: ESYS_TR = 0x1000;
+        const ESYS_TR_RH_AC_LAST: ESYS_TR = 0x10000;
+        let offset =
+            TPM2_HANDLE::from(AttachedComponentTpmHandle::from(attached_component)) - TPM2_AC_FIRST;
+        if offset > ESYS_TR_RH_AC_LAST - ESYS_TR_RH_AC_FIRST {
+            error!(
+                "The attached component handle offset {:#010X} can not be represented as an ESYS_TR",
+                offset
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(ObjectHandle::from(ESYS_TR_RH_AC_FIRST + offset))
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////
-// Act (authenticated timers)
-//
-// TODO: Figure out how to implement this. This is some kind of counter.
+/// Act (authenticated timers)
+///
+/// Enum representing an authenticated countdown timer (ACT).
+///
+/// # Details
+/// This corresponds to the TPMI_RH_ACT interface type.
 //////////////////////////////////////////////////////////////////////////////////
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Act {
+    Act(PermanentTpmHandle),
+}
+
+impl Act {
+    /// Returns the number of the ACT, between 0 and 15.
+    pub fn number(&self) -> u32 {
+        match self {
+            Act::Act(handle) => {
+                TPM2_HANDLE::from(*handle)
+                    - TPM2_HANDLE::from(PermanentTpmHandle::AuthenticatedTimersFirst)
+            }
+        }
+    }
+}
+
+impl TryFrom<PermanentTpmHandle> for Act {
+    type Error = Error;
+
+    fn try_from(permanent_tpm_handle: PermanentTpmHandle) -> Result<Act> {
+        let value = TPM2_HANDLE::from(permanent_tpm_handle);
+        if (TPM2_HANDLE::from(PermanentTpmHandle::AuthenticatedTimersFirst)
+            ..=TPM2_HANDLE::from(PermanentTpmHandle::AuthenticatedTimersLast))
+            .contains(&value)
+        {
+            Ok(Act::Act(permanent_tpm_handle))
+        } else {
+            error!("The handle {:#010X} is not an ACT handle", value);
+            Err(Error::local_error(WrapperErrorKind::InvalidParam))
+        }
+    }
+}
+
+impl From<Act> for PermanentTpmHandle {
+    fn from(act: Act) -> PermanentTpmHandle {
+        match act {
+            Act::Act(handle) => handle,
+        }
+    }
+}
+
+impl From<Act> for TpmHandle {
+    fn from(act: Act) -> TpmHandle {
+        TpmHandle::Permanent(act.into())
+    }
+}
+
+impl From<Act> for ObjectHandle {
+    fn from(act: Act) -> ObjectHandle {
+        // ESYS_TR_RH_ACT_FIRST is not available in all the versions of the TSS.
+        const ESYS_TR_RH_ACT_FIRST: ESYS_TR = 0x120;
+        ObjectHandle::from(ESYS_TR_RH_ACT_FIRST + act.number())
+    }
+}
@@ -105,7 +105,7 @@ pub mod traits;
 pub mod utils;
 
 pub use abstraction::transient::TransientKeyContext;
-pub use context::Context;
+pub use context::{Context, RetryPolicy};
 pub use error::{Error, Result, ReturnCode, WrapperErrorKind};
 pub use tcti_ldr::TctiNameConf;
 // To replace painlessly the old Tcti structure, should maybe be deprecated at some point.
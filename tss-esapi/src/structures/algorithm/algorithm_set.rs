@@ -0,0 +1,35 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+/// Identifier of a set of algorithms.
+///
+/// # Details
+/// This corresponds to the `algorithmSet` parameter of the
+/// TPM2_SetAlgorithmSet command. The meaning of the value is
+/// defined by the TPM vendor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AlgorithmSet(u32);
+
+impl AlgorithmSet {
+    /// Creates a new AlgorithmSet from a vendor defined value.
+    pub const fn new(value: u32) -> Self {
+        AlgorithmSet(value)
+    }
+
+    /// Returns the vendor defined value of the algorithm set.
+    pub const fn value(&self) -> u32 {
+        self.0
+    }
+}
+
+impl From<u32> for AlgorithmSet {
+    fn from(value: u32) -> Self {
+        AlgorithmSet(value)
+    }
+}
+
+impl From<AlgorithmSet> for u32 {
+    fn from(algorithm_set: AlgorithmSet) -> Self {
+        algorithm_set.0
+    }
+}
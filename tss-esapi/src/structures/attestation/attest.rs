@@ -4,7 +4,7 @@
 use crate::{
     constants::tss::TPM2_GENERATED_VALUE,
     interface_types::structure_tags::AttestationType,
-    structures::{AttestInfo, ClockInfo, Data, Name},
+    structures::{AttestInfo, ClockInfo, CommandAuditInfo, Data, Name},
     traits::{Marshall, UnMarshall},
     tss2_esys::TPMS_ATTEST,
     Error, Result, ReturnCode, WrapperErrorKind,
@@ -56,6 +56,15 @@ impl Attest {
     pub const fn attested(&self) -> &AttestInfo {
         &self.attested
     }
+
+    /// Returns the command audit information, if the attestation
+    /// data was produced by TPM2_GetCommandAuditDigest().
+    pub const fn command_audit_info(&self) -> Option<&CommandAuditInfo> {
+        match &self.attested {
+            AttestInfo::CommandAudit { info } => Some(info),
+            _ => None,
+        }
+    }
 }
 
 impl From<Attest> for TPMS_ATTEST {
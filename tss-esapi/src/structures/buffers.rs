@@ -212,6 +212,10 @@ pub mod encrypted_secret {
     named_field_buffer_type!(EncryptedSecret, 256, TPM2B_ENCRYPTED_SECRET, secret);
 }
 
+pub mod event {
+    buffer_type!(Event, 1024, TPM2B_EVENT);
+}
+
 pub mod id_object {
     named_field_buffer_type!(IdObject, 256, TPM2B_ID_OBJECT, credential);
 }
@@ -1,12 +1,20 @@
 // Copyright 2020 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+#[cfg(has_tpms_act_data)]
+use crate::structures::{ActData, ActDataList};
 use crate::{
-    constants::CapabilityType,
+    attributes::CommandCodeAttributes,
+    constants::{CapabilityType, CommandCode, EccCurveIdentifier},
+    handles::TpmHandle,
     structures::{
-        AlgorithmPropertyList, CommandCodeAttributesList, CommandCodeList, EccCurveList,
-        HandleList, PcrSelectionList, TaggedPcrPropertyList, TaggedTpmPropertyList,
+        AlgorithmProperty, AlgorithmPropertyList, CommandCodeAttributesList, CommandCodeList,
+        EccCurveList, HandleList, PcrSelection, PcrSelectionList, TaggedPcrPropertyList,
+        TaggedPcrSelect, TaggedProperty, TaggedTpmPropertyList,
+    },
+    tss2_esys::{
+        TPM2_ALG_ID, TPM2_CAP, TPM2_CC, TPM2_ECC_CURVE, TPM2_HANDLE, TPM2_MAX_CAP_BUFFER, TPM2_PT,
+        TPM2_PT_PCR, TPMS_CAPABILITY_DATA, TPMU_CAPABILITIES,
     },
-    tss2_esys::{TPM2_CAP, TPM2_MAX_CAP_BUFFER, TPMS_CAPABILITY_DATA, TPMU_CAPABILITIES},
     Error, Result, WrapperErrorKind,
 };
 use log::error;
@@ -30,9 +38,117 @@ pub enum CapabilityData {
     TpmProperties(TaggedTpmPropertyList),
     PcrProperties(TaggedPcrPropertyList),
     EccCurves(EccCurveList),
-    // These are in the TPM TPMU_CAPABILITIES, but are not defined by esapi-2.4.1
+    #[cfg(has_tpms_act_data)]
+    ActData(ActDataList),
+    // This is in the TPM TPMU_CAPABILITIES, but is not defined by esapi-2.4.1
     // AuthPolicies(),
-    // ActData(),
+}
+
+/// A single item of the capability data returned by the TPM.
+///
+/// # Details
+/// This corresponds to an element of the list held by
+/// a `TPMS_CAPABILITY_DATA`.
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum CapabilityItem {
+    Algorithm(AlgorithmProperty),
+    Handle(TpmHandle),
+    Command(CommandCodeAttributes),
+    PpCommand(CommandCode),
+    AuditCommand(CommandCode),
+    AssignedPcr(PcrSelection),
+    TpmProperty(TaggedProperty),
+    PcrProperty(TaggedPcrSelect),
+    EccCurve(EccCurveIdentifier),
+    #[cfg(has_tpms_act_data)]
+    Act(ActData),
+}
+
+impl CapabilityItem {
+    /// Returns the property of the item.
+    ///
+    /// # Details
+    /// The property is the value used to select the item in
+    /// [Context::get_capability](crate::Context::get_capability),
+    /// e.g. the algorithm identifier, the handle or the command code.
+    pub fn property(&self) -> u32 {
+        match self {
+            CapabilityItem::Algorithm(algorithm_property) => {
+                TPM2_ALG_ID::from(algorithm_property.algorithm_identifier()).into()
+            }
+            CapabilityItem::Handle(tpm_handle) => TPM2_HANDLE::from(*tpm_handle),
+            CapabilityItem::Command(command_code_attributes) => {
+                TPM2_CC::from(command_code_attributes.command_index())
+                    | (TPM2_CC::from(command_code_attributes.is_vendor_specific()) << 29)
+            }
+            CapabilityItem::PpCommand(command_code)
+            | CapabilityItem::AuditCommand(command_code) => TPM2_CC::from(*command_code),
+            CapabilityItem::AssignedPcr(pcr_selection) => {
+                TPM2_ALG_ID::from(pcr_selection.hashing_algorithm()).into()
+            }
+            CapabilityItem::TpmProperty(tagged_property) => {
+                TPM2_PT::from(tagged_property.property())
+            }
+            CapabilityItem::PcrProperty(tagged_pcr_select) => {
+                TPM2_PT_PCR::from(tagged_pcr_select.pcr_property_tag())
+            }
+            CapabilityItem::EccCurve(ecc_curve_identifier) => {
+                TPM2_ECC_CURVE::from(*ecc_curve_identifier).into()
+            }
+            #[cfg(has_tpms_act_data)]
+            CapabilityItem::Act(act_data) => TPM2_HANDLE::from(TpmHandle::from(act_data.act())),
+        }
+    }
+}
+
+impl From<CapabilityData> for Vec<CapabilityItem> {
+    fn from(capability_data: CapabilityData) -> Self {
+        match capability_data {
+            CapabilityData::Algorithms(data) => data
+                .iter()
+                .copied()
+                .map(CapabilityItem::Algorithm)
+                .collect(),
+            CapabilityData::Handles(data) => {
+                data.iter().copied().map(CapabilityItem::Handle).collect()
+            }
+            CapabilityData::Commands(data) => {
+                data.iter().copied().map(CapabilityItem::Command).collect()
+            }
+            CapabilityData::PpCommands(data) => data
+                .iter()
+                .copied()
+                .map(CapabilityItem::PpCommand)
+                .collect(),
+            CapabilityData::AuditCommands(data) => data
+                .iter()
+                .copied()
+                .map(CapabilityItem::AuditCommand)
+                .collect(),
+            CapabilityData::AssignedPcr(data) => data
+                .get_selections()
+                .iter()
+                .copied()
+                .map(CapabilityItem::AssignedPcr)
+                .collect(),
+            CapabilityData::TpmProperties(data) => data
+                .iter()
+                .copied()
+                .map(CapabilityItem::TpmProperty)
+                .collect(),
+            CapabilityData::PcrProperties(data) => data
+                .iter()
+                .copied()
+                .map(CapabilityItem::PcrProperty)
+                .collect(),
+            CapabilityData::EccCurves(data) => {
+                data.iter().copied().map(CapabilityItem::EccCurve).collect()
+            }
+            #[cfg(has_tpms_act_data)]
+            CapabilityData::ActData(data) => data.into_iter().map(CapabilityItem::Act).collect(),
+        }
+    }
 }
 
 pub const fn max_cap_size<T>() -> usize {
@@ -96,6 +212,13 @@ impl From<CapabilityData> for TPMS_CAPABILITY_DATA {
                     eccCurves: data.into(),
                 },
             },
+            #[cfg(has_tpms_act_data)]
+            CapabilityData::ActData(data) => TPMS_CAPABILITY_DATA {
+                capability: CapabilityType::Act.into(),
+                data: TPMU_CAPABILITIES {
+                    actData: data.into(),
+                },
+            },
         }
     }
 }
@@ -138,6 +261,11 @@ impl TryFrom<TPMS_CAPABILITY_DATA> for CapabilityData {
                 error!("AuthPolicies capability type is currently not supported");
                 Err(Error::WrapperError(WrapperErrorKind::UnsupportedParam))
             }
+            #[cfg(has_tpms_act_data)]
+            CapabilityType::Act => Ok(CapabilityData::ActData(
+                unsafe { tpms_capability_data.data.actData }.try_into()?,
+            )),
+            #[cfg(not(has_tpms_act_data))]
             CapabilityType::Act => {
                 error!("Act capability type is currently not supported");
                 Err(Error::WrapperError(WrapperErrorKind::UnsupportedParam))
@@ -0,0 +1,106 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    constants::AcAttributeTag,
+    structures::AcOutput,
+    tss2_esys::{TPML_AC_CAPABILITIES, TPMS_AC_OUTPUT},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::{convert::TryFrom, iter::IntoIterator, mem::size_of, ops::Deref};
+
+/// A structure holding a list of attributes of an attached component.
+///
+/// # Details
+/// This corresponds to the TPML_AC_CAPABILITIES structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcCapabilityList {
+    ac_outputs: Vec<AcOutput>,
+}
+
+impl AcCapabilityList {
+    pub const MAX_SIZE: usize = Self::calculate_max_size();
+
+    /// Finds the first [AcOutput] in the list matching the provided `tag`.
+    pub fn find(&self, tag: AcAttributeTag) -> Option<&AcOutput> {
+        self.ac_outputs.iter().find(|ao| ao.tag() == tag)
+    }
+
+    /// Private function that calculates the maximum number
+    /// elements allowed in internal storage.
+    const fn calculate_max_size() -> usize {
+        (size_of::<TPML_AC_CAPABILITIES>() - size_of::<u32>()) / size_of::<TPMS_AC_OUTPUT>()
+    }
+}
+
+impl Deref for AcCapabilityList {
+    type Target = Vec<AcOutput>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.ac_outputs
+    }
+}
+
+impl AsRef<[AcOutput]> for AcCapabilityList {
+    fn as_ref(&self) -> &[AcOutput] {
+        self.ac_outputs.as_slice()
+    }
+}
+
+impl TryFrom<Vec<AcOutput>> for AcCapabilityList {
+    type Error = Error;
+
+    fn try_from(ac_outputs: Vec<AcOutput>) -> Result<Self> {
+        if ac_outputs.len() > Self::MAX_SIZE {
+            error!(
+                "Failed to convert Vec<AcOutput> into AcCapabilityList, to many items (> {})",
+                Self::MAX_SIZE
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(AcCapabilityList { ac_outputs })
+    }
+}
+
+impl IntoIterator for AcCapabilityList {
+    type Item = AcOutput;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ac_outputs.into_iter()
+    }
+}
+
+impl TryFrom<TPML_AC_CAPABILITIES> for AcCapabilityList {
+    type Error = Error;
+
+    fn try_from(tpml_ac_capabilities: TPML_AC_CAPABILITIES) -> Result<Self> {
+        let count = tpml_ac_capabilities.count as usize;
+        if count > Self::MAX_SIZE {
+            error!(
+                "Invalid size value in TPML_AC_CAPABILITIES (> {})",
+                Self::MAX_SIZE
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+
+        tpml_ac_capabilities.acCapabilities[..count]
+            .iter()
+            .map(|&ao| AcOutput::try_from(ao))
+            .collect::<Result<Vec<AcOutput>>>()
+            .map(|ac_outputs| AcCapabilityList { ac_outputs })
+    }
+}
+
+impl From<AcCapabilityList> for TPML_AC_CAPABILITIES {
+    fn from(ac_capability_list: AcCapabilityList) -> Self {
+        let mut tpml_ac_capabilities: TPML_AC_CAPABILITIES = Default::default();
+        for ac_output in ac_capability_list {
+            tpml_ac_capabilities.acCapabilities[tpml_ac_capabilities.count as usize] =
+                ac_output.into();
+            tpml_ac_capabilities.count += 1;
+        }
+        tpml_ac_capabilities
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    interface_types::resource_handles::Act,
+    structures::ActData,
+    tss2_esys::{TPML_ACT_DATA, TPMS_ACT_DATA},
+    Error, Result, WrapperErrorKind,
+};
+use log::error;
+use std::{convert::TryFrom, iter::IntoIterator, ops::Deref};
+
+/// A structure holding a list of authenticated countdown timer data.
+///
+/// # Details
+/// This corresponds to the TPML_ACT_DATA structure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActDataList {
+    act_data: Vec<ActData>,
+}
+
+impl ActDataList {
+    pub const MAX_SIZE: usize = Self::calculate_max_size();
+
+    /// Finds the [ActData] in the list matching the provided `act`.
+    pub fn find(&self, act: Act) -> Option<&ActData> {
+        self.act_data.iter().find(|ad| ad.act() == act)
+    }
+
+    /// Private function that calculates the maximum number
+    /// elements allowed in internal storage.
+    const fn calculate_max_size() -> usize {
+        crate::structures::capability_data::max_cap_size::<TPMS_ACT_DATA>()
+    }
+}
+
+impl Deref for ActDataList {
+    type Target = Vec<ActData>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.act_data
+    }
+}
+
+impl AsRef<[ActData]> for ActDataList {
+    fn as_ref(&self) -> &[ActData] {
+        self.act_data.as_slice()
+    }
+}
+
+impl TryFrom<Vec<ActData>> for ActDataList {
+    type Error = Error;
+
+    fn try_from(act_data: Vec<ActData>) -> Result<Self> {
+        if act_data.len() > Self::MAX_SIZE {
+            error!(
+                "Failed to convert Vec<ActData> into ActDataList, to many items (> {})",
+                Self::MAX_SIZE
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(ActDataList { act_data })
+    }
+}
+
+impl IntoIterator for ActDataList {
+    type Item = ActData;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.act_data.into_iter()
+    }
+}
+
+impl TryFrom<TPML_ACT_DATA> for ActDataList {
+    type Error = Error;
+
+    fn try_from(tpml_act_data: TPML_ACT_DATA) -> Result<Self> {
+        let count = tpml_act_data.count as usize;
+        if count > Self::MAX_SIZE {
+            error!("Invalid size value in TPML_ACT_DATA (> {})", Self::MAX_SIZE);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+
+        tpml_act_data.actData[..count]
+            .iter()
+            .map(|&ad| ActData::try_from(ad))
+            .collect::<Result<Vec<ActData>>>()
+            .map(|act_data| ActDataList { act_data })
+    }
+}
+
+impl From<ActDataList> for TPML_ACT_DATA {
+    fn from(act_data_list: ActDataList) -> Self {
+        let mut tpml_act_data: TPML_ACT_DATA = Default::default();
+        for act_data in act_data_list {
+            tpml_act_data.actData[tpml_act_data.count as usize] = act_data.into();
+            tpml_act_data.count += 1;
+        }
+        tpml_act_data
+    }
+}
@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::interface_types::algorithm::HashingAlgorithm;
 use crate::structures::{PcrSelectSize, PcrSelection, PcrSlot};
-use crate::tss2_esys::TPML_PCR_SELECTION;
-use crate::{Error, Result, WrapperErrorKind};
+use crate::traits::Marshall;
+use crate::tss2_esys::{Tss2_MU_TPML_PCR_SELECTION_Marshal, TPML_PCR_SELECTION};
+use crate::{Error, Result, ReturnCode, WrapperErrorKind};
 use log::error;
 use std::collections::HashMap;
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 
 /// A struct representing a pcr selection list. This
 /// corresponds to the TSS TPML_PCR_SELECTION.
@@ -52,6 +53,106 @@ impl PcrSelectionList {
         Ok(())
     }
 
+    /// Returns a [PcrSelectionList] with the pcr slots that are
+    /// selected in `self` or in `other`.
+    ///
+    /// # Details
+    /// The banks keep the order they have in `self`, followed by
+    /// the banks that are only present in `other`.
+    ///
+    /// # Errors
+    /// * if the union contains more than [PcrSelectionList::MAX_SIZE] banks,
+    ///   a `WrongParamSize` wrapper error is returned
+    pub fn union(&self, other: &Self) -> Result<Self> {
+        let mut items = self.items.clone();
+        for other_pcr_selection in &other.items {
+            match items.iter_mut().find(|pcr_selection| {
+                pcr_selection.hashing_algorithm() == other_pcr_selection.hashing_algorithm()
+            }) {
+                Some(pcr_selection) => {
+                    *pcr_selection = pcr_selection.union(other_pcr_selection)?;
+                }
+                None => items.push(*other_pcr_selection),
+            }
+        }
+        let mut pcr_selection_list = PcrSelectionList { items };
+        pcr_selection_list.remove_empty_selections();
+        if pcr_selection_list.len() > PcrSelectionList::MAX_SIZE {
+            error!(
+                "The union contains too many banks (> {})",
+                PcrSelectionList::MAX_SIZE
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+        Ok(pcr_selection_list)
+    }
+
+    /// Returns a [PcrSelectionList] with the pcr slots that are
+    /// selected both in `self` and in `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut pcr_selection_list = PcrSelectionList {
+            items: self
+                .items
+                .iter()
+                .filter_map(|pcr_selection| {
+                    other
+                        .find_selection(pcr_selection.hashing_algorithm())
+                        .and_then(|other_pcr_selection| {
+                            pcr_selection.intersection(other_pcr_selection).ok()
+                        })
+                })
+                .collect(),
+        };
+        pcr_selection_list.remove_empty_selections();
+        pcr_selection_list
+    }
+
+    /// Returns a [PcrSelectionList] with the pcr slots that are
+    /// selected in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut pcr_selection_list = PcrSelectionList {
+            items: self
+                .items
+                .iter()
+                .map(|pcr_selection| {
+                    other
+                        .find_selection(pcr_selection.hashing_algorithm())
+                        .and_then(|other_pcr_selection| {
+                            pcr_selection.difference(other_pcr_selection).ok()
+                        })
+                        .unwrap_or(*pcr_selection)
+                })
+                .collect(),
+        };
+        pcr_selection_list.remove_empty_selections();
+        pcr_selection_list
+    }
+
+    /// Returns true if all the pcr slots selected in `other`
+    /// are selected in `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        other.iter().all(|(hashing_algorithm, pcr_slot)| {
+            self.find_selection(hashing_algorithm)
+                .map(|pcr_selection| pcr_selection.is_selected(pcr_slot))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Returns an iterator over the hashing algorithms of the banks
+    /// and the pcr slots selected in them.
+    pub fn iter(&self) -> impl Iterator<Item = (HashingAlgorithm, PcrSlot)> + '_ {
+        self.items
+            .iter()
+            .flat_map(|pcr_selection| pcr_selection.iter())
+    }
+
+    /// Private method for finding the selection of a bank.
+    fn find_selection(&self, hashing_algorithm: HashingAlgorithm) -> Option<&PcrSelection> {
+        self.items
+            .iter()
+            .find(|pcr_selection| pcr_selection.hashing_algorithm() == hashing_algorithm)
+    }
+
     /// Function for retrieving the PcrSelectionList from Option<PcrSelectionList>
     ///
     /// This returns an empty list if None is passed
@@ -124,6 +225,39 @@ impl TryFrom<TPML_PCR_SELECTION> for PcrSelectionList {
     }
 }
 
+impl Marshall for PcrSelectionList {
+    const BUFFER_SIZE: usize = std::mem::size_of::<TPML_PCR_SELECTION>();
+
+    /// Produce a marshalled [`TPML_PCR_SELECTION`]
+    fn marshall(&self) -> Result<Vec<u8>> {
+        let tpml_pcr_selection = TPML_PCR_SELECTION::from(self.clone());
+        let mut offset = 0;
+        let mut buffer = vec![0; Self::BUFFER_SIZE];
+
+        ReturnCode::ensure_success(
+            unsafe {
+                Tss2_MU_TPML_PCR_SELECTION_Marshal(
+                    &tpml_pcr_selection,
+                    buffer.as_mut_ptr(),
+                    buffer.capacity().try_into().map_err(|e| {
+                        error!("Failed to convert size of buffer to TSS size_t type: {}", e);
+                        Error::local_error(WrapperErrorKind::InvalidParam)
+                    })?,
+                    &mut offset,
+                )
+            },
+            |ret| error!("Failed to marshal PcrSelectionList: {}", ret),
+        )?;
+
+        let checked_offset = usize::try_from(offset).map_err(|e| {
+            error!("Failed to parse offset as usize: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        buffer.truncate(checked_offset);
+        Ok(buffer)
+    }
+}
+
 /// A builder for the PcrSelectionList struct.
 #[derive(Debug, Default)]
 pub struct PcrSelectionListBuilder {
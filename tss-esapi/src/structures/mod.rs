@@ -18,7 +18,7 @@
 /// The capability data section
 /////////////////////////////////////////////////////////
 mod capability_data;
-pub use self::capability_data::CapabilityData;
+pub use self::capability_data::{CapabilityData, CapabilityItem};
 /////////////////////////////////////////////////////////
 /// The names section
 /////////////////////////////////////////////////////////
@@ -29,17 +29,21 @@ pub use names::name::Name;
 /////////////////////////////////////////////////////////
 mod result;
 pub use result::CreateKeyResult;
+pub use result::CreateLoadedKeyResult;
 pub use result::CreatePrimaryKeyResult;
+pub use result::PcrAllocateResult;
+pub use result::TestResult;
 /////////////////////////////////////////////////////////
 /// The sized buffers section
 /////////////////////////////////////////////////////////
 mod buffers;
 pub use self::buffers::{
     attest::AttestBuffer, auth::Auth, data::Data, digest::Digest, ecc_parameter::EccParameter,
-    encrypted_secret::EncryptedSecret, id_object::IdObject, initial_value::InitialValue,
-    max_buffer::MaxBuffer, max_nv_buffer::MaxNvBuffer, nonce::Nonce, private::Private,
-    private_key_rsa::PrivateKeyRsa, private_vendor_specific::PrivateVendorSpecific,
-    public::PublicBuffer, public_key_rsa::PublicKeyRsa, sensitive::SensitiveBuffer,
+    encrypted_secret::EncryptedSecret, event::Event, id_object::IdObject,
+    initial_value::InitialValue, max_buffer::MaxBuffer, max_nv_buffer::MaxNvBuffer, nonce::Nonce,
+    private::Private, private_key_rsa::PrivateKeyRsa,
+    private_vendor_specific::PrivateVendorSpecific, public::PublicBuffer,
+    public_key_rsa::PublicKeyRsa, sensitive::SensitiveBuffer,
     sensitive_create::SensitiveCreateBuffer, sensitive_data::SensitiveData,
     symmetric_key::SymmetricKey, timeout::Timeout,
 };
@@ -81,6 +85,23 @@ pub mod pcr_select_size {
 /// The lists section
 /////////////////////////////////////////////////////////
 mod lists;
+pub use self::ac_capability_list::AcCapabilityList;
+pub mod ac_capability_list {
+    pub use super::lists::ac_capabilities::*;
+}
+
+#[cfg(has_tpms_act_data)]
+pub use self::act_data_list::ActDataList;
+#[cfg(has_tpms_act_data)]
+pub mod act_data_list {
+    pub use super::lists::act_data::*;
+}
+
+pub use self::algorithm_list::AlgorithmList;
+pub mod algorithm_list {
+    pub use super::lists::algorithm::*;
+}
+
 pub use self::digest_list::DigestList;
 pub mod digest_list {
     pub use super::lists::digest::*;
@@ -164,7 +185,7 @@ pub use tagged::{
         EccScheme, KeyDerivationFunctionScheme, KeyedHashScheme, RsaDecryptionScheme, RsaScheme,
         SignatureScheme,
     },
-    sensitive::Sensitive,
+    sensitive::{Sensitive, SensitiveBuilder},
     signature::Signature,
     symmetric::{SymmetricDefinition, SymmetricDefinitionObject},
 };
@@ -172,7 +193,7 @@ pub use tagged::{
 /// ECC structures
 /////////////////////////////////////////////////////////
 mod ecc;
-pub use ecc::point::EccPoint;
+pub use ecc::{algorithm_detail::AlgorithmDetailEcc, point::EccPoint};
 /////////////////////////////////////////////////////////
 /// Signatures structures
 /////////////////////////////////////////////////////////
@@ -197,8 +218,10 @@ pub use clock::{clock_info::ClockInfo, time_info::TimeInfo};
 /// Property Structures
 /////////////////////////////////////////////////////////
 mod property;
+#[cfg(has_tpms_act_data)]
+pub use property::act_data::ActData;
 pub use property::{
-    algorithm_property::AlgorithmProperty, tagged_pcr_select::TaggedPcrSelect,
+    ac_output::AcOutput, algorithm_property::AlgorithmProperty, tagged_pcr_select::TaggedPcrSelect,
     tagged_property::TaggedProperty,
 };
 
@@ -208,7 +231,16 @@ pub use property::{
 mod nv;
 pub use nv::storage::{NvPublic, NvPublicBuilder};
 /////////////////////////////////////////////////////////
+/// Policy structures
+/////////////////////////////////////////////////////////
+mod policy;
+pub use policy::{
+    counter_timer::{CounterTimerCondition, CounterTimerConditionBuilder, CounterTimerField},
+    expiration::PolicyExpiration,
+};
+/////////////////////////////////////////////////////////
 /// Algorithm Structures
 /////////////////////////////////////////////////////////
 mod algorithm;
+pub use algorithm::algorithm_set::AlgorithmSet;
 pub use algorithm::symmetric::sensitive_create::SensitiveCreate;
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    attributes::NvIndexAttributes,
+    attributes::{NvIndexAttributes, NvIndexAttributesBuilder},
+    constants::NvIndexType,
     handles::NvIndexTpmHandle,
     interface_types::algorithm::HashingAlgorithm,
     structures::Digest,
-    tss2_esys::{TPM2B_NV_PUBLIC, TPMS_NV_PUBLIC},
+    tss2_esys::{
+        TPM2B_NV_PUBLIC, TPM2_SHA1_DIGEST_SIZE, TPM2_SHA256_DIGEST_SIZE, TPM2_SHA384_DIGEST_SIZE,
+        TPM2_SHA512_DIGEST_SIZE, TPM2_SM3_256_DIGEST_SIZE, TPMS_NV_PUBLIC,
+    },
     Error, Result, WrapperErrorKind,
 };
 use log::error;
@@ -94,7 +98,13 @@ impl TryFrom<NvPublic> for TPM2B_NV_PUBLIC {
 
 /// Builder for NvPublic.
 ///
-///
+/// # Details
+/// The builder can be created for a specific type of index with one of
+/// the `new_*_index` constructors. In that case, the index type is set in
+/// the attributes, together with the attributes that are mandatory for that
+/// type, and the data size is set to the size required by the type. The
+/// attributes and the data size that are provided are checked against the
+/// requirements of the type when the [NvPublic] is built.
 #[derive(Debug, Default)]
 pub struct NvPublicBuilder {
     nv_index: Option<NvIndexTpmHandle>,
@@ -102,6 +112,7 @@ pub struct NvPublicBuilder {
     attributes: Option<NvIndexAttributes>,
     authorization_policy: Option<Digest>,
     data_size: Option<usize>,
+    index_type: Option<NvIndexType>,
 }
 
 impl NvPublicBuilder {
@@ -112,6 +123,52 @@ impl NvPublicBuilder {
             attributes: None,
             authorization_policy: None,
             data_size: None,
+            index_type: None,
+        }
+    }
+
+    /// Creates a builder for an ordinary index, holding arbitrary data.
+    pub const fn new_ordinary_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::Ordinary)
+    }
+
+    /// Creates a builder for a counter index, holding a 64-bit counter.
+    pub const fn new_counter_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::Counter)
+    }
+
+    /// Creates a builder for a bits index, holding a 64-bit bit field.
+    pub const fn new_bits_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::Bits)
+    }
+
+    /// Creates a builder for an extend index, holding a digest
+    /// computed with the name algorithm of the index.
+    pub const fn new_extend_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::Extend)
+    }
+
+    /// Creates a builder for a PIN pass index.
+    pub const fn new_pin_pass_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::PinPass)
+    }
+
+    /// Creates a builder for a PIN fail index.
+    ///
+    /// The `no_da` attribute, which is mandatory for this type of index,
+    /// is set when the [NvPublic] is built.
+    pub const fn new_pin_fail_index() -> Self {
+        NvPublicBuilder::new_typed_index(NvIndexType::PinFail)
+    }
+
+    const fn new_typed_index(index_type: NvIndexType) -> Self {
+        NvPublicBuilder {
+            nv_index: None,
+            name_algorithm: None,
+            attributes: None,
+            authorization_policy: None,
+            data_size: None,
+            index_type: Some(index_type),
         }
     }
 
@@ -140,33 +197,55 @@ impl NvPublicBuilder {
         self
     }
 
+    /// Builds the [NvPublic]
+    ///
+    /// # Errors
+    /// * if a mandatory parameter is missing, a `ParamsMissing` wrapper error is returned
+    /// * if the builder was created for a specific type of index and the attributes
+    ///   or the data size do not match the requirements of that type, an
+    ///   `InconsistentParams` wrapper error is returned
     pub fn build(self) -> Result<NvPublic> {
-        // TODO: Do some clever checking of the values in
-        // order to determine some defaults values when
-        // some params have not been specified.
-        //
+        // Nv Index
+        let nv_index = self.nv_index.ok_or_else(|| {
+            error!("No NV index was specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+        // Hashing algorithm for the name of index
+        let name_algorithm = self.name_algorithm.ok_or_else(|| {
+            error!("No name algorithm was specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+        // Index attributes
+        let mut attributes = self.attributes.ok_or_else(|| {
+            error!("No attributes were specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+        // Size of the data area of the index
+        let mut data_size = self.data_size;
+
+        if let Some(index_type) = self.index_type {
+            attributes = typed_index_attributes(index_type, attributes)?;
+            let required_data_size = typed_index_data_size(index_type, name_algorithm)?;
+            match (required_data_size, data_size) {
+                (Some(required_size), None) => data_size = Some(required_size),
+                (Some(required_size), Some(size)) if size != required_size => {
+                    error!(
+                        "The data size of a {:?} index must be {} (!= {})",
+                        index_type, required_size, size
+                    );
+                    return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+                }
+                _ => {}
+            }
+        }
 
         Ok(NvPublic {
-            // Nv Index
-            nv_index: self.nv_index.ok_or_else(|| {
-                error!("No NV index was specified");
-                Error::local_error(WrapperErrorKind::ParamsMissing)
-            })?,
-            // Hashing algorithm for the name of index
-            name_algorithm: self.name_algorithm.ok_or_else(|| {
-                error!("No name algorithm was specified");
-                Error::local_error(WrapperErrorKind::ParamsMissing)
-            })?,
-            // Index attributes
-            attributes: self.attributes.ok_or_else(|| {
-                error!("No attributes were specified");
-                Error::local_error(WrapperErrorKind::ParamsMissing)
-            })?,
+            nv_index,
+            name_algorithm,
+            attributes,
             // Index Auth policy
             authorization_policy: self.authorization_policy.unwrap_or_default(),
-            // Size of the data area of the index
-            data_size: self
-                .data_size
+            data_size: data_size
                 .ok_or_else(|| {
                     error!("No data size specified");
                     Error::local_error(WrapperErrorKind::ParamsMissing)
@@ -181,3 +260,77 @@ impl NvPublicBuilder {
         })
     }
 }
+
+/// Sets the index type, and the attributes that are mandatory for it, in the
+/// attributes, checking that they do not conflict with the index type.
+fn typed_index_attributes(
+    index_type: NvIndexType,
+    attributes: NvIndexAttributes,
+) -> Result<NvIndexAttributes> {
+    // The index type of attributes that have been built without
+    // specifying one is the ordinary index type.
+    let current_index_type = attributes.index_type()?;
+    if current_index_type != NvIndexType::Ordinary && current_index_type != index_type {
+        error!(
+            "The attributes are for a {:?} index, not for a {:?} index",
+            current_index_type, index_type
+        );
+        return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+    }
+
+    match index_type {
+        // "If TPM_NT is TPM_NT_COUNTER, then TPMA_NV_CLEAR_STCLEAR
+        // shall be CLEAR."
+        NvIndexType::Counter if attributes.clear_stclear() => {
+            error!("The clear_stclear attribute cannot be set for a counter index");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        // "If TPM_NT is TPM_NT_PIN_FAIL or TPM_NT_PIN_PASS, then
+        // TPMA_NV_AUTHWRITE, TPMA_NV_GLOBALLOCK and TPMA_NV_WRITEDEFINE
+        // shall be CLEAR."
+        NvIndexType::PinPass | NvIndexType::PinFail
+            if attributes.auth_write() || attributes.global_lock() || attributes.write_define() =>
+        {
+            error!("The auth_write, global_lock and write_define attributes cannot be set for a PIN index");
+            return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+        }
+        _ => {}
+    }
+
+    NvIndexAttributesBuilder::with_attributes(attributes)
+        .with_nv_index_type(index_type)
+        .with_no_da(attributes.no_da() || index_type == NvIndexType::PinFail)
+        .build()
+}
+
+/// Returns the data size required by the index type, if any.
+fn typed_index_data_size(
+    index_type: NvIndexType,
+    name_algorithm: HashingAlgorithm,
+) -> Result<Option<usize>> {
+    match index_type {
+        NvIndexType::Ordinary => Ok(None),
+        // "If TPM_NT is TPM_NT_COUNTER, TPM_NT_BITS, TPM_NT_PIN_FAIL,
+        // or TPM_NT_PIN_PASS, then publicInfo→dataSize shall be set to
+        // eight (8)."
+        NvIndexType::Counter | NvIndexType::Bits | NvIndexType::PinFail | NvIndexType::PinPass => {
+            Ok(Some(8))
+        }
+        // "If TPM_NT is TPM_NT_EXTEND, then publicInfo→dataSize shall
+        // match the digest size of the publicInfo.nameAlg."
+        NvIndexType::Extend => {
+            let digest_size = match name_algorithm {
+                HashingAlgorithm::Sha1 => TPM2_SHA1_DIGEST_SIZE,
+                HashingAlgorithm::Sha256 | HashingAlgorithm::Sha3_256 => TPM2_SHA256_DIGEST_SIZE,
+                HashingAlgorithm::Sm3_256 => TPM2_SM3_256_DIGEST_SIZE,
+                HashingAlgorithm::Sha384 | HashingAlgorithm::Sha3_384 => TPM2_SHA384_DIGEST_SIZE,
+                HashingAlgorithm::Sha512 | HashingAlgorithm::Sha3_512 => TPM2_SHA512_DIGEST_SIZE,
+                HashingAlgorithm::Null => {
+                    error!("An extend index requires a name algorithm");
+                    return Err(Error::local_error(WrapperErrorKind::InconsistentParams));
+                }
+            };
+            Ok(Some(digest_size as usize))
+        }
+    }
+}
@@ -128,6 +128,83 @@ impl PcrSelection {
     pub fn is_empty(&self) -> bool {
         self.pcr_slot_collection.is_empty()
     }
+
+    /// Returns a [PcrSelection] with the pcr slots that are selected
+    /// in `self` or in `other`.
+    ///
+    /// # Details
+    /// The size of select of the result is the largest of the two sizes of select.
+    ///
+    /// # Errors
+    /// Returns InvalidParam if there is a hashing algorithm mismatch.
+    pub fn union(&self, other: &Self) -> Result<Self> {
+        self.ensure_hashing_algorithm_equality(other, "union")?;
+        Ok(PcrSelection {
+            hashing_algorithm: self.hashing_algorithm,
+            pcr_slot_collection: self.pcr_slot_collection.union(&other.pcr_slot_collection),
+        })
+    }
+
+    /// Returns a [PcrSelection] with the pcr slots that are selected
+    /// both in `self` and in `other`.
+    ///
+    /// # Errors
+    /// Returns InvalidParam if there is a hashing algorithm mismatch.
+    pub fn intersection(&self, other: &Self) -> Result<Self> {
+        self.ensure_hashing_algorithm_equality(other, "intersection")?;
+        Ok(PcrSelection {
+            hashing_algorithm: self.hashing_algorithm,
+            pcr_slot_collection: self
+                .pcr_slot_collection
+                .intersection(&other.pcr_slot_collection),
+        })
+    }
+
+    /// Returns a [PcrSelection] with the pcr slots that are selected
+    /// in `self` but not in `other`.
+    ///
+    /// # Errors
+    /// Returns InvalidParam if there is a hashing algorithm mismatch.
+    pub fn difference(&self, other: &Self) -> Result<Self> {
+        self.ensure_hashing_algorithm_equality(other, "difference")?;
+        Ok(PcrSelection {
+            hashing_algorithm: self.hashing_algorithm,
+            pcr_slot_collection: self
+                .pcr_slot_collection
+                .difference(&other.pcr_slot_collection),
+        })
+    }
+
+    /// Returns true if `other` has the same hashing algorithm as `self`
+    /// and all the pcr slots selected in `other` are selected in `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.hashing_algorithm == other.hashing_algorithm
+            && self
+                .pcr_slot_collection
+                .contains_all(&other.pcr_slot_collection)
+    }
+
+    /// Returns an iterator over the hashing algorithm and
+    /// the selected pcr slots, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (HashingAlgorithm, PcrSlot)> {
+        let hashing_algorithm = self.hashing_algorithm;
+        self.selected()
+            .into_iter()
+            .map(move |pcr_slot| (hashing_algorithm, pcr_slot))
+    }
+
+    /// Private method for ensuring that the hashing algorithm
+    /// of `other` is equal to the one in `self`.
+    fn ensure_hashing_algorithm_equality(&self, other: &Self, action: &str) -> Result<()> {
+        if self.hashing_algorithm != other.hashing_algorithm {
+            error!(
+                "Failed to perform '{}' due to hashing algorithm mismatch",
+                action
+            );
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<TPMS_PCR_SELECTION> for PcrSelection {
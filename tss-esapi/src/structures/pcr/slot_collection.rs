@@ -124,6 +124,46 @@ impl PcrSlotCollection {
         Ok(())
     }
 
+    /// Returns a collection with the pcr slots that are present
+    /// in `self` or in `other`.
+    ///
+    /// # Details
+    /// The size of select of the result is the largest of the
+    /// two sizes of select.
+    pub fn union(&self, other: &Self) -> Self {
+        PcrSlotCollection {
+            pcr_select_size: if other.pcr_select_size.as_u8() > self.pcr_select_size.as_u8() {
+                other.pcr_select_size
+            } else {
+                self.pcr_select_size
+            },
+            pcr_slots: self.pcr_slots | other.pcr_slots,
+        }
+    }
+
+    /// Returns a collection with the pcr slots that are present
+    /// both in `self` and in `other`.
+    pub fn intersection(&self, other: &Self) -> Self {
+        PcrSlotCollection {
+            pcr_select_size: self.pcr_select_size,
+            pcr_slots: self.pcr_slots & other.pcr_slots,
+        }
+    }
+
+    /// Returns a collection with the pcr slots that are present
+    /// in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        PcrSlotCollection {
+            pcr_select_size: self.pcr_select_size,
+            pcr_slots: self.pcr_slots & !other.pcr_slots,
+        }
+    }
+
+    /// Returns true if all the pcr slots in `other` are present in `self`.
+    pub fn contains_all(&self, other: &Self) -> bool {
+        self.pcr_slots.contains(other.pcr_slots)
+    }
+
     /// Private method for ensuring that a size of select
     /// is equal to the one present in `self`.
     fn ensure_pcr_select_size_equality(
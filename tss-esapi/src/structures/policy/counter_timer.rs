@@ -0,0 +1,164 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{constants::ArithmeticOperation, structures::Digest, Error, Result, WrapperErrorKind};
+use log::error;
+
+/// Enum representing the fields of the time information of the
+/// TPM (`TPMS_TIME_INFO`) that can be used in a
+/// [CounterTimerCondition].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CounterTimerField {
+    /// The time in milliseconds since the last TPM reset or restart.
+    Time,
+    /// The time in milliseconds during which the TPM has been powered.
+    Clock,
+    /// The number of TPM resets since the last clear.
+    ResetCount,
+    /// The number of TPM restarts or resumes since the last reset.
+    RestartCount,
+    /// Whether the value of clock is guaranteed to be greater than any
+    /// previously reported value (1) or not (0).
+    Safe,
+}
+
+impl CounterTimerField {
+    /// Returns the offset of the field in the marshalled time information.
+    pub const fn offset(&self) -> u16 {
+        match self {
+            CounterTimerField::Time => 0,
+            CounterTimerField::Clock => 8,
+            CounterTimerField::ResetCount => 16,
+            CounterTimerField::RestartCount => 20,
+            CounterTimerField::Safe => 24,
+        }
+    }
+
+    /// Returns the size in bytes of the field.
+    pub const fn size(&self) -> usize {
+        match self {
+            CounterTimerField::Time | CounterTimerField::Clock => 8,
+            CounterTimerField::ResetCount | CounterTimerField::RestartCount => 4,
+            CounterTimerField::Safe => 1,
+        }
+    }
+}
+
+/// Condition on the time information of the TPM asserted with
+/// [Context::policy_counter_timer](crate::Context::policy_counter_timer).
+///
+/// # Details
+/// The condition holds the `operandB`, `offset` and `operation`
+/// parameters of the command, where the field at `offset` in the
+/// marshalled time information is compared with `operandB` using
+/// `operation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CounterTimerCondition {
+    operand_b: Digest,
+    offset: u16,
+    operation: ArithmeticOperation,
+}
+
+impl CounterTimerCondition {
+    /// Returns the operand the field is compared with.
+    pub const fn operand_b(&self) -> &Digest {
+        &self.operand_b
+    }
+
+    /// Returns the offset of the field in the marshalled time information.
+    pub const fn offset(&self) -> u16 {
+        self.offset
+    }
+
+    /// Returns the operation used to compare the field with the operand.
+    pub const fn operation(&self) -> ArithmeticOperation {
+        self.operation
+    }
+}
+
+/// A builder for [CounterTimerCondition]
+///
+/// # Example
+/// ```
+/// # use tss_esapi::{
+/// #     constants::ArithmeticOperation,
+/// #     structures::{CounterTimerConditionBuilder, CounterTimerField},
+/// # };
+/// // The reset count of the TPM equals 3.
+/// let condition = CounterTimerConditionBuilder::new()
+///     .with_field(CounterTimerField::ResetCount)
+///     .with_operation(ArithmeticOperation::Eq)
+///     .with_value(3)
+///     .build()
+///     .expect("Failed to build counter timer condition");
+/// assert_eq!(condition.offset(), 16);
+/// assert_eq!(condition.operand_b().as_bytes(), &[0, 0, 0, 3]);
+/// ```
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CounterTimerConditionBuilder {
+    field: Option<CounterTimerField>,
+    operation: Option<ArithmeticOperation>,
+    value: Option<u64>,
+}
+
+impl CounterTimerConditionBuilder {
+    /// Creates a new builder.
+    pub const fn new() -> Self {
+        CounterTimerConditionBuilder {
+            field: None,
+            operation: None,
+            value: None,
+        }
+    }
+
+    /// Adds the field of the time information that is compared.
+    pub fn with_field(mut self, field: CounterTimerField) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// Adds the operation used for the comparison.
+    pub fn with_operation(mut self, operation: ArithmeticOperation) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Adds the value the field is compared with.
+    pub fn with_value(mut self, value: u64) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Builds the [CounterTimerCondition]
+    ///
+    /// # Errors
+    /// * if a parameter is missing, a `ParamsMissing` wrapper error is returned
+    /// * if the value does not fit in the field, an `InvalidParam` wrapper error
+    ///   is returned
+    pub fn build(self) -> Result<CounterTimerCondition> {
+        let field = self.field.ok_or_else(|| {
+            error!("No field was specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+        let operation = self.operation.ok_or_else(|| {
+            error!("No operation was specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+        let value = self.value.ok_or_else(|| {
+            error!("No value was specified");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+
+        // The fields are marshalled in big-endian order.
+        let bytes = value.to_be_bytes();
+        let (high_bytes, operand_b) = bytes.split_at(bytes.len() - field.size());
+        if high_bytes.iter().any(|&byte| byte != 0) {
+            error!("The value {} does not fit in the {:?} field", value, field);
+            return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+        }
+        Ok(CounterTimerCondition {
+            operand_b: Digest::from_bytes(operand_b)?,
+            offset: field.offset(),
+            operation,
+        })
+    }
+}
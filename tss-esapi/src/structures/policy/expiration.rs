@@ -0,0 +1,81 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::{Error, Result, WrapperErrorKind};
+use log::error;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Expiration of an authorization given with
+/// [Context::policy_signed](crate::Context::policy_signed).
+///
+/// # Details
+/// This corresponds to the `expiration` parameter of the
+/// policy commands, where the absolute value is the time in
+/// seconds, relative to the generation of the nonce of the
+/// policy session, after which the authorization expires and
+/// a negative value requests a ticket for the authorization.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PolicyExpiration {
+    /// The authorization does not expire.
+    Never,
+    /// The authorization expires after the duration.
+    After(Duration),
+    /// The authorization expires after the duration and a
+    /// ticket, that can be used with `PolicyTicket` until the
+    /// authorization expires, is requested.
+    AfterWithTicket(Duration),
+}
+
+impl PolicyExpiration {
+    /// Returns the duration after which the authorization
+    /// expires, if it expires.
+    pub const fn duration(&self) -> Option<Duration> {
+        match self {
+            PolicyExpiration::Never => None,
+            PolicyExpiration::After(duration) | PolicyExpiration::AfterWithTicket(duration) => {
+                Some(*duration)
+            }
+        }
+    }
+
+    /// Returns true if a ticket is requested.
+    pub const fn ticket_requested(&self) -> bool {
+        matches!(self, PolicyExpiration::AfterWithTicket(_))
+    }
+}
+
+impl TryFrom<PolicyExpiration> for i32 {
+    type Error = Error;
+
+    fn try_from(policy_expiration: PolicyExpiration) -> Result<Self> {
+        let seconds = |duration: Duration| {
+            i32::try_from(duration.as_secs()).map_err(|e| {
+                error!("Unable to convert duration to i32: {}", e);
+                Error::local_error(WrapperErrorKind::InvalidParam)
+            })
+        };
+        match policy_expiration {
+            PolicyExpiration::Never => Ok(0),
+            PolicyExpiration::After(duration) => seconds(duration),
+            PolicyExpiration::AfterWithTicket(duration) => {
+                let seconds = seconds(duration)?;
+                if seconds == 0 {
+                    error!("A ticket can only be requested for an authorization that expires");
+                    return Err(Error::local_error(WrapperErrorKind::InvalidParam));
+                }
+                Ok(-seconds)
+            }
+        }
+    }
+}
+
+impl From<i32> for PolicyExpiration {
+    fn from(expiration: i32) -> Self {
+        let duration = Duration::from_secs(u64::from(expiration.unsigned_abs()));
+        match expiration {
+            0 => PolicyExpiration::Never,
+            e if e < 0 => PolicyExpiration::AfterWithTicket(duration),
+            _ => PolicyExpiration::After(duration),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+pub mod counter_timer;
+pub mod expiration;
@@ -0,0 +1,78 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    attributes::ActAttributes, handles::PermanentTpmHandle, interface_types::resource_handles::Act,
+    tss2_esys::TPMS_ACT_DATA, Error, Result,
+};
+use std::convert::TryFrom;
+
+/// Struct representing the state of an authenticated countdown timer
+///
+/// # Details
+/// This corresponds to TPMS_ACT_DATA
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ActData {
+    act: Act,
+    timeout: u32,
+    attributes: ActAttributes,
+}
+
+impl ActData {
+    /// Creates a new ActData
+    pub const fn new(act: Act, timeout: u32, attributes: ActAttributes) -> Self {
+        ActData {
+            act,
+            timeout,
+            attributes,
+        }
+    }
+
+    /// Returns the ACT
+    pub const fn act(&self) -> Act {
+        self.act
+    }
+
+    /// Returns the number of seconds remaining before the ACT is signaled
+    pub const fn timeout(&self) -> u32 {
+        self.timeout
+    }
+
+    /// Returns the attributes of the ACT
+    pub const fn attributes(&self) -> ActAttributes {
+        self.attributes
+    }
+
+    /// Returns true if the ACT has been signaled
+    pub fn signaled(&self) -> bool {
+        self.attributes.signaled()
+    }
+
+    /// Returns true if the signaled state of the ACT is preserved
+    /// across a TPM restart
+    pub fn preserve_signaled(&self) -> bool {
+        self.attributes.preserve_signaled()
+    }
+}
+
+impl TryFrom<TPMS_ACT_DATA> for ActData {
+    type Error = Error;
+
+    fn try_from(tpms_act_data: TPMS_ACT_DATA) -> Result<Self> {
+        Ok(ActData {
+            act: Act::try_from(PermanentTpmHandle::new(tpms_act_data.handle)?)?,
+            timeout: tpms_act_data.timeout,
+            attributes: tpms_act_data.attributes.into(),
+        })
+    }
+}
+
+impl From<ActData> for TPMS_ACT_DATA {
+    fn from(act_data: ActData) -> Self {
+        TPMS_ACT_DATA {
+            handle: PermanentTpmHandle::from(act_data.act).into(),
+            timeout: act_data.timeout,
+            attributes: act_data.attributes.into(),
+        }
+    }
+}
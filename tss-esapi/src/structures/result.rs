@@ -3,7 +3,8 @@
 
 use crate::{
     handles::KeyHandle,
-    structures::{CreationData, CreationTicket, Digest, Private, Public},
+    structures::{CreationData, CreationTicket, Digest, MaxBuffer, Private, Public},
+    Result,
 };
 
 #[allow(missing_debug_implementations)]
@@ -23,3 +24,34 @@ pub struct CreatePrimaryKeyResult {
     pub creation_hash: Digest,
     pub creation_ticket: CreationTicket,
 }
+
+#[allow(missing_debug_implementations)]
+pub struct CreateLoadedKeyResult {
+    pub key_handle: KeyHandle,
+    pub out_private: Private,
+    pub out_public: Public,
+}
+
+/// The result of a [Context::pcr_allocate](crate::Context::pcr_allocate) call
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PcrAllocateResult {
+    /// Whether the allocation succeeded. The new allocation takes
+    /// effect after the next TPM reset.
+    pub allocation_success: bool,
+    /// The maximum number of PCR that may be in a bank.
+    pub max_pcr: u32,
+    /// The number of octets required to satisfy the request.
+    pub size_needed: u32,
+    /// The number of octets available for PCR banks.
+    pub size_available: u32,
+}
+
+/// The result of a [Context::get_test_result](crate::Context::get_test_result) call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    /// The manufacturer-specific information about the self tests.
+    pub out_data: MaxBuffer,
+    /// The outcome of the self tests, i.e. the error returned by the
+    /// TPM when the tests failed or have not been completed yet.
+    pub test_result: Result<()>,
+}
@@ -7,9 +7,9 @@ pub mod rsa;
 use crate::{
     attributes::ObjectAttributes,
     interface_types::algorithm::{HashingAlgorithm, PublicAlgorithm},
-    structures::{Digest, EccPoint, PublicKeyRsa, SymmetricCipherParameters},
+    structures::{Digest, EccPoint, PublicKeyRsa, PublicParameters, SymmetricCipherParameters},
     traits::{Marshall, UnMarshall},
-    tss2_esys::{TPM2B_PUBLIC, TPMT_PUBLIC},
+    tss2_esys::{TPM2B_PUBLIC, TPM2B_TEMPLATE, TPMT_PUBLIC},
     Error, Result, ReturnCode, WrapperErrorKind,
 };
 
@@ -375,6 +375,20 @@ impl Public {
         }
     }
 
+    /// Returns the public parameters.
+    ///
+    /// # Details
+    /// The parameters can be used with [Context::test_parms](crate::Context::test_parms)
+    /// to check if the TPM supports the template before creating the object.
+    pub fn parameters(&self) -> PublicParameters {
+        match self {
+            Public::Rsa { parameters, .. } => PublicParameters::Rsa(*parameters),
+            Public::KeyedHash { parameters, .. } => PublicParameters::KeyedHash(*parameters),
+            Public::Ecc { parameters, .. } => PublicParameters::Ecc(*parameters),
+            Public::SymCipher { parameters, .. } => PublicParameters::SymCipher(*parameters),
+        }
+    }
+
     /// Get a builder for this structure
     pub const fn builder() -> PublicBuilder {
         PublicBuilder::new()
@@ -595,3 +609,25 @@ impl TryFrom<Public> for TPM2B_PUBLIC {
         })
     }
 }
+
+impl TryFrom<Public> for TPM2B_TEMPLATE {
+    type Error = Error;
+
+    fn try_from(public: Public) -> Result<Self> {
+        let marshalled_public = public.marshall()?;
+        let mut template = TPM2B_TEMPLATE::default();
+        if marshalled_public.len() > template.buffer.len() {
+            error!(
+                "Invalid size of marshalled Public (> {})",
+                template.buffer.len()
+            );
+            return Err(Error::local_error(WrapperErrorKind::WrongParamSize));
+        }
+        template.buffer[..marshalled_public.len()].copy_from_slice(&marshalled_public);
+        template.size = marshalled_public.len().try_into().map_err(|e| {
+            error!("Failed to convert size of marshalled Public to u16: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        Ok(template)
+    }
+}
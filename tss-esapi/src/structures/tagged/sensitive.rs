@@ -10,6 +10,173 @@ use crate::{
 use log::error;
 use std::convert::{TryFrom, TryInto};
 
+/// A builder for the [Sensitive] type.
+#[derive(Debug, Clone)]
+pub struct SensitiveBuilder {
+    sensitive_type: Option<PublicAlgorithm>,
+    auth_value: Option<Auth>,
+    seed_value: Option<Digest>,
+    rsa_private_key: Option<PrivateKeyRsa>,
+    ecc_private_key: Option<EccParameter>,
+    sensitive_data: Option<SensitiveData>,
+    symmetric_key: Option<SymmetricKey>,
+}
+
+impl SensitiveBuilder {
+    /// Creates a new [SensitiveBuilder]
+    ///
+    /// # Details
+    /// Builds the [Sensitive] type using the provided parameters. Parameters
+    /// associated with other types then the provided sensitive type
+    /// will be ignored.
+    pub const fn new() -> Self {
+        SensitiveBuilder {
+            sensitive_type: None,
+            auth_value: None,
+            seed_value: None,
+            rsa_private_key: None,
+            ecc_private_key: None,
+            sensitive_data: None,
+            symmetric_key: None,
+        }
+    }
+
+    /// Adds the type of the [Sensitive] structure
+    /// to the builder.
+    pub const fn with_sensitive_type(mut self, sensitive_type: PublicAlgorithm) -> Self {
+        self.sensitive_type = Some(sensitive_type);
+        self
+    }
+
+    /// Adds the authorization value of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// If this is not set then an empty authorization value is used.
+    pub fn with_auth_value(mut self, auth_value: Auth) -> Self {
+        self.auth_value = Some(auth_value);
+        self
+    }
+
+    /// Adds the seed value of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// For a keyed hash object this is the value that is combined with the
+    /// sensitive data to compute the unique identifier of the public area.
+    /// If this is not set then an empty seed value is used.
+    pub fn with_seed_value(mut self, seed_value: Digest) -> Self {
+        self.seed_value = Some(seed_value);
+        self
+    }
+
+    /// Adds the RSA private key of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// This is required if the sensitive type is set to
+    /// [Rsa][`crate::interface_types::algorithm::PublicAlgorithm::Rsa].
+    ///
+    /// The private key is one of the prime factors of the modulus.
+    pub fn with_rsa_private_key(mut self, rsa_private_key: PrivateKeyRsa) -> Self {
+        self.rsa_private_key = Some(rsa_private_key);
+        self
+    }
+
+    /// Adds the ECC private key of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// This is required if the sensitive type is set to
+    /// [Ecc][`crate::interface_types::algorithm::PublicAlgorithm::Ecc].
+    ///
+    /// The private key is the scalar value.
+    pub fn with_ecc_private_key(mut self, ecc_private_key: EccParameter) -> Self {
+        self.ecc_private_key = Some(ecc_private_key);
+        self
+    }
+
+    /// Adds the sensitive data of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// This is required if the sensitive type is set to
+    /// [KeyedHash][`crate::interface_types::algorithm::PublicAlgorithm::KeyedHash].
+    ///
+    /// The sensitive data is either the HMAC key or the sealed data.
+    pub fn with_sensitive_data(mut self, sensitive_data: SensitiveData) -> Self {
+        self.sensitive_data = Some(sensitive_data);
+        self
+    }
+
+    /// Adds the symmetric key of the [Sensitive] structure
+    /// to the builder.
+    ///
+    /// # Details
+    /// This is required if the sensitive type is set to
+    /// [SymCipher][`crate::interface_types::algorithm::PublicAlgorithm::SymCipher].
+    pub fn with_symmetric_key(mut self, symmetric_key: SymmetricKey) -> Self {
+        self.symmetric_key = Some(symmetric_key);
+        self
+    }
+
+    /// Builds the [Sensitive] structure.
+    ///
+    /// # Errors
+    /// Will return an error if the sensitive type, or the sensitive value
+    /// required by that type, has not been set in the builder.
+    pub fn build(self) -> Result<Sensitive> {
+        let sensitive_type = self.sensitive_type.ok_or_else(|| {
+            error!("Sensitive type is required and has not been set in the SensitiveBuilder");
+            Error::local_error(WrapperErrorKind::ParamsMissing)
+        })?;
+
+        let auth_value = self.auth_value.unwrap_or_default();
+        let seed_value = self.seed_value.unwrap_or_default();
+
+        match sensitive_type {
+            PublicAlgorithm::Rsa => Ok(Sensitive::Rsa {
+                auth_value,
+                seed_value,
+                sensitive: self.rsa_private_key.ok_or_else(|| {
+                    error!("RSA private key has not been set in the SensitiveBuilder even though the RSA type had been selected");
+                    Error::local_error(WrapperErrorKind::ParamsMissing)
+                })?,
+            }),
+            PublicAlgorithm::Ecc => Ok(Sensitive::Ecc {
+                auth_value,
+                seed_value,
+                sensitive: self.ecc_private_key.ok_or_else(|| {
+                    error!("ECC private key has not been set in the SensitiveBuilder even though the ECC type had been selected");
+                    Error::local_error(WrapperErrorKind::ParamsMissing)
+                })?,
+            }),
+            PublicAlgorithm::KeyedHash => Ok(Sensitive::Bits {
+                auth_value,
+                seed_value,
+                sensitive: self.sensitive_data.ok_or_else(|| {
+                    error!("Sensitive data has not been set in the SensitiveBuilder even though the keyed hash type had been selected");
+                    Error::local_error(WrapperErrorKind::ParamsMissing)
+                })?,
+            }),
+            PublicAlgorithm::SymCipher => Ok(Sensitive::Symmetric {
+                auth_value,
+                seed_value,
+                sensitive: self.symmetric_key.ok_or_else(|| {
+                    error!("Symmetric key has not been set in the SensitiveBuilder even though the symmetric cipher type had been selected");
+                    Error::local_error(WrapperErrorKind::ParamsMissing)
+                })?,
+            }),
+        }
+    }
+}
+
+impl Default for SensitiveBuilder {
+    fn default() -> Self {
+        SensitiveBuilder::new()
+    }
+}
+
 /// Enum describing the Sensitive part of an object.
 ///
 /// # Details
@@ -74,6 +241,11 @@ impl Sensitive {
             Sensitive::Symmetric { .. } => PublicAlgorithm::SymCipher,
         }
     }
+
+    /// Get a builder for this structure
+    pub const fn builder() -> SensitiveBuilder {
+        SensitiveBuilder::new()
+    }
 }
 
 impl From<Sensitive> for TPMT_SENSITIVE {
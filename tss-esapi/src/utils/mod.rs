@@ -9,7 +9,7 @@
 //! type name. Unions are converted to Rust `enum`s by dropping the `TPMU` qualifier and appending
 //! `Union`.
 use crate::attributes::ObjectAttributesBuilder;
-use crate::constants::PropertyTag;
+use crate::constants::{PropertyTag, SessionType};
 use crate::interface_types::{
     algorithm::{HashingAlgorithm, PublicAlgorithm},
     ecc::EccCurve,
@@ -19,8 +19,10 @@ use crate::structures::{
     EccPoint, EccScheme, Public, PublicBuilder, PublicEccParametersBuilder, PublicKeyRsa,
     PublicRsaParametersBuilder, RsaExponent, RsaScheme, SymmetricDefinitionObject,
 };
+use crate::traits::{Marshall, UnMarshall};
 use crate::tss2_esys::*;
-use crate::{Context, Error, Result, WrapperErrorKind};
+use crate::{Context, Error, Result, ReturnCode, WrapperErrorKind};
+use log::error;
 use serde::{Deserialize, Serialize};
 use std::convert::{TryFrom, TryInto};
 use zeroize::{Zeroize, ZeroizeOnDrop};
@@ -30,7 +32,8 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 /// This structure is intended to help with persisting object contexts. As the main reason for
 /// saving the context of an object is to be able to re-use it later, on demand, a serializable
 /// structure is most commonly needed. `TpmsContext` implements the `Serialize` and `Deserialize`
-/// defined by `serde`.
+/// defined by `serde`, as well as [Marshall] and [UnMarshall], which use the stable format of
+/// `TPMS_CONTEXT` defined by the TPM specification.
 #[derive(Debug, Serialize, Deserialize, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct TpmsContext {
     sequence: u64,
@@ -44,6 +47,20 @@ impl TpmsContext {
     pub fn context_blob(&self) -> &Vec<u8> {
         &self.context_blob
     }
+
+    /// Get the `saved_handle` field
+    ///
+    /// # Details
+    /// The saved handle identifies the kind of the saved context, e.g. a
+    /// transient object or a session.
+    pub fn saved_handle(&self) -> TPMI_DH_CONTEXT {
+        self.saved_handle
+    }
+
+    /// Get the `hierarchy` field
+    pub fn hierarchy(&self) -> TPMI_RH_HIERARCHY {
+        self.hierarchy
+    }
 }
 
 // TODO: Replace with `From`
@@ -94,6 +111,142 @@ impl TryFrom<TpmsContext> for TPMS_CONTEXT {
     }
 }
 
+impl Marshall for TpmsContext {
+    const BUFFER_SIZE: usize = std::mem::size_of::<TPMS_CONTEXT>();
+
+    /// Produce a marshalled [`TPMS_CONTEXT`]
+    fn marshall(&self) -> Result<Vec<u8>> {
+        let tpms_context = TPMS_CONTEXT::try_from(self.clone())?;
+        let mut offset = 0;
+        let mut buffer = vec![0; Self::BUFFER_SIZE];
+
+        ReturnCode::ensure_success(
+            unsafe {
+                Tss2_MU_TPMS_CONTEXT_Marshal(
+                    &tpms_context,
+                    buffer.as_mut_ptr(),
+                    buffer.capacity().try_into().map_err(|e| {
+                        error!("Failed to convert size of buffer to TSS size_t type: {}", e);
+                        Error::local_error(WrapperErrorKind::InvalidParam)
+                    })?,
+                    &mut offset,
+                )
+            },
+            |ret| error!("Failed to marshal TpmsContext: {}", ret),
+        )?;
+
+        let checked_offset = usize::try_from(offset).map_err(|e| {
+            error!("Failed to parse offset as usize: {}", e);
+            Error::local_error(WrapperErrorKind::InvalidParam)
+        })?;
+        buffer.truncate(checked_offset);
+        Ok(buffer)
+    }
+}
+
+impl UnMarshall for TpmsContext {
+    /// Unmarshall the structure from [`TPMS_CONTEXT`]
+    fn unmarshall(marshalled_data: &[u8]) -> Result<Self> {
+        let mut tpms_context = TPMS_CONTEXT::default();
+        let mut offset = 0;
+
+        ReturnCode::ensure_success(
+            unsafe {
+                Tss2_MU_TPMS_CONTEXT_Unmarshal(
+                    marshalled_data.as_ptr(),
+                    marshalled_data.len().try_into().map_err(|e| {
+                        error!("Failed to convert length of marshalled data: {}", e);
+                        Error::local_error(WrapperErrorKind::InvalidParam)
+                    })?,
+                    &mut offset,
+                    &mut tpms_context,
+                )
+            },
+            |ret| error!("Failed to unmarshal TpmsContext: {}", ret),
+        )?;
+        TpmsContext::try_from(tpms_context)
+    }
+}
+
+/// The saved context of an authorization session.
+///
+/// # Details
+/// The context of a session does not record the type of the session nor
+/// its hashing algorithm, so they are kept alongside the context in order
+/// to recreate the [AuthSession](crate::interface_types::session_handles::AuthSession)
+/// when the session is loaded with
+/// [Context::session_context_load](crate::Context::session_context_load).
+#[derive(Debug, Clone)]
+pub struct SavedSession {
+    context: TpmsContext,
+    session_type: SessionType,
+    hashing_algorithm: HashingAlgorithm,
+}
+
+impl SavedSession {
+    /// Creates a saved session from the saved context of a session.
+    pub fn new(
+        context: TpmsContext,
+        session_type: SessionType,
+        hashing_algorithm: HashingAlgorithm,
+    ) -> Self {
+        SavedSession {
+            context,
+            session_type,
+            hashing_algorithm,
+        }
+    }
+
+    /// Returns the saved context of the session.
+    pub fn context(&self) -> &TpmsContext {
+        &self.context
+    }
+
+    /// Returns the type of the session.
+    pub fn session_type(&self) -> SessionType {
+        self.session_type
+    }
+
+    /// Returns the hashing algorithm of the session.
+    pub fn hashing_algorithm(&self) -> HashingAlgorithm {
+        self.hashing_algorithm
+    }
+}
+
+/// The metadata of an ESYS object, serialized with `Esys_TR_Serialize`.
+///
+/// # Details
+/// The metadata contains, among others, the TPM handle, the public area and
+/// the name of the object. It can be stored, and then restored with
+/// [Context::tr_deserialize](crate::Context::tr_deserialize) in another
+/// context or process, without reading the public area from the TPM with
+/// [Context::tr_from_tpm_public](crate::Context::tr_from_tpm_public).
+/// `SerializedHandle` implements the `Serialize` and `Deserialize` defined
+/// by `serde`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SerializedHandle {
+    buffer: Vec<u8>,
+}
+
+impl SerializedHandle {
+    /// Returns the serialized metadata.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+}
+
+impl From<Vec<u8>> for SerializedHandle {
+    fn from(buffer: Vec<u8>) -> Self {
+        SerializedHandle { buffer }
+    }
+}
+
+impl From<SerializedHandle> for Vec<u8> {
+    fn from(serialized_handle: SerializedHandle) -> Self {
+        serialized_handle.buffer
+    }
+}
+
 /// Create the [Public] structure for a restricted decryption key.
 ///
 /// * `symmetric` - Cipher to be used for decrypting children of the key
@@ -21,9 +21,9 @@ fi
 # Generate bindings for non-"standard" versions #
 #################################################
 if [[ "$TPM2_TSS_VERSION" != "2.3.3" ]]; then
-	FEATURES="--features=generate-bindings"
+	FEATURES="--features=generate-bindings,openssl"
 else
-	FEATURES=""
+	FEATURES="--features=openssl"
 fi
 
 #################################
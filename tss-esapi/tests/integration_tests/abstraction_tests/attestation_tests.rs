@@ -0,0 +1,221 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::attestation,
+    attributes::NvIndexAttributesBuilder,
+    handles::{KeyHandle, NvIndexTpmHandle},
+    interface_types::{
+        algorithm::HashingAlgorithm,
+        resource_handles::{Hierarchy, NvAuth, Provision},
+        session_handles::AuthSession,
+    },
+    structures::{Attest, Data, MaxNvBuffer, Name, NvPublicBuilder, Signature, SignatureScheme},
+    Context, Error, WrapperErrorKind,
+};
+
+use crate::common::{create_ctx_with_session, signing_key_pub};
+
+fn get_time(context: &mut Context, qualifying_data: &Data) -> (KeyHandle, Attest, Signature) {
+    let sign_key_handle = context
+        .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+        .expect("Failed to create signing key")
+        .key_handle;
+
+    let (attest, signature) = context
+        .execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                ctx.get_time(
+                    sign_key_handle,
+                    qualifying_data.clone(),
+                    SignatureScheme::Null,
+                )
+            },
+        )
+        .expect("Failed to get time");
+    (sign_key_handle, attest, signature)
+}
+
+#[test]
+fn test_verify_time_attestation() {
+    let mut context = create_ctx_with_session();
+    let qualifying_data = Data::try_from(vec![0xab; 16]).expect("Failed to create data");
+    let (sign_key_handle, attest, signature) = get_time(&mut context, &qualifying_data);
+
+    let time_attest_info = attestation::verify_time_attestation(
+        &mut context,
+        sign_key_handle,
+        &attest,
+        signature,
+        &qualifying_data,
+        Some(attest.clock_info().reset_count()),
+        Some(attest.clock_info().restart_count()),
+    )
+    .expect("Failed to verify time attestation");
+    assert_eq!(
+        time_attest_info.time_info().clock_info(),
+        attest.clock_info()
+    );
+}
+
+#[test]
+fn test_verify_time_attestation_with_wrong_counts() {
+    let mut context = create_ctx_with_session();
+    let qualifying_data = Data::try_from(vec![0xab; 16]).expect("Failed to create data");
+    let (sign_key_handle, attest, signature) = get_time(&mut context, &qualifying_data);
+
+    if let Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)) =
+        attestation::verify_time_attestation(
+            &mut context,
+            sign_key_handle,
+            &attest,
+            signature.clone(),
+            &qualifying_data,
+            Some(attest.clock_info().reset_count().wrapping_add(1)),
+            None,
+        )
+    {
+    } else {
+        panic!("Wrong reset count did not result in an InconsistentParams error");
+    }
+
+    if let Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)) =
+        attestation::verify_time_attestation(
+            &mut context,
+            sign_key_handle,
+            &attest,
+            signature,
+            &Data::default(),
+            None,
+            None,
+        )
+    {
+    } else {
+        panic!("Wrong qualifying data did not result in an InconsistentParams error");
+    }
+}
+
+fn nv_certify(
+    context: &mut Context,
+    qualifying_data: &Data,
+) -> (KeyHandle, Attest, Signature, Name) {
+    let nv_index = NvIndexTpmHandle::new(0x01500038).unwrap();
+    let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .build()
+        .expect("Failed to create owner nv index attributes");
+    let owner_nv_public = NvPublicBuilder::new()
+        .with_nv_index(nv_index)
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_nv_index_attributes)
+        .with_data_area_size(8)
+        .build()
+        .expect("Failed to build NvPublic for owner");
+    let nv_index_handle = context
+        .nv_define_space(Provision::Owner, None, owner_nv_public)
+        .expect("Failed to define NV index");
+    let write_result = context.nv_write(
+        NvAuth::Owner,
+        nv_index_handle,
+        MaxNvBuffer::try_from(vec![1, 2, 3, 4, 5, 6, 7, 8]).unwrap(),
+        0,
+    );
+    let read_public_result = context.nv_read_public(nv_index_handle);
+
+    let sign_key_handle = context
+        .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+        .expect("Failed to create signing key")
+        .key_handle;
+    let nv_certify_result = context.execute_with_sessions(
+        (
+            Some(AuthSession::Password),
+            Some(AuthSession::Password),
+            None,
+        ),
+        |ctx| {
+            ctx.nv_certify(
+                sign_key_handle,
+                NvAuth::Owner,
+                nv_index_handle,
+                qualifying_data.clone(),
+                SignatureScheme::Null,
+                4,
+                4,
+            )
+        },
+    );
+
+    context
+        .nv_undefine_space(Provision::Owner, nv_index_handle)
+        .expect("Failed to undefine NV index");
+
+    write_result.expect("Failed to write NV index");
+    let (_, nv_index_name) = read_public_result.expect("Failed to read NV public");
+    let (attest, signature) = nv_certify_result.expect("Failed to certify NV index");
+    (sign_key_handle, attest, signature, nv_index_name)
+}
+
+#[test]
+fn test_verify_nv_attestation() {
+    let mut context = create_ctx_with_session();
+    let qualifying_data = Data::try_from(vec![0xab; 16]).expect("Failed to create data");
+    let (sign_key_handle, attest, signature, nv_index_name) =
+        nv_certify(&mut context, &qualifying_data);
+
+    let nv_certify_info = attestation::verify_nv_attestation(
+        &mut context,
+        sign_key_handle,
+        &attest,
+        signature,
+        &qualifying_data,
+        &nv_index_name,
+        &[1, 2, 3, 4, 5, 6, 7, 8],
+    )
+    .expect("Failed to verify NV attestation");
+    assert_eq!(nv_certify_info.offset(), 4);
+    assert_eq!(nv_certify_info.nv_contents().as_slice(), &[5, 6, 7, 8]);
+}
+
+#[test]
+fn test_verify_nv_attestation_with_wrong_contents() {
+    let mut context = create_ctx_with_session();
+    let qualifying_data = Data::try_from(vec![0xab; 16]).expect("Failed to create data");
+    let (sign_key_handle, attest, signature, nv_index_name) =
+        nv_certify(&mut context, &qualifying_data);
+
+    if let Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)) =
+        attestation::verify_nv_attestation(
+            &mut context,
+            sign_key_handle,
+            &attest,
+            signature.clone(),
+            &qualifying_data,
+            &nv_index_name,
+            &[1, 2, 3, 4, 5, 6, 7, 0],
+        )
+    {
+    } else {
+        panic!("Wrong NV contents did not result in an InconsistentParams error");
+    }
+
+    if let Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)) =
+        attestation::verify_nv_attestation(
+            &mut context,
+            sign_key_handle,
+            &attest,
+            signature,
+            &qualifying_data,
+            &nv_index_name,
+            &[5, 6, 7, 8],
+        )
+    {
+    } else {
+        panic!("Too short NV contents did not result in an InconsistentParams error");
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::audit::AuditSession,
+    interface_types::{
+        algorithm::HashingAlgorithm, resource_handles::Hierarchy, structure_tags::AttestationType,
+    },
+    structures::Data,
+    traits::Marshall,
+};
+
+use crate::common::{create_ctx_without_session, signing_key_pub};
+
+#[test]
+fn test_audit_report() {
+    let mut context = create_ctx_without_session();
+    let sign_key_handle = context
+        .execute_with_nullauth_session(|ctx| {
+            ctx.create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+        })
+        .unwrap()
+        .key_handle;
+
+    let audit_session = AuditSession::start(&mut context, HashingAlgorithm::Sha256).unwrap();
+    let _ = audit_session
+        .execute(&mut context, |ctx| ctx.get_random(16))
+        .unwrap();
+    let _ = audit_session
+        .execute_exclusive(&mut context, |ctx| ctx.get_random(16))
+        .unwrap();
+
+    let qualifying_data = Data::try_from(vec![0xff; 16]).unwrap();
+    let report = audit_session
+        .report(&mut context, sign_key_handle, qualifying_data.clone())
+        .unwrap();
+    assert_eq!(
+        report.attest().attestation_type(),
+        AttestationType::SessionAudit
+    );
+    assert_eq!(report.attest().extra_data(), &qualifying_data);
+    assert_eq!(report.session_digest().len(), 32);
+    assert!(report.exclusive());
+
+    audit_session.close(&mut context).unwrap();
+    context.flush_context(sign_key_handle.into()).unwrap();
+}
+
+#[test]
+fn test_audit_session_not_exclusive() {
+    let mut context = create_ctx_without_session();
+
+    let audit_session = AuditSession::start(&mut context, HashingAlgorithm::Sha256).unwrap();
+    let _ = audit_session
+        .execute(&mut context, |ctx| ctx.get_random(16))
+        .unwrap();
+    // A command executed outside of the audit session breaks the exclusivity.
+    let _ = context.get_random(16).unwrap();
+    let _ = audit_session
+        .execute_exclusive(&mut context, |ctx| ctx.get_random(16))
+        .unwrap_err();
+
+    audit_session.close(&mut context).unwrap();
+}
+
+#[test]
+fn test_audit_session_guard() {
+    let mut context = create_ctx_without_session();
+    let mut audit_session =
+        AuditSession::start_scoped(&mut context, HashingAlgorithm::Sha256).unwrap();
+    for _ in 0..3 {
+        let _ = audit_session.execute(|ctx| ctx.get_random(16)).unwrap();
+    }
+    assert_eq!(audit_session.executions(), 3);
+    assert!(audit_session.is_exclusive());
+
+    let qualifying_data = Data::try_from(vec![0xaa; 16]).unwrap();
+    let digest = audit_session.finalize(qualifying_data.clone()).unwrap();
+    assert_eq!(digest.attest().extra_data(), &qualifying_data);
+    assert_eq!(digest.session_digest().len(), 32);
+    assert!(digest.exclusive());
+    assert!(!digest.attest().marshall().unwrap().is_empty());
+}
+
+#[test]
+fn test_audit_session_guard_loses_exclusivity() {
+    let mut context = create_ctx_without_session();
+    let mut audit_session =
+        AuditSession::start_scoped(&mut context, HashingAlgorithm::Sha256).unwrap();
+    let _ = audit_session.execute(|ctx| ctx.get_random(16)).unwrap();
+    // A command executed outside of the audit session breaks the exclusivity.
+    let _ = audit_session
+        .context()
+        .execute_without_session(|ctx| ctx.get_random(16))
+        .unwrap();
+    let _ = audit_session.execute(|ctx| ctx.get_random(16)).unwrap_err();
+    assert!(!audit_session.is_exclusive());
+
+    // The following commands are audited without requiring exclusivity.
+    let _ = audit_session.execute(|ctx| ctx.get_random(16)).unwrap();
+    assert_eq!(audit_session.executions(), 2);
+    let digest = audit_session.finalize(Data::default()).unwrap();
+    assert!(!digest.exclusive());
+}
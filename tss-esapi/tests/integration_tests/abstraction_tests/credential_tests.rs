@@ -0,0 +1,104 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::{ak, credential, ek},
+    interface_types::algorithm::{AsymmetricAlgorithm, HashingAlgorithm, SignatureSchemeAlgorithm},
+    structures::{Auth, Digest},
+};
+
+use crate::common::create_ctx_without_session;
+
+#[test]
+fn test_activate_credential() {
+    let mut context = create_ctx_without_session();
+
+    let ek_rsa = ek::create_ek_object(&mut context, AsymmetricAlgorithm::Rsa, None)
+        .expect("Failed to create EK");
+    let ak_auth = Auth::try_from(vec![0x1, 0x2, 0x42]).expect("Failed to create AK auth");
+    let att_key = ak::create_ak(
+        &mut context,
+        ek_rsa,
+        HashingAlgorithm::Sha256,
+        SignatureSchemeAlgorithm::RsaPss,
+        Some(ak_auth.clone()),
+        None,
+    )
+    .expect("Failed to create AK");
+    let loaded_ak = ak::load_ak(
+        &mut context,
+        ek_rsa,
+        Some(ak_auth),
+        att_key.out_private,
+        att_key.out_public,
+    )
+    .expect("Failed to load AK");
+
+    let (_, key_name, _) = context
+        .read_public(loaded_ak)
+        .expect("Failed to read AK public");
+    let expected = Digest::try_from(vec![1, 2, 3, 4, 5]).expect("Failed to create credential");
+    let (credential_blob, secret) = context
+        .execute_without_session(|ctx| ctx.make_credential(ek_rsa, expected.clone(), key_name))
+        .expect("Failed to make credential");
+
+    let decrypted =
+        credential::activate_credential(&mut context, loaded_ak, ek_rsa, credential_blob, secret)
+            .expect("Failed to activate credential");
+    assert_eq!(expected, decrypted);
+
+    // The policy session has been flushed so activating the credential
+    // again should work as well.
+    let (credential_blob, secret) = context
+        .execute_without_session(|ctx| {
+            let (_, key_name, _) = ctx.read_public(loaded_ak)?;
+            ctx.make_credential(ek_rsa, expected.clone(), key_name)
+        })
+        .expect("Failed to make credential");
+    let decrypted =
+        credential::activate_credential(&mut context, loaded_ak, ek_rsa, credential_blob, secret)
+            .expect("Failed to activate credential");
+    assert_eq!(expected, decrypted);
+}
+
+#[cfg(feature = "openssl")]
+#[test]
+fn test_make_credential_in_software() {
+    let mut context = create_ctx_without_session();
+
+    let ek_rsa = ek::create_ek_object(&mut context, AsymmetricAlgorithm::Rsa, None)
+        .expect("Failed to create EK");
+    let att_key = ak::create_ak(
+        &mut context,
+        ek_rsa,
+        HashingAlgorithm::Sha256,
+        SignatureSchemeAlgorithm::RsaPss,
+        None,
+        None,
+    )
+    .expect("Failed to create AK");
+    let loaded_ak = ak::load_ak(
+        &mut context,
+        ek_rsa,
+        None,
+        att_key.out_private,
+        att_key.out_public,
+    )
+    .expect("Failed to load AK");
+
+    let (ek_public, _, _) = context
+        .read_public(ek_rsa)
+        .expect("Failed to read EK public");
+    let (_, key_name, _) = context
+        .read_public(loaded_ak)
+        .expect("Failed to read AK public");
+    let expected = Digest::try_from(vec![1, 2, 3, 4, 5]).expect("Failed to create credential");
+    let (credential_blob, secret) =
+        credential::make_credential(&ek_public, expected.clone(), key_name)
+            .expect("Failed to make credential");
+
+    let decrypted =
+        credential::activate_credential(&mut context, loaded_ak, ek_rsa, credential_blob, secret)
+            .expect("Failed to activate credential");
+    assert_eq!(expected, decrypted);
+}
@@ -0,0 +1,36 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{abstraction::dictionary_attack, interface_types::resource_handles::Lockout};
+
+use crate::common::create_ctx_with_session;
+
+#[test]
+fn test_get_lockout_status() {
+    let mut context = create_ctx_with_session();
+    let previous_status =
+        dictionary_attack::get_lockout_status(&mut context).expect("Failed to get lockout status");
+
+    context
+        .dictionary_attack_lock_reset(Lockout::Lockout)
+        .expect("Failed to reset the lockout");
+    context
+        .dictionary_attack_parameters(Lockout::Lockout, 5, 60, 120)
+        .expect("Failed to set the lockout parameters");
+
+    let status =
+        dictionary_attack::get_lockout_status(&mut context).expect("Failed to get lockout status");
+    assert_eq!(status.lockout_counter, 0);
+    assert_eq!(status.max_auth_fail, 5);
+    assert_eq!(status.lockout_interval, 60);
+    assert_eq!(status.lockout_recovery, 120);
+    assert!(!status.in_lockout);
+
+    context
+        .dictionary_attack_parameters(
+            Lockout::Lockout,
+            previous_status.max_auth_fail,
+            previous_status.lockout_interval,
+            previous_status.lockout_recovery,
+        )
+        .expect("Failed to restore the lockout parameters");
+}
@@ -0,0 +1,171 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    abstraction::handles::{self, PersistentHandleRegistry},
+    constants::tss::TPM2_PLATFORM_PERSISTENT,
+    handles::{ObjectHandle, PersistentTpmHandle, TpmHandle},
+    interface_types::{
+        dynamic_handles::Persistent,
+        resource_handles::{Hierarchy, Provision},
+        session_handles::AuthSession,
+    },
+    tss2_esys::TPM2_HANDLE,
+    Error, WrapperErrorKind,
+};
+
+use crate::common::{create_ctx_without_session, decryption_key_pub};
+
+#[test]
+fn test_list_persistent() {
+    let persistent_tpm_handle =
+        PersistentTpmHandle::new(u32::from_be_bytes([0x81, 0x00, 0x00, 0x05]))
+            .expect("Failed to create persistent tpm handle");
+    let persistent = Persistent::Persistent(persistent_tpm_handle);
+
+    let mut context = create_ctx_without_session();
+    context.set_sessions((Some(AuthSession::Password), None, None));
+
+    // Make sure the handle is not already persistent
+    if let Ok(handle) = context.execute_without_session(|ctx| {
+        ctx.tr_from_tpm_public(TpmHandle::Persistent(persistent_tpm_handle))
+    }) {
+        let _ = context
+            .evict_control(Provision::Owner, handle, persistent)
+            .expect("Failed to evict persistent handle");
+    }
+
+    let primary_key_handle = context
+        .create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create primary key")
+        .key_handle;
+    let (expected_public, expected_name, _) = context
+        .read_public(primary_key_handle)
+        .expect("Failed to read the public area");
+    let persistent_handle = context
+        .evict_control(Provision::Owner, primary_key_handle.into(), persistent)
+        .expect("Failed to make the primary key persistent");
+    context
+        .flush_context(ObjectHandle::from(primary_key_handle))
+        .expect("Failed to flush the primary key");
+
+    let persistent_objects =
+        handles::list_persistent(&mut context).expect("Failed to list the persistent objects");
+
+    let _ = context
+        .evict_control(Provision::Owner, persistent_handle, persistent)
+        .expect("Failed to evict persistent handle");
+
+    let (_, name, public) = persistent_objects
+        .into_iter()
+        .find(|(handle, _, _)| *handle == persistent_tpm_handle)
+        .expect("The persistent object was not listed");
+    assert_eq!(name, expected_name);
+    assert_eq!(public, expected_public);
+}
+
+#[test]
+fn test_find_free_persistent() {
+    let mut context = create_ctx_without_session();
+    let used_handles = handles::list_persistent(&mut context)
+        .expect("Failed to list the persistent objects")
+        .into_iter()
+        .map(|(handle, _, _)| handle)
+        .collect::<Vec<PersistentTpmHandle>>();
+
+    let free_handle = handles::find_free_persistent(&mut context, Provision::Owner)
+        .expect("Failed to find a free persistent handle");
+    assert!(TPM2_HANDLE::from(free_handle) < TPM2_PLATFORM_PERSISTENT);
+    assert!(!used_handles.contains(&free_handle));
+}
+
+#[test]
+fn test_registry_allocate_evict() {
+    let mut context = create_ctx_without_session();
+    context.set_sessions((Some(AuthSession::Password), None, None));
+    let primary_key_handle = context
+        .create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create primary key")
+        .key_handle;
+
+    let path = std::env::temp_dir().join("tss_esapi_test_registry_allocate_evict");
+    let mut registry = PersistentHandleRegistry::load(&path).expect("Failed to load the registry");
+    let _ = registry.remove("primary");
+    let persistent_handle = registry
+        .allocate(
+            &mut context,
+            "primary",
+            Provision::Owner,
+            primary_key_handle.into(),
+        )
+        .expect("Failed to make the primary key persistent");
+    assert_eq!(
+        registry
+            .allocate(
+                &mut context,
+                "primary",
+                Provision::Owner,
+                primary_key_handle.into(),
+            )
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+    registry.save(&path).expect("Failed to save the registry");
+
+    let mut registry = PersistentHandleRegistry::load(&path).expect("Failed to load the registry");
+    assert_eq!(registry.get("primary"), Some(persistent_handle));
+    registry
+        .evict(&mut context, Provision::Owner, "primary")
+        .expect("Failed to evict the primary key");
+    assert_eq!(registry.get("primary"), None);
+    registry.save(&path).expect("Failed to save the registry");
+
+    let _ = context
+        .execute_without_session(|ctx| {
+            ctx.tr_from_tpm_public(TpmHandle::Persistent(persistent_handle))
+        })
+        .expect_err("The primary key should have been evicted");
+}
+
+#[test]
+fn test_registry_save_load() {
+    let mut registry = PersistentHandleRegistry::new();
+    let srk_handle = PersistentTpmHandle::new(0x81000001).unwrap();
+    let platform_handle = PersistentTpmHandle::new(0x81800001).unwrap();
+    registry.insert("srk", srk_handle).unwrap();
+    registry.insert("platform key", platform_handle).unwrap();
+    assert_eq!(
+        registry.insert("srk", platform_handle).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+    assert_eq!(
+        registry.insert("two\nlines", srk_handle).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+
+    let path = std::env::temp_dir().join("tss_esapi_test_registry_save_load");
+    registry.save(&path).expect("Failed to save the registry");
+    let loaded_registry =
+        PersistentHandleRegistry::load(&path).expect("Failed to load the registry");
+    std::fs::remove_file(&path).expect("Failed to remove the registry");
+
+    assert_eq!(registry, loaded_registry);
+    assert_eq!(loaded_registry.get("platform key"), Some(platform_handle));
+    assert_eq!(
+        loaded_registry.names().collect::<Vec<&str>>(),
+        vec!["platform key", "srk"]
+    );
+}
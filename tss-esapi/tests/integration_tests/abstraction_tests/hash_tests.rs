@@ -0,0 +1,170 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use sha2::{Digest as _, Sha256};
+use tss_esapi::{
+    abstraction::hash::{TpmEventSequence, TpmHasher, TpmHmac},
+    attributes::ObjectAttributesBuilder,
+    handles::{ObjectHandle, PcrHandle},
+    interface_types::{
+        algorithm::{HashingAlgorithm, PublicAlgorithm},
+        resource_handles::Hierarchy,
+        session_handles::AuthSession,
+    },
+    structures::{
+        KeyedHashScheme, MaxBuffer, PcrSelectionListBuilder, PcrSlot, PublicBuilder,
+        PublicKeyedHashParameters, Ticket,
+    },
+};
+
+use crate::common::{create_ctx_with_session, create_ctx_without_session};
+
+#[test]
+fn test_tpm_hasher() {
+    let mut context = create_ctx_without_session();
+    // Larger than what fits in a single TPM2B_MAX_BUFFER.
+    let data: Vec<u8> = (0..(3 * MaxBuffer::MAX_SIZE + 7))
+        .map(|v| (v % 251) as u8)
+        .collect();
+
+    let (digest, ticket) = context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            let mut hasher = TpmHasher::new(ctx, HashingAlgorithm::Sha256)?;
+            hasher.update(&data[..100])?;
+            hasher.update(&data[100..])?;
+            hasher.finalize(Hierarchy::Owner)
+        })
+        .expect("Failed to hash data");
+
+    assert_eq!(digest.as_bytes(), Sha256::digest(&data).as_slice());
+    assert_eq!(ticket.hierarchy(), Hierarchy::Owner);
+}
+
+#[test]
+fn test_tpm_hasher_drop() {
+    let mut context = create_ctx_without_session();
+    context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            let mut hasher = TpmHasher::new(ctx, HashingAlgorithm::Sha256)?;
+            hasher.update(b"There is no spoon")
+        })
+        .expect("Failed to update hasher");
+
+    // The sequence object has been flushed so the context
+    // should not have any more open handles at this point.
+    let (digest, _) = context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            TpmHasher::new(ctx, HashingAlgorithm::Sha256)?.finalize(Hierarchy::Null)
+        })
+        .expect("Failed to hash empty data");
+    assert_eq!(digest.as_bytes(), Sha256::digest(b"").as_slice());
+}
+
+#[test]
+fn test_tpm_hmac() {
+    let mut context = create_ctx_with_session();
+
+    let object_attributes = ObjectAttributesBuilder::new()
+        .with_sign_encrypt(true)
+        .with_sensitive_data_origin(true)
+        .with_user_with_auth(true)
+        .build()
+        .expect("Failed to build object attributes");
+
+    let key_pub = PublicBuilder::new()
+        .with_public_algorithm(PublicAlgorithm::KeyedHash)
+        .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+        .with_object_attributes(object_attributes)
+        .with_keyed_hash_parameters(PublicKeyedHashParameters::new(
+            KeyedHashScheme::HMAC_SHA_256,
+        ))
+        .with_keyed_hash_unique_identifier(Default::default())
+        .build()
+        .expect("Failed to build public structure for key.");
+
+    let key_handle: ObjectHandle = context
+        .create_primary(Hierarchy::Owner, key_pub, None, None, None, None)
+        .expect("Failed to create key")
+        .key_handle
+        .into();
+
+    let short_data = b"There is no spoon";
+    let mut hmac = TpmHmac::new(&mut context, key_handle, HashingAlgorithm::Sha256)
+        .expect("Failed to start HMAC sequence");
+    hmac.update(&short_data[..8])
+        .expect("Failed to update HMAC sequence");
+    hmac.update(&short_data[8..])
+        .expect("Failed to update HMAC sequence");
+    let streamed_hmac = hmac.finalize().expect("Failed to finalize HMAC sequence");
+    let expected_hmac = context
+        .hmac(
+            key_handle,
+            MaxBuffer::from_bytes(short_data).expect("Failed to create buffer"),
+            HashingAlgorithm::Sha256,
+        )
+        .expect("Failed to calculate HMAC");
+    assert_eq!(expected_hmac, streamed_hmac);
+
+    // Larger than what fits in a single TPM2B_MAX_BUFFER.
+    let data: Vec<u8> = (0..(3 * MaxBuffer::MAX_SIZE + 7))
+        .map(|v| (v % 251) as u8)
+        .collect();
+    let mut hmac = TpmHmac::new(&mut context, key_handle, HashingAlgorithm::Sha256)
+        .expect("Failed to start HMAC sequence");
+    hmac.update(&data).expect("Failed to update HMAC sequence");
+    let large_hmac = hmac.finalize().expect("Failed to finalize HMAC sequence");
+    assert_eq!(large_hmac.len(), 32);
+    assert_ne!(large_hmac, streamed_hmac);
+}
+
+#[test]
+fn test_tpm_event_sequence() {
+    let mut context = create_ctx_without_session();
+    // PCR16 is resettable and extendable from the locality in which the tests are running.
+    context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            ctx.pcr_reset(PcrHandle::Pcr16)
+        })
+        .expect("Failed to reset PCR16");
+
+    let data: Vec<u8> = (0..(2 * MaxBuffer::MAX_SIZE + 7))
+        .map(|v| (v % 251) as u8)
+        .collect();
+    let digest_values = context
+        .execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                let mut event_sequence = TpmEventSequence::new(ctx)?;
+                event_sequence.update(&data)?;
+                event_sequence.finalize(Some(PcrHandle::Pcr16))
+            },
+        )
+        .expect("Failed to hash and extend event");
+
+    let event_digest = Sha256::digest(&data);
+    assert_eq!(
+        digest_values
+            .get(HashingAlgorithm::Sha256)
+            .expect("Missing SHA256 digest")
+            .as_bytes(),
+        event_digest.as_slice()
+    );
+
+    let pcr_selection_list = PcrSelectionListBuilder::new()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+        .build()
+        .expect("Failed to create PcrSelectionList");
+    let (_, _, pcr_digests) = context
+        .execute_without_session(|ctx| ctx.pcr_read(pcr_selection_list))
+        .expect("Failed to read PCR16");
+    let mut expected_pcr_value = Sha256::new();
+    expected_pcr_value.update([0u8; 32]);
+    expected_pcr_value.update(event_digest);
+    assert_eq!(
+        pcr_digests.value()[0].as_bytes(),
+        expected_pcr_value.finalize().as_slice()
+    );
+}
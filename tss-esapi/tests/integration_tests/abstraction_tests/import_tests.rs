@@ -0,0 +1,137 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::import,
+    attributes::ObjectAttributesBuilder,
+    interface_types::{
+        algorithm::{EccSchemeAlgorithm, HashingAlgorithm, PublicAlgorithm},
+        ecc::EccCurve,
+        resource_handles::Hierarchy,
+    },
+    structures::{
+        EccParameter, EccPoint, EccScheme, Public, PublicBuilder, PublicEccParametersBuilder,
+        Sensitive, SensitiveBuilder, SymmetricDefinitionObject,
+    },
+    Context,
+};
+
+use crate::common::{create_ctx_with_session, decryption_key_pub};
+
+fn external_ecc_key() -> (Public, Sensitive) {
+    let group = openssl::ec::EcGroup::from_curve_name(openssl::nid::Nid::X9_62_PRIME256V1)
+        .expect("Failed to create EC group");
+    let key = openssl::ec::EcKey::generate(&group).expect("Failed to generate EC key");
+    let mut x = openssl::bn::BigNum::new().expect("Failed to create BigNum");
+    let mut y = openssl::bn::BigNum::new().expect("Failed to create BigNum");
+    let mut ctx = openssl::bn::BigNumContext::new().expect("Failed to create BigNumContext");
+    key.public_key()
+        .affine_coordinates_gfp(&group, &mut x, &mut y, &mut ctx)
+        .expect("Failed to get the public coordinates");
+
+    let object_attributes = ObjectAttributesBuilder::new()
+        .with_user_with_auth(true)
+        .with_sign_encrypt(true)
+        .build()
+        .expect("Failed to build object attributes");
+    let public = PublicBuilder::new()
+        .with_public_algorithm(PublicAlgorithm::Ecc)
+        .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+        .with_object_attributes(object_attributes)
+        .with_ecc_parameters(
+            PublicEccParametersBuilder::new_unrestricted_signing_key(
+                EccScheme::create(
+                    EccSchemeAlgorithm::EcDsa,
+                    Some(HashingAlgorithm::Sha256),
+                    None,
+                )
+                .expect("Failed to create ECC scheme"),
+                EccCurve::NistP256,
+            )
+            .build()
+            .expect("Failed to build ECC parameters"),
+        )
+        .with_ecc_unique_identifier(EccPoint::new(
+            EccParameter::try_from(x.to_vec_padded(32).unwrap()).unwrap(),
+            EccParameter::try_from(y.to_vec_padded(32).unwrap()).unwrap(),
+        ))
+        .build()
+        .expect("Failed to build public");
+    let sensitive = SensitiveBuilder::new()
+        .with_sensitive_type(PublicAlgorithm::Ecc)
+        .with_ecc_private_key(
+            EccParameter::try_from(key.private_key().to_vec_padded(32).unwrap()).unwrap(),
+        )
+        .build()
+        .expect("Failed to build sensitive");
+    (public, sensitive)
+}
+
+fn import_and_load(context: &mut Context, parent_public: Public) {
+    let parent_handle = context
+        .create_primary(Hierarchy::Owner, parent_public, None, None, None, None)
+        .unwrap()
+        .key_handle;
+    let (parent_public, _, _) = context.read_public(parent_handle).unwrap();
+
+    let (public, sensitive) = external_ecc_key();
+    let (duplicate, encrypted_secret) =
+        import::create_import_blob(&parent_public, &public, &sensitive)
+            .expect("Failed to create import blob");
+
+    let private = context
+        .import(
+            parent_handle.into(),
+            None,
+            public.clone(),
+            duplicate,
+            encrypted_secret,
+            SymmetricDefinitionObject::Null,
+        )
+        .expect("Failed to import the external key");
+    let key_handle = context
+        .load(parent_handle, private, public.clone())
+        .expect("Failed to load the imported key");
+
+    let (loaded_public, _, _) = context.read_public(key_handle).unwrap();
+    assert_eq!(public, loaded_public);
+
+    context.flush_context(key_handle.into()).unwrap();
+    context.flush_context(parent_handle.into()).unwrap();
+}
+
+#[test]
+fn test_create_import_blob_rsa_parent() {
+    let mut context = create_ctx_with_session();
+    import_and_load(&mut context, decryption_key_pub());
+}
+
+#[test]
+fn test_create_import_blob_ecc_parent() {
+    let mut context = create_ctx_with_session();
+    let object_attributes = ObjectAttributesBuilder::new()
+        .with_fixed_tpm(true)
+        .with_fixed_parent(true)
+        .with_sensitive_data_origin(true)
+        .with_user_with_auth(true)
+        .with_decrypt(true)
+        .with_restricted(true)
+        .build()
+        .expect("Failed to build object attributes");
+    let parent_public = PublicBuilder::new()
+        .with_public_algorithm(PublicAlgorithm::Ecc)
+        .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+        .with_object_attributes(object_attributes)
+        .with_ecc_parameters(
+            PublicEccParametersBuilder::new_restricted_decryption_key(
+                SymmetricDefinitionObject::AES_128_CFB,
+                EccCurve::NistP256,
+            )
+            .build()
+            .expect("Failed to build ECC parameters"),
+        )
+        .with_ecc_unique_identifier(EccPoint::default())
+        .build()
+        .expect("Failed to build public");
+    import_and_load(&mut context, parent_public);
+}
@@ -1,9 +1,22 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 mod ak_tests;
+mod attestation_tests;
+mod audit_tests;
+mod credential_tests;
+mod dictionary_attack_tests;
 mod ek_tests;
+mod handles_tests;
+mod hash_tests;
+#[cfg(feature = "openssl")]
+mod import_tests;
 mod nv_tests;
 mod pcr_data_tests;
 mod pcr_tests;
+mod policy_tests;
+mod primary_tests;
 mod public_tests;
+mod session_tests;
+mod symmetric_tests;
 mod transient_key_context_tests;
+mod x509_tests;
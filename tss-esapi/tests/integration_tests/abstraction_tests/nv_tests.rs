@@ -2,22 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::{
-    convert::TryFrom,
+    convert::{TryFrom, TryInto},
     io::{ErrorKind, Seek, SeekFrom, Write},
 };
 use tss_esapi::{
     abstraction::nv,
     attributes::NvIndexAttributesBuilder,
-    handles::{NvIndexHandle, NvIndexTpmHandle},
+    constants::{nv_index_type::NvIndexType, CommandCode, SessionType},
+    handles::{NvIndexHandle, NvIndexTpmHandle, SessionHandle},
     interface_types::{
         algorithm::HashingAlgorithm,
         resource_handles::{NvAuth, Provision},
+        session_handles::PolicySession,
     },
-    structures::{MaxNvBuffer, NvPublicBuilder},
-    Context,
+    structures::{Auth, MaxNvBuffer, NvPublicBuilder},
+    Context, Error, WrapperErrorKind,
 };
 
-use crate::common::create_ctx_with_session;
+use crate::common::{create_ctx_with_session, start_policy_session};
 
 fn write_nv_index(context: &mut Context, nv_index: NvIndexTpmHandle) -> NvIndexHandle {
     // Create owner nv public.
@@ -159,3 +161,581 @@ fn write() {
         .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
         .expect("Call to nv_undefine_space failed");
 }
+
+#[test]
+fn set_bits() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150002B).unwrap();
+
+    let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .with_nv_index_type(NvIndexType::Bits)
+        .build()
+        .expect("Failed to create owner nv index attributes");
+    let owner_nv_public = NvPublicBuilder::new()
+        .with_nv_index(nv_index)
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_nv_index_attributes)
+        .with_data_area_size(8)
+        .build()
+        .unwrap();
+    let owner_nv_index_handle = context
+        .nv_define_space(Provision::Owner, None, owner_nv_public)
+        .expect("Call to nv_define_space failed");
+
+    let first_set_bits_result = nv::set_bits(&mut context, NvAuth::Owner, nv_index, 0b0011);
+    let second_set_bits_result = nv::set_bits(&mut context, NvAuth::Owner, nv_index, 0b1000);
+    let read_result = nv::read_full(&mut context, NvAuth::Owner, nv_index);
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    first_set_bits_result.expect("First call to set_bits failed");
+    second_set_bits_result.expect("Second call to set_bits failed");
+    // The bit field is an 8 byte big-endian value.
+    let value = u64::from_be_bytes(read_result.unwrap().try_into().unwrap());
+    assert_eq!(value, 0b1011);
+}
+
+#[test]
+fn change_auth() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500036).unwrap();
+
+    let start_nv_change_auth_policy_session = |context: &mut Context, session_type| {
+        let policy_session = start_policy_session(context, session_type);
+        context
+            .execute_without_session(|ctx| {
+                ctx.policy_command_code(policy_session, CommandCode::NvChangeAuth)
+            })
+            .unwrap();
+        policy_session
+    };
+
+    let trial_session = start_nv_change_auth_policy_session(&mut context, SessionType::Trial);
+    let policy_digest = context.execute_without_session(|ctx| {
+        let policy_digest = ctx.policy_get_digest(trial_session).unwrap();
+        ctx.flush_context(SessionHandle::from(trial_session).into())
+            .unwrap();
+        policy_digest
+    });
+
+    let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .with_auth_write(true)
+        .with_auth_read(true)
+        .build()
+        .expect("Failed to create owner nv index attributes");
+    let owner_nv_public = NvPublicBuilder::new()
+        .with_nv_index(nv_index)
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_nv_index_attributes)
+        .with_index_auth_policy(policy_digest)
+        .with_data_area_size(32)
+        .build()
+        .unwrap();
+    let owner_nv_index_handle = context
+        .nv_define_space(Provision::Owner, None, owner_nv_public)
+        .expect("Call to nv_define_space failed");
+
+    let policy_session = start_nv_change_auth_policy_session(&mut context, SessionType::Policy);
+    let change_auth_result = context.execute_with_session(Some(policy_session.into()), |ctx| {
+        nv::change_auth(ctx, nv_index, Auth::try_from(vec![1, 2, 3]).unwrap())
+    });
+    context
+        .flush_context(SessionHandle::from(policy_session).into())
+        .unwrap();
+
+    // The index handle that was used to define the index does not know
+    // about the new auth value, so it has to be set explicitly.
+    context
+        .tr_set_auth(
+            owner_nv_index_handle.into(),
+            Auth::try_from(vec![1, 2, 3]).unwrap(),
+        )
+        .unwrap();
+    let write_result = context.nv_write(
+        NvAuth::NvIndex(owner_nv_index_handle),
+        owner_nv_index_handle,
+        MaxNvBuffer::try_from(vec![1, 2, 3, 4, 5, 6, 7]).unwrap(),
+        0,
+    );
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    change_auth_result.expect("Call to change_auth failed");
+    write_result.expect("Call to nv_write with the new auth value failed");
+}
+
+#[test]
+fn write_full() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500039).unwrap();
+
+    let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .build()
+        .expect("Failed to create owner nv index attributes");
+    let owner_nv_public = NvPublicBuilder::new()
+        .with_nv_index(nv_index)
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_nv_index_attributes)
+        .with_data_area_size(1540)
+        .build()
+        .unwrap();
+    let owner_nv_index_handle = context
+        .nv_define_space(Provision::Owner, None, owner_nv_public)
+        .expect("Call to nv_define_space failed");
+
+    // The data is larger than the max NV buffer size, so it needs several writes.
+    let value: Vec<u8> = (0..1540).map(|i| i as u8).collect();
+    let write_result = nv::write_full(&mut context, NvAuth::Owner, nv_index, &value);
+    let read_result = nv::read_full(&mut context, NvAuth::Owner, nv_index);
+    let too_large_write_result = nv::write_full(&mut context, NvAuth::Owner, nv_index, &[0; 1541]);
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    write_result.expect("Call to write_full failed");
+    assert_eq!(read_result.expect("Call to read_full failed"), value);
+    assert_eq!(
+        too_large_write_result.unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::WrongParamSize)
+    );
+}
+
+#[test]
+fn list_iter() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003A).unwrap();
+
+    let owner_nv_index_handle = write_nv_index(&mut context, nv_index);
+
+    let list_result = nv::list_iter(&mut context)
+        .and_then(|nv_indexes| nv_indexes.collect::<tss_esapi::Result<Vec<_>>>());
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    let (_, nv_public) = list_result
+        .expect("Call to list_iter failed")
+        .into_iter()
+        .find(|(handle, _)| *handle == nv_index)
+        .expect("The NV index was not listed");
+    assert_eq!(nv_public.nv_index(), nv_index);
+    assert_eq!(nv_public.data_size(), 1540);
+}
+
+#[test]
+fn counter() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003B).unwrap();
+
+    let mut counter = nv::Counter::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        HashingAlgorithm::Sha256,
+    )
+    .expect("Failed to define counter");
+    let initial_value = counter.value();
+    let read_result = counter.read(&mut context);
+    let increment_result = counter.increment(&mut context);
+    let open_result = nv::Counter::open(&mut context, NvAuth::Owner, nv_index);
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(read_result.expect("Failed to read counter"), initial_value);
+    assert_eq!(
+        increment_result.expect("Failed to increment counter"),
+        initial_value + 1
+    );
+    assert_eq!(
+        open_result.expect("Failed to open counter").value(),
+        initial_value + 1
+    );
+}
+
+#[test]
+fn counter_recreated() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003C).unwrap();
+
+    let mut counter = nv::Counter::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        HashingAlgorithm::Sha256,
+    )
+    .expect("Failed to define counter");
+
+    // Recreate the index with a different name algorithm.
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+    let _ = nv::Counter::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        HashingAlgorithm::Sha1,
+    )
+    .expect("Failed to define counter");
+
+    let increment_result = counter.increment(&mut context);
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(
+        increment_result.unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+}
+
+#[test]
+fn bit_field() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003D).unwrap();
+
+    let bit_field = nv::BitField::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        HashingAlgorithm::Sha256,
+    )
+    .expect("Failed to define bit field");
+    let initial_read_result = bit_field.read(&mut context);
+    let first_set_bits_result = bit_field.set_bits(&mut context, 0b0101);
+    let second_set_bits_result = bit_field.set_bits(&mut context, 1 << 63);
+    let read_result = bit_field.read(&mut context);
+    let open_result = nv::BitField::open(&mut context, NvAuth::Owner, nv_index)
+        .and_then(|bit_field| bit_field.read(&mut context));
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(initial_read_result.expect("Failed to read bit field"), 0);
+    first_set_bits_result.expect("First call to set_bits failed");
+    second_set_bits_result.expect("Second call to set_bits failed");
+    assert_eq!(
+        read_result.expect("Failed to read bit field"),
+        1 << 63 | 0b0101
+    );
+    assert_eq!(
+        open_result.expect("Failed to open bit field"),
+        1 << 63 | 0b0101
+    );
+}
+
+#[test]
+fn bit_field_open_wrong_type() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003E).unwrap();
+
+    let owner_nv_index_handle = write_nv_index(&mut context, nv_index);
+
+    let open_result = nv::BitField::open(&mut context, NvAuth::Owner, nv_index);
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(
+        open_result.unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
+
+#[test]
+fn pin_counter_parameters() {
+    let parameters = nv::PinCounterParameters::new(1, 0x01020304);
+    assert_eq!(parameters.to_bytes(), [0, 0, 0, 1, 1, 2, 3, 4]);
+    assert_eq!(
+        nv::PinCounterParameters::try_from(&parameters.to_bytes()[..]).unwrap(),
+        parameters
+    );
+    assert_eq!(
+        nv::PinCounterParameters::try_from(&[0u8; 7][..]).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::WrongParamSize)
+    );
+}
+
+#[test]
+fn pin_pass() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x0150003F).unwrap();
+    let pin = Auth::try_from(vec![1, 2, 3, 4]).unwrap();
+
+    let pin_index = nv::PinIndex::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        NvIndexType::PinPass,
+        HashingAlgorithm::Sha256,
+        pin.clone(),
+        2,
+    )
+    .expect("Failed to define PIN pass index");
+    let use_results: Vec<_> = (0..3)
+        .map(|_| pin_index.try_use_pin(&mut context, pin.clone()))
+        .collect();
+    let parameters_result = pin_index.parameters(&mut context);
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    let use_results: Vec<bool> = use_results
+        .into_iter()
+        .map(|result| result.expect("Failed to use PIN"))
+        .collect();
+    // The PIN can only be used twice.
+    assert_eq!(use_results, [true, true, false]);
+    assert_eq!(
+        parameters_result.expect("Failed to read parameters"),
+        nv::PinCounterParameters::new(2, 2)
+    );
+}
+
+#[test]
+fn pin_fail() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500040).unwrap();
+    let pin = Auth::try_from(vec![1, 2, 3, 4]).unwrap();
+    let wrong_pin = Auth::try_from(vec![4, 3, 2, 1]).unwrap();
+
+    let pin_index = nv::PinIndex::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        NvIndexType::PinFail,
+        HashingAlgorithm::Sha256,
+        pin.clone(),
+        1,
+    )
+    .expect("Failed to define PIN fail index");
+    let wrong_use_result = pin_index.try_use_pin(&mut context, wrong_pin);
+    let blocked_use_result = pin_index.try_use_pin(&mut context, pin.clone());
+    // Resetting the count allows using the PIN again.
+    let set_parameters_result =
+        pin_index.set_parameters(&mut context, nv::PinCounterParameters::new(0, 1));
+    let use_result = pin_index.try_use_pin(&mut context, pin);
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    assert!(!wrong_use_result.expect("Failed to use wrong PIN"));
+    assert!(!blocked_use_result.expect("Failed to use PIN"));
+    set_parameters_result.expect("Failed to set parameters");
+    assert!(use_result.expect("Failed to use PIN"));
+}
+
+#[test]
+fn extend_index() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500041).unwrap();
+
+    let extend_index = nv::ExtendIndex::define(
+        &mut context,
+        Provision::Owner,
+        nv_index,
+        HashingAlgorithm::Sha256,
+    )
+    .expect("Failed to define extend index");
+    let events: [&[u8]; 2] = [b"first event", b"second event"];
+    let initial_read_result = extend_index.read(&mut context);
+    let extend_results: Vec<_> = events
+        .iter()
+        .map(|event| extend_index.extend(&mut context, event))
+        .collect();
+    let read_result = extend_index.read(&mut context);
+    let expected_value_result = extend_index.expected_value_with_tpm(&mut context, events);
+    let open_result = nv::ExtendIndex::open(&mut context, NvAuth::Owner, nv_index);
+
+    let owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed");
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle.into())
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(
+        initial_read_result
+            .expect("Failed to read extend index")
+            .as_bytes(),
+        &[0u8; 32]
+    );
+    for extend_result in extend_results {
+        extend_result.expect("Call to extend failed");
+    }
+    let value = read_result.expect("Failed to read extend index");
+    assert_eq!(
+        expected_value_result.expect("Failed to compute expected value"),
+        value
+    );
+    #[cfg(feature = "openssl")]
+    assert_eq!(
+        extend_index
+            .expected_value(events)
+            .expect("Failed to compute expected value in software"),
+        value
+    );
+    assert_eq!(
+        open_result
+            .expect("Failed to open extend index")
+            .hashing_algorithm(),
+        HashingAlgorithm::Sha256
+    );
+}
+
+#[test]
+fn extend_index_open_wrong_type() {
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500042).unwrap();
+
+    let owner_nv_index_handle = write_nv_index(&mut context, nv_index);
+
+    let open_result = nv::ExtendIndex::open(&mut context, NvAuth::Owner, nv_index);
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    assert_eq!(
+        open_result.unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
+
+#[test]
+fn write_policy_digest() {
+    fn start_command_code_policy_session(
+        context: &mut Context,
+        session_type: SessionType,
+        command_code: CommandCode,
+    ) -> PolicySession {
+        let policy_session = start_policy_session(context, session_type);
+        context.execute_without_session(|ctx| {
+            ctx.policy_command_code(policy_session, command_code)
+                .expect("Call to policy_command_code failed");
+        });
+        policy_session
+    }
+
+    let mut context = create_ctx_with_session();
+
+    let nv_index = NvIndexTpmHandle::new(0x01500046).unwrap();
+    let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .build()
+        .expect("Failed to create owner nv index attributes");
+    let owner_nv_public = NvPublicBuilder::new()
+        .with_nv_index(nv_index)
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_nv_index_attributes)
+        .with_data_area_size(34)
+        .build()
+        .unwrap();
+    let mut owner_nv_index_handle = context
+        .nv_define_space(Provision::Owner, None, owner_nv_public)
+        .expect("Call to nv_define_space failed");
+
+    // The authorized policy only allows unsealing.
+    let trial_session =
+        start_command_code_policy_session(&mut context, SessionType::Trial, CommandCode::Unseal);
+    let authorized_policy_digest = context
+        .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+        .expect("Call to policy_get_digest failed");
+    context
+        .flush_context(SessionHandle::from(trial_session).into())
+        .expect("Call to flush_context failed");
+    let write_result = nv::write_policy_digest(
+        &mut context,
+        NvAuth::Owner,
+        nv_index,
+        HashingAlgorithm::Sha256,
+        &authorized_policy_digest,
+    );
+
+    // The name of the index has changed when it was written.
+    context
+        .execute_without_session(|ctx| ctx.tr_close(&mut owner_nv_index_handle.into()))
+        .expect("Call to tr_close failed");
+    owner_nv_index_handle = context
+        .execute_without_session(|ctx| ctx.tr_from_tpm_public(nv_index.into()))
+        .expect("Call to tr_from_tpm_public failed")
+        .into();
+
+    let authorize_nv = |context: &mut Context, policy_session: PolicySession| {
+        let result =
+            context.policy_authorize_nv(NvAuth::Owner, owner_nv_index_handle, policy_session);
+        let digest = context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session));
+        let _ = context.flush_context(SessionHandle::from(policy_session).into());
+        result.and(digest)
+    };
+    let trial_session =
+        start_command_code_policy_session(&mut context, SessionType::Trial, CommandCode::Unseal);
+    let expected_policy_digest = authorize_nv(&mut context, trial_session);
+    let policy_session =
+        start_command_code_policy_session(&mut context, SessionType::Policy, CommandCode::Unseal);
+    let policy_digest = authorize_nv(&mut context, policy_session);
+    let policy_session =
+        start_command_code_policy_session(&mut context, SessionType::Policy, CommandCode::Sign);
+    let other_policy_digest = authorize_nv(&mut context, policy_session);
+
+    context
+        .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+        .expect("Call to nv_undefine_space failed");
+
+    write_result.expect("Call to write_policy_digest failed");
+    assert_eq!(
+        policy_digest.expect("Call to policy_authorize_nv failed"),
+        expected_policy_digest.expect("Call to policy_authorize_nv with a trial session failed")
+    );
+    let _ =
+        other_policy_digest.expect_err("Call to policy_authorize_nv with another policy succeeded");
+}
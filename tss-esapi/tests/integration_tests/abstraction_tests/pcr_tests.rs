@@ -1,10 +1,14 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
-use crate::common::create_ctx_without_session;
+use crate::common::{create_ctx_with_session, create_ctx_without_session};
 
+use sha2::{Digest as _, Sha256};
 use tss_esapi::{
+    abstraction::pcr,
+    handles::PcrHandle,
     interface_types::algorithm::HashingAlgorithm,
-    structures::{PcrSelectionListBuilder, PcrSlot},
+    structures::{Event, PcrSelectionListBuilder, PcrSlot},
+    Context,
 };
 
 #[test]
@@ -139,3 +143,209 @@ fn test_pcr_read_all() {
                 })
         })
 }
+
+fn extend_and_check(context: &mut Context, event_data: &[u8]) {
+    context.pcr_reset(PcrHandle::Pcr16).unwrap();
+    let digests =
+        pcr::extend(context, PcrHandle::Pcr16, event_data).expect("Call to pcr::extend failed");
+
+    let event_digest = Sha256::digest(event_data);
+    assert_eq!(
+        digests
+            .get(HashingAlgorithm::Sha256)
+            .expect("Missing SHA-256 digest")
+            .as_bytes(),
+        event_digest.as_slice()
+    );
+
+    let pcr_selection_list = PcrSelectionListBuilder::new()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+        .build()
+        .unwrap();
+    let (_, _, pcr_digests) = context
+        .execute_without_session(|ctx| ctx.pcr_read(pcr_selection_list))
+        .unwrap();
+    let mut hasher = Sha256::new();
+    hasher.update([0u8; 32]);
+    hasher.update(event_digest);
+    assert_eq!(
+        pcr_digests.value()[0].as_bytes(),
+        hasher.finalize().as_slice()
+    );
+}
+
+#[test]
+fn test_pcr_extend() {
+    let mut context = create_ctx_with_session();
+    extend_and_check(&mut context, b"event data");
+}
+
+#[test]
+fn test_pcr_extend_large_event() {
+    let mut context = create_ctx_with_session();
+    extend_and_check(&mut context, &vec![0xA5; Event::MAX_SIZE * 3 + 1]);
+}
+
+#[test]
+fn test_pcr_snapshot() {
+    let mut context = create_ctx_without_session();
+
+    let pcr_snapshot = pcr::snapshot(&mut context).expect("Call to pcr::snapshot failed");
+    assert!(pcr_snapshot
+        .hashing_algorithms()
+        .any(|hashing_algorithm| hashing_algorithm == HashingAlgorithm::Sha256));
+    assert_eq!(
+        pcr_snapshot
+            .bank(HashingAlgorithm::Sha256)
+            .expect("Missing SHA-256 bank")
+            .len(),
+        24
+    );
+    assert_eq!(pcr_snapshot.iter().count(), pcr_snapshot.len());
+
+    let pcr_selection_list = PcrSelectionListBuilder::new()
+        .with_selection(
+            HashingAlgorithm::Sha256,
+            &[PcrSlot::Slot0, PcrSlot::Slot1, PcrSlot::Slot7],
+        )
+        .build()
+        .unwrap();
+    let (_, _, pcr_digests) = context.pcr_read(pcr_selection_list.clone()).unwrap();
+    assert_eq!(
+        &pcr_snapshot[(HashingAlgorithm::Sha256, PcrSlot::Slot0)],
+        &pcr_digests.value()[0]
+    );
+    assert_eq!(
+        pcr_snapshot.get(HashingAlgorithm::Sha256, PcrSlot::Slot7),
+        Some(&pcr_digests.value()[2])
+    );
+
+    let composite_data = pcr_snapshot
+        .composite_data(&pcr_selection_list)
+        .expect("Failed to get composite data");
+    assert_eq!(
+        composite_data,
+        pcr_digests
+            .value()
+            .iter()
+            .flat_map(|digest| digest.as_bytes().to_vec())
+            .collect::<Vec<u8>>()
+    );
+    #[cfg(feature = "openssl")]
+    assert_eq!(
+        pcr_snapshot
+            .composite_digest(&pcr_selection_list, HashingAlgorithm::Sha256)
+            .expect("Failed to compute composite digest")
+            .as_bytes(),
+        Sha256::digest(&composite_data).as_slice()
+    );
+}
+
+#[cfg(feature = "openssl")]
+#[test]
+fn test_pcr_predictor() {
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        constants::SessionType,
+        interface_types::session_handles::PolicySession,
+        structures::{Digest, SymmetricDefinition},
+    };
+
+    let mut context = create_ctx_with_session();
+    let event_data = b"planned event";
+
+    context.pcr_reset(PcrHandle::Pcr16).unwrap();
+    let mut pcr_predictor =
+        pcr::PcrPredictor::new(pcr::snapshot(&mut context).expect("Call to pcr::snapshot failed"));
+    pcr_predictor
+        .extend_event(PcrSlot::Slot16, event_data)
+        .expect("Failed to predict event");
+    let _ = context
+        .pcr_event(
+            PcrHandle::Pcr16,
+            Event::try_from(event_data.to_vec()).unwrap(),
+        )
+        .expect("Call to pcr_event failed");
+
+    let pcr_snapshot = pcr::snapshot(&mut context).expect("Call to pcr::snapshot failed");
+    assert_eq!(pcr_predictor.pcr_snapshot(), &pcr_snapshot);
+
+    let pcr_selection_list = PcrSelectionListBuilder::new()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot0, PcrSlot::Slot16])
+        .build()
+        .unwrap();
+    let predicted_policy_digest = pcr_predictor
+        .policy_pcr_digest(&pcr_selection_list, HashingAlgorithm::Sha256, None)
+        .expect("Failed to predict policy digest");
+
+    let policy_digest = context.execute_without_session(|ctx| {
+        let policy_session = ctx
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Policy,
+                SymmetricDefinition::AES_256_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+        ctx.policy_pcr(policy_session, Digest::default(), pcr_selection_list)
+            .expect("Call to policy_pcr failed");
+        ctx.policy_get_digest(policy_session)
+            .expect("Call to policy_get_digest failed")
+    });
+    assert_eq!(predicted_policy_digest, policy_digest);
+}
+
+#[test]
+fn test_allocated_banks() {
+    let mut context = create_ctx_without_session();
+
+    let pcr_bank_allocations =
+        pcr::allocated_banks(&mut context).expect("Call to pcr::allocated_banks failed");
+    let sha256_bank_allocation = pcr_bank_allocations
+        .iter()
+        .find(|pcr_bank_allocation| {
+            pcr_bank_allocation.hashing_algorithm() == HashingAlgorithm::Sha256
+        })
+        .expect("Missing SHA-256 bank");
+    assert!(sha256_bank_allocation.pcr_count() >= 24);
+    assert!(sha256_bank_allocation.is_allocated(PcrSlot::Slot0));
+    assert!(sha256_bank_allocation.is_fully_allocated());
+    assert!(!sha256_bank_allocation.is_empty());
+    assert_eq!(
+        sha256_bank_allocation.allocated().len(),
+        sha256_bank_allocation.pcr_count()
+    );
+}
+
+#[test]
+fn test_pcr_bank_allocation() {
+    use tss_esapi::structures::{PcrSelectSize, PcrSelection};
+
+    let pcr_bank_allocation = pcr::PcrBankAllocation::new(
+        PcrSelection::create(
+            HashingAlgorithm::Sha1,
+            PcrSelectSize::OneOctet,
+            &[PcrSlot::Slot0, PcrSlot::Slot1, PcrSlot::Slot2],
+        )
+        .unwrap(),
+        4,
+    );
+    assert_eq!(
+        pcr_bank_allocation.hashing_algorithm(),
+        HashingAlgorithm::Sha1
+    );
+    assert!(!pcr_bank_allocation.is_fully_allocated());
+    assert!(!pcr_bank_allocation.is_allocated(PcrSlot::Slot3));
+
+    let empty_pcr_bank_allocation = pcr::PcrBankAllocation::new(
+        PcrSelection::create(HashingAlgorithm::Sha1, PcrSelectSize::ThreeOctets, &[]).unwrap(),
+        24,
+    );
+    assert!(empty_pcr_bank_allocation.is_empty());
+    assert!(!empty_pcr_bank_allocation.is_fully_allocated());
+}
@@ -0,0 +1,1240 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::policy,
+    constants::{tss::TPM2_RH_OWNER, CommandCode},
+    structures::Name,
+};
+
+#[test]
+fn test_cp_hash_data() {
+    let owner_name = Name::try_from(TPM2_RH_OWNER.to_be_bytes().to_vec()).unwrap();
+    let data = policy::cp_hash_data(CommandCode::Clear, &[owner_name], &[0xAB]);
+    assert_eq!(
+        data,
+        vec![0x00, 0x00, 0x01, 0x26, 0x40, 0x00, 0x00, 0x01, 0xAB]
+    );
+}
+
+#[test]
+fn test_name_hash_data() {
+    let owner_name = Name::try_from(TPM2_RH_OWNER.to_be_bytes().to_vec()).unwrap();
+    let other_name = Name::try_from(vec![0x00, 0x0B, 0xAB]).unwrap();
+    let data = policy::name_hash_data(&[owner_name, other_name]);
+    assert_eq!(data, vec![0x40, 0x00, 0x00, 0x01, 0x00, 0x0B, 0xAB]);
+}
+
+#[cfg(feature = "openssl")]
+mod owner_policy {
+    use crate::common::{
+        create_ctx_with_session, decryption_key_pub, signing_key_pub, start_policy_session,
+    };
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy,
+        attributes::NvIndexAttributesBuilder,
+        constants::{tss::TPM2_RH_OWNER, CommandCode, SessionType},
+        handles::{AuthHandle, NvIndexHandle, NvIndexTpmHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{Hierarchy, NvAuth, Provision},
+            session_handles::PolicySession,
+        },
+        structures::{Digest, MaxNvBuffer, Name, NvPublicBuilder},
+        Context, Result,
+    };
+
+    fn define_nv_index(context: &mut Context, nv_index: u32) -> (NvIndexHandle, Name) {
+        let nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create nv index attributes");
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(NvIndexTpmHandle::new(nv_index).unwrap())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(nv_index_attributes)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic");
+        let nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, nv_public)
+            .expect("Call to nv_define_space failed");
+        let (_, nv_index_name) = context
+            .nv_read_public(nv_index_handle)
+            .expect("Call to nv_read_public failed");
+        (nv_index_handle, nv_index_name)
+    }
+
+    fn start_owner_policy_session(
+        context: &mut Context,
+        session_type: SessionType,
+        assertion: &dyn Fn(&mut Context, PolicySession) -> Result<()>,
+    ) -> PolicySession {
+        let policy_session = start_policy_session(context, session_type);
+        context
+            .execute_without_session(|ctx| assertion(ctx, policy_session))
+            .expect("Policy assertion failed");
+        policy_session
+    }
+
+    /// Sets the policy of the owner hierarchy to the digest of `assertion`.
+    fn set_owner_policy(
+        context: &mut Context,
+        assertion: &dyn Fn(&mut Context, PolicySession) -> Result<()>,
+    ) {
+        let trial_session = start_owner_policy_session(context, SessionType::Trial, assertion);
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+        context
+            .set_primary_policy(AuthHandle::Owner, policy_digest, HashingAlgorithm::Sha256)
+            .expect("Call to set_primary_policy failed");
+    }
+
+    fn reset_owner_policy(context: &mut Context) {
+        context
+            .set_primary_policy(AuthHandle::Owner, Digest::default(), HashingAlgorithm::Null)
+            .expect("Call to set_primary_policy failed");
+    }
+
+    /// Writes `data` to the index with the owner authorization given by
+    /// a policy session satisfying `assertion`.
+    fn nv_write_with_policy(
+        context: &mut Context,
+        assertion: &dyn Fn(&mut Context, PolicySession) -> Result<()>,
+        nv_index_handle: NvIndexHandle,
+        data: MaxNvBuffer,
+    ) -> Result<()> {
+        let policy_session = start_owner_policy_session(context, SessionType::Policy, assertion);
+        let result = context.execute_with_session(Some(policy_session.into()), |ctx| {
+            ctx.nv_write(NvAuth::Owner, nv_index_handle, data, 0)
+        });
+        let _ = context.flush_context(SessionHandle::from(policy_session).into());
+        result
+    }
+
+    #[test]
+    fn test_cp_hash_with_nv_write() {
+        let mut context = create_ctx_with_session();
+        let (nv_index_handle, nv_index_name) = define_nv_index(&mut context, 0x01500043);
+
+        // The policy of the owner hierarchy only allows one exact NV write.
+        let data = MaxNvBuffer::try_from(vec![1, 2, 3, 4]).unwrap();
+        let mut marshalled_parameters = (data.len() as u16).to_be_bytes().to_vec();
+        marshalled_parameters.extend_from_slice(data.as_bytes());
+        marshalled_parameters.extend_from_slice(&0u16.to_be_bytes());
+        let owner_name = Name::try_from(TPM2_RH_OWNER.to_be_bytes().to_vec()).unwrap();
+        let cp_hash_a = policy::cp_hash(
+            HashingAlgorithm::Sha256,
+            CommandCode::NvWrite,
+            &[owner_name, nv_index_name],
+            &marshalled_parameters,
+        )
+        .expect("Failed to compute cpHash");
+        let assertion = |ctx: &mut Context, policy_session: PolicySession| {
+            ctx.policy_cp_hash(policy_session, cp_hash_a.clone())
+        };
+
+        set_owner_policy(&mut context, &assertion);
+        let other_write_result = nv_write_with_policy(
+            &mut context,
+            &assertion,
+            nv_index_handle,
+            MaxNvBuffer::try_from(vec![4, 3, 2, 1]).unwrap(),
+        );
+        let write_result = nv_write_with_policy(&mut context, &assertion, nv_index_handle, data);
+        reset_owner_policy(&mut context);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        let _ = other_write_result.expect_err("Call to nv_write with other data succeeded");
+        write_result.expect("Call to nv_write failed");
+    }
+
+    #[test]
+    fn test_name_hash_with_nv_write() {
+        let mut context = create_ctx_with_session();
+        let (nv_index_handle, nv_index_name) = define_nv_index(&mut context, 0x01500044);
+        let (other_nv_index_handle, _) = define_nv_index(&mut context, 0x01500045);
+
+        // The policy of the owner hierarchy only allows NV writes to one index.
+        let owner_name = Name::try_from(TPM2_RH_OWNER.to_be_bytes().to_vec()).unwrap();
+        let name_hash = policy::name_hash(HashingAlgorithm::Sha256, &[owner_name, nv_index_name])
+            .expect("Failed to compute nameHash");
+        let assertion = |ctx: &mut Context, policy_session: PolicySession| {
+            ctx.policy_name_hash(policy_session, name_hash.clone())
+        };
+
+        let data = MaxNvBuffer::try_from(vec![1, 2, 3, 4]).unwrap();
+        set_owner_policy(&mut context, &assertion);
+        let other_write_result = nv_write_with_policy(
+            &mut context,
+            &assertion,
+            other_nv_index_handle,
+            data.clone(),
+        );
+        let write_result = nv_write_with_policy(&mut context, &assertion, nv_index_handle, data);
+        reset_owner_policy(&mut context);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+        context
+            .nv_undefine_space(Provision::Owner, other_nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        let _ = other_write_result.expect_err("Call to nv_write to the other index succeeded");
+        write_result.expect("Call to nv_write failed");
+    }
+
+    #[test]
+    fn test_template_hash_with_create_primary() {
+        let mut context = create_ctx_with_session();
+
+        // The policy of the owner hierarchy only allows the creation of
+        // primary keys from one template.
+        let template_hash = policy::template_hash(HashingAlgorithm::Sha256, &signing_key_pub())
+            .expect("Failed to compute the template hash");
+        let assertion = |ctx: &mut Context, policy_session: PolicySession| {
+            ctx.policy_template(policy_session, template_hash.clone())
+        };
+
+        set_owner_policy(&mut context, &assertion);
+        let create_primary_with_policy = |context: &mut Context, public| {
+            let policy_session =
+                start_owner_policy_session(context, SessionType::Policy, &assertion);
+            let result = context.execute_with_session(Some(policy_session.into()), |ctx| {
+                ctx.create_primary(Hierarchy::Owner, public, None, None, None, None)
+            });
+            let _ = context.flush_context(SessionHandle::from(policy_session).into());
+            result.map(|create_primary_key_result| create_primary_key_result.key_handle)
+        };
+        let other_create_primary_result =
+            create_primary_with_policy(&mut context, decryption_key_pub());
+        let create_primary_result = create_primary_with_policy(&mut context, signing_key_pub());
+        reset_owner_policy(&mut context);
+
+        let _ = other_create_primary_result
+            .expect_err("Call to create_primary with another template succeeded");
+        let key_handle = create_primary_result.expect("Call to create_primary failed");
+        context
+            .flush_context(key_handle.into())
+            .expect("Call to flush_context failed");
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod calculator {
+    use crate::common::create_ctx_with_session;
+    use std::{convert::TryFrom, time::Duration};
+    use tss_esapi::{
+        abstraction::policy::Calculator,
+        attributes::LocalityAttributes,
+        constants::{
+            tss::{TPM2_RH_ENDORSEMENT, TPM2_RH_OWNER},
+            ArithmeticOperation, CommandCode, SessionType,
+        },
+        handles::{AuthHandle, SessionHandle},
+        interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+        structures::{
+            CounterTimerConditionBuilder, CounterTimerField, Digest, DigestList, Name, Nonce,
+            PcrSelectionListBuilder, PcrSlot, SymmetricDefinition,
+        },
+        Context, Error, Result, WrapperErrorKind,
+    };
+
+    /// Returns the policy digest of a trial session after `assertion`.
+    fn trial_policy_digest(
+        context: &mut Context,
+        assertion: &dyn Fn(&mut Context, PolicySession) -> Result<()>,
+    ) -> Digest {
+        let trial_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Trial,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let trial_session = PolicySession::try_from(trial_session)
+            .expect("Failed to convert auth session into policy session");
+        assertion(context, trial_session).expect("Policy assertion failed");
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+        policy_digest
+    }
+
+    #[test]
+    fn test_auth_value() {
+        // The well known policy digest of a PolicyAuthValue assertion.
+        let expected = vec![
+            0x8f, 0xcd, 0x21, 0x69, 0xab, 0x92, 0x69, 0x4e, 0x0c, 0x63, 0x3f, 0x1a, 0xb7, 0x72,
+            0x84, 0x2b, 0x82, 0x41, 0xbb, 0xc2, 0x02, 0x88, 0x98, 0x1f, 0xc7, 0xac, 0x1e, 0xdd,
+            0xc1, 0xfd, 0xdb, 0x0e,
+        ];
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_auth_value().unwrap();
+        assert_eq!(calculator.policy_digest().unwrap().as_bytes(), expected);
+
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_password().unwrap();
+        assert_eq!(calculator.policy_digest().unwrap().as_bytes(), expected);
+    }
+
+    #[test]
+    fn test_unsupported_hashing_algorithm() {
+        assert_eq!(
+            Calculator::new(HashingAlgorithm::Null).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::UnsupportedParam)
+        );
+    }
+
+    #[test]
+    fn test_wrong_digest_size() {
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        let digest = Digest::try_from(vec![0xAB; 20]).unwrap();
+        assert_eq!(
+            calculator.policy_cp_hash(&digest).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::WrongParamSize)
+        );
+        let mut digest_list = DigestList::new();
+        digest_list.add(digest).unwrap();
+        assert_eq!(
+            calculator.policy_or(&digest_list).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+
+    #[test]
+    fn test_with_policy_digest() {
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_auth_value().unwrap();
+        let mut other_calculator = Calculator::with_policy_digest(
+            HashingAlgorithm::Sha256,
+            &calculator.policy_digest().unwrap(),
+        )
+        .unwrap();
+        calculator.policy_command_code(CommandCode::Unseal).unwrap();
+        other_calculator
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap();
+        assert_eq!(calculator, other_calculator);
+
+        assert_eq!(
+            Calculator::with_policy_digest(
+                HashingAlgorithm::Sha256,
+                &Digest::try_from(vec![0xAB; 20]).unwrap()
+            )
+            .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::WrongParamSize)
+        );
+    }
+
+    #[test]
+    fn test_policy_authorize_resets_digest() {
+        let key_sign = Name::try_from(vec![0x00, 0x0B, 0xAB]).unwrap();
+        let policy_ref = Nonce::try_from(vec![1, 2, 3]).unwrap();
+
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_auth_value()
+            .unwrap()
+            .policy_authorize(&key_sign, &policy_ref)
+            .unwrap();
+        let mut other_calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        other_calculator
+            .policy_authorize(&key_sign, &policy_ref)
+            .unwrap();
+        assert_eq!(calculator, other_calculator);
+    }
+
+    #[test]
+    fn test_matches_trial_session() {
+        let mut context = create_ctx_with_session();
+        let cp_hash_a = Digest::try_from(vec![0xAB; 32]).unwrap();
+        let pcr_policy_digest = Digest::try_from(vec![0xCD; 32]).unwrap();
+        let pcr_selection_list = PcrSelectionListBuilder::new()
+            .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot0, PcrSlot::Slot1])
+            .build()
+            .expect("Failed to create PcrSelectionList");
+        let condition = CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::ResetCount)
+            .with_operation(ArithmeticOperation::UnsignedGe)
+            .with_value(1)
+            .build()
+            .expect("Failed to build CounterTimerCondition");
+
+        let trial_digest = trial_policy_digest(
+            &mut context,
+            &|ctx: &mut Context, policy_session: PolicySession| {
+                ctx.execute_without_session(|ctx| {
+                    ctx.policy_command_code(policy_session, CommandCode::Unseal)?;
+                    ctx.policy_auth_value(policy_session)?;
+                    ctx.policy_locality(policy_session, LocalityAttributes::LOCALITY_THREE)?;
+                    ctx.policy_physical_presence(policy_session)?;
+                    ctx.policy_nv_written(policy_session, true)?;
+                    ctx.policy_counter_timer(policy_session, condition.clone())?;
+                    ctx.policy_pcr(
+                        policy_session,
+                        pcr_policy_digest.clone(),
+                        pcr_selection_list.clone(),
+                    )?;
+                    ctx.policy_cp_hash(policy_session, cp_hash_a.clone())
+                })
+            },
+        );
+
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap()
+            .policy_auth_value()
+            .unwrap()
+            .policy_locality(LocalityAttributes::LOCALITY_THREE)
+            .unwrap()
+            .policy_physical_presence()
+            .unwrap()
+            .policy_nv_written(true)
+            .unwrap()
+            .policy_counter_timer(&condition)
+            .unwrap()
+            .policy_pcr(&pcr_policy_digest, &pcr_selection_list)
+            .unwrap()
+            .policy_cp_hash(&cp_hash_a)
+            .unwrap();
+        assert_eq!(calculator.policy_digest().unwrap(), trial_digest);
+    }
+
+    #[test]
+    fn test_matches_trial_session_with_names() {
+        let mut context = create_ctx_with_session();
+        let owner_name = Name::try_from(TPM2_RH_OWNER.to_be_bytes().to_vec()).unwrap();
+        let endorsement_name = Name::try_from(TPM2_RH_ENDORSEMENT.to_be_bytes().to_vec()).unwrap();
+        let policy_ref = Nonce::try_from(vec![1, 2, 3]).unwrap();
+
+        let trial_digest = trial_policy_digest(
+            &mut context,
+            &|ctx: &mut Context, policy_session: PolicySession| {
+                let _ = ctx.policy_secret(
+                    policy_session,
+                    AuthHandle::Endorsement,
+                    Nonce::default(),
+                    Digest::default(),
+                    policy_ref.clone(),
+                    Some(Duration::from_secs(3600)),
+                )?;
+                ctx.execute_without_session(|ctx| {
+                    ctx.policy_duplication_select(
+                        policy_session,
+                        owner_name.clone(),
+                        endorsement_name.clone(),
+                        false,
+                    )
+                })
+            },
+        );
+
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_secret(&endorsement_name, &policy_ref)
+            .unwrap()
+            .policy_duplication_select(&owner_name, &endorsement_name, false)
+            .unwrap();
+        assert_eq!(calculator.policy_digest().unwrap(), trial_digest);
+    }
+
+    #[test]
+    fn test_matches_trial_session_with_or() {
+        let mut context = create_ctx_with_session();
+        let mut branch_a = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        branch_a.policy_auth_value().unwrap();
+        let mut branch_b = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        branch_b.policy_command_code(CommandCode::NvRead).unwrap();
+        let mut digest_list = DigestList::new();
+        digest_list.add(branch_a.policy_digest().unwrap()).unwrap();
+        digest_list.add(branch_b.policy_digest().unwrap()).unwrap();
+
+        let trial_digest = trial_policy_digest(
+            &mut context,
+            &|ctx: &mut Context, policy_session: PolicySession| {
+                ctx.execute_without_session(|ctx| {
+                    ctx.policy_auth_value(policy_session)?;
+                    ctx.policy_or(policy_session, digest_list.clone())
+                })
+            },
+        );
+
+        branch_a.policy_or(&digest_list).unwrap();
+        assert_eq!(branch_a.policy_digest().unwrap(), trial_digest);
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod builder {
+    use crate::common::{create_ctx_with_session, start_policy_session};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy::{Calculator, Policy, PolicyAssertion},
+        constants::{CommandCode, SessionType},
+        handles::SessionHandle,
+        interface_types::algorithm::HashingAlgorithm,
+        structures::{Digest, DigestList, PcrSelectionListBuilder, PcrSlot},
+        Error, WrapperErrorKind,
+    };
+
+    /// A policy satisfied either by PCR 16 having an unexpected value, or by
+    /// the authorization value, and that only allows unsealing.
+    fn pcr_or_auth_value_policy() -> Policy {
+        let pcr_selection_list = PcrSelectionListBuilder::new()
+            .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+            .build()
+            .expect("Failed to create PcrSelectionList");
+        let pcr_policy_digest = Digest::try_from(vec![0xAB; 32]).unwrap();
+        Policy::new(HashingAlgorithm::Sha256)
+            .pcr(pcr_selection_list, pcr_policy_digest)
+            .or(Policy::new(HashingAlgorithm::Sha256).auth_value())
+            .command_code(CommandCode::Unseal)
+    }
+
+    #[test]
+    fn test_or_branches() {
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .auth_value()
+            .or(Policy::new(HashingAlgorithm::Sha256).password())
+            .or(Policy::new(HashingAlgorithm::Sha256).physical_presence());
+        match policy.assertions() {
+            [PolicyAssertion::Or(branches)] => assert_eq!(branches.len(), 3),
+            assertions => panic!("Unexpected assertions {:?}", assertions),
+        }
+    }
+
+    #[test]
+    fn test_digest() {
+        let mut branch_a = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        branch_a.policy_auth_value().unwrap();
+        let mut branch_b = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        branch_b.policy_physical_presence().unwrap();
+        let mut digest_list = DigestList::new();
+        digest_list.add(branch_a.policy_digest().unwrap()).unwrap();
+        digest_list.add(branch_b.policy_digest().unwrap()).unwrap();
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_or(&digest_list)
+            .unwrap()
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap();
+
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .auth_value()
+            .or(Policy::new(HashingAlgorithm::Sha256).physical_presence())
+            .command_code(CommandCode::Unseal);
+        assert_eq!(
+            policy.digest().unwrap(),
+            calculator.policy_digest().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_digest_with_inconsistent_branches() {
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .auth_value()
+            .or(Policy::new(HashingAlgorithm::Sha1).auth_value());
+        assert_eq!(
+            policy.digest().unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InconsistentParams)
+        );
+    }
+
+    #[test]
+    fn test_execute_trial_session() {
+        let mut context = create_ctx_with_session();
+        let policy = pcr_or_auth_value_policy();
+
+        let trial_session = start_policy_session(&mut context, SessionType::Trial);
+        policy
+            .execute(&mut context, trial_session)
+            .expect("Failed to execute the policy");
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+
+        assert_eq!(policy_digest, policy.digest().unwrap());
+    }
+
+    #[test]
+    fn test_execute_policy_session() {
+        let mut context = create_ctx_with_session();
+        let policy = pcr_or_auth_value_policy();
+
+        // The PCR branch fails, so the policy is satisfied by the other branch.
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+        let result = policy.execute(&mut context, policy_session);
+        let policy_digest =
+            context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session));
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+
+        result.expect("Failed to execute the policy");
+        assert_eq!(policy_digest.unwrap(), policy.digest().unwrap());
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod or_tree {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy::{Calculator, Policy, PolicyOrTree},
+        constants::{CommandCode, SessionType},
+        handles::SessionHandle,
+        interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+        structures::{Digest, DigestList, SymmetricDefinition},
+        Context, Error, WrapperErrorKind,
+    };
+
+    const COMMAND_CODES: [CommandCode; 10] = [
+        CommandCode::Unseal,
+        CommandCode::Sign,
+        CommandCode::NvRead,
+        CommandCode::NvWrite,
+        CommandCode::Certify,
+        CommandCode::Quote,
+        CommandCode::Duplicate,
+        CommandCode::ObjectChangeAuth,
+        CommandCode::RsaDecrypt,
+        CommandCode::EccParameters,
+    ];
+
+    /// Returns one leaf policy for each command code.
+    fn leaves() -> Vec<Policy> {
+        COMMAND_CODES
+            .iter()
+            .map(|&command_code| Policy::new(HashingAlgorithm::Sha256).command_code(command_code))
+            .collect()
+    }
+
+    fn or_digest(digests: &[Digest]) -> Digest {
+        let mut digest_list = DigestList::new();
+        for digest in digests {
+            digest_list.add(digest.clone()).unwrap();
+        }
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_or(&digest_list).unwrap();
+        calculator.policy_digest().unwrap()
+    }
+
+    fn execute(context: &mut Context, session_type: SessionType, leaf_index: usize) -> Digest {
+        let policy_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    session_type,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+        let result = PolicyOrTree::new(HashingAlgorithm::Sha256, leaves())
+            .unwrap()
+            .execute(context, policy_session, leaf_index);
+        let policy_digest =
+            context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session));
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        result.expect("Failed to execute the policy tree");
+        policy_digest.expect("Call to policy_get_digest failed")
+    }
+
+    #[test]
+    fn test_digest() {
+        let leaf_digests: Vec<Digest> =
+            leaves().iter().map(|leaf| leaf.digest().unwrap()).collect();
+        let expected = or_digest(&[or_digest(&leaf_digests[..8]), or_digest(&leaf_digests[8..])]);
+
+        let tree = PolicyOrTree::new(HashingAlgorithm::Sha256, leaves()).unwrap();
+        assert_eq!(tree.digest(), expected);
+        assert_eq!(tree.leaves().len(), COMMAND_CODES.len());
+
+        // A single leaf is the root of the tree.
+        let tree = PolicyOrTree::new(HashingAlgorithm::Sha256, leaves()[..1].to_vec()).unwrap();
+        assert_eq!(tree.digest(), leaf_digests[0]);
+    }
+
+    #[test]
+    fn test_invalid_leaves() {
+        assert_eq!(
+            PolicyOrTree::new(HashingAlgorithm::Sha256, Vec::new()).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+        let mut leaves = leaves();
+        leaves.push(Policy::new(HashingAlgorithm::Sha1).auth_value());
+        assert_eq!(
+            PolicyOrTree::new(HashingAlgorithm::Sha256, leaves).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InconsistentParams)
+        );
+    }
+
+    #[test]
+    fn test_execute() {
+        let mut context = create_ctx_with_session();
+        let root_digest = PolicyOrTree::new(HashingAlgorithm::Sha256, leaves())
+            .unwrap()
+            .digest();
+
+        assert_eq!(execute(&mut context, SessionType::Trial, 0), root_digest);
+        assert_eq!(execute(&mut context, SessionType::Trial, 9), root_digest);
+        assert_eq!(execute(&mut context, SessionType::Policy, 3), root_digest);
+        assert_eq!(execute(&mut context, SessionType::Policy, 8), root_digest);
+    }
+
+    #[test]
+    fn test_execute_invalid_leaf() {
+        let mut context = create_ctx_with_session();
+        let tree = PolicyOrTree::new(HashingAlgorithm::Sha256, leaves()).unwrap();
+        let policy_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Trial,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+        let result = tree.execute(&mut context, policy_session, COMMAND_CODES.len());
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        assert_eq!(
+            result.unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod authorize {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy::{self, Calculator, Policy},
+        constants::{CommandCode, SessionType},
+        handles::{KeyHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm, resource_handles::Hierarchy,
+            session_handles::PolicySession,
+        },
+        structures::{Digest, MaxBuffer, Nonce, Signature, SymmetricDefinition},
+        Context, Result,
+    };
+
+    fn create_signing_key(context: &mut Context) -> KeyHandle {
+        context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .expect("Failed to create signing key")
+            .key_handle
+    }
+
+    /// Executes `policy` in a new policy session, authorizes it with
+    /// `signature` and returns the resulting policy digest.
+    fn execute_authorized_policy(
+        context: &mut Context,
+        policy: &Policy,
+        key_handle: KeyHandle,
+        policy_ref: Nonce,
+        signature: Signature,
+    ) -> Result<Digest> {
+        let policy_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Policy,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+        let result = policy
+            .execute(context, policy_session)
+            .and_then(|_| {
+                policy::authorize(context, policy_session, key_handle, policy_ref, signature)
+            })
+            .and_then(|_| {
+                context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+            });
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        result
+    }
+
+    #[test]
+    fn test_approved_policy_hash() {
+        let mut context = create_ctx_with_session();
+        let approved_policy = Policy::new(HashingAlgorithm::Sha256)
+            .command_code(CommandCode::Unseal)
+            .digest()
+            .unwrap();
+        let policy_ref = Nonce::try_from(vec![1, 2, 3, 4]).unwrap();
+
+        let (expected, _) = context
+            .execute_without_session(|ctx| {
+                ctx.hash(
+                    MaxBuffer::from_bytes(&policy::approved_policy_data(
+                        &approved_policy,
+                        &policy_ref,
+                    ))
+                    .unwrap(),
+                    HashingAlgorithm::Sha256,
+                    Hierarchy::Null,
+                )
+            })
+            .expect("Call to hash failed");
+        assert_eq!(
+            policy::approved_policy_hash(HashingAlgorithm::Sha256, &approved_policy, &policy_ref)
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_authorize() {
+        let mut context = create_ctx_with_session();
+        let key_handle = create_signing_key(&mut context);
+        let (_, key_name, _) = context
+            .execute_without_session(|ctx| ctx.read_public(key_handle))
+            .expect("Call to read_public failed");
+        let policy_ref = Nonce::try_from(vec![1, 2, 3, 4]).unwrap();
+
+        // The authorization policy of the objects only depends on the key.
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_authorize(&key_name, &policy_ref).unwrap();
+        let authorized_policy = calculator.policy_digest().unwrap();
+
+        let approved_policy =
+            Policy::new(HashingAlgorithm::Sha256).command_code(CommandCode::Unseal);
+        let signature = policy::sign_approved_policy(
+            &mut context,
+            key_handle,
+            &approved_policy.digest().unwrap(),
+            &policy_ref,
+        )
+        .expect("Failed to sign the approved policy");
+
+        let other_policy = Policy::new(HashingAlgorithm::Sha256).command_code(CommandCode::Sign);
+        let other_result = execute_authorized_policy(
+            &mut context,
+            &other_policy,
+            key_handle,
+            policy_ref.clone(),
+            signature.clone(),
+        );
+        let result = execute_authorized_policy(
+            &mut context,
+            &approved_policy,
+            key_handle,
+            policy_ref,
+            signature,
+        );
+        context
+            .flush_context(key_handle.into())
+            .expect("Call to flush_context failed");
+
+        let _ = other_result.expect_err("Authorized a policy that was not approved");
+        assert_eq!(
+            result.expect("Failed to authorize the approved policy"),
+            authorized_policy
+        );
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod replay {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::{convert::TryFrom, time::Duration};
+    use tss_esapi::{
+        abstraction::policy::{self, Calculator, Policy, PolicyResolver, SignedAuthorization},
+        constants::{tss::TPM2_RH_ENDORSEMENT, CommandCode},
+        handles::{KeyHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{Hierarchy, HierarchyAuth},
+        },
+        structures::{
+            Digest, MaxBuffer, Name, Nonce, PolicyExpiration, Signature, SignatureScheme,
+        },
+        Context, Error, Result, WrapperErrorKind,
+    };
+
+    /// A resolver signing with a key loaded in the TPM.
+    struct KeyResolver {
+        key_handle: KeyHandle,
+        approved_policy: Policy,
+    }
+
+    impl PolicyResolver for KeyResolver {
+        fn sign_authorization(
+            &mut self,
+            context: &mut Context,
+            _auth_name: &Name,
+            nonce_tpm: &Nonce,
+            policy_ref: &Nonce,
+        ) -> Result<SignedAuthorization> {
+            let expiration = PolicyExpiration::After(Duration::from_secs(60));
+            let cp_hash_a = Digest::default();
+            let authorization_data =
+                policy::signed_authorization_data(nonce_tpm, expiration, &cp_hash_a, policy_ref)?;
+            let (digest, ticket) = context.execute_without_session(|ctx| {
+                ctx.hash(
+                    MaxBuffer::from_bytes(&authorization_data)?,
+                    HashingAlgorithm::Sha256,
+                    Hierarchy::Owner,
+                )
+            })?;
+            let signature = context.sign(self.key_handle, digest, SignatureScheme::Null, ticket)?;
+            Ok(SignedAuthorization {
+                key_handle: self.key_handle,
+                expiration,
+                cp_hash_a,
+                signature,
+            })
+        }
+
+        fn approved_policy(
+            &mut self,
+            context: &mut Context,
+            _key_sign: &Name,
+            policy_ref: &Nonce,
+        ) -> Result<(Policy, KeyHandle, Signature)> {
+            let signature = policy::sign_approved_policy(
+                context,
+                self.key_handle,
+                &self.approved_policy.digest()?,
+                policy_ref,
+            )?;
+            Ok((self.approved_policy.clone(), self.key_handle, signature))
+        }
+    }
+
+    /// A resolver without any reference.
+    struct EmptyResolver;
+
+    impl PolicyResolver for EmptyResolver {}
+
+    /// Replays `policy` with a [KeyResolver] and checks that the digest
+    /// of the session matches the digest of the policy.
+    fn replay_with_key(policy: &Policy) {
+        let mut context = create_ctx_with_session();
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .expect("Failed to create signing key")
+            .key_handle;
+        let mut resolver = KeyResolver {
+            key_handle,
+            approved_policy: Policy::new(HashingAlgorithm::Sha256)
+                .command_code(CommandCode::Unseal),
+        };
+
+        let result =
+            policy::replay(&mut context, policy, &mut resolver).and_then(|policy_session| {
+                let result =
+                    context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session));
+                context.flush_context(SessionHandle::from(policy_session).into())?;
+                result
+            });
+        context
+            .flush_context(key_handle.into())
+            .expect("Call to flush_context failed");
+
+        assert_eq!(
+            result.expect("Failed to replay the policy"),
+            policy.digest().unwrap()
+        );
+    }
+
+    fn key_name() -> Name {
+        let mut context = create_ctx_with_session();
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .expect("Failed to create signing key")
+            .key_handle;
+        let (_, key_name, _) = context
+            .execute_without_session(|ctx| ctx.read_public(key_handle))
+            .expect("Call to read_public failed");
+        context
+            .flush_context(key_handle.into())
+            .expect("Call to flush_context failed");
+        key_name
+    }
+
+    #[test]
+    fn test_replay_signed() {
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .signed(key_name(), Nonce::try_from(vec![1, 2, 3, 4]).unwrap())
+            .command_code(CommandCode::Unseal);
+        replay_with_key(&policy);
+    }
+
+    #[test]
+    fn test_replay_authorize() {
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .authorize(key_name(), Nonce::try_from(vec![1, 2, 3, 4]).unwrap());
+        replay_with_key(&policy);
+    }
+
+    #[test]
+    fn test_replay_secret_hierarchy() {
+        let policy_ref = Nonce::try_from(vec![1, 2, 3, 4]).unwrap();
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .secret_hierarchy(HierarchyAuth::Endorsement, policy_ref.clone())
+            .command_code(CommandCode::Unseal);
+
+        let endorsement_name = Name::try_from(TPM2_RH_ENDORSEMENT.to_be_bytes().to_vec()).unwrap();
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_secret(&endorsement_name, &policy_ref)
+            .unwrap()
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap();
+        assert_eq!(
+            policy.digest().unwrap(),
+            calculator.policy_digest().unwrap()
+        );
+
+        // The hierarchy is resolved without the resolver.
+        let mut context = create_ctx_with_session();
+        let policy_session = policy::replay(&mut context, &policy, &mut EmptyResolver)
+            .expect("Failed to replay the policy");
+        let digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        assert_eq!(digest, policy.digest().unwrap());
+    }
+
+    #[test]
+    fn test_replay_unresolved_reference() {
+        let mut context = create_ctx_with_session();
+        let policy = Policy::new(HashingAlgorithm::Sha256)
+            .signed(key_name(), Nonce::default())
+            .command_code(CommandCode::Unseal);
+
+        let result = policy::replay(&mut context, &policy, &mut EmptyResolver);
+        assert!(matches!(
+            result,
+            Err(Error::WrapperError(WrapperErrorKind::UnsupportedParam))
+        ));
+    }
+}
+
+#[cfg(feature = "openssl")]
+mod seal_to_current_pcrs {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy,
+        constants::SessionType,
+        handles::SessionHandle,
+        interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
+        structures::{PcrSelectionList, PcrSelectionListBuilder, PcrSlot, SymmetricDefinition},
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_seal_to_current_pcrs() {
+        let mut context = create_ctx_with_session();
+        let pcr_selection_list = PcrSelectionListBuilder::new()
+            .with_selection(
+                HashingAlgorithm::Sha256,
+                &[PcrSlot::Slot0, PcrSlot::Slot7, PcrSlot::Slot16],
+            )
+            .build()
+            .unwrap();
+        let (policy_digest, policy) =
+            policy::seal_to_current_pcrs(&mut context, pcr_selection_list)
+                .expect("Failed to compute the policy");
+        assert_eq!(policy.hashing_algorithm(), HashingAlgorithm::Sha256);
+
+        // The policy is satisfied while the PCR keep their values.
+        let policy_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Policy,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+        let result = policy.execute(&mut context, policy_session).and_then(|_| {
+            context.execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+        });
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        assert_eq!(result.expect("Failed to execute the policy"), policy_digest);
+    }
+
+    #[test]
+    fn test_seal_to_current_pcrs_with_empty_selection() {
+        let mut context = create_ctx_with_session();
+        let result = policy::seal_to_current_pcrs(&mut context, PcrSelectionList::default());
+        assert!(matches!(
+            result,
+            Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+        ));
+    }
+}
+
+mod secret_ticket {
+    use crate::common::{create_ctx_with_session, start_policy_session};
+    use std::{convert::TryFrom, time::Duration};
+    use tss_esapi::{
+        abstraction::policy::{self, AuthorizationTicket},
+        constants::SessionType,
+        handles::{AuthHandle, SessionHandle},
+        structures::{Digest, Nonce, PolicyExpiration},
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_secret_ticket_without_nonce() {
+        let mut context = create_ctx_with_session();
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+
+        let result = policy::secret_ticket(
+            &mut context,
+            policy_session,
+            AuthHandle::Endorsement,
+            Nonce::default(),
+            Digest::default(),
+            Nonce::default(),
+            Duration::from_secs(60),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+        ));
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+    }
+
+    #[test]
+    fn test_secret_ticket() {
+        let mut context = create_ctx_with_session();
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+        let nonce_tpm = context
+            .session_nonce_tpm(policy_session.into())
+            .expect("Call to session_nonce_tpm failed");
+
+        let ticket = policy::secret_ticket(
+            &mut context,
+            policy_session,
+            AuthHandle::Endorsement,
+            nonce_tpm,
+            Digest::default(),
+            Nonce::try_from(vec![1, 2, 3, 4]).unwrap(),
+            Duration::from_secs(60),
+        )
+        .expect("Failed to get the authorization ticket");
+        let expected_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+
+        // The ticket gives the same authorization in another session.
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+        ticket
+            .execute(&mut context, policy_session)
+            .expect("Failed to execute the authorization ticket");
+        let digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        assert_eq!(digest, expected_digest);
+    }
+
+    #[test]
+    fn test_authorization_ticket_with_null_ticket() {
+        let mut context = create_ctx_with_session();
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+
+        // No ticket was requested, so a NULL ticket is returned.
+        let (timeout, ticket) = context
+            .policy_secret_with_expiration(
+                policy_session,
+                AuthHandle::Endorsement,
+                Nonce::default(),
+                Digest::default(),
+                Nonce::default(),
+                PolicyExpiration::Never,
+            )
+            .expect("Call to policy_secret failed");
+        let endorsement_name = context
+            .tr_get_name(AuthHandle::Endorsement.into())
+            .expect("Call to tr_get_name failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+
+        let result = AuthorizationTicket::new(
+            timeout,
+            ticket,
+            Digest::default(),
+            Nonce::default(),
+            endorsement_name,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    abstraction::primary::PrimaryKeyRecord, interface_types::resource_handles::Hierarchy, Error,
+    WrapperErrorKind,
+};
+
+use crate::common::{create_ctx_with_session, decryption_key_pub};
+
+#[test]
+fn test_recreate_primary() {
+    let mut context = create_ctx_with_session();
+    let (key_handle, record) =
+        PrimaryKeyRecord::create(&mut context, Hierarchy::Owner, decryption_key_pub())
+            .expect("Failed to create the primary key");
+    let expected_name = context
+        .tr_get_name(key_handle.into())
+        .expect("Failed to get the name of the primary key");
+    context
+        .flush_context(key_handle.into())
+        .expect("Failed to flush the primary key");
+    assert_eq!(record.hierarchy().unwrap(), Hierarchy::Owner);
+    assert_eq!(record.name().unwrap(), expected_name);
+
+    let key_handle = record
+        .recreate(&mut context)
+        .expect("Failed to recreate the primary key");
+    assert_eq!(
+        context
+            .tr_get_name(key_handle.into())
+            .expect("Failed to get the name of the primary key"),
+        expected_name
+    );
+}
+
+#[test]
+fn test_create_null_hierarchy_primary() {
+    let mut context = create_ctx_with_session();
+    assert_eq!(
+        PrimaryKeyRecord::create(&mut context, Hierarchy::Null, decryption_key_pub()).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
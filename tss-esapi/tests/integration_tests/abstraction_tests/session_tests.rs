@@ -0,0 +1,365 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::session::{
+        SessionBinding, SessionBuilder, SessionGuard, SessionPool, SessionSwapper,
+    },
+    constants::SessionType,
+    handles::{AuthHandle, ObjectHandle, SessionHandle},
+    interface_types::{
+        algorithm::{AsymmetricAlgorithm, HashingAlgorithm},
+        resource_handles::Hierarchy,
+        session_handles::AuthSession,
+    },
+    structures::{Auth, Nonce, SymmetricDefinition},
+    Context, Error, WrapperErrorKind,
+};
+
+use crate::common::{create_ctx_without_session, decryption_key_pub};
+
+fn start_scoped_session(context: &mut Context) -> SessionGuard<'_> {
+    context
+        .start_scoped_auth_session(
+            None,
+            None,
+            None,
+            SessionType::Hmac,
+            SymmetricDefinition::AES_128_CFB,
+            HashingAlgorithm::Sha256,
+        )
+        .expect("Failed to start the session")
+}
+
+#[test]
+fn test_session_flushed_on_drop() {
+    let mut context = create_ctx_without_session();
+    let mut session_guard = start_scoped_session(&mut context);
+    let session = *session_guard;
+    let _ = session_guard
+        .execute(|ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    let sessions = session_guard.context().sessions();
+    session_guard
+        .context()
+        .set_sessions((Some(session), sessions.1, sessions.2));
+    drop(session_guard);
+
+    // The session is no longer known by the context.
+    assert_eq!(context.sessions(), (None, None, None));
+    assert!(context.tr_sess_get_attributes(session).is_err());
+}
+
+#[test]
+fn test_session_released() {
+    let mut context = create_ctx_without_session();
+    let session = start_scoped_session(&mut context).release();
+
+    // The session can still be used after the guard has been released.
+    let _ = context
+        .execute_with_session(Some(session), |ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    context
+        .flush_context(SessionHandle::from(session).into())
+        .expect("Call to flush_context failed");
+}
+
+#[test]
+fn test_salted_session() {
+    let mut context = create_ctx_without_session();
+    let mut session_guard = SessionBuilder::new()
+        .salted_with_ek(AsymmetricAlgorithm::Rsa)
+        .build_scoped(&mut context)
+        .expect("Failed to start the salted session");
+    let session = *session_guard;
+
+    let attributes = session_guard
+        .context()
+        .tr_sess_get_attributes(session)
+        .expect("Call to tr_sess_get_attributes failed");
+    assert!(attributes.continue_session());
+    assert!(attributes.decrypt());
+    assert!(attributes.encrypt());
+
+    let random_bytes = session_guard
+        .execute(|ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    assert_eq!(random_bytes.len(), 16);
+}
+
+#[test]
+fn test_bound_session() {
+    let mut context = create_ctx_without_session();
+    let session = SessionBuilder::new()
+        .bound_to(ObjectHandle::Owner)
+        .build(&mut context)
+        .expect("Failed to start the bound session");
+
+    let _ = context
+        .execute_with_session(Some(session), |ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    context
+        .flush_context(SessionHandle::from(session).into())
+        .expect("Call to flush_context failed");
+}
+
+#[test]
+fn test_hierarchy_bound_session() {
+    let mut context = create_ctx_without_session();
+    // With empty authorization values, the HMACs of the bound and unbound
+    // usages of the session are the same.
+    let owner_auth = Auth::try_from(vec![0x33; 16]).expect("Failed to create the owner auth");
+    context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            ctx.hierarchy_change_auth(AuthHandle::Owner, owner_auth.clone())
+        })
+        .expect("Failed to change the auth of the owner hierarchy");
+
+    let result = SessionBuilder::new()
+        .bound_to_hierarchy(Hierarchy::Owner)
+        .with_bind_auth(owner_auth)
+        .build(&mut context)
+        .and_then(|session| {
+            // The session authorizes the entity it is bound to.
+            let key_auth = Auth::try_from(vec![0x44; 16]).expect("Failed to create the key auth");
+            let key_handle = context
+                .execute_with_session(Some(session), |ctx| {
+                    ctx.create_primary(
+                        Hierarchy::Owner,
+                        decryption_key_pub(),
+                        Some(key_auth),
+                        None,
+                        None,
+                        None,
+                    )
+                })?
+                .key_handle;
+
+            // The session authorizes an entity it is not bound to.
+            let new_key_auth =
+                Auth::try_from(vec![0x55; 16]).expect("Failed to create the new key auth");
+            let result = context.execute_with_session(Some(session), |ctx| {
+                ctx.object_change_auth(key_handle.into(), ObjectHandle::Owner, new_key_auth)
+            });
+
+            context.flush_context(key_handle.into())?;
+            context.flush_context(SessionHandle::from(session).into())?;
+            result.map(|_| ())
+        });
+
+    context
+        .execute_with_session(Some(AuthSession::Password), |ctx| {
+            ctx.hierarchy_change_auth(AuthHandle::Owner, Auth::default())
+        })
+        .expect("Failed to restore the auth of the owner hierarchy");
+    result.expect("Failed to use the bound session");
+}
+
+#[test]
+fn test_bind_auth_without_binding() {
+    let mut context = create_ctx_without_session();
+    assert!(matches!(
+        SessionBuilder::new()
+            .with_binding(SessionBinding::None)
+            .with_bind_auth(Auth::default())
+            .build(&mut context),
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams))
+    ));
+}
+
+#[test]
+fn test_parameter_encryption_without_symmetric() {
+    let mut context = create_ctx_without_session();
+    let result = SessionBuilder::new()
+        .with_symmetric(SymmetricDefinition::Null)
+        .build(&mut context);
+    assert!(matches!(
+        result,
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams))
+    ));
+}
+
+#[test]
+fn test_session_pool_checkout() {
+    let mut context = create_ctx_without_session();
+    let pool = SessionPool::new(&mut context, SessionBuilder::new(), 2)
+        .expect("Failed to create the session pool");
+    assert_eq!(pool.size(), 2);
+
+    let mut first = pool.checkout();
+    let second = pool.try_checkout().expect("The pool has no session left");
+    assert!(pool.try_checkout().is_none());
+
+    let random_bytes = first
+        .execute(&mut context, |ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    assert_eq!(random_bytes.len(), 16);
+
+    drop(second);
+    assert_eq!(pool.available(), 1);
+    drop(first);
+    assert_eq!(pool.available(), 2);
+    pool.close(&mut context)
+        .expect("Failed to close the session pool");
+}
+
+#[test]
+fn test_session_pool_invalidated_session_is_replaced() {
+    let mut context = create_ctx_without_session();
+    let pool = SessionPool::new(&mut context, SessionBuilder::new(), 1)
+        .expect("Failed to create the session pool");
+
+    let mut pooled_session = pool.checkout();
+    let session = pooled_session
+        .session(&mut context)
+        .expect("Failed to get the pooled session");
+    pooled_session.invalidate(&mut context);
+    assert!(context.tr_sess_get_attributes(session).is_err());
+    drop(pooled_session);
+
+    let mut pooled_session = pool.checkout();
+    let _ = pooled_session
+        .execute(&mut context, |ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    drop(pooled_session);
+    pool.close(&mut context)
+        .expect("Failed to close the session pool");
+}
+
+#[test]
+fn test_session_pool_keeps_session_on_authorization_failure() {
+    let mut context = create_ctx_without_session();
+    let pool = SessionPool::new(&mut context, SessionBuilder::new(), 1)
+        .expect("Failed to create the session pool");
+
+    let mut pooled_session = pool.checkout();
+    let session = pooled_session
+        .session(&mut context)
+        .expect("Failed to get the pooled session");
+    // A wrong authorization value does not make the session invalid.
+    context
+        .tr_set_auth(
+            ObjectHandle::from(Hierarchy::Owner),
+            Auth::try_from(vec![1, 2, 3]).unwrap(),
+        )
+        .expect("Call to tr_set_auth failed");
+    let result = pooled_session.execute(&mut context, |ctx| {
+        ctx.create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        )
+    });
+    context
+        .tr_set_auth(ObjectHandle::from(Hierarchy::Owner), Auth::default())
+        .expect("Call to tr_set_auth failed");
+    assert!(result.is_err());
+    assert_eq!(
+        pooled_session
+            .session(&mut context)
+            .expect("Failed to get the pooled session"),
+        session
+    );
+    assert!(context.tr_sess_get_attributes(session).is_ok());
+    drop(pooled_session);
+    pool.close(&mut context)
+        .expect("Failed to close the session pool");
+}
+
+#[test]
+fn test_session_pool_empty() {
+    let mut context = create_ctx_without_session();
+    assert!(matches!(
+        SessionPool::new(&mut context, SessionBuilder::new(), 0),
+        Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+    ));
+}
+
+#[test]
+fn test_session_swapper() {
+    let mut context = create_ctx_without_session();
+    let mut swapper = SessionSwapper::with_max_loaded(1).expect("Failed to create the swapper");
+    let ids = (0..3)
+        .map(|_| {
+            let session = SessionBuilder::new()
+                .build(&mut context)
+                .expect("Failed to start the session");
+            swapper
+                .insert(&mut context, session)
+                .expect("Failed to insert the session")
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(swapper.len(), 3);
+    assert_eq!(swapper.loaded(), 1);
+
+    for id in ids.iter().chain(ids.iter()) {
+        let random_bytes = swapper
+            .execute(&mut context, *id, |ctx| ctx.get_random(16))
+            .expect("Call to get_random failed");
+        assert_eq!(random_bytes.len(), 16);
+        assert_eq!(swapper.loaded(), 1);
+    }
+
+    let session = swapper
+        .remove(&mut context, ids[0])
+        .expect("Failed to remove the session");
+    context
+        .flush_context(SessionHandle::from(session).into())
+        .expect("Call to flush_context failed");
+    assert_eq!(swapper.len(), 2);
+    swapper
+        .close(&mut context)
+        .expect("Failed to close the swapper");
+}
+
+#[test]
+fn test_session_swapper_from_tpm_properties() {
+    let mut context = create_ctx_without_session();
+    let swapper = SessionSwapper::new(&mut context).expect("Failed to create the swapper");
+    assert!(swapper.max_loaded() > 0);
+    assert!(swapper.is_empty());
+}
+
+#[test]
+fn test_session_with_nonce_caller() {
+    let mut context = create_ctx_without_session();
+    let nonce = Nonce::try_from(vec![0x5a; 32]).expect("Failed to create the nonce");
+    let mut session_guard = SessionBuilder::new()
+        .with_nonce_caller(nonce)
+        .build_scoped(&mut context)
+        .expect("Failed to start the session with a nonceCaller");
+    let _ = session_guard
+        .execute(|ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    drop(session_guard);
+
+    let mut session_guard = SessionBuilder::new()
+        .with_nonce_size(16)
+        .build_scoped(&mut context)
+        .expect("Failed to start the session with a sized nonceCaller");
+    let _ = session_guard
+        .execute(|ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+    drop(session_guard);
+
+    assert!(matches!(
+        SessionBuilder::new().with_nonce_size(8).build(&mut context),
+        Err(Error::WrapperError(WrapperErrorKind::WrongParamSize))
+    ));
+}
+
+#[test]
+fn test_session_with_nonce_size_and_parameter_encryption() {
+    let mut context = create_ctx_without_session();
+    context.set_parameter_encryption(true);
+    let mut session_guard = SessionBuilder::new()
+        .with_nonce_size(16)
+        .build_scoped(&mut context)
+        .expect("Failed to start the session with a sized nonceCaller");
+    let _ = session_guard
+        .execute(|ctx| ctx.get_random(16))
+        .expect("Call to get_random failed");
+}
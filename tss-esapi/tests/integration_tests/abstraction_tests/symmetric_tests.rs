@@ -0,0 +1,153 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::{TryFrom, TryInto};
+use tss_esapi::{
+    abstraction::{cipher::Cipher, symmetric},
+    attributes::ObjectAttributesBuilder,
+    handles::KeyHandle,
+    interface_types::{
+        algorithm::{HashingAlgorithm, PublicAlgorithm, SymmetricMode},
+        resource_handles::Hierarchy,
+    },
+    structures::{InitialValue, MaxBuffer, PublicBuilder, SymmetricCipherParameters},
+    Context,
+};
+
+use crate::common::{create_ctx_with_session, decryption_key_pub};
+
+fn create_symmetric_key(context: &mut Context) -> KeyHandle {
+    let primary_key_handle = context
+        .create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create primary key")
+        .key_handle;
+
+    let symmetric_key_object_attributes = ObjectAttributesBuilder::new()
+        .with_user_with_auth(true)
+        .with_sign_encrypt(true)
+        .with_decrypt(true)
+        .with_fixed_tpm(true)
+        .with_fixed_parent(true)
+        .with_sensitive_data_origin(true)
+        .build()
+        .expect("Failed to create object attributes for symmetric key");
+
+    let symmetric_key_public = PublicBuilder::new()
+        .with_public_algorithm(PublicAlgorithm::SymCipher)
+        .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+        .with_object_attributes(symmetric_key_object_attributes)
+        .with_symmetric_cipher_parameters(SymmetricCipherParameters::new(
+            Cipher::aes_128_cfb()
+                .try_into()
+                .expect("Failed to create symmteric cipher parameters from cipher"),
+        ))
+        .with_symmetric_cipher_unique_identifier(Default::default())
+        .build()
+        .expect("Failed to create public for symmetric key public");
+
+    let symmetric_key_creation_data = context
+        .create(
+            primary_key_handle,
+            symmetric_key_public,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create symmetric key");
+
+    let symmetric_key_handle = context
+        .load(
+            primary_key_handle,
+            symmetric_key_creation_data.out_private,
+            symmetric_key_creation_data.out_public,
+        )
+        .expect("Failed to load symmetric key");
+
+    context
+        .flush_context(primary_key_handle.into())
+        .expect("Failed to flush primary key");
+
+    symmetric_key_handle
+}
+
+#[test]
+fn test_encrypt_decrypt_stream() {
+    let mut context = create_ctx_with_session();
+    let symmetric_key_handle = create_symmetric_key(&mut context);
+    let initial_value =
+        InitialValue::try_from(vec![0xab; 16]).expect("Failed to create InitialValue from data");
+
+    // Larger than what fits in a single call to EncryptDecrypt2
+    // and not a multiple of the block size.
+    let data: Vec<u8> = (0..(3 * MaxBuffer::MAX_SIZE + 7))
+        .map(|v| (v % 251) as u8)
+        .collect();
+
+    let mut encrypted_data = Vec::new();
+    let _ = symmetric::encrypt_stream(
+        &mut context,
+        symmetric_key_handle,
+        SymmetricMode::Cfb,
+        initial_value.clone(),
+        &mut data.as_slice(),
+        &mut encrypted_data,
+    )
+    .expect("Failed to encrypt stream");
+
+    assert_eq!(data.len(), encrypted_data.len());
+    assert_ne!(data, encrypted_data);
+
+    // The first chunk must match a single call to EncryptDecrypt2.
+    let (first_chunk, _) = context
+        .encrypt_decrypt_2(
+            symmetric_key_handle,
+            false,
+            SymmetricMode::Cfb,
+            MaxBuffer::try_from(data[..16].to_vec()).expect("Failed to create MaxBuffer"),
+            initial_value.clone(),
+        )
+        .expect("Call to encrypt_decrypt_2 failed");
+    assert_eq!(first_chunk.as_bytes(), &encrypted_data[..16]);
+
+    let mut decrypted_data = Vec::new();
+    let _ = symmetric::decrypt_stream(
+        &mut context,
+        symmetric_key_handle,
+        SymmetricMode::Cfb,
+        initial_value,
+        &mut encrypted_data.as_slice(),
+        &mut decrypted_data,
+    )
+    .expect("Failed to decrypt stream");
+
+    assert_eq!(data, decrypted_data);
+}
+
+#[test]
+fn test_encrypt_empty_stream() {
+    let mut context = create_ctx_with_session();
+    let symmetric_key_handle = create_symmetric_key(&mut context);
+    let initial_value =
+        InitialValue::try_from(vec![0xab; 16]).expect("Failed to create InitialValue from data");
+
+    let mut encrypted_data = Vec::new();
+    let iv_out = symmetric::encrypt_stream(
+        &mut context,
+        symmetric_key_handle,
+        SymmetricMode::Cfb,
+        initial_value.clone(),
+        &mut std::io::empty(),
+        &mut encrypted_data,
+    )
+    .expect("Failed to encrypt stream");
+
+    assert!(encrypted_data.is_empty());
+    assert_eq!(initial_value, iv_out);
+}
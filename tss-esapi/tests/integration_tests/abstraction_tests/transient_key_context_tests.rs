@@ -4,7 +4,10 @@ use std::convert::{TryFrom, TryInto};
 use tss_esapi::{
     abstraction::ek,
     abstraction::transient::{KeyParams, ObjectWrapper, TransientKeyContextBuilder},
-    constants::return_code::{TpmFormatOneError, TpmFormatZeroError},
+    constants::{
+        return_code::{TpmFormatOneError, TpmFormatZeroError},
+        tss::{TPM2_RH_NULL, TPM2_ST_HASHCHECK},
+    },
     error::{TpmFormatZeroResponseCode, TpmResponseCode},
     interface_types::{
         algorithm::{
@@ -16,8 +19,9 @@ use tss_esapi::{
     },
     structures::{
         Auth, CreateKeyResult, Digest, EccScheme, Public, PublicKeyRsa, RsaExponent, RsaScheme,
-        RsaSignature, Signature, SymmetricDefinitionObject,
+        RsaSignature, Signature, SignatureScheme, SymmetricDefinitionObject,
     },
+    tss2_esys::TPMT_TK_HASHCHECK,
     utils::{create_restricted_decryption_rsa_public, PublicKey},
     Error, ReturnCode, TransientKeyContext, WrapperErrorKind as ErrorKind,
 };
@@ -425,6 +429,109 @@ fn full_test() {
     }
 }
 
+#[test]
+fn create_loaded_key() {
+    let mut ctx = create_ctx();
+    let key_params = KeyParams::Ecc {
+        curve: EccCurve::NistP256,
+        scheme: EccScheme::create(
+            EccSchemeAlgorithm::EcDsa,
+            Some(HashingAlgorithm::Sha256),
+            None,
+        )
+        .expect("Failed to create ecc scheme"),
+    };
+    let (key_handle, key, _) = ctx.create_loaded_key(key_params, 16).unwrap();
+    let signature = ctx
+        .as_mut()
+        .execute_with_nullauth_session(|context| {
+            context.sign(
+                key_handle,
+                Digest::try_from(HASH.to_vec()).unwrap(),
+                SignatureScheme::Null,
+                TPMT_TK_HASHCHECK {
+                    tag: TPM2_ST_HASHCHECK,
+                    hierarchy: TPM2_RH_NULL,
+                    digest: Default::default(),
+                }
+                .try_into()
+                .unwrap(),
+            )
+        })
+        .unwrap();
+    ctx.as_mut().flush_context(key_handle.into()).unwrap();
+
+    let _ = ctx
+        .verify_signature(
+            key,
+            key_params,
+            Digest::try_from(HASH.to_vec()).unwrap(),
+            signature,
+        )
+        .unwrap();
+}
+
+#[test]
+fn change_key_auth() {
+    let mut ctx = create_ctx();
+    let key_params = KeyParams::Rsa {
+        size: RsaKeyBits::Rsa2048,
+        scheme: RsaScheme::create(RsaSchemeAlgorithm::RsaSsa, Some(HashingAlgorithm::Sha256))
+            .expect("Failed to create RSA scheme"),
+        pub_exponent: RsaExponent::default(),
+    };
+    let (key, key_auth) = ctx.create_key(key_params, 16).unwrap();
+    let (new_key, new_key_auth) = ctx
+        .change_key_auth(key.clone(), key_params, key_auth.clone(), 16)
+        .unwrap();
+    assert_ne!(key_auth, new_key_auth);
+
+    // The new key material only works with the new auth value.
+    ctx.sign(
+        new_key.clone(),
+        key_params,
+        key_auth,
+        Digest::try_from(HASH.to_vec()).unwrap(),
+    )
+    .unwrap_err();
+    let signature = ctx
+        .sign(
+            new_key.clone(),
+            key_params,
+            new_key_auth,
+            Digest::try_from(HASH.to_vec()).unwrap(),
+        )
+        .unwrap();
+    let _ = ctx
+        .verify_signature(
+            new_key,
+            key_params,
+            Digest::try_from(HASH.to_vec()).unwrap(),
+            signature,
+        )
+        .unwrap();
+}
+
+#[test]
+fn change_key_auth_public_key() {
+    let mut ctx = create_ctx();
+    let key_params = KeyParams::Rsa {
+        size: RsaKeyBits::Rsa2048,
+        scheme: RsaScheme::create(RsaSchemeAlgorithm::RsaSsa, Some(HashingAlgorithm::Sha256))
+            .expect("Failed to create RSA scheme"),
+        pub_exponent: RsaExponent::default(),
+    };
+    let (key, _) = ctx.create_key(key_params, 0).unwrap();
+    let pub_key = ctx
+        .load_external_public_key(key.public().clone(), key_params)
+        .unwrap();
+    assert_eq!(
+        ctx.change_key_auth(pub_key, key_params, None, 16)
+            .unwrap_err(),
+        Error::WrapperError(ErrorKind::InvalidParam)
+    );
+}
+
 #[test]
 fn create_ecc_key() {
     let mut ctx = create_ctx();
@@ -0,0 +1,132 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::x509,
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{EccParameter, EccSignature, Signature},
+    Error, WrapperErrorKind,
+};
+
+// Name with a single common name attribute "TPM".
+const NAME: [u8; 16] = [
+    0x30, 0x0e, 0x31, 0x0c, 0x30, 0x0a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x03, 0x54, 0x50, 0x4d,
+];
+// Validity from 2022-01-01 to 2032-01-01.
+const VALIDITY: [u8; 32] = [
+    0x30, 0x1e, 0x17, 0x0d, 0x32, 0x32, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30,
+    0x5a, 0x17, 0x0d, 0x33, 0x32, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30, 0x30, 0x30, 0x5a,
+];
+// Extensions containing a key usage extension with digital signature.
+const EXTENSIONS: [u8; 17] = [
+    0xa3, 0x0f, 0x30, 0x0d, 0x30, 0x0b, 0x06, 0x03, 0x55, 0x1d, 0x0f, 0x04, 0x04, 0x03, 0x02, 0x07,
+    0x80,
+];
+// Version, serial number, signature algorithm and a placeholder subject public key info.
+const ADDED_TO_CERTIFICATE: [u8; 21] = [
+    0x30, 0x13, 0xa0, 0x03, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01, 0x30, 0x05, 0x06, 0x03, 0x2a, 0x03,
+    0x04, 0x30, 0x02, 0x05, 0x00,
+];
+
+fn partial_certificate() -> Vec<u8> {
+    x509::partial_certificate(&NAME, &VALIDITY, &NAME, &EXTENSIONS)
+        .expect("Failed to create partial certificate")
+        .to_vec()
+}
+
+#[test]
+fn test_partial_certificate() {
+    let partial_certificate = partial_certificate();
+    let content_size = 2 * NAME.len() + VALIDITY.len() + EXTENSIONS.len();
+    assert_eq!(partial_certificate[0], 0x30);
+    assert_eq!(usize::from(partial_certificate[1]), content_size);
+    assert_eq!(&partial_certificate[2..18], &NAME);
+    assert_eq!(&partial_certificate[(2 + content_size - 17)..], &EXTENSIONS);
+}
+
+#[test]
+fn test_partial_certificate_with_invalid_element() {
+    if let Err(Error::WrapperError(WrapperErrorKind::InvalidParam)) =
+        x509::partial_certificate(&NAME[..10], &VALIDITY, &NAME, &EXTENSIONS)
+    {
+    } else {
+        panic!("A truncated element did not result in an InvalidParam error");
+    }
+
+    let mut two_names = NAME.to_vec();
+    two_names.extend_from_slice(&NAME);
+    if let Err(Error::WrapperError(WrapperErrorKind::InvalidParam)) =
+        x509::partial_certificate(&two_names, &VALIDITY, &NAME, &EXTENSIONS)
+    {
+    } else {
+        panic!("Multiple elements did not result in an InvalidParam error");
+    }
+}
+
+#[test]
+fn test_tbs_certificate() {
+    let tbs_certificate = x509::tbs_certificate(&partial_certificate(), &ADDED_TO_CERTIFICATE)
+        .expect("Failed to create TBS certificate");
+
+    let mut expected_content = Vec::new();
+    expected_content.extend_from_slice(&ADDED_TO_CERTIFICATE[2..17]);
+    expected_content.extend_from_slice(&NAME);
+    expected_content.extend_from_slice(&VALIDITY);
+    expected_content.extend_from_slice(&NAME);
+    expected_content.extend_from_slice(&ADDED_TO_CERTIFICATE[17..]);
+    expected_content.extend_from_slice(&EXTENSIONS);
+    assert_eq!(tbs_certificate[0], 0x30);
+    assert_eq!(usize::from(tbs_certificate[1]), expected_content.len());
+    assert_eq!(&tbs_certificate[2..], expected_content.as_slice());
+}
+
+#[test]
+fn test_tbs_certificate_with_missing_fields() {
+    if let Err(Error::WrapperError(WrapperErrorKind::InvalidParam)) = x509::tbs_certificate(
+        &partial_certificate(),
+        &[0x30, 0x05, 0xa0, 0x03, 0x02, 0x01, 0x02],
+    ) {
+    } else {
+        panic!("Missing added fields did not result in an InvalidParam error");
+    }
+}
+
+#[test]
+fn test_certificate_with_ecc_signature() {
+    let signature = Signature::EcDsa(
+        EccSignature::create(
+            HashingAlgorithm::Sha256,
+            EccParameter::try_from(vec![0x80, 0x01]).expect("Failed to create signature r"),
+            EccParameter::try_from(vec![0x00, 0x01]).expect("Failed to create signature s"),
+        )
+        .expect("Failed to create ECC signature"),
+    );
+    let partial_certificate = partial_certificate();
+    let tbs_certificate = x509::tbs_certificate(&partial_certificate, &ADDED_TO_CERTIFICATE)
+        .expect("Failed to create TBS certificate");
+    let certificate = x509::certificate(&partial_certificate, &ADDED_TO_CERTIFICATE, &signature)
+        .expect("Failed to create certificate");
+
+    let signature_algorithm = &ADDED_TO_CERTIFICATE[10..17];
+    let signature_value = [
+        0x03, 0x0b, 0x00, 0x30, 0x08, 0x02, 0x03, 0x00, 0x80, 0x01, 0x02, 0x01, 0x01,
+    ];
+    let mut expected_content = tbs_certificate;
+    expected_content.extend_from_slice(signature_algorithm);
+    expected_content.extend_from_slice(&signature_value);
+    assert_eq!(certificate[0], 0x30);
+    assert_eq!(usize::from(certificate[1]), expected_content.len());
+    assert_eq!(&certificate[2..], expected_content.as_slice());
+}
+
+#[test]
+fn test_certificate_with_unsupported_signature() {
+    if let Err(Error::WrapperError(WrapperErrorKind::UnsupportedParam)) = x509::certificate(
+        &partial_certificate(),
+        &ADDED_TO_CERTIFICATE,
+        &Signature::Null,
+    ) {
+    } else {
+        panic!("Null signature did not result in an UnsupportedParam error");
+    }
+}
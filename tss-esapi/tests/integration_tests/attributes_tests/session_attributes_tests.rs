@@ -3,6 +3,8 @@
 use std::convert::{TryFrom, TryInto};
 use tss_esapi::{
     attributes::{SessionAttributes, SessionAttributesBuilder, SessionAttributesMask},
+    constants::SessionType,
+    structures::SymmetricDefinition,
     tss2_esys::TPMA_SESSION,
     Error, WrapperErrorKind,
 };
@@ -239,3 +241,79 @@ fn test_session_attributes_builder() {
         "SessionAttributes builder did not produce the expected session attributes mask value"
     )
 }
+
+#[test]
+fn test_session_attributes_builder_presets() {
+    let (parameter_encryption_attributes, parameter_encryption_mask) =
+        SessionAttributesBuilder::for_parameter_encryption()
+            .try_build()
+            .expect("Failed to build the parameter encryption attributes");
+    assert!(parameter_encryption_attributes.continue_session());
+    assert!(parameter_encryption_attributes.decrypt());
+    assert!(parameter_encryption_attributes.encrypt());
+    assert!(!parameter_encryption_attributes.audit());
+    assert_eq!(
+        SessionAttributesMask::try_from(0b01100001u8)
+            .expect("Failed to convert 0b01100001u8 into SessionAttributesMask"),
+        parameter_encryption_mask,
+    );
+
+    let (audit_attributes, audit_mask) = SessionAttributesBuilder::for_audit()
+        .try_build()
+        .expect("Failed to build the audit attributes");
+    assert!(audit_attributes.continue_session());
+    assert!(audit_attributes.audit());
+    assert!(!audit_attributes.decrypt());
+    assert_eq!(
+        SessionAttributesMask::try_from(0b10000001u8)
+            .expect("Failed to convert 0b10000001u8 into SessionAttributesMask"),
+        audit_mask,
+    );
+}
+
+#[test]
+fn test_session_attributes_builder_validation() {
+    // auditExclusive and auditReset can be set on a session that is already auditing.
+    assert!(SessionAttributesBuilder::new()
+        .with_audit_exclusive(true)
+        .with_audit_reset(true)
+        .validate()
+        .is_ok());
+    assert!(SessionAttributesBuilder::for_audit()
+        .with_audit_exclusive(true)
+        .validate()
+        .is_ok());
+
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)),
+        SessionAttributesBuilder::new()
+            .with_audit(false)
+            .with_audit_exclusive(true)
+            .validate(),
+    );
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)),
+        SessionAttributesBuilder::new()
+            .with_audit(false)
+            .with_audit_reset(true)
+            .try_build()
+            .map(|_| ()),
+    );
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)),
+        SessionAttributesBuilder::for_parameter_encryption()
+            .with_session_type(SessionType::Trial)
+            .validate(),
+    );
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InconsistentParams)),
+        SessionAttributesBuilder::for_parameter_encryption()
+            .with_symmetric(SymmetricDefinition::Null)
+            .validate(),
+    );
+    assert!(SessionAttributesBuilder::for_parameter_encryption()
+        .with_session_type(SessionType::Hmac)
+        .with_symmetric(SymmetricDefinition::AES_128_CFB)
+        .validate()
+        .is_ok());
+}
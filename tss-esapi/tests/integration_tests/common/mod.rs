@@ -225,6 +225,25 @@ pub fn create_ctx_with_session() -> Context {
     ctx
 }
 
+/// Starts a policy or trial session, without using the sessions of the context.
+#[allow(dead_code)]
+pub fn start_policy_session(context: &mut Context, session_type: SessionType) -> PolicySession {
+    let session = context
+        .execute_without_session(|ctx| {
+            ctx.start_auth_session(
+                None,
+                None,
+                None,
+                session_type,
+                SymmetricDefinition::AES_256_CFB,
+                HashingAlgorithm::Sha256,
+            )
+        })
+        .expect("Start auth session failed")
+        .expect("Start auth session returned a NONE handle");
+    PolicySession::try_from(session).expect("Failed to convert auth session into policy session")
+}
+
 #[allow(dead_code)]
 pub fn decryption_key_pub() -> Public {
     utils::create_restricted_decryption_rsa_public(
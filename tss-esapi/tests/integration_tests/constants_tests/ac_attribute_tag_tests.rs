@@ -0,0 +1,50 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    constants::{
+        tss::{TPM_AT_ANY, TPM_AT_ERROR, TPM_AT_PV1, TPM_AT_VEND},
+        AcAttributeTag,
+    },
+    tss2_esys::TPM_AT,
+    Error, WrapperErrorKind,
+};
+
+use std::convert::{From, TryFrom};
+
+macro_rules! test_valid_conversion {
+    ($tpm_at:ident, $ac_attribute_tag:ident) => {
+        assert_eq!($tpm_at, TPM_AT::from(AcAttributeTag::$ac_attribute_tag));
+        assert_eq!(
+            AcAttributeTag::$ac_attribute_tag,
+            AcAttributeTag::try_from($tpm_at).unwrap()
+        );
+    };
+}
+
+#[test]
+fn test_valid_conversions() {
+    test_valid_conversion!(TPM_AT_ANY, Any);
+    test_valid_conversion!(TPM_AT_ERROR, Error);
+    test_valid_conversion!(TPM_AT_PV1, Pv1);
+}
+
+#[test]
+fn test_vendor_conversions() {
+    assert_eq!(TPM_AT_VEND, TPM_AT::from(AcAttributeTag::Vendor(0)));
+    assert_eq!(
+        AcAttributeTag::Vendor(0x12),
+        AcAttributeTag::try_from(TPM_AT_VEND + 0x12).unwrap()
+    );
+    assert_eq!(
+        TPM_AT_VEND + 0x12,
+        TPM_AT::from(AcAttributeTag::Vendor(0x12))
+    );
+}
+
+#[test]
+fn test_invalid_conversions() {
+    assert_eq!(
+        AcAttributeTag::try_from(0x3).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
@@ -0,0 +1,50 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    constants::{
+        tss::{
+            TPM2_EO_BITCLEAR, TPM2_EO_BITSET, TPM2_EO_EQ, TPM2_EO_NEQ, TPM2_EO_SIGNED_GE,
+            TPM2_EO_SIGNED_GT, TPM2_EO_SIGNED_LE, TPM2_EO_SIGNED_LT, TPM2_EO_UNSIGNED_GE,
+            TPM2_EO_UNSIGNED_GT, TPM2_EO_UNSIGNED_LE, TPM2_EO_UNSIGNED_LT,
+        },
+        ArithmeticOperation,
+    },
+    tss2_esys::TPM2_EO,
+};
+
+use std::convert::{From, TryFrom};
+
+macro_rules! test_valid_conversion {
+    ($tpm_eo:ident, $arithmetic_operation:ident) => {
+        assert_eq!(
+            $tpm_eo,
+            TPM2_EO::from(ArithmeticOperation::$arithmetic_operation)
+        );
+        assert_eq!(
+            ArithmeticOperation::$arithmetic_operation,
+            ArithmeticOperation::try_from($tpm_eo).unwrap()
+        );
+    };
+}
+
+#[test]
+fn test_valid_conversions() {
+    test_valid_conversion!(TPM2_EO_EQ, Eq);
+    test_valid_conversion!(TPM2_EO_NEQ, Neq);
+    test_valid_conversion!(TPM2_EO_SIGNED_GT, SignedGt);
+    test_valid_conversion!(TPM2_EO_UNSIGNED_GT, UnsignedGt);
+    test_valid_conversion!(TPM2_EO_SIGNED_LT, SignedLt);
+    test_valid_conversion!(TPM2_EO_UNSIGNED_LT, UnsignedLt);
+    test_valid_conversion!(TPM2_EO_SIGNED_GE, SignedGe);
+    test_valid_conversion!(TPM2_EO_UNSIGNED_GE, UnsignedGe);
+    test_valid_conversion!(TPM2_EO_SIGNED_LE, SignedLe);
+    test_valid_conversion!(TPM2_EO_UNSIGNED_LE, UnsignedLe);
+    test_valid_conversion!(TPM2_EO_BITSET, BitSet);
+    test_valid_conversion!(TPM2_EO_BITCLEAR, BitClear);
+}
+
+#[test]
+fn test_invalid_conversions() {
+    const INVALID_VALUE: TPM2_EO = 0x000C;
+    let _ = ArithmeticOperation::try_from(INVALID_VALUE).unwrap_err();
+}
@@ -0,0 +1,46 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    constants::{
+        tss::{
+            TPM2_CLOCK_COARSE_FASTER, TPM2_CLOCK_COARSE_SLOWER, TPM2_CLOCK_FINE_FASTER,
+            TPM2_CLOCK_FINE_SLOWER, TPM2_CLOCK_MEDIUM_FASTER, TPM2_CLOCK_MEDIUM_SLOWER,
+            TPM2_CLOCK_NO_CHANGE,
+        },
+        ClockAdjust,
+    },
+    tss2_esys::TPM2_CLOCK_ADJUST,
+};
+
+use std::convert::{From, TryFrom};
+
+macro_rules! test_valid_conversion {
+    ($tpm_clock_adjust:ident, $clock_adjust:ident) => {
+        assert_eq!(
+            $tpm_clock_adjust,
+            TPM2_CLOCK_ADJUST::from(ClockAdjust::$clock_adjust)
+        );
+        assert_eq!(
+            ClockAdjust::$clock_adjust,
+            ClockAdjust::try_from($tpm_clock_adjust).unwrap()
+        );
+    };
+}
+
+#[test]
+fn test_valid_conversions() {
+    test_valid_conversion!(TPM2_CLOCK_COARSE_SLOWER, CoarseSlower);
+    test_valid_conversion!(TPM2_CLOCK_MEDIUM_SLOWER, MediumSlower);
+    test_valid_conversion!(TPM2_CLOCK_FINE_SLOWER, FineSlower);
+    test_valid_conversion!(TPM2_CLOCK_NO_CHANGE, NoChange);
+    test_valid_conversion!(TPM2_CLOCK_FINE_FASTER, FineFaster);
+    test_valid_conversion!(TPM2_CLOCK_MEDIUM_FASTER, MediumFaster);
+    test_valid_conversion!(TPM2_CLOCK_COARSE_FASTER, CoarseFaster);
+}
+
+#[test]
+fn test_invalid_conversions() {
+    const INVALID_VALUE: TPM2_CLOCK_ADJUST = 4;
+    let _ = ClockAdjust::try_from(INVALID_VALUE).unwrap_err();
+    let _ = ClockAdjust::try_from(-INVALID_VALUE).unwrap_err();
+}
@@ -3,7 +3,7 @@
 
 use std::convert::TryFrom;
 use tss_esapi::{
-    constants::{tss::*, CommandCode},
+    constants::{tss::*, CommandCode, VendorCommandCode},
     tss2_esys::TPM2_CC,
     Error, WrapperErrorKind,
 };
@@ -218,3 +218,26 @@ fn test_invalid_conversions() {
         "A value representing a non existing command code did not produce the expected error"
     );
 }
+
+#[test]
+fn test_vendor_command_code_conversions() {
+    const VENDOR_COMMAND: TPM2_CC = 0b00100000000000001111111111111111u32;
+    let vendor_command_code =
+        VendorCommandCode::try_from(VENDOR_COMMAND).expect("Failed to convert vendor command");
+    assert_eq!(vendor_command_code, VendorCommandCode::new(0xFFFF));
+    assert_eq!(vendor_command_code.command_index(), 0xFFFF);
+    assert_eq!(VENDOR_COMMAND, TPM2_CC::from(vendor_command_code));
+
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InvalidParam)),
+        VendorCommandCode::try_from(TPM2_CC_GetRandom),
+        "A command code without the vendor bit did not produce the expected error"
+    );
+
+    const RESERVED: TPM2_CC = 0b00100000000000010000000000000001u32;
+    assert_eq!(
+        Err(Error::WrapperError(WrapperErrorKind::InvalidParam)),
+        VendorCommandCode::try_from(RESERVED),
+        "Value with bit set a place marked as reserved (reserved) did not produce expected error"
+    );
+}
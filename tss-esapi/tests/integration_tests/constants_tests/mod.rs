@@ -1,7 +1,10 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod ac_attribute_tag_tests;
 mod algorithm_tests;
+mod arithmetic_operation_tests;
 mod capabilities_tests;
+mod clock_adjust_tests;
 mod command_code_tests;
 mod nv_index_type_tests;
 mod pcr_property_tag_tests;
@@ -0,0 +1,108 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::{
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tss_esapi::{
+    handles::KeyHandle, interface_types::resource_handles::Hierarchy, structures::Auth, Context,
+};
+
+use crate::common::{create_ctx_with_session, decryption_key_pub, signing_key_pub};
+
+fn create_primary(context: &mut Context, auth: Auth) -> KeyHandle {
+    context
+        .create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            Some(auth),
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create primary key")
+        .key_handle
+}
+
+fn reload(context: &mut Context, key_handle: KeyHandle) -> KeyHandle {
+    let key_context = context
+        .key_context_save(key_handle)
+        .expect("Failed to save the key context");
+    context
+        .flush_context(key_handle.into())
+        .expect("Failed to flush the key");
+    context
+        .key_context_load(key_context)
+        .expect("Failed to load the key context")
+}
+
+#[test]
+fn test_registered_auth_is_set_on_load() {
+    let mut context = create_ctx_with_session();
+    let auth = Auth::try_from(vec![1, 2, 3, 4]).unwrap();
+    let key_handle = create_primary(&mut context, auth.clone());
+    context
+        .register_auth(key_handle.into(), auth)
+        .expect("Failed to register the auth value");
+
+    let key_handle = reload(&mut context, key_handle);
+    let _ = context
+        .create(key_handle, signing_key_pub(), None, None, None, None)
+        .expect("The auth value of the loaded key should have been set");
+}
+
+#[test]
+fn test_unregistered_auth_is_not_set_on_load() {
+    let mut context = create_ctx_with_session();
+    let auth = Auth::try_from(vec![1, 2, 3, 4]).unwrap();
+    let key_handle = create_primary(&mut context, auth.clone());
+    context
+        .register_auth(key_handle.into(), auth)
+        .expect("Failed to register the auth value");
+    assert!(context.unregister_auth(key_handle.into()).unwrap());
+    assert!(!context.unregister_auth(key_handle.into()).unwrap());
+
+    let key_handle = reload(&mut context, key_handle);
+    assert!(
+        context
+            .create(key_handle, signing_key_pub(), None, None, None, None)
+            .is_err(),
+        "The auth value of the loaded key should not have been set"
+    );
+}
+
+#[test]
+fn test_auth_callback() {
+    let mut context = create_ctx_with_session();
+    let auth = Auth::try_from(vec![5, 6, 7, 8]).unwrap();
+    let key_handle = create_primary(&mut context, auth.clone());
+    let expected_name = context.tr_get_name(key_handle.into()).unwrap();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let callback_calls = calls.clone();
+    context.set_auth_callback(move |name| {
+        let _ = callback_calls.fetch_add(1, Ordering::SeqCst);
+        if *name == expected_name {
+            Some(auth.clone())
+        } else {
+            None
+        }
+    });
+
+    let key_handle = reload(&mut context, key_handle);
+    let _ = context
+        .create(key_handle, signing_key_pub(), None, None, None, None)
+        .expect("The auth value supplied by the callback should have been set");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // The auth value supplied by the callback has been registered.
+    context.clear_auth_callback();
+    let key_handle = reload(&mut context, key_handle);
+    let _ = context
+        .create(key_handle, signing_key_pub(), None, None, None, None)
+        .expect("The registered auth value should have been set");
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
@@ -16,7 +16,10 @@ mod test_tr_from_tpm_public {
 
     use std::convert::TryFrom;
 
-    fn remove_nv_index_handle_from_tpm(nv_index_tpm_handle: NvIndexTpmHandle, nv_auth: Provision) {
+    pub(super) fn remove_nv_index_handle_from_tpm(
+        nv_index_tpm_handle: NvIndexTpmHandle,
+        nv_auth: Provision,
+    ) {
         let mut context = create_ctx_without_session();
         let mut property = TPM2_NV_INDEX_FIRST;
         while let Ok((capability_data, more_data_available)) =
@@ -445,3 +448,164 @@ mod test_tr_from_tpm_public {
         assert_eq!(expected, actual);
     }
 }
+
+mod test_tr_serialize {
+    use super::test_tr_from_tpm_public::remove_nv_index_handle_from_tpm;
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        attributes::NvIndexAttributesBuilder,
+        handles::{NvIndexTpmHandle, ObjectHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm, resource_handles::Provision, session_handles::AuthSession,
+        },
+        structures::NvPublicBuilder,
+        utils::SerializedHandle,
+    };
+
+    #[test]
+    fn test_tr_serialize_deserialize() {
+        let nv_index_tpm_handle = NvIndexTpmHandle::new(0x01500305).unwrap();
+        remove_nv_index_handle_from_tpm(nv_index_tpm_handle, Provision::Owner);
+
+        let mut context = create_ctx_without_session();
+
+        let nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index_tpm_handle)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(nv_index_attributes)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build nv public");
+
+        context.set_sessions((Some(AuthSession::Password), None, None));
+        let nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, nv_public)
+            .expect("Failed to call nv_define_space");
+        let expected_name = context
+            .tr_get_name(nv_index_handle.into())
+            .expect("Failed to call tr_get_name");
+        ///////////////////////////////////////////////////////////////
+        // Serialize the handle, and store its buffer.
+        let serialized_handle = context
+            .tr_serialize(nv_index_handle.into())
+            .expect("Failed to call tr_serialize");
+        let buffer = Vec::<u8>::from(serialized_handle.clone());
+        let mut handle_to_be_closed: ObjectHandle = nv_index_handle.into();
+        context
+            .tr_close(&mut handle_to_be_closed)
+            .expect("Failed to call tr_close");
+        ///////////////////////////////////////////////////////////////
+        // Restore the handle in another context.
+        drop(context);
+        let mut context = create_ctx_without_session();
+        let restored_serialized_handle = SerializedHandle::from(buffer);
+        assert_eq!(serialized_handle, restored_serialized_handle);
+        let restored_handle = context
+            .tr_deserialize(&restored_serialized_handle)
+            .expect("Failed to call tr_deserialize");
+        let actual_name = context
+            .tr_get_name(restored_handle)
+            .expect("Failed to call tr_get_name");
+
+        context.set_sessions((Some(AuthSession::Password), None, None));
+        context
+            .nv_undefine_space(Provision::Owner, restored_handle.into())
+            .expect("Failed to call nv_undefine_space");
+
+        assert_eq!(expected_name, actual_name);
+    }
+
+    #[test]
+    fn test_tr_deserialize_invalid_buffer() {
+        let mut context = create_ctx_without_session();
+        let _ = context
+            .tr_deserialize(&SerializedHandle::from(vec![0xff; 4]))
+            .expect_err("Deserializing an invalid buffer should fail");
+    }
+}
+
+mod test_object_from_tpm_handle {
+    use crate::common::{create_ctx_without_session, decryption_key_pub};
+    use tss_esapi::{
+        handles::{NvIndexTpmHandle, ObjectHandle, PersistentTpmHandle, TpmHandle},
+        interface_types::{
+            dynamic_handles::Persistent,
+            resource_handles::{Hierarchy, Provision},
+            session_handles::AuthSession,
+        },
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_object_from_persistent_tpm_handle() {
+        let persistent_tpm_handle = PersistentTpmHandle::new(0x81000006).unwrap();
+        let persistent = Persistent::Persistent(persistent_tpm_handle);
+        let mut context = create_ctx_without_session();
+        context.set_sessions((Some(AuthSession::Password), None, None));
+
+        // Make sure the handle is not already persistent
+        if let Ok(handle) = context.execute_without_session(|ctx| {
+            ctx.tr_from_tpm_public(TpmHandle::Persistent(persistent_tpm_handle))
+        }) {
+            let _ = context
+                .evict_control(Provision::Owner, handle, persistent)
+                .expect("Failed to evict persistent handle");
+        }
+
+        let key_handle = context
+            .create_primary(
+                Hierarchy::Owner,
+                decryption_key_pub(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .expect("Failed to create primary key")
+            .key_handle;
+        let (expected_public, expected_name, _) = context
+            .read_public(key_handle)
+            .expect("Failed to read the public area");
+        let mut persistent_handle = context
+            .evict_control(Provision::Owner, key_handle.into(), persistent)
+            .expect("Failed to make the primary key persistent");
+        context
+            .flush_context(ObjectHandle::from(key_handle))
+            .expect("Failed to flush the primary key");
+        context
+            .tr_close(&mut persistent_handle)
+            .expect("Failed to close the persistent handle");
+
+        let (object_handle, public, name) = context
+            .execute_without_session(|ctx| {
+                ctx.object_from_tpm_handle(TpmHandle::Persistent(persistent_tpm_handle))
+            })
+            .expect("Failed to call object_from_tpm_handle");
+
+        let _ = context
+            .evict_control(Provision::Owner, object_handle, persistent)
+            .expect("Failed to evict persistent handle");
+
+        assert_eq!(expected_public, public);
+        assert_eq!(expected_name, name);
+    }
+
+    #[test]
+    fn test_object_from_nv_index_tpm_handle() {
+        let mut context = create_ctx_without_session();
+        assert_eq!(
+            context
+                .object_from_tpm_handle(TpmHandle::NvIndex(
+                    NvIndexTpmHandle::new(0x01500306).unwrap()
+                ))
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+}
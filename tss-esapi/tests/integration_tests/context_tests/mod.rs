@@ -1,2 +1,7 @@
+mod auth_registry_tests;
 mod general_esys_tr_tests;
+mod parameter_encryption_tests;
+mod retry_tests;
+mod session_administration_tests;
 mod tpm_commands;
+mod transient_object_tests;
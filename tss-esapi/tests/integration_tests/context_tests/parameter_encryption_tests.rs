@@ -0,0 +1,52 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use crate::common::create_ctx_without_session;
+use tss_esapi::{
+    constants::SessionType, interface_types::algorithm::HashingAlgorithm,
+    structures::SymmetricDefinition, Error, WrapperErrorKind,
+};
+
+#[test]
+fn test_parameter_encryption_without_session() {
+    let mut context = create_ctx_without_session();
+    context.set_parameter_encryption(true);
+    assert!(context.parameter_encryption());
+
+    // The random bytes are not returned in clear.
+    let result = context.get_random(16);
+    assert!(matches!(
+        result,
+        Err(Error::WrapperError(WrapperErrorKind::MissingAuthSession))
+    ));
+
+    context.set_parameter_encryption(false);
+    let _ = context.get_random(16).expect("Call to get_random failed");
+}
+
+#[test]
+fn test_parameter_encryption_with_session() {
+    let mut context = create_ctx_without_session();
+    let session = context
+        .start_auth_session(
+            None,
+            None,
+            None,
+            SessionType::Hmac,
+            SymmetricDefinition::AES_128_CFB,
+            HashingAlgorithm::Sha256,
+        )
+        .expect("Start auth session failed")
+        .expect("Start auth session returned a NONE handle");
+    context.set_sessions((Some(session), None, None));
+    context.set_parameter_encryption(true);
+
+    let random_bytes = context.get_random(16).expect("Call to get_random failed");
+    assert_eq!(random_bytes.len(), 16);
+
+    // The attributes of the session are restored after the command.
+    let attributes = context
+        .tr_sess_get_attributes(session)
+        .expect("Call to tr_sess_get_attributes failed");
+    assert!(!attributes.encrypt());
+    assert!(!attributes.decrypt());
+}
@@ -0,0 +1,49 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::{convert::TryFrom, time::Duration};
+use tss_esapi::{
+    constants::tss::{TPM2_RC_LOCKOUT, TPM2_RC_NV_RATE, TPM2_RC_RETRY, TSS2_TPM_RC_LAYER},
+    Error, RetryPolicy, ReturnCode, WrapperErrorKind,
+};
+
+use crate::common::create_ctx_without_session;
+
+fn tpm_error(tpm_rc: u32) -> Error {
+    Error::TssError(
+        ReturnCode::try_from(TSS2_TPM_RC_LAYER | tpm_rc)
+            .expect("Failed to convert TSS2_RC into a ReturnCode"),
+    )
+}
+
+#[test]
+fn test_retry_policy_backoff() {
+    let retry_policy = RetryPolicy::new(5, Duration::from_millis(10), Duration::from_millis(50));
+    assert_eq!(retry_policy.backoff(1), Duration::from_millis(10));
+    assert_eq!(retry_policy.backoff(2), Duration::from_millis(20));
+    assert_eq!(retry_policy.backoff(3), Duration::from_millis(40));
+    assert_eq!(retry_policy.backoff(4), Duration::from_millis(50));
+    assert_eq!(retry_policy.backoff(64), Duration::from_millis(50));
+}
+
+#[test]
+fn test_transient_errors() {
+    assert!(RetryPolicy::is_transient(&tpm_error(TPM2_RC_RETRY)));
+    assert!(RetryPolicy::is_transient(&tpm_error(TPM2_RC_NV_RATE)));
+    assert!(!RetryPolicy::is_transient(&tpm_error(TPM2_RC_LOCKOUT)));
+    assert!(!RetryPolicy::is_transient(&Error::WrapperError(
+        WrapperErrorKind::InvalidParam
+    )));
+}
+
+#[test]
+fn test_retry_policy() {
+    let mut context = create_ctx_without_session();
+    assert_eq!(context.retry_policy(), RetryPolicy::none());
+    let retry_policy = RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(1));
+    context.set_retry_policy(retry_policy);
+    assert_eq!(context.retry_policy(), retry_policy);
+
+    // The commands are executed through the retry policy of the context.
+    let random_bytes = context.get_random(16).expect("Call to get_random failed");
+    assert_eq!(random_bytes.len(), 16);
+}
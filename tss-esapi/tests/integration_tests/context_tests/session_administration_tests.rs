@@ -0,0 +1,69 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+mod test_tr_sess_get_attributes {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        attributes::SessionAttributesBuilder, constants::SessionType,
+        interface_types::algorithm::HashingAlgorithm, structures::SymmetricDefinition,
+    };
+
+    #[test]
+    fn test_tr_sess_get_attributes() {
+        let mut context = create_ctx_without_session();
+        let session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Hmac,
+                SymmetricDefinition::AES_256_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+            .with_decrypt(true)
+            .with_encrypt(true)
+            .build();
+        context
+            .tr_sess_set_attributes(session, session_attributes, session_attributes_mask)
+            .expect("tr_sess_set_attributes call failed");
+
+        let attributes = context
+            .tr_sess_get_attributes(session)
+            .expect("tr_sess_get_attributes call failed");
+        assert!(attributes.decrypt());
+        assert!(attributes.encrypt());
+        assert!(!attributes.audit());
+    }
+}
+
+mod test_session_nonce_tpm {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        constants::SessionType, interface_types::algorithm::HashingAlgorithm,
+        structures::SymmetricDefinition,
+    };
+
+    #[test]
+    fn test_session_nonce_tpm() {
+        let mut context = create_ctx_without_session();
+        let session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Policy,
+                SymmetricDefinition::AES_256_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+
+        let nonce_tpm = context
+            .session_nonce_tpm(session)
+            .expect("session_nonce_tpm call failed");
+        // The TPM uses a nonce of the size of the digest of the session.
+        assert_eq!(nonce_tpm.len(), 32);
+    }
+}
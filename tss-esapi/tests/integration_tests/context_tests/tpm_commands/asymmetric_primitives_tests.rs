@@ -104,3 +104,116 @@ mod test_rsa_encrypt_decrypt {
         assert_eq!(z_point.x().as_bytes(), param.x().as_bytes());
     }
 }
+
+mod test_zgen_2phase {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::attributes::ObjectAttributesBuilder;
+    use tss_esapi::{
+        interface_types::{
+            algorithm::{EccKeyExchangeAlgorithm, HashingAlgorithm, PublicAlgorithm},
+            ecc::EccCurve,
+            resource_handles::Hierarchy,
+        },
+        structures::{
+            Auth, EccPoint, EccScheme, HashScheme, KeyDerivationFunctionScheme, Public,
+            PublicBuilder, PublicEccParametersBuilder,
+        },
+    };
+
+    #[test]
+    fn test_zgen_2phase() {
+        let mut context = create_ctx_with_session();
+        let random_digest = context.get_random(16).unwrap();
+        let key_auth = Auth::from_bytes(random_digest.as_bytes()).unwrap();
+
+        let ecc_parms = PublicEccParametersBuilder::new()
+            .with_ecc_scheme(EccScheme::EcDh(HashScheme::new(HashingAlgorithm::Sha256)))
+            .with_curve(EccCurve::NistP256)
+            .with_is_signing_key(false)
+            .with_is_decryption_key(true)
+            .with_restricted(false)
+            .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+            .build()
+            .unwrap();
+
+        let object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_sign_encrypt(false)
+            .with_restricted(false)
+            .build()
+            .unwrap();
+
+        let public = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(object_attributes)
+            .with_ecc_parameters(ecc_parms)
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .unwrap();
+
+        let create_primary_result = context
+            .create_primary(Hierarchy::Owner, public, Some(key_auth), None, None, None)
+            .unwrap();
+        let key_handle = create_primary_result.key_handle;
+        let in_qs_b = match create_primary_result.out_public {
+            Public::Ecc { unique, .. } => unique,
+            _ => panic!("Wrong Public type"),
+        };
+
+        let (_, counter) = context.ec_ephemeral(EccCurve::NistP256).unwrap();
+        let (in_qe_b, _) = context.ec_ephemeral(EccCurve::NistP256).unwrap();
+
+        let _ = context
+            .zgen_2phase(
+                key_handle,
+                in_qs_b,
+                in_qe_b,
+                EccKeyExchangeAlgorithm::EcDh,
+                counter,
+            )
+            .unwrap();
+    }
+}
+
+mod test_ecc_parameters {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        interface_types::ecc::EccCurve,
+        structures::{EccScheme, KeyDerivationFunctionScheme},
+    };
+
+    #[test]
+    fn test_ecc_parameters_nist_p256() {
+        let mut context = create_ctx_without_session();
+        let parameters = context
+            .ecc_parameters(EccCurve::NistP256)
+            .expect("Call to ecc_parameters failed");
+
+        let expected_p: [u8; 32] = [
+            0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff,
+        ];
+        let expected_n: [u8; 32] = [
+            0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2,
+            0xfc, 0x63, 0x25, 0x51,
+        ];
+        assert_eq!(parameters.ecc_curve(), EccCurve::NistP256);
+        assert_eq!(parameters.key_size(), 256);
+        assert_eq!(
+            parameters.key_derivation_function_scheme(),
+            KeyDerivationFunctionScheme::Null
+        );
+        assert_eq!(parameters.ecc_scheme(), EccScheme::Null);
+        assert_eq!(parameters.p().as_bytes(), expected_p);
+        assert_eq!(parameters.n().as_bytes(), expected_n);
+        assert_eq!(parameters.g_x().len(), 32);
+        assert_eq!(parameters.g_y().len(), 32);
+    }
+}
@@ -1,2 +1,25 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+#[cfg(has_esys_ac_commands)]
+mod test_ac_send {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        handles::{AttachedComponentTpmHandle, ObjectHandle},
+        interface_types::resource_handles::{AttachedComponent, NvAuth},
+        structures::MaxBuffer,
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_ac_send_without_session() {
+        let mut context = create_ctx_without_session();
+
+        let ac = AttachedComponent::from(AttachedComponentTpmHandle::new(0x9000_0000).unwrap());
+        assert_eq!(
+            context
+                .ac_send(ObjectHandle::Null, NvAuth::Owner, ac, MaxBuffer::default())
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
@@ -166,4 +166,294 @@ mod test_quote {
 
         assert_eq!(signature.algorithm(), SignatureSchemeAlgorithm::Null);
     }
+
+    #[cfg(has_esys_certify_x509)]
+    #[test]
+    fn certify_x509() {
+        use tss_esapi::abstraction::x509;
+
+        let mut context = create_ctx_with_session();
+        let sign_key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        // Name with a single common name attribute "TPM".
+        let name = [
+            0x30, 0x0e, 0x31, 0x0c, 0x30, 0x0a, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x03, 0x54,
+            0x50, 0x4d,
+        ];
+        // Validity from 2022-01-01 to 2032-01-01.
+        let validity = [
+            0x30, 0x1e, 0x17, 0x0d, 0x32, 0x32, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30, 0x30,
+            0x30, 0x30, 0x5a, 0x17, 0x0d, 0x33, 0x32, 0x30, 0x31, 0x30, 0x31, 0x30, 0x30, 0x30,
+            0x30, 0x30, 0x30, 0x5a,
+        ];
+        // Extensions containing a key usage extension with digital signature.
+        let extensions = [
+            0xa3, 0x0f, 0x30, 0x0d, 0x30, 0x0b, 0x06, 0x03, 0x55, 0x1d, 0x0f, 0x04, 0x04, 0x03,
+            0x02, 0x07, 0x80,
+        ];
+        let partial_certificate = x509::partial_certificate(&name, &validity, &name, &extensions)
+            .expect("Failed to create partial certificate");
+
+        let (added_to_certificate, tbs_digest, signature) = context
+            .execute_with_sessions(
+                (
+                    Some(AuthSession::Password),
+                    Some(AuthSession::Password),
+                    None,
+                ),
+                |ctx| {
+                    ctx.certify_x509(
+                        sign_key_handle.into(),
+                        sign_key_handle,
+                        SignatureScheme::Null,
+                        partial_certificate.clone(),
+                    )
+                },
+            )
+            .expect("Failed to certify object handle");
+
+        let tbs_certificate = x509::tbs_certificate(&partial_certificate, &added_to_certificate)
+            .expect("Failed to create TBS certificate");
+        let (digest, _) = context
+            .hash(
+                MaxBuffer::try_from(tbs_certificate).unwrap(),
+                HashingAlgorithm::Sha256,
+                Hierarchy::Null,
+            )
+            .expect("Failed to hash TBS certificate");
+        assert_eq!(digest, tbs_digest);
+
+        let certificate =
+            x509::certificate(&partial_certificate, &added_to_certificate, &signature)
+                .expect("Failed to create certificate");
+        assert_eq!(certificate[0], 0x30);
+    }
+}
+
+mod test_get_time {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        constants::StructureTag,
+        interface_types::{
+            algorithm::HashingAlgorithm, resource_handles::Hierarchy, session_handles::AuthSession,
+            structure_tags::AttestationType,
+        },
+        structures::{AttestInfo, Data, MaxBuffer, SignatureScheme, Ticket},
+        traits::Marshall,
+    };
+
+    #[test]
+    fn get_time() {
+        let mut context = create_ctx_with_session();
+        let qualifying_data = vec![0xff; 16];
+
+        let sign_key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        let (attest, signature) = context
+            .execute_with_sessions(
+                (
+                    Some(AuthSession::Password),
+                    Some(AuthSession::Password),
+                    None,
+                ),
+                |ctx| {
+                    ctx.get_time(
+                        sign_key_handle,
+                        Data::try_from(qualifying_data.clone()).unwrap(),
+                        SignatureScheme::Null,
+                    )
+                },
+            )
+            .expect("Failed to get time");
+
+        assert_eq!(attest.attestation_type(), AttestationType::Time);
+        assert_eq!(attest.extra_data().as_bytes(), qualifying_data);
+        match attest.attested() {
+            AttestInfo::Time { info } => {
+                assert_eq!(
+                    info.time_info().clock_info().reset_count(),
+                    attest.clock_info().reset_count()
+                );
+            }
+            _ => {
+                panic!("Attested did not contain the expected variant.")
+            }
+        }
+
+        let data = MaxBuffer::try_from(attest.marshall().unwrap())
+            .expect("Failed to get data buffer from attestation data");
+        let (digest, _) = context
+            .hash(data, HashingAlgorithm::Sha256, Hierarchy::Null)
+            .expect("Failed to hash data");
+        let ticket = context
+            .execute_with_nullauth_session(|ctx| {
+                ctx.verify_signature(sign_key_handle, digest, signature)
+            })
+            .expect("Failed to verify signature");
+        assert_eq!(ticket.tag(), StructureTag::Verified);
+    }
+}
+
+mod test_get_command_audit_digest {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        constants::CommandCode,
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{Hierarchy, Provision},
+            session_handles::AuthSession,
+            structure_tags::AttestationType,
+        },
+        structures::{CommandCodeList, Data, SignatureScheme},
+    };
+
+    #[test]
+    fn get_command_audit_digest() {
+        let mut context = create_ctx_with_session();
+        let qualifying_data = vec![0xff; 16];
+
+        let sign_key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Sha256,
+                CommandCodeList::new(),
+                CommandCodeList::new(),
+            )
+            .unwrap();
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Null,
+                CommandCodeList::try_from(vec![CommandCode::GetRandom]).unwrap(),
+                CommandCodeList::new(),
+            )
+            .unwrap();
+        let _ = context.get_random(16).unwrap();
+
+        let result = context.execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                ctx.get_command_audit_digest(
+                    sign_key_handle,
+                    Data::try_from(qualifying_data.clone()).unwrap(),
+                    SignatureScheme::Null,
+                )
+            },
+        );
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Null,
+                CommandCodeList::new(),
+                CommandCodeList::try_from(vec![CommandCode::GetRandom]).unwrap(),
+            )
+            .unwrap();
+        let (attest, _signature) = result.expect("Failed to get command audit digest");
+
+        assert_eq!(attest.attestation_type(), AttestationType::CommandAudit);
+        assert_eq!(attest.extra_data().as_bytes(), qualifying_data);
+        let command_audit_info = attest
+            .command_audit_info()
+            .expect("Attested did not contain the expected variant.");
+        assert_eq!(
+            command_audit_info.hashing_algorithm(),
+            HashingAlgorithm::Sha256
+        );
+        assert_eq!(command_audit_info.audit_digest().len(), 32);
+    }
+}
+
+mod test_get_session_audit_digest {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        attributes::SessionAttributesBuilder,
+        constants::SessionType,
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::Hierarchy,
+            session_handles::{AuthSession, HmacSession},
+            structure_tags::AttestationType,
+        },
+        structures::{AttestInfo, Data, SignatureScheme, SymmetricDefinition},
+    };
+
+    #[test]
+    fn get_session_audit_digest() {
+        let mut context = create_ctx_with_session();
+        let qualifying_data = vec![0xff; 16];
+
+        let sign_key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        let audit_session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Hmac,
+                SymmetricDefinition::Null,
+                HashingAlgorithm::Sha256,
+            )
+            .unwrap()
+            .unwrap();
+        let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+            .with_continue_session(true)
+            .with_audit(true)
+            .build();
+        context
+            .tr_sess_set_attributes(audit_session, session_attributes, session_attributes_mask)
+            .unwrap();
+        let _ = context
+            .execute_with_session(Some(audit_session), |ctx| ctx.get_random(16))
+            .unwrap();
+
+        let (attest, _signature) = context
+            .execute_with_sessions(
+                (
+                    Some(AuthSession::Password),
+                    Some(AuthSession::Password),
+                    None,
+                ),
+                |ctx| {
+                    ctx.get_session_audit_digest(
+                        sign_key_handle,
+                        HmacSession::try_from(audit_session).unwrap(),
+                        Data::try_from(qualifying_data.clone()).unwrap(),
+                        SignatureScheme::Null,
+                    )
+                },
+            )
+            .expect("Failed to get session audit digest");
+
+        assert_eq!(attest.attestation_type(), AttestationType::SessionAudit);
+        assert_eq!(attest.extra_data().as_bytes(), qualifying_data);
+        match attest.attested() {
+            AttestInfo::SessionAudit { info } => {
+                assert_eq!(info.session_digest().len(), 32);
+            }
+            _ => {
+                panic!("Attested did not contain the expected variant.")
+            }
+        }
+    }
 }
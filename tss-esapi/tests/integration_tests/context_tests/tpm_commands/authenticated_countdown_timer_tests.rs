@@ -1,2 +1,22 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+#[cfg(has_esys_act_set_timeout)]
+mod test_act_set_timeout {
+    use crate::common::create_ctx_without_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        handles::PermanentTpmHandle, interface_types::resource_handles::Act, Error,
+        WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_act_set_timeout_without_session() {
+        let mut context = create_ctx_without_session();
+
+        let act = Act::try_from(PermanentTpmHandle::AuthenticatedTimersFirst).unwrap();
+        assert_eq!(
+            context.act_set_timeout(act, 60).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
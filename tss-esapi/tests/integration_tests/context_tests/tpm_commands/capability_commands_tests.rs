@@ -38,3 +38,195 @@ mod test_get_capability {
         assert_ne!(year, 0);
     }
 }
+
+mod test_test_parms {
+    use crate::common::{create_ctx_without_session, decryption_key_pub};
+    use tss_esapi::{
+        interface_types::{algorithm::HashingAlgorithm, ecc::EccCurve},
+        structures::{EccScheme, HashScheme, PublicEccParametersBuilder, PublicParameters},
+    };
+
+    #[test]
+    fn test_supported_parameters() {
+        let mut context = create_ctx_without_session();
+
+        context
+            .test_parms(decryption_key_pub().parameters())
+            .expect("The TPM does not support the RSA decryption key parameters");
+
+        let ecc_parameters = PublicEccParametersBuilder::new_unrestricted_signing_key(
+            EccScheme::EcDsa(HashScheme::new(HashingAlgorithm::Sha256)),
+            EccCurve::NistP256,
+        )
+        .build()
+        .expect("Failed to build ECC parameters");
+        context
+            .test_parms(PublicParameters::Ecc(ecc_parameters))
+            .expect("The TPM does not support the ECC signing key parameters");
+    }
+
+    #[test]
+    fn test_unsupported_parameters() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: The BN P638 curve is not enabled in the TPM simulators used
+        // in the tests.
+        let ecc_parameters = PublicEccParametersBuilder::new_unrestricted_signing_key(
+            EccScheme::EcDsa(HashScheme::new(HashingAlgorithm::Sha256)),
+            EccCurve::BnP638,
+        )
+        .build()
+        .expect("Failed to build ECC parameters");
+        let _ = context
+            .test_parms(PublicParameters::Ecc(ecc_parameters))
+            .unwrap_err();
+    }
+}
+
+mod test_get_capabilities_iter {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        constants::{CapabilityType, CommandCode, PropertyTag},
+        structures::{CapabilityData, CapabilityItem},
+    };
+
+    #[test]
+    fn test_get_commands() {
+        let mut context = create_ctx_without_session();
+
+        let mut expected_commands = Vec::new();
+        let mut property = 0;
+        loop {
+            let (capability_data, more_data) = context
+                .get_capability(CapabilityType::Command, property, 1)
+                .unwrap();
+            let command_code_attributes = match capability_data {
+                CapabilityData::Commands(data) => data[0],
+                _ => panic!("Invalid capability data returned"),
+            };
+            expected_commands.push(command_code_attributes);
+            if !more_data {
+                break;
+            }
+            property = u32::from(command_code_attributes.command_index()) + 1;
+        }
+
+        let commands = context
+            .get_capabilities_iter(CapabilityType::Command, 0)
+            .map(|item| match item.unwrap() {
+                CapabilityItem::Command(command_code_attributes) => command_code_attributes,
+                _ => panic!("Invalid capability item returned"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(expected_commands, commands);
+        assert!(commands
+            .iter()
+            .any(|cca| cca.command_index() == u32::from(CommandCode::GetCapability) as u16));
+    }
+
+    #[test]
+    fn test_get_tpm_properties() {
+        let mut context = create_ctx_without_session();
+
+        let properties = context
+            .get_capabilities_iter(
+                CapabilityType::TpmProperties,
+                PropertyTag::FamilyIndicator.into(),
+            )
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert!(properties.iter().any(|item| matches!(
+            item,
+            CapabilityItem::TpmProperty(tagged_property)
+                if tagged_property.property() == PropertyTag::Revision
+        )));
+        assert!(properties
+            .windows(2)
+            .all(|items| items[0].property() < items[1].property()));
+    }
+}
+
+mod test_pcr_properties {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        constants::{CapabilityType, PcrPropertyTag},
+        structures::{CapabilityData, PcrSlot},
+    };
+
+    #[test]
+    fn test_pcr_properties() {
+        let mut context = create_ctx_without_session();
+
+        let pcr_properties = context.pcr_properties().unwrap();
+        let (capability_data, _) = context
+            .get_capability(
+                CapabilityType::PcrProperties,
+                PcrPropertyTag::Save.into(),
+                1,
+            )
+            .unwrap();
+        let first_property = match capability_data {
+            CapabilityData::PcrProperties(data) => data[0],
+            _ => panic!("Invalid capability data returned"),
+        };
+        assert_eq!(Some(&first_property), pcr_properties.first());
+        // All the PCRs can be extended from locality 0 on the simulators.
+        assert!(pcr_properties
+            .find(PcrPropertyTag::ExtendL0)
+            .expect("Missing ExtendL0 property")
+            .selected_pcrs()
+            .contains(&PcrSlot::Slot0));
+    }
+}
+
+#[cfg(has_tpms_act_data)]
+mod test_act_capabilities {
+    use crate::common::create_ctx_without_session;
+
+    #[test]
+    fn test_act_capabilities() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: The simulators used in the tests may not implement
+        // any ACT, so only the consistency of the data is checked.
+        if let Ok(act_capabilities) = context.act_capabilities() {
+            for act_data in act_capabilities.iter() {
+                assert_eq!(Some(act_data), act_capabilities.find(act_data.act()));
+            }
+        }
+    }
+}
+
+mod test_supports {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        constants::{AlgorithmIdentifier, CommandCode},
+        interface_types::algorithm::HashingAlgorithm,
+    };
+
+    #[test]
+    fn test_supports_command() {
+        let mut context = create_ctx_without_session();
+
+        assert!(context
+            .supports_command(CommandCode::GetCapability)
+            .unwrap());
+        // The second call uses the cached values.
+        assert!(context.supports_command(CommandCode::GetRandom).unwrap());
+    }
+
+    #[test]
+    fn test_supports_algorithm() {
+        let mut context = create_ctx_without_session();
+
+        assert!(context
+            .supports_algorithm(HashingAlgorithm::Sha256)
+            .unwrap());
+        assert!(context
+            .supports_algorithm(AlgorithmIdentifier::Rsa)
+            .unwrap());
+        assert!(!context
+            .supports_algorithm(AlgorithmIdentifier::Error)
+            .unwrap());
+    }
+}
@@ -1,2 +1,61 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_read_clock {
+    use crate::common::create_ctx_without_session;
+
+    #[test]
+    fn test_read_clock() {
+        let mut context = create_ctx_without_session();
+
+        let first = context.read_clock().unwrap();
+        let second = context.read_clock().unwrap();
+        assert!(second.clock_info().clock() >= first.clock_info().clock());
+        assert_eq!(
+            first.clock_info().reset_count(),
+            second.clock_info().reset_count()
+        );
+    }
+}
+
+mod test_clock_set {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::interface_types::resource_handles::Provision;
+
+    #[test]
+    fn test_clock_set() {
+        let mut context = create_ctx_with_session();
+
+        let clock = context.read_clock().unwrap().clock_info().clock();
+        let new_time = clock + 60_000;
+        context.clock_set(Provision::Owner, new_time).unwrap();
+        assert!(context.read_clock().unwrap().clock_info().clock() >= new_time);
+    }
+
+    #[test]
+    fn test_clock_set_backwards() {
+        let mut context = create_ctx_with_session();
+
+        let clock = context.read_clock().unwrap().clock_info().clock();
+        let _ = context.clock_set(Provision::Owner, clock / 2).unwrap_err();
+    }
+}
+
+mod test_clock_rate_adjust {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::{constants::ClockAdjust, interface_types::resource_handles::Provision};
+
+    #[test]
+    fn test_clock_rate_adjust() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .clock_rate_adjust(Provision::Owner, ClockAdjust::FineFaster)
+            .unwrap();
+        context
+            .clock_rate_adjust(Provision::Owner, ClockAdjust::FineSlower)
+            .unwrap();
+        context
+            .clock_rate_adjust(Provision::Platform, ClockAdjust::NoChange)
+            .unwrap();
+    }
+}
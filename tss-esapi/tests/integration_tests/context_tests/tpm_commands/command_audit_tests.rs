@@ -1,2 +1,61 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_set_command_code_audit_status {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        constants::{CapabilityType, CommandCode},
+        interface_types::{algorithm::HashingAlgorithm, resource_handles::Provision},
+        structures::{CapabilityData, CommandCodeList},
+        Context,
+    };
+
+    fn audited_commands(context: &mut Context) -> CommandCodeList {
+        let (capability_data, _) = context
+            .get_capability(CapabilityType::AuditCommands, 0, 80)
+            .unwrap();
+        match capability_data {
+            CapabilityData::AuditCommands(list) => list,
+            _ => panic!("Got wrong type of capability data"),
+        }
+    }
+
+    #[test]
+    fn test_set_command_code_audit_status() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Null,
+                CommandCodeList::try_from(vec![CommandCode::GetRandom]).unwrap(),
+                CommandCodeList::new(),
+            )
+            .unwrap();
+        assert!(audited_commands(&mut context).contains(&CommandCode::GetRandom));
+
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Null,
+                CommandCodeList::new(),
+                CommandCodeList::try_from(vec![CommandCode::GetRandom]).unwrap(),
+            )
+            .unwrap();
+        assert!(!audited_commands(&mut context).contains(&CommandCode::GetRandom));
+    }
+
+    #[test]
+    fn test_set_command_code_audit_algorithm() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .set_command_code_audit_status(
+                Provision::Owner,
+                HashingAlgorithm::Sha256,
+                CommandCodeList::new(),
+                CommandCodeList::new(),
+            )
+            .unwrap();
+    }
+}
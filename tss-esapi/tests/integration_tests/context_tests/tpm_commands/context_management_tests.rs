@@ -105,6 +105,147 @@ mod test_ctx_load {
     }
 }
 
+mod test_session_ctx_save_load {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::{
+        constants::SessionType,
+        interface_types::{algorithm::HashingAlgorithm, session_handles::AuthSession},
+        structures::SymmetricDefinition,
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_session_ctx_save_load() {
+        let mut context = create_ctx_without_session();
+        let session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Hmac,
+                SymmetricDefinition::AES_128_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .unwrap()
+            .unwrap();
+        context.set_sessions((Some(session), None, None));
+
+        let saved_session = context.session_context_save(session).unwrap();
+        assert_eq!(saved_session.session_type(), SessionType::Hmac);
+        assert_eq!(saved_session.hashing_algorithm(), HashingAlgorithm::Sha256);
+        // The saved session is no longer used by the context.
+        assert_eq!(context.sessions(), (None, None, None));
+
+        let session = context.session_context_load(saved_session).unwrap();
+        let _ = context
+            .execute_with_session(Some(session), |ctx| ctx.get_random(16))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_password_session_ctx_save() {
+        let mut context = create_ctx_without_session();
+        assert!(matches!(
+            context.session_context_save(AuthSession::Password),
+            Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+        ));
+    }
+}
+
+mod test_key_ctx_save_load {
+    use crate::common::{
+        create_ctx_with_session, create_ctx_without_session, decryption_key_pub, signing_key_pub,
+    };
+    use tss_esapi::{
+        constants::SessionType,
+        handles::KeyHandle,
+        interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+        structures::{Auth, SymmetricDefinition},
+        traits::{Marshall, UnMarshall},
+        utils::TpmsContext,
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_key_ctx_save_load_across_contexts() {
+        let mut context = create_ctx_with_session();
+        let key_auth = context.get_random(16).unwrap();
+
+        let prim_key_handle = context
+            .create_primary(
+                Hierarchy::Owner,
+                decryption_key_pub(),
+                Some(Auth::from_bytes(key_auth.as_bytes()).unwrap()),
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle;
+        let result = context
+            .create(
+                prim_key_handle,
+                signing_key_pub(),
+                Some(Auth::from_bytes(key_auth.as_bytes()).unwrap()),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let key_handle = context
+            .load(prim_key_handle, result.out_private, result.out_public)
+            .unwrap();
+        let (expected_public, expected_name, _) = context.read_public(key_handle).unwrap();
+
+        let marshalled_context = context
+            .key_context_save(key_handle)
+            .unwrap()
+            .marshall()
+            .unwrap();
+        drop(context);
+
+        // Load the key in another context, without its parent.
+        let mut context = create_ctx_with_session();
+        let key_ctx = TpmsContext::unmarshall(&marshalled_context).unwrap();
+        let key_handle = context.key_context_load(key_ctx).unwrap();
+        let (actual_public, actual_name, _) = context.read_public(key_handle).unwrap();
+        assert_eq!(expected_public, actual_public);
+        assert_eq!(expected_name, actual_name);
+    }
+
+    #[test]
+    fn test_key_ctx_save_not_transient() {
+        let mut context = create_ctx_without_session();
+        assert_eq!(
+            context.key_context_save(KeyHandle::Null).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+
+    #[test]
+    fn test_key_ctx_load_session_context() {
+        let mut context = create_ctx_without_session();
+        let session = context
+            .start_auth_session(
+                None,
+                None,
+                None,
+                SessionType::Hmac,
+                SymmetricDefinition::AES_128_CFB,
+                HashingAlgorithm::Sha256,
+            )
+            .unwrap()
+            .unwrap();
+        let saved_session = context.session_context_save(session).unwrap();
+        assert_eq!(
+            context
+                .key_context_load(saved_session.context().clone())
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+}
+
 mod test_flush_context {
     use crate::common::{create_ctx_with_session, decryption_key_pub, signing_key_pub};
     use tss_esapi::{interface_types::resource_handles::Hierarchy, structures::Auth};
@@ -282,3 +423,92 @@ mod test_evict_control {
         assert_ne!(retrieved_persistent_handle, ObjectHandle::None);
     }
 }
+
+mod test_flush_all {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session, decryption_key_pub};
+    use tss_esapi::{
+        constants::{
+            tss::{TPM2_LOADED_SESSION_FIRST, TPM2_TRANSIENT_FIRST},
+            CapabilityType, SessionType,
+        },
+        interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
+        structures::{CapabilityData, SymmetricDefinition},
+        tss2_esys::TPM2_HANDLE,
+        Context, Error, WrapperErrorKind,
+    };
+
+    fn handle_count(context: &mut Context, property: TPM2_HANDLE) -> usize {
+        let (capability_data, _) = context
+            .execute_without_session(|ctx| {
+                ctx.get_capability(CapabilityType::Handles, property, 64)
+            })
+            .expect("Failed to get the handles");
+        match capability_data {
+            CapabilityData::Handles(handles) => handles
+                .iter()
+                .filter(|&&handle| {
+                    TPM2_HANDLE::from(handle).to_be_bytes()[0] == property.to_be_bytes()[0]
+                })
+                .count(),
+            _ => panic!("Unexpected capability data"),
+        }
+    }
+
+    #[test]
+    fn test_flush_all_transient() {
+        let mut context = create_ctx_with_session();
+        let key_handles = (0..2)
+            .map(|_| {
+                context
+                    .create_primary(
+                        Hierarchy::Owner,
+                        decryption_key_pub(),
+                        None,
+                        None,
+                        None,
+                        None,
+                    )
+                    .expect("Failed to create primary key")
+                    .key_handle
+            })
+            .collect::<Vec<_>>();
+
+        let flushed = context
+            .flush_all_transient()
+            .expect("Failed to flush the transient objects");
+        assert!(flushed >= 2);
+        assert_eq!(handle_count(&mut context, TPM2_TRANSIENT_FIRST), 0);
+        // The handles of the context have been flushed with the objects.
+        for key_handle in key_handles {
+            assert_eq!(
+                context.transient_object(key_handle).unwrap_err(),
+                Error::WrapperError(WrapperErrorKind::InvalidHandleState)
+            );
+        }
+    }
+
+    #[test]
+    fn test_flush_all_sessions() {
+        let mut context = create_ctx_without_session();
+        for _ in 0..2 {
+            let _ = context
+                .start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Hmac,
+                    SymmetricDefinition::AES_128_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+                .expect("Failed to start the session")
+                .expect("Received invalid handle");
+        }
+
+        let flushed = context
+            .flush_all_sessions()
+            .expect("Failed to flush the sessions");
+        assert!(flushed >= 2);
+        assert_eq!(context.sessions(), (None, None, None));
+        assert_eq!(handle_count(&mut context, TPM2_LOADED_SESSION_FIRST), 0);
+    }
+}
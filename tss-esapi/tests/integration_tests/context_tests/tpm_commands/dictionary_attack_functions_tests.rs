@@ -1,2 +1,50 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_dictionary_attack_lock_reset {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::interface_types::resource_handles::Lockout;
+
+    #[test]
+    fn test_dictionary_attack_lock_reset() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .dictionary_attack_lock_reset(Lockout::Lockout)
+            .unwrap();
+    }
+}
+
+mod test_dictionary_attack_parameters {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::{constants::PropertyTag, interface_types::resource_handles::Lockout};
+
+    #[test]
+    fn test_dictionary_attack_parameters() {
+        let mut context = create_ctx_with_session();
+
+        let max_tries = context
+            .get_tpm_property(PropertyTag::MaxAuthFail)
+            .unwrap()
+            .unwrap();
+        let recovery_time = context
+            .get_tpm_property(PropertyTag::LockoutInterval)
+            .unwrap()
+            .unwrap();
+        let lockout_recovery = context
+            .get_tpm_property(PropertyTag::LockoutRecovery)
+            .unwrap()
+            .unwrap();
+
+        context
+            .dictionary_attack_parameters(Lockout::Lockout, 5, 60, 120)
+            .unwrap();
+        context
+            .dictionary_attack_parameters(
+                Lockout::Lockout,
+                max_tries,
+                recovery_time,
+                lockout_recovery,
+            )
+            .unwrap();
+    }
+}
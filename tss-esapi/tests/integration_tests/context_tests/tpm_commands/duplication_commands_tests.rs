@@ -303,3 +303,390 @@ mod test_duplicate {
         eprintln!("P: {:?}", private);
     }
 }
+
+mod test_rewrap {
+    use crate::common::{
+        create_ctx_with_session, create_ctx_without_session, start_policy_session,
+    };
+
+    use tss_esapi::attributes::{ObjectAttributesBuilder, SessionAttributesBuilder};
+    use tss_esapi::constants::{CommandCode, SessionType};
+    use tss_esapi::handles::ObjectHandle;
+    use tss_esapi::interface_types::{
+        algorithm::{HashingAlgorithm, PublicAlgorithm},
+        ecc::EccCurve,
+        resource_handles::Hierarchy,
+        session_handles::PolicySession,
+    };
+    use tss_esapi::structures::{
+        EccPoint, EccScheme, KeyDerivationFunctionScheme, Public, PublicBuilder,
+        PublicEccParametersBuilder, SymmetricDefinitionObject,
+    };
+
+    fn parent_public() -> Public {
+        let parent_object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_sign_encrypt(false)
+            .with_restricted(true)
+            .build()
+            .expect("Attributes to be valid");
+
+        PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(parent_object_attributes)
+            .with_ecc_parameters(
+                PublicEccParametersBuilder::new()
+                    .with_ecc_scheme(EccScheme::Null)
+                    .with_curve(EccCurve::NistP256)
+                    .with_is_signing_key(false)
+                    .with_is_decryption_key(true)
+                    .with_restricted(true)
+                    .with_symmetric(SymmetricDefinitionObject::AES_128_CFB)
+                    .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+                    .build()
+                    .expect("Params to be valid"),
+            )
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .expect("public to be valid")
+    }
+
+    fn start_duplicate_policy_session(
+        context: &mut tss_esapi::Context,
+        session_type: SessionType,
+    ) -> PolicySession {
+        let policy_session = start_policy_session(context, session_type);
+        let (session_attributes, session_attributes_mask) = SessionAttributesBuilder::new()
+            .with_decrypt(true)
+            .with_encrypt(true)
+            .build();
+        context
+            .tr_sess_set_attributes(
+                policy_session.into(),
+                session_attributes,
+                session_attributes_mask,
+            )
+            .expect("tr_sess_set_attributes call failed");
+        context
+            .policy_command_code(policy_session, CommandCode::Duplicate)
+            .expect("Policy command code");
+        policy_session
+    }
+
+    #[test]
+    fn test_duplicate_rewrap_and_import() {
+        // Compute a policy digest that allows the object to be duplicated.
+        let mut context = create_ctx_without_session();
+        let trial_session = start_duplicate_policy_session(&mut context, SessionType::Trial);
+        let digest = context
+            .policy_get_digest(trial_session)
+            .expect("Could retrieve digest");
+        drop(context);
+
+        let mut context = create_ctx_with_session();
+
+        // The intermediate parent to which the object is first duplicated.
+        let intermediate_parent_handle: ObjectHandle = context
+            .create_primary(Hierarchy::Owner, parent_public(), None, None, None, None)
+            .unwrap()
+            .key_handle
+            .into();
+        // The final parent under which the object is imported.
+        let new_parent_handle: ObjectHandle = context
+            .create_primary(
+                Hierarchy::Endorsement,
+                parent_public(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle
+            .into();
+
+        let object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(false)
+            .with_fixed_parent(false)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_sign_encrypt(true)
+            .with_restricted(false)
+            .build()
+            .expect("Attributes to be valid");
+
+        let public_child = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(object_attributes)
+            .with_auth_policy(digest)
+            .with_ecc_parameters(
+                PublicEccParametersBuilder::new()
+                    .with_ecc_scheme(EccScheme::Null)
+                    .with_curve(EccCurve::NistP256)
+                    .with_is_signing_key(false)
+                    .with_is_decryption_key(true)
+                    .with_restricted(false)
+                    .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+                    .build()
+                    .expect("Params to be valid"),
+            )
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .expect("public to be valid");
+
+        let parent_of_object_to_duplicate_handle = context
+            .create_primary(Hierarchy::Owner, parent_public(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        let result = context
+            .create(
+                parent_of_object_to_duplicate_handle,
+                public_child,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+
+        let object_to_duplicate_handle: ObjectHandle = context
+            .load(
+                parent_of_object_to_duplicate_handle,
+                result.out_private,
+                result.out_public,
+            )
+            .unwrap()
+            .into();
+
+        let (public, object_name, _) = context
+            .read_public(object_to_duplicate_handle.into())
+            .unwrap();
+
+        let hmac_sessions = context.sessions();
+        context.set_sessions((None, None, None));
+        let policy_session = start_duplicate_policy_session(&mut context, SessionType::Policy);
+        context.set_sessions((Some(policy_session.into()), None, None));
+
+        let (_, duplicate, secret) = context
+            .duplicate(
+                object_to_duplicate_handle,
+                intermediate_parent_handle,
+                None,
+                SymmetricDefinitionObject::Null,
+            )
+            .unwrap();
+
+        context.set_sessions(hmac_sessions);
+
+        // Move the outer wrapper from the intermediate parent to the new parent.
+        let (rewrapped_duplicate, rewrapped_secret) = context
+            .rewrap(
+                intermediate_parent_handle,
+                new_parent_handle,
+                duplicate,
+                object_name,
+                secret,
+            )
+            .unwrap();
+
+        let private = context
+            .import(
+                new_parent_handle,
+                None,
+                public.clone(),
+                rewrapped_duplicate,
+                rewrapped_secret,
+                SymmetricDefinitionObject::Null,
+            )
+            .unwrap();
+
+        let _ = context
+            .load(new_parent_handle.into(), private, public)
+            .expect("Failed to load the imported object");
+    }
+}
+
+mod test_duplicate_to_other_parent {
+    use crate::common::{create_ctx_with_session, start_policy_session};
+    use std::convert::TryInto;
+    use tss_esapi::attributes::ObjectAttributesBuilder;
+    use tss_esapi::constants::SessionType;
+    use tss_esapi::handles::{ObjectHandle, SessionHandle};
+    use tss_esapi::interface_types::{
+        algorithm::{HashingAlgorithm, PublicAlgorithm},
+        ecc::EccCurve,
+        resource_handles::Hierarchy,
+        session_handles::PolicySession,
+    };
+    use tss_esapi::structures::{
+        EccPoint, EccScheme, KeyDerivationFunctionScheme, Name, Public, PublicBuilder,
+        PublicEccParametersBuilder, SymmetricDefinitionObject,
+    };
+    use tss_esapi::Context;
+
+    fn parent_public() -> Public {
+        let parent_object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_sign_encrypt(false)
+            .with_restricted(true)
+            .build()
+            .expect("Attributes to be valid");
+
+        PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(parent_object_attributes)
+            .with_ecc_parameters(
+                PublicEccParametersBuilder::new()
+                    .with_ecc_scheme(EccScheme::Null)
+                    .with_curve(EccCurve::NistP256)
+                    .with_is_signing_key(false)
+                    .with_is_decryption_key(true)
+                    .with_restricted(true)
+                    .with_symmetric(SymmetricDefinitionObject::AES_128_CFB)
+                    .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+                    .build()
+                    .expect("Params to be valid"),
+            )
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .expect("public to be valid")
+    }
+
+    fn start_duplication_select_policy_session(
+        context: &mut Context,
+        session_type: SessionType,
+        object_name: Name,
+        new_parent_name: Name,
+    ) -> PolicySession {
+        let policy_session = start_policy_session(context, session_type);
+        context.execute_without_session(|ctx| {
+            ctx.policy_duplication_select(policy_session, object_name, new_parent_name, false)
+                .expect("Policy duplication select");
+        });
+        policy_session
+    }
+
+    #[test]
+    fn test_duplicate_to_other_parent() {
+        let mut context = create_ctx_with_session();
+
+        let new_parent_handle: ObjectHandle = context
+            .create_primary(Hierarchy::Owner, parent_public(), None, None, None, None)
+            .unwrap()
+            .key_handle
+            .into();
+        let new_parent_name = context.read_public(new_parent_handle.into()).unwrap().1;
+        // The primary key of another hierarchy has another name.
+        let other_parent_handle: ObjectHandle = context
+            .create_primary(
+                Hierarchy::Endorsement,
+                parent_public(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle
+            .into();
+
+        // The object name is not included in the policy digest, so it
+        // can be computed before the object is created.
+        let trial_session = start_duplication_select_policy_session(
+            &mut context,
+            SessionType::Trial,
+            Vec::<u8>::new().try_into().unwrap(),
+            new_parent_name.clone(),
+        );
+        let digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Could retrieve digest");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+
+        let object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(false)
+            .with_fixed_parent(false)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(true)
+            .with_sign_encrypt(true)
+            .with_restricted(false)
+            .build()
+            .expect("Attributes to be valid");
+        let public_child = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(object_attributes)
+            .with_auth_policy(digest)
+            .with_ecc_parameters(
+                PublicEccParametersBuilder::new()
+                    .with_ecc_scheme(EccScheme::Null)
+                    .with_curve(EccCurve::NistP256)
+                    .with_is_signing_key(false)
+                    .with_is_decryption_key(true)
+                    .with_restricted(false)
+                    .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+                    .build()
+                    .expect("Params to be valid"),
+            )
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .expect("public to be valid");
+        let result = context
+            .create(
+                new_parent_handle.into(),
+                public_child,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let object_to_duplicate_handle: ObjectHandle = context
+            .load(
+                new_parent_handle.into(),
+                result.out_private,
+                result.out_public,
+            )
+            .unwrap()
+            .into();
+        let object_name = context
+            .read_public(object_to_duplicate_handle.into())
+            .unwrap()
+            .1;
+
+        // The policy only allows the duplication to the selected new parent.
+        let policy_session = start_duplication_select_policy_session(
+            &mut context,
+            SessionType::Policy,
+            object_name,
+            new_parent_name,
+        );
+        let duplicate_result = context.execute_with_session(Some(policy_session.into()), |ctx| {
+            ctx.duplicate(
+                object_to_duplicate_handle,
+                other_parent_handle,
+                None,
+                SymmetricDefinitionObject::Null,
+            )
+        });
+        let _ = context.flush_context(SessionHandle::from(policy_session).into());
+
+        let _ = duplicate_result.expect_err("Duplication to another parent succeeded");
+    }
+}
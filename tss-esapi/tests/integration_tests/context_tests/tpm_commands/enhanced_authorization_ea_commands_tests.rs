@@ -7,13 +7,18 @@ mod test_policy_signed {
         time::Duration,
     };
     use tss_esapi::{
+        abstraction::policy,
         attributes::SessionAttributesBuilder,
         constants::SessionType,
+        handles::SessionHandle,
         interface_types::{
             algorithm::HashingAlgorithm, resource_handles::Hierarchy,
             session_handles::PolicySession,
         },
-        structures::{Digest, Nonce, PublicKeyRsa, RsaSignature, Signature, SymmetricDefinition},
+        structures::{
+            Digest, MaxBuffer, Nonce, PolicyExpiration, PublicKeyRsa, RsaSignature, Signature,
+            SignatureScheme, SymmetricDefinition,
+        },
     };
     #[test]
     fn test_policy_signed() {
@@ -74,11 +79,85 @@ mod test_policy_signed {
                 nonce_tpm,
                 cp_hash_a,
                 policy_ref,
-                Some(Duration::from_secs(3600)),
+                PolicyExpiration::After(Duration::from_secs(3600)),
                 signature,
             )
             .expect("Call to policy_signed failed");
     }
+
+    #[test]
+    fn test_policy_signed_with_signature() {
+        let mut context = create_ctx_with_session();
+
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+
+        let policy_ref = Nonce::try_from(vec![1, 2, 3]).expect("Failed to convert data into Nonce");
+        let signed_data = policy::signed_authorization_data(
+            &Nonce::default(),
+            PolicyExpiration::Never,
+            &Digest::default(),
+            &policy_ref,
+        )
+        .expect("Failed to create the signed authorization data");
+        let (a_hash, validation) = context
+            .hash(
+                MaxBuffer::try_from(signed_data).expect("Failed to convert data into MaxBuffer"),
+                HashingAlgorithm::Sha256,
+                Hierarchy::Owner,
+            )
+            .expect("Call to hash failed");
+        let signature = context
+            .sign(key_handle, a_hash, SignatureScheme::Null, validation)
+            .expect("Call to sign failed");
+
+        let mut policy_digests = Vec::new();
+        for session_type in [SessionType::Trial, SessionType::Policy] {
+            let policy_auth_session = context
+                .execute_without_session(|ctx| {
+                    ctx.start_auth_session(
+                        None,
+                        None,
+                        None,
+                        session_type,
+                        SymmetricDefinition::AES_256_CFB,
+                        HashingAlgorithm::Sha256,
+                    )
+                })
+                .expect("Start auth session failed")
+                .expect("Start auth session returned a NONE handle");
+            let policy_session = PolicySession::try_from(policy_auth_session)
+                .expect("Failed to convert auth session into policy session");
+
+            // The command does not require an authorization session.
+            let _ = context
+                .execute_without_session(|ctx| {
+                    ctx.policy_signed(
+                        policy_session,
+                        key_handle.into(),
+                        Nonce::default(),
+                        Digest::default(),
+                        policy_ref.clone(),
+                        PolicyExpiration::Never,
+                        signature.clone(),
+                    )
+                })
+                .expect("Call to policy_signed failed");
+            policy_digests.push(
+                context
+                    .execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+                    .expect("Call to policy_get_digest failed"),
+            );
+            context
+                .flush_context(SessionHandle::from(policy_session).into())
+                .expect("Call to flush_context failed");
+        }
+        // The signed authorization was accepted by the policy session, so
+        // the policy digest is the same as the one of the trial session.
+        assert_eq!(policy_digests[0], policy_digests[1]);
+    }
 }
 
 mod test_policy_secret {
@@ -141,6 +220,96 @@ mod test_policy_secret {
     }
 }
 
+mod test_policy_ticket {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy,
+        constants::SessionType,
+        interface_types::{
+            algorithm::HashingAlgorithm, resource_handles::Hierarchy,
+            session_handles::PolicySession,
+        },
+        structures::{
+            Digest, MaxBuffer, Nonce, PolicyExpiration, SignatureScheme, SymmetricDefinition,
+        },
+    };
+
+    #[test]
+    fn test_policy_ticket_with_null_ticket() {
+        let mut context = create_ctx_with_session();
+
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+        let (_, key_name, _) = context
+            .read_public(key_handle)
+            .expect("Call to read_public failed");
+
+        let signed_data = policy::signed_authorization_data(
+            &Nonce::default(),
+            PolicyExpiration::Never,
+            &Digest::default(),
+            &Nonce::default(),
+        )
+        .expect("Failed to create the signed authorization data");
+        let (a_hash, validation) = context
+            .hash(
+                MaxBuffer::try_from(signed_data).expect("Failed to convert data into MaxBuffer"),
+                HashingAlgorithm::Sha256,
+                Hierarchy::Owner,
+            )
+            .expect("Call to hash failed");
+        let signature = context
+            .sign(key_handle, a_hash, SignatureScheme::Null, validation)
+            .expect("Call to sign failed");
+
+        let policy_session = context
+            .execute_without_session(|ctx| {
+                ctx.start_auth_session(
+                    None,
+                    None,
+                    None,
+                    SessionType::Policy,
+                    SymmetricDefinition::AES_256_CFB,
+                    HashingAlgorithm::Sha256,
+                )
+            })
+            .expect("Start auth session failed")
+            .expect("Start auth session returned a NONE handle");
+        let policy_session = PolicySession::try_from(policy_session)
+            .expect("Failed to convert auth session into policy session");
+
+        // No ticket was requested, so a NULL ticket is returned.
+        let (timeout, ticket) = context
+            .execute_without_session(|ctx| {
+                ctx.policy_signed(
+                    policy_session,
+                    key_handle.into(),
+                    Nonce::default(),
+                    Digest::default(),
+                    Nonce::default(),
+                    PolicyExpiration::Never,
+                    signature,
+                )
+            })
+            .expect("Call to policy_signed failed");
+        let _ = context
+            .execute_without_session(|ctx| {
+                ctx.policy_ticket(
+                    policy_session,
+                    timeout,
+                    Digest::default(),
+                    Nonce::default(),
+                    &key_name,
+                    ticket,
+                )
+            })
+            .unwrap_err();
+    }
+}
+
 mod test_policy_or {
     use crate::common::{create_ctx_without_session, get_pcr_policy_digest};
     use std::convert::TryFrom;
@@ -340,6 +509,54 @@ mod test_policy_locality {
     }
 }
 
+mod test_policy_counter_timer {
+    use crate::common::{create_ctx_without_session, start_policy_session};
+    use tss_esapi::{
+        constants::{ArithmeticOperation, SessionType},
+        handles::SessionHandle,
+        structures::{CounterTimerConditionBuilder, CounterTimerField},
+    };
+
+    #[test]
+    fn test_policy_counter_timer() {
+        let mut context = create_ctx_without_session();
+        let reset_count = context
+            .read_clock()
+            .expect("Call to read_clock failed")
+            .clock_info()
+            .reset_count();
+
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+        let condition = CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::ResetCount)
+            .with_operation(ArithmeticOperation::Eq)
+            .with_value(reset_count.into())
+            .build()
+            .expect("Failed to build counter timer condition");
+        context
+            .policy_counter_timer(policy_session, condition)
+            .expect("Call to policy_counter_timer failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+
+        // The condition does not hold, so the TPM rejects the assertion.
+        let policy_session = start_policy_session(&mut context, SessionType::Policy);
+        let condition = CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::ResetCount)
+            .with_operation(ArithmeticOperation::Neq)
+            .with_value(reset_count.into())
+            .build()
+            .expect("Failed to build counter timer condition");
+        let _ = context
+            .policy_counter_timer(policy_session, condition)
+            .unwrap_err();
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+    }
+}
+
 mod test_policy_command_code {
     use crate::common::create_ctx_without_session;
     use std::convert::TryFrom;
@@ -385,13 +602,21 @@ mod test_policy_command_code {
 }
 
 mod test_policy_physical_presence {
-    use crate::common::create_ctx_without_session;
+    use crate::common::{
+        create_ctx_with_session, create_ctx_without_session, start_policy_session,
+    };
     use std::convert::TryFrom;
     use tss_esapi::{
-        attributes::SessionAttributesBuilder,
-        constants::SessionType,
-        interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
-        structures::SymmetricDefinition,
+        attributes::{NvIndexAttributesBuilder, SessionAttributesBuilder},
+        constants::{CommandCode, SessionType},
+        handles::{NvIndexTpmHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+            session_handles::PolicySession,
+        },
+        structures::{MaxNvBuffer, NvPublicBuilder, SymmetricDefinition},
+        Context, Error, WrapperErrorKind,
     };
     #[test]
     fn test_policy_physical_presence() {
@@ -426,6 +651,77 @@ mod test_policy_physical_presence {
             .policy_physical_presence(trial_policy_session)
             .unwrap();
     }
+
+    #[test]
+    fn test_policy_physical_presence_nv_write() {
+        fn start_physical_presence_policy_session(
+            context: &mut Context,
+            session_type: SessionType,
+        ) -> PolicySession {
+            let policy_session = start_policy_session(context, session_type);
+            context.execute_without_session(|ctx| {
+                ctx.policy_command_code(policy_session, CommandCode::NvWrite)
+                    .expect("Call to policy_command_code failed");
+                ctx.policy_physical_presence(policy_session)
+                    .expect("Call to policy_physical_presence failed");
+            });
+            policy_session
+        }
+
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x01500048).unwrap();
+
+        let trial_session =
+            start_physical_presence_policy_session(&mut context, SessionType::Trial);
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+
+        let nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_policy_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create nv index attributes");
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(nv_index_attributes)
+            .with_index_auth_policy(policy_digest)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic");
+        let nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, nv_public)
+            .expect("Call to nv_define_space failed");
+
+        let policy_session =
+            start_physical_presence_policy_session(&mut context, SessionType::Policy);
+        let result = context.execute_with_physical_presence(policy_session, |ctx| {
+            ctx.nv_write(
+                NvAuth::NvIndex(nv_index_handle),
+                nv_index_handle,
+                MaxNvBuffer::try_from(vec![1, 2, 3, 4]).unwrap(),
+                0,
+            )
+        });
+        let _ = context.flush_context(SessionHandle::from(policy_session).into());
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        // Whether physical presence is asserted depends on the platform, the
+        // write only fails if it is not.
+        if let Err(error) = result {
+            assert_eq!(
+                error,
+                Error::WrapperError(WrapperErrorKind::PhysicalPresenceRequired)
+            );
+        }
+    }
 }
 
 mod test_policy_cp_hash {
@@ -797,13 +1093,21 @@ mod test_policy_get_digest {
 }
 
 mod test_policy_nv_written {
-    use crate::common::create_ctx_without_session;
+    use crate::common::{
+        create_ctx_with_session, create_ctx_without_session, start_policy_session,
+    };
     use std::convert::TryFrom;
     use tss_esapi::{
-        attributes::SessionAttributesBuilder,
-        constants::SessionType,
-        interface_types::{algorithm::HashingAlgorithm, session_handles::PolicySession},
-        structures::SymmetricDefinition,
+        attributes::{NvIndexAttributesBuilder, SessionAttributesBuilder},
+        constants::{CommandCode, SessionType},
+        handles::{NvIndexTpmHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+            session_handles::PolicySession,
+        },
+        structures::{MaxNvBuffer, NvPublicBuilder, SymmetricDefinition},
+        Context,
     };
     #[test]
     fn test_policy_nv_written() {
@@ -839,6 +1143,75 @@ mod test_policy_nv_written {
             .policy_nv_written(trial_policy_session, true)
             .unwrap();
     }
+
+    #[test]
+    fn test_policy_nv_written_write_once() {
+        fn start_nv_written_policy_session(
+            context: &mut Context,
+            session_type: SessionType,
+        ) -> PolicySession {
+            let policy_session = start_policy_session(context, session_type);
+            context.execute_without_session(|ctx| {
+                ctx.policy_command_code(policy_session, CommandCode::NvWrite)
+                    .expect("Call to policy_command_code failed");
+                ctx.policy_nv_written(policy_session, false)
+                    .expect("Call to policy_nv_written failed");
+            });
+            policy_session
+        }
+
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x01500047).unwrap();
+
+        // The index can only be written while it has not been written.
+        let trial_session = start_nv_written_policy_session(&mut context, SessionType::Trial);
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+
+        let nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_policy_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create nv index attributes");
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(nv_index_attributes)
+            .with_index_auth_policy(policy_digest)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic");
+        let nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, nv_public)
+            .expect("Call to nv_define_space failed");
+
+        let mut nv_write_with_policy = |data: Vec<u8>| {
+            let policy_session = start_nv_written_policy_session(&mut context, SessionType::Policy);
+            let result = context.execute_with_session(Some(policy_session.into()), |ctx| {
+                ctx.nv_write(
+                    NvAuth::NvIndex(nv_index_handle),
+                    nv_index_handle,
+                    MaxNvBuffer::try_from(data).unwrap(),
+                    0,
+                )
+            });
+            let _ = context.flush_context(SessionHandle::from(policy_session).into());
+            result
+        };
+        let first_write_result = nv_write_with_policy(vec![1, 2, 3, 4]);
+        let second_write_result = nv_write_with_policy(vec![4, 3, 2, 1]);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        first_write_result.expect("First call to nv_write failed");
+        let _ = second_write_result.expect_err("Second call to nv_write succeeded");
+    }
 }
 
 mod test_policy_template {
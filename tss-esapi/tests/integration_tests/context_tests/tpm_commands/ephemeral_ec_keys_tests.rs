@@ -1,2 +1,87 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_ec_ephemeral {
+    use crate::common::create_ctx_without_session;
+    use tss_esapi::interface_types::ecc::EccCurve;
+
+    #[test]
+    fn test_ec_ephemeral() {
+        let mut context = create_ctx_without_session();
+        let (q_1, counter_1) = context
+            .ec_ephemeral(EccCurve::NistP256)
+            .expect("Call to ec_ephemeral failed");
+        let (q_2, counter_2) = context
+            .ec_ephemeral(EccCurve::NistP256)
+            .expect("Call to ec_ephemeral failed");
+        assert_ne!(q_1, q_2);
+        assert_ne!(counter_1, counter_2);
+    }
+}
+
+mod test_commit {
+    use crate::common::create_ctx_with_session;
+    use tss_esapi::{
+        attributes::ObjectAttributesBuilder,
+        interface_types::{
+            algorithm::{HashingAlgorithm, PublicAlgorithm},
+            ecc::EccCurve,
+            resource_handles::Hierarchy,
+        },
+        structures::{
+            EcDaaScheme, EccPoint, EccScheme, KeyDerivationFunctionScheme, PublicBuilder,
+            PublicEccParametersBuilder,
+        },
+    };
+
+    #[test]
+    fn test_commit() {
+        let mut context = create_ctx_with_session();
+
+        let ecc_parms = PublicEccParametersBuilder::new()
+            .with_ecc_scheme(EccScheme::EcDaa(EcDaaScheme::new(
+                HashingAlgorithm::Sha256,
+                0,
+            )))
+            .with_curve(EccCurve::BnP256)
+            .with_is_signing_key(true)
+            .with_is_decryption_key(false)
+            .with_restricted(false)
+            .with_key_derivation_function_scheme(KeyDerivationFunctionScheme::Null)
+            .build()
+            .expect("Failed to build ECC parameters");
+
+        let object_attributes = ObjectAttributesBuilder::new()
+            .with_fixed_tpm(true)
+            .with_fixed_parent(true)
+            .with_sensitive_data_origin(true)
+            .with_user_with_auth(true)
+            .with_decrypt(false)
+            .with_sign_encrypt(true)
+            .with_restricted(false)
+            .build()
+            .expect("Failed to build object attributes");
+
+        let public = PublicBuilder::new()
+            .with_public_algorithm(PublicAlgorithm::Ecc)
+            .with_name_hashing_algorithm(HashingAlgorithm::Sha256)
+            .with_object_attributes(object_attributes)
+            .with_ecc_parameters(ecc_parms)
+            .with_ecc_unique_identifier(EccPoint::default())
+            .build()
+            .expect("Failed to build public");
+
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, public, None, None, None, None)
+            .expect("Failed to create primary key")
+            .key_handle;
+
+        let (_, _, e, counter_1) = context
+            .commit(key_handle, None, None, None)
+            .expect("Call to commit failed");
+        assert!(!e.x().is_empty());
+        let (_, _, _, counter_2) = context
+            .commit(key_handle, None, None, None)
+            .expect("Call to commit failed");
+        assert_ne!(counter_1, counter_2);
+    }
+}
@@ -1,2 +1,63 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_field_upgrade_data {
+    use crate::common::create_ctx_without_session;
+    use std::convert::TryFrom;
+    use tss_esapi::structures::MaxBuffer;
+
+    #[test]
+    fn test_field_upgrade_data_without_upgrade_start() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: Software TPMs do not implement the field upgrade commands and
+        //  a hardware TPM rejects the data if the upgrade has not been started.
+        let _ = context
+            .field_upgrade_data(MaxBuffer::try_from(vec![0xFF; 16]).unwrap())
+            .unwrap_err();
+    }
+}
+
+mod test_field_upgrade_start {
+    use crate::common::{create_ctx_with_session, signatures, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        interface_types::resource_handles::{Hierarchy, Platform},
+        structures::Digest,
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_field_upgrade_start_without_session() {
+        let mut context = create_ctx_with_session();
+
+        let key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .unwrap()
+            .key_handle;
+        context.clear_sessions();
+        let result = context.field_upgrade_start(
+            Platform::Platform,
+            key_handle,
+            Digest::try_from(vec![0xA5; 32]).unwrap(),
+            signatures()[0].clone(),
+        );
+        context.flush_context(key_handle.into()).unwrap();
+        assert_eq!(
+            result.unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
+
+mod test_firmware_read {
+    use crate::common::create_ctx_without_session;
+
+    #[test]
+    fn test_firmware_read() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: Software TPMs do not implement TPM2_FirmwareRead. If this test
+        //  fails you are probably running it against a TPM that does.
+        let _ = context.firmware_read(0).unwrap_err();
+    }
+}
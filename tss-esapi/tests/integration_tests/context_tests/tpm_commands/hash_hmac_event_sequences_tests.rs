@@ -1,2 +1,112 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_hash_sequence {
+    use crate::common::create_ctx_without_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        interface_types::{
+            algorithm::HashingAlgorithm, resource_handles::Hierarchy, session_handles::AuthSession,
+        },
+        structures::{Auth, MaxBuffer, Ticket},
+    };
+
+    #[test]
+    fn test_hash_sequence_with_sha_256() {
+        let mut context = create_ctx_without_session();
+        let expected_hashed_data: [u8; 32] = [
+            0x6b, 0x38, 0x4d, 0x2b, 0xfb, 0x0e, 0x0d, 0xfb, 0x64, 0x89, 0xdb, 0xf4, 0xf8, 0xe9,
+            0xe5, 0x2f, 0x71, 0xee, 0xb1, 0x0d, 0x06, 0x4c, 0x56, 0x59, 0x70, 0xcd, 0xd9, 0x44,
+            0x43, 0x18, 0x5d, 0xc1,
+        ];
+        let expected_hierarchy = Hierarchy::Owner;
+        let sequence_handle = context
+            .hash_sequence_start(
+                HashingAlgorithm::Sha256,
+                Some(Auth::try_from(vec![1, 2, 3, 4]).unwrap()),
+            )
+            .unwrap();
+        let (actual_hashed_data, ticket) = context
+            .execute_with_session(Some(AuthSession::Password), |ctx| {
+                ctx.sequence_update(
+                    sequence_handle,
+                    MaxBuffer::try_from("There is ".as_bytes().to_vec()).unwrap(),
+                )?;
+                ctx.sequence_update(
+                    sequence_handle,
+                    MaxBuffer::try_from("no ".as_bytes().to_vec()).unwrap(),
+                )?;
+                ctx.sequence_complete(
+                    sequence_handle,
+                    MaxBuffer::try_from("spoon".as_bytes().to_vec()).unwrap(),
+                    expected_hierarchy,
+                )
+            })
+            .unwrap();
+        assert_eq!(&expected_hashed_data[..], &actual_hashed_data[..]);
+        assert_eq!(ticket.hierarchy(), expected_hierarchy);
+        assert_ne!(ticket.digest().len(), 0);
+    }
+
+    #[test]
+    fn test_hash_sequence_flush() {
+        let mut context = create_ctx_without_session();
+        let sequence_handle = context
+            .hash_sequence_start(HashingAlgorithm::Sha256, None)
+            .unwrap();
+        context
+            .execute_with_session(Some(AuthSession::Password), |ctx| {
+                ctx.sequence_update(
+                    sequence_handle,
+                    MaxBuffer::try_from(vec![0xff; 32]).unwrap(),
+                )
+            })
+            .unwrap();
+        context.flush_context(sequence_handle).unwrap();
+    }
+}
+
+mod test_event_sequence_complete {
+    use crate::common::create_ctx_without_session;
+    use sha2::{Digest as _, Sha256};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        interface_types::{algorithm::HashingAlgorithm, session_handles::AuthSession},
+        structures::MaxBuffer,
+    };
+
+    #[test]
+    fn test_event_sequence_complete_without_pcr() {
+        let mut context = create_ctx_without_session();
+        let sequence_handle = context
+            .hash_sequence_start(HashingAlgorithm::Null, None)
+            .unwrap();
+        let digest_values = context
+            .execute_with_sessions(
+                (
+                    Some(AuthSession::Password),
+                    Some(AuthSession::Password),
+                    None,
+                ),
+                |ctx| {
+                    ctx.sequence_update(
+                        sequence_handle,
+                        MaxBuffer::try_from("There is ".as_bytes().to_vec()).unwrap(),
+                    )?;
+                    ctx.event_sequence_complete(
+                        None,
+                        sequence_handle,
+                        MaxBuffer::try_from("no spoon".as_bytes().to_vec()).unwrap(),
+                    )
+                },
+            )
+            .unwrap();
+        assert!(!digest_values.is_empty());
+        assert_eq!(
+            digest_values
+                .get(HashingAlgorithm::Sha256)
+                .expect("Missing SHA256 digest")
+                .as_bytes(),
+            Sha256::digest(b"There is no spoon").as_slice()
+        );
+    }
+}
@@ -37,21 +37,61 @@ mod test_clear {
 
         context.clear(AuthHandle::Platform).unwrap();
     }
+
+    #[test]
+    fn test_clear_with_lockout_auth() {
+        let mut context = create_ctx_with_session();
+
+        context.clear(AuthHandle::Lockout).unwrap();
+    }
 }
 
 mod test_clear_control {
     use crate::common::create_ctx_with_session;
-    use tss_esapi::handles::AuthHandle;
+    use tss_esapi::{
+        constants::return_code::{TpmFormatOneError, TpmFormatZeroError},
+        error::{ReturnCode, TpmFormatZeroResponseCode, TpmResponseCode},
+        handles::AuthHandle,
+        Error,
+    };
+
+    fn is_disabled(error: Error) -> bool {
+        matches!(
+            error,
+            Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatZero(
+                TpmFormatZeroResponseCode::Error(response_code)
+            ))) if response_code.error_number() == TpmFormatZeroError::Disabled
+        )
+    }
+
     #[test]
     fn test_clear_control() {
         let mut context = create_ctx_with_session();
 
         context.clear(AuthHandle::Platform).unwrap();
         context.clear_control(AuthHandle::Platform, true).unwrap();
-        context.clear(AuthHandle::Platform).unwrap_err();
+        assert!(is_disabled(
+            context.clear(AuthHandle::Platform).unwrap_err()
+        ));
         context.clear_control(AuthHandle::Platform, false).unwrap();
         context.clear(AuthHandle::Platform).unwrap();
     }
+
+    #[test]
+    fn test_clear_control_with_lockout_auth() {
+        let mut context = create_ctx_with_session();
+
+        context.clear_control(AuthHandle::Lockout, true).unwrap();
+        assert!(is_disabled(context.clear(AuthHandle::Lockout).unwrap_err()));
+        // Only the platform can enable the command again.
+        assert!(matches!(
+            context.clear_control(AuthHandle::Lockout, false),
+            Err(Error::TssError(ReturnCode::Tpm(TpmResponseCode::FormatOne(response_code))))
+                if response_code.error_number() == TpmFormatOneError::AuthFail
+        ));
+        context.clear_control(AuthHandle::Platform, false).unwrap();
+        context.clear(AuthHandle::Lockout).unwrap();
+    }
 }
 
 mod test_change_auth {
@@ -104,6 +144,58 @@ mod test_change_auth {
             .unwrap();
     }
 
+    #[test]
+    fn test_change_key_auth() {
+        let mut context = create_ctx_with_session();
+
+        let prim_key_handle = context
+            .create_primary(
+                Hierarchy::Owner,
+                decryption_key_pub(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle;
+        let random_digest = context.get_random(16).unwrap();
+        let key_auth = Auth::from_bytes(random_digest.as_bytes()).unwrap();
+        let keyresult = context
+            .create(
+                prim_key_handle,
+                decryption_key_pub(),
+                Some(key_auth.clone()),
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+        let loaded_key = context
+            .load(
+                prim_key_handle,
+                keyresult.out_private,
+                keyresult.out_public.clone(),
+            )
+            .unwrap();
+        context.tr_set_auth(loaded_key.into(), key_auth).unwrap();
+
+        let random_digest = context.get_random(16).unwrap();
+        let new_key_auth = Auth::from_bytes(random_digest.as_bytes()).unwrap();
+        let (new_key_handle, _) = context
+            .change_key_auth(loaded_key, prim_key_handle, new_key_auth)
+            .unwrap();
+
+        // The new auth value is set on the new handle, so it can authorize the key.
+        let _ = context
+            .object_change_auth(
+                new_key_handle.into(),
+                prim_key_handle.into(),
+                Auth::default(),
+            )
+            .unwrap();
+    }
+
     #[test]
     fn test_hierarchy_change_auth() {
         let mut context = create_ctx_with_session();
@@ -122,4 +214,143 @@ mod test_change_auth {
             .hierarchy_change_auth(AuthHandle::Platform, Default::default())
             .unwrap();
     }
+
+    #[test]
+    fn test_hierarchy_change_auth_sets_tracked_auth() {
+        let mut context = create_ctx_with_session();
+
+        let random_digest = context.get_random(16).unwrap();
+        let new_auth = Auth::from_bytes(random_digest.as_bytes()).unwrap();
+
+        context
+            .hierarchy_change_auth(AuthHandle::Owner, new_auth)
+            .unwrap();
+        // The new auth value has been set on the hierarchy handle, so
+        // commands authorized by the owner hierarchy keep working.
+        let result = context.create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        );
+        context
+            .hierarchy_change_auth(AuthHandle::Owner, Default::default())
+            .unwrap();
+        let key_handle = result.unwrap().key_handle;
+        context.flush_context(key_handle.into()).unwrap();
+    }
+}
+
+mod test_hierarchy_control {
+    use crate::common::{create_ctx_with_session, decryption_key_pub};
+    use tss_esapi::{
+        handles::AuthHandle,
+        interface_types::resource_handles::{Enables, Hierarchy},
+    };
+
+    #[test]
+    fn test_hierarchy_control() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .hierarchy_control(AuthHandle::Platform, Enables::Endorsement, false)
+            .unwrap();
+        let result = context.create_primary(
+            Hierarchy::Endorsement,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        );
+        context
+            .hierarchy_control(AuthHandle::Platform, Enables::Endorsement, true)
+            .unwrap();
+        assert!(result.is_err());
+
+        let key_handle = context
+            .create_primary(
+                Hierarchy::Endorsement,
+                decryption_key_pub(),
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle;
+        context.flush_context(key_handle.into()).unwrap();
+    }
+}
+
+mod test_change_seeds {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session};
+    use tss_esapi::{interface_types::resource_handles::Platform, Error, WrapperErrorKind};
+
+    // Changing the seed invalidates the keys of the hierarchy, including
+    // the ones used by the other tests, so the test has to be run explicitly.
+    #[ignore]
+    #[test]
+    fn test_change_pps() {
+        let mut context = create_ctx_with_session();
+
+        context.change_pps(Platform::Platform).unwrap();
+    }
+
+    // Changing the seed invalidates the keys of the hierarchy, including
+    // the ones used by the other tests, so the test has to be run explicitly.
+    #[ignore]
+    #[test]
+    fn test_change_eps() {
+        let mut context = create_ctx_with_session();
+
+        context.change_eps(Platform::Platform).unwrap();
+    }
+
+    #[test]
+    fn test_change_seeds_without_session() {
+        let mut context = create_ctx_without_session();
+
+        assert_eq!(
+            context.change_pps(Platform::Platform).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+        assert_eq!(
+            context.change_eps(Platform::Platform).unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
+
+mod test_set_primary_policy {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        handles::AuthHandle, interface_types::algorithm::HashingAlgorithm, structures::Digest,
+    };
+
+    #[test]
+    fn test_set_primary_policy() {
+        let mut context = create_ctx_with_session();
+
+        let policy_digest = Digest::try_from(vec![0xA5; 32]).unwrap();
+        context
+            .set_primary_policy(AuthHandle::Owner, policy_digest, HashingAlgorithm::Sha256)
+            .unwrap();
+        context
+            .set_primary_policy(AuthHandle::Owner, Digest::default(), HashingAlgorithm::Null)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_set_primary_policy_wrong_digest_size() {
+        let mut context = create_ctx_with_session();
+
+        let policy_digest = Digest::try_from(vec![0xA5; 20]).unwrap();
+        let _ = context
+            .set_primary_policy(AuthHandle::Owner, policy_digest, HashingAlgorithm::Sha256)
+            .unwrap_err();
+    }
 }
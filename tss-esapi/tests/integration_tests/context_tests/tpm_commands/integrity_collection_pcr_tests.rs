@@ -245,3 +245,171 @@ mod test_pcr_read {
         assert_ne!(pcr_selection_list_in, pcr_selection_list_out);
     }
 }
+
+mod test_pcr_allocate {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session};
+    use tss_esapi::{
+        constants::CapabilityType, interface_types::resource_handles::Platform,
+        structures::CapabilityData, Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_pcr_allocate_current_allocation() {
+        let mut context = create_ctx_with_session();
+        let (capability_data, _) = context
+            .execute_without_session(|ctx| ctx.get_capability(CapabilityType::AssignedPcr, 0, 1))
+            .expect("Call to get_capability failed");
+        let current_allocation = match capability_data {
+            CapabilityData::AssignedPcr(pcr_selection_list) => pcr_selection_list,
+            _ => panic!("Unexpected capability data"),
+        };
+
+        // Requesting the current allocation leaves the banks unchanged.
+        let result = context
+            .pcr_allocate(Platform::Platform, current_allocation)
+            .expect("Call to pcr_allocate failed");
+        assert!(result.allocation_success);
+        assert!(result.max_pcr >= 24);
+        assert!(result.size_needed <= result.size_available);
+    }
+
+    #[test]
+    fn test_pcr_allocate_without_session() {
+        let mut context = create_ctx_without_session();
+
+        assert_eq!(
+            context
+                .pcr_allocate(Platform::Platform, Default::default())
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
+
+mod test_pcr_set_auth {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        handles::PcrHandle,
+        interface_types::{algorithm::HashingAlgorithm, resource_handles::Platform},
+        structures::{Auth, Digest},
+    };
+
+    // In the reference implementation, PCR 20 to 22 belong to both
+    // the authorization value group and the policy group.
+
+    #[test]
+    fn test_pcr_set_auth_value() {
+        let mut context = create_ctx_with_session();
+        let auth = Auth::try_from(vec![1, 2, 3, 4]).unwrap();
+
+        context
+            .pcr_set_auth_value(PcrHandle::Pcr20, auth)
+            .expect("Call to pcr_set_auth_value failed");
+        // Restoring the empty authorization value is authorized with
+        // the new value, which has been set on the PCR handle.
+        context
+            .pcr_set_auth_value(PcrHandle::Pcr20, Auth::default())
+            .expect("Call to pcr_set_auth_value failed");
+    }
+
+    #[test]
+    fn test_pcr_set_auth_policy() {
+        let mut context = create_ctx_with_session();
+        let auth_policy = Digest::try_from(vec![0xA5; 32]).unwrap();
+
+        context
+            .pcr_set_auth_policy(
+                Platform::Platform,
+                auth_policy,
+                HashingAlgorithm::Sha256,
+                PcrHandle::Pcr20,
+            )
+            .expect("Call to pcr_set_auth_policy failed");
+        context
+            .pcr_set_auth_policy(
+                Platform::Platform,
+                Digest::default(),
+                HashingAlgorithm::Null,
+                PcrHandle::Pcr20,
+            )
+            .expect("Call to pcr_set_auth_policy failed");
+    }
+
+    #[test]
+    fn test_pcr_set_auth_policy_wrong_digest_size() {
+        let mut context = create_ctx_with_session();
+        let auth_policy = Digest::try_from(vec![0xA5; 20]).unwrap();
+
+        let _ = context
+            .pcr_set_auth_policy(
+                Platform::Platform,
+                auth_policy,
+                HashingAlgorithm::Sha256,
+                PcrHandle::Pcr20,
+            )
+            .unwrap_err();
+    }
+}
+
+mod test_pcr_event {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session};
+    use sha2::{Digest as _, Sha256};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        handles::PcrHandle,
+        interface_types::algorithm::HashingAlgorithm,
+        structures::{Event, PcrSelectionListBuilder, PcrSlot},
+        Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_pcr_event() {
+        let mut context = create_ctx_with_session();
+        let event_data = b"event data".to_vec();
+
+        context.pcr_reset(PcrHandle::Pcr16).unwrap();
+        let digests = context
+            .pcr_event(
+                PcrHandle::Pcr16,
+                Event::try_from(event_data.clone()).unwrap(),
+            )
+            .expect("Call to pcr_event failed");
+
+        let event_digest = Sha256::digest(&event_data);
+        assert_eq!(
+            digests
+                .get(HashingAlgorithm::Sha256)
+                .expect("Missing SHA-256 digest")
+                .as_bytes(),
+            event_digest.as_slice()
+        );
+
+        let pcr_selection_list = PcrSelectionListBuilder::new()
+            .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+            .build()
+            .unwrap();
+        let (_, _, pcr_digests) = context
+            .execute_without_session(|ctx| ctx.pcr_read(pcr_selection_list))
+            .unwrap();
+        let mut hasher = Sha256::new();
+        hasher.update([0u8; 32]);
+        hasher.update(event_digest);
+        assert_eq!(
+            pcr_digests.value()[0].as_bytes(),
+            hasher.finalize().as_slice()
+        );
+    }
+
+    #[test]
+    fn test_pcr_event_without_session() {
+        let mut context = create_ctx_without_session();
+
+        assert_eq!(
+            context
+                .pcr_event(PcrHandle::Pcr16, Event::default())
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
@@ -1,2 +1,54 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_pp_commands {
+    use crate::common::create_ctx_without_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        constants::CommandCode, interface_types::resource_handles::Platform,
+        structures::CommandCodeList, Error, WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_pp_commands_without_session() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: The command itself is not sent to the TPM in this test, as it
+        //  requires physical presence to be asserted on the platform.
+        let set_list = CommandCodeList::try_from(vec![CommandCode::Clear]).unwrap();
+        assert_eq!(
+            context
+                .pp_commands(Platform::Platform, set_list, CommandCodeList::new())
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
+
+mod test_set_algorithm_set {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session};
+    use tss_esapi::{
+        interface_types::resource_handles::Platform, structures::AlgorithmSet, Error,
+        WrapperErrorKind,
+    };
+
+    #[test]
+    fn test_set_algorithm_set() {
+        let mut context = create_ctx_with_session();
+
+        context
+            .set_algorithm_set(Platform::Platform, AlgorithmSet::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_set_algorithm_set_without_session() {
+        let mut context = create_ctx_without_session();
+
+        assert_eq!(
+            context
+                .set_algorithm_set(Platform::Platform, AlgorithmSet::new(0))
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::MissingAuthSession)
+        );
+    }
+}
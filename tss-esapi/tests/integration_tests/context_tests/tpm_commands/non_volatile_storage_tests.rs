@@ -421,3 +421,467 @@ mod test_nv_increment {
         assert_eq!(first_value + 1, second_value);
     }
 }
+
+mod test_nv_extend {
+    use crate::common::create_ctx_with_session;
+    use sha2::{Digest as _, Sha256};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        attributes::NvIndexAttributesBuilder,
+        constants::nv_index_type::NvIndexType,
+        handles::NvIndexTpmHandle,
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+        },
+        structures::{MaxNvBuffer, NvPublicBuilder},
+    };
+
+    #[test]
+    fn test_nv_extend() {
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x01500029).unwrap();
+
+        let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_nv_index_type(NvIndexType::Extend)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+
+        let owner_nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_nv_index_attributes)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic for owner");
+
+        let owner_nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, owner_nv_public)
+            .expect("Call to nv_define_space failed");
+
+        let data = vec![0xA5; 32];
+        let nv_extend_result = context.nv_extend(
+            NvAuth::Owner,
+            owner_nv_index_handle,
+            MaxNvBuffer::try_from(data.clone()).unwrap(),
+        );
+
+        let nv_read_result = context.nv_read(NvAuth::Owner, owner_nv_index_handle, 32, 0);
+
+        context
+            .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        nv_extend_result.expect("Call to nv_extend failed");
+        let nv_read_value = nv_read_result.expect("Call to nv_read failed");
+
+        // The index starts with a value of all zeros and is extended in
+        // the same way as a PCR.
+        let mut hasher = Sha256::new();
+        hasher.update([0u8; 32]);
+        hasher.update(&data);
+        assert_eq!(nv_read_value.as_slice(), hasher.finalize().as_slice());
+    }
+}
+
+mod test_nv_set_bits {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryInto;
+    use tss_esapi::{
+        attributes::NvIndexAttributesBuilder,
+        constants::nv_index_type::NvIndexType,
+        handles::NvIndexTpmHandle,
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+        },
+        structures::NvPublicBuilder,
+    };
+
+    #[test]
+    fn test_nv_set_bits() {
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x0150002C).unwrap();
+
+        let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_nv_index_type(NvIndexType::Bits)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+
+        let owner_nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_nv_index_attributes)
+            .with_data_area_size(8)
+            .build()
+            .expect("Failed to build NvPublic for owner");
+
+        let owner_nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, owner_nv_public)
+            .expect("Call to nv_define_space failed");
+
+        let nv_set_bits_result =
+            context.nv_set_bits(NvAuth::Owner, owner_nv_index_handle, 1 << 63 | 1);
+        let nv_read_result = context.nv_read(NvAuth::Owner, owner_nv_index_handle, 8, 0);
+
+        context
+            .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        nv_set_bits_result.expect("Call to nv_set_bits failed");
+        let nv_read_value = nv_read_result.expect("Call to nv_read failed");
+
+        // The bit field is an 8 byte unsigned big-endian value.
+        let value = u64::from_be_bytes(
+            nv_read_value
+                .to_vec()
+                .try_into()
+                .expect("Failed to convert nv_read_value as a vector into an 8 byte array"),
+        );
+        assert_eq!(value, 1 << 63 | 1);
+    }
+}
+
+mod test_nv_lock {
+    use crate::common::create_ctx_with_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        attributes::{NvIndexAttributes, NvIndexAttributesBuilder},
+        handles::{NvIndexHandle, NvIndexTpmHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+        },
+        structures::{MaxNvBuffer, NvPublicBuilder},
+        Context, Error, WrapperErrorKind,
+    };
+
+    fn define_nv_index(
+        context: &mut Context,
+        nv_index: u32,
+        attributes: NvIndexAttributes,
+    ) -> NvIndexHandle {
+        let owner_nv_public = NvPublicBuilder::new()
+            .with_nv_index(NvIndexTpmHandle::new(nv_index).unwrap())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(attributes)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic for owner");
+        context
+            .nv_define_space(Provision::Owner, None, owner_nv_public)
+            .expect("Call to nv_define_space failed")
+    }
+
+    fn data() -> MaxNvBuffer {
+        MaxNvBuffer::try_from(vec![1, 2, 3, 4, 5, 6, 7]).unwrap()
+    }
+
+    #[test]
+    fn test_nv_write_lock() {
+        let mut context = create_ctx_with_session();
+        let attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_write_stclear(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let nv_index_handle = define_nv_index(&mut context, 0x01500030, attributes);
+
+        let first_write_result = context.nv_write(NvAuth::Owner, nv_index_handle, data(), 0);
+        let write_lock_result = context.nv_write_lock(NvAuth::Owner, nv_index_handle);
+        let second_write_result = context.nv_write(NvAuth::Owner, nv_index_handle, data(), 0);
+        let read_result = context.nv_read(NvAuth::Owner, nv_index_handle, 7, 0);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        first_write_result.expect("First call to nv_write failed");
+        write_lock_result.expect("Call to nv_write_lock failed");
+        let _ = second_write_result.expect_err("Writing to a write locked index succeeded");
+        assert_eq!(
+            read_result.expect("Call to nv_read failed").as_slice(),
+            data().as_slice()
+        );
+    }
+
+    #[test]
+    fn test_nv_write_lock_without_lock_attributes() {
+        let mut context = create_ctx_with_session();
+        let attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let nv_index_handle = define_nv_index(&mut context, 0x01500031, attributes);
+
+        let write_lock_result = context.nv_write_lock(NvAuth::Owner, nv_index_handle);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        assert_eq!(
+            write_lock_result.unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+
+    #[test]
+    fn test_nv_read_lock() {
+        let mut context = create_ctx_with_session();
+        let attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_read_stclear(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let nv_index_handle = define_nv_index(&mut context, 0x01500032, attributes);
+
+        let write_result = context.nv_write(NvAuth::Owner, nv_index_handle, data(), 0);
+        let first_read_result = context.nv_read(NvAuth::Owner, nv_index_handle, 7, 0);
+        let read_lock_result = context.nv_read_lock(NvAuth::Owner, nv_index_handle);
+        let second_read_result = context.nv_read(NvAuth::Owner, nv_index_handle, 7, 0);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        write_result.expect("Call to nv_write failed");
+        first_read_result.expect("First call to nv_read failed");
+        read_lock_result.expect("Call to nv_read_lock failed");
+        let _ = second_read_result.expect_err("Reading from a read locked index succeeded");
+    }
+
+    #[test]
+    fn test_nv_read_lock_without_lock_attribute() {
+        let mut context = create_ctx_with_session();
+        let attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let nv_index_handle = define_nv_index(&mut context, 0x01500033, attributes);
+
+        let read_lock_result = context.nv_read_lock(NvAuth::Owner, nv_index_handle);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        assert_eq!(
+            read_lock_result.unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::InvalidParam)
+        );
+    }
+
+    #[test]
+    fn test_nv_global_write_lock() {
+        let mut context = create_ctx_with_session();
+        let attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_global_lock(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let nv_index_handle = define_nv_index(&mut context, 0x01500034, attributes);
+
+        let first_write_result = context.nv_write(NvAuth::Owner, nv_index_handle, data(), 0);
+        let global_write_lock_result = context.nv_global_write_lock(Provision::Owner);
+        let second_write_result = context.nv_write(NvAuth::Owner, nv_index_handle, data(), 0);
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        first_write_result.expect("First call to nv_write failed");
+        global_write_lock_result.expect("Call to nv_global_write_lock failed");
+        let _ = second_write_result.expect_err("Writing to a global locked index succeeded");
+    }
+}
+
+mod test_nv_change_auth {
+    use crate::common::{create_ctx_with_session, start_policy_session};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        attributes::NvIndexAttributesBuilder,
+        constants::{CommandCode, SessionType},
+        handles::{NvIndexTpmHandle, SessionHandle},
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{NvAuth, Provision},
+            session_handles::PolicySession,
+        },
+        structures::{Auth, MaxNvBuffer, NvPublicBuilder},
+        Context,
+    };
+
+    fn start_nv_change_auth_policy_session(
+        context: &mut Context,
+        session_type: SessionType,
+    ) -> PolicySession {
+        let policy_session = start_policy_session(context, session_type);
+        context.execute_without_session(|ctx| {
+            ctx.policy_command_code(policy_session, CommandCode::NvChangeAuth)
+                .expect("Call to policy_command_code failed");
+        });
+        policy_session
+    }
+
+    #[test]
+    fn test_nv_change_auth() {
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x01500035).unwrap();
+
+        // The ADMIN role is satisfied by a policy asserting the command code.
+        let trial_session = start_nv_change_auth_policy_session(&mut context, SessionType::Trial);
+        let policy_digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(trial_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(trial_session).into())
+            .expect("Call to flush_context failed");
+
+        let nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .with_auth_write(true)
+            .with_auth_read(true)
+            .build()
+            .expect("Failed to create nv index attributes");
+        let nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(nv_index_attributes)
+            .with_index_auth_policy(policy_digest)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic");
+        let nv_index_handle = context
+            .nv_define_space(
+                Provision::Owner,
+                Some(Auth::try_from(vec![1, 2, 3]).unwrap()),
+                nv_public,
+            )
+            .expect("Call to nv_define_space failed");
+
+        let policy_session = start_nv_change_auth_policy_session(&mut context, SessionType::Policy);
+        let nv_change_auth_result = context
+            .execute_with_session(Some(policy_session.into()), |ctx| {
+                ctx.nv_change_auth(nv_index_handle, Auth::try_from(vec![4, 5, 6]).unwrap())
+            });
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+
+        // The new auth value is used for the index from now on.
+        let nv_write_result = context.nv_write(
+            NvAuth::NvIndex(nv_index_handle),
+            nv_index_handle,
+            MaxNvBuffer::try_from(vec![1, 2, 3, 4, 5, 6, 7]).unwrap(),
+            0,
+        );
+
+        context
+            .nv_undefine_space(Provision::Owner, nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+
+        nv_change_auth_result.expect("Call to nv_change_auth failed");
+        nv_write_result.expect("Call to nv_write with the new auth value failed");
+    }
+}
+
+mod test_nv_certify {
+    use crate::common::{create_ctx_with_session, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        attributes::NvIndexAttributesBuilder,
+        handles::NvIndexTpmHandle,
+        interface_types::{
+            algorithm::HashingAlgorithm,
+            resource_handles::{Hierarchy, NvAuth, Provision},
+            session_handles::AuthSession,
+        },
+        structures::{AttestInfo, Data, MaxNvBuffer, NvPublicBuilder, SignatureScheme},
+    };
+
+    #[test]
+    fn test_nv_certify() {
+        let mut context = create_ctx_with_session();
+        let nv_index = NvIndexTpmHandle::new(0x01500037).unwrap();
+
+        let owner_nv_index_attributes = NvIndexAttributesBuilder::new()
+            .with_owner_write(true)
+            .with_owner_read(true)
+            .build()
+            .expect("Failed to create owner nv index attributes");
+        let owner_nv_public = NvPublicBuilder::new()
+            .with_nv_index(nv_index)
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_nv_index_attributes)
+            .with_data_area_size(32)
+            .build()
+            .expect("Failed to build NvPublic for owner");
+        let owner_nv_index_handle = context
+            .nv_define_space(Provision::Owner, None, owner_nv_public)
+            .expect("Call to nv_define_space failed");
+        context
+            .nv_write(
+                NvAuth::Owner,
+                owner_nv_index_handle,
+                MaxNvBuffer::try_from(vec![1, 2, 3, 4, 5, 6, 7]).unwrap(),
+                0,
+            )
+            .expect("Call to nv_write failed");
+        let (_, nv_index_name) = context
+            .nv_read_public(owner_nv_index_handle)
+            .expect("Call to nv_read_public failed");
+
+        let sign_key_handle = context
+            .create_primary(Hierarchy::Owner, signing_key_pub(), None, None, None, None)
+            .expect("Failed to create signing key")
+            .key_handle;
+        let qualifying_data = Data::try_from(vec![0xff; 16]).unwrap();
+        let nv_certify_result = context.execute_with_sessions(
+            (
+                Some(AuthSession::Password),
+                Some(AuthSession::Password),
+                None,
+            ),
+            |ctx| {
+                ctx.nv_certify(
+                    sign_key_handle,
+                    NvAuth::Owner,
+                    owner_nv_index_handle,
+                    qualifying_data.clone(),
+                    SignatureScheme::Null,
+                    4,
+                    2,
+                )
+            },
+        );
+
+        context
+            .nv_undefine_space(Provision::Owner, owner_nv_index_handle)
+            .expect("Call to nv_undefine_space failed");
+        context
+            .flush_context(sign_key_handle.into())
+            .expect("Call to flush_context failed");
+
+        let (attest, _) = nv_certify_result.expect("Call to nv_certify failed");
+        assert_eq!(attest.extra_data(), &qualifying_data);
+        match attest.attested() {
+            AttestInfo::Nv { info } => {
+                assert_eq!(info.index_name(), &nv_index_name);
+                assert_eq!(info.offset(), 2);
+                assert_eq!(info.nv_contents().as_slice(), &[3, 4, 5, 6]);
+            }
+            _ => panic!("Attested did not contain the expected variant."),
+        }
+    }
+}
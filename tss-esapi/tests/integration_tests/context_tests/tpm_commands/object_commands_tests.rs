@@ -142,6 +142,7 @@ mod test_load_external {
         },
         structures::{
             Public, PublicBuilder, PublicKeyRsa, PublicRsaParametersBuilder, RsaScheme, Sensitive,
+            SensitiveBuilder,
         },
     };
 
@@ -228,6 +229,22 @@ mod test_load_external {
             .unwrap();
         context.flush_context(key_handle.into()).unwrap();
     }
+
+    #[test]
+    fn test_load_external_with_sensitive_builder() {
+        let mut context = create_ctx_with_session();
+        let pub_key = get_ext_rsa_pub();
+        let priv_key = SensitiveBuilder::new()
+            .with_sensitive_type(PublicAlgorithm::Rsa)
+            .with_rsa_private_key(PRIV_KEY.to_vec().try_into().unwrap())
+            .build()
+            .expect("Failed to build Sensitive structure");
+
+        let key_handle = context
+            .load_external(priv_key, pub_key, Hierarchy::Null)
+            .unwrap();
+        context.flush_context(key_handle.into()).unwrap();
+    }
 }
 
 mod test_read_public {
@@ -426,3 +443,60 @@ mod test_unseal {
         assert!(unsealed == testbytes);
     }
 }
+
+mod test_create_loaded {
+    use crate::common::{create_ctx_with_session, decryption_key_pub, signing_key_pub};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        handles::ObjectHandle, interface_types::resource_handles::Hierarchy, structures::Auth,
+    };
+
+    #[test]
+    fn test_create_loaded() {
+        let mut context = create_ctx_with_session();
+        let random_digest = context.get_random(16).unwrap();
+        let key_auth = Auth::try_from(random_digest.as_bytes().to_vec()).unwrap();
+
+        let prim_key_handle = context
+            .create_primary(
+                Hierarchy::Owner,
+                decryption_key_pub(),
+                Some(key_auth.clone()),
+                None,
+                None,
+                None,
+            )
+            .unwrap()
+            .key_handle;
+
+        let result = context
+            .create_loaded(
+                prim_key_handle.into(),
+                signing_key_pub(),
+                Some(key_auth),
+                None,
+            )
+            .unwrap();
+        assert!(!result.out_private.is_empty());
+
+        let (public, _, _) = context.read_public(result.key_handle).unwrap();
+        assert_eq!(public, result.out_public);
+        context.flush_context(result.key_handle.into()).unwrap();
+    }
+
+    #[test]
+    fn test_create_loaded_primary() {
+        let mut context = create_ctx_with_session();
+
+        let result = context
+            .create_loaded(
+                ObjectHandle::from(Hierarchy::Owner),
+                decryption_key_pub(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(result.out_private.is_empty());
+        context.flush_context(result.key_handle.into()).unwrap();
+    }
+}
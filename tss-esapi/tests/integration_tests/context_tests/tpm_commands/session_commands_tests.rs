@@ -8,6 +8,7 @@ mod test_start_auth_session {
         constants::SessionType,
         interface_types::{algorithm::HashingAlgorithm, resource_handles::Hierarchy},
         structures::{Nonce, SymmetricDefinition},
+        Error, WrapperErrorKind,
     };
 
     #[test]
@@ -48,6 +49,26 @@ mod test_start_auth_session {
             .unwrap();
     }
 
+    #[test]
+    fn test_invalid_nonce_size_sess() {
+        let mut context = create_ctx_without_session();
+        for nonce_size in [15, 33] {
+            assert_eq!(
+                context
+                    .start_auth_session(
+                        None,
+                        None,
+                        Some(Nonce::try_from(vec![0xa5; nonce_size]).unwrap()),
+                        SessionType::Hmac,
+                        SymmetricDefinition::AES_256_CFB,
+                        HashingAlgorithm::Sha256,
+                    )
+                    .unwrap_err(),
+                Error::WrapperError(WrapperErrorKind::WrongParamSize)
+            );
+        }
+    }
+
     #[test]
     fn test_bound_sess() {
         let mut context = create_ctx_with_session();
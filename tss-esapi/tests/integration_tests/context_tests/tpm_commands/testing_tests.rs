@@ -16,7 +16,29 @@ mod test_get_test_result {
     #[test]
     fn test_get_test_result() {
         let mut context = create_ctx_without_session();
-        let (_, rc) = context.get_test_result().unwrap();
-        rc.unwrap();
+        let test_result = context.get_test_result().unwrap();
+        test_result.test_result.unwrap();
+    }
+}
+
+mod test_incremental_self_test {
+    use crate::common::create_ctx_without_session;
+    use std::convert::TryFrom;
+    use tss_esapi::{constants::AlgorithmIdentifier, structures::AlgorithmList};
+
+    #[test]
+    fn test_incremental_self_test() {
+        let mut context = create_ctx_without_session();
+        let to_test = AlgorithmList::try_from(vec![
+            AlgorithmIdentifier::Sha256,
+            AlgorithmIdentifier::Aes,
+            AlgorithmIdentifier::Rsa,
+        ])
+        .unwrap();
+        let _ = context.incremental_self_test(to_test).unwrap();
+
+        // All the algorithms have been tested or are being tested.
+        let to_do_list = context.incremental_self_test(AlgorithmList::new()).unwrap();
+        assert!(!to_do_list.contains(&AlgorithmIdentifier::Sha256));
     }
 }
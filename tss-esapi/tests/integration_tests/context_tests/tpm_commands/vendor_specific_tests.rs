@@ -1,2 +1,35 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod test_execute_vendor_command {
+    use crate::common::{create_ctx_with_session, create_ctx_without_session};
+    use tss_esapi::{constants::VendorCommandCode, Error, WrapperErrorKind};
+
+    #[test]
+    fn test_execute_vendor_command_with_session() {
+        let mut context = create_ctx_with_session();
+
+        assert_eq!(
+            context
+                .execute_vendor_command(VendorCommandCode::new(0xFFFF), &[])
+                .unwrap_err(),
+            Error::WrapperError(WrapperErrorKind::UnsupportedParam)
+        );
+    }
+
+    #[test]
+    fn test_execute_unknown_vendor_command() {
+        let mut context = create_ctx_without_session();
+
+        // NOTE: The vendor command code is not expected to be implemented
+        // by the TPM, which returns an error that is passed to the caller.
+        match context
+            .execute_vendor_command(VendorCommandCode::new(0xFFFF), &[])
+            .unwrap_err()
+        {
+            Error::TssError(_) => {}
+            e => panic!("Unexpected error: {}", e),
+        }
+        // The context is still usable after the raw command.
+        let _ = context.get_random(16).unwrap();
+    }
+}
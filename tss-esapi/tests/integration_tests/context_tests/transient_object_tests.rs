@@ -0,0 +1,188 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::thread;
+use tss_esapi::{
+    handles::KeyHandle, interface_types::resource_handles::Hierarchy, Context, Error,
+    WrapperErrorKind,
+};
+
+use crate::common::{create_ctx_with_session, decryption_key_pub};
+
+fn create_primary(context: &mut Context) -> KeyHandle {
+    context
+        .create_primary(
+            Hierarchy::Owner,
+            decryption_key_pub(),
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create primary key")
+        .key_handle
+}
+
+#[test]
+fn test_dropped_object_is_flushed() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    assert_eq!(key.key_handle(), key_handle);
+    drop(key);
+
+    let _ = context
+        .read_public(key_handle)
+        .expect_err("The key should have been flushed");
+}
+
+#[test]
+fn test_dropped_object_is_flushed_from_another_thread() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    thread::spawn(move || drop(key))
+        .join()
+        .expect("Failed to drop the guard");
+
+    let _ = context
+        .read_public(key_handle)
+        .expect_err("The key should have been flushed");
+}
+
+#[test]
+fn test_object_dropped_after_the_context() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    // The key is flushed by the context.
+    drop(context);
+    drop(key);
+}
+
+#[test]
+fn test_flush_guarded_object() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    key.flush(&mut context).expect("Failed to flush the key");
+
+    let _ = context
+        .read_public(key_handle)
+        .expect_err("The key should have been flushed");
+}
+
+#[test]
+fn test_explicitly_flushed_object_is_not_flushed_again() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    context
+        .flush_context(key_handle.into())
+        .expect("Failed to flush the key");
+    // Flushing the key again would fail, as the handle is no longer valid.
+    drop(key);
+
+    let other_key_handle = create_primary(&mut context);
+    context
+        .flush_context(other_key_handle.into())
+        .expect("Failed to flush the key");
+}
+
+#[test]
+fn test_released_object_is_not_flushed() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    let object_handle = key.release();
+
+    let _ = context
+        .read_public(key_handle)
+        .expect("The key should still be loaded");
+    context
+        .flush_context(object_handle)
+        .expect("Failed to flush the key");
+}
+
+#[test]
+fn test_guard_object_twice() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let _key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    assert_eq!(
+        context.transient_object(key_handle).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidHandleState)
+    );
+}
+
+#[test]
+fn test_flush_with_other_context() {
+    let mut context = create_ctx_with_session();
+    let mut other_context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .transient_object(key_handle)
+        .expect("Failed to guard the key");
+    assert_eq!(
+        key.flush(&mut other_context).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
+
+#[test]
+fn test_flush_loaded() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .take_loaded(key_handle)
+        .expect("Failed to own the key");
+    assert_eq!(key.handle(), key_handle);
+    let _ = context
+        .read_public((&key).into())
+        .expect("Failed to read the public area");
+
+    context.flush_loaded(key).expect("Failed to flush the key");
+    let _ = context
+        .read_public(key_handle)
+        .expect_err("The key should have been flushed");
+}
+
+#[test]
+fn test_take_loaded_twice() {
+    let mut context = create_ctx_with_session();
+    let key_handle = create_primary(&mut context);
+    let key = context
+        .take_loaded(key_handle)
+        .expect("Failed to own the key");
+    assert_eq!(
+        context.take_loaded(key_handle).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidHandleState)
+    );
+    assert_eq!(
+        context.transient_object(key_handle).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidHandleState)
+    );
+    context.flush_loaded(key).expect("Failed to flush the key");
+}
+
+#[test]
+fn test_take_loaded_null_handle() {
+    let mut context = create_ctx_with_session();
+    assert_eq!(
+        context.take_loaded(KeyHandle::Null).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidHandleState)
+    );
+}
@@ -389,3 +389,35 @@ mod symmetric_object_tests {
         )
     }
 }
+
+mod ecc_key_exchange_algorithm_tests {
+    use super::*;
+    use tss_esapi::{
+        constants::{
+            tss::{TPM2_ALG_ECDH, TPM2_ALG_ECMQV, TPM2_ALG_NULL, TPM2_ALG_SM2},
+            AlgorithmIdentifier,
+        },
+        interface_types::algorithm::EccKeyExchangeAlgorithm,
+    };
+    #[test]
+    fn test_ecc_key_exchange_algorithm_conversion() {
+        test_conversion!(TPM2_ALG_ECDH, EccKeyExchangeAlgorithm::EcDh);
+        test_conversion!(TPM2_ALG_ECMQV, EccKeyExchangeAlgorithm::EcMqv);
+        test_conversion!(TPM2_ALG_SM2, EccKeyExchangeAlgorithm::Sm2);
+        test_conversion!(TPM2_ALG_NULL, EccKeyExchangeAlgorithm::Null);
+    }
+
+    #[test]
+    fn test_conversion_of_incorrect_algorithm() {
+        test_invalid_tpm_alg_conversion!(
+            TPM2_ALG_ECDSA,
+            EccKeyExchangeAlgorithm,
+            WrapperErrorKind::InvalidParam
+        );
+        test_invalid_algorithm_conversion!(
+            AlgorithmIdentifier::EcDsa,
+            EccKeyExchangeAlgorithm,
+            WrapperErrorKind::InvalidParam
+        )
+    }
+}
@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::convert::TryFrom;
 use tss_esapi::{
-    handles::{AuthHandle, NvIndexHandle, ObjectHandle, PermanentTpmHandle, TpmHandle},
+    handles::{
+        AttachedComponentTpmHandle, AuthHandle, NvIndexHandle, ObjectHandle, PermanentTpmHandle,
+        TpmHandle,
+    },
     interface_types::resource_handles::{
-        Clear, Enables, Endorsement, Hierarchy, HierarchyAuth, Lockout, NvAuth, Owner, Platform,
-        Provision,
+        Act, AttachedComponent, Clear, Enables, Endorsement, Hierarchy, HierarchyAuth, Lockout,
+        NvAuth, Owner, Platform, Provision,
     },
     tss2_esys::ESYS_TR,
 };
@@ -297,3 +300,63 @@ mod test_lockout {
         );
     }
 }
+
+mod test_act {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        // ESYS_TR_RH_ACT_FIRST and ESYS_TR_RH_ACT_LAST
+        let (esys_tr_act_0, esys_tr_act_f): (ESYS_TR, ESYS_TR) = (0x120, 0x12F);
+
+        let act_0 = Act::try_from(PermanentTpmHandle::AuthenticatedTimersFirst).unwrap();
+        assert_eq!(act_0.number(), 0);
+        assert_eq!(ObjectHandle::from(act_0), ObjectHandle::from(esys_tr_act_0));
+        assert_eq!(
+            TpmHandle::from(act_0),
+            TpmHandle::Permanent(PermanentTpmHandle::AuthenticatedTimersFirst)
+        );
+
+        let act_f = Act::try_from(PermanentTpmHandle::AuthenticatedTimersLast).unwrap();
+        assert_eq!(act_f.number(), 15);
+        assert_eq!(ObjectHandle::from(act_f), ObjectHandle::from(esys_tr_act_f));
+        assert_eq!(
+            PermanentTpmHandle::from(act_f),
+            PermanentTpmHandle::AuthenticatedTimersLast
+        );
+    }
+
+    #[test]
+    fn test_invalid_conversions() {
+        let _ = Act::try_from(PermanentTpmHandle::Owner).unwrap_err();
+        let _ = Act::try_from(PermanentTpmHandle::Platform).unwrap_err();
+    }
+}
+
+mod test_attached_component {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        // ESYS_TR_RH_AC_FIRST
+        let esys_tr_ac_first: ESYS_TR = 0x1000;
+
+        let attached_component_tpm_handle = AttachedComponentTpmHandle::new(0x9000_0002).unwrap();
+        let attached_component = AttachedComponent::from(attached_component_tpm_handle);
+        assert_eq!(
+            AttachedComponentTpmHandle::from(attached_component),
+            attached_component_tpm_handle
+        );
+        assert_eq!(
+            ObjectHandle::try_from(attached_component).unwrap(),
+            ObjectHandle::from(esys_tr_ac_first + 2)
+        );
+    }
+
+    #[test]
+    fn test_invalid_conversions() {
+        let attached_component =
+            AttachedComponent::from(AttachedComponentTpmHandle::new(0x9001_0000).unwrap());
+        let _ = ObjectHandle::try_from(attached_component).unwrap_err();
+    }
+}
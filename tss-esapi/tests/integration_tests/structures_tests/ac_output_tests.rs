@@ -0,0 +1,37 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    constants::AcAttributeTag, structures::AcOutput, tss2_esys::TPMS_AC_OUTPUT, Error,
+    WrapperErrorKind,
+};
+
+use std::convert::TryFrom;
+
+#[test]
+fn test_conversions() {
+    let expected_tag = AcAttributeTag::Pv1;
+    let expected_data = 0xA5A5_0001u32;
+
+    let expected_tpms_ac_output = TPMS_AC_OUTPUT {
+        tag: expected_tag.into(),
+        data: expected_data,
+    };
+
+    let ac_output = AcOutput::try_from(expected_tpms_ac_output)
+        .expect("Failed to convert TPMS_AC_OUTPUT into AcOutput");
+    assert_eq!(expected_tag, ac_output.tag());
+    assert_eq!(expected_data, ac_output.data());
+    assert_eq!(AcOutput::new(expected_tag, expected_data), ac_output);
+
+    let actual_tpms_ac_output = TPMS_AC_OUTPUT::from(ac_output);
+    assert_eq!(expected_tpms_ac_output.tag, actual_tpms_ac_output.tag);
+    assert_eq!(expected_tpms_ac_output.data, actual_tpms_ac_output.data);
+}
+
+#[test]
+fn test_invalid_tag_conversion() {
+    assert_eq!(
+        AcOutput::try_from(TPMS_AC_OUTPUT { tag: 0x3, data: 0 }).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
@@ -0,0 +1,64 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    constants::AlgorithmIdentifier,
+    interface_types::ecc::EccCurve,
+    structures::{AlgorithmDetailEcc, EccParameter, EccScheme, KeyDerivationFunctionScheme},
+    tss2_esys::{TPMS_ALGORITHM_DETAIL_ECC, TPMT_ECC_SCHEME, TPMT_KDF_SCHEME},
+};
+
+fn ecc_parameter(value: u8) -> EccParameter {
+    EccParameter::try_from(vec![value; 32]).expect("Failed to create EccParameter")
+}
+
+#[test]
+fn test_conversion() {
+    let expected_tpms_algorithm_detail_ecc = TPMS_ALGORITHM_DETAIL_ECC {
+        curveID: EccCurve::NistP256.into(),
+        keySize: 256,
+        kdf: TPMT_KDF_SCHEME {
+            scheme: AlgorithmIdentifier::Null.into(),
+            details: Default::default(),
+        },
+        sign: TPMT_ECC_SCHEME {
+            scheme: AlgorithmIdentifier::Null.into(),
+            details: Default::default(),
+        },
+        p: ecc_parameter(1).into(),
+        a: ecc_parameter(2).into(),
+        b: ecc_parameter(3).into(),
+        gX: ecc_parameter(4).into(),
+        gY: ecc_parameter(5).into(),
+        n: ecc_parameter(6).into(),
+        h: EccParameter::try_from(vec![1])
+            .expect("Failed to create EccParameter")
+            .into(),
+    };
+
+    let algorithm_detail_ecc = AlgorithmDetailEcc::try_from(expected_tpms_algorithm_detail_ecc)
+        .expect("Failed to convert TPMS_ALGORITHM_DETAIL_ECC into AlgorithmDetailEcc");
+
+    assert_eq!(EccCurve::NistP256, algorithm_detail_ecc.ecc_curve());
+    assert_eq!(256, algorithm_detail_ecc.key_size());
+    assert_eq!(
+        KeyDerivationFunctionScheme::Null,
+        algorithm_detail_ecc.key_derivation_function_scheme()
+    );
+    assert_eq!(EccScheme::Null, algorithm_detail_ecc.ecc_scheme());
+    assert_eq!(&ecc_parameter(1), algorithm_detail_ecc.p());
+    assert_eq!(&ecc_parameter(2), algorithm_detail_ecc.a());
+    assert_eq!(&ecc_parameter(3), algorithm_detail_ecc.b());
+    assert_eq!(&ecc_parameter(4), algorithm_detail_ecc.g_x());
+    assert_eq!(&ecc_parameter(5), algorithm_detail_ecc.g_y());
+    assert_eq!(&ecc_parameter(6), algorithm_detail_ecc.n());
+    assert_eq!(&[1u8], algorithm_detail_ecc.h().as_bytes());
+
+    let actual_tpms_algorithm_detail_ecc: TPMS_ALGORITHM_DETAIL_ECC =
+        algorithm_detail_ecc.clone().into();
+    assert_eq!(
+        algorithm_detail_ecc,
+        AlgorithmDetailEcc::try_from(actual_tpms_algorithm_detail_ecc)
+            .expect("Failed to convert TPMS_ALGORITHM_DETAIL_ECC into AlgorithmDetailEcc")
+    );
+}
@@ -0,0 +1,26 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{structures::Event, tss2_esys::TPM2B_EVENT, Error, WrapperErrorKind};
+
+#[test]
+fn test_max_sized_data() {
+    let _ = Event::try_from([0xff; 1024].to_vec()).unwrap();
+}
+
+#[test]
+fn test_to_large_data() {
+    assert_eq!(
+        Event::try_from([0xff; 1025].to_vec()).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::WrongParamSize)
+    );
+}
+
+#[test]
+fn test_conversions() {
+    let event = Event::try_from(b"event data".to_vec()).unwrap();
+    let tss_event = TPM2B_EVENT::from(event.clone());
+    assert_eq!(tss_event.size, 10);
+    assert_eq!(&tss_event.buffer[..10], b"event data");
+    assert_eq!(Event::try_from(tss_event).unwrap(), event);
+}
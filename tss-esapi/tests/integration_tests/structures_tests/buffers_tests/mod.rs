@@ -4,6 +4,7 @@ mod attest_buffer_tests;
 mod auth_tests;
 mod data_tests;
 mod digest_tests;
+mod event_tests;
 mod max_buffer_tests;
 mod nonce_tests;
 mod public;
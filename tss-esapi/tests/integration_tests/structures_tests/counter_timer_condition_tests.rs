@@ -0,0 +1,103 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use tss_esapi::{
+    constants::ArithmeticOperation,
+    structures::{CounterTimerConditionBuilder, CounterTimerField},
+    Error, WrapperErrorKind,
+};
+
+#[test]
+fn test_build() {
+    let expected_values = [
+        (
+            CounterTimerField::Time,
+            0u16,
+            vec![0, 0, 0, 0, 0, 0, 0x01, 0x02],
+        ),
+        (
+            CounterTimerField::Clock,
+            8u16,
+            vec![0, 0, 0, 0, 0, 0, 0x01, 0x02],
+        ),
+        (CounterTimerField::ResetCount, 16u16, vec![0, 0, 0x01, 0x02]),
+        (
+            CounterTimerField::RestartCount,
+            20u16,
+            vec![0, 0, 0x01, 0x02],
+        ),
+    ];
+    for (field, expected_offset, expected_operand_b) in expected_values.iter() {
+        let condition = CounterTimerConditionBuilder::new()
+            .with_field(*field)
+            .with_operation(ArithmeticOperation::UnsignedLt)
+            .with_value(0x0102)
+            .build()
+            .expect("Failed to build counter timer condition");
+        assert_eq!(condition.offset(), *expected_offset);
+        assert_eq!(
+            condition.operand_b().as_bytes(),
+            expected_operand_b.as_slice()
+        );
+        assert_eq!(condition.operation(), ArithmeticOperation::UnsignedLt);
+    }
+
+    let condition = CounterTimerConditionBuilder::new()
+        .with_field(CounterTimerField::Safe)
+        .with_operation(ArithmeticOperation::Eq)
+        .with_value(1)
+        .build()
+        .expect("Failed to build counter timer condition");
+    assert_eq!(condition.offset(), 24);
+    assert_eq!(condition.operand_b().as_bytes(), &[1]);
+}
+
+#[test]
+fn test_build_with_missing_parameters() {
+    assert_eq!(
+        CounterTimerConditionBuilder::new()
+            .with_operation(ArithmeticOperation::Eq)
+            .with_value(1)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::ParamsMissing)
+    );
+    assert_eq!(
+        CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::Clock)
+            .with_value(1)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::ParamsMissing)
+    );
+    assert_eq!(
+        CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::Clock)
+            .with_operation(ArithmeticOperation::Eq)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::ParamsMissing)
+    );
+}
+
+#[test]
+fn test_build_with_too_large_value() {
+    assert_eq!(
+        CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::ResetCount)
+            .with_operation(ArithmeticOperation::Eq)
+            .with_value(u64::from(u32::MAX) + 1)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+    assert_eq!(
+        CounterTimerConditionBuilder::new()
+            .with_field(CounterTimerField::Safe)
+            .with_operation(ArithmeticOperation::Eq)
+            .with_value(0x100)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
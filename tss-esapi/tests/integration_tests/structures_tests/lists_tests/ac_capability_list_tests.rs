@@ -0,0 +1,56 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use std::convert::TryFrom;
+use tss_esapi::{
+    constants::AcAttributeTag,
+    structures::{AcCapabilityList, AcOutput},
+    tss2_esys::TPML_AC_CAPABILITIES,
+    Error, WrapperErrorKind,
+};
+
+#[test]
+fn test_conversions() {
+    let expected_ac_outputs = vec![
+        AcOutput::new(AcAttributeTag::Pv1, 0x1234_5678),
+        AcOutput::new(AcAttributeTag::Vendor(1), 0xFFFF_0000),
+    ];
+
+    let ac_capability_list = AcCapabilityList::try_from(expected_ac_outputs.clone())
+        .expect("Failed to convert Vec<AcOutput> into AcCapabilityList");
+    assert_eq!(expected_ac_outputs.len(), ac_capability_list.len());
+
+    let tpml_ac_capabilities = TPML_AC_CAPABILITIES::from(ac_capability_list.clone());
+    assert_eq!(
+        expected_ac_outputs.len(),
+        tpml_ac_capabilities.count as usize
+    );
+
+    let actual_ac_capability_list = AcCapabilityList::try_from(tpml_ac_capabilities)
+        .expect("Failed to convert TPML_AC_CAPABILITIES into AcCapabilityList");
+    assert_eq!(ac_capability_list, actual_ac_capability_list);
+    assert_eq!(
+        Some(&AcOutput::new(AcAttributeTag::Pv1, 0x1234_5678)),
+        actual_ac_capability_list.find(AcAttributeTag::Pv1)
+    );
+    assert_eq!(None, actual_ac_capability_list.find(AcAttributeTag::Error));
+}
+
+#[test]
+fn test_invalid_size() {
+    assert_eq!(
+        AcCapabilityList::try_from(vec![
+            AcOutput::new(AcAttributeTag::Any, 0);
+            AcCapabilityList::MAX_SIZE + 1
+        ])
+        .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+
+    let mut tpml_ac_capabilities =
+        TPML_AC_CAPABILITIES::from(AcCapabilityList::try_from(Vec::<AcOutput>::new()).unwrap());
+    tpml_ac_capabilities.count = AcCapabilityList::MAX_SIZE as u32 + 1;
+    assert_eq!(
+        AcCapabilityList::try_from(tpml_ac_capabilities).unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
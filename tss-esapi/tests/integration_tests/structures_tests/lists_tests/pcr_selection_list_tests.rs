@@ -485,3 +485,60 @@ fn test_invalid_subtraction() {
         "PcrSelectionList subtract method did not produce expected error for invalid parameters"
     );
 }
+
+#[test]
+fn test_set_operations() {
+    let pcr_selection_list_1 = PcrSelectionList::builder()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot0, PcrSlot::Slot7])
+        .with_selection(HashingAlgorithm::Sha1, &[PcrSlot::Slot0])
+        .build()
+        .expect("Failed to create PcrSelectionList pcr_selection_list_1");
+    let pcr_selection_list_2 = PcrSelectionList::builder()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot7, PcrSlot::Slot16])
+        .with_selection(HashingAlgorithm::Sha384, &[PcrSlot::Slot1])
+        .build()
+        .expect("Failed to create PcrSelectionList pcr_selection_list_2");
+
+    let union = pcr_selection_list_1
+        .union(&pcr_selection_list_2)
+        .expect("Failed to compute the union");
+    let mut union_pairs: Vec<_> = union
+        .iter()
+        .map(|(alg, slot)| (u16::from(alg), slot))
+        .collect();
+    union_pairs.sort();
+    let mut expected_union_pairs = vec![
+        (u16::from(HashingAlgorithm::Sha256), PcrSlot::Slot0),
+        (u16::from(HashingAlgorithm::Sha256), PcrSlot::Slot7),
+        (u16::from(HashingAlgorithm::Sha256), PcrSlot::Slot16),
+        (u16::from(HashingAlgorithm::Sha1), PcrSlot::Slot0),
+        (u16::from(HashingAlgorithm::Sha384), PcrSlot::Slot1),
+    ];
+    expected_union_pairs.sort();
+    assert_eq!(union_pairs, expected_union_pairs);
+    assert!(union.contains(&pcr_selection_list_1));
+    assert!(union.contains(&pcr_selection_list_2));
+    assert!(!pcr_selection_list_1.contains(&pcr_selection_list_2));
+
+    let intersection = pcr_selection_list_1.intersection(&pcr_selection_list_2);
+    assert_eq!(
+        intersection.iter().collect::<Vec<_>>(),
+        vec![(HashingAlgorithm::Sha256, PcrSlot::Slot7)]
+    );
+    assert_eq!(intersection.len(), 1);
+
+    let difference = pcr_selection_list_1.difference(&pcr_selection_list_2);
+    assert!(difference.contains(
+        &PcrSelectionList::builder()
+            .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot0])
+            .with_selection(HashingAlgorithm::Sha1, &[PcrSlot::Slot0])
+            .build()
+            .unwrap()
+    ));
+    assert_eq!(difference.iter().count(), 2);
+
+    assert!(pcr_selection_list_1
+        .difference(&pcr_selection_list_1)
+        .is_empty());
+    assert!(pcr_selection_list_1.contains(&PcrSelectionList::default()));
+}
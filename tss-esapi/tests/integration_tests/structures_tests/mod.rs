@@ -1,5 +1,7 @@
 // Copyright 2021 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
+mod ac_output_tests;
+mod algorithm_detail_ecc_tests;
 mod algorithm_property_tests;
 mod algorithm_tests;
 mod attest_info_test;
@@ -9,10 +11,13 @@ mod capability_data_tests;
 mod certify_info_tests;
 mod clock_info_tests;
 mod command_audit_info_tests;
+mod counter_timer_condition_tests;
 mod creation_info_tests;
 mod lists_tests;
 mod nv_certify_info_tests;
+mod nv_public_tests;
 mod pcr_tests;
+mod policy_expiration_tests;
 mod quote_info_tests;
 mod session_audit_info_tests;
 mod tagged_pcr_select_tests;
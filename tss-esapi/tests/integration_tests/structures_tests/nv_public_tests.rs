@@ -0,0 +1,158 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+use tss_esapi::{
+    attributes::{NvIndexAttributes, NvIndexAttributesBuilder},
+    constants::NvIndexType,
+    handles::NvIndexTpmHandle,
+    interface_types::algorithm::HashingAlgorithm,
+    structures::NvPublicBuilder,
+    Error, WrapperErrorKind,
+};
+
+fn owner_attributes() -> NvIndexAttributes {
+    NvIndexAttributesBuilder::new()
+        .with_owner_write(true)
+        .with_owner_read(true)
+        .build()
+        .expect("Failed to create owner nv index attributes")
+}
+
+fn nv_index() -> NvIndexTpmHandle {
+    NvIndexTpmHandle::new(0x01500001).expect("Failed to create NV index tpm handle")
+}
+
+#[test]
+fn test_typed_index_builders() {
+    for (builder, expected_index_type, expected_data_size) in [
+        (
+            NvPublicBuilder::new_counter_index(),
+            NvIndexType::Counter,
+            8,
+        ),
+        (NvPublicBuilder::new_bits_index(), NvIndexType::Bits, 8),
+        (NvPublicBuilder::new_extend_index(), NvIndexType::Extend, 48),
+        (
+            NvPublicBuilder::new_pin_pass_index(),
+            NvIndexType::PinPass,
+            8,
+        ),
+        (
+            NvPublicBuilder::new_pin_fail_index(),
+            NvIndexType::PinFail,
+            8,
+        ),
+    ] {
+        let nv_public = builder
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha384)
+            .with_index_attributes(owner_attributes())
+            .build()
+            .expect("Failed to build NvPublic");
+        assert_eq!(
+            nv_public.attributes().index_type().unwrap(),
+            expected_index_type
+        );
+        assert_eq!(nv_public.data_size(), expected_data_size);
+        assert!(nv_public.attributes().owner_read());
+        assert!(nv_public.attributes().owner_write());
+        assert_eq!(
+            nv_public.attributes().no_da(),
+            expected_index_type == NvIndexType::PinFail
+        );
+    }
+}
+
+#[test]
+fn test_ordinary_index_builder() {
+    let nv_public = NvPublicBuilder::new_ordinary_index()
+        .with_nv_index(nv_index())
+        .with_index_name_algorithm(HashingAlgorithm::Sha256)
+        .with_index_attributes(owner_attributes())
+        .with_data_area_size(1024)
+        .build()
+        .expect("Failed to build NvPublic");
+    assert_eq!(
+        nv_public.attributes().index_type().unwrap(),
+        NvIndexType::Ordinary
+    );
+    assert_eq!(nv_public.data_size(), 1024);
+
+    // The data size of an ordinary index must be provided.
+    assert_eq!(
+        NvPublicBuilder::new_ordinary_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_attributes())
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::ParamsMissing)
+    );
+}
+
+#[test]
+fn test_typed_index_builders_invalid_params() {
+    // Wrong data size
+    assert_eq!(
+        NvPublicBuilder::new_counter_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_attributes())
+            .with_data_area_size(16)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+    assert_eq!(
+        NvPublicBuilder::new_extend_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(owner_attributes())
+            .with_data_area_size(20)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+
+    // Conflicting index type
+    let bits_attributes = NvIndexAttributesBuilder::with_attributes(owner_attributes())
+        .with_nv_index_type(NvIndexType::Bits)
+        .build()
+        .unwrap();
+    assert_eq!(
+        NvPublicBuilder::new_counter_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(bits_attributes)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+
+    // Forbidden attributes
+    let clear_stclear_attributes = NvIndexAttributesBuilder::with_attributes(owner_attributes())
+        .with_clear_stclear(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        NvPublicBuilder::new_counter_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(clear_stclear_attributes)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+    let write_define_attributes = NvIndexAttributesBuilder::with_attributes(owner_attributes())
+        .with_write_define(true)
+        .build()
+        .unwrap();
+    assert_eq!(
+        NvPublicBuilder::new_pin_pass_index()
+            .with_nv_index(nv_index())
+            .with_index_name_algorithm(HashingAlgorithm::Sha256)
+            .with_index_attributes(write_define_attributes)
+            .build()
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InconsistentParams)
+    );
+}
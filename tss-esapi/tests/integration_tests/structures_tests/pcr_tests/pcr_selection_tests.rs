@@ -310,3 +310,85 @@ fn test_subtract_exact_unique_pcr_slot_errors() {
         "Subtract exact PcrSelections with unique PcrSlot did not produce the expected error",
     );
 }
+
+#[test]
+fn test_set_operations() {
+    let pcr_selection_1 = PcrSelection::create(
+        HashingAlgorithm::Sha256,
+        PcrSelectSize::TwoOctets,
+        &[PcrSlot::Slot1, PcrSlot::Slot2, PcrSlot::Slot8],
+    )
+    .expect("Failed to create PcrSelection pcr_selection_1");
+    let pcr_selection_2 = PcrSelection::create(
+        HashingAlgorithm::Sha256,
+        PcrSelectSize::ThreeOctets,
+        &[PcrSlot::Slot2, PcrSlot::Slot16],
+    )
+    .expect("Failed to create PcrSelection pcr_selection_2");
+
+    let union = pcr_selection_1
+        .union(&pcr_selection_2)
+        .expect("Failed to compute union");
+    assert_eq!(union.size_of_select(), PcrSelectSize::ThreeOctets);
+    assert_eq!(
+        union.selected(),
+        vec![
+            PcrSlot::Slot1,
+            PcrSlot::Slot2,
+            PcrSlot::Slot8,
+            PcrSlot::Slot16
+        ]
+    );
+    assert_eq!(
+        pcr_selection_1
+            .intersection(&pcr_selection_2)
+            .expect("Failed to compute intersection")
+            .selected(),
+        vec![PcrSlot::Slot2]
+    );
+    assert_eq!(
+        pcr_selection_1
+            .difference(&pcr_selection_2)
+            .expect("Failed to compute difference")
+            .selected(),
+        vec![PcrSlot::Slot1, PcrSlot::Slot8]
+    );
+    assert!(union.contains(&pcr_selection_1));
+    assert!(union.contains(&pcr_selection_2));
+    assert!(!pcr_selection_1.contains(&pcr_selection_2));
+    assert_eq!(
+        pcr_selection_2.iter().collect::<Vec<_>>(),
+        vec![
+            (HashingAlgorithm::Sha256, PcrSlot::Slot2),
+            (HashingAlgorithm::Sha256, PcrSlot::Slot16)
+        ]
+    );
+}
+
+#[test]
+fn test_set_operations_hashing_algorithm_mismatch() {
+    let pcr_selection_1 = PcrSelection::create(
+        HashingAlgorithm::Sha256,
+        PcrSelectSize::ThreeOctets,
+        &[PcrSlot::Slot1],
+    )
+    .expect("Failed to create PcrSelection pcr_selection_1");
+    let pcr_selection_2 = PcrSelection::create(
+        HashingAlgorithm::Sha1,
+        PcrSelectSize::ThreeOctets,
+        &[PcrSlot::Slot1],
+    )
+    .expect("Failed to create PcrSelection pcr_selection_2");
+
+    for result in [
+        pcr_selection_1.union(&pcr_selection_2),
+        pcr_selection_1.intersection(&pcr_selection_2),
+        pcr_selection_1.difference(&pcr_selection_2),
+    ] {
+        assert_eq!(
+            Err(Error::WrapperError(WrapperErrorKind::InvalidParam)),
+            result
+        );
+    }
+    assert!(!pcr_selection_1.contains(&pcr_selection_2));
+}
@@ -0,0 +1,51 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{convert::TryFrom, time::Duration};
+use tss_esapi::{structures::PolicyExpiration, Error, WrapperErrorKind};
+
+#[test]
+fn test_conversion() {
+    let expected_values = [
+        (PolicyExpiration::Never, 0i32),
+        (PolicyExpiration::After(Duration::from_secs(3600)), 3600i32),
+        (
+            PolicyExpiration::AfterWithTicket(Duration::from_secs(3600)),
+            -3600i32,
+        ),
+    ];
+    for (policy_expiration, expected_expiration) in expected_values.iter() {
+        assert_eq!(
+            i32::try_from(*policy_expiration).expect("Failed to convert PolicyExpiration to i32"),
+            *expected_expiration
+        );
+        assert_eq!(
+            PolicyExpiration::from(*expected_expiration),
+            *policy_expiration
+        );
+    }
+}
+
+#[test]
+fn test_accessors() {
+    let policy_expiration = PolicyExpiration::AfterWithTicket(Duration::from_secs(60));
+    assert_eq!(policy_expiration.duration(), Some(Duration::from_secs(60)));
+    assert!(policy_expiration.ticket_requested());
+
+    assert_eq!(PolicyExpiration::Never.duration(), None);
+    assert!(!PolicyExpiration::Never.ticket_requested());
+}
+
+#[test]
+fn test_invalid_conversion() {
+    assert_eq!(
+        i32::try_from(PolicyExpiration::After(Duration::from_secs(u64::from(
+            u32::MAX
+        )))),
+        Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+    );
+    assert_eq!(
+        i32::try_from(PolicyExpiration::AfterWithTicket(Duration::from_secs(0))),
+        Err(Error::WrapperError(WrapperErrorKind::InvalidParam))
+    );
+}
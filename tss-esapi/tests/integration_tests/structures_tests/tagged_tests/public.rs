@@ -3,8 +3,9 @@
 
 use std::convert::TryFrom;
 use tss_esapi::{
+    interface_types::algorithm::PublicAlgorithm,
     structures::{Public, PublicBuffer},
-    tss2_esys::TPM2B_PUBLIC,
+    tss2_esys::{TPM2B_PUBLIC, TPMT_PUBLIC, TPMT_PUBLIC_PARMS},
 };
 
 #[test]
@@ -37,3 +38,19 @@ fn tpm2b_conversion() {
         )
     });
 }
+
+#[test]
+fn parameters() {
+    crate::common::publics().iter().for_each(|public| {
+        let parameters = public.parameters();
+        assert_eq!(
+            parameters.algorithm(),
+            PublicAlgorithm::try_from(TPMT_PUBLIC::from(public.clone()).type_)
+                .expect("Failed to convert the type of the public area")
+        );
+        assert_eq!(
+            TPMT_PUBLIC_PARMS::from(parameters).type_,
+            TPMT_PUBLIC::from(public.clone()).type_
+        );
+    });
+}
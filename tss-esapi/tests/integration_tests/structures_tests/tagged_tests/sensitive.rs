@@ -1,10 +1,12 @@
 // Copyright 2022 Contributors to the Parsec project.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::convert::TryFrom;
+use std::convert::{TryFrom, TryInto};
 use tss_esapi::{
-    structures::{Sensitive, SensitiveBuffer},
+    interface_types::algorithm::PublicAlgorithm,
+    structures::{Sensitive, SensitiveBuffer, SensitiveBuilder},
     tss2_esys::TPM2B_SENSITIVE,
+    Error, WrapperErrorKind,
 };
 
 #[test]
@@ -38,3 +40,53 @@ fn tpm2b_conversion() {
         )
     });
 }
+
+#[test]
+fn builder() {
+    crate::common::sensitives().iter().for_each(|sensitive| {
+        let builder = SensitiveBuilder::new()
+            .with_sensitive_type(sensitive.sensitive_type())
+            .with_auth_value(sensitive.auth_value().clone())
+            .with_seed_value(sensitive.seed_value().clone());
+        let builder = match sensitive {
+            Sensitive::Rsa { sensitive, .. } => builder.with_rsa_private_key(sensitive.clone()),
+            Sensitive::Ecc { sensitive, .. } => builder.with_ecc_private_key(sensitive.clone()),
+            Sensitive::Bits { sensitive, .. } => builder.with_sensitive_data(sensitive.clone()),
+            Sensitive::Symmetric { sensitive, .. } => builder.with_symmetric_key(sensitive.clone()),
+        };
+        assert_eq!(
+            sensitive,
+            &builder.build().expect("Failed to build Sensitive")
+        );
+    });
+}
+
+#[test]
+fn builder_defaults() {
+    let sensitive = Sensitive::builder()
+        .with_sensitive_type(PublicAlgorithm::KeyedHash)
+        .with_sensitive_data(vec![0x11; 32].try_into().unwrap())
+        .build()
+        .expect("Failed to build Sensitive");
+    assert!(sensitive.auth_value().is_empty());
+    assert!(sensitive.seed_value().is_empty());
+}
+
+#[test]
+fn builder_missing_params() {
+    if let Err(Error::WrapperError(WrapperErrorKind::ParamsMissing)) =
+        SensitiveBuilder::new().build()
+    {
+    } else {
+        panic!("Building a Sensitive without a type should fail");
+    }
+
+    if let Err(Error::WrapperError(WrapperErrorKind::ParamsMissing)) = SensitiveBuilder::new()
+        .with_sensitive_type(PublicAlgorithm::Rsa)
+        .with_ecc_private_key(vec![0x11; 32].try_into().unwrap())
+        .build()
+    {
+    } else {
+        panic!("Building an RSA Sensitive without an RSA private key should fail");
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+// The serialization tests are built as a separate test crate, as the
+// implementations of `PartialEq` added by serde_json make the conversions in
+// the assertions of the integration tests ambiguous.
+#![cfg(feature = "openssl")]
+
+use std::convert::TryFrom;
+use tss_esapi::{
+    abstraction::policy::{Policy, PolicyAssertion},
+    attributes::LocalityAttributes,
+    constants::{ArithmeticOperation, CommandCode},
+    interface_types::algorithm::HashingAlgorithm,
+    structures::{
+        CounterTimerConditionBuilder, CounterTimerField, Digest, PcrSelectionListBuilder, PcrSlot,
+    },
+    Error, WrapperErrorKind,
+};
+
+fn pcr_or_auth_value_policy() -> Policy {
+    let pcr_selection_list = PcrSelectionListBuilder::new()
+        .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+        .build()
+        .expect("Failed to create PcrSelectionList");
+    let pcr_policy_digest = Digest::try_from(vec![0xAB; 32]).unwrap();
+    Policy::new(HashingAlgorithm::Sha256)
+        .pcr(pcr_selection_list, pcr_policy_digest)
+        .or(Policy::new(HashingAlgorithm::Sha256).auth_value())
+        .command_code(CommandCode::Unseal)
+}
+
+#[test]
+fn test_serialization() {
+    let condition = CounterTimerConditionBuilder::new()
+        .with_field(CounterTimerField::RestartCount)
+        .with_operation(ArithmeticOperation::UnsignedLe)
+        .with_value(5)
+        .build()
+        .expect("Failed to build CounterTimerCondition");
+    let policy = pcr_or_auth_value_policy()
+        .counter_timer(condition)
+        .locality(LocalityAttributes::LOCALITY_THREE)
+        .nv_written(true);
+
+    let serialized_policy: String =
+        serde_json::to_string(&policy).expect("Failed to serialize the policy");
+    let deserialized_policy: Policy =
+        serde_json::from_str(&serialized_policy).expect("Failed to deserialize the policy");
+    assert_eq!(
+        deserialized_policy.assertions().len(),
+        policy.assertions().len()
+    );
+    assert_eq!(
+        deserialized_policy.digest().unwrap(),
+        policy.digest().unwrap()
+    );
+}
+
+#[test]
+fn test_deserialization_with_unsupported_version() {
+    let policy = Policy::new(HashingAlgorithm::Sha256).auth_value();
+    let mut serialized_policy: serde_json::Value =
+        serde_json::to_value(&policy).expect("Failed to serialize the policy");
+    serialized_policy["version"] = serde_json::json!(2);
+    assert!(serde_json::from_value::<Policy>(serialized_policy).is_err());
+}
+
+#[test]
+fn test_non_leading_or() {
+    let policy = pcr_or_auth_value_policy();
+    let mut serialized_policy: serde_json::Value =
+        serde_json::to_value(&policy).expect("Failed to serialize the policy");
+    serialized_policy["assertions"]
+        .as_array_mut()
+        .expect("The assertions are not serialized as an array")
+        .reverse();
+    let policy: Policy =
+        serde_json::from_value(serialized_policy).expect("Failed to deserialize the policy");
+    assert!(matches!(policy.assertions()[1], PolicyAssertion::Or(_)));
+    assert_eq!(
+        policy.digest().unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::InvalidParam)
+    );
+}
+
+mod fapi {
+    use sha2::{Digest as _, Sha256};
+    use std::convert::TryFrom;
+    use tss_esapi::{
+        abstraction::policy::{self, Calculator, FapiPolicy, PolicyResolver},
+        constants::CommandCode,
+        handles::SessionHandle,
+        interface_types::algorithm::HashingAlgorithm,
+        structures::{Digest, DigestList, PcrSelectionListBuilder, PcrSlot},
+        Context, Error, Tcti, WrapperErrorKind,
+    };
+
+    const PCR_OR_AUTH_VALUE_POLICY: &str = r#"{
+        "description": "Unseal with the PCR 16 value or with the authorization value",
+        "policy": [
+            {
+                "type": "POLICYOR",
+                "branches": [
+                    {
+                        "name": "PCR",
+                        "description": "PCR 16 is not extended",
+                        "policy": [
+                            {
+                                "type": "POLICYPCR",
+                                "pcrs": [
+                                    {
+                                        "pcr": 16,
+                                        "hashAlg": "TPM2_ALG_SHA256",
+                                        "digest": "0000000000000000000000000000000000000000000000000000000000000000"
+                                    }
+                                ]
+                            }
+                        ]
+                    },
+                    {
+                        "name": "Password",
+                        "description": "The authorization value is given",
+                        "policy": [{ "type": "POLICYAUTHVALUE" }]
+                    }
+                ]
+            },
+            { "type": "POLICYCOMMANDCODE", "code": "TPM2_CC_Unseal" }
+        ]
+    }"#;
+
+    fn create_context() -> Context {
+        Context::new(Tcti::from_environment_variable().expect("Failed to get TCTI"))
+            .expect("Failed to create Context")
+    }
+
+    struct EmptyResolver;
+
+    impl PolicyResolver for EmptyResolver {}
+
+    fn expected_digest() -> Digest {
+        let pcr_selection_list = PcrSelectionListBuilder::new()
+            .with_selection(HashingAlgorithm::Sha256, &[PcrSlot::Slot16])
+            .build()
+            .unwrap();
+        let pcr_policy_digest = Digest::try_from(Sha256::digest([0u8; 32]).to_vec()).unwrap();
+        let mut pcr_branch = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        pcr_branch
+            .policy_pcr(&pcr_policy_digest, &pcr_selection_list)
+            .unwrap();
+        let mut auth_value_branch = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        auth_value_branch.policy_auth_value().unwrap();
+
+        let mut digest_list = DigestList::new();
+        digest_list
+            .add(pcr_branch.policy_digest().unwrap())
+            .unwrap();
+        digest_list
+            .add(auth_value_branch.policy_digest().unwrap())
+            .unwrap();
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator
+            .policy_or(&digest_list)
+            .unwrap()
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap();
+        calculator.policy_digest().unwrap()
+    }
+
+    #[test]
+    fn test_to_policy() {
+        let fapi_policy: FapiPolicy = serde_json::from_str(PCR_OR_AUTH_VALUE_POLICY).unwrap();
+        assert_eq!(
+            fapi_policy.description(),
+            "Unseal with the PCR 16 value or with the authorization value"
+        );
+        let policy = fapi_policy.to_policy(HashingAlgorithm::Sha256).unwrap();
+        assert_eq!(policy.digest().unwrap(), expected_digest());
+    }
+
+    #[test]
+    fn test_to_policy_with_elements_before_or() {
+        let json = r#"{
+            "policy": [
+                { "type": "POLICYCOMMANDCODE", "code": "TPM2_CC_Unseal" },
+                {
+                    "type": "POLICYOR",
+                    "branches": [
+                        { "name": "AuthValue", "policy": [{ "type": "POLICYAUTHVALUE" }] },
+                        { "name": "Password", "policy": [{ "type": "POLICYPASSWORD" }] }
+                    ]
+                }
+            ]
+        }"#;
+        let mut auth_value_branch = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        auth_value_branch
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap()
+            .policy_auth_value()
+            .unwrap();
+        let mut password_branch = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        password_branch
+            .policy_command_code(CommandCode::Unseal)
+            .unwrap()
+            .policy_password()
+            .unwrap();
+        let mut digest_list = DigestList::new();
+        digest_list
+            .add(auth_value_branch.policy_digest().unwrap())
+            .unwrap();
+        digest_list
+            .add(password_branch.policy_digest().unwrap())
+            .unwrap();
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        let _ = calculator.policy_or(&digest_list).unwrap();
+
+        let fapi_policy: FapiPolicy = serde_json::from_str(json).unwrap();
+        let policy = fapi_policy.to_policy(HashingAlgorithm::Sha256).unwrap();
+        assert_eq!(
+            policy.digest().unwrap(),
+            calculator.policy_digest().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_policy_with_policy_digests() {
+        let json = r#"{
+            "policyDigests": [{ "hashAlg": "SHA256", "digest": "DIGEST" }],
+            "policy": [{ "type": "POLICYAUTHVALUE" }]
+        }"#;
+        let mut calculator = Calculator::new(HashingAlgorithm::Sha256).unwrap();
+        calculator.policy_auth_value().unwrap();
+        let digest = calculator
+            .policy_digest()
+            .unwrap()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+
+        let fapi_policy: FapiPolicy =
+            serde_json::from_str(&json.replace("DIGEST", &digest)).unwrap();
+        let _ = fapi_policy
+            .to_policy(HashingAlgorithm::Sha256)
+            .expect("Failed to convert a policy with a matching digest");
+
+        let fapi_policy: FapiPolicy =
+            serde_json::from_str(&json.replace("DIGEST", &"00".repeat(32))).unwrap();
+        assert!(matches!(
+            fapi_policy.to_policy(HashingAlgorithm::Sha256),
+            Err(Error::WrapperError(WrapperErrorKind::InconsistentParams))
+        ));
+    }
+
+    #[test]
+    fn test_to_policy_with_key_path() {
+        let json = r#"{
+            "policy": [{ "type": "POLICYSIGNED", "keyPath": "HS/SRK/myKey" }]
+        }"#;
+        let fapi_policy: FapiPolicy = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            fapi_policy.to_policy(HashingAlgorithm::Sha256),
+            Err(Error::WrapperError(WrapperErrorKind::UnsupportedParam))
+        ));
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut context = create_context();
+        let fapi_policy: FapiPolicy = serde_json::from_str(PCR_OR_AUTH_VALUE_POLICY).unwrap();
+        let policy = fapi_policy.to_policy(HashingAlgorithm::Sha256).unwrap();
+
+        let policy_session = policy::replay(&mut context, &policy, &mut EmptyResolver)
+            .expect("Failed to replay the policy");
+        let digest = context
+            .execute_without_session(|ctx| ctx.policy_get_digest(policy_session))
+            .expect("Call to policy_get_digest failed");
+        context
+            .flush_context(SessionHandle::from(policy_session).into())
+            .expect("Call to flush_context failed");
+        assert_eq!(digest, expected_digest());
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2022 Contributors to the Parsec project.
+// SPDX-License-Identifier: Apache-2.0
+
+// The serialization tests are built as a separate test crate, as the
+// implementations of `PartialEq` added by serde_json make the conversions in
+// the assertions of the integration tests ambiguous.
+use tss_esapi::{
+    abstraction::primary::PrimaryKeyRecord,
+    interface_types::{
+        algorithm::{HashingAlgorithm, RsaSchemeAlgorithm},
+        key_bits::RsaKeyBits,
+        resource_handles::Hierarchy,
+    },
+    structures::{Public, RsaExponent, RsaScheme, SymmetricDefinitionObject},
+    utils::{create_restricted_decryption_rsa_public, create_unrestricted_signing_rsa_public},
+    Context, Error, Tcti, WrapperErrorKind,
+};
+
+fn create_context() -> Context {
+    Context::new(Tcti::from_environment_variable().expect("Failed to get TCTI"))
+        .expect("Failed to create Context")
+}
+
+fn decryption_key_pub() -> Public {
+    create_restricted_decryption_rsa_public(
+        SymmetricDefinitionObject::AES_128_CFB,
+        RsaKeyBits::Rsa2048,
+        RsaExponent::default(),
+    )
+    .expect("Failed to create a restricted decryption rsa public structure")
+}
+
+fn signing_key_pub() -> Public {
+    create_unrestricted_signing_rsa_public(
+        RsaScheme::create(RsaSchemeAlgorithm::RsaSsa, Some(HashingAlgorithm::Sha256))
+            .expect("Failed to create RSA scheme"),
+        RsaKeyBits::Rsa2048,
+        RsaExponent::default(),
+    )
+    .expect("Failed to create an unrestricted signing rsa public structure")
+}
+
+fn create_primary(context: &mut Context, template: Public) -> PrimaryKeyRecord {
+    let (key_handle, record) = context
+        .execute_with_nullauth_session(|ctx| {
+            PrimaryKeyRecord::create(ctx, Hierarchy::Owner, template)
+        })
+        .expect("Failed to create the primary key");
+    context
+        .flush_context(key_handle.into())
+        .expect("Failed to flush the primary key");
+    record
+}
+
+#[test]
+fn test_recreate_deserialized_primary() {
+    let mut context = create_context();
+    let record = create_primary(&mut context, decryption_key_pub());
+
+    let json: String = serde_json::to_string(&record).expect("Failed to serialize the record");
+    let deserialized_record: PrimaryKeyRecord =
+        serde_json::from_str(&json).expect("Failed to deserialize the record");
+    assert_eq!(deserialized_record, record);
+    let key_handle = context
+        .execute_with_nullauth_session(|ctx| deserialized_record.recreate(ctx))
+        .expect("Failed to recreate the primary key");
+    assert_eq!(
+        context
+            .tr_get_name(key_handle.into())
+            .expect("Failed to get the name of the primary key"),
+        record.name().unwrap()
+    );
+    context
+        .flush_context(key_handle.into())
+        .expect("Failed to flush the primary key");
+}
+
+#[test]
+fn test_recreate_primary_with_other_name() {
+    let mut context = create_context();
+    let record = create_primary(&mut context, decryption_key_pub());
+    let other_record = create_primary(&mut context, signing_key_pub());
+
+    // Simulate a change of the seed of the hierarchy, by recording another name.
+    let mut json: serde_json::Value =
+        serde_json::to_value(&record).expect("Failed to serialize the record");
+    json["name"] = serde_json::to_value(&other_record).unwrap()["name"].clone();
+    let record: PrimaryKeyRecord =
+        serde_json::from_value(json).expect("Failed to deserialize the record");
+    assert_eq!(
+        context
+            .execute_with_nullauth_session(|ctx| record.recreate(ctx))
+            .unwrap_err(),
+        Error::WrapperError(WrapperErrorKind::WrongValueFromTpm)
+    );
+}